@@ -1,7 +1,7 @@
 use std::{
     fmt::Display,
     fs::{File, metadata, read_dir},
-    io::{self, Write},
+    io::{self, BufReader, BufWriter, Write},
     mem,
     path::{Path, PathBuf},
     str::FromStr,
@@ -13,19 +13,28 @@ use ethrex_blockchain::{
     BlockchainOptions, BlockchainType, L2Config,
     error::{ChainError, InvalidBlockError},
 };
-use ethrex_common::types::{Block, DEFAULT_BUILDER_GAS_CEIL, Genesis, validate_block_body};
+use ethrex_common::{
+    U256,
+    types::{Block, DEFAULT_BUILDER_GAS_CEIL, Genesis, validate_block_body},
+};
 use ethrex_p2p::{
-    discovery::INITIAL_LOOKUP_INTERVAL_MS, peer_table::TARGET_PEERS, sync::SyncMode,
-    tx_broadcaster::BROADCAST_INTERVAL_MS, types::Node,
+    discovery::{INITIAL_LOOKUP_INTERVAL_MS, dns::EnrTreeUrl},
+    nat::NatMode,
+    peer_table::TARGET_PEERS,
+    sync::{SyncCheckpoint, SyncMode},
+    tx_broadcaster::BROADCAST_INTERVAL_MS,
+    types::Node,
 };
 use ethrex_rlp::encode::RLPEncode;
-use ethrex_storage::{error::StoreError, has_valid_db};
+use ethrex_storage::{GcMode, HistoryRetention, StoreConfig, error::StoreError, has_valid_db};
 use tokio_util::sync::CancellationToken;
 use tracing::{Level, error, info, warn};
 
 use crate::{
+    era::{self, Era1Block, EraError, MAX_ERA1_BLOCKS},
     initializers::{
-        get_network, init_blockchain, init_store, init_tracing, load_store, regenerate_head_state,
+        get_network, init_blockchain, init_store, init_store_with_config, init_tracing, load_store,
+        regenerate_head_state,
     },
     utils::{
         self, default_datadir, get_client_version, get_client_version_string,
@@ -79,6 +88,28 @@ pub struct Options {
     pub network: Option<Network>,
     #[arg(long = "bootnodes", value_parser = clap::value_parser!(Node), value_name = "BOOTNODE_LIST", value_delimiter = ',', num_args = 1.., help = "Comma separated enode URLs for P2P discovery bootstrap.", help_heading = "P2P options", env = "ETHREX_BOOTNODES")]
     pub bootnodes: Vec<Node>,
+    #[arg(
+        long = "p2p.static-peers",
+        value_parser = clap::value_parser!(Node),
+        value_name = "STATIC_PEER_LIST",
+        value_delimiter = ',',
+        num_args = 1..,
+        help = "Comma separated enode URLs of peers to always stay connected to. They are redialed with backoff whenever the connection drops.",
+        help_heading = "P2P options",
+        env = "ETHREX_P2P_STATIC_PEERS"
+    )]
+    pub static_peers: Vec<Node>,
+    #[arg(
+        long = "p2p.trusted-peers",
+        value_parser = clap::value_parser!(Node),
+        value_name = "TRUSTED_PEER_LIST",
+        value_delimiter = ',',
+        num_args = 1..,
+        help = "Comma separated enode URLs of peers that are always accepted, even when the peer limit is reached, and are never penalized below a neutral score.",
+        help_heading = "P2P options",
+        env = "ETHREX_P2P_TRUSTED_PEERS"
+    )]
+    pub trusted_peers: Vec<Node>,
     #[arg(
         long = "datadir",
         value_name = "DATABASE_DIRECTORY",
@@ -89,6 +120,15 @@ pub struct Options {
         env = "ETHREX_DATADIR"
     )]
     pub datadir: PathBuf,
+    #[arg(
+        long = "datadir.ancient",
+        value_name = "ANCIENT_DIRECTORY",
+        help = "Directory for old finalized block bodies and receipts. Defaults to the `ancient` subdirectory of the database.",
+        long_help = "Directory of the ancient store, an append-only flat-file store where the bodies and receipts of blocks finalized more than 90000 blocks ago are moved to keep the database small. Use it to place them on a separate (cheaper) disk. Defaults to the `ancient` subdirectory of the database and is ignored for the `memory` datadir.",
+        help_heading = "Node options",
+        env = "ETHREX_DATADIR_ANCIENT"
+    )]
+    pub ancient_datadir: Option<PathBuf>,
     #[arg(
         long = "force",
         help = "Force remove the database",
@@ -119,8 +159,50 @@ pub struct Options {
         env = "ETHREX_ROCKSDB_BLOCK_CACHE_SIZE",
     )]
     pub rocksdb_block_cache_size: usize,
+    #[arg(
+        long = "gcmode",
+        default_value = "full",
+        value_name = "GC_MODE",
+        value_parser = utils::parse_gc_mode,
+        help = "Whether to prune historical state (\"full\") or keep it (\"archive\").",
+        long_help = "Can be either \"full\" or \"archive\" with \"full\" as default value. \
+                     In archive mode the state of every block imported since archive mode was \
+                     enabled stays queryable, at the cost of extra disk space. Blocks are executed \
+                     one by one during full sync, and a database that keeps archive history can't \
+                     be reopened in full mode. Archived state can be read but not built on, so \
+                     reorgs deeper than the 128 most recent blocks kept in memory fail.",
+        help_heading = "Storage options",
+        env = "ETHREX_GCMODE"
+    )]
+    pub gc_mode: GcMode,
+    #[arg(
+        long = "history.retention",
+        default_value = "all",
+        value_name = "RETENTION",
+        value_parser = utils::parse_history_retention,
+        help = "Which blocks keep their bodies and receipts: \"all\", \"post-merge\" or the last N blocks.",
+        long_help = "Can be \"all\", \"post-merge\" or a number of blocks, with \"all\" as default \
+                     value. Bodies, receipts and transaction lookups of finalized blocks outside the \
+                     retention window are pruned (EIP-4444). Headers are always kept, and RPC \
+                     requests for pruned history fail with a \"pruned history unavailable\" error.",
+        help_heading = "Storage options",
+        env = "ETHREX_HISTORY_RETENTION"
+    )]
+    pub history_retention: HistoryRetention,
     #[arg(long = "syncmode", default_value = "snap", value_name = "SYNC_MODE", value_parser = utils::parse_sync_mode, help = "The way in which the node will sync its state.", long_help = "Can be either \"full\" or \"snap\" with \"snap\" as default value.", help_heading = "P2P options", env = "ETHREX_SYNCMODE")]
     pub syncmode: SyncMode,
+    #[arg(
+        long = "sync.checkpoint",
+        value_name = "HASH_OR_NUMBER:STATE_ROOT",
+        help = "Trusted header to snap sync from without a consensus client.",
+        long_help = "Trusted (weak subjectivity) header given as <hash|number>:<stateRoot>. The header \
+                     is fetched from peers and checked against the state root, its state is snap \
+                     synced and the node then keeps full syncing the blocks announced by peers. \
+                     Meant for bringing up test networks without a consensus client.",
+        help_heading = "P2P options",
+        env = "ETHREX_SYNC_CHECKPOINT"
+    )]
+    pub sync_checkpoint: Option<SyncCheckpoint>,
     #[arg(
         long = "metrics.addr",
         value_name = "ADDRESS",
@@ -263,11 +345,21 @@ pub struct Options {
         value_delimiter = ',',
         value_parser = utils::parse_http_namespace,
         help = "Comma-separated JSON-RPC namespaces enabled over HTTP/WS.",
-        long_help = "Comma-separated list of JSON-RPC namespaces exposed on the public HTTP and WebSocket endpoints. Defaults to `eth,net,web3`. Enable `admin`, `debug` or `txpool` only when needed; the `engine` namespace is served on the authenticated RPC port and cannot be toggled here.",
+        long_help = "Comma-separated list of JSON-RPC namespaces exposed on the public HTTP and WebSocket endpoints. Defaults to `eth,net,web3`. Enable `admin`, `debug`, `txpool` or `trace` only when needed; the `engine` namespace is served on the authenticated RPC port and cannot be toggled here.",
         help_heading = "RPC options",
         env = "ETHREX_HTTP_API"
     )]
     pub http_api: Vec<ethrex_rpc::RpcNamespace>,
+    #[arg(
+        long = "rpc.maxfilters",
+        default_value_t = ethrex_rpc::DEFAULT_MAX_FILTERS,
+        value_name = "MAX_FILTERS",
+        help = "Maximum number of filters installed at the same time.",
+        long_help = "Maximum number of filters (`eth_newFilter`, `eth_newBlockFilter`, `eth_newPendingTransactionFilter`) installed at the same time across all clients. Filters that are not polled for 5 minutes are removed.",
+        help_heading = "RPC options",
+        env = "ETHREX_RPC_MAX_FILTERS"
+    )]
+    pub rpc_max_filters: usize,
     #[arg(
         long = "ws.enabled",
         default_value = "false",
@@ -296,6 +388,15 @@ pub struct Options {
         env = "ETHREX_WS_PORT"
     )]
     pub ws_port: String,
+    #[arg(
+        long = "ipc.path",
+        value_name = "IPC_PATH",
+        help = "Path of the IPC socket. Disabled if not set.",
+        long_help = "Path of the Unix domain socket for the IPC JSON-RPC server. IPC serves every namespace except `engine` (regardless of `--http.api`) using newline-delimited JSON, including `eth_subscribe`. The socket is only accessible to the user running the node. Disabled if not set.",
+        help_heading = "RPC options",
+        env = "ETHREX_IPC_PATH"
+    )]
+    pub ipc_path: Option<PathBuf>,
     #[arg(
         long = "authrpc.addr",
         default_value = "127.0.0.1",
@@ -329,16 +430,27 @@ pub struct Options {
         long = "p2p.addr",
         value_name = "ADDRESS",
         help = "Bind address for the P2P protocol (UDP discovery and TCP RLPx).",
-        long_help = "The address to bind P2P sockets to. Defaults to the local IP. Use 0.0.0.0 (IPv4) or :: (IPv6) to listen on all interfaces. See also --nat.extip to announce a different external address.",
+        long_help = "The address to bind P2P sockets to. Defaults to the local IP. Use 0.0.0.0 (IPv4) or :: (IPv6) to listen on all interfaces. See also --nat to announce a different external address.",
         help_heading = "P2P options",
         env = "ETHREX_P2P_ADDR"
     )]
     pub p2p_addr: Option<String>,
+    #[arg(
+        long = "nat",
+        default_value_t = NatMode::None,
+        value_name = "MODE",
+        help = "NAT traversal mode: any, upnp, pmp, extip:<IP> or none.",
+        long_help = "How the node makes itself reachable from behind a NAT. upnp and pmp map the --p2p.port TCP and --discovery.port UDP ports on the gateway with UPnP IGD or NAT-PMP, refresh the mappings and announce the external endpoint the gateway reports; any tries UPnP and then NAT-PMP. NAT-PMP finds the gateway in the Linux routing table, so pmp only works on Linux. extip:<IP> announces a fixed external IP, like --nat.extip. Unless the external IP is fixed, the announced IP also follows the address peers observe in discovery PONGs.",
+        conflicts_with = "nat_extip",
+        help_heading = "P2P options",
+        env = "ETHREX_P2P_NAT"
+    )]
+    pub nat: NatMode,
     #[arg(
         long = "nat.extip",
         value_name = "IP",
         help = "External IP address to announce to peers.",
-        long_help = "The IP address advertised to other nodes via discovery and ENR. Use this when the node is behind NAT and --p2p.addr is a private/unspecified address. Defaults to the value of --p2p.addr (or the auto-detected local IP if neither is set).",
+        long_help = "The IP address advertised to other nodes via discovery and ENR. Use this when the node is behind NAT and --p2p.addr is a private/unspecified address. Defaults to the value of --p2p.addr (or the auto-detected local IP if neither is set). Same as --nat extip:<IP>.",
         help_heading = "P2P options",
         env = "ETHREX_P2P_NAT_EXTIP"
     )]
@@ -377,6 +489,17 @@ pub struct Options {
         help_heading = "P2P options"
     )]
     pub discv5_enabled: bool,
+    #[arg(
+        long = "discovery.dns",
+        value_parser = clap::value_parser!(EnrTreeUrl),
+        value_name = "ENRTREE_URL_LIST",
+        value_delimiter = ',',
+        num_args = 1..,
+        help = "Comma separated enrtree:// URLs of EIP-1459 DNS trees used as an extra bootstrap source. Defaults to the public trees of the selected network.",
+        help_heading = "P2P options",
+        env = "ETHREX_DISCOVERY_DNS"
+    )]
+    pub dns_trees: Vec<EnrTreeUrl>,
     #[arg(
         long = "p2p.tx-broadcasting-interval",
         default_value_t = BROADCAST_INTERVAL_MS,
@@ -484,6 +607,14 @@ impl Options {
             ..Default::default()
         }
     }
+
+    /// NAT mode set with `--nat`, or `extip:<IP>` when the legacy `--nat.extip` is used.
+    pub fn nat_mode(&self) -> NatMode {
+        match &self.nat_extip {
+            Some(ip) => NatMode::ExtIp(ip.parse().expect("Failed to parse --nat.extip address")),
+            None => self.nat,
+        }
+    }
 }
 
 impl Default for Options {
@@ -492,9 +623,11 @@ impl Default for Options {
             http_addr: Default::default(),
             http_port: Default::default(),
             http_api: ethrex_rpc::DEFAULT_HTTP_API.to_vec(),
+            rpc_max_filters: ethrex_rpc::DEFAULT_MAX_FILTERS,
             ws_enabled: false,
             ws_addr: Default::default(),
             ws_port: Default::default(),
+            ipc_path: None,
             log_level: Level::INFO,
             log_color: Default::default(),
             log_dir: None,
@@ -503,16 +636,24 @@ impl Default for Options {
             authrpc_jwtsecret: Default::default(),
             p2p_disabled: Default::default(),
             p2p_addr: None,
+            nat: NatMode::None,
             nat_extip: None,
             p2p_port: Default::default(),
             discovery_port: Default::default(),
             discv4_enabled: true,
             discv5_enabled: true,
+            dns_trees: Default::default(),
             network: Default::default(),
             bootnodes: Default::default(),
+            static_peers: Default::default(),
+            trusted_peers: Default::default(),
             datadir: Default::default(),
+            ancient_datadir: None,
             rocksdb_block_cache_size: ethrex_storage::DEFAULT_ROCKSDB_BLOCK_CACHE_SIZE_BYTES,
+            gc_mode: Default::default(),
+            history_retention: Default::default(),
             syncmode: Default::default(),
+            sync_checkpoint: None,
             metrics_addr: "0.0.0.0".to_owned(),
             metrics_port: Default::default(),
             metrics_enabled: Default::default(),
@@ -551,7 +692,7 @@ pub enum Subcommand {
         #[arg(
             required = true,
             value_name = "FILE_PATH/FOLDER",
-            help = "Path to a RLP chain file or a folder containing files with individual Blocks"
+            help = "Path to a RLP chain file, an Era1 (.era1) or Era (.era) archive, or a folder containing such files"
         )]
         path: String,
         #[arg(long = "removedb", action = ArgAction::SetTrue)]
@@ -590,15 +731,22 @@ pub enum Subcommand {
     },
     #[command(
         name = "export",
-        about = "Export blocks in the current chain into a file in rlp encoding"
+        about = "Export blocks in the current chain into a file in rlp encoding or Era1 archives"
     )]
     Export {
         #[arg(
             required = true,
             value_name = "FILE_PATH",
-            help = "Path to the file where the rlp blocks will be written to"
+            help = "Path to the file where the rlp blocks will be written to, or to the directory for the Era1 archives"
         )]
         path: String,
+        #[arg(
+            long = "format",
+            default_value_t = ExportFormat::Rlp,
+            help = "Format of the exported blocks.",
+            long_help = "Possible values: rlp, era1. Era1 archives hold 8192 pre-merge blocks each, so the export starts at the beginning of the archive containing the first block and stops at the merge. A last epoch that is still incomplete is not exported."
+        )]
+        format: ExportFormat,
         #[arg(
             long = "first",
             value_name = "NUMBER",
@@ -714,6 +862,12 @@ impl Subcommand {
                         r#type: blockchain_type,
                         ..Default::default()
                     },
+                    StoreConfig {
+                        rocksdb_block_cache_size: opts.rocksdb_block_cache_size,
+                        gc_mode: opts.gc_mode,
+                        ancient_dir: opts.ancient_datadir.clone(),
+                        history_retention: opts.history_retention,
+                    },
                 )
                 .await?;
             }
@@ -753,9 +907,21 @@ impl Subcommand {
                 )
                 .await?;
             }
-            Subcommand::Export { path, first, last } => {
-                export_blocks(&path, &effective_datadir, first, last).await
-            }
+            Subcommand::Export {
+                path,
+                format,
+                first,
+                last,
+            } => match format {
+                ExportFormat::Rlp => export_blocks(&path, &effective_datadir, first, last).await,
+                ExportFormat::Era1 => {
+                    let network_name = match &network {
+                        Network::GenesisPath(_) => "custom".to_string(),
+                        network => network.to_string(),
+                    };
+                    export_era1(&path, &effective_datadir, &network_name, first, last).await
+                }
+            },
             Subcommand::ComputeStateRoot { genesis_path } => {
                 let genesis = Network::from(genesis_path).get_genesis()?;
                 let state_root = genesis.compute_state_root();
@@ -819,6 +985,37 @@ impl FromStr for LogColor {
     }
 }
 
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ExportFormat {
+    #[default]
+    Rlp,
+    Era1,
+}
+
+impl Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportFormat::Rlp => write!(f, "rlp"),
+            ExportFormat::Era1 => write!(f, "era1"),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rlp" => Ok(ExportFormat::Rlp),
+            "era1" => Ok(ExportFormat::Era1),
+            _ => Err(format!(
+                "Invalid export format '{}'. Expected: rlp or era1",
+                s
+            )),
+        }
+    }
+}
+
 pub fn remove_db(datadir: &Path, force: bool) {
     init_datadir(datadir);
 
@@ -850,6 +1047,7 @@ pub async fn import_blocks(
     datadir: &Path,
     genesis: Genesis,
     blockchain_opts: BlockchainOptions,
+    store_config: StoreConfig,
 ) -> Result<(), ChainError> {
     const IMPORT_BATCH_SIZE: usize = 1024;
     // This value is higher than the spec (128) as the latter block's state nodes will be kept in memory and not committed when using rocksdb
@@ -857,7 +1055,7 @@ pub async fn import_blocks(
     const MIN_FULL_BLOCKS: usize = 132;
     let start_time = Instant::now();
     init_datadir(datadir);
-    let store = init_store(datadir, genesis).await?;
+    let store = init_store_with_config(datadir, genesis, store_config).await?;
     let blockchain = init_blockchain(store.clone(), blockchain_opts);
     // Re-execute any blocks above the last committed state root so the in-memory diff
     // layers are populated before this import appends. Required for per-file imports
@@ -869,32 +1067,32 @@ pub async fn import_blocks(
         .map_err(|e| ChainError::Custom(format!("regenerate_head_state failed: {e}")))?;
     let path_metadata = metadata(path).expect("Failed to read path");
 
-    // If it's an .rlp file it will be just one chain, but if it's a directory there can be multiple chains.
-    let chains: Vec<Vec<Block>> = if path_metadata.is_dir() {
+    // If it's a single file it will be just one chain, but if it's a directory there can be multiple chains.
+    let files: Vec<PathBuf> = if path_metadata.is_dir() {
         info!(path = %path, "Importing blocks from directory");
         let mut entries: Vec<_> = read_dir(path)
             .expect("Failed to read blocks directory")
             .map(|res| res.expect("Failed to open file in directory").path())
             .collect();
 
-        // Sort entries to process files in order (e.g., 1.rlp, 2.rlp, ...)
-        entries.sort();
+        // Archive directories usually come with other files, like checksums, so only take the archives
+        if entries.iter().any(|entry| is_era_archive(entry)) {
+            entries.retain(|entry| is_era_archive(entry));
+        }
 
+        // Sort entries to process files in order (e.g., 1.rlp, 2.rlp, ... or mainnet-00000-*.era1, ...)
+        entries.sort();
         entries
-            .iter()
-            .map(|entry| {
-                let path_str = entry.to_str().expect("Couldn't convert path to string");
-                info!(path = %path_str, "Importing blocks from file");
-                utils::read_chain_file(path_str)
-            })
-            .collect()
     } else {
-        info!(path = %path, "Importing blocks from file");
-        vec![utils::read_chain_file(path)]
+        vec![PathBuf::from(path)]
     };
 
     let mut total_blocks_imported = 0;
-    for blocks in chains {
+    for file in files {
+        // Files are read and imported one at a time, so only one chain is held in memory
+        let path_str = file.to_str().expect("Couldn't convert path to string");
+        info!(path = %path_str, "Importing blocks from file");
+        let blocks = read_import_file(path_str)?;
         let mut block_batch = vec![];
         let size = blocks.len();
         let mut numbers_and_hashes = blocks
@@ -930,7 +1128,8 @@ pub async fn import_blocks(
             validate_block_body(&block.header, &block.body, &ethrex_crypto::NativeCrypto)
                 .map_err(InvalidBlockError::InvalidBody)?;
 
-            if index + MIN_FULL_BLOCKS < size {
+            // Archive nodes keep the state of every block, so they can't batch
+            if index + MIN_FULL_BLOCKS < size && !store.is_archive() {
                 block_batch.push(block);
                 if block_batch.len() >= IMPORT_BATCH_SIZE || index + MIN_FULL_BLOCKS + 1 == size {
                     blockchain
@@ -979,6 +1178,34 @@ pub async fn import_blocks(
     Ok(())
 }
 
+fn is_era_archive(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "era1" || extension == "era")
+}
+
+/// Reads the blocks of an RLP chain file or, based on its extension, an Era1 or Era archive.
+fn read_import_file(path: &str) -> Result<Vec<Block>, ChainError> {
+    let read_archive = |read: fn(BufReader<File>) -> Result<Vec<Block>, EraError>| {
+        File::open(path)
+            .map_err(EraError::from)
+            .and_then(|file| read(BufReader::new(file)))
+            .map_err(|err| ChainError::Custom(format!("Failed to read archive {path}: {err}")))
+    };
+    match Path::new(path)
+        .extension()
+        .and_then(|extension| extension.to_str())
+    {
+        Some("era1") => read_archive(|reader| {
+            Ok(era::read_era1(reader)?
+                .into_iter()
+                .map(|block| block.block)
+                .collect())
+        }),
+        Some("era") => read_archive(era::read_era),
+        _ => Ok(utils::read_chain_file(path)),
+    }
+}
+
 pub async fn import_blocks_bench(
     path: &str,
     datadir: &Path,
@@ -1214,6 +1441,14 @@ pub async fn export_blocks(
             return;
         }
     };
+    // Blocks below the history cutoff only have their headers
+    let earliest_number = match store.get_history_cutoff().await {
+        Ok(cutoff) => earliest_number.max(cutoff),
+        Err(err) => {
+            error!("Failed to get history cutoff: {err}");
+            return;
+        }
+    };
 
     // Check that the requested range doesn't exceed our current chain length
     if last_number.is_some_and(|number| number > latest_number) {
@@ -1294,10 +1529,154 @@ pub async fn export_blocks(
     );
 }
 
+/// Exports the pre-merge blocks in `[first, last]` as Era1 archives in `dir`, one per
+/// 8192 blocks. As archives must be aligned, the export starts at the beginning of the
+/// archive containing `first`, and an archive is only written once its epoch is complete,
+/// or ends at the merge, so it can't be mistaken for the canonical archive of its epoch.
+pub async fn export_era1(
+    dir: &str,
+    datadir: &Path,
+    network: &str,
+    first_number: Option<u64>,
+    last_number: Option<u64>,
+) {
+    init_datadir(datadir);
+    let store = match load_store(datadir).await {
+        Err(err) => {
+            error!("Failed to load Store due to: {err}");
+            return;
+        }
+        Ok(store) => store,
+    };
+    let latest_number = match store.get_latest_block_number().await {
+        Ok(number) => number,
+        Err(StoreError::MissingLatestBlockNumber) => {
+            warn!("No blocks in the current chain, nothing to export!");
+            return;
+        }
+        Err(_) => panic!("Internal DB Error"),
+    };
+    if last_number.is_some_and(|number| number > latest_number) {
+        warn!(
+            "The requested block range exceeds the current amount of blocks in the chain {latest_number}"
+        );
+        return;
+    }
+    let end = last_number.unwrap_or(latest_number);
+    let first_epoch = first_number.unwrap_or_default() / MAX_ERA1_BLOCKS as u64;
+    let start = first_epoch * MAX_ERA1_BLOCKS as u64;
+    if start > end {
+        warn!("Cannot export block range [{start}..{end}], please input a valid range");
+        return;
+    }
+    if let Err(err) = std::fs::create_dir_all(dir) {
+        error!("Failed to create export directory {dir}: {err}");
+        return;
+    }
+
+    // Archives store the total difficulty of each block, which we don't keep
+    let mut total_difficulty = U256::zero();
+    for n in 0..start {
+        match store.get_block_header(n) {
+            Ok(Some(header)) => total_difficulty += header.difficulty,
+            _ => {
+                error!(block_number = n, "Failed to read block header from DB");
+                return;
+            }
+        }
+    }
+
+    let mut exported_count: u64 = 0;
+    let mut reached_merge = false;
+    for epoch in first_epoch.. {
+        let epoch_start = epoch * MAX_ERA1_BLOCKS as u64;
+        if reached_merge || epoch_start > end {
+            break;
+        }
+        let epoch_end = end.min(epoch_start + MAX_ERA1_BLOCKS as u64 - 1);
+        let mut blocks = Vec::with_capacity(MAX_ERA1_BLOCKS);
+        for n in epoch_start..=epoch_end {
+            let block = match store.get_block_by_number(n).await {
+                Ok(Some(block)) => block,
+                Ok(None) => {
+                    error!(
+                        block_number = n,
+                        "Block is missing, history that was pruned can't be exported"
+                    );
+                    return;
+                }
+                Err(err) => {
+                    error!(block_number = n, error = %err, "Failed to read block from DB");
+                    return;
+                }
+            };
+            if n > 0 && block.header.difficulty.is_zero() {
+                reached_merge = true;
+                break;
+            }
+            let receipts = match store.get_receipts_for_block(&block.hash()).await {
+                Ok(receipts) => receipts,
+                Err(err) => {
+                    error!(block_number = n, error = %err, "Failed to read receipts from DB");
+                    return;
+                }
+            };
+            total_difficulty += block.header.difficulty;
+            blocks.push(Era1Block::new(block, &receipts, total_difficulty));
+        }
+        if blocks.is_empty() {
+            break;
+        }
+        if !reached_merge && blocks.len() < MAX_ERA1_BLOCKS {
+            warn!(
+                epoch,
+                blocks = blocks.len(),
+                "Skipping incomplete Era1 archive, only complete epochs are exported"
+            );
+            break;
+        }
+
+        // The file name depends on the accumulator root, so write to a temporary file first
+        let temp_path = Path::new(dir).join(format!("{network}-{epoch:05}.era1.tmp"));
+        let written = File::create(&temp_path)
+            .map_err(EraError::from)
+            .and_then(|file| era::write_era1(BufWriter::new(file), &blocks));
+        let path = match written {
+            Ok(root) => Path::new(dir).join(era::era1_file_name(network, epoch, root)),
+            Err(err) => {
+                error!(epoch, error = %err, "Failed to write Era1 archive");
+                return;
+            }
+        };
+        if let Err(err) = std::fs::rename(&temp_path, &path) {
+            error!(epoch, error = %err, "Failed to write Era1 archive");
+            return;
+        }
+        exported_count += blocks.len() as u64;
+        info!(
+            epoch,
+            blocks = blocks.len(),
+            path = %path.display(),
+            "Exported Era1 archive"
+        );
+    }
+
+    if reached_merge {
+        info!("Reached the merge, Era1 archives only hold pre-merge blocks");
+    }
+    info!(
+        blocks_exported = exported_count,
+        dir = %dir,
+        "Exported blocks to Era1 archives"
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use clap::Parser;
+    use ethrex_common::H256;
+    use ethrex_p2p::rlpx::eth::blocks::HashOrNumber;
     use ethrex_rpc::RpcNamespace;
 
     /// `--http.addr` must default to `127.0.0.1` so a fresh install on a public
@@ -1407,4 +1786,45 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn sync_checkpoint_accepts_hash_or_number() {
+        let state_root = format!("0x{}", "11".repeat(32));
+        let hash = format!("0x{}", "22".repeat(32));
+
+        let cli = CLI::parse_from(["ethrex", &format!("--sync.checkpoint=1234:{state_root}")]);
+        let checkpoint = cli.opts.sync_checkpoint.unwrap();
+        assert_eq!(checkpoint.block, HashOrNumber::Number(1234));
+        assert_eq!(checkpoint.state_root, H256::repeat_byte(0x11));
+
+        let cli = CLI::parse_from(["ethrex", &format!("--sync.checkpoint={hash}:{state_root}")]);
+        let checkpoint = cli.opts.sync_checkpoint.unwrap();
+        assert_eq!(
+            checkpoint.block,
+            HashOrNumber::Hash(H256::repeat_byte(0x22))
+        );
+
+        assert!(CLI::try_parse_from(["ethrex", "--sync.checkpoint=1234"]).is_err());
+    }
+
+    #[test]
+    fn nat_accepts_modes_and_legacy_extip() {
+        let cli = CLI::parse_from(["ethrex", "--nat=upnp"]);
+        assert_eq!(cli.opts.nat_mode(), NatMode::Upnp);
+
+        let cli = CLI::parse_from(["ethrex", "--nat=extip:203.0.113.7"]);
+        assert_eq!(
+            cli.opts.nat_mode(),
+            NatMode::ExtIp("203.0.113.7".parse().unwrap())
+        );
+
+        let cli = CLI::parse_from(["ethrex", "--nat.extip=203.0.113.7"]);
+        assert_eq!(
+            cli.opts.nat_mode(),
+            NatMode::ExtIp("203.0.113.7".parse().unwrap())
+        );
+
+        assert!(CLI::try_parse_from(["ethrex", "--nat=stun"]).is_err());
+        assert!(CLI::try_parse_from(["ethrex", "--nat=pmp", "--nat.extip=203.0.113.7"]).is_err());
+    }
 }
@@ -0,0 +1,1033 @@
+//! Reading and writing of Era1 and Era history archives.
+//!
+//! Both formats are [e2store](https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md)
+//! files: a sequence of `type (2 bytes) | length (4 bytes, LE) | reserved (2 bytes) | value`
+//! entries, with compressed values using the snappy framed format.
+//!
+//! - Era1 archives hold up to 8192 pre-merge execution blocks (header, body, receipts and
+//!   total difficulty) followed by the SSZ root of the header accumulator, which we verify
+//!   against the blocks in the file when reading it.
+//! - Era archives hold the signed beacon blocks of 8192 slots and the beacon state at the
+//!   end of the period. We only read them, extracting the execution payload of post-merge
+//!   blocks and checking it hashes to the block hash committed in the beacon block.
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, Bloom, H256, U256,
+    constants::DEFAULT_OMMERS_HASH,
+    types::{
+        Block, BlockBody, BlockHeader, BlockNumber, Receipt, ReceiptWithBloom, Transaction,
+        Withdrawal, compute_transactions_root, compute_withdrawals_root,
+        requests::{EncodedRequests, compute_requests_hash},
+    },
+};
+use ethrex_rlp::{decode::RLPDecode, encode::RLPEncode, error::RLPDecodeError};
+use sha2::{Digest, Sha256};
+
+/// Maximum number of blocks in an Era1 archive, which is also the accumulator list limit.
+pub const MAX_ERA1_BLOCKS: usize = 8192;
+
+const VERSION: u16 = 0x3265;
+const COMPRESSED_SIGNED_BEACON_BLOCK: u16 = 0x01;
+const COMPRESSED_HEADER: u16 = 0x03;
+const COMPRESSED_BODY: u16 = 0x04;
+const COMPRESSED_RECEIPTS: u16 = 0x05;
+const TOTAL_DIFFICULTY: u16 = 0x06;
+const ACCUMULATOR: u16 = 0x07;
+const BLOCK_INDEX: u16 = 0x3266;
+
+const ENTRY_HEADER_LEN: u64 = 8;
+/// Depth of the accumulator merkle tree, `log2(MAX_ERA1_BLOCKS)`.
+const ACCUMULATOR_DEPTH: usize = 13;
+
+// Sizes of the fixed part of `BeaconBlockBody` for each fork with an execution payload.
+const BELLATRIX_BODY_FIXED_LEN: usize = 384;
+const CAPELLA_BODY_FIXED_LEN: usize = 388;
+const DENEB_BODY_FIXED_LEN: usize = 392;
+const ELECTRA_BODY_FIXED_LEN: usize = 396;
+// Sizes of the fixed part of `ExecutionPayload` for each fork.
+const BELLATRIX_PAYLOAD_FIXED_LEN: usize = 508;
+const CAPELLA_PAYLOAD_FIXED_LEN: usize = 512;
+const DENEB_PAYLOAD_FIXED_LEN: usize = 528;
+
+#[derive(Debug, thiserror::Error)]
+pub enum EraError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+    #[error("RLP decode error: {0}")]
+    Rlp(#[from] RLPDecodeError),
+    #[error("Invalid archive: {0}")]
+    InvalidArchive(String),
+    #[error("Invalid beacon block: {0}")]
+    InvalidBeaconBlock(String),
+    #[error("Accumulator root mismatch: archive has {expected:#x}, blocks hash to {computed:#x}")]
+    AccumulatorMismatch { expected: H256, computed: H256 },
+    #[error("Block {number} hash mismatch: expected {expected:#x}, got {computed:#x}")]
+    BlockHashMismatch {
+        number: BlockNumber,
+        expected: H256,
+        computed: H256,
+    },
+}
+
+/// A pre-merge block as stored in an Era1 archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Era1Block {
+    pub block: Block,
+    /// RLP-encoded receipts. They are kept encoded as pre-Byzantium receipts hold an
+    /// intermediate state root, which [`Receipt`] can't represent.
+    pub receipts: Bytes,
+    pub total_difficulty: U256,
+}
+
+impl Era1Block {
+    pub fn new(block: Block, receipts: &[Receipt], total_difficulty: U256) -> Self {
+        let receipts: Vec<ReceiptWithBloom> = receipts.iter().map(ReceiptWithBloom::from).collect();
+        Self {
+            block,
+            receipts: receipts.encode_to_vec().into(),
+            total_difficulty,
+        }
+    }
+}
+
+/// Returns the canonical name of the Era1 archive for `epoch`, e.g. `mainnet-00000-5ec1ffb8.era1`.
+pub fn era1_file_name(network: &str, epoch: u64, accumulator_root: H256) -> String {
+    format!(
+        "{network}-{epoch:05}-{}.era1",
+        hex::encode(&accumulator_root[..4])
+    )
+}
+
+/// Reads an Era1 archive, checking the headers against the accumulator root stored in
+/// the archive.
+pub fn read_era1(reader: impl Read + Seek) -> Result<Vec<Era1Block>, EraError> {
+    let mut reader = E2StoreReader::new(reader)?;
+    let mut headers = Vec::new();
+    let mut bodies = Vec::new();
+    let mut receipts = Vec::new();
+    let mut total_difficulties = Vec::new();
+    let mut accumulator = None;
+    let mut index = None;
+    while let Some((entry_type, len)) = reader.next_entry()? {
+        match entry_type {
+            COMPRESSED_HEADER => headers.push(decompress(&reader.read_value(len)?)?),
+            COMPRESSED_BODY => bodies.push(decompress(&reader.read_value(len)?)?),
+            COMPRESSED_RECEIPTS => receipts.push(decompress(&reader.read_value(len)?)?),
+            TOTAL_DIFFICULTY => {
+                let value = reader.read_value(len)?;
+                if value.len() != 32 {
+                    return Err(invalid_archive("total difficulty must be 32 bytes"));
+                }
+                total_difficulties.push(U256::from_little_endian(&value));
+            }
+            ACCUMULATOR => {
+                let value = reader.read_value(len)?;
+                if value.len() != 32 {
+                    return Err(invalid_archive("accumulator root must be 32 bytes"));
+                }
+                accumulator = Some(H256::from_slice(&value));
+            }
+            BLOCK_INDEX => index = Some(parse_block_index(&reader.read_value(len)?)?),
+            _ => reader.skip_value(len)?,
+        }
+    }
+
+    let expected_root = accumulator.ok_or_else(|| invalid_archive("missing accumulator"))?;
+    let (start_number, count) = index.ok_or_else(|| invalid_archive("missing block index"))?;
+    let count = usize::try_from(count).unwrap_or(usize::MAX);
+    if count > MAX_ERA1_BLOCKS
+        || [bodies.len(), receipts.len(), total_difficulties.len()]
+            .iter()
+            .any(|&len| len != headers.len())
+        || headers.len() != count
+    {
+        return Err(invalid_archive("block tuples don't match the block index"));
+    }
+
+    let mut blocks = Vec::with_capacity(count);
+    for (i, (((header, body), receipts), total_difficulty)) in headers
+        .iter()
+        .zip(&bodies)
+        .zip(receipts)
+        .zip(total_difficulties)
+        .enumerate()
+    {
+        let header = BlockHeader::decode(header)?;
+        if header.number != start_number + i as u64 {
+            return Err(invalid_archive(&format!(
+                "block {} found at index {i} of an archive starting at {start_number}",
+                header.number
+            )));
+        }
+        if let Some(previous) = blocks.last().map(|b: &Era1Block| b.total_difficulty)
+            && previous + header.difficulty != total_difficulty
+        {
+            return Err(invalid_archive(&format!(
+                "total difficulty of block {} doesn't match its difficulty",
+                header.number
+            )));
+        }
+        blocks.push(Era1Block {
+            block: Block::new(header, BlockBody::decode(body)?),
+            receipts: receipts.into(),
+            total_difficulty,
+        });
+    }
+
+    let computed = accumulator_root(
+        blocks
+            .iter()
+            .map(|block| (block.block.hash(), block.total_difficulty)),
+    );
+    if computed != expected_root {
+        return Err(EraError::AccumulatorMismatch {
+            expected: expected_root,
+            computed,
+        });
+    }
+    Ok(blocks)
+}
+
+/// Writes `blocks` (consecutive, at most [`MAX_ERA1_BLOCKS`]) as an Era1 archive and
+/// returns its accumulator root.
+pub fn write_era1(writer: impl Write, blocks: &[Era1Block]) -> Result<H256, EraError> {
+    let Some(first) = blocks.first() else {
+        return Err(invalid_archive("an archive needs at least one block"));
+    };
+    if blocks.len() > MAX_ERA1_BLOCKS {
+        return Err(invalid_archive(&format!(
+            "an archive holds at most {MAX_ERA1_BLOCKS} blocks"
+        )));
+    }
+    let mut writer = E2StoreWriter::new(writer)?;
+    let mut offsets = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        offsets.push(writer.write_entry(
+            COMPRESSED_HEADER,
+            &compress(&block.block.header.encode_to_vec())?,
+        )?);
+        writer.write_entry(
+            COMPRESSED_BODY,
+            &compress(&block.block.body.encode_to_vec())?,
+        )?;
+        writer.write_entry(COMPRESSED_RECEIPTS, &compress(&block.receipts)?)?;
+        writer.write_entry(TOTAL_DIFFICULTY, &block.total_difficulty.to_little_endian())?;
+    }
+
+    let root = accumulator_root(
+        blocks
+            .iter()
+            .map(|block| (block.block.hash(), block.total_difficulty)),
+    );
+    writer.write_entry(ACCUMULATOR, root.as_bytes())?;
+
+    // Offsets in the index are relative to the start of the index entry
+    let index_offset = writer.position;
+    let mut index = Vec::with_capacity(16 + offsets.len() * 8);
+    index.extend_from_slice(&first.block.header.number.to_le_bytes());
+    for offset in offsets {
+        index.extend_from_slice(&(offset as i64 - index_offset as i64).to_le_bytes());
+    }
+    index.extend_from_slice(&(blocks.len() as u64).to_le_bytes());
+    writer.write_entry(BLOCK_INDEX, &index)?;
+    writer.inner.flush()?;
+    Ok(root)
+}
+
+/// Computes the root of the header accumulator of an Era1 archive: the SSZ
+/// `hash_tree_root` of a `List[HeaderRecord, 8192]`, where each `HeaderRecord` holds
+/// the block hash and the total difficulty.
+pub fn accumulator_root(records: impl IntoIterator<Item = (H256, U256)>) -> H256 {
+    let mut layer: Vec<[u8; 32]> = records
+        .into_iter()
+        .map(|(hash, total_difficulty)| {
+            sha256_pair(hash.as_fixed_bytes(), &total_difficulty.to_little_endian())
+        })
+        .collect();
+    let len = layer.len() as u64;
+
+    let mut zero_hash = [0u8; 32];
+    for _ in 0..ACCUMULATOR_DEPTH {
+        if layer.len() % 2 == 1 {
+            layer.push(zero_hash);
+        }
+        layer = layer
+            .chunks_exact(2)
+            .map(|pair| sha256_pair(&pair[0], &pair[1]))
+            .collect();
+        zero_hash = sha256_pair(&zero_hash, &zero_hash);
+    }
+    let root = layer.first().copied().unwrap_or(zero_hash);
+
+    let mut length = [0u8; 32];
+    length[..8].copy_from_slice(&len.to_le_bytes());
+    H256(sha256_pair(&root, &length))
+}
+
+/// Reads an Era archive and returns the execution blocks of its post-merge slots.
+pub fn read_era(reader: impl Read + Seek) -> Result<Vec<Block>, EraError> {
+    let mut reader = E2StoreReader::new(reader)?;
+    let mut blocks = Vec::new();
+    while let Some((entry_type, len)) = reader.next_entry()? {
+        if entry_type == COMPRESSED_SIGNED_BEACON_BLOCK {
+            let beacon_block = decompress(&reader.read_value(len)?)?;
+            blocks.extend(execution_block(&beacon_block)?);
+        } else {
+            // Beacon states are large and not needed, skip them without reading
+            reader.skip_value(len)?;
+        }
+    }
+    Ok(blocks)
+}
+
+/// Extracts the execution block from an SSZ-encoded `SignedBeaconBlock`, returns `None`
+/// for blocks without an execution payload (pre-Bellatrix or pre-merge).
+fn execution_block(signed_block: &[u8]) -> Result<Option<Block>, EraError> {
+    let message = ssz_slice(
+        signed_block,
+        ssz_offset(signed_block, 0)?,
+        signed_block.len(),
+    )?;
+    let parent_root = H256::from_slice(ssz_slice(message, 16, 48)?);
+    let body = ssz_slice(message, ssz_offset(message, 80)?, message.len())?;
+    // The first variable-size field starts right after the fixed part, whose size tells
+    // us the fork of the block
+    let fixed_len = ssz_offset(body, 200)?;
+    if fixed_len < BELLATRIX_BODY_FIXED_LEN {
+        return Ok(None);
+    }
+    if ![
+        BELLATRIX_BODY_FIXED_LEN,
+        CAPELLA_BODY_FIXED_LEN,
+        DENEB_BODY_FIXED_LEN,
+        ELECTRA_BODY_FIXED_LEN,
+    ]
+    .contains(&fixed_len)
+    {
+        return Err(EraError::InvalidBeaconBlock(format!(
+            "unsupported block body layout with a {fixed_len} bytes fixed part"
+        )));
+    }
+    let payload_end = if fixed_len > BELLATRIX_BODY_FIXED_LEN {
+        ssz_offset(body, 384)?
+    } else {
+        body.len()
+    };
+    let payload = ssz_slice(body, ssz_offset(body, 380)?, payload_end)?;
+    let has_withdrawals = fixed_len >= CAPELLA_BODY_FIXED_LEN;
+    let has_blobs = fixed_len >= DENEB_BODY_FIXED_LEN;
+    let payload_fixed_len = match (has_withdrawals, has_blobs) {
+        (_, true) => DENEB_PAYLOAD_FIXED_LEN,
+        (true, false) => CAPELLA_PAYLOAD_FIXED_LEN,
+        (false, false) => BELLATRIX_PAYLOAD_FIXED_LEN,
+    };
+    if payload.len() < payload_fixed_len {
+        return Err(EraError::InvalidBeaconBlock(
+            "execution payload is too short".to_string(),
+        ));
+    }
+
+    let block_hash = H256::from_slice(&payload[472..504]);
+    if block_hash.is_zero() {
+        return Ok(None);
+    }
+    let extra_data_offset = ssz_offset(payload, 436)?;
+    let transactions_offset = ssz_offset(payload, 504)?;
+    let transactions_end = if has_withdrawals {
+        ssz_offset(payload, 508)?
+    } else {
+        payload.len()
+    };
+    let transactions = ssz_byte_lists(ssz_slice(payload, transactions_offset, transactions_end)?)?
+        .into_iter()
+        .map(Transaction::decode_canonical)
+        .collect::<Result<Vec<_>, _>>()?;
+    let withdrawals = if has_withdrawals {
+        let withdrawals = ssz_slice(payload, transactions_end, payload.len())?;
+        if withdrawals.len() % 44 != 0 {
+            return Err(EraError::InvalidBeaconBlock(
+                "invalid withdrawals length".to_string(),
+            ));
+        }
+        Some(
+            withdrawals
+                .chunks_exact(44)
+                .map(|withdrawal| Withdrawal {
+                    index: u64_at(withdrawal, 0),
+                    validator_index: u64_at(withdrawal, 8),
+                    address: Address::from_slice(&withdrawal[16..36]),
+                    amount: u64_at(withdrawal, 36),
+                })
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        None
+    };
+    let base_fee_per_gas = U256::from_little_endian(&payload[440..472]);
+    if base_fee_per_gas > U256::from(u64::MAX) {
+        return Err(EraError::InvalidBeaconBlock(
+            "base fee doesn't fit in 64 bits".to_string(),
+        ));
+    }
+    let requests_hash = if fixed_len >= ELECTRA_BODY_FIXED_LEN {
+        let requests = ssz_slice(body, ssz_offset(body, 392)?, body.len())?;
+        Some(execution_requests_hash(requests)?)
+    } else {
+        None
+    };
+
+    let body = BlockBody {
+        transactions,
+        ommers: vec![],
+        withdrawals,
+    };
+    let header = BlockHeader {
+        parent_hash: H256::from_slice(&payload[0..32]),
+        ommers_hash: *DEFAULT_OMMERS_HASH,
+        coinbase: Address::from_slice(&payload[32..52]),
+        state_root: H256::from_slice(&payload[52..84]),
+        transactions_root: compute_transactions_root(
+            &body.transactions,
+            &ethrex_crypto::NativeCrypto,
+        ),
+        receipts_root: H256::from_slice(&payload[84..116]),
+        logs_bloom: Bloom::from_slice(&payload[116..372]),
+        difficulty: U256::zero(),
+        number: u64_at(payload, 404),
+        gas_limit: u64_at(payload, 412),
+        gas_used: u64_at(payload, 420),
+        timestamp: u64_at(payload, 428),
+        extra_data: Bytes::copy_from_slice(ssz_slice(
+            payload,
+            extra_data_offset,
+            transactions_offset,
+        )?),
+        prev_randao: H256::from_slice(&payload[372..404]),
+        nonce: 0,
+        base_fee_per_gas: Some(base_fee_per_gas.as_u64()),
+        withdrawals_root: body
+            .withdrawals
+            .as_ref()
+            .map(|w| compute_withdrawals_root(w, &ethrex_crypto::NativeCrypto)),
+        blob_gas_used: has_blobs.then(|| u64_at(payload, 512)),
+        excess_blob_gas: has_blobs.then(|| u64_at(payload, 520)),
+        parent_beacon_block_root: has_blobs.then_some(parent_root),
+        requests_hash,
+        ..Default::default()
+    };
+    let block = Block::new(header, body);
+    if block.hash() != block_hash {
+        return Err(EraError::BlockHashMismatch {
+            number: block.header.number,
+            expected: block_hash,
+            computed: block.hash(),
+        });
+    }
+    Ok(Some(block))
+}
+
+/// Computes the EIP-7685 requests hash of an SSZ-encoded `ExecutionRequests`.
+fn execution_requests_hash(requests: &[u8]) -> Result<H256, EraError> {
+    let deposits = ssz_offset(requests, 0)?;
+    let withdrawals = ssz_offset(requests, 4)?;
+    let consolidations = ssz_offset(requests, 8)?;
+    let encoded = [
+        (0x00, ssz_slice(requests, deposits, withdrawals)?),
+        (0x01, ssz_slice(requests, withdrawals, consolidations)?),
+        (0x02, ssz_slice(requests, consolidations, requests.len())?),
+    ]
+    .map(|(request_type, data)| EncodedRequests([&[request_type][..], data].concat().into()));
+    Ok(compute_requests_hash(&encoded))
+}
+
+/// Splits an SSZ list of variable-size byte lists into its items.
+fn ssz_byte_lists(bytes: &[u8]) -> Result<Vec<&[u8]>, EraError> {
+    if bytes.is_empty() {
+        return Ok(Vec::new());
+    }
+    let first = ssz_offset(bytes, 0)?;
+    if first == 0 || first % 4 != 0 {
+        return Err(EraError::InvalidBeaconBlock(
+            "invalid list offset".to_string(),
+        ));
+    }
+    let mut offsets = (0..first / 4)
+        .map(|i| ssz_offset(bytes, i * 4))
+        .collect::<Result<Vec<_>, _>>()?;
+    offsets.push(bytes.len());
+    offsets
+        .windows(2)
+        .map(|window| ssz_slice(bytes, window[0], window[1]))
+        .collect()
+}
+
+fn ssz_offset(bytes: &[u8], at: usize) -> Result<usize, EraError> {
+    let offset = ssz_slice(bytes, at, at + 4)?;
+    Ok(u32::from_le_bytes([offset[0], offset[1], offset[2], offset[3]]) as usize)
+}
+
+fn ssz_slice(bytes: &[u8], start: usize, end: usize) -> Result<&[u8], EraError> {
+    bytes
+        .get(start..end)
+        .ok_or_else(|| EraError::InvalidBeaconBlock(format!("out of bounds range {start}..{end}")))
+}
+
+/// Reads a little-endian `u64` at `at`, which must be in bounds.
+fn u64_at(bytes: &[u8], at: usize) -> u64 {
+    let mut value = [0u8; 8];
+    value.copy_from_slice(&bytes[at..at + 8]);
+    u64::from_le_bytes(value)
+}
+
+/// Parses an Era1 block index into its starting block number and block count.
+fn parse_block_index(index: &[u8]) -> Result<(BlockNumber, u64), EraError> {
+    if index.len() < 16 || index.len() % 8 != 0 {
+        return Err(invalid_archive("invalid block index length"));
+    }
+    let start = u64_at(index, 0);
+    let count = u64_at(index, index.len() - 8);
+    if count != (index.len() as u64 - 16) / 8 {
+        return Err(invalid_archive(
+            "block index count doesn't match its offsets",
+        ));
+    }
+    Ok((start, count))
+}
+
+fn sha256_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut compressed = Vec::new();
+    {
+        let mut encoder = snap::write::FrameEncoder::new(&mut compressed);
+        encoder.write_all(data)?;
+        encoder.flush()?;
+    }
+    Ok(compressed)
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>, EraError> {
+    let mut decompressed = Vec::new();
+    snap::read::FrameDecoder::new(data).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+fn invalid_archive(reason: &str) -> EraError {
+    EraError::InvalidArchive(reason.to_string())
+}
+
+/// Sequential reader of e2store entries that checks the leading version entry.
+struct E2StoreReader<R> {
+    inner: R,
+}
+
+impl<R: Read + Seek> E2StoreReader<R> {
+    fn new(inner: R) -> Result<Self, EraError> {
+        let mut reader = Self { inner };
+        match reader.next_entry()? {
+            Some((VERSION, 0)) => Ok(reader),
+            _ => Err(invalid_archive("missing version entry")),
+        }
+    }
+
+    /// Reads the header of the next entry, returning its type and value length, or
+    /// `None` at the end of the file.
+    fn next_entry(&mut self) -> Result<Option<(u16, u32)>, EraError> {
+        let mut header = [0u8; ENTRY_HEADER_LEN as usize];
+        if self.inner.read(&mut header[..1])? == 0 {
+            return Ok(None);
+        }
+        self.inner.read_exact(&mut header[1..])?;
+        if header[6..] != [0, 0] {
+            return Err(invalid_archive("non-zero reserved entry bytes"));
+        }
+        let entry_type = u16::from_le_bytes([header[0], header[1]]);
+        let len = u32::from_le_bytes([header[2], header[3], header[4], header[5]]);
+        Ok(Some((entry_type, len)))
+    }
+
+    fn read_value(&mut self, len: u32) -> Result<Vec<u8>, EraError> {
+        let mut value = vec![0u8; len as usize];
+        self.inner.read_exact(&mut value)?;
+        Ok(value)
+    }
+
+    fn skip_value(&mut self, len: u32) -> Result<(), EraError> {
+        self.inner.seek(SeekFrom::Current(len.into()))?;
+        Ok(())
+    }
+}
+
+/// Writer of e2store entries that starts with the version entry and tracks offsets.
+struct E2StoreWriter<W> {
+    inner: W,
+    position: u64,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    fn new(inner: W) -> Result<Self, EraError> {
+        let mut writer = Self { inner, position: 0 };
+        writer.write_entry(VERSION, &[])?;
+        Ok(writer)
+    }
+
+    /// Writes an entry and returns its offset in the file.
+    fn write_entry(&mut self, entry_type: u16, value: &[u8]) -> Result<u64, EraError> {
+        let len = u32::try_from(value.len())
+            .map_err(|_| invalid_archive("entry value doesn't fit in 32 bits"))?;
+        let offset = self.position;
+        self.inner.write_all(&entry_type.to_le_bytes())?;
+        self.inner.write_all(&len.to_le_bytes())?;
+        self.inner.write_all(&[0, 0])?;
+        self.inner.write_all(value)?;
+        self.position += ENTRY_HEADER_LEN + u64::from(len);
+        Ok(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::{
+        EIP1559Transaction, LegacyTransaction, Log, TxKind, TxType, compute_receipts_root,
+    };
+    use std::io::Cursor;
+
+    /// Deposit request of the Electra fixture block, an SSZ `DepositRequest` is 192 bytes.
+    const DEPOSIT_REQUEST: [u8; 192] = [9; 192];
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+    enum Fork {
+        Altair,
+        Bellatrix,
+        Capella,
+        Deneb,
+        Electra,
+    }
+
+    enum SszField {
+        Fixed(Vec<u8>),
+        Variable(Vec<u8>),
+    }
+
+    /// Encodes an SSZ container: fixed fields and offsets first, then variable fields.
+    fn ssz_container(fields: Vec<SszField>) -> Vec<u8> {
+        let fixed_len: usize = fields
+            .iter()
+            .map(|field| match field {
+                SszField::Fixed(bytes) => bytes.len(),
+                SszField::Variable(_) => 4,
+            })
+            .sum();
+        let mut fixed = Vec::new();
+        let mut variable = Vec::new();
+        for field in fields {
+            match field {
+                SszField::Fixed(bytes) => fixed.extend(bytes),
+                SszField::Variable(bytes) => {
+                    fixed.extend(((fixed_len + variable.len()) as u32).to_le_bytes());
+                    variable.extend(bytes);
+                }
+            }
+        }
+        fixed.extend(variable);
+        fixed
+    }
+
+    fn ssz_list_of_byte_lists(items: Vec<Vec<u8>>) -> Vec<u8> {
+        ssz_container(items.into_iter().map(SszField::Variable).collect())
+    }
+
+    /// Execution block of the fixture beacon block of `fork`.
+    fn fixture_block(fork: Fork) -> Block {
+        let transactions = vec![
+            Transaction::LegacyTransaction(LegacyTransaction {
+                nonce: 1,
+                gas_price: U256::from(10),
+                gas: 21_000,
+                to: TxKind::Call(Address::repeat_byte(0xaa)),
+                value: U256::from(100),
+                v: U256::from(27),
+                r: U256::one(),
+                s: U256::one(),
+                ..Default::default()
+            }),
+            Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id: 1,
+                nonce: 2,
+                max_priority_fee_per_gas: 1,
+                max_fee_per_gas: 10,
+                gas_limit: 21_000,
+                to: TxKind::Call(Address::repeat_byte(0xbb)),
+                data: Bytes::from_static(&[1, 2, 3]),
+                signature_r: U256::one(),
+                signature_s: U256::one(),
+                ..Default::default()
+            }),
+        ];
+        let withdrawals = (fork >= Fork::Capella).then(|| {
+            vec![Withdrawal {
+                index: 1,
+                validator_index: 2,
+                address: Address::repeat_byte(3),
+                amount: 4,
+            }]
+        });
+        let requests_hash = (fork >= Fork::Electra).then(|| {
+            // Only the deposits list is non-empty, the empty request types aren't hashed
+            let deposits = [&[0x00][..], &DEPOSIT_REQUEST].concat();
+            H256::from_slice(&Sha256::digest(Sha256::digest(deposits)))
+        });
+        let header = BlockHeader {
+            parent_hash: H256::repeat_byte(1),
+            ommers_hash: *DEFAULT_OMMERS_HASH,
+            coinbase: Address::repeat_byte(2),
+            state_root: H256::repeat_byte(3),
+            transactions_root: compute_transactions_root(
+                &transactions,
+                &ethrex_crypto::NativeCrypto,
+            ),
+            receipts_root: H256::repeat_byte(4),
+            logs_bloom: Bloom::repeat_byte(5),
+            prev_randao: H256::repeat_byte(6),
+            number: 15_537_394,
+            gas_limit: 30_000_000,
+            gas_used: 42_000,
+            timestamp: 1_663_224_179,
+            extra_data: Bytes::from_static(b"ethrex"),
+            base_fee_per_gas: Some(7),
+            withdrawals_root: withdrawals
+                .as_ref()
+                .map(|w| compute_withdrawals_root(w, &ethrex_crypto::NativeCrypto)),
+            blob_gas_used: (fork >= Fork::Deneb).then_some(131_072),
+            excess_blob_gas: (fork >= Fork::Deneb).then_some(262_144),
+            parent_beacon_block_root: (fork >= Fork::Deneb).then(|| H256::repeat_byte(8)),
+            requests_hash,
+            ..Default::default()
+        };
+        Block::new(
+            header,
+            BlockBody {
+                transactions,
+                ommers: vec![],
+                withdrawals,
+            },
+        )
+    }
+
+    /// SSZ `ExecutionPayload` of `block` as of `fork`, committing to `block_hash`.
+    fn execution_payload(fork: Fork, block: &Block, block_hash: H256) -> Vec<u8> {
+        let header = &block.header;
+        let mut base_fee_per_gas = [0u8; 32];
+        base_fee_per_gas[..8].copy_from_slice(&header.base_fee_per_gas.unwrap_or(0).to_le_bytes());
+        let mut fields = vec![
+            SszField::Fixed(header.parent_hash.as_bytes().to_vec()),
+            SszField::Fixed(header.coinbase.as_bytes().to_vec()),
+            SszField::Fixed(header.state_root.as_bytes().to_vec()),
+            SszField::Fixed(header.receipts_root.as_bytes().to_vec()),
+            SszField::Fixed(header.logs_bloom.as_bytes().to_vec()),
+            SszField::Fixed(header.prev_randao.as_bytes().to_vec()),
+            SszField::Fixed(header.number.to_le_bytes().to_vec()),
+            SszField::Fixed(header.gas_limit.to_le_bytes().to_vec()),
+            SszField::Fixed(header.gas_used.to_le_bytes().to_vec()),
+            SszField::Fixed(header.timestamp.to_le_bytes().to_vec()),
+            SszField::Variable(header.extra_data.to_vec()),
+            SszField::Fixed(base_fee_per_gas.to_vec()),
+            SszField::Fixed(block_hash.as_bytes().to_vec()),
+            SszField::Variable(ssz_list_of_byte_lists(
+                block
+                    .body
+                    .transactions
+                    .iter()
+                    .map(Transaction::encode_canonical_to_vec)
+                    .collect(),
+            )),
+        ];
+        if fork >= Fork::Capella {
+            let withdrawals = block.body.withdrawals.iter().flatten().flat_map(|w| {
+                [
+                    &w.index.to_le_bytes()[..],
+                    &w.validator_index.to_le_bytes(),
+                    w.address.as_bytes(),
+                    &w.amount.to_le_bytes(),
+                ]
+                .concat()
+            });
+            fields.push(SszField::Variable(withdrawals.collect()));
+        }
+        if fork >= Fork::Deneb {
+            let blob_gas_used = header.blob_gas_used.unwrap_or(0);
+            let excess_blob_gas = header.excess_blob_gas.unwrap_or(0);
+            fields.push(SszField::Fixed(blob_gas_used.to_le_bytes().to_vec()));
+            fields.push(SszField::Fixed(excess_blob_gas.to_le_bytes().to_vec()));
+        }
+        ssz_container(fields)
+    }
+
+    /// SSZ `SignedBeaconBlock` of `fork` holding `block` as its execution payload.
+    fn signed_beacon_block(fork: Fork, block: &Block, block_hash: H256) -> Vec<u8> {
+        let mut body = vec![
+            // randao_reveal, eth1_data and graffiti
+            SszField::Fixed(vec![0; 96]),
+            SszField::Fixed(vec![0; 72]),
+            SszField::Fixed(vec![0; 32]),
+            // proposer_slashings, attester_slashings, attestations, deposits, voluntary_exits
+            SszField::Variable(vec![]),
+            SszField::Variable(vec![]),
+            SszField::Variable(vec![]),
+            SszField::Variable(vec![]),
+            SszField::Variable(vec![]),
+            // sync_aggregate
+            SszField::Fixed(vec![0; 160]),
+        ];
+        if fork >= Fork::Bellatrix {
+            body.push(SszField::Variable(execution_payload(
+                fork, block, block_hash,
+            )));
+        }
+        if fork >= Fork::Capella {
+            // bls_to_execution_changes
+            body.push(SszField::Variable(vec![]));
+        }
+        if fork >= Fork::Deneb {
+            // blob_kzg_commitments
+            body.push(SszField::Variable(vec![]));
+        }
+        if fork >= Fork::Electra {
+            body.push(SszField::Variable(ssz_container(vec![
+                SszField::Variable(DEPOSIT_REQUEST.to_vec()),
+                SszField::Variable(vec![]),
+                SszField::Variable(vec![]),
+            ])));
+        }
+        let parent_root = block.header.parent_beacon_block_root.unwrap_or_default();
+        let message = ssz_container(vec![
+            // slot and proposer_index
+            SszField::Fixed(block.header.number.to_le_bytes().to_vec()),
+            SszField::Fixed(0u64.to_le_bytes().to_vec()),
+            SszField::Fixed(parent_root.as_bytes().to_vec()),
+            SszField::Fixed(vec![0; 32]),
+            SszField::Variable(ssz_container(body)),
+        ]);
+        ssz_container(vec![
+            SszField::Variable(message),
+            // signature
+            SszField::Fixed(vec![0; 96]),
+        ])
+    }
+
+    fn fixture_beacon_block(fork: Fork) -> Vec<u8> {
+        let block = fixture_block(fork);
+        signed_beacon_block(fork, &block, block.hash())
+    }
+
+    /// Era archive with the given beacon blocks followed by a beacon state.
+    fn era_archive(beacon_blocks: &[Vec<u8>]) -> Vec<u8> {
+        let mut archive = Vec::new();
+        let mut writer = E2StoreWriter::new(&mut archive).unwrap();
+        for beacon_block in beacon_blocks {
+            writer
+                .write_entry(
+                    COMPRESSED_SIGNED_BEACON_BLOCK,
+                    &compress(beacon_block).unwrap(),
+                )
+                .unwrap();
+        }
+        // Compressed beacon state, skipped by the reader
+        writer
+            .write_entry(0x02, &compress(&[0xff; 1024]).unwrap())
+            .unwrap();
+        archive
+    }
+
+    fn assert_same_block(read: &Block, expected: &Block) {
+        assert_eq!(read.hash(), expected.hash());
+        assert_eq!(read.header, expected.header);
+        assert_eq!(read.body.withdrawals, expected.body.withdrawals);
+        assert_eq!(
+            read.body
+                .transactions
+                .iter()
+                .map(Transaction::encode_canonical_to_vec)
+                .collect::<Vec<_>>(),
+            expected
+                .body
+                .transactions
+                .iter()
+                .map(Transaction::encode_canonical_to_vec)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn era_execution_blocks_of_each_fork() {
+        let forks = [Fork::Bellatrix, Fork::Capella, Fork::Deneb, Fork::Electra];
+        let pre_merge = fixture_block(Fork::Bellatrix);
+        let mut beacon_blocks = vec![
+            // Neither pre-Bellatrix blocks nor pre-merge blocks with an empty payload
+            // have an execution block
+            signed_beacon_block(Fork::Altair, &pre_merge, H256::zero()),
+            signed_beacon_block(Fork::Bellatrix, &pre_merge, H256::zero()),
+        ];
+        beacon_blocks.extend(forks.map(fixture_beacon_block));
+
+        let blocks = read_era(Cursor::new(era_archive(&beacon_blocks))).unwrap();
+
+        assert_eq!(blocks.len(), forks.len());
+        for (block, fork) in blocks.iter().zip(forks) {
+            assert_same_block(block, &fixture_block(fork));
+        }
+    }
+
+    #[test]
+    fn era_block_with_wrong_hash_is_rejected() {
+        let block = fixture_block(Fork::Deneb);
+        let beacon_block = signed_beacon_block(Fork::Deneb, &block, H256::repeat_byte(0xee));
+
+        assert!(matches!(
+            execution_block(&beacon_block),
+            Err(EraError::BlockHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn truncated_era_beacon_block_is_rejected() {
+        for fork in [Fork::Bellatrix, Fork::Capella, Fork::Deneb, Fork::Electra] {
+            let beacon_block = fixture_beacon_block(fork);
+            for len in 0..beacon_block.len() {
+                assert!(
+                    execution_block(&beacon_block[..len]).is_err(),
+                    "{fork:?} block truncated to {len} bytes was accepted"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn era_beacon_block_with_invalid_offset_is_rejected() {
+        let beacon_block = fixture_beacon_block(Fork::Electra);
+        // The message starts right after the signed block's fixed part, its body after the
+        // message's fixed part
+        let body = 100 + 84;
+        let payload = body + ssz_offset(&beacon_block, body + 380).unwrap();
+        let offsets = [
+            // SignedBeaconBlock.message
+            0,
+            // BeaconBlock.body
+            100 + 80,
+            // BeaconBlockBody.execution_payload and execution_requests
+            body + 380,
+            body + 392,
+            // ExecutionPayload.extra_data, transactions and withdrawals
+            payload + 436,
+            payload + 504,
+            payload + 508,
+        ];
+        for at in offsets {
+            for offset in [u32::MAX, beacon_block.len() as u32 + 1] {
+                let mut corrupted = beacon_block.clone();
+                corrupted[at..at + 4].copy_from_slice(&offset.to_le_bytes());
+                assert!(
+                    execution_block(&corrupted).is_err(),
+                    "offset {offset} at {at} was accepted"
+                );
+            }
+        }
+
+        // extra_data ending before it starts
+        let transactions = ssz_offset(&beacon_block, payload + 504).unwrap() as u32;
+        let mut corrupted = beacon_block.clone();
+        corrupted[payload + 436..payload + 440].copy_from_slice(&(transactions + 1).to_le_bytes());
+        assert!(execution_block(&corrupted).is_err());
+    }
+
+    #[test]
+    fn truncated_era_archive_is_rejected() {
+        let archive = era_archive(&[fixture_beacon_block(Fork::Capella)]);
+        let beacon_block_entry_end = archive.len() - 8 - compress(&[0xff; 1024]).unwrap().len();
+
+        assert!(read_era(Cursor::new(&archive[..beacon_block_entry_end - 1])).is_err());
+        assert!(read_era(Cursor::new(&archive[..beacon_block_entry_end])).is_ok());
+    }
+
+    fn era1_blocks(count: u64) -> Vec<Era1Block> {
+        let mut parent_hash = H256::zero();
+        let mut total_difficulty = U256::zero();
+        (0..count)
+            .map(|number| {
+                let receipts = vec![Receipt {
+                    tx_type: TxType::Legacy,
+                    succeeded: true,
+                    cumulative_gas_used: 21_000,
+                    logs: vec![Log {
+                        address: Address::repeat_byte(1),
+                        topics: vec![H256::repeat_byte(2)],
+                        data: Bytes::from_static(&[3]),
+                    }],
+                }];
+                let header = BlockHeader {
+                    parent_hash,
+                    number,
+                    difficulty: U256::from(131_072 + number),
+                    receipts_root: compute_receipts_root(&receipts, &ethrex_crypto::NativeCrypto),
+                    ..Default::default()
+                };
+                parent_hash = header.hash();
+                total_difficulty += header.difficulty;
+                Era1Block::new(
+                    Block::new(header, BlockBody::default()),
+                    &receipts,
+                    total_difficulty,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn era1_round_trip() {
+        let blocks = era1_blocks(5);
+        let mut archive = Vec::new();
+        let root = write_era1(&mut archive, &blocks).unwrap();
+
+        assert_eq!(read_era1(Cursor::new(&archive)).unwrap(), blocks);
+        assert_eq!(
+            root,
+            accumulator_root(
+                blocks
+                    .iter()
+                    .map(|block| (block.block.hash(), block.total_difficulty))
+            )
+        );
+        assert_eq!(
+            era1_file_name("mainnet", 3, root),
+            format!("mainnet-00003-{}.era1", hex::encode(&root[..4]))
+        );
+    }
+
+    #[test]
+    fn era1_with_wrong_accumulator_is_rejected() {
+        let blocks = era1_blocks(3);
+        let mut archive = Vec::new();
+        let root = write_era1(&mut archive, &blocks).unwrap();
+
+        // Flip a byte of the accumulator entry
+        let position = archive
+            .windows(32)
+            .position(|window| window == root.as_bytes())
+            .unwrap();
+        archive[position] ^= 0xff;
+
+        assert!(matches!(
+            read_era1(Cursor::new(&archive)),
+            Err(EraError::AccumulatorMismatch { .. })
+        ));
+    }
+}
@@ -4,12 +4,15 @@ use ethrex::{
     initializers::{init_l1, init_tracing},
     utils::{NodeConfigFile, get_client_version, is_memory_datadir, store_node_config_file},
 };
-use ethrex_p2p::{peer_table::PeerTable, types::NodeRecord};
+use ethrex_p2p::{
+    peer_table::{PeerTable, PeerTableServerProtocol as _},
+    types::NodeRecord,
+};
 use serde::Deserialize;
 use std::{path::Path, time::Duration};
 use tokio::signal::unix::{SignalKind, signal};
 use tokio_util::sync::CancellationToken;
-use tracing::info;
+use tracing::{debug, info};
 
 const LATEST_VERSION_URL: &str = "https://api.github.com/repos/lambdaclass/ethrex/releases/latest";
 
@@ -56,8 +59,16 @@ async fn server_shutdown(
     if !is_memory_datadir(datadir) {
         let node_config_path = datadir.join("node_config.json");
         info!("Storing config at {:?}...", node_config_path);
-        let node_config = NodeConfigFile::new(peer_table, local_node_record).await;
-        store_node_config_file(node_config, node_config_path);
+        store_node_config_file(
+            NodeConfigFile {
+                node_record: local_node_record,
+            },
+            node_config_path,
+        );
+        // The peer table also persists itself on Ctrl-C, but not on SIGTERM
+        if let Err(err) = peer_table.persist_table() {
+            debug!("Could not persist the peer table: {err}");
+        }
         tokio::time::sleep(Duration::from_secs(1)).await;
     }
     info!("Server shutting down!");
@@ -9,7 +9,7 @@ use ethrex_blockchain::{Blockchain, BlockchainOptions, BlockchainType};
 use ethrex_common::fd_limit::raise_fd_limit;
 use ethrex_common::types::Genesis;
 use ethrex_config::networks::Network;
-use ethrex_rpc::WebSocketConfig;
+use ethrex_rpc::{IpcConfig, WebSocketConfig};
 
 use ethrex_metrics::profiling::{FunctionProfilingLayer, initialize_block_processing_profile};
 use ethrex_metrics::rpc::initialize_rpc_metrics;
@@ -18,10 +18,10 @@ use ethrex_p2p::{
     DiscoveryConfig,
     network::P2PContext,
     peer_handler::PeerHandler,
-    peer_table::{PeerTable, PeerTableServer},
+    peer_table::{PeerTable, PeerTableServer, PeerTableServerProtocol as _},
     sync::SyncMode,
     sync_manager::SyncManager,
-    types::{NetworkConfig, Node, NodeRecord},
+    types::{Endpoint, NetworkConfig, Node, NodeRecord},
     utils::public_key_from_signing_key,
 };
 use ethrex_storage::{
@@ -254,17 +254,32 @@ pub async fn init_rpc_api(
         blockchain.clone(),
         store.clone(),
         datadir.to_path_buf(),
+        opts.sync_checkpoint,
     )
     .await;
 
-    let ws_config = if opts.ws_enabled {
-        Some(WebSocketConfig {
+    // WS and IPC share a single subscription manager.
+    let subscription_manager = (opts.ws_enabled || opts.ipc_path.is_some()).then(|| {
+        ethrex_rpc::SubscriptionManager::spawn_with_chain_events(
+            blockchain.subscribe_events(),
+            blockchain.subscribe_pending_transactions(),
+        )
+    });
+    let ws_config = subscription_manager
+        .clone()
+        .filter(|_| opts.ws_enabled)
+        .map(|subscription_manager| WebSocketConfig {
             addr: get_ws_socket_addr(opts),
-            subscription_manager: ethrex_rpc::SubscriptionManager::spawn(),
-        })
-    } else {
-        None
-    };
+            subscription_manager,
+        });
+    let ipc_config =
+        opts.ipc_path
+            .clone()
+            .zip(subscription_manager)
+            .map(|(path, subscription_manager)| IpcConfig {
+                path,
+                subscription_manager,
+            });
 
     let rpc_api = ethrex_rpc::start_api(
         get_http_socket_addr(opts),
@@ -282,6 +297,8 @@ pub async fn init_rpc_api(
         opts.gas_limit,
         opts.extra_data.clone(),
         opts.http_api.iter().copied().collect(),
+        opts.rpc_max_filters,
+        ipc_config,
     );
 
     tracker.spawn(rpc_api);
@@ -291,7 +308,6 @@ pub async fn init_rpc_api(
 pub async fn init_network(
     opts: &Options,
     network: &Network,
-    datadir: &Path,
     peer_handler: PeerHandler,
     tracker: TaskTracker,
     blockchain: Arc<Blockchain>,
@@ -305,11 +321,17 @@ pub async fn init_network(
         );
     }
 
-    let bootnodes = get_bootnodes(opts, network, datadir);
+    let bootnodes = get_bootnodes(opts, network);
 
     let discovery_config = DiscoveryConfig {
         discv4_enabled: opts.discv4_enabled,
         discv5_enabled: opts.discv5_enabled,
+        dns_trees: if opts.dns_trees.is_empty() {
+            network.get_dns_trees()
+        } else {
+            opts.dns_trees.clone()
+        },
+        nat: opts.nat_mode(),
         ..Default::default()
     };
 
@@ -363,19 +385,11 @@ pub fn get_network(opts: &Options) -> Network {
     opts.network.clone().unwrap_or(default)
 }
 
-pub fn get_bootnodes(opts: &Options, network: &Network, datadir: &Path) -> Vec<Node> {
+pub fn get_bootnodes(opts: &Options, network: &Network) -> Vec<Node> {
     let mut bootnodes: Vec<Node> = opts.bootnodes.clone();
 
     bootnodes.extend(network.get_bootnodes());
 
-    debug!("Loading known peers from config");
-
-    match read_node_config_file(datadir) {
-        Ok(Some(ref mut config)) => bootnodes.append(&mut config.known_peers),
-        Ok(None) => {} // No config file, nothing to do
-        Err(e) => warn!("Could not read from peers file: {e}"),
-    };
-
     if bootnodes.is_empty() {
         warn!("No bootnodes specified. This node will not be able to connect to the network.");
     }
@@ -383,6 +397,20 @@ pub fn get_bootnodes(opts: &Options, network: &Network, datadir: &Path) -> Vec<N
     bootnodes
 }
 
+/// Registers the `--p2p.static-peers` and `--p2p.trusted-peers` lists in the peer table.
+pub fn add_static_and_trusted_peers(opts: &Options, peer_table: &PeerTable) {
+    for node in &opts.static_peers {
+        if let Err(err) = peer_table.add_static_peer(node.clone()) {
+            warn!(peer=%node, "Failed to add static peer: {err}");
+        }
+    }
+    for node in &opts.trusted_peers {
+        if let Err(err) = peer_table.add_trusted_peer(node.node_id()) {
+            warn!(peer=%node, "Failed to add trusted peer: {err}");
+        }
+    }
+}
+
 pub fn get_signer(datadir: &Path) -> SecretKey {
     if is_memory_datadir(datadir) {
         return SecretKey::new(&mut OsRng);
@@ -411,14 +439,17 @@ pub fn get_signer(datadir: &Path) -> SecretKey {
 /// Decide the bind and externally-announced addresses for the P2P endpoint.
 ///
 /// Precedence:
-/// - `--nat.extip` wins for the announced address; bind comes from `--p2p.addr` if given,
-///   else the unspecified address of the matching family.
+/// - `--nat extip:<ip>` (or `--nat.extip`) wins for the announced address; bind comes from
+///   `--p2p.addr` if given, else the unspecified address of the matching family.
 /// - `--p2p.addr` alone is used for both bind and announce, except when it's an unspecified
 ///   address (`0.0.0.0` / `::`). In that case the announced address falls back to the
 ///   auto-detected local IP of the matching family; this avoids advertising `0.0.0.0` in
 ///   the ENR, which would make the node unreachable for inbound connections. Operators
-///   behind NAT still need `--nat.extip` for that case to resolve correctly.
+///   behind NAT need `--nat` for that case to resolve correctly.
 /// - With neither flag set, the auto-detected local IP is used for both bind and announce.
+///
+/// Binding to `::` opens dual-stack sockets; see [`dual_stack_ip`] for the second announced
+/// address.
 fn resolve_p2p_endpoints(
     p2p_addr: Option<&str>,
     nat_extip: Option<&str>,
@@ -431,9 +462,11 @@ fn resolve_p2p_endpoints(
             let bind: IpAddr = p2p_addr
                 .map(|a| {
                     let addr: IpAddr = a.parse().expect("Failed to parse p2p address");
+                    // `::` binds a dual-stack socket, which serves both families
                     assert!(
-                        addr.is_ipv4() == external.is_ipv4(),
-                        "--p2p.addr and --nat.extip must use the same address family (both IPv4 or both IPv6)"
+                        addr.is_ipv4() == external.is_ipv4()
+                            || (addr.is_ipv6() && addr.is_unspecified()),
+                        "--p2p.addr and --nat.extip must use the same address family (both IPv4 or both IPv6), unless --p2p.addr is ::"
                     );
                     addr
                 })
@@ -485,6 +518,24 @@ fn resolve_p2p_endpoints(
     }
 }
 
+/// Returns the address of the other family to announce alongside `external` when bound to
+/// `::`, which serves both IPv4 and IPv6 peers.
+fn dual_stack_ip(
+    bind: IpAddr,
+    external: IpAddr,
+    local_v4: Option<IpAddr>,
+    local_v6: Option<IpAddr>,
+) -> Option<IpAddr> {
+    if !(bind.is_ipv6() && bind.is_unspecified()) || external.is_unspecified() {
+        return None;
+    }
+    if external.is_ipv6() {
+        local_v4
+    } else {
+        local_v6
+    }
+}
+
 pub fn get_local_p2p_node(opts: &Options, signer: &SecretKey) -> (Node, NetworkConfig) {
     let tcp_port = opts.p2p_port.parse().expect("Failed to parse p2p port");
     let udp_port = opts
@@ -494,14 +545,23 @@ pub fn get_local_p2p_node(opts: &Options, signer: &SecretKey) -> (Node, NetworkC
 
     let local_public_key = public_key_from_signing_key(signer);
 
+    let nat_extip = opts.nat_mode().external_ip().map(|ip| ip.to_string());
+    let (local_v4, local_v6) = (local_ip().ok(), local_ipv6().ok());
     let (bind_addr, external_addr) = resolve_p2p_endpoints(
         opts.p2p_addr.as_deref(),
-        opts.nat_extip.as_deref(),
-        local_ip().ok(),
-        local_ipv6().ok(),
+        nat_extip.as_deref(),
+        local_v4,
+        local_v6,
     );
 
-    let node = Node::new(external_addr, udp_port, tcp_port, local_public_key);
+    let mut node = Node::new(external_addr, udp_port, tcp_port, local_public_key);
+    if let Some(ip) = dual_stack_ip(bind_addr, external_addr, local_v4, local_v6) {
+        node = node.with_dual_stack_endpoint(Endpoint {
+            ip,
+            udp_port,
+            tcp_port,
+        });
+    }
     let network_config = NetworkConfig {
         bind_addr,
         tcp_port,
@@ -592,6 +652,9 @@ pub async fn init_l1(
 
     let store_config = StoreConfig {
         rocksdb_block_cache_size: opts.rocksdb_block_cache_size,
+        gc_mode: opts.gc_mode,
+        ancient_dir: opts.ancient_datadir.clone(),
+        history_retention: opts.history_retention,
     };
     let store_result = if opts.skip_genesis_validation {
         init_store_skip_validation_with_config(&datadir, genesis, store_config).await
@@ -646,6 +709,7 @@ pub async fn init_l1(
 
     let peer_table =
         PeerTableServer::spawn(local_p2p_node.node_id(), opts.target_peers, store.clone());
+    add_static_and_trusted_peers(&opts, &peer_table);
 
     // TODO: Check every module starts properly.
     let tracker = TaskTracker::new();
@@ -696,7 +760,6 @@ pub async fn init_l1(
         init_network(
             &opts,
             &network,
-            &datadir,
             peer_handler.clone(),
             tracker.clone(),
             blockchain.clone(),
@@ -918,7 +981,7 @@ pub async fn regenerate_head_state(
 
 #[cfg(test)]
 mod tests {
-    use super::resolve_p2p_endpoints;
+    use super::{dual_stack_ip, resolve_p2p_endpoints};
     use std::net::IpAddr;
 
     fn ip(s: &str) -> IpAddr {
@@ -983,6 +1046,30 @@ mod tests {
         assert_eq!(ext, local6);
     }
 
+    #[test]
+    fn dual_stack_bind_accepts_extip_of_either_family() {
+        let (bind, ext) = resolve_p2p_endpoints(Some("::"), Some("203.0.113.5"), None, None);
+        assert_eq!(bind, ip("::"));
+        assert_eq!(ext, ip("203.0.113.5"));
+    }
+
+    #[test]
+    fn dual_stack_bind_announces_other_family() {
+        let (local4, local6) = (Some(ip("10.0.0.5")), Some(ip("2001:db8::5")));
+        assert_eq!(
+            dual_stack_ip(ip("::"), ip("203.0.113.5"), local4, local6),
+            local6
+        );
+        assert_eq!(
+            dual_stack_ip(ip("::"), ip("2001:db8::5"), local4, local6),
+            local4
+        );
+        assert_eq!(
+            dual_stack_ip(ip("0.0.0.0"), ip("10.0.0.5"), local4, local6),
+            None
+        );
+    }
+
     #[test]
     #[should_panic(expected = "--p2p.addr and --nat.extip must use the same address family")]
     fn family_mismatch_panics() {
@@ -1,8 +1,8 @@
 use crate::cli::Options as L1Options;
 use crate::initializers::{
-    self, get_authrpc_socket_addr, get_http_socket_addr, get_local_node_record, get_local_p2p_node,
-    get_network, get_signer, get_ws_socket_addr, init_blockchain, init_network,
-    init_store_with_config,
+    self, add_static_and_trusted_peers, get_authrpc_socket_addr, get_http_socket_addr,
+    get_local_node_record, get_local_p2p_node, get_network, get_signer, get_ws_socket_addr,
+    init_blockchain, init_network, init_store_with_config,
 };
 use crate::l2::{L2Options, SequencerOptions};
 use crate::utils::{
@@ -18,14 +18,15 @@ use ethrex_l2::sequencer::l1_committer::{self, l1_committer_protocol, regenerate
 use ethrex_p2p::{
     network::P2PContext,
     peer_handler::PeerHandler,
-    peer_table::PeerTableServer,
+    peer_table::{PeerTableServer, PeerTableServerProtocol as _},
     rlpx::{initiator::RLPxInitiator, l2::l2_connection::P2PBasedContext},
     sync_manager::SyncManager,
     types::{Node, NodeRecord},
 };
-use ethrex_rpc::{SubscriptionManager, WebSocketConfig};
+use ethrex_rpc::{IpcConfig, SubscriptionManager, WebSocketConfig};
 use ethrex_storage::{Store, StoreConfig};
 use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
+use ethrex_vm::PrecompileRegistry;
 use eyre::OptionExt;
 use secp256k1::SecretKey;
 
@@ -33,7 +34,7 @@ use spawned_concurrency::tasks::ActorRef;
 use std::{fs::read_to_string, path::Path, sync::Arc, time::Duration};
 use tokio::task::JoinSet;
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 use tracing_subscriber::{EnvFilter, Registry, layer::SubscriberExt, reload};
 use tui_logger::{LevelFilter, TuiTracingSubscriberLayer};
 use url::Url;
@@ -53,6 +54,7 @@ fn init_rpc_api(
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
     l2_gas_limit: u64,
     ws: Option<WebSocketConfig>,
+    ipc: Option<IpcConfig>,
 ) {
     init_datadir(&opts.datadir);
 
@@ -78,6 +80,8 @@ fn init_rpc_api(
         l2_opts.sponsored_gas_limit,
         allowed_namespaces,
         ethrex_namespace_allowed,
+        opts.rpc_max_filters,
+        ipc,
     );
 
     tracker.spawn(rpc_api);
@@ -188,6 +192,17 @@ async fn shutdown_sequencer_handles(
 pub async fn init_l2(
     opts: L2Options,
     log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
+) -> eyre::Result<()> {
+    init_l2_with_precompiles(opts, log_filter_handler, None).await
+}
+
+/// Like [`init_l2`], executing blocks with the custom precompiles of `precompiles` on top
+/// of the builtin ones. Must match the registry the prover's guest program runs with, see
+/// `execution_program_with_precompiles`.
+pub async fn init_l2_with_precompiles(
+    opts: L2Options,
+    log_filter_handler: Option<reload::Handle<EnvFilter, Registry>>,
+    precompiles: Option<&'static PrecompileRegistry>,
 ) -> eyre::Result<()> {
     raise_fd_limit()?;
     let datadir = opts.node_opts.datadir.clone();
@@ -203,6 +218,9 @@ pub async fn init_l2(
     let genesis = network.get_genesis()?;
     let store_config = StoreConfig {
         rocksdb_block_cache_size: opts.node_opts.rocksdb_block_cache_size,
+        gc_mode: opts.node_opts.gc_mode,
+        ancient_dir: opts.node_opts.ancient_datadir.clone(),
+        history_retention: opts.node_opts.history_retention,
     };
     let store = init_store_with_config(&datadir, genesis.clone(), store_config).await?;
     let rollup_store = init_rollup_store(&rollup_store_dir).await;
@@ -223,6 +241,7 @@ pub async fn init_l2(
     // update the L1 fee periodically.
     let l2_config = L2Config {
         fee_config: Arc::new(std::sync::RwLock::new(fee_config)),
+        precompiles,
     };
 
     let blockchain_opts = ethrex_blockchain::BlockchainOptions {
@@ -262,6 +281,7 @@ pub async fn init_l2(
             opts.node_opts.target_peers,
             store.clone(),
         );
+        add_static_and_trusted_peers(&opts.node_opts, &peer_table);
         let p2p_context = P2PContext::new(
             local_p2p_node.clone(),
             network_config,
@@ -303,6 +323,7 @@ pub async fn init_l2(
             blockchain.clone(),
             store.clone(),
             opts.node_opts.datadir.clone(),
+            opts.node_opts.sync_checkpoint,
         )
         .await;
 
@@ -313,7 +334,6 @@ pub async fn init_l2(
         init_network(
             &opts.node_opts,
             &network,
-            &datadir,
             peer_handler.clone(),
             tracker.clone(),
             blockchain.clone(),
@@ -334,15 +354,30 @@ pub async fn init_l2(
     )
     .await?;
 
-    // Create WebSocket config when WS is enabled.
-    let ws_config = if opts.node_opts.ws_enabled {
-        Some(WebSocketConfig {
+    // WS and IPC share a single subscription manager, created when either is enabled.
+    let subscription_manager = (opts.node_opts.ws_enabled || opts.node_opts.ipc_path.is_some())
+        .then(|| {
+            SubscriptionManager::spawn_with_chain_events(
+                blockchain.subscribe_events(),
+                blockchain.subscribe_pending_transactions(),
+            )
+        });
+    let ws_config = subscription_manager
+        .clone()
+        .filter(|_| opts.node_opts.ws_enabled)
+        .map(|subscription_manager| WebSocketConfig {
             addr: get_ws_socket_addr(&opts.node_opts),
-            subscription_manager: SubscriptionManager::spawn(),
-        })
-    } else {
-        None
-    };
+            subscription_manager,
+        });
+    let ipc_config = opts
+        .node_opts
+        .ipc_path
+        .clone()
+        .zip(subscription_manager)
+        .map(|(path, subscription_manager)| IpcConfig {
+            path,
+            subscription_manager,
+        });
 
     init_rpc_api(
         &opts.node_opts,
@@ -357,7 +392,8 @@ pub async fn init_l2(
         rollup_store.clone(),
         log_filter_handler,
         l2_gas_limit,
-        ws_config.clone(),
+        ws_config,
+        ipc_config,
     );
 
     // Initialize metrics if enabled
@@ -381,7 +417,6 @@ pub async fn init_l2(
         genesis,
         checkpoints_dir,
         l2_gas_limit,
-        ws_config.as_ref().map(|ws| ws.subscription_manager.clone()),
     )
     .await?;
     join_set.spawn(l2_sequencer);
@@ -404,8 +439,16 @@ pub async fn init_l2(
     cancel_token.cancel();
     if !opts.node_opts.p2p_disabled {
         let peer_handler = peer_handler.ok_or_eyre("Peer handler not initialized")?;
-        let node_config = NodeConfigFile::new(peer_handler.peer_table, local_node_record).await;
-        store_node_config_file(node_config, node_config_path);
+        store_node_config_file(
+            NodeConfigFile {
+                node_record: local_node_record,
+            },
+            node_config_path,
+        );
+        // The peer table also persists itself on Ctrl-C, but not on SIGTERM
+        if let Err(err) = peer_handler.peer_table.persist_table() {
+            debug!("Could not persist the peer table: {err}");
+        }
     }
     tokio::time::sleep(Duration::from_secs(1)).await;
     info!("Server shutting down!");
@@ -5,7 +5,7 @@ pub mod deployer;
 pub mod options;
 
 pub use command::L2Command;
-pub use initializers::{init_l2, init_tracing};
+pub use initializers::{init_l2, init_l2_with_precompiles, init_tracing};
 pub use options::{
     BlockProducerOptions, CommitterOptions, EthOptions, Options as L2Options,
     ProofCoordinatorOptions, SequencerOptions, WatcherOptions,
@@ -1,4 +1,5 @@
 pub mod cli;
+pub mod era;
 pub mod initializers;
 #[cfg(feature = "l2")]
 pub mod l2;
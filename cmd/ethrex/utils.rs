@@ -2,11 +2,8 @@ use crate::decode;
 use bytes::Bytes;
 use directories::ProjectDirs;
 use ethrex_common::types::{Block, Genesis};
-use ethrex_p2p::{
-    peer_table::{PeerTable, PeerTableServerProtocol as _},
-    sync::SyncMode,
-    types::{Node, NodeRecord},
-};
+use ethrex_p2p::{sync::SyncMode, types::NodeRecord};
+use ethrex_storage::{GcMode, HistoryRetention};
 use hex::FromHexError;
 use secp256k1::{PublicKey, SecretKey};
 use serde::{Deserialize, Serialize};
@@ -18,23 +15,13 @@ use std::{
 };
 use tracing::{error, info};
 
+/// Node config kept in the datadir across restarts. Known peers are persisted with the
+/// peer table in the store instead.
 #[derive(Serialize, Deserialize)]
 pub struct NodeConfigFile {
-    pub known_peers: Vec<Node>,
     pub node_record: NodeRecord,
 }
 
-impl NodeConfigFile {
-    pub async fn new(peer_table: PeerTable, node_record: NodeRecord) -> Self {
-        let connected_peers = peer_table.get_connected_nodes().await.unwrap_or(Vec::new());
-
-        NodeConfigFile {
-            known_peers: connected_peers,
-            node_record,
-        }
-    }
-}
-
 pub fn read_jwtsecret_file(jwt_secret_path: &str) -> Bytes {
     match File::open(jwt_secret_path) {
         Ok(mut file) => decode::jwtsecret_file(&mut file),
@@ -82,7 +69,7 @@ pub fn parse_http_namespace(s: &str) -> eyre::Result<ethrex_rpc::RpcNamespace> {
     }
     ethrex_rpc::RpcNamespace::from_prefix(&trimmed.to_ascii_lowercase()).ok_or_else(|| {
         eyre::eyre!(
-            "unknown RPC namespace {trimmed:?}; expected one of eth, net, web3, debug, admin, txpool"
+            "unknown RPC namespace {trimmed:?}; expected one of eth, net, web3, debug, admin, txpool, trace"
         )
     })
 }
@@ -97,6 +84,29 @@ pub fn parse_sync_mode(s: &str) -> eyre::Result<SyncMode> {
     }
 }
 
+pub fn parse_gc_mode(s: &str) -> eyre::Result<GcMode> {
+    match s {
+        "full" => Ok(GcMode::Full),
+        "archive" => Ok(GcMode::Archive),
+        other => Err(eyre::eyre!(
+            "Invalid gcmode {other:?} expected either full or archive",
+        )),
+    }
+}
+
+pub fn parse_history_retention(s: &str) -> eyre::Result<HistoryRetention> {
+    match s {
+        "all" => Ok(HistoryRetention::All),
+        "post-merge" => Ok(HistoryRetention::PostMerge),
+        other => match other.parse() {
+            Ok(blocks) if blocks > 0 => Ok(HistoryRetention::Blocks(blocks)),
+            _ => Err(eyre::eyre!(
+                "Invalid history retention {other:?} expected all, post-merge or a positive number of blocks",
+            )),
+        },
+    }
+}
+
 pub fn parse_socket_addr(addr: &str, port: &str) -> io::Result<SocketAddr> {
     // NOTE: this blocks until hostname can be resolved
     format!("{addr}:{port}")
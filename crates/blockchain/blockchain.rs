@@ -44,6 +44,7 @@
 
 pub mod constants;
 pub mod error;
+pub mod events;
 pub mod fork_choice;
 pub mod mempool;
 pub mod payload;
@@ -55,6 +56,7 @@ use constants::{AMSTERDAM_MAX_INITCODE_SIZE, MAX_INITCODE_SIZE, POST_OSAKA_GAS_L
 use error::MempoolError;
 use error::{ChainError, InvalidBlockError};
 use ethrex_common::constants::{EMPTY_TRIE_HASH, MIN_BASE_FEE_PER_BLOB_GAS};
+use events::{BlockWithReceipts, ChainEvent, MAX_HEAD_CHANGE_BLOCKS};
 
 use crossbeam::channel::{self as cb, TryRecvError, select};
 // Re-export stateless validation functions for backwards compatibility
@@ -93,7 +95,7 @@ use ethrex_vm::backends::CachingDatabase;
 #[cfg(all(feature = "rayon", not(feature = "eip-8025")))]
 use ethrex_vm::backends::levm::LEVM;
 use ethrex_vm::backends::levm::db::DatabaseLogger;
-use ethrex_vm::{BlockExecutionResult, DynVmDatabase, Evm, EvmError};
+use ethrex_vm::{BlockExecutionResult, DynVmDatabase, Evm, EvmError, PrecompileRegistry};
 use mempool::Mempool;
 use payload::PayloadOrTask;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -102,7 +104,7 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::LazyLock;
 use std::sync::mpsc::Sender;
 use std::sync::{
-    Arc, RwLock,
+    Arc, Once, RwLock,
     atomic::{AtomicBool, AtomicUsize, Ordering},
     mpsc::{Receiver, channel},
 };
@@ -172,6 +174,8 @@ pub struct L2Config {
     ///
     /// Uses `RwLock` because the Watcher updates L1 fee config periodically.
     pub fee_config: Arc<RwLock<FeeConfig>>,
+    /// Custom precompiles of the L2 on top of the builtin ones, if any.
+    pub precompiles: Option<&'static PrecompileRegistry>,
 }
 
 /// Core blockchain implementation for block validation and execution.
@@ -225,6 +229,16 @@ pub struct Blockchain {
     /// production path keeps the original semantics (one fresh pool per call
     /// to `Blockchain::new` / `default_with_store`).
     merkle_pool: Arc<rayon::ThreadPool>,
+    /// Publishes head changes and sync status changes.
+    /// See [`Blockchain::subscribe_events`].
+    events: tokio::sync::broadcast::Sender<ChainEvent>,
+    /// Publishes the transactions added to the mempool. Kept apart from `events` so
+    /// pending transaction consumers don't make head changes load blocks and receipts.
+    /// See [`Blockchain::subscribe_pending_transactions`].
+    pending_transactions: tokio::sync::broadcast::Sender<ChainEvent>,
+    /// Starts the task publishing the store's head updates as [`ChainEvent::HeadChanged`],
+    /// on the first [`Blockchain::subscribe_events`].
+    head_publisher: Once,
 }
 
 /// Configuration options for the blockchain.
@@ -370,13 +384,19 @@ impl Blockchain {
     }
 
     pub fn new(store: Store, blockchain_opts: BlockchainOptions) -> Self {
+        let events = events::channel();
+        let pending_transactions = events::channel();
         Self {
             storage: store,
-            mempool: Mempool::new(blockchain_opts.max_mempool_size),
+            mempool: Mempool::new(blockchain_opts.max_mempool_size)
+                .with_events(pending_transactions.clone()),
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
             options: blockchain_opts,
             merkle_pool: Self::build_merkle_pool(),
+            events,
+            pending_transactions,
+            head_publisher: Once::new(),
         }
     }
 
@@ -390,24 +410,36 @@ impl Blockchain {
     /// all 16 worker jobs to run concurrently (they cross-communicate via
     /// channels); sharing a pool across simultaneous callers deadlocks.
     pub fn default_with_store_and_pool(store: Store, pool: Arc<rayon::ThreadPool>) -> Self {
+        let events = events::channel();
+        let pending_transactions = events::channel();
         Self {
             storage: store,
-            mempool: Mempool::new(MAX_MEMPOOL_SIZE_DEFAULT),
+            mempool: Mempool::new(MAX_MEMPOOL_SIZE_DEFAULT)
+                .with_events(pending_transactions.clone()),
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
             options: BlockchainOptions::default(),
             merkle_pool: pool,
+            events,
+            pending_transactions,
+            head_publisher: Once::new(),
         }
     }
 
     pub fn default_with_store(store: Store) -> Self {
+        let events = events::channel();
+        let pending_transactions = events::channel();
         Self {
             storage: store,
-            mempool: Mempool::new(MAX_MEMPOOL_SIZE_DEFAULT),
+            mempool: Mempool::new(MAX_MEMPOOL_SIZE_DEFAULT)
+                .with_events(pending_transactions.clone()),
             is_synced: AtomicBool::new(false),
             payloads: Arc::new(TokioMutex::new(Vec::new())),
             options: BlockchainOptions::default(),
             merkle_pool: Self::build_merkle_pool(),
+            events,
+            pending_transactions,
+            head_publisher: Once::new(),
         }
     }
 
@@ -621,6 +653,8 @@ impl Blockchain {
             std::thread::scope(|s| -> Result<_, ChainError> {
                 #[cfg(all(feature = "rayon", not(feature = "eip-8025")))]
                 let vm_type = vm.vm_type;
+                #[cfg(all(feature = "rayon", not(feature = "eip-8025")))]
+                let precompiles = vm.db.precompiles;
                 let cancelled_ref = &cancelled;
                 #[cfg(all(feature = "rayon", not(feature = "eip-8025")))]
                 let bal_prefetch_enabled = self.options.bal_prefetch_enabled;
@@ -649,6 +683,7 @@ impl Blockchain {
                                     block,
                                     caching_store,
                                     vm_type,
+                                    precompiles,
                                     &NativeCrypto,
                                     cancelled_ref,
                                 ) {
@@ -661,6 +696,7 @@ impl Blockchain {
                                 block,
                                 caching_store,
                                 vm_type,
+                                precompiles,
                                 &NativeCrypto,
                                 cancelled_ref,
                             ) {
@@ -1448,8 +1484,8 @@ impl Blockchain {
                 BlockchainType::L1 => {
                     Evm::new_from_db_for_l1(logger.clone(), Arc::new(NativeCrypto))
                 }
-                BlockchainType::L2(_) => {
-                    let l2_config = match fee_configs {
+                BlockchainType::L2(ref l2_config) => {
+                    let fee_config = match fee_configs {
                         Some(fee_configs) => {
                             fee_configs.get(i).ok_or(ChainError::WitnessGeneration(
                                 "FeeConfig not found for witness generation".to_string(),
@@ -1459,7 +1495,8 @@ impl Blockchain {
                             "L2Config not found for witness generation".to_string(),
                         ))?,
                     };
-                    Evm::new_from_db_for_l2(logger.clone(), *l2_config, Arc::new(NativeCrypto))
+                    Evm::new_from_db_for_l2(logger.clone(), *fee_config, Arc::new(NativeCrypto))
+                        .with_precompiles(l2_config.precompiles)
                 }
             };
 
@@ -2096,7 +2133,8 @@ impl Blockchain {
                         EvmError::Custom("Fee config lock was poisoned".to_string())
                     })?,
                     Arc::new(NativeCrypto),
-                ),
+                )
+                .with_precompiles(l2_config.precompiles),
             };
             (vm, Some(logger))
         } else {
@@ -2933,13 +2971,129 @@ impl Blockchain {
     /// Marks the node's chain as up to date with the current chain
     /// Once the initial sync has taken place, the node will be considered as sync
     pub fn set_synced(&self) {
-        self.is_synced.store(true, Ordering::Relaxed);
+        if !self.is_synced.swap(true, Ordering::Relaxed) {
+            let _ = self
+                .events
+                .send(ChainEvent::SyncStatusChanged { syncing: false });
+        }
     }
 
     /// Marks the node's chain as not up to date with the current chain.
     /// This will be used when the node is one batch or more behind the current chain.
     pub fn set_not_synced(&self) {
-        self.is_synced.store(false, Ordering::Relaxed);
+        if self.is_synced.swap(false, Ordering::Relaxed) {
+            let _ = self
+                .events
+                .send(ChainEvent::SyncStatusChanged { syncing: true });
+        }
+    }
+
+    /// Returns a receiver for the head changes and sync status changes published from
+    /// now on. Head changes are published for every canonical head update made through
+    /// the store, see [`Store::subscribe_head_updates`].
+    pub fn subscribe_events(self: &Arc<Self>) -> tokio::sync::broadcast::Receiver<ChainEvent> {
+        self.start_head_publisher();
+        self.events.subscribe()
+    }
+
+    /// Returns a receiver for the [`ChainEvent::NewPendingTransaction`]s published from
+    /// now on.
+    pub fn subscribe_pending_transactions(&self) -> tokio::sync::broadcast::Receiver<ChainEvent> {
+        self.pending_transactions.subscribe()
+    }
+
+    /// Returns the number of live pending transaction subscriptions.
+    pub fn pending_transaction_subscribers(&self) -> usize {
+        self.pending_transactions.receiver_count()
+    }
+
+    /// Spawns, once, the task that turns the store's head updates into
+    /// [`ChainEvent::HeadChanged`]s. The changed blocks and their receipts are loaded by
+    /// the task, so fork choice updates don't wait on it. Updates that arrive while a
+    /// change is being published are coalesced into the next one.
+    fn start_head_publisher(self: &Arc<Self>) {
+        self.head_publisher.call_once(|| {
+            let mut head_updates = self.storage.subscribe_head_updates();
+            let mut previous_head = *head_updates.borrow_and_update();
+            // The task holds a weak reference so it ends once the blockchain is dropped
+            let blockchain = Arc::downgrade(self);
+            tokio::spawn(async move {
+                while head_updates.changed().await.is_ok() {
+                    let new_head = *head_updates.borrow_and_update();
+                    let Some(blockchain) = blockchain.upgrade() else {
+                        break;
+                    };
+                    if let Err(err) = blockchain.send_head_change(previous_head, new_head).await {
+                        warn!("Failed to publish head change to subscribers: {err}");
+                    }
+                    previous_head = new_head;
+                }
+            });
+        });
+    }
+
+    /// Sends the [`ChainEvent::HeadChanged`] for the head moving from `previous_head` to
+    /// `new_head`. Both branches are walked back to their common ancestor, up to
+    /// [`MAX_HEAD_CHANGE_BLOCKS`] blocks each. Does nothing if there are no subscribers.
+    async fn send_head_change(
+        &self,
+        previous_head: BlockHash,
+        new_head: BlockHash,
+    ) -> Result<(), StoreError> {
+        if self.events.receiver_count() == 0 || previous_head == new_head {
+            return Ok(());
+        }
+
+        let mut removed_hashes = Vec::new();
+        let mut added_hashes = Vec::new();
+        let mut old = self.storage.get_block_header_by_hash(previous_head)?;
+        let mut new = self.storage.get_block_header_by_hash(new_head)?;
+        while removed_hashes.len() < MAX_HEAD_CHANGE_BLOCKS
+            && added_hashes.len() < MAX_HEAD_CHANGE_BLOCKS
+        {
+            match (&old, &new) {
+                (Some(old_header), Some(new_header)) if old_header.hash() == new_header.hash() => {
+                    break;
+                }
+                (Some(old_header), Some(new_header)) if old_header.number >= new_header.number => {
+                    removed_hashes.push(old_header.hash());
+                    old = self
+                        .storage
+                        .get_block_header_by_hash(old_header.parent_hash)?;
+                }
+                (_, Some(new_header)) => {
+                    added_hashes.push(new_header.hash());
+                    new = self
+                        .storage
+                        .get_block_header_by_hash(new_header.parent_hash)?;
+                }
+                (_, None) => break,
+            }
+        }
+        added_hashes.reverse();
+
+        let removed = self.blocks_with_receipts(&removed_hashes).await?;
+        let added = self.blocks_with_receipts(&added_hashes).await?;
+        let _ = self.events.send(ChainEvent::HeadChanged {
+            removed: Arc::new(removed),
+            added: Arc::new(added),
+        });
+        Ok(())
+    }
+
+    async fn blocks_with_receipts(
+        &self,
+        hashes: &[BlockHash],
+    ) -> Result<Vec<BlockWithReceipts>, StoreError> {
+        let mut blocks = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let Some(block) = self.storage.get_block_by_hash(*hash).await? else {
+                continue;
+            };
+            let receipts = self.storage.get_receipts_for_block(hash).await?;
+            blocks.push(BlockWithReceipts { block, receipts });
+        }
+        Ok(blocks)
     }
 
     /// Returns whether the node's chain is up to date with the current chain
@@ -3483,6 +3637,7 @@ pub fn new_evm(blockchain_type: &BlockchainType, vm_db: StoreVmDatabase) -> Resu
                 .map_err(|_| EvmError::Custom("Fee config lock was poisoned".to_string()))?;
 
             Evm::new_for_l2(vm_db, fee_config, Arc::new(NativeCrypto))?
+                .with_precompiles(l2_config.precompiles)
         }
     };
     Ok(evm)
@@ -0,0 +1,50 @@
+//! Chain events published by [`Blockchain`](crate::Blockchain) for external consumers
+//! (e.g. the RPC `eth_subscribe` notifications).
+//!
+//! Events are sent through [`tokio::sync::broadcast`] channels: publishing never blocks
+//! and a consumer that falls behind loses the oldest events instead of stalling block
+//! import or mempool insertion.
+
+use std::sync::Arc;
+
+use ethrex_common::{
+    H256,
+    types::{Block, MempoolTransaction, Receipt},
+};
+use tokio::sync::broadcast;
+
+/// Number of events buffered per receiver before the oldest ones are dropped.
+pub const CHAIN_EVENTS_CHANNEL_CAPACITY: usize = 1024;
+
+/// Maximum number of blocks walked back on each side of a head change. Deeper reorgs
+/// are reported truncated to the most recent blocks.
+pub const MAX_HEAD_CHANGE_BLOCKS: usize = 64;
+
+/// A block together with the receipts of its transactions.
+#[derive(Debug, Clone)]
+pub struct BlockWithReceipts {
+    pub block: Block,
+    pub receipts: Vec<Receipt>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ChainEvent {
+    /// The canonical head moved. `removed` holds the blocks that left the canonical chain
+    /// (newest first) and `added` the blocks that joined it (oldest first). `removed` is
+    /// only non-empty on reorgs.
+    HeadChanged {
+        removed: Arc<Vec<BlockWithReceipts>>,
+        added: Arc<Vec<BlockWithReceipts>>,
+    },
+    /// A transaction was accepted into the mempool.
+    NewPendingTransaction {
+        hash: H256,
+        transaction: Box<MempoolTransaction>,
+    },
+    /// The node started (`syncing: true`) or finished (`syncing: false`) syncing.
+    SyncStatusChanged { syncing: bool },
+}
+
+pub(crate) fn channel() -> broadcast::Sender<ChainEvent> {
+    broadcast::channel(CHAIN_EVENTS_CHANNEL_CAPACITY).0
+}
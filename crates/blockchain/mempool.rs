@@ -15,6 +15,7 @@ use crate::{
         TX_INIT_CODE_WORD_GAS_COST,
     },
     error::MempoolError,
+    events::ChainEvent,
 };
 use ethrex_common::{
     Address, H160, H256, U256,
@@ -248,6 +249,8 @@ pub struct Mempool {
     /// snapshotted the mempool, so it can decide whether a stale build is safe
     /// to return.
     tx_seq: AtomicU64,
+    /// Where accepted transactions are announced, if anyone is listening.
+    events: Option<tokio::sync::broadcast::Sender<ChainEvent>>,
 }
 
 impl Mempool {
@@ -256,9 +259,17 @@ impl Mempool {
             inner: RwLock::new(MempoolInner::new(max_mempool_size)),
             tx_added: tokio::sync::Notify::new(),
             tx_seq: AtomicU64::new(0),
+            events: None,
         }
     }
 
+    /// Publish a [`ChainEvent::NewPendingTransaction`] on `events` for every transaction
+    /// added to the pool. Builder-style.
+    pub fn with_events(mut self, events: tokio::sync::broadcast::Sender<ChainEvent>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
     /// Override the blob sub-pool capacity (defaults to [`MAX_BLOB_MEMPOOL_SIZE`]).
     /// Builder-style; intended for configuration and tests.
     pub fn with_max_blob_mempool_size(self, max_blob_mempool_size: usize) -> Self {
@@ -295,6 +306,12 @@ impl Mempool {
         sender: Address,
         transaction: MempoolTransaction,
     ) -> Result<(), StoreError> {
+        // Only pay for the clone when there are subscribers to notify.
+        let event = self
+            .events
+            .as_ref()
+            .filter(|events| events.receiver_count() > 0)
+            .map(|events| (events, transaction.clone()));
         let mut inner = self.write()?;
         let is_blob = matches!(transaction.tx_type(), TxType::EIP4844);
         // Prune the regular order queue if it has grown too much
@@ -340,6 +357,13 @@ impl Mempool {
         // already incorporated as "new since last build".
         self.tx_seq.fetch_add(1, Ordering::Release);
         self.tx_added.notify_waiters();
+        if let Some((events, transaction)) = event {
+            // Sending only fails when every receiver was dropped in the meantime.
+            let _ = events.send(ChainEvent::NewPendingTransaction {
+                hash,
+                transaction: Box::new(transaction),
+            });
+        }
 
         Ok(())
     }
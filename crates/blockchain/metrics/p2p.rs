@@ -1,6 +1,6 @@
 use prometheus::{
-    Encoder, Histogram, HistogramOpts, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
-    TextEncoder,
+    Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts,
+    Registry, TextEncoder,
 };
 use std::sync::LazyLock;
 
@@ -19,6 +19,9 @@ pub struct MetricsP2P {
     discv4_outgoing_messages: IntCounterVec,
     discv5_incoming_messages: IntCounterVec,
     discv5_outgoing_messages: IntCounterVec,
+    served_request_cost: IntCounterVec,
+    rate_limited_requests: IntCounterVec,
+    leecher_disconnections: IntCounter,
     kademlia_insert_contact_duration: Histogram,
     kademlia_iter_contacts_duration: Histogram,
 }
@@ -95,6 +98,27 @@ impl MetricsP2P {
                 &["msg_type"],
             )
             .expect("Failed to create discv5_outgoing_messages metric"),
+            served_request_cost: IntCounterVec::new(
+                Opts::new(
+                    "ethrex_p2p_served_request_cost",
+                    "Total serving cost of the requests served to peers by message family",
+                ),
+                &["family"],
+            )
+            .expect("Failed to create served_request_cost metric"),
+            rate_limited_requests: IntCounterVec::new(
+                Opts::new(
+                    "ethrex_p2p_rate_limited_requests",
+                    "Total number of peer requests rejected by the rate limiter by message family",
+                ),
+                &["family"],
+            )
+            .expect("Failed to create rate_limited_requests metric"),
+            leecher_disconnections: IntCounter::new(
+                "ethrex_p2p_leecher_disconnections",
+                "Total number of peers disconnected for consuming far more than they contribute",
+            )
+            .expect("Failed to create leecher_disconnections metric"),
             kademlia_insert_contact_duration: Histogram::with_opts(
                 HistogramOpts::new(
                     "ethrex_kademlia_insert_contact_duration_seconds",
@@ -178,6 +202,22 @@ impl MetricsP2P {
             .inc();
     }
 
+    pub fn inc_served_request_cost(&self, family: &str, cost: u64) {
+        self.served_request_cost
+            .with_label_values(&[family])
+            .inc_by(cost);
+    }
+
+    pub fn inc_rate_limited_request(&self, family: &str) {
+        self.rate_limited_requests
+            .with_label_values(&[family])
+            .inc();
+    }
+
+    pub fn inc_leecher_disconnection(&self) {
+        self.leecher_disconnections.inc();
+    }
+
     pub fn observe_insert_contact_duration(&self, duration_secs: f64) {
         self.kademlia_insert_contact_duration.observe(duration_secs);
     }
@@ -207,6 +247,12 @@ impl MetricsP2P {
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.discv5_outgoing_messages.clone()))
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.served_request_cost.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.rate_limited_requests.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.leecher_disconnections.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.kademlia_insert_contact_duration.clone()))
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.kademlia_iter_contacts_duration.clone()))
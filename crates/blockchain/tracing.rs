@@ -5,11 +5,12 @@ use std::{
 
 use ethrex_common::{
     H256,
-    tracing::{CallTrace, OpcodeTraceResult, PrestateResult},
-    types::Block,
+    tracing::{CallTrace, NativeTraceResult, OpcodeTraceResult, PrestateResult},
+    types::{Block, BlockHeader, GenericTransaction},
 };
 use ethrex_storage::Store;
-use ethrex_vm::tracing::OpcodeTracerConfig;
+use ethrex_vm::overrides::CallOverrides;
+use ethrex_vm::tracing::{NativeTracerConfig, OpcodeTracerConfig};
 use ethrex_vm::{Evm, EvmError};
 
 use crate::{Blockchain, error::ChainError, vm::StoreVmDatabase};
@@ -218,6 +219,162 @@ impl Blockchain {
         Ok(traces)
     }
 
+    /// Outputs the trace of the given transaction for one of the built-in tracers run natively
+    /// (4byteTracer, flatCallTracer, muxTracer, noopTracer, …).
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`.
+    pub async fn trace_transaction_native(
+        &self,
+        tx_hash: H256,
+        reexec: u32,
+        timeout: Duration,
+        cfg: NativeTracerConfig,
+    ) -> Result<NativeTraceResult, ChainError> {
+        let Some((_, block_hash, tx_index)) =
+            self.storage.get_transaction_location(tx_hash).await?
+        else {
+            return Err(ChainError::Custom("Transaction not Found".to_string()));
+        };
+        let tx_index = tx_index as usize;
+        let Some(block) = self.storage.get_block_by_hash(block_hash).await? else {
+            return Err(ChainError::Custom("Block not Found".to_string()));
+        };
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        vm.rerun_block(&block, Some(tx_index))?;
+        timeout_trace_operation(timeout, move || vm.trace_tx_native(&block, tx_index, &cfg)).await
+    }
+
+    /// Outputs the trace of each transaction in the block for one of the built-in tracers run
+    /// natively, along with the transaction's hash.
+    /// May need to re-execute blocks in order to rebuild the block's prestate, up to the amount
+    /// given by `reexec`.
+    /// Returns traces from oldest to newest transaction.
+    pub async fn trace_block_native(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+        cfg: NativeTracerConfig,
+    ) -> Result<Vec<(H256, NativeTraceResult)>, ChainError> {
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        vm.rerun_block(&block, Some(0))?;
+        let vm = Arc::new(Mutex::new(vm));
+        let block = Arc::new(block);
+        let mut traces = vec![];
+        for index in 0..block.body.transactions.len() {
+            let block = block.clone();
+            let vm = vm.clone();
+            let tx_hash = block.as_ref().body.transactions[index].hash();
+            let cfg = cfg.clone();
+            let result = timeout_trace_operation(timeout, move || {
+                vm.lock()
+                    .map_err(|_| EvmError::Custom("Unexpected Runtime Error".to_string()))?
+                    .trace_tx_native(block.as_ref(), index, &cfg)
+            })
+            .await?;
+            traces.push((tx_hash, result));
+        }
+        Ok(traces)
+    }
+
+    /// Outputs the call trace for a simulated (unsigned) transaction executed on top of the state
+    /// after the block given by `header`, with the given state and block overrides applied.
+    /// May need to re-execute blocks in order to rebuild the block's state, up to the amount given by `reexec`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn trace_call_calls(
+        &self,
+        tx: GenericTransaction,
+        header: BlockHeader,
+        overrides: CallOverrides,
+        reexec: u32,
+        timeout: Duration,
+        only_top_call: bool,
+        with_log: bool,
+    ) -> Result<CallTrace, ChainError> {
+        let (mut vm, header) = self.prepare_call_state(header, &overrides, reexec).await?;
+        timeout_trace_operation(timeout, move || {
+            vm.trace_generic_tx_calls(&tx, &header, &overrides.block, only_top_call, with_log)
+        })
+        .await
+    }
+
+    /// Outputs the prestate trace for a simulated (unsigned) transaction executed on top of the
+    /// state after the block given by `header`, with the given state and block overrides applied.
+    /// See [`Blockchain::trace_transaction_prestate`] for the meaning of `diff_mode` and `include_empty`.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn trace_call_prestate(
+        &self,
+        tx: GenericTransaction,
+        header: BlockHeader,
+        overrides: CallOverrides,
+        reexec: u32,
+        timeout: Duration,
+        diff_mode: bool,
+        include_empty: bool,
+    ) -> Result<PrestateResult, ChainError> {
+        let (mut vm, header) = self.prepare_call_state(header, &overrides, reexec).await?;
+        timeout_trace_operation(timeout, move || {
+            vm.trace_generic_tx_prestate(&tx, &header, &overrides.block, diff_mode, include_empty)
+        })
+        .await
+    }
+
+    /// Outputs the per-opcode (EIP-3155) trace for a simulated (unsigned) transaction executed on
+    /// top of the state after the block given by `header`, with the given state and block overrides applied.
+    pub async fn trace_call_opcodes(
+        &self,
+        tx: GenericTransaction,
+        header: BlockHeader,
+        overrides: CallOverrides,
+        reexec: u32,
+        timeout: Duration,
+        cfg: OpcodeTracerConfig,
+    ) -> Result<OpcodeTraceResult, ChainError> {
+        let (mut vm, header) = self.prepare_call_state(header, &overrides, reexec).await?;
+        timeout_trace_operation(timeout, move || {
+            vm.trace_generic_tx_opcodes(&tx, &header, &overrides.block, cfg)
+        })
+        .await
+    }
+
+    /// Outputs the trace of a simulated (unsigned) transaction for one of the built-in tracers run
+    /// natively, executed on top of the state after the block given by `header` with the given
+    /// state and block overrides applied.
+    pub async fn trace_call_native(
+        &self,
+        tx: GenericTransaction,
+        header: BlockHeader,
+        overrides: CallOverrides,
+        reexec: u32,
+        timeout: Duration,
+        cfg: NativeTracerConfig,
+    ) -> Result<NativeTraceResult, ChainError> {
+        let (mut vm, header) = self.prepare_call_state(header, &overrides, reexec).await?;
+        timeout_trace_operation(timeout, move || {
+            vm.trace_generic_tx_native(&tx, &header, &overrides.block, &cfg)
+        })
+        .await
+    }
+
+    /// Builds an `Evm` holding the state after the block given by `header` with the state overrides
+    /// layered on top, and returns it along with the header the simulated call should execute under
+    /// (the block overrides applied).
+    async fn prepare_call_state(
+        &self,
+        header: BlockHeader,
+        overrides: &CallOverrides,
+        reexec: u32,
+    ) -> Result<(Evm, BlockHeader), ChainError> {
+        let mut vm = self.rebuild_parent_state(header.hash(), reexec).await?;
+        vm.apply_state_overrides(&overrides.state)?;
+        let mut header = header;
+        overrides.block.apply(&mut header);
+        Ok((vm, header))
+    }
+
     /// Rebuild the parent state for a block given its parent hash, returning an `Evm` instance with all changes cached
     /// Will re-execute all ancestor block's which's state is not stored up to a maximum given by `reexec`
     async fn rebuild_parent_state(
@@ -1,4 +1,4 @@
-use ethrex_p2p::types::Node;
+use ethrex_p2p::{discovery::dns::EnrTreeUrl, types::Node};
 use std::{
     fmt::{self},
     path::PathBuf,
@@ -12,6 +12,9 @@ const MAINNET_BOOTNODES: &str = include_str!("../../../cmd/ethrex/networks/mainn
 const SEPOLIA_BOOTNODES: &str = include_str!("../../../cmd/ethrex/networks/sepolia/bootnodes.json");
 const HOODI_BOOTNODES: &str = include_str!("../../../cmd/ethrex/networks/hoodi/bootnodes.json");
 
+/// Signing key of the EIP-1459 node trees published at `ethdisco.net`.
+const ETHDISCO_TREE_KEY: &str = "AKA3AM6LPBYEUDMVNU3BSVQJ5AD45Y7YPOHJLEF6W26QOE4VTUDPE";
+
 pub const MAINNET_GENESIS_CONTENTS: &str =
     include_str!("../../../cmd/ethrex/networks/mainnet/genesis.json");
 pub const HOODI_GENESIS_CONTENTS: &str =
@@ -160,6 +163,18 @@ impl Network {
         };
         serde_json::from_str(bootnodes).expect("bootnodes file should be valid JSON")
     }
+
+    /// Returns the public EIP-1459 DNS discovery trees of the network.
+    pub fn get_dns_trees(&self) -> Vec<EnrTreeUrl> {
+        let name = match self {
+            Network::PublicNetwork(PublicNetwork::Hoodi) => "hoodi",
+            Network::PublicNetwork(PublicNetwork::Mainnet) => "mainnet",
+            Network::PublicNetwork(PublicNetwork::Sepolia) => "sepolia",
+            _ => return vec![],
+        };
+        let url = format!("enrtree://{ETHDISCO_TREE_KEY}@all.{name}.ethdisco.net");
+        vec![url.parse().expect("ethdisco tree URL should be valid")]
+    }
 }
 
 fn get_genesis_contents(network: PublicNetwork) -> &'static str {
@@ -183,6 +198,18 @@ mod tests {
         assert_eq!(genesis_hash, H256::from_slice(&expected_hash));
     }
 
+    #[test]
+    fn test_public_networks_have_valid_dns_trees() {
+        for network in [
+            PublicNetwork::Hoodi,
+            PublicNetwork::Mainnet,
+            PublicNetwork::Sepolia,
+        ] {
+            assert_eq!(Network::PublicNetwork(network).get_dns_trees().len(), 1);
+        }
+        assert!(Network::LocalDevnet.get_dns_trees().is_empty());
+    }
+
     #[test]
     fn test_sepolia_genesis_block_hash() {
         // Values taken from the geth codebase:
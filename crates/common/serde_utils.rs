@@ -418,6 +418,34 @@ pub mod bytes {
         serializer.serialize_str(&format!("0x{value:x}"))
     }
 
+    pub mod opt {
+        use super::*;
+
+        pub fn deserialize<'de, D>(d: D) -> Result<Option<Bytes>, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let value = Option::<String>::deserialize(d)?;
+            value
+                .map(|value| {
+                    hex_simd::decode_to_vec(value.trim_start_matches("0x"))
+                        .map(Bytes::from)
+                        .map_err(|e| D::Error::custom(e.to_string()))
+                })
+                .transpose()
+        }
+
+        pub fn serialize<S>(value: &Option<Bytes>, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            match value {
+                Some(value) => serializer.serialize_str(&format!("0x{value:x}")),
+                None => serializer.serialize_none(),
+            }
+        }
+    }
+
     pub mod vec {
         use super::*;
 
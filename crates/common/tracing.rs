@@ -82,7 +82,7 @@ pub struct CallTraceFrame {
     pub logs: Vec<CallLog>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub enum CallType {
     #[default]
     CALL,
@@ -163,6 +163,28 @@ fn is_zero_nonce(n: &u64) -> bool {
     *n == 0
 }
 
+/// Output of geth's `4byteTracer`: amount of calls per `"0x{selector}-{calldata size}"` key,
+/// where the calldata size doesn't include the 4-byte selector.
+/// https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#4byte-tracer
+pub type FourByteTrace = BTreeMap<String, u64>;
+
+/// Result of one of the built-in tracers run natively by LEVM.
+#[derive(Debug)]
+pub enum NativeTraceResult {
+    /// `callTracer` call tree.
+    Call(CallTraceFrame),
+    /// `flatCallTracer` call tree, flattened into Parity-style traces by the caller.
+    FlatCall(CallTraceFrame),
+    /// `4byteTracer` selector counts.
+    FourByte(FourByteTrace),
+    /// `prestateTracer` result.
+    Prestate(PrestateResult),
+    /// `noopTracer`, which always outputs an empty object.
+    Noop,
+    /// `muxTracer`: the result of each tracer along with its name, in the configured order.
+    Mux(Vec<(String, NativeTraceResult)>),
+}
+
 // ─── OpcodeTracer types ──────────────────────────────────────────────────────
 
 /// Per-opcode trace entry — pure data, no `Serialize` impl.
@@ -219,6 +241,9 @@ pub struct OpcodeTraceResult {
     pub pass: bool,
     pub output: bytes::Bytes,
     pub steps: Vec<OpcodeStep>,
+    /// Code run by each frame with at least one step, in the order the frames were
+    /// entered: the first one is the code of the transaction's top frame.
+    pub frame_codes: Vec<bytes::Bytes>,
 }
 
 // ─── Helpers ──────────────────────────────────────────────────────────────
@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use ethrex_crypto::Crypto;
 use ethrex_l2_common::messages::get_balance_diffs;
-use ethrex_vm::{Evm, GuestProgramStateWrapper};
+use ethrex_vm::{Evm, GuestProgramStateWrapper, PrecompileRegistry};
 
 use crate::common::{BatchExecutionResult, execute_blocks};
 use crate::l2::blobs::verify_blob;
@@ -18,6 +18,16 @@ use crate::l2::output::ProgramOutput;
 pub fn execution_program(
     input: ProgramInput,
     crypto: Arc<dyn Crypto>,
+) -> Result<ProgramOutput, L2ExecutionError> {
+    execution_program_with_precompiles(input, crypto, None)
+}
+
+/// Like [`execution_program`], executing the blocks with the custom precompiles of `precompiles`
+/// on top of the builtin ones. Must match the registry used by the L2 sequencer.
+pub fn execution_program_with_precompiles(
+    input: ProgramInput,
+    crypto: Arc<dyn Crypto>,
+    precompiles: Option<&'static PrecompileRegistry>,
 ) -> Result<ProgramOutput, L2ExecutionError> {
     let ProgramInput {
         blocks,
@@ -48,6 +58,7 @@ pub fn execution_program(
                 )
             })?;
             Evm::new_for_l2(db.clone(), fee_config, crypto.clone())
+                .map(|evm| evm.with_precompiles(precompiles))
                 .map_err(crate::common::ExecutionError::Evm)
         },
         crypto.clone(),
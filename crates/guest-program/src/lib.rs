@@ -28,7 +28,7 @@ pub mod output {
 
 #[cfg(feature = "l2")]
 pub mod execution {
-    pub use crate::l2::execution_program;
+    pub use crate::l2::{execution_program, execution_program_with_precompiles};
 }
 #[cfg(not(feature = "l2"))]
 pub mod execution {
@@ -204,6 +204,7 @@ impl RpcHandler for SponsoredTx {
             &ethrex_rpc::EstimateGasRequest {
                 transaction: generic,
                 block: None,
+                overrides: Default::default(),
             },
             context.l1_ctx.clone(),
         )
@@ -22,7 +22,7 @@ use ethrex_rpc::RpcHandler as L1RpcHandler;
 use ethrex_rpc::RpcNamespace as L1RpcNamespace;
 use ethrex_rpc::debug::execution_witness::ExecutionWitnessRequest;
 use ethrex_rpc::{
-    ClientVersion, GasTipEstimator, NodeData, RpcRequestWrapper, WebSocketConfig,
+    ClientVersion, GasTipEstimator, IpcConfig, NodeData, RpcRequestWrapper, WebSocketConfig,
     types::transaction::SendRawTransactionRequest,
     utils::{RpcRequest, RpcRequestId},
 };
@@ -96,6 +96,8 @@ pub async fn start_api(
     sponsored_gas_limit: u64,
     allowed_namespaces: HashSet<L1RpcNamespace>,
     ethrex_namespace_allowed: bool,
+    max_filters: usize,
+    ipc: Option<IpcConfig>,
 ) -> Result<(), RpcErr> {
     // TODO: Refactor how filters are handled,
     // filters are used by the filters endpoints (eth_newFilter, eth_getFilterChanges, ...etc)
@@ -112,6 +114,7 @@ pub async fn start_api(
             storage,
             blockchain,
             active_filters: active_filters.clone(),
+            max_filters,
             syncer,
             peer_handler,
             node_data: NodeData {
@@ -126,6 +129,7 @@ pub async fn start_api(
             gas_ceil: l2_gas_limit,
             block_worker_channel,
             ws: ws.clone(),
+            ipc: ipc.clone(),
             allowed_namespaces: Arc::new(allowed_namespaces),
         },
         valid_delegation_addresses,
@@ -167,6 +171,36 @@ pub async fn start_api(
 
     info!("Not starting Auth-RPC server. The address passed as argument is {authrpc_addr}");
 
+    let ipc_server = match ipc {
+        Some(ipc_config) => {
+            let ipc_listener = ethrex_rpc::ipc::bind_ipc(&ipc_config.path)?;
+            info!("Starting IPC server at {}", ipc_config.path.display());
+            // IPC is only reachable by the node's user, so it also serves `ethrex_*`
+            // regardless of `--http.api`.
+            let ctx = RpcApiContext {
+                l1_ctx: ethrex_rpc::ipc::ipc_context(&service_context.l1_ctx),
+                ethrex_namespace_allowed: true,
+                ..service_context.clone()
+            };
+            Some(ethrex_rpc::ipc::serve_ipc(
+                ipc_listener,
+                ipc_config.path,
+                ctx.l1_ctx.clone(),
+                move |req| {
+                    let c = ctx.clone();
+                    async move { map_http_requests(&req, c).await }
+                },
+            ))
+        }
+        None => None,
+    };
+    let ipc_server = async {
+        match ipc_server {
+            Some(server) => server.await,
+            None => Ok(()),
+        }
+    };
+
     if let Some(ref ws_config) = ws {
         let ws_handler = |ws: WebSocketUpgrade, State(ctx): State<RpcApiContext>| async move {
             ws.on_upgrade(|mut socket| async move {
@@ -189,10 +223,10 @@ pub async fn start_api(
             .into_future();
         info!("Starting WS server at {}", ws_config.addr);
 
-        let _ = tokio::try_join!(http_server, ws_server)
+        let _ = tokio::try_join!(http_server, ws_server, ipc_server)
             .inspect_err(|e| info!("Error shutting down servers: {e:?}"));
     } else {
-        let _ = tokio::try_join!(http_server)
+        let _ = tokio::try_join!(http_server, ipc_server)
             .inspect_err(|e| info!("Error shutting down servers: {e:?}"));
     }
 
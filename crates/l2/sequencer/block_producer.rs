@@ -16,7 +16,7 @@ use ethrex_common::H256;
 use ethrex_common::{Address, U256};
 use ethrex_l2_sdk::calldata::encode_calldata;
 use ethrex_rpc::{
-    EthClient, SubscriptionManager, SubscriptionManagerProtocol,
+    EthClient,
     clients::{EthClientError, Overrides},
 };
 use ethrex_storage::Store;
@@ -64,8 +64,6 @@ pub struct BlockProducer {
     block_gas_limit: u64,
     eth_client: EthClient,
     router_address: Address,
-    /// Actor handle for sending new block headers to WS subscribers.
-    subscription_manager: Option<ActorRef<SubscriptionManager>>,
 }
 
 #[derive(Clone, Serialize)]
@@ -87,7 +85,6 @@ impl BlockProducer {
         sequencer_state: SequencerState,
         router_address: Address,
         l2_gas_limit: u64,
-        subscription_manager: Option<ActorRef<SubscriptionManager>>,
     ) -> Result<Self, EthClientError> {
         let BlockProducerConfig {
             block_time_ms,
@@ -126,7 +123,6 @@ impl BlockProducer {
             block_gas_limit: l2_gas_limit,
             eth_client,
             router_address,
-            subscription_manager,
         })
     }
 
@@ -207,8 +203,6 @@ impl BlockProducer {
         let transactions_count = block.body.transactions.len();
         let block_number = block.header.number;
         let block_hash = block.hash();
-        // Save the header for newHeads notifications before block is moved into store_block.
-        let block_header = block.header.clone();
         self.store_fee_config_by_block(block.header.number).await?;
         self.blockchain
             .store_block(block, account_updates_list, execution_result)?;
@@ -225,11 +219,6 @@ impl BlockProducer {
         // Make the new head be part of the canonical chain
         apply_fork_choice(&self.store, block_hash, block_hash, block_hash).await?;
 
-        // Notify all eth_subscribe("newHeads") subscribers.
-        if let Some(ref manager) = self.subscription_manager {
-            let _ = manager.new_head(block_header);
-        }
-
         metrics!(
             METRICS_BLOCKS.set_block_number(block_number);
             #[allow(clippy::as_conversions)]
@@ -304,7 +293,6 @@ impl BlockProducer {
 
 #[actor(protocol = BlockProducerProtocol)]
 impl BlockProducer {
-    #[expect(clippy::too_many_arguments)]
     pub async fn spawn(
         store: Store,
         rollup_store: StoreRollup,
@@ -313,7 +301,6 @@ impl BlockProducer {
         sequencer_state: SequencerState,
         router_address: Address,
         l2_gas_limit: u64,
-        subscription_manager: Option<ActorRef<SubscriptionManager>>,
     ) -> Result<ActorRef<BlockProducer>, BlockProducerError> {
         let block_producer = Self::new(
             &cfg.block_producer,
@@ -324,7 +311,6 @@ impl BlockProducer {
             sequencer_state,
             router_address,
             l2_gas_limit,
-            subscription_manager,
         )?;
         let actor_ref = block_producer.start_with_backend(Backend::Blocking);
         Ok(actor_ref)
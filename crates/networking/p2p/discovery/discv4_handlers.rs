@@ -215,8 +215,9 @@ impl DiscoveryServer {
         }
 
         for (idx, node_id, node, message) in queries {
-            if let Err(e) = self.udp_socket.send_to(&message, &node.udp_addr()).await {
-                debug!(protocol = "discv4", sending = "FindNode", addr = ?node.udp_addr(), err=?e, "Error sending message");
+            let addr = self.udp_addr_of(&node);
+            if let Err(e) = self.udp_socket.send_to(&message, addr).await {
+                debug!(protocol = "discv4", sending = "FindNode", addr = ?addr, err=?e, "Error sending message");
                 self.peer_table.set_disposable(node_id)?;
                 METRICS.record_new_discarded_node();
                 if let Some(discv4) = &mut self.discv4
@@ -257,9 +258,10 @@ impl DiscoveryServer {
             udp_port: self.local_node.udp_port,
             tcp_port: self.local_node.tcp_port,
         };
+        let addr = self.udp_addr_of(node);
         let to = Endpoint {
-            ip: node.ip,
-            udp_port: node.udp_port,
+            ip: addr.ip().to_canonical(),
+            udp_port: addr.port(),
             tcp_port: node.tcp_port,
         };
         let enr_seq = self.local_node_record.seq;
@@ -285,7 +287,7 @@ impl DiscoveryServer {
         };
         let enr_seq = self.local_node_record.seq;
         let pong = Message::Pong(PongMessage::new(to, ping_hash, expiration).with_enr_seq(enr_seq));
-        self.discv4_send(pong, node.udp_addr()).await?;
+        self.discv4_send(pong, self.udp_addr_of(node)).await?;
         trace!(protocol = "discv4", sent = "Pong", to = %format!("{:#x}", node.public_key));
         Ok(())
     }
@@ -297,7 +299,7 @@ impl DiscoveryServer {
     ) -> Result<(), DiscoveryServerError> {
         let expiration: u64 = get_msg_expiration_from_seconds(EXPIRATION_SECONDS);
         let msg = Message::Neighbors(NeighborsMessage::new(neighbors, expiration));
-        self.discv4_send(msg, node.udp_addr()).await?;
+        self.discv4_send(msg, self.udp_addr_of(node)).await?;
         trace!(protocol = "discv4", sent = "Neighbors", to = %format!("{:#x}", node.public_key));
         Ok(())
     }
@@ -370,6 +372,11 @@ impl DiscoveryServer {
         let ping_id = Bytes::copy_from_slice(message.ping_hash.as_bytes());
         self.peer_table.record_pong_received(node_id, ping_id)?;
 
+        // `to` is our endpoint as seen by the peer; the vote tally is shared with discv5
+        if let Some(winning_ip) = self.ip_votes.record(message.to.ip.to_canonical(), node_id) {
+            self.apply_voted_ip(winning_ip);
+        }
+
         let stored_enr_seq = contact.record.map(|r| r.seq);
         let received_enr_seq = message.enr_seq;
         if let (Some(received), Some(stored)) = (received_enr_seq, stored_enr_seq)
@@ -393,7 +400,20 @@ impl DiscoveryServer {
             .await
         {
             let target_id = node_id(&target);
-            let neighbors = self.peer_table.get_closest_nodes(target_id).await?;
+            // Announce the endpoints of the requester's address family, and nodes that
+            // have one first
+            let requester_ipv6 = from.ip().is_ipv6();
+            let mut neighbors: Vec<Node> = self
+                .peer_table
+                .get_closest_nodes(target_id)
+                .await?
+                .iter()
+                .map(|node| {
+                    node.in_family(requester_ipv6)
+                        .unwrap_or_else(|| node.clone())
+                })
+                .collect();
+            neighbors.sort_by_key(|node| node.ip.to_canonical().is_ipv6() != requester_ipv6);
 
             for chunk in neighbors.chunks(8) {
                 let _ = self
@@ -601,7 +621,8 @@ impl DiscoveryServer {
         }
         let mut buf = BytesMut::new();
         message.encode_with_header(&mut buf, &self.signer);
-        Ok(self.udp_socket.send_to(&buf, addr).await.inspect_err(
+        let socket_addr = self.socket_addr_for(addr);
+        Ok(self.udp_socket.send_to(&buf, socket_addr).await.inspect_err(
             |e| debug!(protocol = "discv4", sending = ?message, addr = ?addr, err=?e, "Error sending message"),
         )?)
     }
@@ -621,8 +642,9 @@ impl DiscoveryServer {
         let message_hash: [u8; 32] = buf[..32]
             .try_into()
             .expect("first 32 bytes are the message hash");
-        if let Err(e) = self.udp_socket.send_to(&buf, node.udp_addr()).await {
-            debug!(protocol = "discv4", sending = ?message, addr = ?node.udp_addr(), to = ?node.node_id(), err=?e, "Error sending message");
+        let addr = self.udp_addr_of(node);
+        if let Err(e) = self.udp_socket.send_to(&buf, addr).await {
+            debug!(protocol = "discv4", sending = ?message, addr = ?addr, to = ?node.node_id(), err=?e, "Error sending message");
             self.peer_table.set_disposable(node.node_id())?;
             METRICS.record_new_discarded_node();
             return Err(e.into());
@@ -6,7 +6,7 @@ use crate::{
             NodesMessage, Ordinary, Packet, PacketTrait as _, PingMessage, PongMessage,
             TalkResMessage, WhoAreYou, decrypt_message,
         },
-        server::{Discv5Message, Discv5State, update_local_ip},
+        server::Discv5Message,
         session::{
             build_challenge_data, create_id_signature, derive_session_keys, verify_id_signature,
         },
@@ -27,7 +27,10 @@ use std::{
 };
 use tracing::{debug, trace, warn};
 
-use super::server::{DiscoveryServer, DiscoveryServerError};
+use super::{
+    ip_votes::is_private_ip,
+    server::{DiscoveryServer, DiscoveryServerError},
+};
 
 /// Maximum number of ENRs per NODES message (limited by UDP packet size).
 const MAX_ENRS_PER_MESSAGE: usize = 3;
@@ -81,15 +84,15 @@ impl DiscoveryServer {
         let ordinary = match decrypt_key {
             Some(key) => match Ordinary::decode(&packet, &key) {
                 Ok(ordinary) => {
-                    if let Some(session_ip) = discv5.session_ips.get(&src_id)
-                        && addr.ip() != *session_ip
+                    if let Some(session_addr) = discv5.session_addrs.get(&src_id)
+                        && addr != *session_addr
                     {
                         trace!(
                             protocol = "discv5",
                             from = %src_id,
                             %addr,
-                            expected_ip = %session_ip,
-                            "IP mismatch for existing session, sending WhoAreYou"
+                            expected_addr = %session_addr,
+                            "Endpoint mismatch for existing session, sending WhoAreYou"
                         );
                         discv5.whoareyou_rate_limit.pop(&(addr.ip(), src_id));
                         return self
@@ -253,7 +256,7 @@ impl DiscoveryServer {
 
         self.peer_table.set_session_info(src_id, session.clone())?;
         let discv5 = self.discv5.as_mut().expect("discv5 state must exist");
-        discv5.session_ips.insert(src_id, addr.ip());
+        discv5.session_addrs.insert(src_id, addr);
 
         let mut encrypted = packet.encrypted_message.clone();
         decrypt_message(&session.inbound_key, &packet, &mut encrypted)?;
@@ -354,7 +357,7 @@ impl DiscoveryServer {
         for (idx, target, node_id, node) in queries {
             let find_node_msg = self.discv5_build_find_node_for_target(target, &node);
             if let Err(e) = self.discv5_send_ordinary(find_node_msg, &node).await {
-                debug!(protocol = "discv5", sending = "FindNode", addr = ?node.udp_addr(), err=?e, "Error sending message");
+                debug!(protocol = "discv5", sending = "FindNode", addr = ?self.udp_addr_of(&node), err=?e, "Error sending message");
                 self.peer_table.set_disposable(node_id)?;
                 METRICS.record_new_discarded_node();
                 if let Some(discv5) = &mut self.discv5
@@ -440,22 +443,11 @@ impl DiscoveryServer {
             }
         }
 
-        let discv5 = self.discv5.as_mut().expect("discv5 state must exist");
-        if let Some(winning_ip) = discv5.record_ip_vote(pong_message.recipient_addr.ip(), sender_id)
-            && winning_ip != self.local_node.ip
+        if let Some(winning_ip) = self
+            .ip_votes
+            .record(pong_message.recipient_addr.ip(), sender_id)
         {
-            tracing::info!(
-                protocol = "discv5",
-                old_ip = %self.local_node.ip,
-                new_ip = %winning_ip,
-                "External IP detected via PONG voting, updating local ENR"
-            );
-            update_local_ip(
-                &mut self.local_node,
-                &mut self.local_node_record,
-                &self.signer,
-                winning_ip,
-            );
+            self.apply_voted_ip(winning_ip);
         }
 
         Ok(())
@@ -488,6 +480,9 @@ impl DiscoveryServer {
         if find_node_message.distances.contains(&0) {
             nodes.push(self.local_node_record.clone());
         }
+        // Records reachable over the requester's address family go first
+        let requester_ipv6 = sender_addr.ip().is_ipv6();
+        nodes.sort_by_key(|record| !record.pairs().has_family(requester_ipv6));
 
         let key = self
             .discv5_resolve_outbound_key(&sender_id, outbound_key)
@@ -600,7 +595,7 @@ impl DiscoveryServer {
 
         let packet = ordinary.encode(&nonce, masking_iv.to_be_bytes(), &encrypt_key)?;
 
-        self.discv5_send_packet(&packet, &node.node_id(), node.udp_addr())
+        self.discv5_send_packet(&packet, &node.node_id(), self.udp_addr_of(node))
             .await?;
         let discv5 = self.discv5.as_mut().expect("discv5 state must exist");
         discv5
@@ -697,7 +692,7 @@ impl DiscoveryServer {
 
         let packet = handshake.encode(&nonce, masking_iv.to_be_bytes(), &encrypt_key)?;
 
-        self.discv5_send_packet(&packet, &node.node_id(), node.udp_addr())
+        self.discv5_send_packet(&packet, &node.node_id(), self.udp_addr_of(node))
             .await?;
         let discv5 = self.discv5.as_mut().expect("discv5 state must exist");
         discv5
@@ -717,6 +712,7 @@ impl DiscoveryServer {
             use ethrex_metrics::p2p::METRICS_P2P;
             METRICS_P2P.inc_discv5_outgoing("WhoAreYou");
         }
+        let socket_addr = self.socket_addr_for(addr);
         let discv5 = self.discv5.as_mut().expect("discv5 state must exist");
 
         let rate_key = (addr.ip(), src_id);
@@ -748,12 +744,12 @@ impl DiscoveryServer {
                 %addr,
                 "Resending existing WhoAreYou challenge"
             );
-            self.udp_socket.send_to(raw_bytes, addr).await?;
+            self.udp_socket.send_to(raw_bytes, socket_addr).await?;
             return Ok(());
         }
 
         // Per-(IP, node) rate limit
-        if !Discv5State::is_private_ip(addr.ip())
+        if !is_private_ip(addr.ip())
             && let Some(last_sent) = discv5.whoareyou_rate_limit.get(&rate_key)
             && now.duration_since(*last_sent) < WHOAREYOU_RATE_LIMIT
         {
@@ -798,7 +794,7 @@ impl DiscoveryServer {
             .pending_challenges
             .insert(src_id, (challenge_data, Instant::now(), raw_bytes.clone()));
 
-        self.udp_socket.send_to(&raw_bytes, addr).await?;
+        self.udp_socket.send_to(&raw_bytes, socket_addr).await?;
         trace!(protocol = "discv5", to = %src_id, %addr, flag = packet.header.flag, "Sent packet");
 
         Ok(())
@@ -812,7 +808,9 @@ impl DiscoveryServer {
     ) -> Result<(), DiscoveryServerError> {
         let mut buf = BytesMut::new();
         packet.encode(&mut buf, dest_id)?;
-        self.udp_socket.send_to(&buf, addr).await?;
+        self.udp_socket
+            .send_to(&buf, self.socket_addr_for(addr))
+            .await?;
         trace!(protocol = "discv5", to = %dest_id, %addr, flag = packet.header.flag, "Sent packet");
         Ok(())
     }
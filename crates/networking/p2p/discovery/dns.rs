@@ -0,0 +1,567 @@
+//! EIP-1459 node discovery via DNS.
+//!
+//! A node list is published as a Merkle tree of TXT records under a domain. The root
+//! record (`enrtree-root:v1 e=<enr-root> l=<link-root> seq=<n> sig=<sig>`) is signed
+//! by the key in the `enrtree://<key>@<domain>` URL and points to two subtrees: one
+//! with the node records (`enr:` leaves) and one with links to other trees
+//! (`enrtree://` leaves). Every other entry lives at `<hash>.<domain>`, where the hash
+//! is the base32-encoded first 16 bytes of the keccak256 of the entry, so verifying the
+//! root signature authenticates the whole tree.
+//!
+//! The resolved records are fed into the peer table as discovery contacts, the same
+//! way discv5 `Nodes` responses are.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    future::Future,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    str::FromStr,
+    time::Duration,
+};
+
+use ethrex_common::base64;
+use ethrex_crypto::keccak::keccak_hash;
+use ethrex_rlp::decode::RLPDecode;
+use rustc_hash::FxHashSet;
+use secp256k1::{PublicKey, ecdsa::Signature};
+use tokio::net::UdpSocket;
+use tracing::{debug, info, warn};
+
+use crate::{
+    peer_table::{PeerTable, PeerTableServerProtocol as _},
+    types::NodeRecord,
+};
+
+const ROOT_PREFIX: &str = "enrtree-root:v1";
+const BRANCH_PREFIX: &str = "enrtree-branch:";
+const LINK_PREFIX: &str = "enrtree://";
+const ENR_PREFIX: &str = "enr:";
+
+/// How often the configured trees are resolved again to pick up new records.
+pub const DNS_DISCOVERY_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Maximum number of entries resolved per tree, bounding the work a hostile tree can cause.
+const MAX_TREE_ENTRIES: usize = 10_000;
+/// Maximum number of trees resolved from a single URL, including linked ones.
+const MAX_LINKED_TREES: usize = 16;
+/// Nameserver used when none can be read from `/etc/resolv.conf`.
+const DEFAULT_NAMESERVER: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(1, 1, 1, 1)), 53);
+const DNS_QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+const DNS_TYPE_TXT: u16 = 16;
+const DNS_CLASS_IN: u16 = 1;
+
+#[derive(Debug, thiserror::Error)]
+pub enum DnsDiscoveryError {
+    #[error("Invalid enrtree URL: {0}")]
+    InvalidUrl(String),
+    #[error("Invalid tree entry: {0}")]
+    InvalidEntry(String),
+    #[error("Invalid root signature for {0}")]
+    InvalidSignature(String),
+    #[error("No tree root found at {0}")]
+    MissingRoot(String),
+    #[error("No entry matching its hash found at {0}")]
+    MissingEntry(String),
+    #[error("Tree at {0} has too many entries")]
+    TreeTooLarge(String),
+    #[error("DNS query failed: {0}")]
+    Query(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+/// Source of DNS TXT records. Implemented by [`UdpDnsResolver`] and by in-process fake
+/// zones in tests.
+pub trait DnsResolver: Send + Sync {
+    /// Returns the TXT records at `name`, each one with its character-strings
+    /// concatenated. A name without records resolves to an empty list.
+    fn txt_records(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<Vec<String>, DnsDiscoveryError>> + Send;
+}
+
+/// An `enrtree://<base32 compressed public key>@<domain>` URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnrTreeUrl {
+    pub public_key: PublicKey,
+    pub domain: String,
+}
+
+impl FromStr for EnrTreeUrl {
+    type Err = DnsDiscoveryError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let invalid = || DnsDiscoveryError::InvalidUrl(url.to_string());
+        let (key, domain) = url
+            .strip_prefix(LINK_PREFIX)
+            .and_then(|rest| rest.split_once('@'))
+            .ok_or_else(invalid)?;
+        if domain.is_empty() {
+            return Err(invalid());
+        }
+        let key = base32_decode(key).ok_or_else(invalid)?;
+        let public_key = PublicKey::from_slice(&key).map_err(|_| invalid())?;
+        Ok(Self {
+            public_key,
+            domain: domain.trim_end_matches('.').to_string(),
+        })
+    }
+}
+
+impl fmt::Display for EnrTreeUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{LINK_PREFIX}{}@{}",
+            base32_encode(&self.public_key.serialize()),
+            self.domain
+        )
+    }
+}
+
+/// Returns the subdomain under which `entry` is published: the unpadded base32 encoding
+/// of the first 16 bytes of its keccak256 hash.
+pub fn subdomain_hash(entry: &str) -> String {
+    base32_encode(&keccak_hash(entry.as_bytes())[..16])
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RootEntry {
+    enr_root: String,
+    link_root: String,
+    seq: u64,
+}
+
+impl RootEntry {
+    /// Parses a root record and verifies its signature with the key of `url`.
+    fn parse(text: &str, url: &EnrTreeUrl) -> Result<Self, DnsDiscoveryError> {
+        let invalid = || DnsDiscoveryError::InvalidEntry(text.to_string());
+        let (signed, signature) = text.rsplit_once(" sig=").ok_or_else(invalid)?;
+        let fields = signed.strip_prefix(ROOT_PREFIX).ok_or_else(invalid)?;
+
+        let (mut enr_root, mut link_root, mut seq) = (None, None, None);
+        for field in fields.split_whitespace() {
+            match field.split_once('=') {
+                Some(("e", value)) => enr_root = Some(value.to_string()),
+                Some(("l", value)) => link_root = Some(value.to_string()),
+                Some(("seq", value)) => seq = value.parse().ok(),
+                _ => return Err(invalid()),
+            }
+        }
+
+        // The signature is [R || S || V], only R and S are needed to verify it
+        let signature = base64::decode(signature.as_bytes());
+        let verified = signature.len() == 65
+            && Signature::from_compact(&signature[..64])
+                .map(|mut signature| {
+                    signature.normalize_s();
+                    let digest = keccak_hash(signed.as_bytes());
+                    let message = secp256k1::Message::from_digest(digest);
+                    secp256k1::SECP256K1
+                        .verify_ecdsa(&message, &signature, &url.public_key)
+                        .is_ok()
+                })
+                .unwrap_or(false);
+        if !verified {
+            return Err(DnsDiscoveryError::InvalidSignature(url.domain.clone()));
+        }
+
+        Ok(Self {
+            enr_root: enr_root.ok_or_else(invalid)?,
+            link_root: link_root.ok_or_else(invalid)?,
+            seq: seq.ok_or_else(invalid)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+enum TreeEntry {
+    Branch(Vec<String>),
+    Enr(Box<NodeRecord>),
+    Link(EnrTreeUrl),
+}
+
+impl TreeEntry {
+    fn parse(text: &str) -> Result<Self, DnsDiscoveryError> {
+        if let Some(children) = text.strip_prefix(BRANCH_PREFIX) {
+            Ok(Self::Branch(
+                children
+                    .split(',')
+                    .filter(|child| !child.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            ))
+        } else if let Some(record) = text.strip_prefix(ENR_PREFIX) {
+            let record = NodeRecord::decode(&base64::decode(record.as_bytes()))
+                .map_err(|_| DnsDiscoveryError::InvalidEntry(text.to_string()))?;
+            Ok(Self::Enr(Box::new(record)))
+        } else if text.starts_with(LINK_PREFIX) {
+            Ok(Self::Link(text.parse()?))
+        } else {
+            Err(DnsDiscoveryError::InvalidEntry(text.to_string()))
+        }
+    }
+}
+
+/// Walks EIP-1459 trees using a [`DnsResolver`].
+#[derive(Debug, Clone)]
+pub struct DnsDiscovery<R> {
+    resolver: R,
+}
+
+impl<R: DnsResolver> DnsDiscovery<R> {
+    pub fn new(resolver: R) -> Self {
+        Self { resolver }
+    }
+
+    /// Resolves the tree at `url` and the trees it links to, returning every node record
+    /// found. Linked trees that fail to resolve are skipped.
+    pub async fn resolve(&self, url: &EnrTreeUrl) -> Result<Vec<NodeRecord>, DnsDiscoveryError> {
+        let mut links = Vec::new();
+        let mut records = self.resolve_tree(url, &mut links).await?;
+
+        let mut visited = FxHashSet::from_iter([url.domain.clone()]);
+        while let Some(link) = links.pop() {
+            if visited.len() >= MAX_LINKED_TREES {
+                debug!(tree=%url, "Too many linked DNS trees, ignoring the rest");
+                break;
+            }
+            if !visited.insert(link.domain.clone()) {
+                continue;
+            }
+            match self.resolve_tree(&link, &mut links).await {
+                Ok(linked_records) => records.extend(linked_records),
+                Err(err) => debug!(tree=%link, %err, "Failed to resolve linked DNS tree"),
+            }
+        }
+        Ok(records)
+    }
+
+    /// Resolves the records of the tree at `url`, pushing the trees it links to into `links`.
+    async fn resolve_tree(
+        &self,
+        url: &EnrTreeUrl,
+        links: &mut Vec<EnrTreeUrl>,
+    ) -> Result<Vec<NodeRecord>, DnsDiscoveryError> {
+        let root = self.resolve_root(url).await?;
+        debug!(tree=%url, seq=root.seq, "Resolved DNS tree root");
+
+        let mut records = Vec::new();
+        for entry in self.resolve_subtree(&url.domain, &root.enr_root).await? {
+            match entry {
+                TreeEntry::Enr(record) => records.push(*record),
+                other => debug!(tree=%url, entry=?other, "Ignoring non-ENR entry in ENR subtree"),
+            }
+        }
+        for entry in self.resolve_subtree(&url.domain, &root.link_root).await? {
+            match entry {
+                TreeEntry::Link(link) => links.push(link),
+                other => debug!(tree=%url, entry=?other, "Ignoring non-link entry in link subtree"),
+            }
+        }
+        Ok(records)
+    }
+
+    async fn resolve_root(&self, url: &EnrTreeUrl) -> Result<RootEntry, DnsDiscoveryError> {
+        let root = self
+            .resolver
+            .txt_records(&url.domain)
+            .await?
+            .into_iter()
+            .find(|text| text.starts_with(ROOT_PREFIX))
+            .ok_or_else(|| DnsDiscoveryError::MissingRoot(url.domain.clone()))?;
+        RootEntry::parse(&root, url)
+    }
+
+    /// Returns the leaves of the subtree rooted at `hash`, checking that every entry
+    /// matches the hash it was published under.
+    async fn resolve_subtree(
+        &self,
+        domain: &str,
+        hash: &str,
+    ) -> Result<Vec<TreeEntry>, DnsDiscoveryError> {
+        let mut leaves = Vec::new();
+        let mut pending = VecDeque::from([hash.to_string()]);
+        let mut visited = FxHashSet::default();
+        while let Some(hash) = pending.pop_front() {
+            if !visited.insert(hash.to_ascii_uppercase()) {
+                continue;
+            }
+            if visited.len() > MAX_TREE_ENTRIES {
+                return Err(DnsDiscoveryError::TreeTooLarge(domain.to_string()));
+            }
+            let name = format!("{hash}.{domain}");
+            let entry = self
+                .resolver
+                .txt_records(&name)
+                .await?
+                .into_iter()
+                .find(|text| subdomain_hash(text).eq_ignore_ascii_case(&hash))
+                .ok_or(DnsDiscoveryError::MissingEntry(name))?;
+            match TreeEntry::parse(&entry)? {
+                TreeEntry::Branch(children) => pending.extend(children),
+                leaf => leaves.push(leaf),
+            }
+        }
+        Ok(leaves)
+    }
+}
+
+/// Resolves `trees` every [`DNS_DISCOVERY_INTERVAL`] and adds the nodes found to the
+/// peer table. Runs until the peer table is stopped.
+pub async fn run_dns_discovery<R: DnsResolver>(
+    resolver: R,
+    trees: Vec<EnrTreeUrl>,
+    peer_table: PeerTable,
+) {
+    let discovery = DnsDiscovery::new(resolver);
+    loop {
+        for tree in &trees {
+            match discovery.resolve(tree).await {
+                Ok(records) => {
+                    info!(tree=%tree, nodes=records.len(), "Resolved DNS discovery tree");
+                    if let Err(err) = peer_table.new_contact_records(records) {
+                        debug!(%err, "Peer table stopped, stopping DNS discovery");
+                        return;
+                    }
+                }
+                Err(err) => warn!(tree=%tree, %err, "Failed to resolve DNS discovery tree"),
+            }
+        }
+        tokio::time::sleep(DNS_DISCOVERY_INTERVAL).await;
+    }
+}
+
+/// Minimal DNS client sending TXT queries over UDP to a single recursive nameserver.
+#[derive(Debug, Clone)]
+pub struct UdpDnsResolver {
+    nameserver: SocketAddr,
+}
+
+impl UdpDnsResolver {
+    pub fn new(nameserver: SocketAddr) -> Self {
+        Self { nameserver }
+    }
+
+    /// Uses the first nameserver listed in `/etc/resolv.conf`, falling back to a public
+    /// resolver if there is none.
+    pub fn from_system_config() -> Self {
+        let nameserver = std::fs::read_to_string("/etc/resolv.conf")
+            .ok()
+            .and_then(|config| {
+                config.lines().find_map(|line| {
+                    let mut fields = line.split_whitespace();
+                    (fields.next() == Some("nameserver"))
+                        .then(|| fields.next()?.parse::<IpAddr>().ok())
+                        .flatten()
+                })
+            })
+            .map(|ip| SocketAddr::new(ip, 53))
+            .unwrap_or(DEFAULT_NAMESERVER);
+        Self::new(nameserver)
+    }
+}
+
+impl DnsResolver for UdpDnsResolver {
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>, DnsDiscoveryError> {
+        let id = rand::random::<u16>();
+        let query = encode_txt_query(id, name)?;
+        let bind_addr = match self.nameserver {
+            SocketAddr::V4(_) => SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0),
+            SocketAddr::V6(_) => SocketAddr::new(IpAddr::V6(Ipv6Addr::UNSPECIFIED), 0),
+        };
+        let socket = UdpSocket::bind(bind_addr).await?;
+        socket.connect(self.nameserver).await?;
+        socket.send(&query).await?;
+
+        let mut buf = vec![0; 4096];
+        loop {
+            let len = tokio::time::timeout(DNS_QUERY_TIMEOUT, socket.recv(&mut buf))
+                .await
+                .map_err(|_| DnsDiscoveryError::Query(format!("{name}: timed out")))??;
+            // Ignore stray datagrams that don't answer our query
+            if let Some(records) = decode_txt_response(id, &buf[..len])? {
+                return Ok(records);
+            }
+        }
+    }
+}
+
+/// Encodes a recursive query for the TXT records of `name`.
+fn encode_txt_query(id: u16, name: &str) -> Result<Vec<u8>, DnsDiscoveryError> {
+    let mut query = Vec::with_capacity(18 + name.len());
+    query.extend_from_slice(&id.to_be_bytes());
+    // Flags: recursion desired
+    query.extend_from_slice(&0x0100u16.to_be_bytes());
+    // One question, no answer, authority or additional records
+    query.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(DnsDiscoveryError::Query(format!("invalid name {name}")));
+        }
+        query.push(label.len() as u8);
+        query.extend_from_slice(label.as_bytes());
+    }
+    query.push(0);
+    query.extend_from_slice(&DNS_TYPE_TXT.to_be_bytes());
+    query.extend_from_slice(&DNS_CLASS_IN.to_be_bytes());
+    Ok(query)
+}
+
+/// Decodes the TXT records of a response to query `id`, returning `None` if the message
+/// isn't a response to it.
+fn decode_txt_response(id: u16, msg: &[u8]) -> Result<Option<Vec<String>>, DnsDiscoveryError> {
+    let malformed = || DnsDiscoveryError::Query("malformed response".to_string());
+    let read_u16 = |pos: usize| -> Result<u16, DnsDiscoveryError> {
+        msg.get(pos..pos + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(malformed)
+    };
+
+    let flags = read_u16(2)?;
+    if read_u16(0)? != id || flags & 0x8000 == 0 {
+        return Ok(None);
+    }
+    if flags & 0x0200 != 0 {
+        return Err(DnsDiscoveryError::Query("truncated response".to_string()));
+    }
+    match flags & 0x000f {
+        0 => {}
+        // NXDOMAIN
+        3 => return Ok(Some(Vec::new())),
+        rcode => return Err(DnsDiscoveryError::Query(format!("response code {rcode}"))),
+    }
+
+    let questions = read_u16(4)?;
+    let answers = read_u16(6)?;
+    let mut pos = 12;
+    for _ in 0..questions {
+        pos = skip_name(msg, pos).ok_or_else(malformed)? + 4;
+    }
+
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        pos = skip_name(msg, pos).ok_or_else(malformed)?;
+        let record_type = read_u16(pos)?;
+        let data_len = read_u16(pos + 8)? as usize;
+        let data_start = pos + 10;
+        let data = msg
+            .get(data_start..data_start + data_len)
+            .ok_or_else(malformed)?;
+        pos = data_start + data_len;
+        if record_type != DNS_TYPE_TXT {
+            continue;
+        }
+
+        // A TXT record is a sequence of length-prefixed character-strings
+        let mut text = Vec::with_capacity(data_len);
+        let mut rest = data;
+        while let Some((&len, tail)) = rest.split_first() {
+            let chunk = tail.get(..len as usize).ok_or_else(malformed)?;
+            text.extend_from_slice(chunk);
+            rest = &tail[len as usize..];
+        }
+        if let Ok(text) = String::from_utf8(text) {
+            records.push(text);
+        }
+    }
+    Ok(Some(records))
+}
+
+/// Returns the position right after the (possibly compressed) name starting at `pos`.
+fn skip_name(msg: &[u8], mut pos: usize) -> Option<usize> {
+    loop {
+        let len = *msg.get(pos)?;
+        match len {
+            0 => return Some(pos + 1),
+            // Compression pointer, the name ends here
+            len if len & 0xc0 == 0xc0 => return Some(pos + 2),
+            len => pos += 1 + len as usize,
+        }
+    }
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// Unpadded RFC 4648 base32 encoding.
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        encoded.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    encoded
+}
+
+/// RFC 4648 base32 decoding, accepting lowercase and optional padding.
+fn base32_decode(text: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(text.len() * 5 / 8);
+    let (mut buffer, mut bits) = (0u32, 0u32);
+    for char in text.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&c| c == char.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base32_round_trip() {
+        let bytes = (0u8..=40).collect::<Vec<_>>();
+        for len in 0..bytes.len() {
+            let encoded = base32_encode(&bytes[..len]);
+            assert!(!encoded.contains('='));
+            assert_eq!(base32_decode(&encoded).unwrap(), &bytes[..len]);
+            assert_eq!(
+                base32_decode(&encoded.to_ascii_lowercase()).unwrap(),
+                &bytes[..len]
+            );
+        }
+        // RFC 4648 test vector
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+    }
+
+    #[test]
+    fn decode_txt_answer_with_multiple_strings() {
+        let id = 0x1234;
+        let mut msg = encode_txt_query(id, "nodes.example.org").unwrap();
+        // Turn the query into a response with one answer
+        msg[2] = 0x81;
+        msg[3] = 0x80;
+        msg[7] = 1;
+        // Name as a compression pointer to the question
+        msg.extend_from_slice(&[0xc0, 12]);
+        msg.extend_from_slice(&DNS_TYPE_TXT.to_be_bytes());
+        msg.extend_from_slice(&DNS_CLASS_IN.to_be_bytes());
+        msg.extend_from_slice(&60u32.to_be_bytes());
+        msg.extend_from_slice(&9u16.to_be_bytes());
+        msg.extend_from_slice(b"\x03enr\x04tree");
+
+        assert_eq!(
+            decode_txt_response(id, &msg).unwrap(),
+            Some(vec!["enrtree".to_string()])
+        );
+        assert_eq!(decode_txt_response(id + 1, &msg).unwrap(), None);
+    }
+}
@@ -0,0 +1,205 @@
+use ethrex_common::H256;
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+/// Time window for collecting IP votes from PONGs.
+const IP_VOTE_WINDOW: Duration = Duration::from_secs(300);
+/// Minimum number of agreeing votes required to update external IP.
+const IP_VOTE_THRESHOLD: usize = 3;
+
+/// External IP detection via majority voting over the endpoint peers report in their PONGs
+/// (discv4 `to`, discv5 `recipient_addr`). Shared by both protocols, so a node running only
+/// one of them still learns its external IP.
+#[derive(Debug, Default)]
+pub struct IpVotes {
+    /// Voters for each reported IP in the current round.
+    pub votes: FxHashMap<IpAddr, FxHashSet<H256>>,
+    /// When the current voting period started. None if no votes received yet.
+    pub period_start: Option<Instant>,
+    /// Whether the first (fast) voting round has completed.
+    pub first_round_completed: bool,
+}
+
+impl IpVotes {
+    /// Records an IP vote from a PONG.
+    /// Returns `Some(ip)` if the voting round ended with a winning IP to apply.
+    pub fn record(&mut self, reported_ip: IpAddr, voter_id: H256) -> Option<IpAddr> {
+        if is_private_ip(reported_ip) {
+            return None;
+        }
+
+        let now = Instant::now();
+
+        if self.period_start.is_none() {
+            self.period_start = Some(now);
+        }
+
+        self.votes.entry(reported_ip).or_default().insert(voter_id);
+
+        let total_votes: usize = self.votes.values().map(|v| v.len()).sum();
+        let round_ended = if !self.first_round_completed {
+            total_votes >= IP_VOTE_THRESHOLD
+        } else {
+            self.period_start
+                .is_some_and(|start| now.duration_since(start) >= IP_VOTE_WINDOW)
+        };
+
+        if round_ended {
+            return self.finalize_round();
+        }
+        None
+    }
+
+    /// Ends the current voting round if it has timed out.
+    /// Returns `Some(ip)` if the round produced a winning IP to apply.
+    pub fn expire_round(&mut self) -> Option<IpAddr> {
+        if let Some(start) = self.period_start
+            && start.elapsed() >= IP_VOTE_WINDOW
+        {
+            return self.finalize_round();
+        }
+        None
+    }
+
+    /// Finalizes the current voting round.
+    /// Returns `Some(winning_ip)` if a winner reached the threshold and should be applied.
+    fn finalize_round(&mut self) -> Option<IpAddr> {
+        let winner = self
+            .votes
+            .iter()
+            .map(|(ip, voters)| (*ip, voters.len()))
+            .max_by_key(|(_, count)| *count);
+
+        let result = winner.and_then(|(winning_ip, vote_count)| {
+            (vote_count >= IP_VOTE_THRESHOLD).then_some(winning_ip)
+        });
+
+        self.votes.clear();
+        self.period_start = Some(Instant::now());
+        self.first_round_completed = true;
+
+        result
+    }
+}
+
+/// Returns true if the IP is private/local (not useful for external connectivity).
+pub fn is_private_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_private() || v4.is_loopback() || v4.is_link_local(),
+        IpAddr::V6(v6) => {
+            v6.is_loopback()
+                || v6.is_unspecified()
+                // unique local (fc00::/7)
+                || (v6.segments()[0] & 0xfe00) == 0xfc00
+                // link-local (fe80::/10)
+                || (v6.segments()[0] & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ip_voting_returns_winning_ip() {
+        let mut votes = IpVotes::default();
+
+        let new_ip: IpAddr = "203.0.113.50".parse().unwrap();
+        let voter1 = H256::from_low_u64_be(1);
+        let voter2 = H256::from_low_u64_be(2);
+        let voter3 = H256::from_low_u64_be(3);
+
+        assert_eq!(votes.record(new_ip, voter1), None);
+        assert_eq!(votes.record(new_ip, voter2), None);
+        // Third vote triggers round end, returns the winning IP
+        assert_eq!(votes.record(new_ip, voter3), Some(new_ip));
+        assert!(votes.votes.is_empty());
+    }
+
+    #[test]
+    fn test_ip_voting_same_peer_votes_once() {
+        let mut votes = IpVotes::default();
+
+        let new_ip: IpAddr = "203.0.113.50".parse().unwrap();
+        let same_voter = H256::from_low_u64_be(1);
+
+        votes.record(new_ip, same_voter);
+        votes.record(new_ip, same_voter);
+        votes.record(new_ip, same_voter);
+
+        assert_eq!(votes.votes.get(&new_ip).map(|v| v.len()), Some(1));
+    }
+
+    #[test]
+    fn test_ip_voting_ignores_private_ips() {
+        let mut votes = IpVotes::default();
+
+        let voter1 = H256::from_low_u64_be(1);
+
+        let private_ip: IpAddr = "192.168.1.100".parse().unwrap();
+        votes.record(private_ip, voter1);
+        assert!(votes.votes.is_empty());
+
+        let loopback: IpAddr = "127.0.0.1".parse().unwrap();
+        votes.record(loopback, voter1);
+        assert!(votes.votes.is_empty());
+
+        let public_ip: IpAddr = "203.0.113.50".parse().unwrap();
+        votes.record(public_ip, voter1);
+        assert_eq!(votes.votes.get(&public_ip).map(|v| v.len()), Some(1));
+    }
+
+    #[test]
+    fn test_ip_voting_split_votes_no_winner() {
+        let mut votes = IpVotes::default();
+
+        let ip1: IpAddr = "203.0.113.50".parse().unwrap();
+        let ip2: IpAddr = "203.0.113.51".parse().unwrap();
+        let voter1 = H256::from_low_u64_be(1);
+        let voter2 = H256::from_low_u64_be(2);
+        let voter3 = H256::from_low_u64_be(3);
+
+        votes.record(ip1, voter1);
+        votes.record(ip2, voter2);
+        // ip1 has 2 votes, ip2 has 1 — ip1 wins but only has 2 < threshold 3
+        assert_eq!(votes.record(ip1, voter3), None);
+        assert!(votes.votes.is_empty());
+        assert!(votes.first_round_completed);
+    }
+
+    #[test]
+    fn test_ip_vote_round_not_expired() {
+        let mut votes = IpVotes::default();
+
+        let ip: IpAddr = "203.0.113.50".parse().unwrap();
+        votes.record(ip, H256::from_low_u64_be(1));
+        assert_eq!(votes.votes.len(), 1);
+
+        // The round hasn't timed out yet, so the votes are retained
+        assert_eq!(votes.expire_round(), None);
+        assert_eq!(votes.votes.len(), 1);
+        assert!(!votes.first_round_completed);
+    }
+
+    #[test]
+    fn test_ip_vote_round_expired() {
+        let mut votes = IpVotes::default();
+
+        let ip: IpAddr = "203.0.113.50".parse().unwrap();
+        votes.record(ip, H256::from_low_u64_be(1));
+        votes.period_start = Some(
+            Instant::now()
+                .checked_sub(IP_VOTE_WINDOW)
+                .expect("monotonic clock is past the vote window"),
+        );
+
+        // A single vote is below the threshold, so the timed-out round has no winner
+        assert_eq!(votes.expire_round(), None);
+        assert!(votes.votes.is_empty());
+        assert!(votes.first_round_completed);
+    }
+}
@@ -13,11 +13,17 @@
 pub mod codec;
 mod discv4_handlers;
 mod discv5_handlers;
+pub mod dns;
+pub mod ip_votes;
 pub mod lookup;
 pub mod server;
 
 pub use server::{DiscoveryServer, DiscoveryServerError, is_discv4_packet};
 
+use dns::EnrTreeUrl;
+
+use crate::nat::NatMode;
+
 use std::time::Duration;
 
 /// Configuration for which discovery protocols to enable.
@@ -26,6 +32,10 @@ pub struct DiscoveryConfig {
     pub discv4_enabled: bool,
     pub discv5_enabled: bool,
     pub initial_lookup_interval: f64,
+    /// EIP-1459 trees resolved periodically as an extra bootstrap source.
+    pub dns_trees: Vec<EnrTreeUrl>,
+    /// NAT traversal mode; ports are mapped on the gateway by the discovery server.
+    pub nat: NatMode,
 }
 
 impl Default for DiscoveryConfig {
@@ -34,6 +44,8 @@ impl Default for DiscoveryConfig {
             discv4_enabled: true,
             discv5_enabled: true,
             initial_lookup_interval: INITIAL_LOOKUP_INTERVAL_MS,
+            dns_trees: Vec::new(),
+            nat: NatMode::None,
         }
     }
 }
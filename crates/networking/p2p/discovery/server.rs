@@ -5,8 +5,9 @@ use crate::{
     },
     discv5::{
         messages::{Packet as Discv5Packet, PacketCodecError},
-        server::{Discv5Message, Discv5State, update_local_ip},
+        server::{Discv5Message, Discv5State, update_local_endpoint},
     },
+    nat::{self, ExternalEndpoint, NatMode},
     peer_table::{DiscoveryProtocol, PeerTable, PeerTableServerProtocol as _},
     types::{INITIAL_ENR_SEQ, Node, NodeRecord},
 };
@@ -24,13 +25,22 @@ use spawned_concurrency::{
         spawn_listener,
     },
 };
-use std::{net::SocketAddr, sync::Arc, time::Duration};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::Duration,
+};
 use thiserror::Error;
 use tokio::net::UdpSocket;
 use tokio_util::udp::UdpFramed;
 use tracing::{debug, error, info, trace};
 
-use super::{DiscoveryConfig, codec::DiscriminatingCodec, lookup_interval_function};
+use super::{
+    DiscoveryConfig,
+    codec::DiscriminatingCodec,
+    ip_votes::{IpVotes, is_private_ip},
+    lookup_interval_function,
+};
 
 /// Minimum packet size for a valid discv4 packet.
 /// hash (32) + signature (65) + type (1) = 98 bytes
@@ -79,6 +89,7 @@ pub trait DiscoveryServerProtocol: Send + Sync {
     fn lookup_v5(&self) -> Result<(), ActorError>;
     fn enr_lookup(&self) -> Result<(), ActorError>;
     fn prune(&self) -> Result<(), ActorError>;
+    fn nat_mapped(&self, endpoint: ExternalEndpoint) -> Result<(), ActorError>;
     fn shutdown(&self) -> Result<(), ActorError>;
 }
 
@@ -92,6 +103,8 @@ pub struct DiscoveryServer {
     pub(crate) config: DiscoveryConfig,
     pub discv4: Option<Discv4State>,
     pub discv5: Option<Discv5State>,
+    /// External IP votes from the PONGs of both protocols.
+    pub ip_votes: IpVotes,
 }
 
 impl std::fmt::Debug for DiscoveryServer {
@@ -159,6 +172,7 @@ impl DiscoveryServer {
             config,
             discv4,
             discv5,
+            ip_votes: IpVotes::default(),
         };
 
         // Ping discv4 bootnodes
@@ -219,6 +233,19 @@ impl DiscoveryServer {
             let _ = ctx.send(discovery_server_protocol::LookupV5);
         }
 
+        // Gateway port mappings, refreshed for as long as the server runs
+        if self.config.nat.maps_ports() {
+            let mappings = nat::port_mappings(
+                self.config.nat,
+                self.local_node.tcp_port,
+                self.local_node.udp_port,
+            );
+            spawn_listener(
+                ctx.clone(),
+                mappings.map(|endpoint| discovery_server_protocol::NatMapped { endpoint }),
+            );
+        }
+
         // Shared prune timer
         send_interval(
             PRUNE_INTERVAL,
@@ -318,6 +345,45 @@ impl DiscoveryServer {
             .inspect_err(|e| error!(err=?e, "Error Pruning peer table"));
     }
 
+    #[send_handler]
+    async fn handle_nat_mapped(
+        &mut self,
+        msg: discovery_server_protocol::NatMapped,
+        _ctx: &Context<Self>,
+    ) {
+        let ExternalEndpoint {
+            ip,
+            tcp_port,
+            udp_port,
+        } = msg.endpoint;
+        if is_private_ip(ip) {
+            debug!(%ip, "Gateway external address is private, not announcing it");
+            return;
+        }
+        if ip == self.local_node.ip
+            && tcp_port == self.local_node.tcp_port
+            && udp_port == self.local_node.udp_port
+        {
+            return;
+        }
+        info!(
+            nat = %self.config.nat,
+            old_endpoint = %self.local_node.udp_addr(),
+            new_ip = %ip,
+            tcp_port,
+            udp_port,
+            "External endpoint mapped on the gateway, updating local ENR"
+        );
+        update_local_endpoint(
+            &mut self.local_node,
+            &mut self.local_node_record,
+            &self.signer,
+            ip,
+            udp_port,
+            tcp_port,
+        );
+    }
+
     #[send_handler]
     async fn handle_shutdown(
         &mut self,
@@ -330,6 +396,8 @@ impl DiscoveryServer {
     // --- Shared logic ---
 
     async fn route_packet(&mut self, data: &[u8], from: SocketAddr) {
+        // A dual-stack socket reports IPv4 senders as IPv4-mapped IPv6 addresses
+        let from = SocketAddr::new(from.ip().to_canonical(), from.port());
         if is_discv4_packet(data) {
             self.route_to_discv4(data, from).await;
         } else {
@@ -387,29 +455,62 @@ impl DiscoveryServer {
                 .pending_find_node
                 .retain(|_, sent_at| sent_at.elapsed() < expiration);
         }
-        let winning_ip = self
-            .discv5
-            .as_mut()
-            .and_then(|discv5| discv5.cleanup_stale_entries());
-        if let Some(winning_ip) = winning_ip
-            && winning_ip != self.local_node.ip
-        {
-            info!(
-                protocol = "discv5",
-                old_ip = %self.local_node.ip,
-                new_ip = %winning_ip,
-                "External IP detected via PONG voting, updating local ENR"
-            );
-            update_local_ip(
-                &mut self.local_node,
-                &mut self.local_node_record,
-                &self.signer,
-                winning_ip,
-            );
+        if let Some(discv5) = &mut self.discv5 {
+            discv5.cleanup_stale_entries();
+        }
+        if let Some(winning_ip) = self.ip_votes.expire_round() {
+            self.apply_voted_ip(winning_ip);
         }
         Ok(())
     }
 
+    /// Announces the external IP that won a round of PONG voting, unless it's already the
+    /// announced one or the external IP was fixed with `--nat extip:<ip>`.
+    pub(crate) fn apply_voted_ip(&mut self, winning_ip: IpAddr) {
+        // Votes from peers of the other address family can't replace the primary endpoint
+        if self.local_node.has_ip(winning_ip)
+            || winning_ip.is_ipv6() != self.local_node.ip.is_ipv6()
+            || matches!(self.config.nat, NatMode::ExtIp(_))
+        {
+            return;
+        }
+        info!(
+            old_ip = %self.local_node.ip,
+            new_ip = %winning_ip,
+            "External IP detected via PONG voting, updating local ENR"
+        );
+        let (udp_port, tcp_port) = (self.local_node.udp_port, self.local_node.tcp_port);
+        update_local_endpoint(
+            &mut self.local_node,
+            &mut self.local_node_record,
+            &self.signer,
+            winning_ip,
+            udp_port,
+            tcp_port,
+        );
+    }
+
+    /// Returns the UDP address to reach `node` at from our socket, picking the node's
+    /// endpoint of the address family the socket is bound to.
+    pub(crate) fn udp_addr_of(&self, node: &Node) -> SocketAddr {
+        self.udp_socket
+            .local_addr()
+            .ok()
+            .and_then(|local| node.udp_addr_from(local.ip()))
+            .unwrap_or_else(|| node.udp_addr())
+    }
+
+    /// Returns `addr` as our socket can send to it: dual-stack sockets reach IPv4 peers at
+    /// their IPv4-mapped IPv6 address.
+    pub(crate) fn socket_addr_for(&self, addr: SocketAddr) -> SocketAddr {
+        match (self.udp_socket.local_addr(), addr.ip()) {
+            (Ok(SocketAddr::V6(_)), IpAddr::V4(ip)) => {
+                SocketAddr::new(IpAddr::V6(ip.to_ipv6_mapped()), addr.port())
+            }
+            _ => addr,
+        }
+    }
+
     pub(crate) async fn get_lookup_interval(&self) -> Duration {
         let peer_completion = self
             .peer_table
@@ -458,9 +559,12 @@ impl DiscoveryServer {
                 discv4_enabled: false,
                 discv5_enabled: true,
                 initial_lookup_interval: 1000.0,
+                dns_trees: Vec::new(),
+                nat: NatMode::None,
             },
             discv4: None,
             discv5: Some(Discv5State::default()),
+            ip_votes: IpVotes::default(),
         }
     }
 }
@@ -53,6 +53,12 @@ pub struct Metrics {
     pub disconnections_by_client_type: Arc<Mutex<BTreeMap<String, BTreeMap<String, u64>>>>,
     /// RLPx connection attempt failures grouped and counted by reason
     pub connection_attempt_failures: Arc<Mutex<BTreeMap<String, u64>>>,
+    /// Total serving cost of the requests served to peers.
+    pub served_request_cost: IntCounter,
+    /// Requests and gossip rejected because a peer exceeded its rate limit.
+    pub rate_limited_requests: IntCounter,
+    /// Peers disconnected for consuming far more than they contribute.
+    pub leecher_disconnections: IntCounter,
 
     /* Snap Sync */
     // Common
@@ -302,6 +308,37 @@ impl Metrics {
             .and_modify(|count| *count -= 1);
     }
 
+    pub fn record_served_request(&self, family: &str, cost: u64) {
+        self.served_request_cost.inc_by(cost);
+        #[cfg(feature = "metrics")]
+        {
+            use ethrex_metrics::p2p::METRICS_P2P;
+            METRICS_P2P.inc_served_request_cost(family, cost);
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = family;
+    }
+
+    pub fn record_rate_limited_request(&self, family: &str) {
+        self.rate_limited_requests.inc();
+        #[cfg(feature = "metrics")]
+        {
+            use ethrex_metrics::p2p::METRICS_P2P;
+            METRICS_P2P.inc_rate_limited_request(family);
+        }
+        #[cfg(not(feature = "metrics"))]
+        let _ = family;
+    }
+
+    pub fn record_leecher_disconnection(&self) {
+        self.leecher_disconnections.inc();
+        #[cfg(feature = "metrics")]
+        {
+            use ethrex_metrics::p2p::METRICS_P2P;
+            METRICS_P2P.inc_leecher_disconnection();
+        }
+    }
+
     pub async fn record_new_rlpx_conn_failure(&self, reason: PeerConnectionError) {
         let mut failures_grouped_by_reason = self.connection_attempt_failures.lock().await;
 
@@ -646,6 +683,36 @@ impl Default for Metrics {
             .register(Box::new(pings_sent_rate.clone()))
             .expect("Failed to register pings_sent_rate gauge");
 
+        let served_request_cost = IntCounter::new(
+            "rlpx_served_request_cost",
+            "Total serving cost of the requests served to peers",
+        )
+        .expect("Failed to create served_request_cost counter");
+
+        let rate_limited_requests = IntCounter::new(
+            "rlpx_rate_limited_requests",
+            "Total number of peer requests rejected by the rate limiter",
+        )
+        .expect("Failed to create rate_limited_requests counter");
+
+        let leecher_disconnections = IntCounter::new(
+            "rlpx_leecher_disconnections",
+            "Total number of peers disconnected for leeching",
+        )
+        .expect("Failed to create leecher_disconnections counter");
+
+        registry
+            .register(Box::new(served_request_cost.clone()))
+            .expect("Failed to register served_request_cost counter");
+
+        registry
+            .register(Box::new(rate_limited_requests.clone()))
+            .expect("Failed to register rate_limited_requests counter");
+
+        registry
+            .register(Box::new(leecher_disconnections.clone()))
+            .expect("Failed to register leecher_disconnections counter");
+
         let storage_leaves_inserted = IntCounter::new(
             "storage_leaves_inserted",
             "Total number of storage leaves inserted",
@@ -706,6 +773,10 @@ impl Default for Metrics {
 
             connection_attempt_failures: Arc::new(Mutex::new(BTreeMap::new())),
 
+            served_request_cost,
+            rate_limited_requests,
+            leecher_disconnections,
+
             /* Snap Sync */
             // Common
             sync_head_block: AtomicU64::new(0),
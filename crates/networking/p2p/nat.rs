@@ -0,0 +1,696 @@
+//! NAT traversal
+//!
+//! Maps the RLPx TCP port and the discovery UDP port on the local gateway, using UPnP IGD
+//! or NAT-PMP (RFC 6886), and reports the external endpoint the gateway assigned so it can
+//! be announced in the local ENR. Mappings are requested with a limited lease, which is
+//! refreshed before it expires; if the node stops, the gateway drops them on its own.
+//!
+//! NAT-PMP needs the address of the default gateway, which is only looked up on Linux
+//! (from `/proc/net/route`). On other platforms only UPnP can map ports.
+
+use std::{
+    fmt::Display,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    str::FromStr,
+    time::Duration,
+};
+
+use futures::{Stream, stream};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpStream, UdpSocket},
+    time::{sleep, timeout},
+};
+use tracing::{debug, warn};
+
+/// Lease requested for each port mapping.
+const MAPPING_LIFETIME: Duration = Duration::from_secs(20 * 60);
+/// Mappings are refreshed (and failed attempts retried) this often, well before the lease
+/// expires.
+const MAPPING_REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Time to wait for a gateway to answer a single request.
+const GATEWAY_TIMEOUT: Duration = Duration::from_secs(3);
+/// Largest HTTP response read from a gateway. Device descriptions and SOAP responses are
+/// a few KiB at most.
+const MAX_HTTP_RESPONSE_SIZE: u64 = 128 * 1024;
+/// Description attached to UPnP port mappings.
+const MAPPING_DESCRIPTION: &str = "ethrex";
+
+const NAT_PMP_PORT: u16 = 5351;
+/// NAT-PMP requests are retransmitted with a doubling timeout, starting at this value.
+const NAT_PMP_INITIAL_TIMEOUT: Duration = Duration::from_millis(250);
+const NAT_PMP_ATTEMPTS: u32 = 4;
+
+const SSDP_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(239, 255, 255, 250)), 1900);
+const IGD_SEARCH_TARGET: &str = "urn:schemas-upnp-org:device:InternetGatewayDevice:1";
+/// WAN services able to map ports, in order of preference.
+const WAN_SERVICES: [&str; 3] = [
+    "urn:schemas-upnp-org:service:WANIPConnection:2",
+    "urn:schemas-upnp-org:service:WANIPConnection:1",
+    "urn:schemas-upnp-org:service:WANPPPConnection:1",
+];
+
+/// How the node makes itself reachable from behind a NAT, set with `--nat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NatMode {
+    /// Try UPnP first and fall back to NAT-PMP.
+    Any,
+    /// Map ports with UPnP IGD.
+    Upnp,
+    /// Map ports with NAT-PMP.
+    Pmp,
+    /// Announce a fixed external IP without mapping any port.
+    ExtIp(IpAddr),
+    /// No NAT traversal.
+    #[default]
+    None,
+}
+
+impl NatMode {
+    /// Returns the fixed external IP of `extip:<ip>`.
+    pub fn external_ip(&self) -> Option<IpAddr> {
+        match self {
+            NatMode::ExtIp(ip) => Some(*ip),
+            _ => None,
+        }
+    }
+
+    /// Whether ports are mapped on the gateway.
+    pub fn maps_ports(&self) -> bool {
+        matches!(self, NatMode::Any | NatMode::Upnp | NatMode::Pmp)
+    }
+}
+
+impl Display for NatMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NatMode::Any => write!(f, "any"),
+            NatMode::Upnp => write!(f, "upnp"),
+            NatMode::Pmp => write!(f, "pmp"),
+            NatMode::ExtIp(ip) => write!(f, "extip:{ip}"),
+            NatMode::None => write!(f, "none"),
+        }
+    }
+}
+
+impl FromStr for NatMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "any" => Ok(NatMode::Any),
+            "upnp" => Ok(NatMode::Upnp),
+            "pmp" | "natpmp" => Ok(NatMode::Pmp),
+            "none" => Ok(NatMode::None),
+            mode => match mode.strip_prefix("extip:") {
+                Some(ip) => ip
+                    .parse()
+                    .map(NatMode::ExtIp)
+                    .map_err(|err| format!("Invalid external IP {ip:?}: {err}")),
+                None => Err(format!(
+                    "Invalid NAT mode '{s}'. Expected: any, upnp, pmp, extip:<IP> or none"
+                )),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum NatError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("No gateway answered in time")]
+    Timeout,
+    #[error("No default gateway found")]
+    NoGateway,
+    #[error("Finding the default gateway is only supported on Linux")]
+    GatewayLookupUnsupported,
+    #[error("Gateway has no WAN connection service")]
+    NoWanService,
+    #[error("Invalid gateway response: {0}")]
+    InvalidResponse(String),
+    #[error("Gateway refused the request: {0}")]
+    Refused(String),
+}
+
+/// Endpoint at which peers reach the node through the gateway.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExternalEndpoint {
+    pub ip: IpAddr,
+    pub tcp_port: u16,
+    pub udp_port: u16,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Protocol {
+    Tcp,
+    Udp,
+}
+
+impl Protocol {
+    fn upnp_name(&self) -> &'static str {
+        match self {
+            Protocol::Tcp => "TCP",
+            Protocol::Udp => "UDP",
+        }
+    }
+
+    fn nat_pmp_opcode(&self) -> u8 {
+        match self {
+            Protocol::Udp => 1,
+            Protocol::Tcp => 2,
+        }
+    }
+}
+
+/// Maps `tcp_port` and `udp_port` on the gateway according to `mode` and keeps refreshing
+/// the mappings. Yields the external endpoint after every successful refresh, and nothing
+/// for modes that don't map ports.
+pub fn port_mappings(
+    mode: NatMode,
+    tcp_port: u16,
+    udp_port: u16,
+) -> impl Stream<Item = ExternalEndpoint> {
+    stream::unfold(true, move |first| async move {
+        if !mode.maps_ports() {
+            return None;
+        }
+        let mut wait = !first;
+        loop {
+            if wait {
+                sleep(MAPPING_REFRESH_INTERVAL).await;
+            }
+            wait = true;
+            match map_ports(mode, tcp_port, udp_port).await {
+                Ok(endpoint) => {
+                    debug!(%mode, ?endpoint, "Mapped P2P ports on the gateway");
+                    return Some((endpoint, false));
+                }
+                Err(err) => warn!(%mode, %err, "Failed to map P2P ports on the gateway"),
+            }
+        }
+    })
+}
+
+async fn map_ports(
+    mode: NatMode,
+    tcp_port: u16,
+    udp_port: u16,
+) -> Result<ExternalEndpoint, NatError> {
+    match mode {
+        NatMode::Upnp => upnp_map_ports(tcp_port, udp_port).await,
+        NatMode::Pmp => nat_pmp_map_ports(tcp_port, udp_port).await,
+        NatMode::Any => match upnp_map_ports(tcp_port, udp_port).await {
+            Ok(endpoint) => Ok(endpoint),
+            Err(err) => {
+                debug!(%err, "UPnP port mapping failed, trying NAT-PMP");
+                nat_pmp_map_ports(tcp_port, udp_port).await
+            }
+        },
+        NatMode::ExtIp(_) | NatMode::None => Err(NatError::NoGateway),
+    }
+}
+
+// --- NAT-PMP ---
+
+async fn nat_pmp_map_ports(tcp_port: u16, udp_port: u16) -> Result<ExternalEndpoint, NatError> {
+    let gateway = default_gateway()?;
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    socket.connect((gateway, NAT_PMP_PORT)).await?;
+
+    // Opcode 0: external address request
+    let response = nat_pmp_request(&socket, &[0, 0], 12).await?;
+    let ip = Ipv4Addr::new(response[8], response[9], response[10], response[11]);
+    let tcp_port = nat_pmp_map_port(&socket, Protocol::Tcp, tcp_port).await?;
+    let udp_port = nat_pmp_map_port(&socket, Protocol::Udp, udp_port).await?;
+    Ok(ExternalEndpoint {
+        ip: IpAddr::V4(ip),
+        tcp_port,
+        udp_port,
+    })
+}
+
+/// Requests a mapping of `port` to the same external port and returns the external port
+/// the gateway assigned, which may differ.
+async fn nat_pmp_map_port(
+    socket: &UdpSocket,
+    protocol: Protocol,
+    port: u16,
+) -> Result<u16, NatError> {
+    let mut request = [0u8; 12];
+    request[1] = protocol.nat_pmp_opcode();
+    request[4..6].copy_from_slice(&port.to_be_bytes());
+    request[6..8].copy_from_slice(&port.to_be_bytes());
+    request[8..12].copy_from_slice(&(MAPPING_LIFETIME.as_secs() as u32).to_be_bytes());
+    let response = nat_pmp_request(socket, &request, 16).await?;
+    Ok(u16::from_be_bytes([response[10], response[11]]))
+}
+
+/// Sends a NAT-PMP request, retransmitting it until the gateway answers, and checks the
+/// response header.
+async fn nat_pmp_request(
+    socket: &UdpSocket,
+    request: &[u8],
+    response_len: usize,
+) -> Result<Vec<u8>, NatError> {
+    let mut wait = NAT_PMP_INITIAL_TIMEOUT;
+    let mut buf = [0u8; 16];
+    for _ in 0..NAT_PMP_ATTEMPTS {
+        socket.send(request).await?;
+        if let Ok(read) = timeout(wait, socket.recv(&mut buf)).await {
+            let read = read?;
+            if read < response_len || buf[0] != 0 || buf[1] != (request[1] | 0x80) {
+                return Err(NatError::InvalidResponse(format!(
+                    "unexpected NAT-PMP response {}",
+                    hex::encode(&buf[..read])
+                )));
+            }
+            let result = u16::from_be_bytes([buf[2], buf[3]]);
+            if result != 0 {
+                return Err(NatError::Refused(format!("NAT-PMP result code {result}")));
+            }
+            return Ok(buf[..read].to_vec());
+        }
+        wait *= 2;
+    }
+    Err(NatError::Timeout)
+}
+
+/// Reads the IPv4 default gateway from the kernel routing table.
+#[cfg(target_os = "linux")]
+fn default_gateway() -> Result<Ipv4Addr, NatError> {
+    let routes = std::fs::read_to_string("/proc/net/route").map_err(|_| NatError::NoGateway)?;
+    parse_default_gateway(&routes).ok_or(NatError::NoGateway)
+}
+
+/// The routing table is only read on Linux, NAT-PMP isn't available elsewhere.
+#[cfg(not(target_os = "linux"))]
+fn default_gateway() -> Result<Ipv4Addr, NatError> {
+    Err(NatError::GatewayLookupUnsupported)
+}
+
+/// Parses the default gateway out of the contents of `/proc/net/route`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_default_gateway(routes: &str) -> Option<Ipv4Addr> {
+    const RTF_GATEWAY: u32 = 0x2;
+    routes.lines().skip(1).find_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (destination, gateway, flags) = (fields.get(1)?, fields.get(2)?, fields.get(3)?);
+        let flags = u32::from_str_radix(flags, 16).ok()?;
+        if *destination != "00000000" || flags & RTF_GATEWAY == 0 {
+            return None;
+        }
+        // Addresses are printed as the host-endian value of the network-order bytes
+        let gateway = u32::from_str_radix(gateway, 16).ok()?;
+        Some(Ipv4Addr::from(gateway.to_le_bytes()))
+    })
+}
+
+// --- UPnP IGD ---
+
+/// WAN connection service of an Internet Gateway Device.
+struct Igd {
+    control_url: HttpUrl,
+    service_type: &'static str,
+    /// Address of this host on the gateway's network.
+    local_ip: IpAddr,
+}
+
+async fn upnp_map_ports(tcp_port: u16, udp_port: u16) -> Result<ExternalEndpoint, NatError> {
+    let igd = upnp_discover().await?;
+    let response = igd.soap_request("GetExternalIPAddress", "").await?;
+    let ip = xml_element(&response, "NewExternalIPAddress")
+        .and_then(|ip| ip.trim().parse().ok())
+        .ok_or_else(|| NatError::InvalidResponse("missing external IP address".to_string()))?;
+    igd.add_port_mapping(Protocol::Tcp, tcp_port).await?;
+    igd.add_port_mapping(Protocol::Udp, udp_port).await?;
+    Ok(ExternalEndpoint {
+        ip,
+        tcp_port,
+        udp_port,
+    })
+}
+
+/// Finds the gateway with an SSDP search and reads its WAN connection service from the
+/// device description.
+async fn upnp_discover() -> Result<Igd, NatError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+    let search = format!(
+        "M-SEARCH * HTTP/1.1\r\nHOST: {SSDP_ADDR}\r\nST: {IGD_SEARCH_TARGET}\r\n\
+         MAN: \"ssdp:discover\"\r\nMX: 2\r\n\r\n"
+    );
+    socket.send_to(search.as_bytes(), SSDP_ADDR).await?;
+
+    let mut buf = [0u8; 2048];
+    let location = timeout(GATEWAY_TIMEOUT, async {
+        loop {
+            let (read, _) = socket.recv_from(&mut buf).await?;
+            let response = String::from_utf8_lossy(&buf[..read]);
+            if let Some(location) = igd_location(&response) {
+                return Ok::<_, NatError>(location.to_string());
+            }
+        }
+    })
+    .await
+    .map_err(|_| NatError::Timeout)??;
+
+    let location: HttpUrl = location.parse()?;
+    let (description, local_ip) = http_request(&location, "GET", &[], "").await?;
+    let (service_type, control_url) = WAN_SERVICES
+        .iter()
+        .find_map(|service_type| {
+            xml_elements(&description, "service")
+                .find(|service| {
+                    xml_element(service, "serviceType")
+                        .is_some_and(|found| found.trim() == *service_type)
+                })
+                .and_then(|service| xml_element(service, "controlURL"))
+                .map(|control_url| (*service_type, control_url.trim().to_string()))
+        })
+        .ok_or(NatError::NoWanService)?;
+
+    Ok(Igd {
+        control_url: location.join(&control_url)?,
+        service_type,
+        local_ip,
+    })
+}
+
+/// Returns the device description URL of an SSDP search response, if it comes from an
+/// Internet Gateway Device. Other devices may answer a search for a different target.
+fn igd_location(response: &str) -> Option<&str> {
+    let search_target = http_header(response, "st")?;
+    if !search_target.eq_ignore_ascii_case(IGD_SEARCH_TARGET) {
+        return None;
+    }
+    http_header(response, "location")
+}
+
+impl Igd {
+    async fn add_port_mapping(&self, protocol: Protocol, port: u16) -> Result<(), NatError> {
+        let arguments = format!(
+            "<NewRemoteHost></NewRemoteHost>\
+             <NewExternalPort>{port}</NewExternalPort>\
+             <NewProtocol>{}</NewProtocol>\
+             <NewInternalPort>{port}</NewInternalPort>\
+             <NewInternalClient>{}</NewInternalClient>\
+             <NewEnabled>1</NewEnabled>\
+             <NewPortMappingDescription>{MAPPING_DESCRIPTION}</NewPortMappingDescription>\
+             <NewLeaseDuration>{}</NewLeaseDuration>",
+            protocol.upnp_name(),
+            self.local_ip,
+            MAPPING_LIFETIME.as_secs(),
+        );
+        self.soap_request("AddPortMapping", &arguments).await?;
+        Ok(())
+    }
+
+    /// Invokes `action` on the WAN connection service and returns the response body.
+    async fn soap_request(&self, action: &str, arguments: &str) -> Result<String, NatError> {
+        let service_type = self.service_type;
+        let body = format!(
+            "<?xml version=\"1.0\"?>\
+             <s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" \
+             s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\">\
+             <s:Body><u:{action} xmlns:u=\"{service_type}\">{arguments}</u:{action}></s:Body>\
+             </s:Envelope>"
+        );
+        let soap_action = format!("\"{service_type}#{action}\"");
+        let headers = [
+            ("Content-Type", "text/xml; charset=\"utf-8\""),
+            ("SOAPAction", soap_action.as_str()),
+        ];
+        let (response, _) = http_request(&self.control_url, "POST", &headers, &body).await?;
+        Ok(response)
+    }
+}
+
+/// `http://` URL, the only kind UPnP devices use on the local network.
+#[derive(Debug, Clone, PartialEq)]
+struct HttpUrl {
+    host: String,
+    port: u16,
+    path: String,
+}
+
+impl FromStr for HttpUrl {
+    type Err = NatError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        let invalid = || NatError::InvalidResponse(format!("invalid URL {url:?}"));
+        let rest = url.trim().strip_prefix("http://").ok_or_else(invalid)?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, "/"),
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| invalid())?),
+            None => (authority, 80),
+        };
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            path: path.to_string(),
+        })
+    }
+}
+
+impl HttpUrl {
+    /// Resolves a URL found in a document fetched from `self`.
+    fn join(&self, url: &str) -> Result<Self, NatError> {
+        if url.starts_with("http://") {
+            return url.parse();
+        }
+        let path = if url.starts_with('/') {
+            url.to_string()
+        } else {
+            format!("/{url}")
+        };
+        Ok(Self {
+            path,
+            ..self.clone()
+        })
+    }
+}
+
+/// Sends an HTTP/1.0 request, so the response is never chunked and ends when the gateway
+/// closes the connection. Returns the body of a successful response and the local address
+/// of the connection. Responses over [`MAX_HTTP_RESPONSE_SIZE`] are rejected.
+async fn http_request(
+    url: &HttpUrl,
+    method: &str,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> Result<(String, IpAddr), NatError> {
+    timeout(GATEWAY_TIMEOUT, async {
+        let mut stream = TcpStream::connect((url.host.as_str(), url.port)).await?;
+        let local_ip = stream.local_addr()?.ip();
+
+        let mut request = format!(
+            "{method} {} HTTP/1.0\r\nHost: {}:{}\r\nContent-Length: {}\r\n",
+            url.path,
+            url.host,
+            url.port,
+            body.len()
+        );
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+        request.push_str(body);
+        stream.write_all(request.as_bytes()).await?;
+
+        let mut response = Vec::new();
+        stream
+            .take(MAX_HTTP_RESPONSE_SIZE + 1)
+            .read_to_end(&mut response)
+            .await?;
+        if response.len() as u64 > MAX_HTTP_RESPONSE_SIZE {
+            return Err(NatError::InvalidResponse(
+                "HTTP response too large".to_string(),
+            ));
+        }
+        let response = String::from_utf8_lossy(&response);
+        let (head, body) = response
+            .split_once("\r\n\r\n")
+            .ok_or_else(|| NatError::InvalidResponse("truncated HTTP response".to_string()))?;
+        let status = head.split_whitespace().nth(1).unwrap_or_default();
+        if status != "200" {
+            let reason = xml_element(body, "errorDescription")
+                .or_else(|| xml_element(body, "faultstring"))
+                .unwrap_or(status);
+            return Err(NatError::Refused(reason.trim().to_string()));
+        }
+        Ok((body.to_string(), local_ip))
+    })
+    .await
+    .map_err(|_| NatError::Timeout)?
+}
+
+/// Returns the value of header `name` (lowercase) of an HTTP message.
+fn http_header<'a>(message: &'a str, name: &str) -> Option<&'a str> {
+    message.lines().skip(1).find_map(|line| {
+        let (header, value) = line.split_once(':')?;
+        header
+            .trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+/// Returns the contents of every `tag` element in `xml`, ignoring namespace prefixes.
+/// Device descriptions and SOAP responses are simple enough not to need a real parser.
+fn xml_elements<'a>(xml: &'a str, tag: &str) -> impl Iterator<Item = &'a str> {
+    let mut rest = xml;
+    let tag = tag.to_string();
+    std::iter::from_fn(move || {
+        loop {
+            let start = rest.find('<')?;
+            rest = &rest[start + 1..];
+            let end = rest.find('>')?;
+            let name = rest[..end].split_whitespace().next().unwrap_or_default();
+            let local_name = name.rsplit(':').next().unwrap_or_default();
+            rest = &rest[end + 1..];
+            if local_name != tag {
+                continue;
+            }
+            let closing = format!("</{name}>");
+            let content_end = rest.find(&closing)?;
+            let content = &rest[..content_end];
+            rest = &rest[content_end + closing.len()..];
+            return Some(content);
+        }
+    })
+}
+
+fn xml_element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    xml_elements(xml, tag).next()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nat_mode_round_trip() {
+        for mode in [
+            NatMode::Any,
+            NatMode::Upnp,
+            NatMode::Pmp,
+            NatMode::ExtIp("203.0.113.7".parse().unwrap()),
+            NatMode::ExtIp("2001:db8::1".parse().unwrap()),
+            NatMode::None,
+        ] {
+            assert_eq!(mode.to_string().parse::<NatMode>(), Ok(mode));
+        }
+        assert!("extip:not-an-ip".parse::<NatMode>().is_err());
+        assert!("stun".parse::<NatMode>().is_err());
+    }
+
+    #[test]
+    fn parses_default_gateway_from_route_table() {
+        let routes = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                      eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\n\
+                      eth0\t00000000\t0101A8C0\t0003\t0\t0\t0\t00000000\n";
+        assert_eq!(
+            parse_default_gateway(routes),
+            Some(Ipv4Addr::new(192, 168, 1, 1))
+        );
+        let no_default_route = "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\n\
+                                eth0\t0000A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\n";
+        assert_eq!(parse_default_gateway(no_default_route), None);
+    }
+
+    #[test]
+    fn finds_wan_service_in_device_description() {
+        let description = "<root><device><serviceList>\
+            <service><serviceType>urn:schemas-upnp-org:service:Layer3Forwarding:1</serviceType>\
+            <controlURL>/l3f</controlURL></service>\
+            <service><serviceType>urn:schemas-upnp-org:service:WANIPConnection:1</serviceType>\
+            <controlURL>/ctl/IPConn</controlURL></service>\
+            </serviceList></device></root>";
+        let control_url = xml_elements(description, "service")
+            .find(|service| xml_element(service, "serviceType") == Some(WAN_SERVICES[1]))
+            .and_then(|service| xml_element(service, "controlURL"));
+        assert_eq!(control_url, Some("/ctl/IPConn"));
+
+        let soap = "<s:Envelope><s:Body><u:GetExternalIPAddressResponse>\
+            <NewExternalIPAddress>203.0.113.7</NewExternalIPAddress>\
+            </u:GetExternalIPAddressResponse></s:Body></s:Envelope>";
+        assert_eq!(
+            xml_element(soap, "NewExternalIPAddress"),
+            Some("203.0.113.7")
+        );
+    }
+
+    #[test]
+    fn only_gateways_answering_the_igd_search_are_used() {
+        let gateway = "HTTP/1.1 200 OK\r\nCACHE-CONTROL: max-age=120\r\n\
+                       ST: urn:schemas-upnp-org:device:InternetGatewayDevice:1\r\n\
+                       LOCATION: http://192.168.1.1:5000/rootDesc.xml\r\n\r\n";
+        assert_eq!(
+            igd_location(gateway),
+            Some("http://192.168.1.1:5000/rootDesc.xml")
+        );
+
+        let renderer = "HTTP/1.1 200 OK\r\n\
+                        ST: urn:schemas-upnp-org:device:MediaRenderer:1\r\n\
+                        LOCATION: http://192.168.1.20:8080/desc.xml\r\n\r\n";
+        assert_eq!(igd_location(renderer), None);
+
+        let no_search_target = "HTTP/1.1 200 OK\r\n\
+                                LOCATION: http://192.168.1.20:8080/desc.xml\r\n\r\n";
+        assert_eq!(igd_location(no_search_target), None);
+    }
+
+    /// Serves a single HTTP response with a `body_size` bytes body.
+    async fn serve_response(body_size: usize) -> HttpUrl {
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await;
+            let response = format!("HTTP/1.0 200 OK\r\n\r\n{}", "a".repeat(body_size));
+            let _ = stream.write_all(response.as_bytes()).await;
+        });
+        HttpUrl {
+            host: Ipv4Addr::LOCALHOST.to_string(),
+            port,
+            path: "/rootDesc.xml".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn http_responses_are_size_capped() {
+        let url = serve_response(1024).await;
+        let (body, _) = http_request(&url, "GET", &[], "").await.unwrap();
+        assert_eq!(body.len(), 1024);
+
+        let url = serve_response(MAX_HTTP_RESPONSE_SIZE as usize).await;
+        assert!(matches!(
+            http_request(&url, "GET", &[], "").await,
+            Err(NatError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn resolves_control_url_against_location() {
+        let location: HttpUrl = "http://192.168.1.1:5000/rootDesc.xml".parse().unwrap();
+        assert_eq!(
+            location.join("/ctl/IPConn").unwrap(),
+            HttpUrl {
+                host: "192.168.1.1".to_string(),
+                port: 5000,
+                path: "/ctl/IPConn".to_string(),
+            }
+        );
+        assert_eq!(
+            location.join("http://192.168.1.1:6000/ctl").unwrap().port,
+            6000
+        );
+    }
+}
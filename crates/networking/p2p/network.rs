@@ -4,7 +4,10 @@ use crate::rlpx::l2::l2_connection::P2PBasedContext;
 #[derive(Clone, Debug)]
 pub struct P2PBasedContext;
 use crate::{
-    discovery::{DiscoveryConfig, DiscoveryServer, DiscoveryServerError},
+    discovery::{
+        DiscoveryConfig, DiscoveryServer, DiscoveryServerError,
+        dns::{UdpDnsResolver, run_dns_discovery},
+    },
     metrics::{CurrentStepValue, METRICS},
     peer_table::{PeerData, PeerTable, PeerTableServerProtocol as _},
     rlpx::{
@@ -19,6 +22,7 @@ use ethrex_blockchain::Blockchain;
 use ethrex_common::H256;
 use ethrex_storage::Store;
 use secp256k1::SecretKey;
+use socket2::{Domain, SockRef, Socket, Type};
 use spawned_concurrency::tasks::ActorRef;
 use std::{
     io,
@@ -116,11 +120,17 @@ pub async fn start_network(
     config: DiscoveryConfig,
 ) -> Result<(), NetworkError> {
     let udp_socket = Arc::new(
-        UdpSocket::bind(context.network_config.bind_udp_addr())
-            .await
-            .map_err(NetworkError::UdpSocketError)?,
+        udp_socket(context.network_config.bind_udp_addr()).map_err(NetworkError::UdpSocketError)?,
     );
 
+    if !config.dns_trees.is_empty() {
+        context.tracker.spawn(run_dns_discovery(
+            UdpDnsResolver::from_system_config(),
+            config.dns_trees.clone(),
+            context.table.clone(),
+        ));
+    }
+
     DiscoveryServer::spawn(
         context.storage.clone(),
         context.local_node.clone(),
@@ -162,6 +172,8 @@ pub(crate) async fn serve_p2p_requests(context: P2PContext) {
             }
         };
 
+        // Dual-stack listeners report IPv4 peers with IPv4-mapped addresses
+        let peer_addr = SocketAddr::new(peer_addr.ip().to_canonical(), peer_addr.port());
         if external_tcp_addr == peer_addr {
             // Ignore connections from self
             continue;
@@ -178,11 +190,33 @@ fn listener(tcp_addr: SocketAddr) -> Result<TcpListener, io::Error> {
     }?;
     tcp_socket.set_reuseport(true).ok();
     tcp_socket.set_reuseaddr(true).ok();
+    if is_dual_stack(tcp_addr) {
+        SockRef::from(&tcp_socket).set_only_v6(false)?;
+    }
     tcp_socket.bind(tcp_addr)?;
 
     tcp_socket.listen(50)
 }
 
+fn udp_socket(udp_addr: SocketAddr) -> Result<UdpSocket, io::Error> {
+    let socket = Socket::new(Domain::for_address(udp_addr), Type::DGRAM, None)?;
+    if is_dual_stack(udp_addr) {
+        socket.set_only_v6(false)?;
+    }
+    socket.set_nonblocking(true)?;
+    socket.bind(&udp_addr.into())?;
+    UdpSocket::from_std(socket.into())
+}
+
+/// Sockets bound to the unspecified IPv6 address `::` also accept IPv4 traffic, regardless
+/// of the system's default.
+fn is_dual_stack(addr: SocketAddr) -> bool {
+    match addr {
+        SocketAddr::V6(addr) => addr.ip().is_unspecified(),
+        SocketAddr::V4(_) => false,
+    }
+}
+
 pub async fn periodically_show_peer_stats(blockchain: Arc<Blockchain>, peer_table: PeerTable) {
     periodically_show_peer_stats_during_syncing(blockchain, &peer_table).await;
     periodically_show_peer_stats_after_sync(&peer_table).await;
@@ -67,6 +67,7 @@ pub mod discovery;
 pub mod discv4;
 pub mod discv5;
 pub(crate) mod metrics;
+pub mod nat;
 pub mod network;
 pub mod peer_handler;
 pub mod peer_table;
@@ -13,6 +13,7 @@ use crate::{
                 BLOCK_HEADER_LIMIT, BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders,
                 HashOrNumber,
             },
+            receipts::{GetReceipts69, Receipts68, Receipts69, Receipts70},
         },
         message::Message as RLPxMessage,
         p2p::{Capability, SUPPORTED_ETH_CAPABILITIES},
@@ -20,7 +21,11 @@ use crate::{
 };
 use ethrex_common::{
     H256,
-    types::{BlockBody, BlockHeader, block_access_list::BlockAccessList, validate_block_body},
+    constants::EMPTY_TRIE_HASH,
+    types::{
+        BlockBody, BlockHeader, Receipt, block_access_list::BlockAccessList, compute_receipts_root,
+        validate_block_body,
+    },
 };
 use ethrex_crypto::NativeCrypto;
 use spawned_concurrency::{error::ActorError, tasks::ActorRef};
@@ -397,12 +402,12 @@ impl PeerHandler {
         Ok(Some(ret))
     }
 
-    /// Requests block headers from any suitable peer, starting from the `start` block hash towards either older or newer blocks depending on the order
+    /// Requests block headers from any suitable peer, starting from the `start` block hash (or number) towards either older or newer blocks depending on the order
     /// - No peer returned a valid response in the given time and retry limits
     ///   Since request_block_headers brought problems in cases of reorg seen in this pr https://github.com/lambdaclass/ethrex/pull/4028, we have this other function to request block headers only for full sync.
     pub async fn request_block_headers_from_hash(
         &mut self,
-        start: H256,
+        start: impl Into<HashOrNumber>,
         order: BlockRequestOrder,
     ) -> Result<Option<Vec<BlockHeader>>, PeerHandlerError> {
         let request_id = rand::random();
@@ -450,6 +455,48 @@ impl PeerHandler {
         }
     }
 
+    /// Requests the header identified by `block` from a random peer.
+    /// Returns `None` when the peer doesn't have it, or when it answers with a header that
+    /// doesn't match the requested hash or number, in which case the peer is penalized.
+    pub async fn request_block_header(
+        &mut self,
+        block: HashOrNumber,
+    ) -> Result<Option<BlockHeader>, PeerHandlerError> {
+        let request = RLPxMessage::GetBlockHeaders(GetBlockHeaders {
+            id: rand::random(),
+            startblock: block,
+            limit: 1,
+            skip: 0,
+            reverse: false,
+        });
+        let Some((peer_id, mut connection, permit)) =
+            self.get_random_peer(&SUPPORTED_ETH_CAPABILITIES).await?
+        else {
+            return Ok(None);
+        };
+        let response = connection
+            .outgoing_request(request, PEER_REPLY_TIMEOUT)
+            .await;
+        drop(permit);
+        let Ok(RLPxMessage::BlockHeaders(BlockHeaders { block_headers, .. })) = response else {
+            debug!("Didn't receive block header from peer, penalizing peer {peer_id}");
+            self.peer_table.record_failure(peer_id)?;
+            return Ok(None);
+        };
+        let Some(header) = block_headers.into_iter().next() else {
+            debug!("Received empty headers from peer {peer_id}, trying another");
+            let _ = self.peer_table.set_disposable(peer_id);
+            return Ok(None);
+        };
+        if !block.identifies(&header) {
+            debug!("Received a header other than the requested one, penalizing peer {peer_id}");
+            self.peer_table.record_failure(peer_id)?;
+            return Ok(None);
+        }
+        self.peer_table.record_success(peer_id)?;
+        Ok(Some(header))
+    }
+
     /// Given a peer id, a chunk start and a chunk limit, requests the block headers from the peer.
     /// Releases the peer slot as soon as the wire response is in; validation
     /// below is pure computation.
@@ -565,6 +612,90 @@ impl PeerHandler {
         Ok(None)
     }
 
+    /// Requests the receipts of the given blocks from any suitable peer and validates them
+    /// against the receipts roots of the block headers.
+    /// Returns the receipts of a prefix of the requested blocks or None if:
+    /// - There are no available peers (the node just started up or was rejected by all other nodes)
+    /// - No peer returned a valid response in the given time and retry limits
+    pub async fn request_receipts(
+        &mut self,
+        block_headers: &[BlockHeader],
+    ) -> Result<Option<Vec<Vec<Receipt>>>, PeerHandlerError> {
+        let block_hashes: Vec<H256> = block_headers.iter().map(|h| h.hash()).collect();
+
+        for _ in 0..REQUEST_RETRY_ATTEMPTS {
+            let request_id = rand::random();
+            // Sent in the eth/69 format, the connection translates it to the negotiated version
+            let request =
+                RLPxMessage::GetReceipts69(GetReceipts69::new(request_id, block_hashes.clone()));
+            let Some((peer_id, mut connection, permit)) =
+                self.get_random_peer(&SUPPORTED_ETH_CAPABILITIES).await?
+            else {
+                return Ok(None);
+            };
+            let response = connection
+                .outgoing_request(request, PEER_REPLY_TIMEOUT)
+                .await;
+            drop(permit);
+            let mut block_receipts = match response {
+                Ok(RLPxMessage::Receipts68(Receipts68 { id, receipts })) if id == request_id => {
+                    receipts
+                        .iter()
+                        .map(|receipts| receipts.iter().map(Receipt::from).collect())
+                        .collect()
+                }
+                Ok(RLPxMessage::Receipts69(Receipts69 { id, receipts })) if id == request_id => {
+                    receipts
+                }
+                Ok(RLPxMessage::Receipts70(Receipts70 {
+                    id,
+                    last_block_incomplete,
+                    mut receipts,
+                })) if id == request_id => {
+                    if last_block_incomplete {
+                        receipts.pop();
+                    }
+                    receipts
+                }
+                _ => {
+                    debug!("Didn't receive receipts from peer, penalizing peer {peer_id}");
+                    self.peer_table.record_failure(peer_id)?;
+                    continue;
+                }
+            };
+            // Peers that don't have the receipts of a block send an empty list for it
+            if let Some(missing) =
+                block_receipts
+                    .iter()
+                    .zip(block_headers)
+                    .position(|(receipts, header)| {
+                        receipts.is_empty() && header.receipts_root != *EMPTY_TRIE_HASH
+                    })
+            {
+                block_receipts.truncate(missing);
+            }
+            if block_receipts.is_empty() || block_receipts.len() > block_headers.len() {
+                debug!("Received no usable receipts from peer {peer_id}");
+                self.peer_table.record_failure(peer_id)?;
+                continue;
+            }
+            let valid = block_receipts
+                .iter()
+                .zip(block_headers)
+                .all(|(receipts, header)| {
+                    compute_receipts_root(receipts, &NativeCrypto) == header.receipts_root
+                });
+            if !valid {
+                debug!("Invalid receipts, discarding peer {peer_id} and retrying");
+                self.peer_table.record_critical_failure(peer_id)?;
+                continue;
+            }
+            self.peer_table.record_success(peer_id)?;
+            return Ok(Some(block_receipts));
+        }
+        Ok(None)
+    }
+
     /// Requests block access lists from a peer that supports eth/71.
     /// Returns a vector of optional BALs (one per requested block hash) or None if:
     /// - There are no available eth/71 peers
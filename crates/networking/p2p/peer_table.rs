@@ -18,6 +18,12 @@ use crate::{
 };
 use bytes::Bytes;
 use ethrex_common::{H256, U256};
+use ethrex_rlp::{
+    decode::RLPDecode,
+    encode::RLPEncode,
+    error::RLPDecodeError,
+    structs::{Decoder, Encoder},
+};
 use ethrex_storage::Store;
 use indexmap::IndexMap;
 use rand::distributions::WeightedIndex;
@@ -28,11 +34,14 @@ use spawned_concurrency::{
     actor,
     error::ActorError,
     protocol,
-    tasks::{Actor, ActorRef, ActorStart as _, Context, Handler, Response, send_message_on},
+    tasks::{
+        Actor, ActorRef, ActorStart as _, Context, Handler, Response, send_interval,
+        send_message_on,
+    },
 };
 use std::{
     net::IpAddr,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 const MAX_SCORE: i64 = 50;
@@ -64,6 +73,13 @@ const MAX_REPLACEMENTS_PER_BUCKET: usize = 10;
 /// structure allows (256 × 16 = 4,096 vs this larger capacity).
 /// 10K matches what Reth and Nethermind use for their candidate pools.
 const MAX_CONNECTION_POOL_SIZE: usize = 10_000;
+/// How often the peer table is written to the store, on top of the write on shutdown.
+const PERSIST_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// Persisted contacts that weren't seen for this long (in seconds) are dropped.
+const PERSISTED_CONTACT_MAX_AGE: u64 = 5 * 24 * 60 * 60;
+/// A contact's failure count drops by one for every this many seconds without a new
+/// failure, so peers that misbehaved once are eventually dialed again.
+const FAILURE_DECAY_INTERVAL: u64 = 10 * 60;
 
 /// A single k-bucket in the Kademlia routing table.
 /// Each bucket stores contacts at a specific XOR distance range from the local node.
@@ -165,6 +181,14 @@ pub(crate) fn xor_distance(a: &H256, b: &H256) -> H256 {
     *a ^ *b
 }
 
+/// Seconds since the UNIX epoch.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
 /// Identifies which discovery protocol was used to find a contact.
 /// This allows protocol-specific lookups to only query compatible contacts.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -209,6 +233,16 @@ pub struct Contact {
     pub unwanted: bool,
     /// Whether the last known fork ID is valid, None if unknown.
     pub is_fork_id_valid: Option<bool>,
+    /// Timestamp (seconds since UNIX epoch) of when the contact was discovered or last
+    /// answered us. Used to age out persisted contacts.
+    pub last_seen: u64,
+    /// Number of successful responses to our requests, across restarts.
+    pub successes: u64,
+    /// Number of failed or invalid responses to our requests, across restarts. Decays
+    /// over time, see [`Contact::recent_failures`].
+    pub failures: u64,
+    /// Timestamp (seconds since UNIX epoch) of the last failure, 0 if there was none.
+    pub last_failure: u64,
     /// Session information for discv5 (None for discv4 contacts)
     session: Option<Session>,
 }
@@ -245,6 +279,27 @@ impl Contact {
     pub fn has_pending_enr_request(&self) -> bool {
         self.enr_request_hash.is_some()
     }
+
+    /// Score earned in previous connections, used as the starting score of a new one.
+    pub fn reputation(&self) -> i64 {
+        (self.successes as i64)
+            .saturating_sub(self.recent_failures(unix_now()) as i64)
+            .clamp(MIN_SCORE, MAX_SCORE)
+    }
+
+    /// Failures left after decaying one per [`FAILURE_DECAY_INTERVAL`] elapsed since the
+    /// last failure.
+    pub fn recent_failures(&self, now: u64) -> u64 {
+        let decayed = now.saturating_sub(self.last_failure) / FAILURE_DECAY_INTERVAL;
+        self.failures.saturating_sub(decayed)
+    }
+
+    /// Records a failed or invalid response, on top of the failures that didn't decay yet.
+    fn record_failure(&mut self) {
+        let now = unix_now();
+        self.failures = self.recent_failures(now).saturating_add(1);
+        self.last_failure = now;
+    }
 }
 
 impl Contact {
@@ -261,6 +316,10 @@ impl Contact {
             knows_us: true,
             unwanted: false,
             is_fork_id_valid: None,
+            last_seen: unix_now(),
+            successes: 0,
+            failures: 0,
+            last_failure: 0,
             session: None,
         }
     }
@@ -282,6 +341,128 @@ impl Contact {
     }
 }
 
+/// Contact as persisted in the store, so that good peers are kept and known-bad ones
+/// aren't dialed again after a restart.
+#[derive(Debug, Clone)]
+struct PersistedContact {
+    node: Node,
+    is_discv4: bool,
+    is_discv5: bool,
+    validated: bool,
+    unwanted: bool,
+    is_fork_id_valid: Option<bool>,
+    last_seen: u64,
+    successes: u64,
+    failures: u64,
+    last_failure: u64,
+    record: Option<NodeRecord>,
+}
+
+impl From<&Contact> for PersistedContact {
+    fn from(contact: &Contact) -> Self {
+        Self {
+            node: contact.node.clone(),
+            is_discv4: contact.is_discv4,
+            is_discv5: contact.is_discv5,
+            validated: contact.was_validated(),
+            unwanted: contact.unwanted,
+            is_fork_id_valid: contact.is_fork_id_valid,
+            last_seen: contact.last_seen,
+            successes: contact.successes,
+            failures: contact.failures,
+            last_failure: contact.last_failure,
+            record: contact.record.clone(),
+        }
+    }
+}
+
+impl From<PersistedContact> for Contact {
+    fn from(persisted: PersistedContact) -> Self {
+        let mut contact = Contact::new(persisted.node, DiscoveryProtocol::Discv4);
+        contact.is_discv4 = persisted.is_discv4;
+        contact.is_discv5 = persisted.is_discv5;
+        // Validated contacts are trusted until the next revalidation
+        if persisted.validated {
+            contact.validation_timestamp = Some(Instant::now());
+        }
+        contact.unwanted = persisted.unwanted;
+        contact.is_fork_id_valid = persisted.is_fork_id_valid;
+        contact.last_seen = persisted.last_seen;
+        contact.successes = persisted.successes;
+        contact.failures = persisted.failures;
+        contact.last_failure = persisted.last_failure;
+        contact.record = persisted.record;
+        contact
+    }
+}
+
+impl RLPEncode for PersistedContact {
+    fn encode(&self, buf: &mut dyn bytes::BufMut) {
+        // 0 = unknown, 1 = valid, 2 = invalid
+        let is_fork_id_valid: u8 = match self.is_fork_id_valid {
+            None => 0,
+            Some(true) => 1,
+            Some(false) => 2,
+        };
+        Encoder::new(buf)
+            .encode_field(&self.node)
+            .encode_field(&self.is_discv4)
+            .encode_field(&self.is_discv5)
+            .encode_field(&self.validated)
+            .encode_field(&self.unwanted)
+            .encode_field(&is_fork_id_valid)
+            .encode_field(&self.last_seen)
+            .encode_field(&self.successes)
+            .encode_field(&self.failures)
+            .encode_field(&self.last_failure)
+            .encode_optional_field(&self.record)
+            .finish();
+    }
+}
+
+impl RLPDecode for PersistedContact {
+    fn decode_unfinished(rlp: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
+        let decoder = Decoder::new(rlp)?;
+        let (node, decoder) = decoder.decode_field("node")?;
+        let (is_discv4, decoder) = decoder.decode_field("is_discv4")?;
+        let (is_discv5, decoder) = decoder.decode_field("is_discv5")?;
+        let (validated, decoder) = decoder.decode_field("validated")?;
+        let (unwanted, decoder) = decoder.decode_field("unwanted")?;
+        let (is_fork_id_valid, decoder) = decoder.decode_field::<u8>("is_fork_id_valid")?;
+        let (last_seen, decoder) = decoder.decode_field("last_seen")?;
+        let (successes, decoder) = decoder.decode_field("successes")?;
+        let (failures, decoder) = decoder.decode_field("failures")?;
+        let (last_failure, decoder) = decoder.decode_field("last_failure")?;
+        let (record, decoder) = decoder.decode_optional_field();
+        let is_fork_id_valid = match is_fork_id_valid {
+            0 => None,
+            1 => Some(true),
+            2 => Some(false),
+            _ => {
+                return Err(RLPDecodeError::Custom(
+                    "Invalid fork id validity".to_string(),
+                ));
+            }
+        };
+        Ok((
+            Self {
+                node,
+                is_discv4,
+                is_discv5,
+                validated,
+                unwanted,
+                is_fork_id_valid,
+                last_seen,
+                successes,
+                failures,
+                last_failure,
+                record,
+            },
+            decoder.finish()?,
+        ))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PeerData {
     pub node: Node,
@@ -298,6 +479,10 @@ pub struct PeerData {
     requests: i64,
     /// Timestamp (seconds since UNIX epoch) of the last successful response from this peer
     pub last_response_time: Option<u64>,
+    /// Set to true if the peer is a static peer, which is always redialed when disconnected
+    pub is_static: bool,
+    /// Set to true if the peer is trusted, which exempts it from the peer limit and score penalties
+    pub is_trusted: bool,
 }
 
 impl PeerData {
@@ -316,8 +501,27 @@ impl PeerData {
             score: Default::default(),
             requests: Default::default(),
             last_response_time: None,
+            is_static: false,
+            is_trusted: false,
         }
     }
+
+    /// Lowers the score after a failed request. Trusted peers are never penalized below a
+    /// neutral score.
+    fn record_failure(&mut self) {
+        let min_score = if self.is_trusted { 0 } else { MIN_SCORE };
+        self.score = (self.score - 1).max(min_score);
+    }
+
+    /// Drops the score to the minimum after a critical failure, or to a neutral score for
+    /// trusted peers.
+    fn record_critical_failure(&mut self) {
+        self.score = if self.is_trusted {
+            self.score.min(0)
+        } else {
+            MIN_SCORE_CRITICAL
+        };
+    }
 }
 
 /// Diagnostic snapshot of a peer's state, used by admin RPC endpoints.
@@ -417,6 +621,12 @@ pub trait PeerTableServerProtocol: Send + Sync {
     fn set_disposable(&self, node_id: H256) -> Result<(), ActorError>;
     fn mark_knows_us(&self, node_id: H256) -> Result<(), ActorError>;
     fn prune_table(&self) -> Result<(), ActorError>;
+    /// Writes the contacts to the store, so they survive a restart.
+    fn persist_table(&self) -> Result<(), ActorError>;
+    fn add_static_peer(&self, node: Node) -> Result<(), ActorError>;
+    fn remove_static_peer(&self, node_id: H256) -> Result<(), ActorError>;
+    fn add_trusted_peer(&self, node_id: H256) -> Result<(), ActorError>;
+    fn remove_trusted_peer(&self, node_id: H256) -> Result<(), ActorError>;
     fn shutdown(&self) -> Result<(), ActorError>;
 
     // Request (call) methods
@@ -424,6 +634,11 @@ pub trait PeerTableServerProtocol: Send + Sync {
     fn peer_count_by_capabilities(&self, capabilities: Vec<Capability>) -> Response<usize>;
     fn target_reached(&self) -> Response<bool>;
     fn target_peers_reached(&self) -> Response<bool>;
+    /// Like `target_peers_reached`, but always false for static and trusted peers,
+    /// which are accepted regardless of the peer limit.
+    fn target_peers_reached_for(&self, node_id: H256) -> Response<bool>;
+    /// Whether the peer was configured as trusted or added with `admin_addTrustedPeer`.
+    fn is_trusted_peer(&self, node_id: H256) -> Response<bool>;
     fn target_peers_completion(&self) -> Response<f64>;
     fn get_contact_to_initiate(&self) -> Response<Option<Box<Contact>>>;
     fn get_contact_for_enr_lookup(&self) -> Response<Option<Box<Contact>>>;
@@ -467,6 +682,8 @@ pub trait PeerTableServerProtocol: Send + Sync {
     fn get_session_info(&self, node_id: H256) -> Response<Option<Session>>;
     fn get_peer_diagnostics(&self) -> Response<Vec<PeerDiagnostics>>;
     fn get_peer_connection(&self, peer_id: H256) -> Response<Option<PeerConnection>>;
+    /// Returns the static peers we are not currently connected to.
+    fn get_static_peers_to_dial(&self) -> Response<Vec<Node>>;
 }
 
 #[derive(Debug)]
@@ -486,6 +703,10 @@ pub struct PeerTableServer {
     /// allows (k-buckets: 256 × 16 = 4,096 max; this pool: up to 50,000).
     /// K-buckets are still used for all Kademlia protocol operations.
     connection_pool: IndexMap<H256, Node>,
+    /// Peers configured with `--p2p.static-peers` or added with `admin_addPeer`.
+    static_peers: IndexMap<H256, Node>,
+    /// Peers configured with `--p2p.trusted-peers` or added with `admin_addTrustedPeer`.
+    trusted_peers: FxHashSet<H256>,
 }
 
 #[actor(protocol = PeerTableServerProtocol)]
@@ -504,11 +725,19 @@ impl PeerTableServer {
             store,
             sessions: Default::default(),
             connection_pool: IndexMap::with_capacity(MAX_CONNECTION_POOL_SIZE),
+            static_peers: Default::default(),
+            trusted_peers: Default::default(),
         }
     }
 
     #[started]
     async fn started(&mut self, ctx: &Context<Self>) {
+        self.load_persisted_contacts().await;
+        send_interval(
+            PERSIST_INTERVAL,
+            ctx.clone(),
+            peer_table_server_protocol::PersistTable,
+        );
         send_message_on(
             ctx.clone(),
             tokio::signal::ctrl_c(),
@@ -543,7 +772,16 @@ impl PeerTableServer {
         _ctx: &Context<Self>,
     ) {
         let new_peer_id = msg.node.node_id();
-        let new_peer = PeerData::new(msg.node, None, Some(msg.connection), msg.capabilities);
+        let mut new_peer = PeerData::new(msg.node, None, Some(msg.connection), msg.capabilities);
+        new_peer.is_static = self.static_peers.contains_key(&new_peer_id);
+        new_peer.is_trusted = self.trusted_peers.contains(&new_peer_id);
+        // Start from the reputation earned in previous connections
+        if let Some(contact) = self.get_contact_or_replacement(&new_peer_id) {
+            new_peer.score = contact.reputation();
+            if new_peer.is_trusted {
+                new_peer.score = new_peer.score.max(0);
+            }
+        }
         self.peers.insert(new_peer_id, new_peer);
     }
 
@@ -619,14 +857,15 @@ impl PeerTableServer {
         msg: peer_table_server_protocol::RecordSuccess,
         _ctx: &Context<Self>,
     ) {
-        let now = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_secs();
+        let now = unix_now();
         self.peers.entry(msg.node_id).and_modify(|peer_data| {
             peer_data.score = (peer_data.score + 1).min(MAX_SCORE);
             peer_data.last_response_time = Some(now);
         });
+        if let Some(contact) = self.get_contact_or_replacement_mut(&msg.node_id) {
+            contact.successes += 1;
+            contact.last_seen = now;
+        }
     }
 
     #[send_handler]
@@ -637,7 +876,10 @@ impl PeerTableServer {
     ) {
         self.peers
             .entry(msg.node_id)
-            .and_modify(|peer_data| peer_data.score = (peer_data.score - 1).max(MIN_SCORE));
+            .and_modify(PeerData::record_failure);
+        if let Some(contact) = self.get_contact_or_replacement_mut(&msg.node_id) {
+            contact.record_failure();
+        }
     }
 
     #[send_handler]
@@ -648,7 +890,15 @@ impl PeerTableServer {
     ) {
         self.peers
             .entry(msg.node_id)
-            .and_modify(|peer_data| peer_data.score = MIN_SCORE_CRITICAL);
+            .and_modify(PeerData::record_critical_failure);
+        let is_trusted = self.trusted_peers.contains(&msg.node_id);
+        if let Some(contact) = self.get_contact_or_replacement_mut(&msg.node_id) {
+            contact.record_failure();
+            // Malicious peers are not dialed again, even after a restart
+            if !is_trusted {
+                contact.unwanted = true;
+            }
+        }
     }
 
     #[send_handler]
@@ -676,6 +926,7 @@ impl PeerTableServer {
                 .unwrap_or(false)
         {
             contact.ping_id = None;
+            contact.last_seen = unix_now();
         }
     }
 
@@ -732,12 +983,72 @@ impl PeerTableServer {
         self.prune();
     }
 
+    #[send_handler]
+    async fn handle_persist_table(
+        &mut self,
+        _msg: peer_table_server_protocol::PersistTable,
+        _ctx: &Context<Self>,
+    ) {
+        self.persist_contacts().await;
+    }
+
+    #[send_handler]
+    async fn handle_add_static_peer(
+        &mut self,
+        msg: peer_table_server_protocol::AddStaticPeer,
+        _ctx: &Context<Self>,
+    ) {
+        let node_id = msg.node.node_id();
+        if let Some(peer_data) = self.peers.get_mut(&node_id) {
+            peer_data.is_static = true;
+        }
+        self.static_peers.insert(node_id, msg.node);
+    }
+
+    #[send_handler]
+    async fn handle_remove_static_peer(
+        &mut self,
+        msg: peer_table_server_protocol::RemoveStaticPeer,
+        _ctx: &Context<Self>,
+    ) {
+        if let Some(peer_data) = self.peers.get_mut(&msg.node_id) {
+            peer_data.is_static = false;
+        }
+        self.static_peers.swap_remove(&msg.node_id);
+    }
+
+    #[send_handler]
+    async fn handle_add_trusted_peer(
+        &mut self,
+        msg: peer_table_server_protocol::AddTrustedPeer,
+        _ctx: &Context<Self>,
+    ) {
+        if let Some(peer_data) = self.peers.get_mut(&msg.node_id) {
+            peer_data.is_trusted = true;
+            peer_data.score = peer_data.score.max(0);
+        }
+        self.trusted_peers.insert(msg.node_id);
+    }
+
+    #[send_handler]
+    async fn handle_remove_trusted_peer(
+        &mut self,
+        msg: peer_table_server_protocol::RemoveTrustedPeer,
+        _ctx: &Context<Self>,
+    ) {
+        if let Some(peer_data) = self.peers.get_mut(&msg.node_id) {
+            peer_data.is_trusted = false;
+        }
+        self.trusted_peers.remove(&msg.node_id);
+    }
+
     #[send_handler]
     async fn handle_shutdown(
         &mut self,
         _msg: peer_table_server_protocol::Shutdown,
         ctx: &Context<Self>,
     ) {
+        self.persist_contacts().await;
         ctx.stop();
     }
 
@@ -779,6 +1090,26 @@ impl PeerTableServer {
         self.peers.len() >= self.target_peers
     }
 
+    #[request_handler]
+    async fn handle_target_peers_reached_for(
+        &mut self,
+        msg: peer_table_server_protocol::TargetPeersReachedFor,
+        _ctx: &Context<Self>,
+    ) -> bool {
+        !self.static_peers.contains_key(&msg.node_id)
+            && !self.trusted_peers.contains(&msg.node_id)
+            && self.peers.len() >= self.target_peers
+    }
+
+    #[request_handler]
+    async fn handle_is_trusted_peer(
+        &mut self,
+        msg: peer_table_server_protocol::IsTrustedPeer,
+        _ctx: &Context<Self>,
+    ) -> bool {
+        self.trusted_peers.contains(&msg.node_id)
+    }
+
     #[request_handler]
     async fn handle_target_peers_completion(
         &mut self,
@@ -1034,6 +1365,19 @@ impl PeerTableServer {
             .and_then(|peer_data| peer_data.connection.clone())
     }
 
+    #[request_handler]
+    async fn handle_get_static_peers_to_dial(
+        &mut self,
+        _msg: peer_table_server_protocol::GetStaticPeersToDial,
+        _ctx: &Context<Self>,
+    ) -> Vec<Node> {
+        self.static_peers
+            .iter()
+            .filter(|(node_id, _)| !self.peers.contains_key(*node_id))
+            .map(|(_, node)| node.clone())
+            .collect()
+    }
+
     #[request_handler]
     async fn handle_get_peer_diagnostics(
         &mut self,
@@ -1283,7 +1627,12 @@ impl PeerTableServer {
                 || self.already_tried_peers.contains(&node_id)
                 || self
                     .get_contact_or_replacement(&node_id)
-                    .map(|c| !c.knows_us || c.unwanted || c.is_fork_id_valid == Some(false))
+                    .map(|c| {
+                        !c.knows_us
+                            || c.unwanted
+                            || c.is_fork_id_valid == Some(false)
+                            || c.reputation() <= MIN_SCORE
+                    })
                     .unwrap_or(false)
             {
                 continue;
@@ -1366,8 +1715,8 @@ impl PeerTableServer {
         }
 
         // Check that the IP address from which we receive the request matches the one we have stored
-        // to prevent amplification attacks.
-        if sender_ip != contact.node.ip {
+        // to prevent amplification attacks. Dual-stack nodes may use either of their addresses.
+        if !contact.node.has_ip(sender_ip) {
             return ContactValidation::IpMismatch;
         }
         ContactValidation::Valid(Box::new(contact.clone()))
@@ -1504,6 +1853,57 @@ impl PeerTableServer {
         }
     }
 
+    /// Writes every contact seen within [`PERSISTED_CONTACT_MAX_AGE`] to the store,
+    /// replacing the previously persisted table.
+    async fn persist_contacts(&self) {
+        let min_last_seen = unix_now().saturating_sub(PERSISTED_CONTACT_MAX_AGE);
+        let entries: Vec<(H256, Vec<u8>)> = self
+            .iter_contacts()
+            .filter(|(_, contact)| !contact.disposable && contact.last_seen >= min_last_seen)
+            .map(|(node_id, contact)| (*node_id, PersistedContact::from(contact).encode_to_vec()))
+            .collect();
+        let count = entries.len();
+        match self.store.store_peers(entries).await {
+            Ok(()) => tracing::debug!(count, "Persisted peer table"),
+            Err(err) => tracing::warn!(%err, "Failed to persist peer table"),
+        }
+    }
+
+    /// Restores the contacts persisted by a previous run, dropping the ones that
+    /// weren't seen within [`PERSISTED_CONTACT_MAX_AGE`].
+    async fn load_persisted_contacts(&mut self) {
+        let entries = match self.store.get_peers().await {
+            Ok(entries) => entries,
+            Err(err) => {
+                tracing::warn!(%err, "Failed to load persisted peer table");
+                return;
+            }
+        };
+        let min_last_seen = unix_now().saturating_sub(PERSISTED_CONTACT_MAX_AGE);
+        let mut loaded = 0;
+        for entry in entries {
+            let Ok(persisted) = PersistedContact::decode(&entry) else {
+                continue;
+            };
+            let node_id = persisted.node.node_id();
+            if persisted.last_seen < min_last_seen
+                || node_id == self.local_node_id
+                || self.contact_exists(&node_id)
+            {
+                continue;
+            }
+            let mut contact = Contact::from(persisted);
+            // Our fork may have changed since the record was checked
+            if let Some(record) = &contact.record {
+                contact.is_fork_id_valid = Self::evaluate_fork_id(record, &self.store).await;
+            }
+            self.insert_to_connection_pool(node_id, contact.node.clone());
+            self.insert_contact(node_id, contact);
+            loaded += 1;
+        }
+        tracing::info!(count = loaded, "Loaded persisted peer table");
+    }
+
     async fn evaluate_fork_id(record: &NodeRecord, store: &Store) -> Option<bool> {
         if let Some(remote_fork_id) = record.get_fork_id() {
             backend::is_fork_id_valid(store, remote_fork_id)
@@ -1772,4 +2172,197 @@ mod tests {
         remote.0[0] = 0x80;
         assert_eq!(bucket_index(&local, &remote), Some(255));
     }
+
+    // --- persistence ---
+
+    #[test]
+    fn persisted_contact_round_trip() {
+        let (_, mut contact) = dummy_contact(1);
+        contact.is_discv5 = true;
+        contact.unwanted = true;
+        contact.is_fork_id_valid = Some(false);
+        contact.successes = 7;
+        contact.failures = 3;
+        contact.last_failure = unix_now();
+
+        let encoded = PersistedContact::from(&contact).encode_to_vec();
+        let restored = Contact::from(PersistedContact::decode(&encoded).unwrap());
+
+        assert_eq!(restored.node, contact.node);
+        assert!(restored.is_discv4 && restored.is_discv5);
+        assert!(!restored.was_validated());
+        assert!(restored.unwanted);
+        assert_eq!(restored.is_fork_id_valid, Some(false));
+        assert_eq!(restored.last_seen, contact.last_seen);
+        assert_eq!(restored.last_failure, contact.last_failure);
+        assert_eq!(restored.reputation(), 4);
+        assert!(restored.record.is_none());
+    }
+
+    #[test]
+    fn failures_decay_over_time() {
+        let (_, mut contact) = dummy_contact(1);
+        contact.failures = 3;
+        contact.last_failure = 1_000;
+
+        assert_eq!(contact.recent_failures(1_000), 3);
+        assert_eq!(contact.recent_failures(1_000 + FAILURE_DECAY_INTERVAL), 2);
+        assert_eq!(
+            contact.recent_failures(1_000 + 5 * FAILURE_DECAY_INTERVAL),
+            0
+        );
+    }
+
+    #[test]
+    fn failures_are_counted_on_top_of_the_decayed_ones() {
+        let (_, mut contact) = dummy_contact(1);
+        contact.failures = 3;
+        contact.last_failure = unix_now() - 2 * FAILURE_DECAY_INTERVAL;
+
+        contact.record_failure();
+        assert_eq!(contact.failures, 2);
+        assert_eq!(contact.recent_failures(unix_now()), 2);
+    }
+
+    #[test]
+    fn bad_reputation_recovers_once_failures_decay() {
+        let (_, mut contact) = dummy_contact(1);
+        contact.failures = (-MIN_SCORE) as u64;
+        contact.last_failure = unix_now();
+        assert_eq!(contact.reputation(), MIN_SCORE);
+
+        contact.last_failure = unix_now() - 10 * FAILURE_DECAY_INTERVAL;
+        assert_eq!(contact.reputation(), MIN_SCORE + 10);
+    }
+
+    // --- static and trusted peers ---
+
+    fn dummy_peer(seed: u8, is_trusted: bool) -> PeerData {
+        let (_, contact) = dummy_contact(seed);
+        let mut peer = PeerData::new(contact.node, None, None, vec![]);
+        peer.is_trusted = is_trusted;
+        peer
+    }
+
+    #[test]
+    fn trusted_peers_score_never_goes_below_neutral() {
+        let mut peer = dummy_peer(1, false);
+        let mut trusted = dummy_peer(2, true);
+        for _ in 0..2 * MIN_SCORE.unsigned_abs() {
+            peer.record_failure();
+            trusted.record_failure();
+        }
+        assert_eq!(peer.score, MIN_SCORE);
+        assert_eq!(trusted.score, 0);
+
+        trusted.score = 10;
+        trusted.record_failure();
+        assert_eq!(trusted.score, 9);
+    }
+
+    #[test]
+    fn critical_failures_drop_trusted_peers_to_neutral() {
+        let mut peer = dummy_peer(1, false);
+        let mut trusted = dummy_peer(2, true);
+        peer.score = 10;
+        trusted.score = 10;
+        peer.record_critical_failure();
+        trusted.record_critical_failure();
+        assert_eq!(peer.score, MIN_SCORE_CRITICAL);
+        assert_eq!(trusted.score, 0);
+    }
+
+    #[tokio::test]
+    async fn static_and_trusted_peers_bypass_the_peer_limit() {
+        let store = Store::new("", ethrex_storage::EngineType::InMemory).unwrap();
+        // With no room for peers the limit is always reached
+        let table = PeerTableServer::spawn(H256::zero(), 0, store);
+        let (regular, _) = dummy_contact(1);
+        let (trusted, _) = dummy_contact(2);
+        let (_, static_contact) = dummy_contact(3);
+        let static_peer = static_contact.node.node_id();
+
+        table.add_trusted_peer(trusted).unwrap();
+        table.add_static_peer(static_contact.node).unwrap();
+        assert!(table.target_peers_reached_for(regular).await.unwrap());
+        assert!(!table.target_peers_reached_for(trusted).await.unwrap());
+        assert!(!table.target_peers_reached_for(static_peer).await.unwrap());
+
+        table.remove_trusted_peer(trusted).unwrap();
+        table.remove_static_peer(static_peer).unwrap();
+        assert!(table.target_peers_reached_for(trusted).await.unwrap());
+        assert!(table.target_peers_reached_for(static_peer).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn disconnected_static_peers_are_dialed() {
+        let store = Store::new("", ethrex_storage::EngineType::InMemory).unwrap();
+        let table = PeerTableServer::spawn(H256::zero(), 10, store);
+        let (_, contact) = dummy_contact(1);
+        let node_id = contact.node.node_id();
+
+        table.add_static_peer(contact.node).unwrap();
+        let to_dial = table.get_static_peers_to_dial().await.unwrap();
+        assert_eq!(
+            to_dial.iter().map(Node::node_id).collect::<Vec<_>>(),
+            [node_id]
+        );
+
+        table.remove_static_peer(node_id).unwrap();
+        assert!(table.get_static_peers_to_dial().await.unwrap().is_empty());
+    }
+
+    // --- persisted contacts ---
+
+    #[tokio::test]
+    async fn persisted_contacts_are_reloaded_without_stale_or_bad_ones() {
+        let store = Store::new("", ethrex_storage::EngineType::InMemory).unwrap();
+        let mut table = PeerTableServer::new(H256::zero(), 10, store.clone());
+        let (good, good_contact) = dummy_contact(1);
+        let (unwanted, mut unwanted_contact) = dummy_contact(2);
+        unwanted_contact.unwanted = true;
+        let (bad, mut bad_contact) = dummy_contact(3);
+        bad_contact.failures = (-MIN_SCORE) as u64;
+        bad_contact.last_failure = unix_now();
+        let (stale, mut stale_contact) = dummy_contact(4);
+        stale_contact.last_seen = unix_now() - PERSISTED_CONTACT_MAX_AGE - 1;
+        for (node_id, contact) in [
+            (good, good_contact),
+            (unwanted, unwanted_contact),
+            (bad, bad_contact),
+            (stale, stale_contact),
+        ] {
+            table.insert_to_connection_pool(node_id, contact.node.clone());
+            assert!(table.insert_contact(node_id, contact));
+        }
+
+        // Stale contacts aren't persisted
+        table.persist_contacts().await;
+        assert_eq!(store.get_peers().await.unwrap().len(), 3);
+
+        // Nor restored, if they were persisted by a run that saw them recently
+        let entries = table
+            .iter_contacts()
+            .map(|(node_id, contact)| (*node_id, PersistedContact::from(contact).encode_to_vec()))
+            .collect();
+        store.store_peers(entries).await.unwrap();
+        assert_eq!(store.get_peers().await.unwrap().len(), 4);
+
+        let mut reloaded = PeerTableServer::new(H256::zero(), 10, store);
+        reloaded.load_persisted_contacts().await;
+        assert!(reloaded.contact_exists(&good));
+        assert!(reloaded.contact_exists(&unwanted));
+        assert!(reloaded.contact_exists(&bad));
+        assert!(!reloaded.contact_exists(&stale));
+
+        // Only the good contact is dialed: the unwanted and bad reputation ones are kept
+        // but skipped
+        assert_eq!(
+            reloaded
+                .do_get_contact_to_initiate()
+                .map(|contact| contact.node.node_id()),
+            Some(good)
+        );
+        assert!(reloaded.do_get_contact_to_initiate().is_none());
+    }
 }
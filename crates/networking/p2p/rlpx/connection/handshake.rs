@@ -64,7 +64,10 @@ pub(crate) async fn perform(
 ) -> Result<(Established, SplitStream<Framed<TcpStream, RLPxCodec>>), PeerConnectionError> {
     let (context, node, framed) = match state {
         ConnectionState::Initiator(Initiator { context, node }) => {
-            let addr = SocketAddr::new(node.ip, node.tcp_port);
+            // Dial dual-stack nodes over the address family we listen on
+            let addr = node
+                .tcp_addr_from(context.network_config.bind_tcp_addr().ip())
+                .unwrap_or_else(|| node.tcp_addr());
             let mut stream = match tcp_stream(addr).await {
                 Ok(result) => result,
                 Err(error) => {
@@ -147,8 +150,7 @@ pub(crate) async fn perform(
             current_requests: HashMap::new(),
             disconnect_reason: None,
             is_validated: false,
-            serve_request_window_start: std::time::Instant::now(),
-            serve_requests_in_window: 0,
+            serve_limiter: Default::default(),
             txs_sent_to_peer: 0,
             received_txs_from_peer: false,
         },
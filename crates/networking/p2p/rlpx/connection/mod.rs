@@ -1,3 +1,4 @@
 mod codec;
 pub mod handshake;
+mod rate_limit;
 pub mod server;
@@ -0,0 +1,361 @@
+//! Per-peer serving limits.
+//!
+//! Every request a peer sends us has a serving cost, similar to LES request costs: a base
+//! cost plus a cost per requested item (headers, bodies, KiB of snap data, transactions).
+//! Each message family has its own token bucket per peer, which refills at a fixed rate,
+//! and a request that doesn't fit in its bucket is rejected, while gossip over the limit is
+//! dropped. Responses to our requests and transactions the mempool accepts from the peer are
+//! valued with the same costs, so the connection can tell peers that consume far more than
+//! they contribute.
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Instant,
+};
+
+use crate::rlpx::{eth::blocks::BLOCK_HEADER_LIMIT, message::Message};
+
+/// Message families that are rate limited independently of each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MessageFamily {
+    /// `GetBlockHeaders`
+    Headers = 0,
+    /// `GetBlockBodies` and `GetBlockAccessLists`
+    Bodies,
+    /// `GetReceipts` (all versions)
+    Receipts,
+    /// Snap `GetAccountRange`, `GetStorageRanges`, `GetByteCodes` and `GetTrieNodes`
+    SnapRanges,
+    /// `Transactions`, `NewPooledTransactionHashes` and `GetPooledTransactions`
+    TxGossip,
+}
+
+impl MessageFamily {
+    const ALL: [MessageFamily; 5] = [
+        MessageFamily::Headers,
+        MessageFamily::Bodies,
+        MessageFamily::Receipts,
+        MessageFamily::SnapRanges,
+        MessageFamily::TxGossip,
+    ];
+
+    pub(crate) fn label(&self) -> &'static str {
+        match self {
+            MessageFamily::Headers => "headers",
+            MessageFamily::Bodies => "bodies",
+            MessageFamily::Receipts => "receipts",
+            MessageFamily::SnapRanges => "snap_ranges",
+            MessageFamily::TxGossip => "tx_gossip",
+        }
+    }
+
+    fn cost(&self) -> RequestCost {
+        match self {
+            MessageFamily::Headers => RequestCost {
+                base: 10,
+                per_item: 1,
+            },
+            MessageFamily::Bodies | MessageFamily::Receipts => RequestCost {
+                base: 10,
+                per_item: 10,
+            },
+            // Items are KiB of requested response size
+            MessageFamily::SnapRanges => RequestCost {
+                base: 10,
+                per_item: 1,
+            },
+            MessageFamily::TxGossip => RequestCost {
+                base: 1,
+                per_item: 1,
+            },
+        }
+    }
+
+    /// Bucket size and refill rate (per second), large enough for a peer syncing from us
+    /// with several requests in flight.
+    fn bucket(&self) -> TokenBucket {
+        match self {
+            MessageFamily::Headers => TokenBucket::new(20_000, 5_000),
+            MessageFamily::Bodies | MessageFamily::Receipts => TokenBucket::new(50_000, 10_000),
+            MessageFamily::SnapRanges => TokenBucket::new(100_000, 20_000),
+            MessageFamily::TxGossip => TokenBucket::new(50_000, 5_000),
+        }
+    }
+}
+
+/// Serving cost of a request: `base + per_item * items`.
+#[derive(Debug, Clone, Copy)]
+struct RequestCost {
+    base: u64,
+    per_item: u64,
+}
+
+impl RequestCost {
+    fn of(&self, items: usize) -> u64 {
+        self.base
+            .saturating_add(self.per_item.saturating_mul(items as u64))
+    }
+}
+
+/// Returns the family and serving cost of a request or unsolicited gossip received from a
+/// peer, `None` for messages that aren't rate limited.
+pub(crate) fn request_cost(message: &Message) -> Option<(MessageFamily, u64)> {
+    let (family, items) = match message {
+        // We never serve more than BLOCK_HEADER_LIMIT headers
+        Message::GetBlockHeaders(msg) => (
+            MessageFamily::Headers,
+            msg.limit.min(BLOCK_HEADER_LIMIT) as usize,
+        ),
+        Message::GetBlockBodies(msg) => (MessageFamily::Bodies, msg.block_hashes.len()),
+        Message::GetBlockAccessLists(msg) => (MessageFamily::Bodies, msg.block_hashes.len()),
+        Message::GetReceipts68(msg) | Message::GetReceipts69(msg) => {
+            (MessageFamily::Receipts, msg.block_hashes.len())
+        }
+        Message::GetReceipts70(msg) => (MessageFamily::Receipts, msg.block_hashes.len()),
+        Message::GetAccountRange(msg) => (MessageFamily::SnapRanges, kib(msg.response_bytes)),
+        Message::GetStorageRanges(msg) => (MessageFamily::SnapRanges, kib(msg.response_bytes)),
+        Message::GetByteCodes(msg) => (MessageFamily::SnapRanges, kib(msg.bytes)),
+        Message::GetTrieNodes(msg) => (MessageFamily::SnapRanges, kib(msg.bytes)),
+        Message::Transactions(msg) => (MessageFamily::TxGossip, msg.transactions.len()),
+        Message::NewPooledTransactionHashes(msg) => {
+            (MessageFamily::TxGossip, msg.transaction_hashes.len())
+        }
+        Message::GetPooledTransactions(msg) => {
+            (MessageFamily::TxGossip, msg.transaction_hashes.len())
+        }
+        _ => return None,
+    };
+    Some((family, family.cost().of(items)))
+}
+
+/// Returns the value of a response a peer sent us, valued with the cost we would charge
+/// for serving it, or 0 for messages that aren't responses. Only responses to requests we
+/// made should be credited.
+pub(crate) fn contribution_value(message: &Message) -> u64 {
+    let (family, items) = match message {
+        Message::BlockHeaders(msg) => (MessageFamily::Headers, msg.block_headers.len()),
+        Message::BlockBodies(msg) => (MessageFamily::Bodies, msg.block_bodies.len()),
+        Message::BlockAccessLists(msg) => (MessageFamily::Bodies, msg.block_access_lists.len()),
+        Message::Receipts68(msg) => (MessageFamily::Receipts, msg.receipts.len()),
+        Message::Receipts69(msg) => (MessageFamily::Receipts, msg.receipts.len()),
+        Message::Receipts70(msg) => (MessageFamily::Receipts, msg.receipts.len()),
+        Message::AccountRange(msg) => (
+            MessageFamily::SnapRanges,
+            // Rough size of an account range entry
+            kib(msg.accounts.len() as u64 * 100),
+        ),
+        Message::StorageRanges(msg) => (
+            MessageFamily::SnapRanges,
+            kib(msg.slots.iter().map(Vec::len).sum::<usize>() as u64 * 64),
+        ),
+        Message::ByteCodes(msg) => (
+            MessageFamily::SnapRanges,
+            kib(msg.codes.iter().map(|code| code.len() as u64).sum()),
+        ),
+        Message::TrieNodes(msg) => (
+            MessageFamily::SnapRanges,
+            kib(msg.nodes.iter().map(|node| node.len() as u64).sum()),
+        ),
+        _ => return 0,
+    };
+    family.cost().of(items)
+}
+
+/// Returns the value of `accepted` transactions the peer sent us that entered the mempool.
+pub(crate) fn transactions_value(accepted: usize) -> u64 {
+    MessageFamily::TxGossip.cost().of(accepted)
+}
+
+fn kib(bytes: u64) -> usize {
+    bytes.div_ceil(1024) as usize
+}
+
+#[derive(Debug, Clone)]
+struct TokenBucket {
+    capacity: f64,
+    refill_per_sec: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: u64, refill_per_sec: u64) -> Self {
+        Self {
+            capacity: capacity as f64,
+            refill_per_sec: refill_per_sec as f64,
+            tokens: capacity as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes `cost` tokens if there are enough of them.
+    fn try_consume(&mut self, cost: u64, now: Instant) -> bool {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens < cost as f64 {
+            return false;
+        }
+        self.tokens -= cost as f64;
+        true
+    }
+}
+
+/// Minimum cost served to a peer before comparing it against what the peer contributed.
+const LEECH_MIN_SERVED_COST: u64 = 1_000_000;
+/// A peer is a leecher once we've served it this many times what it contributed.
+const LEECH_RATIO: u64 = 100;
+
+/// Serving limits and accounting of a single connection.
+#[derive(Debug, Clone)]
+pub(crate) struct ServeLimiter {
+    buckets: [TokenBucket; MessageFamily::ALL.len()],
+    /// Total cost of the requests served to the peer.
+    served: u64,
+    /// Total value of the data the peer sent us.
+    contributed: Contributions,
+}
+
+impl Default for ServeLimiter {
+    fn default() -> Self {
+        Self {
+            buckets: MessageFamily::ALL.map(|family| family.bucket()),
+            served: 0,
+            contributed: Contributions::default(),
+        }
+    }
+}
+
+/// Total value of the data a peer sent us. Cloning it shares the total, so transactions
+/// added to the mempool outside the connection can still be credited to the peer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Contributions(Arc<AtomicU64>);
+
+impl Contributions {
+    pub(crate) fn record(&self, value: u64) {
+        let _ = self
+            .0
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |total| {
+                Some(total.saturating_add(value))
+            });
+    }
+
+    fn total(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl ServeLimiter {
+    /// Charges a request of `family` costing `cost`. Returns false if the peer exceeded
+    /// its limit for that family, in which case nothing is charged.
+    pub(crate) fn try_serve(&mut self, family: MessageFamily, cost: u64, is_gossip: bool) -> bool {
+        if !self.buckets[family as usize].try_consume(cost, Instant::now()) {
+            return false;
+        }
+        // Gossip is limited like requests, but it isn't something we serve
+        if !is_gossip {
+            self.served = self.served.saturating_add(cost);
+        }
+        true
+    }
+
+    /// Records data sent by the peer, valued with [`contribution_value`] or
+    /// [`transactions_value`].
+    pub(crate) fn record_contribution(&self, value: u64) {
+        self.contributed.record(value);
+    }
+
+    /// Returns a handle to credit the peer from outside the connection.
+    pub(crate) fn contributions(&self) -> Contributions {
+        self.contributed.clone()
+    }
+
+    /// Whether the peer consumed far more than it contributed.
+    pub(crate) fn is_leeching(&self) -> bool {
+        self.served >= LEECH_MIN_SERVED_COST
+            && self.served > self.contributed.total().saturating_mul(LEECH_RATIO)
+    }
+
+    pub(crate) fn served(&self) -> u64 {
+        self.served
+    }
+
+    pub(crate) fn contributed(&self) -> u64 {
+        self.contributed.total()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn bucket_refills_over_time() {
+        let mut bucket = TokenBucket::new(100, 10);
+        let start = bucket.last_refill;
+        assert!(bucket.try_consume(100, start));
+        assert!(!bucket.try_consume(1, start));
+        assert!(!bucket.try_consume(20, start + Duration::from_secs(1)));
+        assert!(bucket.try_consume(20, start + Duration::from_secs(2)));
+        // Never refills above capacity
+        assert!(!bucket.try_consume(101, start + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn families_are_limited_independently() {
+        let mut limiter = ServeLimiter::default();
+        assert!(limiter.try_serve(MessageFamily::Headers, 20_000, false));
+        assert!(!limiter.try_serve(MessageFamily::Headers, 1_000, false));
+        assert!(limiter.try_serve(MessageFamily::Bodies, 1_000, false));
+        assert!(limiter.try_serve(MessageFamily::TxGossip, 1_000, true));
+        assert_eq!(limiter.served(), 21_000);
+    }
+
+    #[test]
+    fn leeching_needs_a_minimum_served_cost() {
+        let mut limiter = ServeLimiter {
+            served: LEECH_MIN_SERVED_COST - 1,
+            ..Default::default()
+        };
+        assert!(!limiter.is_leeching());
+        limiter.served = LEECH_MIN_SERVED_COST;
+        assert!(limiter.is_leeching());
+        limiter.record_contribution(LEECH_MIN_SERVED_COST / LEECH_RATIO);
+        assert!(!limiter.is_leeching());
+    }
+
+    #[test]
+    fn contributions_recorded_outside_the_connection_are_shared() {
+        let limiter = ServeLimiter {
+            served: LEECH_MIN_SERVED_COST,
+            ..Default::default()
+        };
+        assert!(limiter.is_leeching());
+        let contributions = limiter.contributions();
+        std::thread::spawn(move || {
+            contributions.record(transactions_value(LEECH_MIN_SERVED_COST as usize / 100))
+        })
+        .join()
+        .unwrap();
+        assert!(!limiter.is_leeching());
+    }
+
+    #[test]
+    fn only_responses_have_contribution_value() {
+        use crate::rlpx::eth::transactions::Transactions;
+        use ethrex_common::types::Transaction;
+
+        let gossip =
+            Message::Transactions(Transactions::new(vec![Transaction::EIP1559Transaction(
+                Default::default(),
+            )]));
+        assert_eq!(contribution_value(&gossip), 0);
+        assert_eq!(transactions_value(1), 2);
+    }
+}
@@ -12,7 +12,11 @@ use crate::{
     peer_table::{PeerTable, PeerTableServerProtocol as _},
     rlpx::{
         Message,
-        connection::{codec::RLPxCodec, handshake},
+        connection::{
+            codec::RLPxCodec,
+            handshake,
+            rate_limit::{ServeLimiter, contribution_value, request_cost, transactions_value},
+        },
         error::PeerConnectionError,
         eth::{
             block_access_lists::{BlockAccessLists, GetBlockAccessLists},
@@ -80,10 +84,6 @@ const INFLIGHT_TX_TIMEOUT: Duration = Duration::from_secs(30);
 /// How often to flush buffered transaction hash requests into a single
 /// batched GetPooledTransactions message.
 const TX_REQUEST_BATCH_INTERVAL: Duration = Duration::from_millis(50);
-/// Fixed (tumbling) time window for incoming request rate limiting.
-const SERVE_REQUEST_WINDOW: Duration = Duration::from_secs(60);
-/// Maximum number of data-serving requests allowed per peer within the rate-limit window.
-const MAX_SERVE_REQUESTS_PER_WINDOW: u64 = 500;
 /// Number of transactions sent to a peer before checking for leeching behaviour.
 const LEECH_TX_SENT_THRESHOLD: u64 = 10_000;
 
@@ -109,6 +109,7 @@ pub trait PeerConnectionServerProtocol: Send + Sync {
         announcement: NewPooledTransactionHashes,
         hashes: Vec<H256>,
     ) -> Result<(), ActorError>;
+    fn disconnect(&self) -> Result<(), ActorError>;
 }
 
 #[cfg(feature = "l2")]
@@ -174,6 +175,13 @@ impl PeerConnection {
             .map_err(|err| PeerConnectionError::InternalError(err.to_string()))
     }
 
+    /// Closes the connection, sending a `Disconnect` message to the peer.
+    pub fn disconnect(&self) -> Result<(), PeerConnectionError> {
+        self.handle
+            .disconnect()
+            .map_err(|err| PeerConnectionError::InternalError(err.to_string()))
+    }
+
     pub async fn outgoing_request(
         &mut self,
         message: Message,
@@ -257,10 +265,8 @@ pub struct Established {
     pub(crate) disconnect_reason: Option<DisconnectReason>,
     // Indicates if the peer has been validated (ie. the connection was established successfully)
     pub(crate) is_validated: bool,
-    // Rate limiting: start of the current incoming-request window
-    pub(crate) serve_request_window_start: Instant,
-    // Rate limiting: number of data-serving requests received in the current window
-    pub(crate) serve_requests_in_window: u64,
+    // Rate limiting: per-family token buckets and serving cost accounting
+    pub(crate) serve_limiter: ServeLimiter,
     // Leech detection: total transactions sent to this peer via GetPooledTransactions responses
     pub(crate) txs_sent_to_peer: u64,
     // Leech detection: whether we have received any transactions from this peer
@@ -473,6 +479,23 @@ impl PeerConnectionServer {
         }
     }
 
+    #[send_handler]
+    async fn handle_disconnect(
+        &mut self,
+        _msg: peer_connection_server_protocol::Disconnect,
+        ctx: &Context<Self>,
+    ) {
+        if let ConnectionState::Established(ref mut established_state) = self.state {
+            debug!(peer=%established_state.node, "Disconnecting peer on request");
+            let reason = DisconnectReason::DisconnectRequested;
+            send_disconnect_message(established_state, Some(reason)).await;
+            established_state.disconnect_reason = Some(reason);
+            ctx.stop();
+        } else {
+            debug!("Connection not yet established");
+        }
+    }
+
     #[send_handler]
     async fn handle_request_timeout(
         &mut self,
@@ -714,7 +737,11 @@ async fn initialize_connection<S>(
 where
     S: Unpin + Send + Stream<Item = Result<Message, PeerConnectionError>> + 'static,
 {
-    if state.peer_table.target_peers_reached().await? {
+    if state
+        .peer_table
+        .target_peers_reached_for(state.node.node_id())
+        .await?
+    {
         debug!(peer=%state.node, "Reached target peer connections, discarding.");
         return Err(PeerConnectionError::TooManyPeers);
     }
@@ -1132,16 +1159,76 @@ where
     stream.next().await
 }
 
-/// Returns true if the peer is within its rate limit for data-serving requests, false if exceeded.
-/// Resets the window counter when the window duration has elapsed.
-fn check_serve_request_rate(state: &mut Established) -> bool {
-    let now = Instant::now();
-    if now.duration_since(state.serve_request_window_start) >= SERVE_REQUEST_WINDOW {
-        state.serve_request_window_start = now;
-        state.serve_requests_in_window = 0;
+/// What to do with an incoming message, according to the peer's serving limits.
+enum ServeDecision {
+    Serve,
+    /// Gossip over the limit is ignored rather than penalized.
+    Drop,
+    Disconnect(DisconnectReason),
+}
+
+/// Charges an incoming request or gossip message against the peer's serving limits.
+/// Requests over the limit disconnect the peer while gossip over the limit is dropped,
+/// and trusted peers are exempt from the limits. Peers that consume far more than they
+/// contribute are also disconnected while we have no free slots.
+async fn check_serve_limits(state: &mut Established, message: &Message) -> ServeDecision {
+    let Some((family, cost)) = request_cost(message) else {
+        return ServeDecision::Serve;
+    };
+    let is_gossip = matches!(
+        message,
+        Message::Transactions(_) | Message::NewPooledTransactionHashes(_)
+    );
+    if !state.serve_limiter.try_serve(family, cost, is_gossip) {
+        if state
+            .peer_table
+            .is_trusted_peer(state.node.node_id())
+            .await
+            .unwrap_or(false)
+        {
+            return ServeDecision::Serve;
+        }
+        METRICS.record_rate_limited_request(family.label());
+        if is_gossip {
+            debug!(
+                peer = %state.node,
+                family = family.label(),
+                cost,
+                "Dropping gossip: exceeded incoming gossip rate limit",
+            );
+            return ServeDecision::Drop;
+        }
+        debug!(
+            peer = %state.node,
+            family = family.label(),
+            cost,
+            "Disconnecting peer: exceeded incoming request rate limit",
+        );
+        return ServeDecision::Disconnect(DisconnectReason::UselessPeer);
+    }
+    if !is_gossip {
+        METRICS.record_served_request(family.label(), cost);
+    }
+
+    // Leechers are only dropped when their slot could go to a more useful peer.
+    // Static and trusted peers never take a slot.
+    if state.serve_limiter.is_leeching()
+        && state
+            .peer_table
+            .target_peers_reached_for(state.node.node_id())
+            .await
+            .unwrap_or(false)
+    {
+        METRICS.record_leecher_disconnection();
+        debug!(
+            peer = %state.node,
+            served = state.serve_limiter.served(),
+            contributed = state.serve_limiter.contributed(),
+            "Disconnecting peer: consumes far more than it contributes",
+        );
+        return ServeDecision::Disconnect(DisconnectReason::TooManyPeers);
     }
-    state.serve_requests_in_window += 1;
-    state.serve_requests_in_window <= MAX_SERVE_REQUESTS_PER_WINDOW
+    ServeDecision::Serve
 }
 
 async fn handle_incoming_message(
@@ -1154,30 +1241,14 @@ async fn handle_incoming_message(
         METRICS_P2P.inc_incoming_message(message.metric_label());
     }
 
-    // Rate-limit incoming data-serving requests to prevent resource exhaustion.
-    let is_data_request = matches!(
-        message,
-        Message::GetBlockHeaders(_)
-            | Message::GetBlockBodies(_)
-            | Message::GetReceipts68(_)
-            | Message::GetReceipts69(_)
-            | Message::GetReceipts70(_)
-            | Message::GetPooledTransactions(_)
-            | Message::GetAccountRange(_)
-            | Message::GetStorageRanges(_)
-            | Message::GetByteCodes(_)
-            | Message::GetTrieNodes(_)
-    );
-    if is_data_request && !check_serve_request_rate(state) {
-        debug!(
-            peer = %state.node,
-            window_requests = state.serve_requests_in_window,
-            "Disconnecting peer: exceeded incoming request rate limit",
-        );
-        send_disconnect_message(state, Some(DisconnectReason::UselessPeer)).await;
-        return Err(PeerConnectionError::DisconnectSent(
-            DisconnectReason::UselessPeer,
-        ));
+    // Rate-limit incoming requests and gossip to prevent resource exhaustion.
+    match check_serve_limits(state, &message).await {
+        ServeDecision::Serve => {}
+        ServeDecision::Drop => return Ok(()),
+        ServeDecision::Disconnect(reason) => {
+            send_disconnect_message(state, Some(reason)).await;
+            return Err(PeerConnectionError::DisconnectSent(reason));
+        }
     }
 
     let peer_supports_eth = state.negotiated_eth_capability.is_some();
@@ -1240,9 +1311,12 @@ async fn handle_incoming_message(
                 // the ConnectionServer (validation + signature recovery are expensive).
                 let blockchain = state.blockchain.clone();
                 let peer = state.node.to_string();
+                // Only the transactions the mempool accepts count as a contribution
+                let contributions = state.serve_limiter.contributions();
                 #[cfg(feature = "l2")]
                 let is_l2_mode = state.l2_state.is_supported();
                 tokio::spawn(async move {
+                    let mut accepted = 0;
                     for tx in txs.transactions {
                         #[cfg(feature = "l2")]
                         if (is_l2_mode && matches!(tx, Transaction::EIP4844Transaction(_)))
@@ -1253,14 +1327,16 @@ async fn handle_incoming_message(
                             continue;
                         }
 
-                        if let Err(e) = blockchain.add_transaction_to_pool(tx).await {
-                            debug!(
+                        match blockchain.add_transaction_to_pool(tx).await {
+                            Ok(_) => accepted += 1,
+                            Err(e) => debug!(
                                 peer=%peer,
                                 error=%e,
                                 "Error adding transaction"
-                            );
+                            ),
                         }
                     }
+                    contributions.record(transactions_value(accepted));
                 });
 
                 // Notify the broadcaster immediately — it only tracks hashes
@@ -1513,11 +1589,9 @@ async fn handle_incoming_message(
 
                 #[cfg(not(feature = "l2"))]
                 let is_l2_mode = false;
-                if let Err(error) = msg.handle(&state.node, &state.blockchain, is_l2_mode).await {
-                    if matches!(
-                        error,
-                        ethrex_blockchain::error::MempoolError::BlobsBundleError(_)
-                    ) {
+                let accepted = match msg.handle(&state.node, &state.blockchain, is_l2_mode).await {
+                    Ok(accepted) => accepted,
+                    Err(error @ ethrex_blockchain::error::MempoolError::BlobsBundleError(_)) => {
                         debug!(
                             peer=%state.node,
                             reason=%error,
@@ -1537,8 +1611,11 @@ async fn handle_incoming_message(
                             DisconnectReason::SubprotocolError,
                         ));
                     }
-                    return Err(error.into());
-                }
+                    Err(error) => return Err(error.into()),
+                };
+                state
+                    .serve_limiter
+                    .record_contribution(transactions_value(accepted));
             }
         }
         Message::GetStorageRanges(req) => {
@@ -1582,6 +1659,10 @@ async fn handle_incoming_message(
                 .request_id()
                 .and_then(|id| state.current_requests.remove(&id))
             {
+                // Only responses to our own requests count as a contribution
+                state
+                    .serve_limiter
+                    .record_contribution(contribution_value(&message));
                 tx.send(message)
                     .map_err(|e| PeerConnectionError::SendMessage(e.to_string()))?
             } else {
@@ -1607,11 +1688,32 @@ async fn handle_outgoing_message(
     Ok(())
 }
 
+/// Receipt requests are built in the eth/69 format, translates them to the negotiated
+/// version. Any other message is returned as is.
+fn receipts_request_for(message: Message, eth_capability: Option<&Capability>) -> Message {
+    match message {
+        Message::GetReceipts69(request) if eth_capability == Some(&Capability::eth(68)) => {
+            Message::GetReceipts68(request)
+        }
+        Message::GetReceipts69(GetReceipts68 { id, block_hashes })
+            if eth_capability == Some(&Capability::eth(70)) =>
+        {
+            Message::GetReceipts70(GetReceipts70 {
+                id,
+                first_block_receipt_index: 0,
+                block_hashes,
+            })
+        }
+        message => message,
+    }
+}
+
 async fn handle_outgoing_request(
     state: &mut Established,
     message: Message,
     sender: oneshot::Sender<Message>,
 ) -> Result<(), PeerConnectionError> {
+    let message = receipts_request_for(message, state.negotiated_eth_capability.as_ref());
     // Insert the request in the request map if it supports a request id.
     message.request_id().and_then(|id| {
         state
@@ -1793,3 +1895,66 @@ async fn retry_on_alternates(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rlpx::eth::{blocks::GetBlockBodies, receipts::GetReceipts69};
+
+    fn receipts_request() -> Message {
+        Message::GetReceipts69(GetReceipts69 {
+            id: 7,
+            block_hashes: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+        })
+    }
+
+    #[test]
+    fn receipts_requests_are_sent_as_eth_68() {
+        let message = receipts_request_for(receipts_request(), Some(&Capability::eth(68)));
+        let Message::GetReceipts68(request) = message else {
+            panic!("expected an eth/68 request, got {message}");
+        };
+        assert_eq!(request.id, 7);
+        assert_eq!(
+            request.block_hashes,
+            vec![H256::repeat_byte(1), H256::repeat_byte(2)]
+        );
+    }
+
+    #[test]
+    fn receipts_requests_are_sent_as_eth_69() {
+        let message = receipts_request_for(receipts_request(), Some(&Capability::eth(69)));
+        let Message::GetReceipts69(request) = message else {
+            panic!("expected an eth/69 request, got {message}");
+        };
+        assert_eq!(request.id, 7);
+        assert_eq!(request.block_hashes.len(), 2);
+    }
+
+    #[test]
+    fn receipts_requests_are_sent_as_eth_70_from_the_first_receipt() {
+        let message = receipts_request_for(receipts_request(), Some(&Capability::eth(70)));
+        let Message::GetReceipts70(request) = message else {
+            panic!("expected an eth/70 request, got {message}");
+        };
+        assert_eq!(request.id, 7);
+        assert_eq!(request.first_block_receipt_index, 0);
+        assert_eq!(
+            request.block_hashes,
+            vec![H256::repeat_byte(1), H256::repeat_byte(2)]
+        );
+    }
+
+    #[test]
+    fn other_requests_are_not_translated() {
+        let message = Message::GetBlockBodies(GetBlockBodies {
+            id: 3,
+            block_hashes: vec![H256::repeat_byte(1)],
+        });
+        let message = receipts_request_for(message, Some(&Capability::eth(68)));
+        assert!(matches!(
+            message,
+            Message::GetBlockBodies(GetBlockBodies { id: 3, .. })
+        ));
+    }
+}
@@ -21,6 +21,16 @@ pub enum HashOrNumber {
     Number(BlockNumber),
 }
 
+impl HashOrNumber {
+    /// Whether `header` is the block this hash or number refers to.
+    pub fn identifies(&self, header: &BlockHeader) -> bool {
+        match self {
+            HashOrNumber::Hash(hash) => header.hash() == *hash,
+            HashOrNumber::Number(number) => header.number == *number,
+        }
+    }
+}
+
 impl core::fmt::Display for HashOrNumber {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -52,6 +62,12 @@ impl From<BlockHash> for HashOrNumber {
     }
 }
 
+impl From<BlockNumber> for HashOrNumber {
+    fn from(value: BlockNumber) -> Self {
+        Self::Number(value)
+    }
+}
+
 impl RLPDecode for HashOrNumber {
     fn decode_unfinished(buf: &[u8]) -> Result<(Self, &[u8]), RLPDecodeError> {
         let first_byte = buf.first().ok_or(RLPDecodeError::InvalidLength)?;
@@ -105,7 +105,7 @@ impl StatusMessage70 {
             network_id,
             genesis,
             fork_id,
-            earliest_block: 0,
+            earliest_block: storage.get_history_cutoff().await?,
             latest_block,
             latest_block_hash,
         })
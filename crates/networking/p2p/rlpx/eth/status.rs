@@ -67,7 +67,7 @@ impl StatusDataPost68 {
             network_id,
             genesis,
             fork_id,
-            earliest_block: 0,
+            earliest_block: storage.get_history_cutoff().await?,
             latest_block,
             latest_block_hash,
         })
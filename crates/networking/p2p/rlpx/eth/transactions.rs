@@ -313,12 +313,14 @@ impl PooledTransactions {
     }
 
     /// Saves every incoming pooled transaction to the mempool.
+    /// Returns how many of them the mempool accepted.
     pub async fn handle(
         self,
         node: &Node,
         blockchain: &Blockchain,
         is_l2_mode: bool,
-    ) -> Result<(), MempoolError> {
+    ) -> Result<usize, MempoolError> {
+        let mut accepted = 0;
         for tx in self.pooled_transactions {
             if let P2PTransaction::EIP4844TransactionWithBlobs(itx) = tx {
                 if is_l2_mode {
@@ -355,8 +357,9 @@ impl PooledTransactions {
                     continue;
                 }
             }
+            accepted += 1;
         }
-        Ok(())
+        Ok(accepted)
     }
 }
 
@@ -30,7 +30,7 @@ impl BlockRangeUpdate {
         let latest_block_hash = block_header.hash();
 
         Ok(Self {
-            earliest_block: 0,
+            earliest_block: storage.get_history_cutoff().await?,
             latest_block,
             latest_block_hash,
         })
@@ -2,6 +2,8 @@ use crate::discovery::{LOOKUP_INTERVAL_MS, lookup_interval_function};
 use crate::peer_table::PeerTableServerProtocol as _;
 use crate::types::Node;
 use crate::{metrics::METRICS, network::P2PContext, rlpx::connection::server::PeerConnection};
+use ethrex_common::H256;
+use rustc_hash::FxHashMap;
 use spawned_concurrency::{
     actor,
     error::ActorError,
@@ -10,9 +12,16 @@ use spawned_concurrency::{
         Actor, ActorRef, ActorStart as _, Backend, Context, Handler, send_after, send_message_on,
     },
 };
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, error};
 
+/// How often disconnected static peers are checked for redialing.
+const STATIC_PEERS_DIAL_INTERVAL: Duration = Duration::from_secs(5);
+/// Delay before redialing a static peer after the first attempt, doubled on every further one.
+const STATIC_PEER_MIN_BACKOFF: Duration = Duration::from_secs(5);
+/// Upper bound for the delay between dials of the same static peer.
+const STATIC_PEER_MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+
 #[derive(Debug, thiserror::Error)]
 pub enum RLPxInitiatorError {
     #[error(transparent)]
@@ -22,19 +31,52 @@ pub enum RLPxInitiatorError {
 #[protocol]
 pub trait RlpxInitiatorProtocol: Send + Sync {
     fn look_for_peer(&self) -> Result<(), ActorError>;
+    fn dial_static_peers(&self) -> Result<(), ActorError>;
     fn initiate(&self, node: Node) -> Result<(), ActorError>;
     fn shutdown(&self) -> Result<(), ActorError>;
 }
 
+/// Redial schedule of a disconnected static peer.
+#[derive(Debug, Clone)]
+struct StaticDial {
+    next_attempt: Instant,
+    backoff: Duration,
+}
+
+impl StaticDial {
+    fn new(now: Instant) -> Self {
+        Self {
+            next_attempt: now,
+            backoff: STATIC_PEER_MIN_BACKOFF,
+        }
+    }
+
+    /// Returns whether the peer is due for a dial at `now`, scheduling the next attempt
+    /// after the current backoff if it is.
+    fn attempt(&mut self, now: Instant) -> bool {
+        if self.next_attempt > now {
+            return false;
+        }
+        self.next_attempt = now + self.backoff;
+        self.backoff = (self.backoff * 2).min(STATIC_PEER_MAX_BACKOFF);
+        true
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RLPxInitiator {
     context: P2PContext,
+    /// Static peers we are trying to reconnect to, reset once they're connected again.
+    static_dials: FxHashMap<H256, StaticDial>,
 }
 
 #[actor(protocol = RlpxInitiatorProtocol)]
 impl RLPxInitiator {
     pub fn new(context: P2PContext) -> Self {
-        Self { context }
+        Self {
+            context,
+            static_dials: Default::default(),
+        }
     }
 
     pub fn spawn(context: P2PContext) -> ActorRef<RLPxInitiator> {
@@ -56,6 +98,7 @@ impl RLPxInitiator {
             None => state.start(),
         };
         let _ = actor_ref.send(rlpx_initiator_protocol::LookForPeer);
+        let _ = actor_ref.send(rlpx_initiator_protocol::DialStaticPeers);
         actor_ref
     }
 
@@ -86,6 +129,24 @@ impl RLPxInitiator {
         );
     }
 
+    #[send_handler]
+    async fn handle_dial_static_peers(
+        &mut self,
+        _msg: rlpx_initiator_protocol::DialStaticPeers,
+        ctx: &Context<Self>,
+    ) {
+        let _ = self
+            .do_dial_static_peers()
+            .await
+            .inspect_err(|e| error!(err=?e, "Error dialing static peers"));
+
+        send_after(
+            STATIC_PEERS_DIAL_INTERVAL,
+            ctx.clone(),
+            rlpx_initiator_protocol::DialStaticPeers,
+        );
+    }
+
     #[send_handler]
     async fn handle_initiate(
         &mut self,
@@ -117,6 +178,29 @@ impl RLPxInitiator {
         Ok(())
     }
 
+    /// Dials every disconnected static peer whose backoff has elapsed.
+    async fn do_dial_static_peers(&mut self) -> Result<(), RLPxInitiatorError> {
+        let to_dial = self.context.table.get_static_peers_to_dial().await?;
+        // Peers that got connected (or are no longer static) start over with the minimum backoff
+        self.static_dials
+            .retain(|node_id, _| to_dial.iter().any(|node| node.node_id() == *node_id));
+
+        let now = Instant::now();
+        for node in to_dial {
+            let dial = self
+                .static_dials
+                .entry(node.node_id())
+                .or_insert_with(|| StaticDial::new(now));
+            if !dial.attempt(now) {
+                continue;
+            }
+            debug!(peer=%node, next_attempt_in=?dial.next_attempt - now, "Dialing static peer");
+            PeerConnection::spawn_as_initiator(self.context.clone(), &node);
+            METRICS.record_new_rlpx_conn_attempt().await;
+        }
+        Ok(())
+    }
+
     // We use the same lookup intervals as Discovery to try to get both process to check at the same rate
     async fn get_lookup_interval(&mut self) -> Duration {
         let peer_completion = self
@@ -132,3 +216,33 @@ impl RLPxInitiator {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn static_peers_are_dialed_right_away() {
+        let now = Instant::now();
+        let mut dial = StaticDial::new(now);
+        assert!(dial.attempt(now));
+        // Dialing again before the backoff elapsed is a no-op
+        assert!(!dial.attempt(now));
+        assert!(!dial.attempt(now + STATIC_PEER_MIN_BACKOFF - Duration::from_millis(1)));
+        assert!(dial.attempt(now + STATIC_PEER_MIN_BACKOFF));
+    }
+
+    #[test]
+    fn static_peer_backoff_doubles_up_to_the_max() {
+        let mut now = Instant::now();
+        let mut dial = StaticDial::new(now);
+        let mut delays = vec![];
+        for _ in 0..10 {
+            assert!(dial.attempt(now));
+            delays.push(dial.next_attempt - now);
+            now = dial.next_attempt;
+        }
+        let secs: Vec<_> = delays.iter().map(Duration::as_secs).collect();
+        assert_eq!(secs, [5, 10, 20, 40, 80, 160, 300, 300, 300, 300]);
+    }
+}
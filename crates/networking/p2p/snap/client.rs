@@ -242,7 +242,7 @@ pub async fn request_account_range(
 
         let tx = task_sender.clone();
 
-        if block_is_stale(pivot_header) {
+        if block_is_stale(pivot_header, block_sync_state.pinned_state_root) {
             debug!("Pivot became stale during account range download, updating pivot");
             *pivot_header = update_pivot(
                 pivot_header.number,
@@ -503,6 +503,7 @@ pub async fn request_storage_ranges(
     account_storages_snapshots_dir: &Path,
     mut chunk_index: u64,
     pivot_header: &mut BlockHeader,
+    pinned_state_root: Option<H256>,
     store: Store,
 ) -> Result<u64, SnapError> {
     METRICS
@@ -911,7 +912,7 @@ pub async fn request_storage_ranges(
             }
         }
 
-        if block_is_stale(pivot_header) {
+        if block_is_stale(pivot_header, pinned_state_root) {
             debug!("Pivot became stale during storage range download, stopping this round");
             break;
         }
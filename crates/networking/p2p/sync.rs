@@ -4,6 +4,7 @@
 //! between full sync mode (all blocks executed) and snap sync mode (state fetched
 //! via snap protocol).
 
+mod backfill;
 mod code_collector;
 mod full;
 mod healing;
@@ -11,6 +12,7 @@ mod snap_sync;
 
 use crate::metrics::METRICS;
 use crate::peer_handler::{BlockRequestOrder, PeerHandler, PeerHandlerError};
+use crate::rlpx::eth::blocks::HashOrNumber;
 use crate::snap::constants::{EXECUTE_BATCH_SIZE_DEFAULT, MIN_FULL_BLOCKS};
 use crate::utils::delete_leaves_folder;
 use ethrex_blockchain::{Blockchain, error::ChainError};
@@ -22,6 +24,7 @@ use ethrex_trie::trie_sorted::TrieGenerationError;
 use spawned_concurrency::error::ActorError;
 use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::{
     Arc,
     atomic::{AtomicBool, Ordering},
@@ -31,6 +34,7 @@ use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
+pub use backfill::backfill_history;
 // Re-export types used by submodules
 pub use snap_sync::{
     SnapBlockSyncState, block_is_stale, calculate_staleness_timestamp, update_pivot,
@@ -53,6 +57,43 @@ pub enum SyncMode {
     Snap,
 }
 
+/// Trusted (weak subjectivity) header to sync from without a consensus client, set with
+/// `--sync.checkpoint <hash|number>:<stateRoot>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncCheckpoint {
+    /// Hash or number of the trusted header.
+    pub block: HashOrNumber,
+    /// Expected state root of the trusted header, which is used as snap sync pivot.
+    pub state_root: H256,
+}
+
+impl FromStr for SyncCheckpoint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (block, state_root) = s.split_once(':').ok_or_else(|| {
+            format!("Invalid checkpoint {s:?}, expected <hash|number>:<stateRoot>")
+        })?;
+        let parse_hash = |hash: &str| {
+            H256::from_str(hash.trim_start_matches("0x"))
+                .map_err(|err| format!("Invalid hash {hash:?}: {err}"))
+        };
+        let block = if block.starts_with("0x") {
+            HashOrNumber::Hash(parse_hash(block)?)
+        } else {
+            HashOrNumber::Number(
+                block
+                    .parse()
+                    .map_err(|err| format!("Invalid block number {block:?}: {err}"))?,
+            )
+        };
+        Ok(Self {
+            block,
+            state_root: parse_hash(state_root)?,
+        })
+    }
+}
+
 /// Diagnostic snapshot of the sync state, used by admin RPC endpoints.
 #[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct SyncDiagnostics {
@@ -65,6 +106,18 @@ pub struct SyncDiagnostics {
     pub phase_progress: std::collections::HashMap<String, u64>,
     pub recent_pivot_changes: std::collections::VecDeque<PivotChangeEvent>,
     pub recent_errors: std::collections::VecDeque<SyncErrorEvent>,
+    /// Progress of the history backfill that follows snap sync, if one ran.
+    pub backfill: Option<BackfillProgress>,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct BackfillProgress {
+    /// Next block whose body and receipts will be downloaded, `None` once done.
+    pub next_block: Option<u64>,
+    /// Lowest block that will be backfilled.
+    pub target_block: u64,
+    /// Blocks backfilled since the node started.
+    pub blocks_backfilled: u64,
 }
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -117,6 +170,8 @@ pub struct Syncer {
     /// used during the syncing process
     datadir: PathBuf,
     diagnostics: Arc<tokio::sync::RwLock<SyncDiagnostics>>,
+    /// State root of the `--sync.checkpoint` header, whose pivot never becomes stale
+    pinned_state_root: Option<H256>,
 }
 
 impl Syncer {
@@ -127,6 +182,7 @@ impl Syncer {
         blockchain: Arc<Blockchain>,
         datadir: PathBuf,
         diagnostics: Arc<tokio::sync::RwLock<SyncDiagnostics>>,
+        pinned_state_root: Option<H256>,
     ) -> Self {
         Self {
             snap_enabled,
@@ -135,6 +191,7 @@ impl Syncer {
             blockchain,
             datadir,
             diagnostics,
+            pinned_state_root,
         }
     }
 
@@ -145,7 +202,7 @@ impl Syncer {
     /// After the sync cycle is complete, the sync mode will be set to full
     /// If the sync fails, no error will be returned but a warning will be emitted
     /// [WARNING] Sync is done optimistically, so headers and bodies may be stored even if their data has not been fully synced if the sync is aborted halfway
-    /// [WARNING] Sync is currenlty simplified and will not download bodies + receipts previous to the pivot during snap sync
+    /// Bodies and receipts previous to the pivot of a snap sync are downloaded afterwards by the history backfill
     pub async fn start_sync(&mut self, sync_head: H256, store: Store) {
         let start_time = Instant::now();
         match self.sync_cycle(sync_head, store).await {
@@ -248,6 +305,7 @@ impl Syncer {
                 store,
                 &self.datadir,
                 &self.diagnostics,
+                self.pinned_state_root,
             )
             .await;
             METRICS.disable().await;
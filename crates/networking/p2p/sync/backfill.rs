@@ -0,0 +1,108 @@
+//! History backfill after snap sync
+//!
+//! Snap sync only downloads the headers of the blocks below the pivot. Once the node is
+//! following the head, this task downloads their bodies and receipts backwards from the
+//! pivot, validates them against the stored headers and stores them. Progress is kept in
+//! the store, so the backfill resumes where it left off after a restart.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethrex_storage::Store;
+use tokio::time::sleep;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::peer_handler::PeerHandler;
+
+use super::{BackfillProgress, SyncDiagnostics, SyncError};
+
+/// Maximum number of blocks whose history is requested at once.
+const BACKFILL_BATCH_SIZE: u64 = 128;
+/// Pause between batches, so the backfill doesn't compete with block processing.
+const BACKFILL_BATCH_INTERVAL: Duration = Duration::from_millis(500);
+/// Pause before retrying when a sync cycle is running or no peer served the history.
+const BACKFILL_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Downloads the bodies and receipts of the blocks skipped by snap sync, from the block
+/// set with [`Store::set_backfill_block`] down to the history cutoff.
+/// Backs off while `is_syncing` returns true.
+pub async fn backfill_history(
+    mut peers: PeerHandler,
+    store: Store,
+    diagnostics: Arc<tokio::sync::RwLock<SyncDiagnostics>>,
+    cancel_token: CancellationToken,
+    is_syncing: impl Fn() -> bool,
+) -> Result<(), SyncError> {
+    let mut blocks_backfilled = 0;
+    loop {
+        if cancel_token.is_cancelled() {
+            return Ok(());
+        }
+        if is_syncing() {
+            sleep(BACKFILL_RETRY_INTERVAL).await;
+            continue;
+        }
+        // Blocks below the cutoff would be pruned right away
+        let target_block = store.get_history_cutoff().await?.max(1);
+        let next_block = match store.get_backfill_block().await? {
+            Some(next_block) if next_block >= target_block => next_block,
+            Some(_) => {
+                store.set_backfill_block(None).await?;
+                None
+            }
+            None => None,
+        };
+        diagnostics.write().await.backfill = Some(BackfillProgress {
+            next_block,
+            target_block,
+            blocks_backfilled,
+        });
+        let Some(next_block) = next_block else {
+            info!(blocks_backfilled, "History backfill finished");
+            return Ok(());
+        };
+
+        // Newest first, so a partial response still extends the backfilled range
+        let first_block = next_block
+            .saturating_sub(BACKFILL_BATCH_SIZE - 1)
+            .max(target_block);
+        let mut headers = Vec::new();
+        for number in (first_block..=next_block).rev() {
+            headers.push(
+                store
+                    .get_block_header(number)?
+                    .ok_or(SyncError::CorruptDB)?,
+            );
+        }
+        let Some(bodies) = peers.request_block_bodies(&headers).await? else {
+            debug!("No peer served the bodies of blocks {first_block}..={next_block}");
+            sleep(BACKFILL_RETRY_INTERVAL).await;
+            continue;
+        };
+        let Some(receipts) = peers.request_receipts(&headers[..bodies.len()]).await? else {
+            debug!("No peer served the receipts of blocks {first_block}..={next_block}");
+            sleep(BACKFILL_RETRY_INTERVAL).await;
+            continue;
+        };
+
+        let count = receipts.len() as u64;
+        let lowest_block = next_block + 1 - count;
+        let blocks = headers
+            .iter()
+            .zip(bodies)
+            .zip(receipts)
+            .map(|((header, body), receipts)| (header.number, header.hash(), body, receipts))
+            .collect();
+        store
+            .add_backfilled_blocks(
+                blocks,
+                (lowest_block > target_block).then(|| lowest_block - 1),
+            )
+            .await?;
+        blocks_backfilled += count;
+        debug!("Backfilled the history of blocks {lowest_block}..={next_block}");
+
+        sleep(BACKFILL_BATCH_INTERVAL).await;
+    }
+}
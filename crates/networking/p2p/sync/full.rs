@@ -442,9 +442,11 @@ async fn add_blocks_in_batch(
             vec![None; blocks.len()]
         }
     };
-    // Run the batch
+    // Run the batch. Archive nodes execute block by block so the state of every block
+    // gets its own diff-layer, and thus its own entry in the state history.
+    let sequential = final_batch || store.is_archive();
     if let Err((err, batch_failure)) =
-        add_blocks(blockchain.clone(), blocks, bals, final_batch, cancel_token).await
+        add_blocks(blockchain.clone(), blocks, bals, sequential, cancel_token).await
     {
         if let Some(batch_failure) = batch_failure {
             warn!("Failed to add block during FullSync: {err}");
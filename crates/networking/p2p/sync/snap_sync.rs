@@ -54,13 +54,16 @@ use ethrex_rlp::encode::RLPEncode;
 #[derive(Clone)]
 pub struct SnapBlockSyncState {
     pub block_hashes: Vec<H256>,
+    /// State root of the trusted checkpoint header (`--sync.checkpoint`), see [`block_is_stale`].
+    pub pinned_state_root: Option<H256>,
     store: Store,
 }
 
 impl SnapBlockSyncState {
-    pub fn new(store: Store) -> Self {
+    pub fn new(store: Store, pinned_state_root: Option<H256>) -> Self {
         Self {
             block_hashes: Vec::new(),
+            pinned_state_root,
             store,
         }
     }
@@ -108,11 +111,12 @@ pub async fn sync_cycle_snap(
     store: Store,
     datadir: &Path,
     diagnostics: &Arc<tokio::sync::RwLock<super::SyncDiagnostics>>,
+    pinned_state_root: Option<H256>,
 ) -> Result<(), SyncError> {
     // Request all block headers between the current head and the sync head
     // We will begin from the current head so that we download the earliest state first
     // This step is not parallelized
-    let mut block_sync_state = SnapBlockSyncState::new(store.clone());
+    let mut block_sync_state = SnapBlockSyncState::new(store.clone(), pinned_state_root);
     // Check if we have some blocks downloaded from a previous sync attempt
     // This applies only to snap sync—full sync always starts fetching headers
     // from the canonical block, which updates as new block headers are fetched.
@@ -290,8 +294,9 @@ pub async fn snap_sync(
     let mut pivot_header = store
         .get_block_header_by_hash(*pivot_hash)?
         .ok_or(SyncError::CorruptDB)?;
+    let pinned_state_root = block_sync_state.pinned_state_root;
 
-    while block_is_stale(&pivot_header) {
+    while block_is_stale(&pivot_header, pinned_state_root) {
         pivot_header = update_pivot(
             pivot_header.number,
             pivot_header.timestamp,
@@ -392,7 +397,7 @@ pub async fn snap_sync(
         let mut state_leafs_healed = 0_u64;
         let mut storage_range_request_attempts = 0;
         loop {
-            while block_is_stale(&pivot_header) {
+            while block_is_stale(&pivot_header, pinned_state_root) {
                 pivot_header = update_pivot(
                     pivot_header.number,
                     pivot_header.timestamp,
@@ -408,7 +413,7 @@ pub async fn snap_sync(
                 pivot_header.state_root,
                 store.clone(),
                 peers,
-                calculate_staleness_timestamp(pivot_header.timestamp),
+                calculate_staleness_timestamp(&pivot_header, pinned_state_root),
                 &mut state_leafs_healed,
                 &mut storage_accounts,
                 &mut code_hash_collector,
@@ -430,6 +435,7 @@ pub async fn snap_sync(
                     account_storages_snapshots_dir.as_ref(),
                     chunk_index,
                     &mut pivot_header,
+                    pinned_state_root,
                     store.clone(),
                 )
                 .await?;
@@ -465,7 +471,7 @@ pub async fn snap_sync(
                 // because we don't know if the storage root is still valid
                 storage_accounts.healed_accounts.len(),
             );
-            if !block_is_stale(&pivot_header) {
+            if !block_is_stale(&pivot_header, pinned_state_root) {
                 break;
             }
             debug!("Pivot became stale during storage download, restarting loop");
@@ -501,7 +507,7 @@ pub async fn snap_sync(
     let mut healing_done = false;
     while !healing_done {
         // This if is an edge case for the skip snap sync scenario
-        if block_is_stale(&pivot_header) {
+        if block_is_stale(&pivot_header, pinned_state_root) {
             pivot_header = update_pivot(
                 pivot_header.number,
                 pivot_header.timestamp,
@@ -515,7 +521,7 @@ pub async fn snap_sync(
             pivot_header.state_root,
             store.clone(),
             peers,
-            calculate_staleness_timestamp(pivot_header.timestamp),
+            calculate_staleness_timestamp(&pivot_header, pinned_state_root),
             &mut global_state_leafs_healed,
             &mut storage_accounts,
             &mut code_hash_collector,
@@ -530,7 +536,7 @@ pub async fn snap_sync(
             peers,
             store.clone(),
             HashMap::new(),
-            calculate_staleness_timestamp(pivot_header.timestamp),
+            calculate_staleness_timestamp(&pivot_header, pinned_state_root),
             &mut global_storage_leafs_healed,
         )
         .await?;
@@ -641,6 +647,11 @@ pub async fn snap_sync(
             None,
         )
         .await?;
+    // Only the pivot body was downloaded, its receipts and the history of the blocks
+    // below it are downloaded in the background once the node follows the head
+    store
+        .set_backfill_block(Some(pivot_header.number).filter(|number| *number > 0))
+        .await?;
     Ok(())
 }
 
@@ -849,8 +860,10 @@ pub async fn update_pivot(
     }
 }
 
-pub fn block_is_stale(block_header: &BlockHeader) -> bool {
-    let threshold = calculate_staleness_timestamp(block_header.timestamp);
+/// A pivot with the `pinned_state_root` of the trusted checkpoint header (`--sync.checkpoint`)
+/// never becomes stale, as the state is synced from the trusted header alone.
+pub fn block_is_stale(block_header: &BlockHeader, pinned_state_root: Option<H256>) -> bool {
+    let threshold = calculate_staleness_timestamp(block_header, pinned_state_root);
     let now = current_unix_time();
     let is_stale = threshold < now;
     if is_stale {
@@ -867,8 +880,14 @@ pub fn block_is_stale(block_header: &BlockHeader) -> bool {
     is_stale
 }
 
-pub fn calculate_staleness_timestamp(timestamp: u64) -> u64 {
-    timestamp + (SNAP_LIMIT as u64 * 12)
+pub fn calculate_staleness_timestamp(
+    block_header: &BlockHeader,
+    pinned_state_root: Option<H256>,
+) -> u64 {
+    if pinned_state_root == Some(block_header.state_root) {
+        return u64::MAX;
+    }
+    block_header.timestamp + (SNAP_LIMIT as u64 * 12)
 }
 
 pub async fn validate_state_root(store: Store, state_root: H256) -> bool {
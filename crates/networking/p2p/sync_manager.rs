@@ -7,7 +7,7 @@ use std::{
 };
 
 use ethrex_blockchain::Blockchain;
-use ethrex_common::H256;
+use ethrex_common::{H256, types::BlockHeader};
 use ethrex_storage::Store;
 use tokio::{
     sync::Mutex,
@@ -17,12 +17,17 @@ use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use crate::{
-    peer_handler::PeerHandler,
-    sync::{SyncDiagnostics, SyncMode, Syncer},
+    peer_handler::{BlockRequestOrder, PeerHandler},
+    rlpx::eth::blocks::{BLOCK_HEADER_LIMIT, HashOrNumber},
+    sync::{SyncCheckpoint, SyncDiagnostics, SyncMode, Syncer, backfill_history},
 };
 
+/// Interval between checks for the checkpoint header and for new blocks of the peers
+/// when syncing from a checkpoint.
+const CHECKPOINT_POLL_INTERVAL: Duration = Duration::from_secs(12);
+
 /// Abstraction to interact with the active sync process without disturbing it
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SyncManager {
     /// This is also held by the Syncer and allows tracking it's latest syncmode
     /// It is a READ_ONLY value, as modifications will disrupt the current active sync progress
@@ -31,6 +36,11 @@ pub struct SyncManager {
     last_fcu_head: Arc<Mutex<H256>>,
     store: Store,
     diagnostics: Arc<tokio::sync::RwLock<SyncDiagnostics>>,
+    /// Used by the history backfill, which runs alongside the syncer
+    peers: PeerHandler,
+    cancel_token: CancellationToken,
+    /// Whether the history backfill task is running
+    backfill_active: Arc<AtomicBool>,
 }
 
 impl SyncManager {
@@ -41,6 +51,7 @@ impl SyncManager {
         blockchain: Arc<Blockchain>,
         store: Store,
         datadir: PathBuf,
+        checkpoint: Option<SyncCheckpoint>,
     ) -> Self {
         let snap_enabled = Arc::new(AtomicBool::new(matches!(sync_mode, SyncMode::Snap)));
 
@@ -79,12 +90,13 @@ impl SyncManager {
 
         let diagnostics = Arc::new(tokio::sync::RwLock::new(SyncDiagnostics::default()));
         let syncer = Arc::new(Mutex::new(Syncer::new(
-            peer_handler,
+            peer_handler.clone(),
             snap_enabled.clone(),
-            cancel_token,
+            cancel_token.clone(),
             blockchain,
             datadir,
             diagnostics.clone(),
+            checkpoint.map(|checkpoint| checkpoint.state_root),
         )));
         let sync_manager = Self {
             snap_enabled,
@@ -92,6 +104,9 @@ impl SyncManager {
             last_fcu_head: Arc::new(Mutex::new(H256::zero())),
             store: store.clone(),
             diagnostics,
+            peers: peer_handler,
+            cancel_token,
+            backfill_active: Arc::new(AtomicBool::new(false)),
         };
         // If the node was in the middle of a sync and then re-started we must resume syncing
         // Otherwise we will incorreclty assume the node is already synced and work on invalid state
@@ -99,6 +114,11 @@ impl SyncManager {
         if has_checkpoint && sync_manager.snap_enabled.load(Ordering::Relaxed) {
             sync_manager.start_sync();
         }
+        // Resume the history backfill of a previous snap sync
+        sync_manager.start_backfill();
+        if let Some(checkpoint) = checkpoint {
+            tokio::spawn(sync_manager.clone().follow_checkpoint(checkpoint));
+        }
         sync_manager
     }
 
@@ -197,6 +217,7 @@ impl SyncManager {
         let syncer = self.syncer.clone();
         let store = self.store.clone();
         let sync_head = self.last_fcu_head.clone();
+        let backfill = self.backfill_starter();
 
         tokio::spawn(async move {
             // If we can't get hold of the syncer, then it means that there is an active sync in process
@@ -241,10 +262,220 @@ impl SyncManager {
                     break;
                 }
             }
+            // Snap sync may have left history to backfill now that we follow the head
+            backfill.start();
         });
     }
 
+    /// Starts the history backfill in the background, unless it's already running
+    fn start_backfill(&self) {
+        self.backfill_starter().start();
+    }
+
+    fn backfill_starter(&self) -> BackfillStarter {
+        BackfillStarter {
+            peers: self.peers.clone(),
+            store: self.store.clone(),
+            syncer: self.syncer.clone(),
+            diagnostics: self.diagnostics.clone(),
+            cancel_token: self.cancel_token.clone(),
+            active: self.backfill_active.clone(),
+        }
+    }
+
+    /// Syncs to the trusted `checkpoint` header and then keeps following the chain of the
+    /// peers, for networks without a consensus client driving the sync.
+    async fn follow_checkpoint(self, checkpoint: SyncCheckpoint) {
+        let mut peers = self.peers.clone();
+        let checkpoint_header = loop {
+            if self.cancel_token.is_cancelled() {
+                return;
+            }
+            // Headers that don't match the checkpoint hash or number are discarded by the
+            // peer handler, so a wrong or malicious peer only costs a retry with another one
+            let header = peers
+                .request_block_header(checkpoint.block)
+                .await
+                .inspect_err(|err| warn!("Failed to request checkpoint header: {err}"))
+                .ok()
+                .flatten();
+            match header {
+                Some(header) if header.state_root == checkpoint.state_root => {
+                    info!(
+                        number = header.number,
+                        hash = %header.hash(),
+                        "Syncing from checkpoint"
+                    );
+                    break header;
+                }
+                Some(header) if matches!(checkpoint.block, HashOrNumber::Hash(_)) => {
+                    // The hash authenticates the header, so the checkpoint is wrong
+                    error!(
+                        expected = %checkpoint.state_root,
+                        found = %header.state_root,
+                        "Checkpoint state root mismatch, not syncing from checkpoint"
+                    );
+                    return;
+                }
+                Some(header) => {
+                    warn!(
+                        number = header.number,
+                        "Peer sent a checkpoint header with a different state root, retrying"
+                    );
+                }
+                None => debug!("Checkpoint header not available yet, retrying"),
+            }
+            sleep(CHECKPOINT_POLL_INTERVAL).await;
+        };
+        let latest = self
+            .store
+            .get_latest_block_number()
+            .await
+            .unwrap_or_default();
+        if latest < checkpoint_header.number {
+            self.sync_to_head(checkpoint_header.hash());
+        }
+
+        // Follow the chain of the peers
+        loop {
+            sleep(CHECKPOINT_POLL_INTERVAL).await;
+            if self.cancel_token.is_cancelled() {
+                return;
+            }
+            if self.is_active() {
+                continue;
+            }
+            let Ok(latest) = self.store.get_latest_block_number().await else {
+                continue;
+            };
+            if let Some(head) = request_peer_head(&mut peers, latest).await
+                && head.number > latest
+            {
+                debug!(number = head.number, "Syncing to peer head");
+                self.sync_to_head(head.hash());
+            }
+        }
+    }
+
     pub fn get_last_fcu_head(&self) -> Result<H256, tokio::sync::TryLockError> {
         Ok(*self.last_fcu_head.try_lock()?)
     }
 }
+
+/// Follows the chain of the peers from block `start` and returns the last header found.
+async fn request_peer_head(peers: &mut PeerHandler, start: u64) -> Option<BlockHeader> {
+    follow_headers(start, |from| {
+        let mut peers = peers.clone();
+        async move {
+            peers
+                .request_block_headers_from_hash(from, BlockRequestOrder::OldToNew)
+                .await
+                .ok()
+                .flatten()
+        }
+    })
+    .await
+}
+
+/// Requests batches of headers from block `start` on, each starting at the last header of
+/// the previous one, until a batch isn't full. Returns the last header found, or `None` if
+/// a request fails.
+async fn follow_headers<F, Fut>(start: u64, mut request_headers: F) -> Option<BlockHeader>
+where
+    F: FnMut(u64) -> Fut,
+    Fut: Future<Output = Option<Vec<BlockHeader>>>,
+{
+    let mut head: Option<BlockHeader> = None;
+    loop {
+        let from = head.as_ref().map_or(start, |head| head.number);
+        let headers = request_headers(from).await?;
+        let full_batch = headers.len() as u64 >= BLOCK_HEADER_LIMIT;
+        head = headers.into_iter().last().or(head);
+        if !full_batch {
+            return head;
+        }
+    }
+}
+
+/// Everything needed to start the history backfill from within a sync task
+struct BackfillStarter {
+    peers: PeerHandler,
+    store: Store,
+    syncer: Arc<Mutex<Syncer>>,
+    diagnostics: Arc<tokio::sync::RwLock<SyncDiagnostics>>,
+    cancel_token: CancellationToken,
+    active: Arc<AtomicBool>,
+}
+
+impl BackfillStarter {
+    fn start(self) {
+        if self.active.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        tokio::spawn(async move {
+            match self.store.get_backfill_block().await {
+                Ok(Some(_)) => {
+                    info!("Starting history backfill");
+                    let syncer = self.syncer;
+                    if let Err(err) = backfill_history(
+                        self.peers,
+                        self.store,
+                        self.diagnostics,
+                        self.cancel_token,
+                        // Back off while the syncer is busy
+                        move || syncer.try_lock().is_err(),
+                    )
+                    .await
+                    {
+                        error!("History backfill failed: {err}");
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => warn!("Failed to read history backfill progress: {err}"),
+            }
+            self.active.store(false, Ordering::Release);
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serves the headers of a chain of `len` blocks, `BLOCK_HEADER_LIMIT` at a time.
+    fn chain(len: u64) -> impl FnMut(u64) -> std::future::Ready<Option<Vec<BlockHeader>>> {
+        move |from| {
+            let to = (from + BLOCK_HEADER_LIMIT).min(len);
+            let headers = (from..to)
+                .map(|number| BlockHeader {
+                    number,
+                    ..Default::default()
+                })
+                .collect();
+            std::future::ready(Some(headers))
+        }
+    }
+
+    #[tokio::test]
+    async fn follow_headers_reaches_the_head_across_batches() {
+        let len = 2 * BLOCK_HEADER_LIMIT + 10;
+        let head = follow_headers(5, chain(len)).await;
+        assert_eq!(head.map(|head| head.number), Some(len - 1));
+    }
+
+    #[tokio::test]
+    async fn follow_headers_stops_at_a_head_ending_a_full_batch() {
+        // The second request only returns the head again
+        let len = BLOCK_HEADER_LIMIT;
+        let head = follow_headers(0, chain(len)).await;
+        assert_eq!(head.map(|head| head.number), Some(len - 1));
+    }
+
+    #[tokio::test]
+    async fn follow_headers_without_headers() {
+        let head = follow_headers(3, |_| std::future::ready(Some(vec![]))).await;
+        assert!(head.is_none());
+        let head = follow_headers(3, |_| std::future::ready(None)).await;
+        assert!(head.is_none());
+    }
+}
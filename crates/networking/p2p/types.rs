@@ -112,6 +112,9 @@ pub struct Node {
     pub tcp_port: u16,
     pub public_key: H512,
     pub version: Option<String>,
+    /// Endpoint of the other address family, for dual-stack nodes announcing both `ip` and
+    /// `ip6` in their ENR.
+    pub dual_stack_endpoint: Option<Endpoint>,
     node_id: OnceLock<H256>,
 }
 
@@ -170,10 +173,17 @@ impl Node {
             tcp_port,
             public_key,
             version: None,
+            dual_stack_endpoint: None,
             node_id: OnceLock::new(),
         }
     }
 
+    /// Adds an endpoint of the other address family, making the node dual-stack.
+    pub fn with_dual_stack_endpoint(mut self, endpoint: Endpoint) -> Self {
+        self.dual_stack_endpoint = Some(endpoint);
+        self
+    }
+
     pub fn client_name(&self) -> &str {
         self.version
             .as_deref()
@@ -188,11 +198,16 @@ impl Node {
     }
 
     pub fn from_enode_url(enode: &str) -> Result<Self, NodeError> {
-        let public_key = H512::from_str(&enode[8..136])
-            .map_err(|_| NodeError::ParseError("Could not parse public_key".into()))?;
+        let public_key = enode
+            .get(8..136)
+            .and_then(|public_key| H512::from_str(public_key).ok())
+            .ok_or_else(|| NodeError::ParseError("Could not parse public_key".into()))?;
 
+        // IPv6 addresses are enclosed in brackets, e.g. `[::1]:30303`
         let address_start = 137;
-        let address_part = &enode[address_start..];
+        let address_part = enode
+            .get(address_start..)
+            .ok_or_else(|| NodeError::ParseError("Could not parse socket address".into()))?;
 
         // Remove `?discport=` if present
         let address_part = match address_part.find('?') {
@@ -233,51 +248,135 @@ impl Node {
         let encoded = verifying_key.serialize_uncompressed();
         let public_key = H512::from_slice(&encoded[1..]);
 
-        let ip: IpAddr = match (pairs.ip, pairs.ip6) {
+        // both udp and tcp can be defined in the pairs or only one
+        // in the latter case, we have to default both ports to the one provided
+        let udp_port = pairs.udp_port.or(pairs.tcp_port);
+        let tcp_port = pairs.tcp_port.or(pairs.udp_port);
+        let missing_port = || NodeError::MissingField("No port found in record".into());
+        // `udp6` and `tcp6` default to `udp` and `tcp`
+        let ipv6_endpoint = |ip6: Ipv6Addr| -> Result<Endpoint, NodeError> {
+            Ok(Endpoint {
+                ip: IpAddr::V6(ip6),
+                udp_port: pairs
+                    .udp6_port
+                    .or(udp_port)
+                    .or(pairs.tcp6_port)
+                    .ok_or_else(missing_port)?,
+                tcp_port: pairs
+                    .tcp6_port
+                    .or(tcp_port)
+                    .or(pairs.udp6_port)
+                    .ok_or_else(missing_port)?,
+            })
+        };
+
+        // IPv4 is the primary address of dual-stack nodes
+        let (primary, dual_stack_endpoint) = match (pairs.ip, pairs.ip6) {
             (None, None) => {
                 return Err(NodeError::MissingField(
                     "Ip not found in record, can't construct node".into(),
                 ));
             }
-            (None, Some(ipv6)) => IpAddr::from(ipv6),
-            (Some(ipv4), None) => IpAddr::from(ipv4),
-            (Some(ipv4), Some(_ipv6)) => IpAddr::from(ipv4),
+            (None, Some(ipv6)) => (ipv6_endpoint(ipv6)?, None),
+            (Some(ipv4), ipv6) => (
+                Endpoint {
+                    ip: IpAddr::V4(ipv4),
+                    udp_port: udp_port.ok_or_else(missing_port)?,
+                    tcp_port: tcp_port.ok_or_else(missing_port)?,
+                },
+                ipv6.map(ipv6_endpoint).transpose()?,
+            ),
         };
 
-        // both udp and tcp can be defined in the pairs or only one
-        // in the latter case, we have to default both ports to the one provided
-        let udp_port = pairs
-            .udp_port
-            .or(pairs.tcp_port)
-            .ok_or(NodeError::MissingField("No port found in record".into()))?;
-        let tcp_port = pairs
-            .tcp_port
-            .or(pairs.udp_port)
-            .ok_or(NodeError::MissingField("No port found in record".into()))?;
-
-        Ok(Self::new(ip, udp_port, tcp_port, public_key))
+        let mut node = Self::new(primary.ip, primary.udp_port, primary.tcp_port, public_key);
+        node.dual_stack_endpoint = dual_stack_endpoint;
+        Ok(node)
     }
 
     pub fn enode_url(&self) -> String {
         let public_key = hex::encode(self.public_key);
-        let node_ip = self.ip;
         let discovery_port = self.udp_port;
-        let listener_port = self.tcp_port;
-        if discovery_port != listener_port {
-            format!("enode://{public_key}@{node_ip}:{listener_port}?discport={discovery_port}")
+        let listener_addr = self.tcp_addr();
+        if discovery_port != listener_addr.port() {
+            format!("enode://{public_key}@{listener_addr}?discport={discovery_port}")
         } else {
-            format!("enode://{public_key}@{node_ip}:{listener_port}")
+            format!("enode://{public_key}@{listener_addr}")
         }
     }
 
     pub fn udp_addr(&self) -> SocketAddr {
-        // Nodes that use ipv6 currently are only ipv4 masked addresses, so we can convert it to an ipv4 address.
-        // If in the future we have real ipv6 nodes, we will need to handle them differently.
+        // IPv4-mapped IPv6 addresses are reported by dual-stack sockets for IPv4 peers
         SocketAddr::new(self.ip.to_canonical(), self.udp_port)
     }
 
     pub fn tcp_addr(&self) -> SocketAddr {
-        SocketAddr::new(self.ip, self.tcp_port)
+        SocketAddr::new(self.ip.to_canonical(), self.tcp_port)
+    }
+
+    /// Whether `ip` is one of the node's addresses.
+    pub fn has_ip(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.ip.to_canonical() == ip
+            || self
+                .dual_stack_endpoint
+                .is_some_and(|endpoint| endpoint.ip.to_canonical() == ip)
+    }
+
+    /// Returns the node with its address of the requested family as the primary one, or
+    /// `None` if it has no such address.
+    pub fn in_family(&self, ipv6: bool) -> Option<Node> {
+        if self.ip.to_canonical().is_ipv6() == ipv6 {
+            return Some(self.clone());
+        }
+        let endpoint = self
+            .dual_stack_endpoint
+            .filter(|endpoint| endpoint.ip.to_canonical().is_ipv6() == ipv6)?;
+        let mut node = self.clone();
+        node.ip = endpoint.ip;
+        node.udp_port = endpoint.udp_port;
+        node.tcp_port = endpoint.tcp_port;
+        node.dual_stack_endpoint = Some(Endpoint {
+            ip: self.ip,
+            udp_port: self.udp_port,
+            tcp_port: self.tcp_port,
+        });
+        Some(node)
+    }
+
+    /// Returns the UDP address of the node that a socket bound to `local` can send to.
+    pub fn udp_addr_from(&self, local: IpAddr) -> Option<SocketAddr> {
+        self.endpoint_from(local)
+            .map(|endpoint| SocketAddr::new(endpoint.ip, endpoint.udp_port))
+    }
+
+    /// Returns the TCP address of the node that a socket bound to `local` can connect to.
+    pub fn tcp_addr_from(&self, local: IpAddr) -> Option<SocketAddr> {
+        self.endpoint_from(local)
+            .map(|endpoint| SocketAddr::new(endpoint.ip, endpoint.tcp_port))
+    }
+
+    /// Picks the primary endpoint if it's reachable from `local`, else the dual-stack one.
+    /// Sockets bound to `::` are dual-stack and reach IPv4 nodes through IPv4-mapped
+    /// addresses.
+    fn endpoint_from(&self, local: IpAddr) -> Option<Endpoint> {
+        let primary = Endpoint {
+            ip: self.ip,
+            udp_port: self.udp_port,
+            tcp_port: self.tcp_port,
+        };
+        std::iter::once(primary)
+            .chain(self.dual_stack_endpoint)
+            .find_map(|endpoint| {
+                let ip = match (local.to_canonical(), endpoint.ip.to_canonical()) {
+                    (IpAddr::V4(_), ip @ IpAddr::V4(_)) => ip,
+                    (IpAddr::V6(local), IpAddr::V4(ip)) if local.is_unspecified() => {
+                        IpAddr::V6(ip.to_ipv6_mapped())
+                    }
+                    (IpAddr::V6(_), ip @ IpAddr::V6(_)) => ip,
+                    _ => return None,
+                };
+                Some(Endpoint { ip, ..endpoint })
+            })
     }
 
     pub fn node_id(&self) -> H256 {
@@ -288,11 +387,10 @@ impl Node {
 impl Display for Node {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&format!(
-            "{0} #{1}({2}:{3})",
+            "{0} #{1}({2})",
             self.client_name(),
             self.node_id(),
-            self.ip,
-            self.tcp_port
+            self.tcp_addr()
         ))
     }
 }
@@ -310,13 +408,15 @@ pub struct NodeRecordPairs {
     // I think the confusion comes from the fact that geth decodes the bytes and then builds an IPV4/6 big-integer structure.
     pub tcp_port: Option<u16>,
     pub udp_port: Option<u16>,
+    /// IPv6-specific ports, which default to `tcp_port` and `udp_port` when omitted.
+    pub tcp6_port: Option<u16>,
+    pub udp6_port: Option<u16>,
     pub secp256k1: Option<H264>,
     // https://github.com/ethereum/devp2p/blob/master/enr-entries/eth.md
     pub eth: Option<ForkId>,
     // Snap entry is being used by some tests such as `test_encode_enr_response`.
     pub snap: Option<Vec<u32>>,
     pub other: Vec<(Bytes, Bytes)>,
-    // TODO implement ipv6 specific ports
 }
 
 impl NodeRecordPairs {
@@ -331,6 +431,8 @@ impl NodeRecordPairs {
                 b"ip6" => decoded_pairs.ip6 = Some(Ipv6Addr::decode(&value)?),
                 b"tcp" => decoded_pairs.tcp_port = Some(u16::decode(&value)?),
                 b"udp" => decoded_pairs.udp_port = Some(u16::decode(&value)?),
+                b"tcp6" => decoded_pairs.tcp6_port = Some(u16::decode(&value)?),
+                b"udp6" => decoded_pairs.udp6_port = Some(u16::decode(&value)?),
                 b"secp256k1" => decoded_pairs.secp256k1 = Some(H264(<[u8; 33]>::decode(&value)?)),
                 b"snap" => decoded_pairs.snap = Some(Vec::<u32>::decode(&value)?),
                 b"eth" => {
@@ -354,6 +456,15 @@ impl NodeRecordPairs {
         Ok(decoded_pairs)
     }
 
+    /// Whether the record announces an address of the given family.
+    pub fn has_family(&self, ipv6: bool) -> bool {
+        if ipv6 {
+            self.ip6.is_some()
+        } else {
+            self.ip.is_some()
+        }
+    }
+
     /// Encodes to a list of (key, value) where keys are ascii bytes and values are rlp encoded bytes.
     pub fn encode_pairs(&self) -> Vec<(Bytes, Bytes)> {
         // The key/value pairs must be sorted by key and must be unique
@@ -388,6 +499,12 @@ impl NodeRecordPairs {
         if let Some(udp) = self.udp_port {
             pairs.push(("udp".into(), udp.encode_to_vec().into()));
         }
+        if let Some(tcp6) = self.tcp6_port {
+            pairs.push(("tcp6".into(), tcp6.encode_to_vec().into()));
+        }
+        if let Some(udp6) = self.udp6_port {
+            pairs.push(("udp6".into(), udp6.encode_to_vec().into()));
+        }
         pairs.extend(self.other.clone());
         pairs.sort_by(|(left_key, _), (right_key, _)| left_key.cmp(right_key));
         pairs
@@ -434,9 +551,25 @@ impl NodeRecord {
             udp_port: Some(node.udp_port),
             ..Default::default()
         };
-        match node.ip.to_canonical() {
-            IpAddr::V4(ip) => pairs.ip = Some(ip),
-            IpAddr::V6(ip) => pairs.ip6 = Some(ip),
+        let endpoints = std::iter::once(Endpoint {
+            ip: node.ip,
+            udp_port: node.udp_port,
+            tcp_port: node.tcp_port,
+        })
+        .chain(node.dual_stack_endpoint);
+        for endpoint in endpoints {
+            match endpoint.ip.to_canonical() {
+                IpAddr::V4(ip) => {
+                    pairs.ip = Some(ip);
+                    pairs.tcp_port = Some(endpoint.tcp_port);
+                    pairs.udp_port = Some(endpoint.udp_port);
+                }
+                IpAddr::V6(ip) => {
+                    pairs.ip6 = Some(ip);
+                    pairs.tcp6_port = Some(endpoint.tcp_port);
+                    pairs.udp6_port = Some(endpoint.udp_port);
+                }
+            }
         }
 
         let mut record = NodeRecord {
@@ -10,7 +10,9 @@ use crate::{
     utils::{RpcErr, RpcRequest},
 };
 mod peers;
-pub use peers::{add_peer, peer_scores, peers, sync_status};
+pub use peers::{
+    add_peer, add_trusted_peer, peer_scores, peers, remove_peer, remove_trusted_peer, sync_status,
+};
 
 #[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
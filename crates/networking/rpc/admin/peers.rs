@@ -4,8 +4,8 @@ use core::net::SocketAddr;
 use ethrex_common::H256;
 use ethrex_p2p::{
     peer_handler::PeerHandler,
-    peer_table::PeerData,
-    rlpx::{initiator::RlpxInitiatorProtocol as _, p2p::Capability},
+    peer_table::{PeerData, PeerTableServerProtocol as _},
+    rlpx::p2p::Capability,
     types::Node,
 };
 use serde::Serialize;
@@ -27,9 +27,11 @@ pub struct RpcPeer {
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct PeerNetwork {
-    // We can add more data about the connection here, such the local address, whether the peer is trusted, etc
+    // We can add more data about the connection here, such as the local address
     inbound: bool,
     remote_address: SocketAddr,
+    r#static: bool,
+    trusted: bool,
 }
 
 /// Serializable peer protocols data returned by the node's rpc
@@ -79,6 +81,8 @@ impl From<PeerData> for RpcPeer {
             network: PeerNetwork {
                 remote_address: peer.node.udp_addr(),
                 inbound: peer.is_connection_inbound,
+                r#static: peer.is_static,
+                trusted: peer.is_trusted,
             },
             protocols,
         }
@@ -119,17 +123,24 @@ fn parse(request: &RpcRequest) -> Result<Node, RpcErr> {
     Node::from_enode_url(url).map_err(|error| RpcErr::BadParams(error.to_string()))
 }
 
+/// Keeps the given node as a static peer, so it's dialed and redialed whenever the
+/// connection drops, and waits for the connection to be established.
 pub async fn add_peer(context: &mut RpcApiContext, request: &RpcRequest) -> Result<Value, RpcErr> {
     let Some(peer_handler) = context.peer_handler.as_mut() else {
         return Err(RpcErr::Internal("Peer handler not initialized".to_string()));
     };
-    let server = peer_handler.initiator.clone();
     let node = parse(request)?;
 
+    // The initiator's static dial loop owns dialing static peers, dialing here too would
+    // race it with a second connection attempt
+    peer_handler
+        .peer_table
+        .add_static_peer(node.clone())
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+
     let start = Instant::now();
     let runtime = Duration::from_secs(10);
 
-    let cast_result = server.initiate(node.clone());
     // This loop is necessary because connections are asynchronous, so to check if the connection with the peer was actually
     // established we need to wait.
     loop {
@@ -137,13 +148,75 @@ pub async fn add_peer(context: &mut RpcApiContext, request: &RpcRequest) -> Resu
             return Ok(serde_json::to_value(true)?);
         }
 
-        if cast_result.is_err() || start.elapsed() >= runtime {
+        if start.elapsed() >= runtime {
             return Ok(serde_json::to_value(false)?);
         }
         let _ = tokio::time::sleep(Duration::from_millis(100)).await;
     }
 }
 
+/// Removes the given node from the static peers and disconnects from it if connected.
+pub async fn remove_peer(
+    context: &mut RpcApiContext,
+    request: &RpcRequest,
+) -> Result<Value, RpcErr> {
+    let Some(peer_handler) = &context.peer_handler else {
+        return Err(RpcErr::Internal("Peer handler not initialized".to_string()));
+    };
+    let node_id = parse(request)?.node_id();
+
+    peer_handler
+        .peer_table
+        .remove_static_peer(node_id)
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    let connection = peer_handler
+        .peer_table
+        .get_peer_connection(node_id)
+        .await
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    if let Some(connection) = connection {
+        connection
+            .disconnect()
+            .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    }
+    Ok(serde_json::to_value(true)?)
+}
+
+/// Marks the given node as trusted, allowing it to connect even when the peer limit
+/// is reached and protecting it from score penalties.
+pub async fn add_trusted_peer(
+    context: &mut RpcApiContext,
+    request: &RpcRequest,
+) -> Result<Value, RpcErr> {
+    let Some(peer_handler) = &context.peer_handler else {
+        return Err(RpcErr::Internal("Peer handler not initialized".to_string()));
+    };
+    let node = parse(request)?;
+
+    peer_handler
+        .peer_table
+        .add_trusted_peer(node.node_id())
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    Ok(serde_json::to_value(true)?)
+}
+
+/// Removes the given node from the trusted peers. It isn't disconnected.
+pub async fn remove_trusted_peer(
+    context: &mut RpcApiContext,
+    request: &RpcRequest,
+) -> Result<Value, RpcErr> {
+    let Some(peer_handler) = &context.peer_handler else {
+        return Err(RpcErr::Internal("Peer handler not initialized".to_string()));
+    };
+    let node = parse(request)?;
+
+    peer_handler
+        .peer_table
+        .remove_trusted_peer(node.node_id())
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    Ok(serde_json::to_value(true)?)
+}
+
 async fn peer_is_connected(peer_handler: &mut PeerHandler, enode_url: &str) -> bool {
     peer_handler
         .read_connected_peers()
@@ -213,9 +286,9 @@ mod tests {
         peer.is_connection_inbound = false;
         peer.node.version = Some("ethrex/test".to_string());
         // The first serialized peer shown in geth's documentation example: https://geth.ethereum.org/docs/interacting-with-geth/rpc/ns-admin#admin-peers
-        // The fields "localAddress", "static", "trusted" and "name" were removed as we do not have the necessary information to show them
+        // The fields "localAddress" and "name" were removed as we do not have the necessary information to show them
         // Misc: Added 0x prefix to node id, there is no set spec for this method so the prefix shouldn't be a problem, also changed version name
-        let expected_serialized_peer = r#"{"caps":["eth/68","snap/1"],"enode":"enode://4aeb4ab6c14b23e2c4cfdce879c04b0748a20d8e9b59e25ded2a08143e265c6c25936e74cbc8e641e3312ca288673d91f2f93f8e277de3cfa444ecdaaf982052@157.90.35.166:30303","id":"0x6b36f791352f15eb3ec4f67787074ab8ad9d487e37c4401d383f0561a0a20507","name":"ethrex/test","network":{"inbound":false,"remoteAddress":"157.90.35.166:30303"},"protocols":{"eth":{"version":68},"snap":{"version":1}}}"#.to_string();
+        let expected_serialized_peer = r#"{"caps":["eth/68","snap/1"],"enode":"enode://4aeb4ab6c14b23e2c4cfdce879c04b0748a20d8e9b59e25ded2a08143e265c6c25936e74cbc8e641e3312ca288673d91f2f93f8e277de3cfa444ecdaaf982052@157.90.35.166:30303","id":"0x6b36f791352f15eb3ec4f67787074ab8ad9d487e37c4401d383f0561a0a20507","name":"ethrex/test","network":{"inbound":false,"remoteAddress":"157.90.35.166:30303","static":false,"trusted":false},"protocols":{"eth":{"version":68},"snap":{"version":1}}}"#.to_string();
         let serialized_peer =
             serde_json::to_string(&RpcPeer::from(peer)).expect("Failed to serialize peer");
         assert_eq!(serialized_peer, expected_serialized_peer);
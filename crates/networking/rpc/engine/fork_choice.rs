@@ -10,7 +10,6 @@ use tracing::{debug, info, warn};
 
 use crate::{
     rpc::{RpcApiContext, RpcHandler},
-    subscription_manager::SubscriptionManagerProtocol,
     types::{
         fork_choice::{
             ForkChoiceResponse, ForkChoiceState, PayloadAttributesV3, PayloadAttributesV4,
@@ -314,11 +313,6 @@ async fn handle_forkchoice(
                 }
             };
 
-            // Notify all eth_subscribe("newHeads") subscribers.
-            if let Some(ws) = &context.ws {
-                let _ = ws.subscription_manager.new_head(head.clone());
-            }
-
             Ok((
                 Some(head),
                 ForkChoiceResponse::from(PayloadStatus::valid_with_hash(
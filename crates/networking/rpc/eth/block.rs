@@ -12,7 +12,7 @@ use crate::{
     utils::RpcErr,
 };
 use ethrex_common::types::{
-    Block, BlockBody, BlockHash, BlockHeader, Receipt, calculate_base_fee_per_blob_gas,
+    Block, BlockBody, BlockHash, BlockHeader, BlockNumber, Receipt, calculate_base_fee_per_blob_gas,
 };
 use ethrex_storage::Store;
 
@@ -75,7 +75,10 @@ impl RpcHandler for GetBlockByNumberRequest {
         let (header, body) = match (header, body) {
             (Some(header), Some(body)) => (header, body),
             // Block not found
-            _ => return Ok(Value::Null),
+            _ => {
+                check_history_available(storage, block_number).await?;
+                return Ok(Value::Null);
+            }
         };
         let hash = header.hash();
         let block = RpcBlock::build(header, body, hash, self.hydrated)?;
@@ -109,7 +112,10 @@ impl RpcHandler for GetBlockByHashRequest {
         let (header, body) = match (header, body) {
             (Some(header), Some(body)) => (header, body),
             // Block not found
-            _ => return Ok(Value::Null),
+            _ => {
+                check_history_available(storage, block_number).await?;
+                return Ok(Value::Null);
+            }
         };
         let hash = header.hash();
         let block = RpcBlock::build(header, body, hash, self.hydrated)?;
@@ -141,7 +147,10 @@ impl RpcHandler for GetBlockTransactionCountRequest {
         };
         let block_body = match context.storage.get_block_body(block_number).await? {
             Some(block_body) => block_body,
-            _ => return Ok(Value::Null),
+            _ => {
+                check_history_available(&context.storage, block_number).await?;
+                return Ok(Value::Null);
+            }
         };
         let transaction_count = block_body.transactions.len();
 
@@ -175,7 +184,10 @@ impl RpcHandler for GetBlockReceiptsRequest {
         let (header, body) = match (header, body) {
             (Some(header), Some(body)) => (header, body),
             // Block not found
-            _ => return Ok(Value::Null),
+            _ => {
+                check_history_available(storage, block_number).await?;
+                return Ok(Value::Null);
+            }
         };
         let receipts = get_all_block_rpc_receipts(header, body, storage, None).await?;
 
@@ -239,7 +251,10 @@ impl RpcHandler for GetRawBlockRequest {
         let body = context.storage.get_block_body(block_number).await?;
         let (header, body) = match (header, body) {
             (Some(header), Some(body)) => (header, body),
-            _ => return Ok(Value::Null),
+            _ => {
+                check_history_available(&context.storage, block_number).await?;
+                return Ok(Value::Null);
+            }
         };
         let block = Block::new(header, body).encode_to_vec();
 
@@ -272,6 +287,7 @@ impl RpcHandler for GetRawReceipts {
             Some(header) => header,
             None => return Ok(Value::Null),
         };
+        check_history_available(storage, block_number).await?;
         let receipts: Vec<String> = get_all_block_receipts(header, storage)
             .await?
             .iter()
@@ -327,6 +343,18 @@ impl RpcHandler for GetBlobBaseFee {
     }
 }
 
+/// Returns [`RpcErr::PrunedHistory`] if the body and receipts of `block_number` were
+/// pruned by the node's history retention policy (EIP-4444).
+pub(crate) async fn check_history_available(
+    storage: &Store,
+    block_number: BlockNumber,
+) -> Result<(), RpcErr> {
+    if block_number != 0 && block_number < storage.get_history_cutoff().await? {
+        return Err(RpcErr::PrunedHistory(block_number));
+    }
+    Ok(())
+}
+
 /// Fetches RPC receipts for a block, optionally stopping after `target_index`.
 ///
 /// When `target_index` is `Some(n)`, only receipts 0..=n are fetched using a
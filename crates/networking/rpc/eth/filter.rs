@@ -2,24 +2,38 @@
 // - Manually testing the behaviour deploying contracts on the Sepolia test network.
 // - Go-Ethereum, specifically: https://github.com/ethereum/go-ethereum/blob/368e16f39d6c7e5cce72a92ec289adbfbaed4854/eth/filters/filter.go
 // - Ethereum's reference: https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_newfilter
-use ethrex_common::types::BlockNumber;
+use ethrex_blockchain::events::ChainEvent;
+use ethrex_common::{H256, types::BlockNumber};
 use ethrex_storage::Store;
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
     time::{Duration, Instant},
 };
-use tracing::error;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, warn};
 
 use crate::rpc::RpcHandler;
 use crate::{
-    types::block_identifier::{BlockIdentifier, BlockTag},
+    types::block_identifier::BlockIdentifier,
     utils::{RpcErr, RpcRequest, parse_json_hex},
 };
 use serde_json::{Value, json};
 
 use super::logs::{LogsFilter, fetch_logs_with_filter};
 
+/// Default maximum number of filters that can be installed at the same time.
+pub const DEFAULT_MAX_FILTERS: usize = 10_000;
+
+/// Maximum number of transaction hashes a pending transaction filter buffers
+/// between polls. Transactions added to the mempool while the buffer is full
+/// are not reported.
+pub const MAX_PENDING_FILTER_HASHES: usize = 4096;
+
+/// Maximum number of block hashes returned by a single poll of a block filter.
+/// A filter lagging further behind only gets the most recent hashes.
+pub const MAX_BLOCK_FILTER_HASHES: u64 = 1024;
+
 #[derive(Debug, Clone)]
 pub struct NewFilterRequest {
     pub request_data: LogsFilter,
@@ -31,17 +45,13 @@ pub struct NewFilterRequest {
 /// - filter_duration: represents how many *seconds* filter can last,
 ///   if any filter is older than this, it will be removed.
 pub fn clean_outdated_filters(filters: ActiveFilters, filter_duration: Duration) {
-    let mut active_filters_guard = filters.lock().unwrap_or_else(|mut poisoned_guard| {
-        error!("THREAD CRASHED WITH MUTEX TAKEN; SYSTEM MIGHT BE UNSTABLE");
-        **poisoned_guard.get_mut() = HashMap::new();
-        filters.clear_poison();
-        poisoned_guard.into_inner()
-    });
+    let mut active_filters_guard = lock_filters(&filters);
 
     // Keep only filters that have not expired.
     active_filters_guard
         .retain(|_, (filter_timestamp, _)| filter_timestamp.elapsed() <= filter_duration);
 }
+
 /// Maps IDs to active pollable filters and their timestamps.
 pub type ActiveFilters = Arc<Mutex<HashMap<u64, (Instant, PollableFilter)>>>;
 
@@ -50,10 +60,118 @@ pub struct PollableFilter {
     /// Last block number from when this
     /// filter was requested or created.
     /// i.e. if this filter is requested,
-    /// the log will be applied from this
-    /// block number up to the latest one.
+    /// the changes will be collected from the
+    /// next block number up to the latest one.
     pub last_block_number: BlockNumber,
-    pub filter_data: LogsFilter,
+    pub kind: FilterKind,
+}
+
+/// What a [`PollableFilter`] reports on `eth_getFilterChanges`.
+#[derive(Debug, Clone)]
+pub enum FilterKind {
+    /// Logs matching the filter, installed with `eth_newFilter`.
+    Logs(LogsFilter),
+    /// Hashes of new canonical blocks, installed with `eth_newBlockFilter`.
+    Blocks,
+    /// Hashes of the transactions added to the mempool since the last poll,
+    /// installed with `eth_newPendingTransactionFilter`.
+    PendingTransactions(PendingTransactions),
+}
+
+/// The buffer of a pending transaction filter. A task fills it with the hashes of
+/// the transactions added to the mempool for as long as the filter is installed,
+/// up to [`MAX_PENDING_FILTER_HASHES`] hashes between polls. Clones share the buffer.
+#[derive(Debug, Clone)]
+pub struct PendingTransactions(Arc<Mutex<mpsc::Receiver<H256>>>);
+
+impl PendingTransactions {
+    pub fn new(mut events: broadcast::Receiver<ChainEvent>) -> Self {
+        let (sender, receiver) = mpsc::channel(MAX_PENDING_FILTER_HASHES);
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    event = events.recv() => match event {
+                        Ok(ChainEvent::NewPendingTransaction { hash, .. }) => {
+                            if sender.try_send(hash).is_err() {
+                                debug!("Pending transaction filter is full, dropping {hash:#x}");
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                            warn!(
+                                skipped,
+                                "Pending transaction filter fell behind the mempool"
+                            );
+                        }
+                        Err(broadcast::error::RecvError::Closed) => break,
+                    },
+                    // The filter was uninstalled
+                    _ = sender.closed() => break,
+                }
+            }
+        });
+        Self(Arc::new(Mutex::new(receiver)))
+    }
+
+    /// Returns the hashes of the transactions added to the mempool since the
+    /// last call.
+    fn take_hashes(&self) -> Vec<H256> {
+        let mut receiver = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut hashes = Vec::new();
+        while let Ok(hash) = receiver.try_recv() {
+            hashes.push(hash);
+        }
+        hashes
+    }
+}
+
+fn lock_filters(
+    filters: &ActiveFilters,
+) -> MutexGuard<'_, HashMap<u64, (Instant, PollableFilter)>> {
+    filters.lock().unwrap_or_else(|mut poisoned_guard| {
+        error!("THREAD CRASHED WITH MUTEX TAKEN; SYSTEM MIGHT BE UNSTABLE");
+        **poisoned_guard.get_mut() = HashMap::new();
+        filters.clear_poison();
+        poisoned_guard.into_inner()
+    })
+}
+
+/// Registers `filter` under a new random ID and returns the ID hex encoded.
+/// Fails if `max_filters` filters are already installed.
+fn install_filter(
+    filters: &ActiveFilters,
+    max_filters: usize,
+    filter: PollableFilter,
+) -> Result<Value, RpcErr> {
+    let mut active_filters_guard = lock_filters(filters);
+    if active_filters_guard.len() >= max_filters {
+        return Err(RpcErr::Internal(format!(
+            "Too many active filters (max {max_filters})"
+        )));
+    }
+    let mut id: u64 = rand::random();
+    while active_filters_guard.contains_key(&id) {
+        id = rand::random();
+    }
+    active_filters_guard.insert(id, (Instant::now(), filter));
+    Ok(json!(format!("0x{:x}", id)))
+}
+
+fn parse_filter_id(params: &Option<Vec<Value>>) -> Result<u64, RpcErr> {
+    match params.as_deref() {
+        Some([param]) => parse_json_hex(param).map_err(|_err| RpcErr::BadHexFormat(0)),
+        Some(_) => Err(RpcErr::BadParams(
+            "Expected an array with a single hex encoded id".to_string(),
+        )),
+        None => Err(RpcErr::MissingParam("0".to_string())),
+    }
+}
+
+fn parse_no_params(params: &Option<Vec<Value>>) -> Result<(), RpcErr> {
+    match params.as_deref() {
+        None | Some([]) => Ok(()),
+        Some(_) => Err(RpcErr::BadParams("No params expected".to_string())),
+    }
 }
 
 impl NewFilterRequest {
@@ -68,6 +186,7 @@ impl NewFilterRequest {
         &self,
         storage: ethrex_storage::Store,
         filters: ActiveFilters,
+        max_filters: usize,
     ) -> Result<serde_json::Value, crate::utils::RpcErr> {
         let from = self
             .request_data
@@ -87,35 +206,100 @@ impl NewFilterRequest {
         }
 
         let last_block_number = storage.get_latest_block_number().await?;
-        let id: u64 = rand::random();
-        let timestamp = Instant::now();
-        let mut active_filters_guard = filters.lock().unwrap_or_else(|mut poisoned_guard| {
-            error!("THREAD CRASHED WITH MUTEX TAKEN; SYSTEM MIGHT BE UNSTABLE");
-            **poisoned_guard.get_mut() = HashMap::new();
-            filters.clear_poison();
-            poisoned_guard.into_inner()
-        });
-        active_filters_guard.insert(
-            id,
-            (
-                timestamp,
-                PollableFilter {
-                    last_block_number,
-                    filter_data: self.request_data.clone(),
-                },
-            ),
-        );
-        let as_hex = json!(format!("0x{:x}", id));
-        Ok(as_hex)
+        install_filter(
+            &filters,
+            max_filters,
+            PollableFilter {
+                last_block_number,
+                kind: FilterKind::Logs(self.request_data.clone()),
+            },
+        )
     }
 
     pub async fn stateful_call(
         req: &RpcRequest,
         storage: Store,
         state: ActiveFilters,
+        max_filters: usize,
     ) -> Result<Value, RpcErr> {
         let request = Self::parse(&req.params)?;
-        request.handle(storage, state).await
+        request.handle(storage, state, max_filters).await
+    }
+}
+
+/// `eth_newBlockFilter`
+pub struct NewBlockFilterRequest;
+
+impl NewBlockFilterRequest {
+    pub fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        parse_no_params(params)?;
+        Ok(NewBlockFilterRequest)
+    }
+
+    pub async fn handle(
+        &self,
+        storage: ethrex_storage::Store,
+        filters: ActiveFilters,
+        max_filters: usize,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        let last_block_number = storage.get_latest_block_number().await?;
+        install_filter(
+            &filters,
+            max_filters,
+            PollableFilter {
+                last_block_number,
+                kind: FilterKind::Blocks,
+            },
+        )
+    }
+
+    pub async fn stateful_call(
+        req: &RpcRequest,
+        storage: Store,
+        state: ActiveFilters,
+        max_filters: usize,
+    ) -> Result<Value, RpcErr> {
+        let request = Self::parse(&req.params)?;
+        request.handle(storage, state, max_filters).await
+    }
+}
+
+/// `eth_newPendingTransactionFilter`
+pub struct NewPendingTransactionFilterRequest;
+
+impl NewPendingTransactionFilterRequest {
+    pub fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        parse_no_params(params)?;
+        Ok(NewPendingTransactionFilterRequest)
+    }
+
+    pub async fn handle(
+        &self,
+        storage: ethrex_storage::Store,
+        events: broadcast::Receiver<ChainEvent>,
+        filters: ActiveFilters,
+        max_filters: usize,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        let last_block_number = storage.get_latest_block_number().await?;
+        install_filter(
+            &filters,
+            max_filters,
+            PollableFilter {
+                last_block_number,
+                kind: FilterKind::PendingTransactions(PendingTransactions::new(events)),
+            },
+        )
+    }
+
+    pub async fn stateful_call(
+        req: &RpcRequest,
+        storage: Store,
+        events: broadcast::Receiver<ChainEvent>,
+        state: ActiveFilters,
+        max_filters: usize,
+    ) -> Result<Value, RpcErr> {
+        let request = Self::parse(&req.params)?;
+        request.handle(storage, events, state, max_filters).await
     }
 }
 
@@ -125,16 +309,9 @@ pub struct DeleteFilterRequest {
 
 impl DeleteFilterRequest {
     pub fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
-        match params.as_deref() {
-            Some([param]) => {
-                let id = parse_json_hex(param).map_err(|_err| RpcErr::BadHexFormat(0))?;
-                Ok(DeleteFilterRequest { id })
-            }
-            Some(_) => Err(RpcErr::BadParams(
-                "Expected an array with a single hex encoded id".to_string(),
-            )),
-            None => Err(RpcErr::MissingParam("0".to_string())),
-        }
+        Ok(DeleteFilterRequest {
+            id: parse_filter_id(params)?,
+        })
     }
 
     pub fn handle(
@@ -142,12 +319,7 @@ impl DeleteFilterRequest {
         _storage: ethrex_storage::Store,
         filters: ActiveFilters,
     ) -> Result<serde_json::Value, crate::utils::RpcErr> {
-        let mut active_filters_guard = filters.lock().unwrap_or_else(|mut poisoned_guard| {
-            error!("THREAD CRASHED WITH MUTEX TAKEN; SYSTEM MIGHT BE UNSTABLE");
-            **poisoned_guard.get_mut() = HashMap::new();
-            filters.clear_poison();
-            poisoned_guard.into_inner()
-        });
+        let mut active_filters_guard = lock_filters(&filters);
         match active_filters_guard.remove(&self.id) {
             Some(_) => Ok(true.into()),
             None => Ok(false.into()),
@@ -170,74 +342,132 @@ pub struct FilterChangesRequest {
 
 impl FilterChangesRequest {
     pub fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
-        match params.as_deref() {
-            Some([param]) => {
-                let id = parse_json_hex(param).map_err(|_err| RpcErr::BadHexFormat(0))?;
-                Ok(FilterChangesRequest { id })
-            }
-            Some(_) => Err(RpcErr::BadParams(
-                "Expected an array with a single hex encoded id".to_string(),
-            )),
-            None => Err(RpcErr::MissingParam("0".to_string())),
-        }
+        Ok(FilterChangesRequest {
+            id: parse_filter_id(params)?,
+        })
     }
+
     pub async fn handle(
         &self,
         storage: ethrex_storage::Store,
         filters: ActiveFilters,
     ) -> Result<serde_json::Value, crate::utils::RpcErr> {
         let latest_block_num = storage.get_latest_block_number().await?;
-        // Box needed to keep the future Sync
-        // https://github.com/rust-lang/rust/issues/128095
-        let mut active_filters_guard =
-            Box::new(filters.lock().unwrap_or_else(|mut poisoned_guard| {
-                error!("THREAD CRASHED WITH MUTEX TAKEN; SYSTEM MIGHT BE UNSTABLE");
-                **poisoned_guard.get_mut() = HashMap::new();
-                filters.clear_poison();
-                poisoned_guard.into_inner()
-            }));
-        if let Some((timestamp, filter)) = active_filters_guard.get_mut(&self.id) {
-            // We'll only get changes for a filter that either has a block
-            // range for upcoming blocks, or for the 'latest' tag.
-            let valid_block_range = match filter.filter_data.to_block {
-                BlockIdentifier::Tag(BlockTag::Latest) => true,
-                BlockIdentifier::Number(block_num) if block_num >= latest_block_num => true,
-                _ => false,
+        // Advance the filter's cursor and take what is needed to compute the
+        // changes, releasing the lock before querying the store.
+        let (kind, last_polled_block) = {
+            let mut active_filters_guard = lock_filters(&filters);
+            let Some((timestamp, filter)) = active_filters_guard.get_mut(&self.id) else {
+                return Err(RpcErr::BadParams(
+                    "No matching filter for given id".to_string(),
+                ));
             };
-            // This filter has a valid block range, so here's what we'll do:
-            // - Update the filter's timestamp and block number from the last poll.
-            // - Do the query to fetch logs in range last_block_number..=to_block for
-            //   this filter.
-            if valid_block_range {
-                // Since the filter was polled, updated its timestamp, so
-                // it does not expire.
-                *timestamp = Instant::now();
-                // Update this filter so the current query
-                // starts from the last polled block.
-                filter.filter_data.from_block = BlockIdentifier::Number(filter.last_block_number);
-                filter.last_block_number = latest_block_num;
-                let mut filter = filter.clone();
-                filter.filter_data.to_block = BlockIdentifier::Number(latest_block_num);
-                // Drop the lock early to process this filter's query
-                // and not keep the lock more than we should.
-                drop(active_filters_guard);
-                let logs = fetch_logs_with_filter(&filter.filter_data, storage).await?;
+            // Since the filter was polled, updated its timestamp, so
+            // it does not expire.
+            *timestamp = Instant::now();
+            let last_polled_block =
+                std::mem::replace(&mut filter.last_block_number, latest_block_num);
+            let kind = match &mut filter.kind {
+                FilterKind::PendingTransactions(pending) => {
+                    return Ok(json!(pending.take_hashes()));
+                }
+                kind => kind.clone(),
+            };
+            (kind, last_polled_block)
+        };
+
+        let first_new_block = last_polled_block.saturating_add(1);
+        match kind {
+            FilterKind::Logs(mut logs_filter) => {
+                // Only logs from blocks after the last poll that fall within
+                // the filter's block range are reported.
+                let from = match logs_filter.from_block {
+                    BlockIdentifier::Number(from) => from.max(first_new_block),
+                    _ => first_new_block,
+                };
+                let to = match logs_filter.to_block {
+                    BlockIdentifier::Number(to) => to.min(latest_block_num),
+                    _ => latest_block_num,
+                };
+                if from > to {
+                    return Ok(json!([]));
+                }
+                logs_filter.from_block = BlockIdentifier::Number(from);
+                logs_filter.to_block = BlockIdentifier::Number(to);
+                let logs = fetch_logs_with_filter(&logs_filter, storage).await?;
                 serde_json::to_value(logs).map_err(|error| {
                     tracing::error!("Log filtering request failed with: {error}");
                     RpcErr::Internal("Failed to filter logs".to_string())
                 })
-            } else {
-                serde_json::to_value(Vec::<u8>::new()).map_err(|error| {
-                    tracing::error!("Log filtering request failed with: {error}");
-                    RpcErr::Internal("Failed to filter logs".to_string())
-                })
             }
-        } else {
-            Err(RpcErr::BadParams(
-                "No matching filter for given id".to_string(),
-            ))
+            FilterKind::Blocks => {
+                let from = first_new_block.max(
+                    latest_block_num
+                        .saturating_add(1)
+                        .saturating_sub(MAX_BLOCK_FILTER_HASHES),
+                );
+                let mut hashes = Vec::new();
+                for block_number in from..=latest_block_num {
+                    if let Some(hash) = storage.get_canonical_block_hash(block_number).await? {
+                        hashes.push(hash);
+                    }
+                }
+                Ok(json!(hashes))
+            }
+            // Answered above, while the filter was locked
+            FilterKind::PendingTransactions(_) => Ok(json!([])),
         }
     }
+
+    pub async fn stateful_call(
+        req: &RpcRequest,
+        storage: ethrex_storage::Store,
+        filters: ActiveFilters,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        let request = Self::parse(&req.params)?;
+        request.handle(storage, filters).await
+    }
+}
+
+/// `eth_getFilterLogs`: every log matching a filter installed with
+/// `eth_newFilter`, regardless of what was already returned by
+/// `eth_getFilterChanges`.
+pub struct FilterLogsRequest {
+    pub id: u64,
+}
+
+impl FilterLogsRequest {
+    pub fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        Ok(FilterLogsRequest {
+            id: parse_filter_id(params)?,
+        })
+    }
+
+    pub async fn handle(
+        &self,
+        storage: ethrex_storage::Store,
+        filters: ActiveFilters,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        let logs_filter = {
+            let mut active_filters_guard = lock_filters(&filters);
+            let Some((timestamp, filter)) = active_filters_guard.get_mut(&self.id) else {
+                return Err(RpcErr::BadParams(
+                    "No matching filter for given id".to_string(),
+                ));
+            };
+            let FilterKind::Logs(logs_filter) = &filter.kind else {
+                return Err(RpcErr::BadParams("Filter is not a logs filter".to_string()));
+            };
+            *timestamp = Instant::now();
+            logs_filter.clone()
+        };
+        let logs = fetch_logs_with_filter(&logs_filter, storage).await?;
+        serde_json::to_value(logs).map_err(|error| {
+            tracing::error!("Log filtering request failed with: {error}");
+            RpcErr::Internal("Failed to filter logs".to_string())
+        })
+    }
+
     pub async fn stateful_call(
         req: &RpcRequest,
         storage: ethrex_storage::Store,
@@ -256,17 +486,21 @@ mod tests {
         time::{Duration, Instant},
     };
 
-    use super::ActiveFilters;
+    use super::{ActiveFilters, MAX_PENDING_FILTER_HASHES};
     use crate::{
         eth::{
-            filter::PollableFilter,
+            filter::{FilterKind, PollableFilter},
             logs::{AddressFilter, LogsFilter, TopicFilter},
         },
-        rpc::{FILTER_DURATION, map_http_requests},
+        rpc::{FILTER_DURATION, RpcApiContext, map_http_requests},
         test_utils::{TEST_GENESIS, default_context_with_storage, start_test_api},
     };
     use crate::{types::block_identifier::BlockIdentifier, utils::RpcRequest};
-    use ethrex_common::types::Genesis;
+    use ethrex_blockchain::events::CHAIN_EVENTS_CHANNEL_CAPACITY;
+    use ethrex_common::{
+        Address, H256,
+        types::{Genesis, MempoolTransaction, Transaction},
+    };
     use ethrex_storage::{EngineType, Store};
 
     use serde_json::{Value, json};
@@ -296,19 +530,13 @@ mod tests {
         let filters = filters.lock().unwrap();
         assert!(filters.len() == 1);
         let (_, filter) = filters.clone().get(&id).unwrap().clone();
-        assert!(matches!(
-            filter.filter_data.from_block,
-            BlockIdentifier::Number(1)
-        ));
-        assert!(matches!(
-            filter.filter_data.to_block,
-            BlockIdentifier::Number(2)
-        ));
-        assert!(filter.filter_data.address_filters.is_none());
-        assert!(matches!(
-            &filter.filter_data.topics[..],
-            [TopicFilter::Topic(_)]
-        ));
+        let FilterKind::Logs(filter_data) = filter.kind else {
+            panic!("expected a logs filter");
+        };
+        assert!(matches!(filter_data.from_block, BlockIdentifier::Number(1)));
+        assert!(matches!(filter_data.to_block, BlockIdentifier::Number(2)));
+        assert!(filter_data.address_filters.is_none());
+        assert!(matches!(&filter_data.topics[..], [TopicFilter::Topic(_)]));
     }
 
     #[tokio::test]
@@ -333,16 +561,13 @@ mod tests {
         let filters = filters.lock().unwrap();
         assert!(filters.len() == 1);
         let (_, filter) = filters.clone().get(&id).unwrap().clone();
-        assert!(matches!(
-            filter.filter_data.from_block,
-            BlockIdentifier::Number(1)
-        ));
-        assert!(matches!(
-            filter.filter_data.to_block,
-            BlockIdentifier::Number(255)
-        ));
-        assert!(filter.filter_data.address_filters.is_none());
-        assert!(matches!(&filter.filter_data.topics[..], []));
+        let FilterKind::Logs(filter_data) = filter.kind else {
+            panic!("expected a logs filter");
+        };
+        assert!(matches!(filter_data.from_block, BlockIdentifier::Number(1)));
+        assert!(matches!(filter_data.to_block, BlockIdentifier::Number(255)));
+        assert!(filter_data.address_filters.is_none());
+        assert!(matches!(&filter_data.topics[..], []));
     }
 
     #[tokio::test]
@@ -367,19 +592,16 @@ mod tests {
         let filters = filters.lock().unwrap();
         assert!(filters.len() == 1);
         let (_, filter) = filters.clone().get(&id).unwrap().clone();
+        let FilterKind::Logs(filter_data) = filter.kind else {
+            panic!("expected a logs filter");
+        };
+        assert!(matches!(filter_data.from_block, BlockIdentifier::Number(1)));
+        assert!(matches!(filter_data.to_block, BlockIdentifier::Number(255)));
         assert!(matches!(
-            filter.filter_data.from_block,
-            BlockIdentifier::Number(1)
-        ));
-        assert!(matches!(
-            filter.filter_data.to_block,
-            BlockIdentifier::Number(255)
-        ));
-        assert!(matches!(
-            filter.filter_data.address_filters.unwrap(),
+            filter_data.address_filters.unwrap(),
             AddressFilter::Many(_)
         ));
-        assert!(matches!(&filter.filter_data.topics[..], []));
+        assert!(matches!(&filter_data.topics[..], []));
     }
 
     #[tokio::test]
@@ -474,12 +696,12 @@ mod tests {
                 Instant::now(),
                 PollableFilter {
                     last_block_number: 0,
-                    filter_data: LogsFilter {
+                    kind: FilterKind::Logs(LogsFilter {
                         from_block: BlockIdentifier::Number(1),
                         to_block: BlockIdentifier::Number(2),
                         address_filters: None,
                         topics: vec![],
-                    },
+                    }),
                 },
             ),
         );
@@ -602,4 +824,211 @@ mod tests {
 
         server_handle.abort();
     }
+
+    async fn context_with_genesis(filters: ActiveFilters) -> RpcApiContext {
+        let storage = Store::new("in-mem", EngineType::InMemory)
+            .expect("Fatal: could not create in memory test db");
+        let genesis_config: Genesis =
+            serde_json::from_str(TEST_GENESIS).expect("Fatal: non-valid genesis test config");
+        storage
+            .add_initial_state(genesis_config)
+            .await
+            .expect("Fatal: could not add test genesis in test");
+        let mut context = default_context_with_storage(storage).await;
+        context.active_filters = filters;
+        context
+    }
+
+    fn request(method: &str, params: Value) -> RpcRequest {
+        serde_json::from_value(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+            "id": 1
+        }))
+        .expect("Json for test is not a valid request")
+    }
+
+    #[tokio::test]
+    async fn block_filter_without_new_blocks_returns_no_changes() {
+        let filters: ActiveFilters = Default::default();
+        let context = context_with_genesis(filters.clone()).await;
+
+        let id = map_http_requests(&request("eth_newBlockFilter", json!([])), context.clone())
+            .await
+            .unwrap();
+        assert!(matches!(
+            filters.lock().unwrap().values().next(),
+            Some((
+                _,
+                PollableFilter {
+                    kind: FilterKind::Blocks,
+                    ..
+                }
+            ))
+        ));
+
+        let changes = map_http_requests(&request("eth_getFilterChanges", json!([id])), context)
+            .await
+            .unwrap();
+        assert_eq!(changes, json!([]));
+    }
+
+    #[tokio::test]
+    async fn pending_transaction_filter_returns_each_hash_once() {
+        let filters: ActiveFilters = Default::default();
+        let context = context_with_genesis(filters.clone()).await;
+        let mempool = &context.blockchain.mempool;
+        let transaction = || {
+            MempoolTransaction::new(
+                Transaction::EIP1559Transaction(Default::default()),
+                Default::default(),
+            )
+        };
+
+        // Transactions added before the filter is installed aren't reported
+        mempool
+            .add_transaction(H256::repeat_byte(1), Address::zero(), transaction())
+            .unwrap();
+        let id = map_http_requests(
+            &request("eth_newPendingTransactionFilter", json!([])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+
+        let hash = H256::repeat_byte(2);
+        mempool
+            .add_transaction(hash, Address::zero(), transaction())
+            .unwrap();
+        // Let the filter's task buffer the hash
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let changes = map_http_requests(
+            &request("eth_getFilterChanges", json!([id.clone()])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(changes, json!([hash]));
+
+        let changes = map_http_requests(&request("eth_getFilterChanges", json!([id])), context)
+            .await
+            .unwrap();
+        assert_eq!(changes, json!([]));
+    }
+
+    #[tokio::test]
+    async fn pending_transaction_filters_only_subscribe_while_installed() {
+        let filters: ActiveFilters = Default::default();
+        let context = context_with_genesis(filters.clone()).await;
+        let subscribers = || context.blockchain.pending_transaction_subscribers();
+        let initial = subscribers();
+
+        let id = map_http_requests(
+            &request("eth_newPendingTransactionFilter", json!([])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(subscribers(), initial + 1);
+
+        map_http_requests(
+            &request("eth_uninstallFilter", json!([id])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+        // The filter's task unsubscribes once it sees the filter is gone
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while subscribers() != initial {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("The uninstalled filter is still subscribed");
+    }
+
+    #[tokio::test]
+    async fn pending_transaction_filter_buffers_past_the_events_channel_capacity() {
+        let filters: ActiveFilters = Default::default();
+        let context = context_with_genesis(filters.clone()).await;
+        let mempool = &context.blockchain.mempool;
+        let transaction = || {
+            MempoolTransaction::new(
+                Transaction::EIP1559Transaction(Default::default()),
+                Default::default(),
+            )
+        };
+
+        let id = map_http_requests(
+            &request("eth_newPendingTransactionFilter", json!([])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+
+        // More transactions than the events channel holds, and than the filter buffers
+        let hashes: Vec<H256> = (1..=MAX_PENDING_FILTER_HASHES as u64 + 1)
+            .map(H256::from_low_u64_be)
+            .collect();
+        assert!(MAX_PENDING_FILTER_HASHES > CHAIN_EVENTS_CHANNEL_CAPACITY);
+        for hash in &hashes {
+            mempool
+                .add_transaction(*hash, Address::zero(), transaction())
+                .unwrap();
+            // Let the filter's task drain the events channel, as a busy node would
+            tokio::task::yield_now().await;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let changes = map_http_requests(
+            &request("eth_getFilterChanges", json!([id.clone()])),
+            context.clone(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(changes, json!(hashes[..MAX_PENDING_FILTER_HASHES]));
+
+        // Polling makes room for new transactions again
+        let hash = H256::repeat_byte(0xff);
+        mempool
+            .add_transaction(hash, Address::zero(), transaction())
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let changes = map_http_requests(&request("eth_getFilterChanges", json!([id])), context)
+            .await
+            .unwrap();
+        assert_eq!(changes, json!([hash]));
+    }
+
+    #[tokio::test]
+    async fn get_filter_logs_rejects_non_log_filters() {
+        let filters: ActiveFilters = Default::default();
+        let context = context_with_genesis(filters).await;
+
+        let id = map_http_requests(&request("eth_newBlockFilter", json!([])), context.clone())
+            .await
+            .unwrap();
+        let res = map_http_requests(&request("eth_getFilterLogs", json!([id])), context).await;
+        assert!(res.is_err());
+    }
+
+    #[tokio::test]
+    async fn installing_filters_past_the_cap_fails() {
+        let filters: ActiveFilters = Default::default();
+        let mut context = context_with_genesis(filters.clone()).await;
+        context.max_filters = 1;
+
+        map_http_requests(&request("eth_newBlockFilter", json!([])), context.clone())
+            .await
+            .unwrap();
+        let res = map_http_requests(
+            &request("eth_newPendingTransactionFilter", json!([])),
+            context,
+        )
+        .await;
+        assert!(res.is_err());
+        assert_eq!(filters.lock().unwrap().len(), 1);
+    }
 }
@@ -3,6 +3,7 @@
 // - Go-Ethereum, specifically: https://github.com/ethereum/go-ethereum/blob/368e16f39d6c7e5cce72a92ec289adbfbaed4854/eth/filters/filter.go
 // - Ethereum's reference: https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_newfilter
 use crate::{
+    eth::block::check_history_available,
     rpc::{RpcApiContext, RpcHandler},
     types::{
         block_identifier::{BlockIdentifier, BlockTag},
@@ -14,7 +15,6 @@ use ethrex_common::{H160, H256};
 use ethrex_storage::Store;
 use serde::Deserialize;
 use serde_json::Value;
-use std::collections::HashSet;
 
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
@@ -53,6 +53,62 @@ pub struct LogsFilter {
     /// Which topics to filter.
     pub topics: Vec<TopicFilter>,
 }
+
+impl LogsFilter {
+    /// Parses the optional filter object of an `eth_subscribe("logs", ...)` request.
+    /// Only `address` and `topics` apply there, and both may be omitted.
+    pub(crate) fn parse_subscription(param: Option<&Value>) -> Result<LogsFilter, RpcErr> {
+        let param = match param {
+            Some(Value::Object(param)) => Some(param),
+            Some(Value::Null) | None => None,
+            Some(_) => return Err(RpcErr::BadParams("Param is not a object".to_owned())),
+        };
+        let field = |name: &str| param.and_then(|param| param.get(name)).cloned();
+        let address_filters = field("address")
+            .map(serde_json::from_value::<Option<AddressFilter>>)
+            .transpose()
+            .map_err(|_| RpcErr::WrongParam("address".to_string()))?
+            .flatten();
+        let topics = field("topics")
+            .map(serde_json::from_value::<Option<Vec<TopicFilter>>>)
+            .transpose()
+            .map_err(|_| RpcErr::WrongParam("topics".to_string()))?
+            .flatten()
+            .unwrap_or_default();
+        Ok(LogsFilter {
+            from_block: BlockIdentifier::Tag(BlockTag::Latest),
+            to_block: BlockIdentifier::Tag(BlockTag::Latest),
+            address_filters,
+            topics,
+        })
+    }
+
+    /// Returns whether a log emitted by `address` with `topics` passes the filters.
+    pub(crate) fn matches(&self, address: &H160, topics: &[H256]) -> bool {
+        if let Some(addresses) = &self.address_filters
+            && !addresses.as_ref().is_empty()
+            && !addresses.as_ref().contains(address)
+        {
+            return false;
+        }
+        if self.topics.len() > topics.len() {
+            return false;
+        }
+        self.topics
+            .iter()
+            .zip(topics)
+            .all(|(topic_filter, log_topic)| match topic_filter {
+                TopicFilter::Topic(topic) => topic.is_none_or(|topic| *log_topic == topic),
+                TopicFilter::Topics(sub_topics) => {
+                    sub_topics.is_empty()
+                        || sub_topics
+                            .iter()
+                            .any(|sub_topic| sub_topic.is_none_or(|t| *log_topic == t))
+                }
+            })
+    }
+}
+
 impl RpcHandler for LogsFilter {
     fn parse(params: &Option<Vec<Value>>) -> Result<LogsFilter, RpcErr> {
         match params.as_deref() {
@@ -136,14 +192,9 @@ pub(crate) async fn fetch_logs_with_filter(
     if (from..=to).is_empty() {
         return Err(RpcErr::BadParams("Empty range".to_string()));
     }
-    let address_filter: HashSet<_> = match &filter.address_filters {
-        Some(AddressFilter::Single(address)) => std::iter::once(address).collect(),
-        Some(AddressFilter::Many(addresses)) => addresses.iter().collect(),
-        None => HashSet::new(),
-    };
-
+    check_history_available(&storage, from).await?;
     let mut logs: Vec<RpcLog> = Vec::new();
-    // The idea here is to fetch every log and filter by address, if given.
+    // The idea here is to fetch every log and filter it by address and topics.
     // For that, we'll need each block in range, and its transactions,
     // and for each transaction, we'll need its receipts, which
     // contain the actual logs we want.
@@ -176,7 +227,7 @@ pub(crate) async fn fetch_logs_with_filter(
 
             if receipt.succeeded {
                 for log in &receipt.logs {
-                    if address_filter.is_empty() || address_filter.contains(&log.address) {
+                    if filter.matches(&log.address, &log.topics) {
                         // Some extra data is needed when
                         // forming the RPC response.
                         logs.push(RpcLog {
@@ -194,41 +245,7 @@ pub(crate) async fn fetch_logs_with_filter(
             }
         }
     }
-    // Now that we have the logs filtered by address,
-    // we still need to filter by topics if it was a given parameter.
-
-    let filtered_logs = if filter.topics.is_empty() {
-        logs
-    } else {
-        logs.into_iter()
-            .filter(|rpc_log| {
-                if filter.topics.len() > rpc_log.log.topics.len() {
-                    return false;
-                }
-                for (i, topic_filter) in filter.topics.iter().enumerate() {
-                    match topic_filter {
-                        TopicFilter::Topic(topic) => {
-                            if topic.is_some_and(|topic| rpc_log.log.topics[i] != topic) {
-                                return false;
-                            }
-                        }
-                        TopicFilter::Topics(sub_topics) => {
-                            if !sub_topics.is_empty()
-                                && !sub_topics
-                                    .iter()
-                                    .any(|st| st.is_none_or(|t| rpc_log.log.topics[i] == t))
-                            {
-                                return false;
-                            }
-                        }
-                    }
-                }
-                true
-            })
-            .collect::<Vec<RpcLog>>()
-    };
-
-    Ok(filtered_logs)
+    Ok(logs)
 }
 
 #[cfg(test)]
@@ -280,4 +297,38 @@ mod tests {
         );
         assert_eq!(request.topics, vec![TopicFilter::Topic(Some(H256::zero()))]);
     }
+
+    #[test]
+    fn test_subscription_filter_is_optional() {
+        let filter = LogsFilter::parse_subscription(None).unwrap();
+        assert!(filter.address_filters.is_none());
+        assert!(filter.topics.is_empty());
+        assert!(filter.matches(&H160::from_low_u64_be(1), &[]));
+
+        let filter = LogsFilter::parse_subscription(Some(&json!({}))).unwrap();
+        assert!(filter.address_filters.is_none());
+        assert!(filter.topics.is_empty());
+
+        assert!(LogsFilter::parse_subscription(Some(&json!("0x01"))).is_err());
+    }
+
+    #[test]
+    fn test_filter_matches_address_and_topics() {
+        let topic = H256::from_low_u64_be(7);
+        let filter = LogsFilter::parse_subscription(Some(&json!({
+            "address": "0x0000000000000000000000000000000000000001",
+            "topics": [null, [topic, H256::zero()]]
+        })))
+        .unwrap();
+
+        let address = H160::from_low_u64_be(1);
+        assert!(filter.matches(&address, &[H256::repeat_byte(9), topic]));
+        assert!(filter.matches(&address, &[H256::repeat_byte(9), H256::zero(), topic]));
+        // Wrong address.
+        assert!(!filter.matches(&H160::from_low_u64_be(2), &[H256::zero(), topic]));
+        // Second topic not in the alternatives.
+        assert!(!filter.matches(&address, &[topic, H256::repeat_byte(9)]));
+        // Fewer topics than the filter positions.
+        assert!(!filter.matches(&address, &[topic]));
+    }
 }
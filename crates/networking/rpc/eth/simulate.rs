@@ -0,0 +1,566 @@
+//! `eth_simulateV1`: executes a chain of simulated blocks on top of a given block.
+//!
+//! Every block is built from the previous one (the first from the requested block) and
+//! can override header fields and account state before running its calls. All blocks
+//! share a single `Evm`, so state changes carry over from one block to the next without
+//! ever being written to the store. The state root of every block is computed on an
+//! in-memory copy of the requested block's state trie.
+
+use bytes::Bytes;
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    H256, serde_utils,
+    types::{
+        Block, BlockBody, BlockHeader, EIP1559Transaction, EIP7702Transaction,
+        ELASTICITY_MULTIPLIER, GenericTransaction, Receipt, Transaction, TxKind,
+        calculate_base_fee_per_gas, compute_receipts_root_and_logs_bloom,
+        compute_transactions_root, compute_withdrawals_root,
+    },
+};
+use ethrex_crypto::NativeCrypto;
+use ethrex_rlp::encode::RLPEncode;
+use ethrex_storage::Store;
+use ethrex_vm::{
+    Evm, ExecutionResult,
+    backends::levm::get_max_allowed_gas_limit,
+    overrides::{BlockOverrides, StateOverride},
+    simulate::SimulationOptions,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::debug;
+
+use crate::{
+    rpc::{RpcApiContext, RpcHandler},
+    types::{
+        block::{BlockBodyWrapper, FullBlockBody, OnlyHashesBlockBody, RpcBlock},
+        block_identifier::{BlockIdentifier, BlockIdentifierOrHash},
+        receipt::{RpcLog, RpcLogInfo},
+        transaction::RpcTransaction,
+    },
+    utils::{RpcErr, RpcErrorMetadata},
+};
+
+/// Maximum number of blocks (including the ones filling number gaps) a request may simulate.
+pub const MAX_SIMULATE_BLOCKS: u64 = 256;
+/// Seconds added to the parent timestamp when a block doesn't override its time.
+const SIMULATED_BLOCK_TIME: u64 = 12;
+
+pub struct SimulateV1Request {
+    payload: SimulationPayload,
+    block: Option<BlockIdentifierOrHash>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulationPayload {
+    block_state_calls: Vec<BlockStateCall>,
+    #[serde(default)]
+    trace_transfers: bool,
+    #[serde(default)]
+    validation: bool,
+    #[serde(default)]
+    return_full_transactions: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockStateCall {
+    #[serde(default)]
+    block_overrides: BlockOverrides,
+    #[serde(default)]
+    state_overrides: StateOverride,
+    #[serde(default)]
+    calls: Vec<GenericTransaction>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedBlock {
+    #[serde(flatten)]
+    block: RpcBlock,
+    calls: Vec<SimulatedCall>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimulatedCall {
+    #[serde(with = "serde_utils::bytes")]
+    return_data: Bytes,
+    logs: Vec<RpcLog>,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    gas_used: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    status: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<RpcErrorMetadata>,
+}
+
+impl RpcHandler for SimulateV1Request {
+    fn parse(params: &Option<Vec<Value>>) -> Result<SimulateV1Request, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.is_empty() || params.len() > 2 {
+            return Err(RpcErr::BadParams(format!(
+                "Expected one or two params and {} were provided",
+                params.len()
+            )));
+        }
+        let block = match params.get(1) {
+            // Differentiate between missing and bad block param
+            Some(value) => Some(BlockIdentifierOrHash::parse(value.clone(), 1)?),
+            None => None,
+        };
+        Ok(SimulateV1Request {
+            payload: serde_json::from_value(params[0].clone())?,
+            block,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let block = self
+            .block
+            .clone()
+            .unwrap_or(BlockIdentifierOrHash::Identifier(BlockIdentifier::default()));
+        debug!("Requested simulation on block: {}", block);
+        let Some(mut parent) = block.resolve_block_header(&context.storage).await? else {
+            return Ok(Value::Null);
+        };
+        let blocks = sanitize_chain(&parent, &self.payload.block_state_calls)?;
+
+        let base_state_root = parent.state_root;
+        let vm_db = StoreVmDatabase::new(context.storage.clone(), parent.clone())?;
+        // Simulated blocks aren't in the store, so BLOCKHASH can only find them here
+        let block_hashes = vm_db.block_hash_cache.clone();
+        let mut vm = context.blockchain.new_evm(vm_db)?;
+        let mut options = SimulationOptions {
+            validation: self.payload.validation,
+            trace_transfers: self.payload.trace_transfers,
+            ..Default::default()
+        };
+
+        let mut simulated_blocks = Vec::with_capacity(blocks.len());
+        for block in &blocks {
+            block_hashes
+                .lock()
+                .map_err(|_| RpcErr::Internal("LockError".to_string()))?
+                .insert(parent.number, parent.hash());
+            let header = simulated_header(&parent, &block.block_overrides, options.validation);
+            let (simulated, header) = simulate_block(
+                &mut vm,
+                header,
+                block,
+                &mut options,
+                &context.storage,
+                base_state_root,
+                self.payload.return_full_transactions,
+            )?;
+            simulated_blocks.push(simulated);
+            parent = header;
+        }
+
+        serde_json::to_value(simulated_blocks).map_err(|error| RpcErr::Internal(error.to_string()))
+    }
+}
+
+/// Assigns a number and timestamp to every requested block and inserts empty blocks where
+/// the requested numbers skip ahead, so the result is a contiguous chain on top of `parent`.
+fn sanitize_chain(
+    parent: &BlockHeader,
+    blocks: &[BlockStateCall],
+) -> Result<Vec<BlockStateCall>, RpcErr> {
+    let mut chain: Vec<BlockStateCall> = Vec::with_capacity(blocks.len());
+    let (mut number, mut time) = (parent.number, parent.timestamp);
+    for block in blocks {
+        let target = block.block_overrides.number.unwrap_or(number + 1);
+        if target <= number {
+            return Err(RpcErr::InvalidBlockNumber(format!(
+                "block numbers must be in order: {target} <= {number}"
+            )));
+        }
+        if target - parent.number > MAX_SIMULATE_BLOCKS {
+            return Err(RpcErr::ClientLimitExceeded(format!(
+                "too many blocks: at most {MAX_SIMULATE_BLOCKS} can be simulated"
+            )));
+        }
+        for gap in number + 1..target {
+            time += SIMULATED_BLOCK_TIME;
+            let mut empty = BlockStateCall::default();
+            empty.block_overrides.number = Some(gap);
+            empty.block_overrides.time = Some(time);
+            chain.push(empty);
+        }
+
+        let target_time = block
+            .block_overrides
+            .time
+            .unwrap_or(time + SIMULATED_BLOCK_TIME);
+        if target_time <= time {
+            return Err(RpcErr::InvalidBlockTimestamp(format!(
+                "block timestamps must be in order: {target_time} <= {time}"
+            )));
+        }
+        let mut block = block.clone();
+        block.block_overrides.number = Some(target);
+        block.block_overrides.time = Some(target_time);
+        chain.push(block);
+        (number, time) = (target, target_time);
+    }
+    Ok(chain)
+}
+
+/// Builds the header of a simulated block on top of `parent`. Without validation the base
+/// fee is zero unless overridden, matching `eth_call`.
+fn simulated_header(
+    parent: &BlockHeader,
+    overrides: &BlockOverrides,
+    validation: bool,
+) -> BlockHeader {
+    let mut header = BlockHeader {
+        hash: Default::default(),
+        parent_hash: parent.hash(),
+        gas_used: 0,
+        blob_gas_used: parent.blob_gas_used.map(|_| 0),
+        ..parent.clone()
+    };
+    overrides.apply(&mut header);
+    if overrides.base_fee_per_gas.is_none()
+        && let Some(parent_base_fee) = parent.base_fee_per_gas
+    {
+        header.base_fee_per_gas = Some(if validation {
+            calculate_base_fee_per_gas(
+                header.gas_limit,
+                parent.gas_limit,
+                parent.gas_used,
+                parent_base_fee,
+                ELASTICITY_MULTIPLIER,
+            )
+            .unwrap_or(parent_base_fee)
+        } else {
+            0
+        });
+    }
+    header
+}
+
+/// Runs the calls of `block` and returns its RPC representation along with the final
+/// header, which becomes the parent of the next simulated block.
+fn simulate_block(
+    vm: &mut Evm,
+    mut header: BlockHeader,
+    block: &BlockStateCall,
+    options: &mut SimulationOptions,
+    store: &Store,
+    base_state_root: H256,
+    full_transactions: bool,
+) -> Result<(SimulatedBlock, BlockHeader), RpcErr> {
+    let chain_config = store.get_chain_config();
+    vm.begin_simulated_block(&header, &block.state_overrides, options)?;
+    let gas_cap = get_max_allowed_gas_limit(header.gas_limit, chain_config.fork(header.timestamp));
+
+    let mut transactions = Vec::with_capacity(block.calls.len());
+    let mut senders = Vec::with_capacity(block.calls.len());
+    let mut receipts = Vec::with_capacity(block.calls.len());
+    let mut results = Vec::with_capacity(block.calls.len());
+    for call in &block.calls {
+        let mut tx = call.clone();
+        let remaining = header.gas_limit.saturating_sub(header.gas_used);
+        let gas = tx.gas.unwrap_or(remaining.min(gas_cap));
+        if gas > remaining {
+            return Err(RpcErr::BlockGasLimitExceeded(format!(
+                "block gas limit reached: call needs {gas} gas, {remaining} left in block {}",
+                header.number
+            )));
+        }
+        tx.gas = Some(gas);
+        if tx.nonce.is_none() {
+            let account = vm
+                .db
+                .get_account(tx.from)
+                .map_err(|error| RpcErr::Internal(error.to_string()))?;
+            tx.nonce = Some(account.info.nonce);
+        }
+        tx.chain_id = tx.chain_id.or(Some(chain_config.chain_id));
+
+        let result = vm.simulate_call(&tx, &header, options)?;
+        header.gas_used += result.gas_used();
+        let transaction = simulated_transaction(&tx, chain_config.chain_id);
+        receipts.push(Receipt::new(
+            transaction.tx_type(),
+            result.is_success(),
+            header.gas_used,
+            result.logs(),
+        ));
+        transactions.push(transaction);
+        senders.push(tx.from);
+        results.push(result);
+    }
+
+    header.state_root = simulated_state_root(vm, store, base_state_root)?;
+    let (receipts_root, logs_bloom) =
+        compute_receipts_root_and_logs_bloom(&receipts, &NativeCrypto);
+    header.transactions_root = compute_transactions_root(&transactions, &NativeCrypto);
+    header.receipts_root = receipts_root;
+    header.logs_bloom = logs_bloom;
+    header.withdrawals_root = header
+        .withdrawals_root
+        .map(|_| compute_withdrawals_root(&[], &NativeCrypto));
+    let hash = header.hash();
+
+    let mut log_index = 0;
+    let calls = results
+        .into_iter()
+        .zip(&transactions)
+        .enumerate()
+        .map(|(index, (result, transaction))| {
+            let logs = result
+                .logs()
+                .into_iter()
+                .map(|log| {
+                    let log = RpcLog {
+                        log: RpcLogInfo::from(log),
+                        log_index,
+                        removed: false,
+                        transaction_hash: transaction.hash(),
+                        transaction_index: index as u64,
+                        block_hash: hash,
+                        block_number: header.number,
+                    };
+                    log_index += 1;
+                    log
+                })
+                .collect();
+            simulated_call(result, logs)
+        })
+        .collect();
+
+    let body = BlockBody {
+        transactions,
+        ommers: Vec::new(),
+        withdrawals: header.withdrawals_root.map(|_| Vec::new()),
+    };
+    let size = Block::new(header.clone(), body.clone()).length() as u64;
+    let body = if full_transactions {
+        BlockBodyWrapper::Full(FullBlockBody {
+            transactions: body
+                .transactions
+                .into_iter()
+                .zip(senders)
+                .enumerate()
+                .map(|(index, (tx, from))| {
+                    RpcTransaction::build_with_sender(
+                        tx,
+                        from,
+                        Some(header.number),
+                        Some(hash),
+                        Some(index),
+                    )
+                })
+                .collect(),
+            uncles: Vec::new(),
+            withdrawals: body.withdrawals.unwrap_or_default(),
+        })
+    } else {
+        BlockBodyWrapper::OnlyHashes(OnlyHashesBlockBody {
+            transactions: body.transactions.iter().map(Transaction::hash).collect(),
+            uncles: Vec::new(),
+            withdrawals: body.withdrawals.unwrap_or_default(),
+        })
+    };
+
+    let block = RpcBlock {
+        hash,
+        size,
+        header: header.clone(),
+        body,
+    };
+    Ok((SimulatedBlock { block, calls }, header))
+}
+
+/// Computes the state root after the blocks simulated so far by applying every change
+/// they made to the state at `base_state_root`. The trie changes are never committed.
+fn simulated_state_root(vm: &Evm, store: &Store, base_state_root: H256) -> Result<H256, RpcErr> {
+    let account_updates = vm.simulated_state_transitions()?;
+    let mut state_trie = store.open_state_trie(base_state_root)?;
+    Ok(store
+        .apply_account_updates_from_trie_batch(&mut state_trie, &account_updates)?
+        .state_trie_hash)
+}
+
+fn simulated_call(result: ExecutionResult, logs: Vec<RpcLog>) -> SimulatedCall {
+    let gas_used = result.gas_used();
+    match result {
+        ExecutionResult::Success { output, .. } => SimulatedCall {
+            return_data: output,
+            logs,
+            gas_used,
+            status: 1,
+            error: None,
+        },
+        ExecutionResult::Revert { output, .. } => SimulatedCall {
+            error: Some(
+                RpcErr::Revert {
+                    data: format!("0x{output:#x}"),
+                }
+                .into(),
+            ),
+            return_data: output,
+            logs,
+            gas_used,
+            status: 0,
+        },
+        ExecutionResult::Halt { reason, .. } => SimulatedCall {
+            return_data: Bytes::new(),
+            logs,
+            gas_used,
+            status: 0,
+            error: Some(RpcErr::Vm(reason).into()),
+        },
+    }
+}
+
+/// Builds the (unsigned) transaction that represents a simulated call in its block.
+fn simulated_transaction(tx: &GenericTransaction, chain_id: u64) -> Transaction {
+    let max_fee_per_gas = tx
+        .max_fee_per_gas
+        .unwrap_or(u64::try_from(tx.gas_price).unwrap_or(u64::MAX));
+    let max_priority_fee_per_gas = tx.max_priority_fee_per_gas.unwrap_or(max_fee_per_gas);
+    let access_list = tx
+        .access_list
+        .iter()
+        .map(|entry| (entry.address, entry.storage_keys.clone()))
+        .collect();
+    match (&tx.authorization_list, &tx.to) {
+        (Some(authorization_list), TxKind::Call(to)) => {
+            Transaction::EIP7702Transaction(EIP7702Transaction {
+                chain_id,
+                nonce: tx.nonce.unwrap_or_default(),
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas_limit: tx.gas.unwrap_or_default(),
+                to: *to,
+                value: tx.value,
+                data: tx.input.clone(),
+                access_list,
+                authorization_list: authorization_list.iter().cloned().map(Into::into).collect(),
+                ..Default::default()
+            })
+        }
+        _ => Transaction::EIP1559Transaction(EIP1559Transaction {
+            chain_id,
+            nonce: tx.nonce.unwrap_or_default(),
+            max_priority_fee_per_gas,
+            max_fee_per_gas,
+            gas_limit: tx.gas.unwrap_or_default(),
+            to: tx.to.clone(),
+            value: tx.value,
+            data: tx.input.clone(),
+            access_list,
+            ..Default::default()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::Address;
+    use serde_json::json;
+
+    fn parent() -> BlockHeader {
+        BlockHeader {
+            number: 10,
+            timestamp: 1000,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(7),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parse_simulate_request() {
+        let params = Some(vec![
+            json!({
+                "blockStateCalls": [{
+                    "blockOverrides": {"number": "0xc", "baseFeePerGas": "0x0"},
+                    "stateOverrides": {
+                        "0x0000000000000000000000000000000000000001": {
+                            "movePrecompileToAddress": "0x0000000000000000000000000000000000123456"
+                        }
+                    },
+                    "calls": [{
+                        "from": "0x0000000000000000000000000000000000000aaa",
+                        "to": "0x0000000000000000000000000000000000000bbb",
+                        "value": "0x1"
+                    }]
+                }],
+                "traceTransfers": true
+            }),
+            json!("latest"),
+        ]);
+        let request = SimulateV1Request::parse(&params).unwrap();
+        assert!(request.payload.trace_transfers);
+        assert!(!request.payload.validation);
+        let block = &request.payload.block_state_calls[0];
+        assert_eq!(block.block_overrides.number, Some(12));
+        assert_eq!(block.calls.len(), 1);
+        assert_eq!(
+            block.state_overrides[&Address::from_low_u64_be(1)].move_precompile_to_address,
+            Some(Address::from_low_u64_be(0x123456))
+        );
+    }
+
+    #[test]
+    fn sanitize_fills_number_gaps() {
+        let mut skip = BlockStateCall::default();
+        skip.block_overrides.number = Some(13);
+        let chain = sanitize_chain(&parent(), &[BlockStateCall::default(), skip]).unwrap();
+
+        let numbers: Vec<_> = chain
+            .iter()
+            .map(|block| block.block_overrides.number.unwrap())
+            .collect();
+        let times: Vec<_> = chain
+            .iter()
+            .map(|block| block.block_overrides.time.unwrap())
+            .collect();
+        assert_eq!(numbers, vec![11, 12, 13]);
+        assert_eq!(times, vec![1012, 1024, 1036]);
+    }
+
+    #[test]
+    fn sanitize_rejects_unordered_blocks() {
+        let mut past = BlockStateCall::default();
+        past.block_overrides.number = Some(10);
+        assert!(matches!(
+            sanitize_chain(&parent(), &[past]),
+            Err(RpcErr::InvalidBlockNumber(_))
+        ));
+
+        let mut too_far = BlockStateCall::default();
+        too_far.block_overrides.number = Some(11 + MAX_SIMULATE_BLOCKS);
+        assert!(matches!(
+            sanitize_chain(&parent(), &[too_far]),
+            Err(RpcErr::ClientLimitExceeded(_))
+        ));
+
+        let mut stale = BlockStateCall::default();
+        stale.block_overrides.time = Some(1000);
+        assert!(matches!(
+            sanitize_chain(&parent(), &[stale]),
+            Err(RpcErr::InvalidBlockTimestamp(_))
+        ));
+    }
+
+    #[test]
+    fn simulated_header_zeroes_base_fee_without_validation() {
+        let parent = parent();
+        let header = simulated_header(&parent, &BlockOverrides::default(), false);
+        assert_eq!(header.parent_hash, parent.hash());
+        assert_eq!(header.base_fee_per_gas, Some(0));
+
+        let header = simulated_header(&parent, &BlockOverrides::default(), true);
+        assert!(header.base_fee_per_gas.is_some_and(|fee| fee > 0));
+    }
+}
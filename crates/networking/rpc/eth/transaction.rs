@@ -11,14 +11,18 @@ use crate::{
 };
 use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
 use ethrex_common::{
-    H256,
+    Address, H256, U256,
     types::{AccessListEntry, BlockHash, BlockHeader, BlockNumber, GenericTransaction, TxKind},
 };
 
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_storage::Store;
 
-use ethrex_vm::{ExecutionResult, backends::levm::get_max_allowed_gas_limit};
+use ethrex_vm::{
+    ExecutionResult,
+    backends::levm::get_max_allowed_gas_limit,
+    overrides::{BlockOverrides, CallOverrides, StateOverride},
+};
 use serde::Serialize;
 
 use serde_json::Value;
@@ -31,6 +35,7 @@ pub const TRANSACTION_GAS: u64 = 21_000; // Per transaction not creating a contr
 pub struct CallRequest {
     transaction: GenericTransaction,
     block: Option<BlockIdentifierOrHash>,
+    overrides: CallOverrides,
 }
 
 pub struct GetTransactionByBlockNumberAndIndexRequest {
@@ -58,6 +63,7 @@ pub struct CreateAccessListRequest {
 pub struct EstimateGasRequest {
     pub transaction: GenericTransaction,
     pub block: Option<BlockIdentifier>,
+    pub overrides: CallOverrides,
 }
 
 pub struct GetRawTransaction {
@@ -82,9 +88,9 @@ impl RpcHandler for CallRequest {
         if params.is_empty() {
             return Err(RpcErr::BadParams("No params provided".to_owned()));
         }
-        if params.len() > 2 {
+        if params.len() > 4 {
             return Err(RpcErr::BadParams(format!(
-                "Expected one or two params and {} were provided",
+                "Expected one to four params and {} were provided",
                 params.len()
             )));
         }
@@ -96,6 +102,7 @@ impl RpcHandler for CallRequest {
         Ok(CallRequest {
             transaction: serde_json::from_value(params[0].clone())?,
             block,
+            overrides: parse_call_overrides(params)?,
         })
     }
     async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
@@ -113,6 +120,7 @@ impl RpcHandler for CallRequest {
         let result = simulate_tx(
             &self.transaction,
             &header,
+            &self.overrides,
             context.storage,
             context.blockchain,
         )?;
@@ -420,9 +428,9 @@ impl RpcHandler for EstimateGasRequest {
         if params.is_empty() {
             return Err(RpcErr::BadParams("No params provided".to_owned()));
         }
-        if params.len() > 2 {
+        if params.len() > 4 {
             return Err(RpcErr::BadParams(format!(
-                "Expected one or two params and {} were provided",
+                "Expected one to four params and {} were provided",
                 params.len()
             )));
         }
@@ -434,6 +442,7 @@ impl RpcHandler for EstimateGasRequest {
         Ok(EstimateGasRequest {
             transaction: serde_json::from_value(params[0].clone())?,
             block,
+            overrides: parse_call_overrides(params)?,
         })
     }
     async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
@@ -449,14 +458,29 @@ impl RpcHandler for EstimateGasRequest {
             _ => return Ok(Value::Null),
         };
 
-        let current_fork = chain_config.fork(block_header.timestamp);
+        // Gas limits and fork rules follow the header the transaction will execute on, while
+        // the state is still read from the requested block.
+        let mut execution_header = block_header.clone();
+        self.overrides.block.apply(&mut execution_header);
+        let current_fork = chain_config.fork(execution_header.timestamp);
+        let account_override = |address: Address| self.overrides.state.get(&address);
 
         let transaction = match self.transaction.nonce {
             Some(_nonce) => self.transaction.clone(),
             None => {
-                let transaction_nonce = storage
-                    .get_nonce_by_account_address(block_header.number, self.transaction.from)
-                    .await?;
+                let transaction_nonce = match account_override(self.transaction.from)
+                    .and_then(|account| account.nonce)
+                {
+                    Some(nonce) => Some(nonce),
+                    None => {
+                        storage
+                            .get_nonce_by_account_address(
+                                block_header.number,
+                                self.transaction.from,
+                            )
+                            .await?
+                    }
+                };
 
                 let mut cloned_transaction = self.transaction.clone();
                 cloned_transaction.nonce = transaction_nonce;
@@ -470,12 +494,16 @@ impl RpcHandler for EstimateGasRequest {
                 .get_account_info(block_header.number, address)
                 .await?;
             let code = account_info.map(|info| storage.get_account_code(info.code_hash));
-            if code.is_none() {
+            let code_overridden = account_override(address)
+                .and_then(|account| account.code.as_ref())
+                .is_some_and(|code| !code.is_empty());
+            if code.is_none() && !code_overridden {
                 let mut value_transfer_transaction = transaction.clone();
                 value_transfer_transaction.gas = Some(TRANSACTION_GAS);
                 let result: Result<ExecutionResult, RpcErr> = simulate_tx(
                     &value_transfer_transaction,
                     &block_header,
+                    &self.overrides,
                     storage.clone(),
                     blockchain.clone(),
                 );
@@ -487,7 +515,7 @@ impl RpcHandler for EstimateGasRequest {
         }
 
         // Prepare binary search
-        let highest_gas_limit = get_max_allowed_gas_limit(block_header.gas_limit, current_fork);
+        let highest_gas_limit = get_max_allowed_gas_limit(execution_header.gas_limit, current_fork);
         let mut highest_gas_limit = match transaction.gas {
             Some(gas) => gas.min(highest_gas_limit),
             None => highest_gas_limit,
@@ -497,6 +525,7 @@ impl RpcHandler for EstimateGasRequest {
             highest_gas_limit = recap_with_account_balances(
                 highest_gas_limit,
                 &transaction,
+                account_override(transaction.from).and_then(|account| account.balance),
                 storage,
                 block_header.number,
             )
@@ -509,6 +538,7 @@ impl RpcHandler for EstimateGasRequest {
         let result = simulate_tx(
             &transaction,
             &block_header,
+            &self.overrides,
             storage.clone(),
             blockchain.clone(),
         )?;
@@ -537,6 +567,7 @@ impl RpcHandler for EstimateGasRequest {
             let result = simulate_tx(
                 &transaction,
                 &block_header,
+                &self.overrides,
                 storage.clone(),
                 blockchain.clone(),
             );
@@ -556,30 +587,72 @@ impl RpcHandler for EstimateGasRequest {
 async fn recap_with_account_balances(
     highest_gas_limit: u64,
     transaction: &GenericTransaction,
+    balance_override: Option<U256>,
     storage: &Store,
     block_number: BlockNumber,
 ) -> Result<u64, RpcErr> {
-    let account_balance = storage
-        .get_account_info(block_number, transaction.from)
-        .await?
-        .map(|acc| acc.balance)
-        .unwrap_or_default();
+    let account_balance = match balance_override {
+        Some(balance) => balance,
+        None => storage
+            .get_account_info(block_number, transaction.from)
+            .await?
+            .map(|acc| acc.balance)
+            .unwrap_or_default(),
+    };
     let account_gas = account_balance.saturating_sub(transaction.value) / transaction.gas_price;
     // If account_gas exceeds u64, the account can afford any gas limit.
     let account_gas = u64::try_from(account_gas).unwrap_or(highest_gas_limit);
     Ok(highest_gas_limit.min(account_gas))
 }
 
+/// Parses the optional state (third) and block (fourth) override params shared by
+/// `eth_call` and `eth_estimateGas`.
+fn parse_call_overrides(params: &[Value]) -> Result<CallOverrides, RpcErr> {
+    let state: Option<StateOverride> = params
+        .get(2)
+        .map(|value| serde_json::from_value(value.clone()))
+        .transpose()?
+        .flatten();
+    let block: Option<BlockOverrides> = params
+        .get(3)
+        .map(|value| serde_json::from_value(value.clone()))
+        .transpose()?
+        .flatten();
+    let state = state.unwrap_or_default();
+    reject_moved_precompiles(&state)?;
+    Ok(CallOverrides {
+        state,
+        block: block.unwrap_or_default(),
+    })
+}
+
+/// `movePrecompileToAddress` is only supported by `eth_simulateV1`, other endpoints
+/// reject it instead of silently running the precompile at its original address.
+pub(crate) fn reject_moved_precompiles(state: &StateOverride) -> Result<(), RpcErr> {
+    match state
+        .iter()
+        .find(|(_, account)| account.move_precompile_to_address.is_some())
+    {
+        Some((address, _)) => Err(RpcErr::BadParams(format!(
+            "movePrecompileToAddress of {address:#x} is only supported by eth_simulateV1"
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Executes `transaction` on top of the state after `block_header`, with `overrides`
+/// layered on top of that state and of the header.
 fn simulate_tx(
     transaction: &GenericTransaction,
     block_header: &BlockHeader,
+    overrides: &CallOverrides,
     storage: Store,
     blockchain: Arc<Blockchain>,
 ) -> Result<ExecutionResult, RpcErr> {
     let vm_db = StoreVmDatabase::new(storage, block_header.clone())?;
     let mut vm = blockchain.new_evm(vm_db)?;
 
-    match vm.simulate_tx_from_generic(transaction, block_header)? {
+    match vm.simulate_tx_with_overrides(transaction, block_header, overrides)? {
         ExecutionResult::Revert {
             gas_used: _,
             output,
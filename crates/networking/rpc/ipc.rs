@@ -0,0 +1,189 @@
+//! JSON-RPC over a Unix domain socket.
+//!
+//! Each connection carries newline-delimited JSON: every line is a request (or a batch)
+//! and every response or `eth_subscription` notification is written back as a single
+//! line. Requests are processed with the same pipeline as WebSocket messages, so
+//! subscriptions and batches behave exactly like they do over WS.
+//!
+//! The socket file is only accessible to the user running the node, so unlike the
+//! HTTP/WS endpoints IPC serves every namespace (including `admin` and `debug`)
+//! regardless of `--http.api`. The `engine` namespace stays on the authenticated port.
+
+use std::collections::HashSet;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use futures::StreamExt;
+use serde_json::Value;
+use spawned_concurrency::tasks::ActorRef;
+use tokio::io::AsyncWriteExt;
+use tokio::net::{UnixListener, UnixStream};
+use tokio_util::codec::{FramedRead, LinesCodec, LinesCodecError};
+use tracing::warn;
+
+use crate::rpc::{RpcApiContext, handle_ws_request, shutdown_signal};
+use crate::subscription_manager::{SUBSCRIBER_CHANNEL_CAPACITY, SubscriptionManager};
+use crate::utils::{RpcErr, RpcErrorMetadata, RpcNamespace, RpcRequest};
+
+/// Namespaces served over IPC.
+pub const IPC_NAMESPACES: &[RpcNamespace] = &[
+    RpcNamespace::Eth,
+    RpcNamespace::Admin,
+    RpcNamespace::Debug,
+    RpcNamespace::Web3,
+    RpcNamespace::Net,
+    RpcNamespace::Mempool,
+    RpcNamespace::Trace,
+];
+
+/// Maximum length of a request line. Longer lines close the connection instead of being
+/// buffered without bound. Matches the default WebSocket message size limit.
+pub const MAX_IPC_LINE_LENGTH: usize = 64 << 20;
+
+/// Configuration for the IPC RPC server.
+#[derive(Clone)]
+pub struct IpcConfig {
+    /// Path of the Unix domain socket.
+    pub path: PathBuf,
+    /// Actor handle for managing `eth_subscribe` / `eth_unsubscribe` connections.
+    pub subscription_manager: ActorRef<SubscriptionManager>,
+}
+
+/// Returns a copy of `context` with every [`IPC_NAMESPACES`] namespace allowed.
+pub fn ipc_context(context: &RpcApiContext) -> RpcApiContext {
+    let mut context = context.clone();
+    context.allowed_namespaces = Arc::new(IPC_NAMESPACES.iter().copied().collect::<HashSet<_>>());
+    context
+}
+
+/// Serves JSON-RPC on `listener` (bound with [`bind_ipc`] at `path`) until SIGINT is
+/// received, then removes the socket file.
+///
+/// `context` is used as-is, so callers should pass it through [`ipc_context`] first.
+/// The `route_request` closure handles non-subscription methods, like in
+/// [`handle_websocket`](crate::rpc::handle_websocket).
+pub async fn serve_ipc<F, Fut, E>(
+    listener: UnixListener,
+    path: PathBuf,
+    context: RpcApiContext,
+    route_request: F,
+) -> std::io::Result<()>
+where
+    F: Fn(RpcRequest) -> Fut + Clone + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<Value, E>> + Send,
+    E: Into<RpcErrorMetadata> + Send,
+{
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => {
+                    let context = context.clone();
+                    let route_request = route_request.clone();
+                    tokio::spawn(async move {
+                        handle_ipc_connection(stream, &context, route_request).await;
+                    });
+                }
+                Err(err) => warn!("Failed to accept IPC connection: {err}"),
+            }
+        }
+    }
+
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+/// Binds the IPC socket at `path`, replacing a stale one left behind by a previous run.
+pub fn bind_ipc(path: &Path) -> Result<UnixListener, RpcErr> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(parent).map_err(|error| {
+            RpcErr::Internal(format!("Failed to create IPC socket directory: {error}"))
+        })?;
+    }
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_socket() => {
+            std::fs::remove_file(path).map_err(|error| {
+                RpcErr::Internal(format!("Failed to remove stale IPC socket: {error}"))
+            })?;
+        }
+        Ok(_) => {
+            return Err(RpcErr::Internal(format!(
+                "IPC path {} exists and is not a socket",
+                path.display()
+            )));
+        }
+        Err(_) => {}
+    }
+    let listener = UnixListener::bind(path).map_err(|error| RpcErr::Internal(error.to_string()))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|error| RpcErr::Internal(error.to_string()))?;
+    Ok(listener)
+}
+
+/// Handle an IPC connection until the client closes it.
+pub async fn handle_ipc_connection<F, Fut, E>(
+    stream: UnixStream,
+    context: &RpcApiContext,
+    route_request: F,
+) where
+    F: Fn(RpcRequest) -> Fut,
+    Fut: std::future::Future<Output = Result<Value, E>>,
+    E: Into<RpcErrorMetadata>,
+{
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = FramedRead::new(reader, LinesCodec::new_with_max_length(MAX_IPC_LINE_LENGTH));
+    let (out_tx, mut out_rx) = tokio::sync::mpsc::channel::<String>(SUBSCRIBER_CHANNEL_CAPACITY);
+    let mut subscription_ids: Vec<String> = Vec::new();
+
+    loop {
+        tokio::select! {
+            line = lines.next() => {
+                let line = match line {
+                    Some(Ok(line)) => line,
+                    Some(Err(LinesCodecError::MaxLineLengthExceeded)) => {
+                        warn!("IPC request exceeds {MAX_IPC_LINE_LENGTH} bytes, closing the connection");
+                        break;
+                    }
+                    _ => break,
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let response = handle_ws_request(
+                    &line, context, &out_tx, &mut subscription_ids, &route_request,
+                ).await;
+                if let Some(resp) = response
+                    && write_line(&mut writer, resp).await.is_err()
+                {
+                    break;
+                }
+            }
+
+            Some(msg) = out_rx.recv() => {
+                if write_line(&mut writer, msg).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    if let Some(subscription_manager) = context.subscription_manager() {
+        for id in subscription_ids {
+            let _ = subscription_manager.unsubscribe(id).await;
+        }
+    }
+}
+
+async fn write_line(
+    writer: &mut tokio::net::unix::OwnedWriteHalf,
+    mut msg: String,
+) -> std::io::Result<()> {
+    msg.push('\n');
+    writer.write_all(msg.as_bytes()).await
+}
@@ -8,6 +8,7 @@
 //! - **HTTP API**: Public JSON-RPC endpoint for client requests (`eth_*`, `debug_*`, `net_*`, etc.)
 //! - **WebSocket API**: Optional WebSocket endpoint for subscriptions and real-time updates
 //! - **Auth RPC API**: Authenticated endpoint for consensus client communication (`engine_*` methods)
+//! - **IPC API**: Optional Unix domain socket serving every non-engine namespace to local tooling
 //!
 //! ## Supported Namespaces
 //!
@@ -18,6 +19,7 @@
 //! - `admin`: Node administration methods
 //! - `web3`: Web3 utility methods
 //! - `txpool`: Transaction pool inspection methods
+//! - `trace`: OpenEthereum-style transaction tracing methods
 //!
 //! ## Usage
 //!
@@ -64,10 +66,12 @@ mod authentication;
 pub mod debug;
 pub mod engine;
 mod eth;
+pub mod ipc;
 mod mempool;
 mod net;
 pub mod rpc;
 pub mod subscription_manager;
+mod trace;
 mod tracing;
 
 pub mod clients;
@@ -82,11 +86,12 @@ pub mod test_utils;
 // TODO: These exports are needed by ethrex-l2-rpc, but we do not want to
 // export them in the public API of this crate.
 pub use eth::{
-    filter::{ActiveFilters, clean_outdated_filters},
+    filter::{ActiveFilters, DEFAULT_MAX_FILTERS, clean_outdated_filters},
     gas_price::GasPrice,
     gas_tip_estimator::GasTipEstimator,
     transaction::EstimateGasRequest,
 };
+pub use ipc::IpcConfig;
 pub use rpc::{
     ClientVersion, NodeData, RpcApiContext, RpcHandler, RpcRequestWrapper, WebSocketConfig,
     handle_eth_subscribe, handle_eth_unsubscribe, handle_websocket, map_debug_requests,
@@ -35,18 +35,29 @@ use crate::eth::{
     block_access_list::BlockAccessListRequest,
     client::{ChainId, Syncing},
     fee_market::FeeHistoryRequest,
-    filter::{self, ActiveFilters, DeleteFilterRequest, FilterChangesRequest, NewFilterRequest},
+    filter::{
+        self, ActiveFilters, DeleteFilterRequest, FilterChangesRequest, FilterLogsRequest,
+        NewBlockFilterRequest, NewFilterRequest, NewPendingTransactionFilterRequest,
+    },
     gas_price::GasPrice,
     gas_tip_estimator::GasTipEstimator,
     logs::LogsFilter,
+    simulate::SimulateV1Request,
     transaction::{
         CallRequest, CreateAccessListRequest, EstimateGasRequest, GetRawTransaction,
         GetTransactionByBlockHashAndIndexRequest, GetTransactionByBlockNumberAndIndexRequest,
         GetTransactionByHashRequest, GetTransactionReceiptRequest,
     },
 };
-use crate::subscription_manager::{SubscriptionManager, SubscriptionManagerProtocol};
-use crate::tracing::{TraceBlockByNumberRequest, TraceTransactionRequest};
+use crate::ipc::{IpcConfig, bind_ipc, ipc_context, serve_ipc};
+use crate::subscription_manager::{
+    SubscriptionKind, SubscriptionManager, SubscriptionManagerProtocol,
+};
+use crate::trace;
+use crate::tracing::{
+    TraceBlockByHashRequest, TraceBlockByNumberRequest, TraceBlockRequest, TraceCallRequest,
+    TraceTransactionRequest,
+};
 use crate::types::transaction::SendRawTransactionRequest;
 use crate::utils::{
     RpcErr, RpcErrorMetadata, RpcErrorResponse, RpcNamespace, RpcRequest, RpcRequestId,
@@ -199,8 +210,10 @@ pub struct RpcApiContext {
     pub storage: Store,
     /// Blockchain instance for block validation and execution.
     pub blockchain: Arc<Blockchain>,
-    /// Active log filters for `eth_newFilter` / `eth_getFilterChanges` endpoints.
+    /// Active filters for the `eth_new*Filter` / `eth_getFilterChanges` endpoints.
     pub active_filters: ActiveFilters,
+    /// Maximum number of filters that can be installed at the same time.
+    pub max_filters: usize,
     /// Sync manager for coordinating block synchronization (None for L2 nodes).
     pub syncer: Option<Arc<SyncManager>>,
     /// Peer handler for P2P network operations (None for L2 nodes).
@@ -217,6 +230,8 @@ pub struct RpcApiContext {
     pub block_worker_channel: UnboundedSender<BlockWorkerMessage>,
     /// WebSocket configuration. `None` when the WS server is disabled.
     pub ws: Option<WebSocketConfig>,
+    /// IPC configuration. `None` when the IPC server is disabled.
+    pub ipc: Option<IpcConfig>,
     /// Set of RPC namespaces that are allowed over the public HTTP/WS endpoints.
     ///
     /// Methods belonging to namespaces not in this set return `MethodNotFound`.
@@ -234,6 +249,17 @@ pub struct WebSocketConfig {
     pub subscription_manager: ActorRef<SubscriptionManager>,
 }
 
+impl RpcApiContext {
+    /// Returns the [`SubscriptionManager`] shared by the WS and IPC servers, if any
+    /// of them is enabled.
+    pub fn subscription_manager(&self) -> Option<&ActorRef<SubscriptionManager>> {
+        self.ws
+            .as_ref()
+            .map(|ws| &ws.subscription_manager)
+            .or_else(|| self.ipc.as_ref().map(|ipc| &ipc.subscription_manager))
+    }
+}
+
 impl std::fmt::Debug for RpcApiContext {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut s = f.debug_struct("RpcApiContext");
@@ -414,12 +440,16 @@ fn get_error_kind(err: &RpcErr) -> &'static str {
         RpcErr::InvalidHeaderFormat(_) => "InvalidHeaderFormat",
         RpcErr::InvalidPayload(_) => "InvalidPayload",
         RpcErr::ProofGenerationUnavailable(_) => "ProofGenerationUnavailable",
+        RpcErr::BlockGasLimitExceeded(_) => "BlockGasLimitExceeded",
+        RpcErr::InvalidBlockNumber(_) => "InvalidBlockNumber",
+        RpcErr::InvalidBlockTimestamp(_) => "InvalidBlockTimestamp",
+        RpcErr::ClientLimitExceeded(_) => "ClientLimitExceeded",
     }
 }
 
 /// Duration after which inactive filters are cleaned up.
 ///
-/// Filters created via `eth_newFilter` are automatically removed if not
+/// Filters created via `eth_new*Filter` are automatically removed if not
 /// accessed within this duration. In tests, this is set to 1 second for
 /// faster test execution.
 pub const FILTER_DURATION: Duration = {
@@ -478,13 +508,18 @@ pub fn start_block_executor(blockchain: Arc<Blockchain>) -> UnboundedSender<Bloc
 ///    methods (`eth_*`, `debug_*`, `net_*`, `admin_*`, `web3_*`, `txpool_*`).
 ///
 /// 2. **WebSocket Server** (`ws`): Optional endpoint that serves the same methods as
-///    HTTP plus the subscription methods `eth_subscribe` / `eth_unsubscribe` (currently
-///    only `"newHeads"` is supported). Enabled by passing a [`WebSocketConfig`]
-///    containing the listen address and the [`SubscriptionManager`] actor handle.
+///    HTTP plus the subscription methods `eth_subscribe` / `eth_unsubscribe` (`"newHeads"`,
+///    `"logs"`, `"newPendingTransactions"` and `"syncing"`). Enabled by passing a
+///    [`WebSocketConfig`] containing the listen address and the [`SubscriptionManager`]
+///    actor handle.
 ///
 /// 3. **Auth RPC Server** (`authrpc_addr`): JWT-authenticated endpoint for Engine API
 ///    methods (`engine_*`) used by consensus clients.
 ///
+/// 4. **IPC Server** (`ipc`): Optional Unix domain socket serving every namespace except
+///    `engine` with newline-delimited JSON framing, including subscriptions. Enabled by
+///    passing an [`IpcConfig`].
+///
 /// # Arguments
 ///
 /// * `http_addr` - Socket address for the HTTP server (e.g., `127.0.0.1:8545`)
@@ -502,6 +537,9 @@ pub fn start_block_executor(blockchain: Arc<Blockchain>) -> UnboundedSender<Bloc
 /// * `log_filter_handler` - Optional handler for dynamic log level changes
 /// * `gas_ceil` - Maximum gas limit for payload building
 /// * `extra_data` - Extra data to include in mined blocks
+/// * `max_filters` - Maximum number of filters installed at the same time
+/// * `ipc` - Optional [`IpcConfig`] with the socket path and the [`SubscriptionManager`]
+///   actor handle. `None` disables the IPC server.
 ///
 /// # Errors
 ///
@@ -527,6 +565,8 @@ pub async fn start_api(
     gas_ceil: u64,
     extra_data: String,
     allowed_namespaces: HashSet<RpcNamespace>,
+    max_filters: usize,
+    ipc: Option<IpcConfig>,
 ) -> Result<(), RpcErr> {
     // TODO: Refactor how filters are handled,
     // filters are used by the filters endpoints (eth_newFilter, eth_getFilterChanges, ...etc)
@@ -536,6 +576,7 @@ pub async fn start_api(
         storage,
         blockchain,
         active_filters: active_filters.clone(),
+        max_filters,
         syncer: Some(Arc::new(syncer)),
         peer_handler: Some(peer_handler),
         node_data: NodeData {
@@ -550,6 +591,7 @@ pub async fn start_api(
         gas_ceil,
         block_worker_channel,
         ws: ws.clone(),
+        ipc: ipc.clone(),
         allowed_namespaces: Arc::new(allowed_namespaces),
     };
 
@@ -619,6 +661,26 @@ pub async fn start_api(
         .into_future();
     info!("Starting Auth-RPC server at {authrpc_addr}");
 
+    let ipc_server = match ipc {
+        Some(ipc_config) => {
+            let ipc_listener = bind_ipc(&ipc_config.path)?;
+            info!("Starting IPC server at {}", ipc_config.path.display());
+            let ctx = ipc_context(&service_context);
+            let route_ctx = ctx.clone();
+            Some(serve_ipc(ipc_listener, ipc_config.path, ctx, move |req| {
+                let c = route_ctx.clone();
+                async move { map_http_requests(&req, c).await }
+            }))
+        }
+        None => None,
+    };
+    let ipc_server = async {
+        match ipc_server {
+            Some(server) => server.await,
+            None => Ok(()),
+        }
+    };
+
     if let Some(ref ws_config) = ws {
         let ws_handler = |ws: WebSocketUpgrade, State(ctx): State<RpcApiContext>| async move {
             ws.on_upgrade(|mut socket| async move {
@@ -641,10 +703,10 @@ pub async fn start_api(
             .into_future();
         info!("Starting WS server at {}", ws_config.addr);
 
-        let _ = tokio::try_join!(authrpc_server, http_server, ws_server)
+        let _ = tokio::try_join!(authrpc_server, http_server, ws_server, ipc_server)
             .inspect_err(|e| error!("Error shutting down servers: {e:?}"));
     } else {
-        let _ = tokio::try_join!(authrpc_server, http_server)
+        let _ = tokio::try_join!(authrpc_server, http_server, ipc_server)
             .inspect_err(|e| error!("Error shutting down servers: {e:?}"));
     }
 
@@ -802,8 +864,9 @@ pub async fn handle_authrpc_request(
 
 /// Handle a WebSocket connection.
 ///
-/// Supports eth_subscribe / eth_unsubscribe for "newHeads" in addition to
-/// regular JSON-RPC request-response calls that work the same as over HTTP.
+/// Supports eth_subscribe / eth_unsubscribe for "newHeads", "logs",
+/// "newPendingTransactions" and "syncing" in addition to regular JSON-RPC
+/// request-response calls that work the same as over HTTP.
 ///
 /// The `route_request` closure handles non-subscription JSON-RPC methods.
 /// L1 passes its own `map_http_requests`; L2 passes its variant so that
@@ -820,9 +883,8 @@ pub async fn handle_websocket<F, Fut, E>(
     let (out_tx, mut out_rx) = tokio::sync::mpsc::channel::<String>(
         crate::subscription_manager::SUBSCRIBER_CHANNEL_CAPACITY,
     );
-    // Currently only "newHeads" subscriptions are supported. When additional
-    // subscription types (e.g., "logs", "newPendingTransactions") are added,
-    // the subscription tracking below will need per-type handling.
+    // The subscription type is tracked by the `SubscriptionManager`; the
+    // connection only needs the IDs to clean them up once it closes.
     let mut subscription_ids: Vec<String> = Vec::new();
 
     loop {
@@ -854,14 +916,14 @@ pub async fn handle_websocket<F, Fut, E>(
         }
     }
 
-    if let Some(ws) = &context.ws {
+    if let Some(subscription_manager) = context.subscription_manager() {
         for id in subscription_ids {
-            let _ = ws.subscription_manager.unsubscribe(id).await;
+            let _ = subscription_manager.unsubscribe(id).await;
         }
     }
 }
 
-async fn handle_ws_request<F, Fut, E>(
+pub(crate) async fn handle_ws_request<F, Fut, E>(
     body: &str,
     context: &RpcApiContext,
     out_tx: &tokio::sync::mpsc::Sender<String>,
@@ -969,8 +1031,10 @@ fn ws_error_response(id: Option<RpcRequestId>, code: i32, message: &str) -> Stri
 
 /// Handle `eth_subscribe`.
 ///
-/// Only `"newHeads"` is supported. Registers this connection with the
-/// `SubscriptionManager` actor and returns the subscription ID.
+/// Supports `"newHeads"`, `"logs"` (with an optional `address`/`topics` filter
+/// object), `"newPendingTransactions"` (with an optional `true` to receive full
+/// transactions instead of hashes) and `"syncing"`. Registers this connection
+/// with the `SubscriptionManager` actor and returns the subscription ID.
 pub async fn handle_eth_subscribe(
     req: &crate::utils::RpcRequest,
     context: &RpcApiContext,
@@ -990,27 +1054,39 @@ pub async fn handle_eth_subscribe(
         )));
     }
 
-    match sub_type {
-        "newHeads" => {
-            let ws = context
-                .ws
-                .as_ref()
-                .ok_or_else(|| RpcErr::Internal("WebSocket server not enabled".to_string()))?;
-
-            let id = ws
-                .subscription_manager
-                .subscribe(out_tx.clone())
-                .await
-                .map_err(|e| RpcErr::Internal(format!("Subscription failed: {e}")))?
-                .ok_or_else(|| RpcErr::Internal("Global subscription cap reached".to_string()))?;
-
-            subscription_ids.push(id.clone());
-            Ok(Value::String(id))
+    let kind = match sub_type {
+        "newHeads" => SubscriptionKind::NewHeads,
+        "logs" => SubscriptionKind::Logs(Box::new(LogsFilter::parse_subscription(params.get(1))?)),
+        "newPendingTransactions" => {
+            let full = match params.get(1) {
+                Some(Value::Bool(full)) => *full,
+                Some(Value::Null) | None => false,
+                Some(_) => {
+                    return Err(RpcErr::WrongParam("fullTransactions".to_string()));
+                }
+            };
+            SubscriptionKind::NewPendingTransactions { full }
         }
-        other => Err(RpcErr::BadParams(format!(
-            "Unsupported subscription type: {other}"
-        ))),
-    }
+        "syncing" => SubscriptionKind::Syncing,
+        other => {
+            return Err(RpcErr::BadParams(format!(
+                "Unsupported subscription type: {other}"
+            )));
+        }
+    };
+
+    let subscription_manager = context.subscription_manager().ok_or_else(|| {
+        RpcErr::Internal("Subscriptions require the WS or IPC server".to_string())
+    })?;
+
+    let id = subscription_manager
+        .subscribe(out_tx.clone(), kind)
+        .await
+        .map_err(|e| RpcErr::Internal(format!("Subscription failed: {e}")))?
+        .ok_or_else(|| RpcErr::Internal("Global subscription cap reached".to_string()))?;
+
+    subscription_ids.push(id.clone());
+    Ok(Value::String(id))
 }
 
 /// Handle `eth_unsubscribe`.
@@ -1036,8 +1112,8 @@ pub async fn handle_eth_unsubscribe(
         return Ok(Value::Bool(false));
     };
 
-    let removed = if let Some(ref ws) = context.ws {
-        ws.subscription_manager
+    let removed = if let Some(subscription_manager) = context.subscription_manager() {
+        subscription_manager
             .unsubscribe(sub_id)
             .await
             .unwrap_or(false)
@@ -1068,6 +1144,7 @@ pub async fn map_http_requests(req: &RpcRequest, context: RpcApiContext) -> Resu
         RpcNamespace::Web3 => map_web3_requests(req, context),
         RpcNamespace::Net => map_net_requests(req, context).await,
         RpcNamespace::Mempool => map_mempool_requests(req, context),
+        RpcNamespace::Trace => map_trace_requests(req, context).await,
         // Engine is served on the authenticated port only. The CLI parser
         // already rejects `--http.api engine`, but `allowed_namespaces` can
         // also be built programmatically (e.g. in tests or future call sites),
@@ -1095,7 +1172,9 @@ pub async fn map_authrpc_requests(
 /// - Block queries: `eth_getBlockByNumber`, `eth_getBlockByHash`, `eth_blockNumber`
 /// - Transaction operations: `eth_sendRawTransaction`, `eth_getTransactionByHash`, `eth_getTransactionReceipt`
 /// - Gas estimation: `eth_estimateGas`, `eth_gasPrice`, `eth_maxPriorityFeePerGas`, `eth_feeHistory`
-/// - Filters: `eth_newFilter`, `eth_getFilterChanges`, `eth_uninstallFilter`, `eth_getLogs`
+/// - Filters: `eth_newFilter`, `eth_newBlockFilter`, `eth_newPendingTransactionFilter`,
+///   `eth_getFilterChanges`, `eth_getFilterLogs`, `eth_uninstallFilter`, `eth_getLogs`
+/// - Simulation: `eth_call`, `eth_simulateV1`
 /// - Misc: `eth_chainId`, `eth_syncing`, `eth_createAccessList`, `eth_getProof`
 pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
@@ -1125,13 +1204,39 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
         "eth_createAccessList" => CreateAccessListRequest::call(req, context).await,
         "eth_blockNumber" => BlockNumberRequest::call(req, context).await,
         "eth_call" => CallRequest::call(req, context).await,
+        "eth_simulateV1" => SimulateV1Request::call(req, context).await,
         "eth_blobBaseFee" => GetBlobBaseFee::call(req, context).await,
         "eth_getTransactionCount" => GetTransactionCountRequest::call(req, context).await,
         "eth_feeHistory" => FeeHistoryRequest::call(req, context).await,
         "eth_estimateGas" => EstimateGasRequest::call(req, context).await,
         "eth_getLogs" => LogsFilter::call(req, context).await,
         "eth_newFilter" => {
-            NewFilterRequest::stateful_call(req, context.storage, context.active_filters).await
+            NewFilterRequest::stateful_call(
+                req,
+                context.storage,
+                context.active_filters,
+                context.max_filters,
+            )
+            .await
+        }
+        "eth_newBlockFilter" => {
+            NewBlockFilterRequest::stateful_call(
+                req,
+                context.storage,
+                context.active_filters,
+                context.max_filters,
+            )
+            .await
+        }
+        "eth_newPendingTransactionFilter" => {
+            NewPendingTransactionFilterRequest::stateful_call(
+                req,
+                context.storage,
+                context.blockchain.subscribe_pending_transactions(),
+                context.active_filters,
+                context.max_filters,
+            )
+            .await
         }
         "eth_uninstallFilter" => {
             DeleteFilterRequest::stateful_call(req, context.storage, context.active_filters)
@@ -1139,6 +1244,9 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
         "eth_getFilterChanges" => {
             FilterChangesRequest::stateful_call(req, context.storage, context.active_filters).await
         }
+        "eth_getFilterLogs" => {
+            FilterLogsRequest::stateful_call(req, context.storage, context.active_filters).await
+        }
         "eth_sendRawTransaction" => SendRawTransactionRequest::call(req, context).await,
         "eth_getProof" => GetProofRequest::call(req, context).await,
         "eth_gasPrice" => GasPrice::call(req, context).await,
@@ -1155,7 +1263,8 @@ pub async fn map_eth_requests(req: &RpcRequest, context: RpcApiContext) -> Resul
 /// Handles debugging and introspection methods:
 /// - Raw data: `debug_getRawHeader`, `debug_getRawBlock`, `debug_getRawTransaction`, `debug_getRawReceipts`
 /// - Execution witness: `debug_executionWitness` (for stateless validation)
-/// - Tracing: `debug_traceTransaction`, `debug_traceBlockByNumber`
+/// - Tracing: `debug_traceTransaction`, `debug_traceBlockByNumber`, `debug_traceBlockByHash`,
+///   `debug_traceBlock`, `debug_traceCall`
 pub async fn map_debug_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
     match req.method.as_str() {
         "debug_getRawHeader" => GetRawHeaderRequest::call(req, context).await,
@@ -1169,6 +1278,9 @@ pub async fn map_debug_requests(req: &RpcRequest, context: RpcApiContext) -> Res
         "debug_chainConfig" => ChainConfigRequest::call(req, context).await,
         "debug_traceTransaction" => TraceTransactionRequest::call(req, context).await,
         "debug_traceBlockByNumber" => TraceBlockByNumberRequest::call(req, context).await,
+        "debug_traceBlockByHash" => TraceBlockByHashRequest::call(req, context).await,
+        "debug_traceBlock" => TraceBlockRequest::call(req, context).await,
+        "debug_traceCall" => TraceCallRequest::call(req, context).await,
         unknown_debug_method => Err(RpcErr::MethodNotFound(unknown_debug_method.to_owned())),
     }
 }
@@ -1250,6 +1362,9 @@ pub async fn map_admin_requests(
         "admin_syncStatus" => admin::sync_status(&mut context).await,
         "admin_setLogLevel" => admin::set_log_level(req, &context.log_filter_handler),
         "admin_addPeer" => admin::add_peer(&mut context, req).await,
+        "admin_removePeer" => admin::remove_peer(&mut context, req).await,
+        "admin_addTrustedPeer" => admin::add_trusted_peer(&mut context, req).await,
+        "admin_removeTrustedPeer" => admin::remove_trusted_peer(&mut context, req).await,
         unknown_admin_method => Err(RpcErr::MethodNotFound(unknown_admin_method.to_owned())),
     }
 }
@@ -1280,6 +1395,23 @@ pub fn map_mempool_requests(req: &RpcRequest, contex: RpcApiContext) -> Result<V
     }
 }
 
+/// Routes `trace_*` namespace requests to their handlers.
+///
+/// Handles OpenEthereum-style tracing methods:
+/// - `trace_block`, `trace_transaction`, `trace_filter`
+/// - `trace_replayBlockTransactions` (with `trace`, `stateDiff` and `vmTrace` modes)
+pub async fn map_trace_requests(req: &RpcRequest, context: RpcApiContext) -> Result<Value, RpcErr> {
+    match req.method.as_str() {
+        "trace_block" => trace::TraceBlockRequest::call(req, context).await,
+        "trace_transaction" => trace::TraceTransactionRequest::call(req, context).await,
+        "trace_filter" => trace::TraceFilterRequest::call(req, context).await,
+        "trace_replayBlockTransactions" => {
+            trace::ReplayBlockTransactionsRequest::call(req, context).await
+        }
+        unknown_trace_method => Err(RpcErr::MethodNotFound(unknown_trace_method.to_owned())),
+    }
+}
+
 /// Formats a handler result into a JSON-RPC 2.0 response.
 ///
 /// Wraps the result in either a success response (with `result` field) or
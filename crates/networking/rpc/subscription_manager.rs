@@ -1,15 +1,23 @@
 //! Actor-based subscription manager for WebSocket `eth_subscribe` connections.
 //!
 //! The `SubscriptionManager` is a GenServer actor that owns all subscription
-//! state. It receives `NewHead` messages from block producers / fork choice
-//! handlers and fans out notifications to all connected WebSocket clients
-//! through per-connection `mpsc` channels.
+//! state. It receives [`ChainEvent`]s from the [`Blockchain`](ethrex_blockchain::Blockchain)
+//! (head changes, new pending transactions and sync status changes), and fans
+//! out notifications to the matching WebSocket clients through per-connection
+//! `mpsc` channels.
 //!
-//! Using an actor removes the need for a `broadcast` channel and eliminates
-//! the "lagged subscriber" problem: when a connection drops, its sender is
-//! removed during the next `new_head` fan-out rather than silently accumulating
-//! unread messages.
+//! Supported subscription types are `newHeads`, `logs`, `newPendingTransactions`
+//! and `syncing`. On reorgs, `logs` subscribers first receive the logs of the
+//! blocks that left the canonical chain with `removed: true`.
+//!
+//! The chain events arrive through the blockchain's `broadcast` channels, which
+//! never block block import or mempool insertion: if the actor falls behind, the
+//! oldest events are dropped and a warning is logged. From there, each subscriber
+//! gets its own bounded channel, so a slow WebSocket client only loses its own
+//! notifications, and a dropped connection's sender is removed during the next
+//! fan-out rather than silently accumulating unread messages.
 
+use ethrex_blockchain::events::{BlockWithReceipts, ChainEvent};
 use ethrex_common::types::BlockHeader;
 use rand::RngCore;
 use serde_json::Value;
@@ -20,9 +28,14 @@ use spawned_concurrency::{
     tasks::{Actor, ActorRef, ActorStart as _, Context, Handler, Response},
 };
 use std::collections::HashMap;
-use tokio::sync::mpsc::Sender;
+use tokio::sync::{broadcast, mpsc::Sender};
 use tracing::{debug, warn};
 
+use crate::{
+    eth::logs::LogsFilter,
+    types::{receipt::RpcLog, transaction::RpcTransaction},
+};
+
 /// Maximum number of buffered notifications per subscriber.
 /// If a subscriber's channel is full (slow WebSocket client), the notification
 /// is dropped rather than blocking the actor. Matches Geth's approach of
@@ -49,7 +62,26 @@ pub const MAX_TOTAL_SUBSCRIPTIONS: usize = 10_000;
 /// corresponding WebSocket write-loop.
 #[derive(Default)]
 pub struct SubscriptionManager {
-    subscribers: HashMap<String, Sender<String>>,
+    subscribers: HashMap<String, Subscriber>,
+}
+
+/// The kind of events a subscription is notified about.
+#[derive(Debug, Clone)]
+pub enum SubscriptionKind {
+    /// `eth_subscribe("newHeads")`: every new canonical head.
+    NewHeads,
+    /// `eth_subscribe("logs", filter)`: logs matching the filter's addresses and topics.
+    Logs(Box<LogsFilter>),
+    /// `eth_subscribe("newPendingTransactions", full)`: transaction hashes, or whole
+    /// transactions when `full` is set.
+    NewPendingTransactions { full: bool },
+    /// `eth_subscribe("syncing")`: the node starting or finishing a sync.
+    Syncing,
+}
+
+struct Subscriber {
+    kind: SubscriptionKind,
+    sender: Sender<String>,
 }
 
 /// Messages understood by the [`SubscriptionManager`].
@@ -62,12 +94,16 @@ pub trait SubscriptionManagerProtocol: Send + Sync {
     /// when their channel is closed.
     fn new_head(&self, header: BlockHeader) -> Result<(), ActorError>;
 
-    /// Register a new subscriber.
+    /// Notify the subscribers interested in `event`.
+    fn notify_chain_event(&self, event: ChainEvent) -> Result<(), ActorError>;
+
+    /// Register a new subscriber for events of the given kind.
     ///
     /// Returns `Some(id)` with the subscription ID that the client should use
     /// in subsequent `eth_unsubscribe` calls, or `None` if the global cap
     /// [`MAX_TOTAL_SUBSCRIPTIONS`] has been reached.
-    fn subscribe(&self, sender: Sender<String>) -> Response<Option<String>>;
+    fn subscribe(&self, sender: Sender<String>, kind: SubscriptionKind)
+    -> Response<Option<String>>;
 
     /// Remove a subscriber by ID.
     ///
@@ -83,6 +119,18 @@ impl SubscriptionManager {
         SubscriptionManager::default().start()
     }
 
+    /// Spawn the actor and forward it every [`ChainEvent`] received on `events` and
+    /// `pending_transactions`.
+    pub fn spawn_with_chain_events(
+        events: broadcast::Receiver<ChainEvent>,
+        pending_transactions: broadcast::Receiver<ChainEvent>,
+    ) -> ActorRef<SubscriptionManager> {
+        let manager = Self::spawn();
+        forward_chain_events(manager.clone(), events);
+        forward_chain_events(manager.clone(), pending_transactions);
+        manager
+    }
+
     #[send_handler]
     async fn handle_new_head(
         &mut self,
@@ -93,41 +141,98 @@ impl SubscriptionManager {
             return;
         }
 
-        // Serialize the header and inject the computed block hash.
-        let header = msg.header;
-        let block_hash = header.hash();
-        let mut header_value = match serde_json::to_value(&header) {
-            Ok(v) => v,
-            Err(e) => {
-                warn!("Failed to serialize block header for newHeads: {e}");
-                return;
-            }
+        let Some(header_value) = new_head_value(&msg.header) else {
+            return;
         };
-        if let Value::Object(ref mut map) = header_value {
-            map.insert(
-                "hash".to_string(),
-                Value::String(format!("{block_hash:#x}")),
-            );
-        }
 
-        let mut dead_ids: Vec<String> = Vec::new();
+        self.notify(|kind| match kind {
+            SubscriptionKind::NewHeads => vec![header_value.clone()],
+            _ => Vec::new(),
+        });
+    }
 
-        for (sub_id, sender) in &self.subscribers {
-            let notification = build_subscription_notification(sub_id, &header_value);
-            match sender.try_send(notification) {
-                Ok(()) => {}
-                Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
-                    dead_ids.push(sub_id.clone());
-                }
-                Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
-                    warn!(sub_id = %sub_id, "Subscriber channel full, dropping notification");
-                }
-            }
+    #[send_handler]
+    async fn handle_notify_chain_event(
+        &mut self,
+        msg: subscription_manager_protocol::NotifyChainEvent,
+        _ctx: &Context<Self>,
+    ) {
+        if self.subscribers.is_empty() {
+            return;
         }
 
-        for id in dead_ids {
-            debug!(sub_id = %id, "Removing closed newHeads subscriber");
-            self.subscribers.remove(&id);
+        match msg.event {
+            ChainEvent::HeadChanged { removed, added } => {
+                let heads: Vec<Value> = if self
+                    .subscribers
+                    .values()
+                    .any(|sub| matches!(sub.kind, SubscriptionKind::NewHeads))
+                {
+                    added
+                        .iter()
+                        .filter_map(|block| new_head_value(&block.block.header))
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                let mut logs = Vec::new();
+                if self
+                    .subscribers
+                    .values()
+                    .any(|sub| matches!(sub.kind, SubscriptionKind::Logs(_)))
+                {
+                    for block in removed.iter() {
+                        collect_block_logs(block, true, &mut logs);
+                    }
+                    for block in added.iter() {
+                        collect_block_logs(block, false, &mut logs);
+                    }
+                }
+                self.notify(|kind| match kind {
+                    SubscriptionKind::NewHeads => heads.clone(),
+                    SubscriptionKind::Logs(filter) => logs
+                        .iter()
+                        .filter(|log| filter.matches(&log.log.address, &log.log.topics))
+                        .filter_map(|log| serde_json::to_value(log).ok())
+                        .collect(),
+                    _ => Vec::new(),
+                });
+            }
+            ChainEvent::NewPendingTransaction { hash, transaction } => {
+                let hash_value = Value::String(format!("{hash:#x}"));
+                let mut full_value = None;
+                self.notify(|kind| match kind {
+                    SubscriptionKind::NewPendingTransactions { full: false } => {
+                        vec![hash_value.clone()]
+                    }
+                    SubscriptionKind::NewPendingTransactions { full: true } => full_value
+                        .get_or_insert_with(|| {
+                            let tx = RpcTransaction::build_with_sender(
+                                transaction.transaction().clone(),
+                                transaction.sender(),
+                                None,
+                                None,
+                                None,
+                            );
+                            serde_json::to_value(tx).ok()
+                        })
+                        .iter()
+                        .cloned()
+                        .collect(),
+                    _ => Vec::new(),
+                });
+            }
+            ChainEvent::SyncStatusChanged { syncing } => {
+                let status = if syncing {
+                    serde_json::json!({ "syncing": true })
+                } else {
+                    Value::Bool(false)
+                };
+                self.notify(|kind| match kind {
+                    SubscriptionKind::Syncing => vec![status.clone()],
+                    _ => Vec::new(),
+                });
+            }
         }
     }
 
@@ -145,7 +250,13 @@ impl SubscriptionManager {
             return None;
         }
         let id = generate_subscription_id();
-        self.subscribers.insert(id.clone(), msg.sender);
+        self.subscribers.insert(
+            id.clone(),
+            Subscriber {
+                kind: msg.kind,
+                sender: msg.sender,
+            },
+        );
         Some(id)
     }
 
@@ -159,6 +270,107 @@ impl SubscriptionManager {
     }
 }
 
+impl SubscriptionManager {
+    /// Sends each subscriber the results `results_for` returns for its kind, one
+    /// notification per result, and removes the subscribers whose channel is closed.
+    fn notify(&mut self, mut results_for: impl FnMut(&SubscriptionKind) -> Vec<Value>) {
+        let mut dead_ids: Vec<String> = Vec::new();
+
+        'subscribers: for (sub_id, subscriber) in &self.subscribers {
+            for result in results_for(&subscriber.kind) {
+                let notification = build_subscription_notification(sub_id, &result);
+                match subscriber.sender.try_send(notification) {
+                    Ok(()) => {}
+                    Err(tokio::sync::mpsc::error::TrySendError::Closed(_)) => {
+                        dead_ids.push(sub_id.clone());
+                        continue 'subscribers;
+                    }
+                    Err(tokio::sync::mpsc::error::TrySendError::Full(_)) => {
+                        warn!(sub_id = %sub_id, "Subscriber channel full, dropping notification");
+                    }
+                }
+            }
+        }
+
+        for id in dead_ids {
+            debug!(sub_id = %id, "Removing closed subscriber");
+            self.subscribers.remove(&id);
+        }
+    }
+}
+
+/// Forwards every event received on `events` to `manager` until either side closes.
+fn forward_chain_events(
+    manager: ActorRef<SubscriptionManager>,
+    mut events: broadcast::Receiver<ChainEvent>,
+) {
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if manager.notify_chain_event(event).is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(
+                        skipped,
+                        "Subscription manager fell behind, dropped chain events"
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Serializes `header` for a `newHeads` notification, injecting the computed block hash.
+fn new_head_value(header: &BlockHeader) -> Option<Value> {
+    let block_hash = header.hash();
+    let mut header_value = match serde_json::to_value(header) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Failed to serialize block header for newHeads: {e}");
+            return None;
+        }
+    };
+    if let Value::Object(ref mut map) = header_value {
+        map.insert(
+            "hash".to_string(),
+            Value::String(format!("{block_hash:#x}")),
+        );
+    }
+    Some(header_value)
+}
+
+/// Appends the logs of `block` to `logs`, flagged with `removed`.
+fn collect_block_logs(block: &BlockWithReceipts, removed: bool, logs: &mut Vec<RpcLog>) {
+    let block_hash = block.block.hash();
+    let mut log_index = 0_u64;
+    for (tx_index, (tx, receipt)) in block
+        .block
+        .body
+        .transactions
+        .iter()
+        .zip(&block.receipts)
+        .enumerate()
+    {
+        let transaction_hash = tx.hash();
+        for log in &receipt.logs {
+            logs.push(RpcLog {
+                log: log.clone().into(),
+                log_index,
+                removed,
+                transaction_hash,
+                transaction_index: tx_index as u64,
+                block_hash,
+                block_number: block.block.header.number,
+            });
+            log_index += 1;
+        }
+    }
+}
+
 /// Build the standard Ethereum subscription notification envelope.
 ///
 /// `result` is cloned per subscriber — cheap relative to re-serializing the
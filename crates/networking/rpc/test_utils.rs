@@ -6,7 +6,7 @@
 #![allow(clippy::unwrap_used)]
 
 use crate::{
-    eth::gas_tip_estimator::GasTipEstimator,
+    eth::{filter::DEFAULT_MAX_FILTERS, gas_tip_estimator::GasTipEstimator},
     rpc::{
         ClientVersion, NodeData, RpcApiContext, handle_authrpc_request, handle_http_request,
         start_api, start_block_executor,
@@ -296,6 +296,8 @@ pub async fn start_test_api() -> tokio::task::JoinHandle<()> {
             DEFAULT_BUILDER_GAS_CEIL,
             String::new(),
             all_namespaces_for_tests(),
+            DEFAULT_MAX_FILTERS,
+            None,
         )
         .await
         .unwrap()
@@ -311,6 +313,7 @@ pub fn all_namespaces_for_tests() -> HashSet<RpcNamespace> {
         RpcNamespace::Debug,
         RpcNamespace::Admin,
         RpcNamespace::Mempool,
+        RpcNamespace::Trace,
     ])
 }
 
@@ -325,6 +328,7 @@ pub async fn default_context_with_storage(storage: Store) -> RpcApiContext {
         storage: storage.clone(),
         blockchain: blockchain.clone(),
         active_filters: Default::default(),
+        max_filters: DEFAULT_MAX_FILTERS,
         syncer: Some(Arc::new(dummy_sync_manager().await)),
         peer_handler: Some(dummy_peer_handler(storage).await),
         node_data: NodeData {
@@ -346,6 +350,7 @@ pub async fn default_context_with_storage(storage: Store) -> RpcApiContext {
         gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
         block_worker_channel,
         ws: None,
+        ipc: None,
         allowed_namespaces: Arc::new(all_namespaces_for_tests()),
     }
 }
@@ -366,6 +371,7 @@ pub async fn dummy_sync_manager() -> SyncManager {
         Store::new("temp.db", ethrex_storage::EngineType::InMemory)
             .expect("Failed to start Storage Engine"),
         ".".into(),
+        None,
     )
     .await
 }
@@ -0,0 +1,378 @@
+//! OpenEthereum-style `trace_*` namespace.
+//!
+//! Transactions are re-executed with the same tracers used by `debug_trace*` and their output
+//! is converted into the flat trace format expected by OpenEthereum/Erigon consumers
+//! (see [`parity`]).
+
+pub mod parity;
+
+use std::collections::HashSet;
+
+use ethrex_common::{
+    Address, H256,
+    tracing::{CallTrace, CallTraceFrame, PrestateResult},
+    types::Block,
+};
+use ethrex_storage::Store;
+use ethrex_vm::tracing::OpcodeTracerConfig;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    eth::block::check_history_available,
+    rpc::{RpcApiContext, RpcHandler},
+    tracing::{DEFAULT_REEXEC, DEFAULT_TIMEOUT},
+    types::block_identifier::{BlockIdentifier, BlockTag},
+    utils::RpcErr,
+};
+use parity::{LocalizedTrace, TraceResults, TransactionTrace};
+
+/// Max amount of blocks `trace_filter` will re-execute in a single request
+const MAX_FILTER_BLOCK_RANGE: u64 = 1000;
+
+/// `trace_block`: all traces produced by the transactions of a block.
+pub struct TraceBlockRequest {
+    block: BlockIdentifier,
+}
+
+/// `trace_transaction`: all traces produced by a transaction.
+pub struct TraceTransactionRequest {
+    tx_hash: H256,
+}
+
+/// `trace_filter`: traces matching the given addresses within a block range.
+pub struct TraceFilterRequest {
+    from_block: BlockIdentifier,
+    to_block: BlockIdentifier,
+    from_address: HashSet<Address>,
+    to_address: HashSet<Address>,
+    /// Amount of matching traces to skip
+    after: usize,
+    /// Max amount of traces to return
+    count: Option<usize>,
+}
+
+/// `trace_replayBlockTransactions`: replays every transaction of a block with the requested trace modes.
+pub struct ReplayBlockTransactionsRequest {
+    block: BlockIdentifier,
+    trace_types: HashSet<TraceMode>,
+}
+
+#[derive(Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+enum TraceMode {
+    Trace,
+    StateDiff,
+    VmTrace,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFilterParams {
+    #[serde(default)]
+    from_block: Option<Value>,
+    #[serde(default)]
+    to_block: Option<Value>,
+    #[serde(default)]
+    from_address: Option<Vec<Address>>,
+    #[serde(default)]
+    to_address: Option<Vec<Address>>,
+    #[serde(default)]
+    after: Option<usize>,
+    #[serde(default)]
+    count: Option<usize>,
+}
+
+impl RpcHandler for TraceBlockRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        Ok(TraceBlockRequest {
+            block: BlockIdentifier::parse(params[0].clone(), 0)?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let Some(block) = get_block(&self.block, &context.storage).await? else {
+            return Ok(Value::Null);
+        };
+        let traces = localized_block_traces(block, &context).await?;
+        Ok(serde_json::to_value(traces)?)
+    }
+}
+
+impl RpcHandler for TraceTransactionRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        Ok(TraceTransactionRequest {
+            tx_hash: serde_json::from_value(params[0].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let Some((block_number, block_hash, tx_index)) = context
+            .storage
+            .get_transaction_location(self.tx_hash)
+            .await?
+        else {
+            return Ok(Value::Null);
+        };
+        let call_trace = context
+            .blockchain
+            .trace_transaction_calls(self.tx_hash, DEFAULT_REEXEC, DEFAULT_TIMEOUT, false, false)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        let traces: Vec<LocalizedTrace> = parity::flatten_call_trace(top_frame(call_trace)?)
+            .into_iter()
+            .map(|trace| LocalizedTrace {
+                trace,
+                block_hash,
+                block_number,
+                transaction_hash: self.tx_hash,
+                transaction_position: tx_index,
+            })
+            .collect();
+        Ok(serde_json::to_value(traces)?)
+    }
+}
+
+impl RpcHandler for TraceFilterRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 {
+            return Err(RpcErr::BadParams("Expected 1 param".to_owned()));
+        };
+        let filter: TraceFilterParams = serde_json::from_value(params[0].clone())?;
+        let parse_block = |block: Option<Value>| {
+            block
+                .map(|block| BlockIdentifier::parse(block, 0))
+                .transpose()
+                .map(|block| block.unwrap_or(BlockIdentifier::Tag(BlockTag::Latest)))
+        };
+        Ok(TraceFilterRequest {
+            from_block: parse_block(filter.from_block)?,
+            to_block: parse_block(filter.to_block)?,
+            from_address: filter
+                .from_address
+                .unwrap_or_default()
+                .into_iter()
+                .collect(),
+            to_address: filter.to_address.unwrap_or_default().into_iter().collect(),
+            after: filter.after.unwrap_or_default(),
+            count: filter.count,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let from = self
+            .from_block
+            .resolve_block_number(&context.storage)
+            .await?
+            .ok_or(RpcErr::WrongParam("fromBlock".to_string()))?;
+        let to = self
+            .to_block
+            .resolve_block_number(&context.storage)
+            .await?
+            .ok_or(RpcErr::WrongParam("toBlock".to_string()))?;
+        if (from..=to).is_empty() {
+            return Err(RpcErr::BadParams("Empty range".to_string()));
+        }
+        if to - from >= MAX_FILTER_BLOCK_RANGE {
+            return Err(RpcErr::BadParams(format!(
+                "Block range too large, max is {MAX_FILTER_BLOCK_RANGE} blocks"
+            )));
+        }
+
+        let mut skipped = 0;
+        let mut traces = Vec::new();
+        let is_full =
+            |traces: &[LocalizedTrace]| self.count.is_some_and(|count| traces.len() >= count);
+        for block_number in from..=to {
+            if is_full(&traces) {
+                break;
+            }
+            let Some(block) =
+                get_block(&BlockIdentifier::Number(block_number), &context.storage).await?
+            else {
+                return Err(RpcErr::BadParams(format!("Block {block_number} not found")));
+            };
+            for trace in localized_block_traces(block, &context).await? {
+                if !self.matches(&trace.trace) {
+                    continue;
+                }
+                if skipped < self.after {
+                    skipped += 1;
+                    continue;
+                }
+                if is_full(&traces) {
+                    break;
+                }
+                traces.push(trace);
+            }
+        }
+        Ok(serde_json::to_value(traces)?)
+    }
+}
+
+impl TraceFilterRequest {
+    /// A trace matches if its sender and receiver are in their respective address sets,
+    /// an empty set matches any address.
+    fn matches(&self, trace: &TransactionTrace) -> bool {
+        let from_matches =
+            self.from_address.is_empty() || self.from_address.contains(&trace.action.from());
+        let to_matches = self.to_address.is_empty()
+            || trace.to().is_some_and(|to| self.to_address.contains(&to));
+        from_matches && to_matches
+    }
+}
+
+impl RpcHandler for ReplayBlockTransactionsRequest {
+    fn parse(params: &Option<Vec<Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 2 params".to_owned()));
+        };
+        let trace_types: HashSet<TraceMode> =
+            serde_json::from_value(params[1].clone()).map_err(|_| {
+                RpcErr::BadParams(
+                    "Trace types must be a list of 'trace', 'stateDiff' or 'vmTrace'".to_owned(),
+                )
+            })?;
+        Ok(ReplayBlockTransactionsRequest {
+            block: BlockIdentifier::parse(params[0].clone(), 0)?,
+            trace_types,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let Some(block) = get_block(&self.block, &context.storage).await? else {
+            return Ok(Value::Null);
+        };
+
+        // Each mode needs its own tracer, so the block is re-executed once per requested mode.
+        // The call trace is always needed as it holds each transaction's output.
+        let call_traces = context
+            .blockchain
+            .trace_block_calls(block.clone(), DEFAULT_REEXEC, DEFAULT_TIMEOUT, false, false)
+            .await
+            .map_err(|err| RpcErr::Internal(err.to_string()))?;
+        let mut state_diffs = if self.trace_types.contains(&TraceMode::StateDiff) {
+            let prestate_traces = context
+                .blockchain
+                .trace_block_prestate(block.clone(), DEFAULT_REEXEC, DEFAULT_TIMEOUT, true, false)
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string()))?;
+            prestate_traces
+                .into_iter()
+                .map(|(_, result)| match result {
+                    PrestateResult::Diff(diff) => Ok(Some(parity::state_diff(diff))),
+                    PrestateResult::Prestate(_) => Err(RpcErr::Internal(
+                        "Expected a prestate diff trace".to_string(),
+                    )),
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else {
+            Vec::new()
+        };
+        let mut vm_traces = if self.trace_types.contains(&TraceMode::VmTrace) {
+            // Stack is needed to rebuild pushed values and storage/memory writes
+            let cfg = OpcodeTracerConfig {
+                disable_storage: true,
+                ..Default::default()
+            };
+            let opcode_traces = context
+                .blockchain
+                .trace_block_opcodes(block.clone(), DEFAULT_REEXEC, DEFAULT_TIMEOUT, cfg)
+                .await
+                .map_err(|err| RpcErr::Internal(err.to_string()))?;
+            // The tracer records the code each frame ran, which can differ from the code
+            // found in any state if it changed within the block
+            opcode_traces
+                .into_iter()
+                .map(|(_, result)| Some(parity::build_vm_trace(&result.steps, &result.frame_codes)))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut results = Vec::with_capacity(call_traces.len());
+        for (index, (tx_hash, call_trace)) in call_traces.into_iter().enumerate() {
+            let frame = top_frame(call_trace)?;
+            let output = frame.output.clone();
+            let trace = if self.trace_types.contains(&TraceMode::Trace) {
+                parity::flatten_call_trace(frame)
+            } else {
+                Vec::new()
+            };
+            results.push(TraceResults {
+                output,
+                state_diff: state_diffs.get_mut(index).and_then(Option::take),
+                trace,
+                vm_trace: vm_traces.get_mut(index).and_then(Option::take),
+                transaction_hash: tx_hash,
+            });
+        }
+        Ok(serde_json::to_value(results)?)
+    }
+}
+
+/// Fails with [`RpcErr::PrunedHistory`] if the block's body was pruned.
+async fn get_block(block: &BlockIdentifier, storage: &Store) -> Result<Option<Block>, RpcErr> {
+    let Some(block_number) = block.resolve_block_number(storage).await? else {
+        return Ok(None);
+    };
+    let block = storage.get_block_by_number(block_number).await?;
+    if block.is_none() {
+        check_history_available(storage, block_number).await?;
+    }
+    Ok(block)
+}
+
+/// Traces every transaction of `block` and flattens the results into localized traces.
+async fn localized_block_traces(
+    block: Block,
+    context: &RpcApiContext,
+) -> Result<Vec<LocalizedTrace>, RpcErr> {
+    let block_hash = block.hash();
+    let block_number = block.header.number;
+    let call_traces = context
+        .blockchain
+        .trace_block_calls(block, DEFAULT_REEXEC, DEFAULT_TIMEOUT, false, false)
+        .await
+        .map_err(|err| RpcErr::Internal(err.to_string()))?;
+    let mut traces = Vec::new();
+    for (position, (tx_hash, call_trace)) in call_traces.into_iter().enumerate() {
+        traces.extend(
+            parity::flatten_call_trace(top_frame(call_trace)?)
+                .into_iter()
+                .map(|trace| LocalizedTrace {
+                    trace,
+                    block_hash,
+                    block_number,
+                    transaction_hash: tx_hash,
+                    transaction_position: position as u64,
+                }),
+        );
+    }
+    Ok(traces)
+}
+
+fn top_frame(call_trace: CallTrace) -> Result<CallTraceFrame, RpcErr> {
+    call_trace
+        .into_iter()
+        .next()
+        .ok_or(RpcErr::Internal("Empty call trace".to_string()))
+}
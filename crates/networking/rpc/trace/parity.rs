@@ -0,0 +1,725 @@
+//! OpenEthereum (Parity) trace wire format used by the `trace_*` namespace.
+//!
+//! The data comes from the same tracers backing `debug_trace*`:
+//! - call traces are the `LevmCallTracer` frame tree flattened in depth-first order, each
+//!   frame tagged with its `traceAddress` (the path of sub-call indices from the top call),
+//! - state diffs are built from the prestate tracer's diff mode,
+//! - vm traces are rebuilt from the opcode tracer steps.
+//!
+//! Reference: https://openethereum.github.io/JSONRPC-trace-module
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256, serde_utils,
+    tracing::{CallTraceFrame, CallType, OpcodeStep, PrePostState, PrestateAccountState},
+};
+use serde::Serialize;
+
+/// Error reported by OpenEthereum for frames ended by the REVERT opcode.
+const REVERTED_ERROR: &str = "Reverted";
+
+/// A single flattened call frame of a transaction.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+    pub action: Action,
+    #[serde(flatten)]
+    pub result: TraceResult,
+    pub subtraces: usize,
+    pub trace_address: Vec<usize>,
+    #[serde(rename = "type")]
+    pub trace_type: TraceType,
+}
+
+/// A [`TransactionTrace`] along with the location of its transaction in the chain,
+/// as returned by `trace_block`, `trace_transaction` and `trace_filter`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedTrace {
+    #[serde(flatten)]
+    pub trace: TransactionTrace,
+    pub block_hash: H256,
+    pub block_number: u64,
+    pub transaction_hash: H256,
+    pub transaction_position: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceType {
+    Call,
+    Create,
+    Suicide,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Action {
+    Call(CallAction),
+    Create(CreateAction),
+    Suicide(SuicideAction),
+}
+
+impl Action {
+    /// Address that initiated the action.
+    pub fn from(&self) -> Address {
+        match self {
+            Action::Call(action) => action.from,
+            Action::Create(action) => action.from,
+            Action::Suicide(action) => action.address,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallAction {
+    pub call_type: CallKind,
+    pub from: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas: u64,
+    #[serde(with = "serde_utils::bytes")]
+    pub input: Bytes,
+    pub to: Address,
+    pub value: U256,
+}
+
+#[derive(Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum CallKind {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAction {
+    pub from: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas: u64,
+    #[serde(with = "serde_utils::bytes")]
+    pub init: Bytes,
+    pub value: U256,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuicideAction {
+    pub address: Address,
+    pub balance: U256,
+    pub refund_address: Address,
+}
+
+/// Outcome of a frame, serialized either as `"result": {...}` or `"error": "..."`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TraceResult {
+    /// `None` for frames without output, such as self-destructs.
+    Result(Option<TraceOutput>),
+    Error(String),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum TraceOutput {
+    Call(CallOutput),
+    Create(CreateOutput),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallOutput {
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas_used: u64,
+    #[serde(with = "serde_utils::bytes")]
+    pub output: Bytes,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOutput {
+    pub address: Address,
+    #[serde(with = "serde_utils::bytes")]
+    pub code: Bytes,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas_used: u64,
+}
+
+impl TransactionTrace {
+    /// Address that received the action: the callee, the created contract or the
+    /// self-destruct beneficiary.
+    pub fn to(&self) -> Option<Address> {
+        match (&self.action, &self.result) {
+            (Action::Call(action), _) => Some(action.to),
+            (Action::Create(_), TraceResult::Result(Some(TraceOutput::Create(output)))) => {
+                Some(output.address)
+            }
+            (Action::Create(_), _) => None,
+            (Action::Suicide(action), _) => Some(action.refund_address),
+        }
+    }
+}
+
+/// Flattens a call frame tree into OpenEthereum traces, parents before their sub-calls.
+pub fn flatten_call_trace(frame: CallTraceFrame) -> Vec<TransactionTrace> {
+    let mut traces = Vec::new();
+    flatten_frame(frame, Vec::new(), &mut traces);
+    traces
+}
+
+fn flatten_frame(
+    frame: CallTraceFrame,
+    trace_address: Vec<usize>,
+    traces: &mut Vec<TransactionTrace>,
+) {
+    let CallTraceFrame {
+        call_type,
+        from,
+        to,
+        value,
+        gas,
+        gas_used,
+        input,
+        output,
+        error,
+        calls,
+        ..
+    } = frame;
+
+    let (trace_type, action, output) = match call_type {
+        CallType::CREATE | CallType::CREATE2 => (
+            TraceType::Create,
+            Action::Create(CreateAction {
+                from,
+                gas,
+                init: input,
+                value,
+            }),
+            Some(TraceOutput::Create(CreateOutput {
+                address: to,
+                code: output,
+                gas_used,
+            })),
+        ),
+        CallType::SELFDESTRUCT => (
+            TraceType::Suicide,
+            Action::Suicide(SuicideAction {
+                address: from,
+                balance: value,
+                refund_address: to,
+            }),
+            None,
+        ),
+        call_type => {
+            let call_type = match call_type {
+                CallType::CALLCODE => CallKind::CallCode,
+                CallType::DELEGATECALL => CallKind::DelegateCall,
+                CallType::STATICCALL => CallKind::StaticCall,
+                _ => CallKind::Call,
+            };
+            (
+                TraceType::Call,
+                Action::Call(CallAction {
+                    call_type,
+                    from,
+                    gas,
+                    input,
+                    to,
+                    value,
+                }),
+                Some(TraceOutput::Call(CallOutput { gas_used, output })),
+            )
+        }
+    };
+    let result = match error {
+        // `VMError::RevertOpcode` is the only error caused by the REVERT opcode
+        Some(error) if error == "RevertOpcode" => TraceResult::Error(REVERTED_ERROR.to_string()),
+        Some(error) => TraceResult::Error(error),
+        None => TraceResult::Result(output),
+    };
+
+    traces.push(TransactionTrace {
+        action,
+        result,
+        subtraces: calls.len(),
+        trace_address: trace_address.clone(),
+        trace_type,
+    });
+    for (index, call) in calls.into_iter().enumerate() {
+        let mut sub_address = trace_address.clone();
+        sub_address.push(index);
+        flatten_frame(call, sub_address, traces);
+    }
+}
+
+/// Replay result of a single transaction, as returned by `trace_replayBlockTransactions`.
+/// Modes that weren't requested are left empty.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResults {
+    #[serde(with = "serde_utils::bytes")]
+    pub output: Bytes,
+    pub state_diff: Option<StateDiff>,
+    pub trace: Vec<TransactionTrace>,
+    pub vm_trace: Option<VmTrace>,
+    pub transaction_hash: H256,
+}
+
+// ─── State diff ───────────────────────────────────────────────────────────
+
+/// Per-account changes made by a transaction.
+pub type StateDiff = BTreeMap<Address, AccountDiff>;
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct AccountDiff {
+    pub balance: Diff<U256>,
+    pub nonce: Diff<U256>,
+    /// `0x`-prefixed hex bytecode.
+    pub code: Diff<String>,
+    pub storage: BTreeMap<H256, Diff<H256>>,
+}
+
+/// Change of a single value: unchanged (`=`), created (`+`), removed (`-`) or modified (`*`).
+#[derive(Debug, Serialize, PartialEq)]
+pub enum Diff<T> {
+    #[serde(rename = "=")]
+    Same,
+    #[serde(rename = "+")]
+    Born(T),
+    #[serde(rename = "-")]
+    Died(T),
+    #[serde(rename = "*")]
+    Changed(ChangedValue<T>),
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ChangedValue<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: PartialEq> Diff<T> {
+    fn changed(from: T, to: T) -> Self {
+        if from == to {
+            Diff::Same
+        } else {
+            Diff::Changed(ChangedValue { from, to })
+        }
+    }
+}
+
+/// Converts a diff-mode prestate trace into an OpenEthereum state diff.
+///
+/// Accounts only present in `post` were created by the transaction, accounts only present
+/// in `pre` were destroyed, and the rest only list the fields that actually changed.
+pub fn state_diff(diff: PrePostState) -> StateDiff {
+    let PrePostState { pre, mut post } = diff;
+    let mut state_diff: StateDiff = pre
+        .into_iter()
+        .map(|(address, pre)| {
+            let account_diff = match post.remove(&address) {
+                Some(post) => changed_account(pre, post),
+                None => died_account(pre),
+            };
+            (address, account_diff)
+        })
+        .collect();
+    state_diff.extend(
+        post.into_iter()
+            .map(|(address, post)| (address, born_account(post))),
+    );
+    state_diff
+}
+
+fn born_account(post: PrestateAccountState) -> AccountDiff {
+    AccountDiff {
+        balance: Diff::Born(post.balance.unwrap_or_default()),
+        nonce: Diff::Born(post.nonce.into()),
+        code: Diff::Born(hex_code(&post.code)),
+        storage: post
+            .storage
+            .into_iter()
+            .map(|(key, value)| (key, Diff::Born(value)))
+            .collect(),
+    }
+}
+
+fn died_account(pre: PrestateAccountState) -> AccountDiff {
+    AccountDiff {
+        balance: Diff::Died(pre.balance.unwrap_or_default()),
+        nonce: Diff::Died(pre.nonce.into()),
+        code: Diff::Died(hex_code(&pre.code)),
+        storage: pre
+            .storage
+            .into_iter()
+            .map(|(key, value)| (key, Diff::Died(value)))
+            .collect(),
+    }
+}
+
+fn changed_account(pre: PrestateAccountState, post: PrestateAccountState) -> AccountDiff {
+    let balance = match post.balance {
+        Some(balance) => Diff::changed(pre.balance.unwrap_or_default(), balance),
+        None => Diff::Same,
+    };
+    // The post state omits fields that didn't change, a zero nonce is never a change
+    let nonce = if post.nonce != 0 {
+        Diff::changed(pre.nonce.into(), post.nonce.into())
+    } else {
+        Diff::Same
+    };
+    let code = if !post.code.is_empty() {
+        Diff::changed(hex_code(&pre.code), hex_code(&post.code))
+    } else {
+        Diff::Same
+    };
+    // Both sides only hold changed slots, cleared slots are absent from post and
+    // slots that were zero before are absent from pre
+    let slots: BTreeSet<H256> = pre
+        .storage
+        .keys()
+        .chain(post.storage.keys())
+        .copied()
+        .collect();
+    let storage = slots
+        .into_iter()
+        .map(|slot| {
+            let from = pre.storage.get(&slot).copied().unwrap_or_default();
+            let to = post.storage.get(&slot).copied().unwrap_or_default();
+            (slot, Diff::changed(from, to))
+        })
+        .collect();
+    AccountDiff {
+        balance,
+        nonce,
+        code,
+        storage,
+    }
+}
+
+fn hex_code(code: &Bytes) -> String {
+    format!("0x{}", hex::encode(code))
+}
+
+// ─── VM trace ─────────────────────────────────────────────────────────────
+
+/// Opcode-level execution trace of a call frame.
+#[derive(Debug, Serialize)]
+pub struct VmTrace {
+    #[serde(with = "serde_utils::bytes")]
+    pub code: Bytes,
+    pub ops: Vec<VmOperation>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VmOperation {
+    pub cost: u64,
+    /// `None` if the operation failed.
+    pub ex: Option<VmExecutedOperation>,
+    pub pc: u64,
+    /// Trace of the frame entered by this operation, if any.
+    pub sub: Option<VmTrace>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VmExecutedOperation {
+    pub mem: Option<MemoryDiff>,
+    pub push: Vec<U256>,
+    pub store: Option<StorageDiff>,
+    /// Gas left after the operation.
+    pub used: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemoryDiff {
+    pub off: u64,
+    #[serde(with = "serde_utils::bytes")]
+    pub data: Bytes,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageDiff {
+    pub key: U256,
+    pub val: U256,
+}
+
+/// Rebuilds the vm trace of a transaction from its opcode tracer steps.
+///
+/// Steps must have been captured with the stack enabled. `frame_codes` is the code run by
+/// each frame with steps, in the order the frames were entered, as recorded by the tracer.
+pub fn build_vm_trace(steps: &[OpcodeStep], frame_codes: &[Bytes]) -> VmTrace {
+    let mut frame_codes = frame_codes.iter().cloned();
+    let code = frame_codes.next().unwrap_or_default();
+    build_frame_vm_trace(code, steps, &mut frame_codes)
+}
+
+/// Vm trace of a frame running `code`, taking the code of the frames it enters from
+/// `frame_codes`.
+fn build_frame_vm_trace(
+    code: Bytes,
+    steps: &[OpcodeStep],
+    frame_codes: &mut impl Iterator<Item = Bytes>,
+) -> VmTrace {
+    let mut ops = Vec::new();
+    let mut index = 0;
+    while let Some(step) = steps.get(index) {
+        // Steps deeper than the current one right after it belong to the frame it entered
+        let sub_steps_len = steps[index + 1..]
+            .iter()
+            .take_while(|sub_step| sub_step.depth > step.depth)
+            .count();
+        let sub_steps = &steps[index + 1..index + 1 + sub_steps_len];
+        index += 1 + sub_steps_len;
+
+        let sub = (!sub_steps.is_empty()).then(|| {
+            let code = frame_codes.next().unwrap_or_default();
+            build_frame_vm_trace(code, sub_steps, frame_codes)
+        });
+        let ex = step
+            .error
+            .is_none()
+            .then(|| executed_operation(step, steps.get(index)));
+        ops.push(VmOperation {
+            cost: step.gas_cost,
+            ex,
+            pc: step.pc,
+            sub,
+        });
+    }
+    VmTrace { code, ops }
+}
+
+/// Returns the `n`-th item from the top of the step's stack.
+fn stack_item(step: &OpcodeStep, n: usize) -> Option<U256> {
+    let stack = step.stack.as_ref()?;
+    stack.len().checked_sub(n + 1).map(|index| stack[index])
+}
+
+fn executed_operation(step: &OpcodeStep, next: Option<&OpcodeStep>) -> VmExecutedOperation {
+    let push = next
+        .and_then(|next| next.stack.as_ref())
+        .map(|stack| {
+            let pushed = stack_outputs(step.op).min(stack.len());
+            stack[stack.len() - pushed..].to_vec()
+        })
+        .unwrap_or_default();
+    let mem = match step.op {
+        // MSTORE
+        0x52 => stack_item(step, 0)
+            .zip(stack_item(step, 1))
+            .map(|(offset, value)| MemoryDiff {
+                off: offset.low_u64(),
+                data: Bytes::copy_from_slice(&value.to_big_endian()),
+            }),
+        // MSTORE8
+        0x53 => stack_item(step, 0)
+            .zip(stack_item(step, 1))
+            .map(|(offset, value)| MemoryDiff {
+                off: offset.low_u64(),
+                data: Bytes::copy_from_slice(&[value.byte(0)]),
+            }),
+        _ => None,
+    };
+    let store = match step.op {
+        // SSTORE
+        0x55 => stack_item(step, 0)
+            .zip(stack_item(step, 1))
+            .map(|(key, val)| StorageDiff { key, val }),
+        _ => None,
+    };
+    let used = next
+        .map(|next| next.gas)
+        .unwrap_or(step.gas.saturating_sub(step.gas_cost));
+    VmExecutedOperation {
+        mem,
+        push,
+        store,
+        used,
+    }
+}
+
+/// Amount of stack items reported as pushed by an opcode. Following OpenEthereum,
+/// DUPn and SWAPn report every stack item they touched.
+fn stack_outputs(op: u8) -> usize {
+    match op {
+        // DUP1..DUP16
+        0x80..=0x8F => usize::from(op - 0x80) + 2,
+        // SWAP1..SWAP16
+        0x90..=0x9F => usize::from(op - 0x90) + 2,
+        // STOP, POP, MSTORE, MSTORE8, SSTORE, JUMP, JUMPI, JUMPDEST, TSTORE, MCOPY
+        0x00 | 0x50 | 0x52 | 0x53 | 0x55 | 0x56 | 0x57 | 0x5B | 0x5D | 0x5E => 0,
+        // CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY
+        0x37 | 0x39 | 0x3C | 0x3E => 0,
+        // LOG0..LOG4
+        0xA0..=0xA4 => 0,
+        // RETURN, REVERT, INVALID, SELFDESTRUCT
+        0xF3 | 0xFD | 0xFE | 0xFF => 0,
+        _ => 1,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(call_type: CallType, calls: Vec<CallTraceFrame>) -> CallTraceFrame {
+        CallTraceFrame {
+            call_type,
+            calls,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flatten_assigns_trace_addresses_depth_first() {
+        let tree = frame(
+            CallType::CALL,
+            vec![
+                frame(
+                    CallType::DELEGATECALL,
+                    vec![frame(CallType::CREATE, vec![])],
+                ),
+                frame(CallType::SELFDESTRUCT, vec![]),
+            ],
+        );
+        let traces = flatten_call_trace(tree);
+        let addresses: Vec<_> = traces.iter().map(|t| t.trace_address.clone()).collect();
+        assert_eq!(addresses, vec![vec![], vec![0], vec![0, 0], vec![1]]);
+        let subtraces: Vec<_> = traces.iter().map(|t| t.subtraces).collect();
+        assert_eq!(subtraces, vec![2, 1, 0, 0]);
+        let types: Vec<_> = traces.iter().map(|t| t.trace_type).collect();
+        assert_eq!(
+            types,
+            vec![
+                TraceType::Call,
+                TraceType::Call,
+                TraceType::Create,
+                TraceType::Suicide
+            ]
+        );
+    }
+
+    #[test]
+    fn reverted_frame_serializes_error_instead_of_result() {
+        let mut top = frame(CallType::CALL, vec![]);
+        top.error = Some("RevertOpcode".to_string());
+        let traces = flatten_call_trace(top);
+        let value = serde_json::to_value(&traces[0]).unwrap();
+        assert_eq!(value["error"], "Reverted");
+        assert!(value.get("result").is_none());
+        assert_eq!(value["action"]["callType"], "call");
+    }
+
+    #[test]
+    fn state_diff_marks_born_died_and_changed_accounts() {
+        let born = Address::repeat_byte(1);
+        let died = Address::repeat_byte(2);
+        let changed = Address::repeat_byte(3);
+        let slot = H256::repeat_byte(9);
+        let mut diff = PrePostState::default();
+        diff.post.insert(
+            born,
+            PrestateAccountState {
+                balance: Some(U256::from(5)),
+                ..Default::default()
+            },
+        );
+        diff.pre.insert(
+            died,
+            PrestateAccountState {
+                balance: Some(U256::from(7)),
+                nonce: 1,
+                ..Default::default()
+            },
+        );
+        diff.pre.insert(
+            changed,
+            PrestateAccountState {
+                balance: Some(U256::from(10)),
+                nonce: 1,
+                storage: [(slot, H256::from_low_u64_be(1))].into(),
+                ..Default::default()
+            },
+        );
+        diff.post.insert(
+            changed,
+            PrestateAccountState {
+                balance: Some(U256::from(8)),
+                nonce: 2,
+                ..Default::default()
+            },
+        );
+
+        let state_diff = state_diff(diff);
+        assert_eq!(state_diff[&born].balance, Diff::Born(U256::from(5)));
+        assert_eq!(state_diff[&died].nonce, Diff::Died(U256::one()));
+        let changed = &state_diff[&changed];
+        assert_eq!(
+            changed.balance,
+            Diff::changed(U256::from(10), U256::from(8))
+        );
+        assert_eq!(changed.nonce, Diff::changed(U256::one(), U256::from(2)));
+        assert_eq!(changed.code, Diff::Same);
+        assert_eq!(
+            changed.storage[&slot],
+            Diff::changed(H256::from_low_u64_be(1), H256::zero())
+        );
+    }
+
+    #[test]
+    fn vm_trace_nests_sub_call_steps() {
+        let step = |pc, op, depth, gas, stack: Vec<u64>| OpcodeStep {
+            pc,
+            op,
+            gas,
+            gas_cost: 3,
+            mem_size: 0,
+            depth,
+            return_data: Bytes::new(),
+            refund: 0,
+            stack: Some(stack.into_iter().map(U256::from).collect()),
+            memory: None,
+            storage: None,
+            error: None,
+        };
+        let steps = vec![
+            step(0, 0xFA, 1, 1000, vec![]),
+            step(0, 0x60, 2, 400, vec![]),
+            step(2, 0x00, 2, 397, vec![42]),
+            step(1, 0xFA, 1, 900, vec![1]),
+            step(0, 0x00, 2, 400, vec![]),
+            step(2, 0x00, 1, 800, vec![1, 1]),
+        ];
+        let top_code = Bytes::from_static(&[0xFA, 0xFA, 0x00]);
+        let first_callee = Bytes::from_static(&[0x60, 0x2A, 0x00]);
+        // The callee's code changed between both calls, e.g. it was selfdestructed and
+        // redeployed, so each frame must keep the code it actually ran
+        let second_callee = Bytes::from_static(&[0x00]);
+        let trace = build_vm_trace(
+            &steps,
+            &[
+                top_code.clone(),
+                first_callee.clone(),
+                second_callee.clone(),
+            ],
+        );
+        assert_eq!(trace.code, top_code);
+        assert_eq!(trace.ops.len(), 3);
+        let call = &trace.ops[0];
+        let ex = call.ex.as_ref().unwrap();
+        assert_eq!(ex.used, 900);
+        assert_eq!(ex.push, vec![U256::one()]);
+        let sub = call.sub.as_ref().unwrap();
+        assert_eq!(sub.code, first_callee);
+        assert_eq!(sub.ops.len(), 2);
+        assert_eq!(sub.ops[0].ex.as_ref().unwrap().push, vec![U256::from(42)]);
+        let second_sub = trace.ops[1].sub.as_ref().unwrap();
+        assert_eq!(second_sub.code, second_callee);
+        assert_eq!(second_sub.ops.len(), 1);
+        assert!(trace.ops[2].sub.is_none());
+    }
+}
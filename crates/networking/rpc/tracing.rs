@@ -1,20 +1,33 @@
 use std::time::Duration;
 
-use ethrex_common::H256;
+use ethrex_common::{
+    H256,
+    types::{Block, GenericTransaction},
+};
 use ethrex_common::{
     serde_utils,
-    tracing::{CallTraceFrame, PrestateResult, StructLoggerEmit, StructLoggerResult},
+    tracing::{
+        CallTraceFrame, NativeTraceResult, PrestateResult, StructLoggerEmit, StructLoggerResult,
+    },
 };
-use ethrex_vm::tracing::OpcodeTracerConfig;
+use ethrex_rlp::decode::RLPDecode;
+use ethrex_vm::overrides::{BlockOverrides, CallOverrides, StateOverride};
+use ethrex_vm::tracing::{NativeTracerConfig, OpcodeTracerConfig};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-use crate::{rpc::RpcHandler, types::block_identifier::BlockIdentifier, utils::RpcErr};
+use crate::{
+    eth::transaction::reject_moved_precompiles,
+    rpc::RpcHandler,
+    trace::parity::{LocalizedTrace, flatten_call_trace},
+    types::block_identifier::{BlockIdentifier, BlockIdentifierOrHash},
+    utils::RpcErr,
+};
 
 /// Default max amount of blocks to re-excute if it is not given
-const DEFAULT_REEXEC: u32 = 128;
+pub(crate) const DEFAULT_REEXEC: u32 = 128;
 /// Default max amount of time to spend tracing a transaction (doesn't take into account state rebuild time)
-const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct TraceTransactionRequest {
     tx_hash: H256,
@@ -26,6 +39,23 @@ pub struct TraceBlockByNumberRequest {
     trace_config: TraceConfig,
 }
 
+pub struct TraceBlockByHashRequest {
+    block_hash: H256,
+    trace_config: TraceConfig,
+}
+
+/// `debug_traceBlock`: traces an RLP-encoded block on top of its parent's state without importing it.
+pub struct TraceBlockRequest {
+    block: Block,
+    trace_config: TraceConfig,
+}
+
+pub struct TraceCallRequest {
+    transaction: GenericTransaction,
+    block: BlockIdentifierOrHash,
+    trace_config: TraceCallConfig,
+}
+
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct TraceConfig {
@@ -40,6 +70,43 @@ struct TraceConfig {
     reexec: Option<u32>,
 }
 
+/// `debug_traceCall` config: the regular trace config plus geth's state and block overrides.
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct TraceCallConfig {
+    #[serde(flatten)]
+    trace_config: TraceConfig,
+    #[serde(default)]
+    state_overrides: Option<StateOverride>,
+    #[serde(default)]
+    block_overrides: Option<BlockOverrides>,
+}
+
+impl TraceConfig {
+    /// Parses the `tracerConfig` field as the config of the selected tracer,
+    /// falling back to its default when absent.
+    fn tracer_config<T: serde::de::DeserializeOwned + Default>(&self) -> Result<T, RpcErr> {
+        parse_tracer_config(self.tracer_config.as_ref())
+    }
+
+    /// Builds the config of the selected tracer for the tracers run through LEVM's
+    /// native tracer path.
+    fn native_tracer_config(&self) -> Result<NativeTracerConfig, RpcErr> {
+        self.tracer.native_config(self.tracer_config.as_ref())
+    }
+}
+
+/// Parses a `tracerConfig` value, falling back to the default config when absent or null.
+fn parse_tracer_config<T: serde::de::DeserializeOwned + Default>(
+    value: Option<&Value>,
+) -> Result<T, RpcErr> {
+    Ok(value
+        .filter(|value| !value.is_null())
+        .map(|value| serde_json::from_value(value.clone()))
+        .transpose()?
+        .unwrap_or_default())
+}
+
 /// The tracer variant to use for a debug trace request.
 ///
 /// **Divergence from geth**: geth's default (when no `tracer` field is provided) is the
@@ -59,6 +126,66 @@ enum TracerType {
     /// `structLogger` wrapper shape (`{failed, gas, returnValue, structLogs}`).
     /// Selected via `"tracer": "opcodeTracer"`.
     OpcodeTracer,
+    /// Counts calls per function selector and calldata size.
+    #[serde(rename = "4byteTracer")]
+    FourByteTracer,
+    /// The call trace flattened into Parity-style traces.
+    FlatCallTracer,
+    /// Runs several tracers in a single execution. Its config is an object mapping each
+    /// tracer's name to that tracer's config.
+    MuxTracer,
+    /// Outputs an empty object.
+    NoopTracer,
+}
+
+impl TracerType {
+    /// Builds the native tracer config for this tracer out of its raw `tracerConfig`.
+    /// The opcode tracer isn't one of them, so it can't be run through `muxTracer`.
+    fn native_config(&self, config: Option<&Value>) -> Result<NativeTracerConfig, RpcErr> {
+        Ok(match self {
+            TracerType::CallTracer => {
+                let config: CallTracerConfig = parse_tracer_config(config)?;
+                NativeTracerConfig::Call {
+                    only_top_call: config.only_top_call,
+                    with_log: config.with_log,
+                }
+            }
+            TracerType::PrestateTracer => {
+                let config: PrestateTracerConfig = parse_tracer_config(config)?;
+                config.validate()?;
+                NativeTracerConfig::Prestate {
+                    diff_mode: config.diff_mode,
+                    include_empty: config.include_empty,
+                }
+            }
+            TracerType::OpcodeTracer => {
+                return Err(RpcErr::BadParams(
+                    "opcodeTracer is not supported by muxTracer".to_string(),
+                ));
+            }
+            TracerType::FourByteTracer => NativeTracerConfig::FourByte,
+            TracerType::FlatCallTracer => {
+                let config: FlatCallTracerConfig = parse_tracer_config(config)?;
+                NativeTracerConfig::FlatCall {
+                    include_precompiles: config.include_precompiles,
+                }
+            }
+            TracerType::MuxTracer => {
+                let tracers: serde_json::Map<String, Value> = parse_tracer_config(config)?;
+                NativeTracerConfig::Mux(
+                    tracers
+                        .into_iter()
+                        .map(|(name, config)| {
+                            let tracer: TracerType =
+                                serde_json::from_value(Value::String(name.clone()))?;
+                            Ok((name, tracer.native_config(Some(&config))?))
+                        })
+                        .collect::<Result<_, RpcErr>>()?,
+                )
+            }
+            TracerType::NoopTracer => NativeTracerConfig::Noop,
+        })
+    }
 }
 
 #[derive(Deserialize, Default)]
@@ -79,6 +206,13 @@ struct PrestateTracerConfig {
     include_empty: bool,
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct FlatCallTracerConfig {
+    #[serde(default)]
+    include_precompiles: bool,
+}
+
 impl PrestateTracerConfig {
     fn validate(&self) -> Result<(), RpcErr> {
         if self.diff_mode && self.include_empty {
@@ -90,6 +224,49 @@ impl PrestateTracerConfig {
     }
 }
 
+/// Position of the traced transaction in the chain, which flatCallTracer output carries.
+struct TxLocation {
+    block_hash: H256,
+    block_number: u64,
+    tx_hash: H256,
+    tx_position: u64,
+}
+
+/// Serializes the result of a native tracer in the shape geth outputs for it.
+fn native_trace_to_value(
+    result: NativeTraceResult,
+    location: &TxLocation,
+) -> Result<Value, RpcErr> {
+    Ok(match result {
+        NativeTraceResult::Call(frame) => serde_json::to_value(frame)?,
+        NativeTraceResult::FlatCall(frame) => {
+            let traces: Vec<LocalizedTrace> = flatten_call_trace(frame)
+                .into_iter()
+                .map(|trace| LocalizedTrace {
+                    trace,
+                    block_hash: location.block_hash,
+                    block_number: location.block_number,
+                    transaction_hash: location.tx_hash,
+                    transaction_position: location.tx_position,
+                })
+                .collect();
+            serde_json::to_value(traces)?
+        }
+        NativeTraceResult::FourByte(ids) => serde_json::to_value(ids)?,
+        NativeTraceResult::Prestate(PrestateResult::Prestate(trace)) => {
+            serde_json::to_value(trace)?
+        }
+        NativeTraceResult::Prestate(PrestateResult::Diff(diff)) => serde_json::to_value(diff)?,
+        NativeTraceResult::Noop => Value::Object(Default::default()),
+        NativeTraceResult::Mux(results) => Value::Object(
+            results
+                .into_iter()
+                .map(|(name, result)| Ok((name, native_trace_to_value(result, location)?)))
+                .collect::<Result<_, RpcErr>>()?,
+        ),
+    })
+}
+
 type BlockTrace<TxTrace> = Vec<BlockTraceComponent<TxTrace>>;
 
 #[derive(Serialize)]
@@ -209,6 +386,31 @@ impl RpcHandler for TraceTransactionRequest {
                     emit,
                 })?)
             }
+            TracerType::FourByteTracer
+            | TracerType::FlatCallTracer
+            | TracerType::MuxTracer
+            | TracerType::NoopTracer => {
+                let cfg = self.trace_config.native_tracer_config()?;
+                let (block_number, block_hash, tx_position) = context
+                    .storage
+                    .get_transaction_location(self.tx_hash)
+                    .await?
+                    .ok_or(RpcErr::Internal("Transaction not Found".to_string()))?;
+                let result = context
+                    .blockchain
+                    .trace_transaction_native(self.tx_hash, reexec, timeout, cfg)
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                native_trace_to_value(
+                    result,
+                    &TxLocation {
+                        block_hash,
+                        block_number,
+                        tx_hash: self.tx_hash,
+                        tx_position,
+                    },
+                )
+            }
         }
     }
 }
@@ -249,16 +451,101 @@ impl RpcHandler for TraceBlockByNumberRequest {
             .get_block_by_number(block_number)
             .await?
             .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
-        let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
-        let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        match self.trace_config.tracer {
+        self.trace_config.trace_block(block, &context).await
+    }
+}
+
+impl RpcHandler for TraceBlockByHashRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 && params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 1 or 2 params".to_owned()));
+        };
+        let trace_config = if params.len() == 2 {
+            serde_json::from_value(params[1].clone())?
+        } else {
+            TraceConfig::default()
+        };
+
+        Ok(TraceBlockByHashRequest {
+            block_hash: serde_json::from_value(params[0].clone())?,
+            trace_config,
+        })
+    }
+
+    async fn handle(
+        &self,
+        context: crate::rpc::RpcApiContext,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        // Looked up by hash so side-chain blocks that are still in the store can be traced too
+        let block = context
+            .storage
+            .get_block_by_hash(self.block_hash)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        self.trace_config.trace_block(block, &context).await
+    }
+}
+
+impl RpcHandler for TraceBlockRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() != 1 && params.len() != 2 {
+            return Err(RpcErr::BadParams("Expected 1 or 2 params".to_owned()));
+        };
+        let trace_config = if params.len() == 2 {
+            serde_json::from_value(params[1].clone())?
+        } else {
+            TraceConfig::default()
+        };
+
+        let rlp_str: String = serde_json::from_value(params[0].clone())?;
+        let rlp_str = rlp_str.strip_prefix("0x").ok_or(RpcErr::BadHexFormat(0))?;
+        let rlp = hex::decode(rlp_str).map_err(|_| RpcErr::BadHexFormat(0))?;
+        let block = Block::decode(&rlp)
+            .map_err(|err| RpcErr::BadParams(format!("Invalid block RLP: {err}")))?;
+
+        Ok(TraceBlockRequest {
+            block,
+            trace_config,
+        })
+    }
+
+    async fn handle(
+        &self,
+        context: crate::rpc::RpcApiContext,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        if context
+            .storage
+            .get_block_header_by_hash(self.block.header.parent_hash)?
+            .is_none()
+        {
+            return Err(RpcErr::Internal("Parent Block not Found".to_string()));
+        }
+        self.trace_config
+            .trace_block(self.block.clone(), &context)
+            .await
+    }
+}
+
+impl TraceConfig {
+    /// Traces every transaction of `block` on top of its parent's state with the selected tracer.
+    /// The block doesn't need to be canonical nor even stored, only its parent's state must be
+    /// reachable within `reexec` blocks.
+    async fn trace_block(
+        &self,
+        block: Block,
+        context: &crate::rpc::RpcApiContext,
+    ) -> Result<serde_json::Value, RpcErr> {
+        let reexec = self.reexec.unwrap_or(DEFAULT_REEXEC);
+        let timeout = self.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        match self.tracer {
             TracerType::CallTracer => {
-                // Parse tracer config now that we know the type
-                let config = if let Some(value) = &self.trace_config.tracer_config {
-                    serde_json::from_value(value.clone())?
-                } else {
-                    CallTracerConfig::default()
-                };
+                let config: CallTracerConfig = self.tracer_config()?;
                 let call_traces = context
                     .blockchain
                     .trace_block_calls(
@@ -285,12 +572,7 @@ impl RpcHandler for TraceBlockByNumberRequest {
                 Ok(serde_json::to_value(block_trace)?)
             }
             TracerType::PrestateTracer => {
-                let config: PrestateTracerConfig =
-                    if let Some(value) = &self.trace_config.tracer_config {
-                        serde_json::from_value(value.clone())?
-                    } else {
-                        PrestateTracerConfig::default()
-                    };
+                let config: PrestateTracerConfig = self.tracer_config()?;
                 config.validate()?;
                 let prestate_traces = context
                     .blockchain
@@ -321,13 +603,7 @@ impl RpcHandler for TraceBlockByNumberRequest {
                 Ok(serde_json::to_value(block_trace)?)
             }
             TracerType::OpcodeTracer => {
-                let cfg: OpcodeTracerConfig = self
-                    .trace_config
-                    .tracer_config
-                    .as_ref()
-                    .map(|v| serde_json::from_value(v.clone()))
-                    .transpose()?
-                    .unwrap_or_default();
+                let cfg: OpcodeTracerConfig = self.tracer_config()?;
                 let emit = StructLoggerEmit {
                     mem_size: cfg.enable_memory,
                     return_data: cfg.enable_return_data,
@@ -339,7 +615,7 @@ impl RpcHandler for TraceBlockByNumberRequest {
                     .await
                     .map_err(|err| RpcErr::Internal(err.to_string()))?;
                 // Wrap each result with StructLoggerResult so it serializes in the
-                // geth-RPC shape expected by `debug_traceBlockByNumber` consumers.
+                // geth-RPC shape expected by `debug_traceBlock*` consumers.
                 let block_trace: Vec<serde_json::Value> = opcode_traces
                     .into_iter()
                     .map(|(hash, result)| {
@@ -355,6 +631,164 @@ impl RpcHandler for TraceBlockByNumberRequest {
                     .collect::<Result<_, serde_json::Error>>()?;
                 Ok(serde_json::to_value(block_trace)?)
             }
+            TracerType::FourByteTracer
+            | TracerType::FlatCallTracer
+            | TracerType::MuxTracer
+            | TracerType::NoopTracer => {
+                let cfg = self.native_tracer_config()?;
+                let (block_hash, block_number) = (block.hash(), block.header.number);
+                let traces = context
+                    .blockchain
+                    .trace_block_native(block, reexec, timeout, cfg)
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                let block_trace: BlockTrace<Value> = traces
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, (hash, result))| {
+                        let location = TxLocation {
+                            block_hash,
+                            block_number,
+                            tx_hash: hash,
+                            tx_position: index as u64,
+                        };
+                        Ok((hash, native_trace_to_value(result, &location)?).into())
+                    })
+                    .collect::<Result<_, RpcErr>>()?;
+                Ok(serde_json::to_value(block_trace)?)
+            }
+        }
+    }
+}
+
+impl RpcHandler for TraceCallRequest {
+    fn parse(params: &Option<Vec<serde_json::Value>>) -> Result<Self, RpcErr> {
+        let params = params
+            .as_ref()
+            .ok_or(RpcErr::BadParams("No params provided".to_owned()))?;
+        if params.len() < 2 || params.len() > 3 {
+            return Err(RpcErr::BadParams("Expected 2 or 3 params".to_owned()));
+        };
+        let trace_config: TraceCallConfig = if params.len() == 3 {
+            serde_json::from_value(params[2].clone())?
+        } else {
+            TraceCallConfig::default()
+        };
+        if let Some(state_overrides) = &trace_config.state_overrides {
+            reject_moved_precompiles(state_overrides)?;
+        }
+
+        Ok(TraceCallRequest {
+            transaction: serde_json::from_value(params[0].clone())?,
+            block: BlockIdentifierOrHash::parse(params[1].clone(), 1)?,
+            trace_config,
+        })
+    }
+
+    async fn handle(
+        &self,
+        context: crate::rpc::RpcApiContext,
+    ) -> Result<serde_json::Value, crate::utils::RpcErr> {
+        let header = self
+            .block
+            .resolve_block_header(&context.storage)
+            .await?
+            .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
+        let overrides = CallOverrides {
+            state: self
+                .trace_config
+                .state_overrides
+                .clone()
+                .unwrap_or_default(),
+            block: self
+                .trace_config
+                .block_overrides
+                .clone()
+                .unwrap_or_default(),
+        };
+        let trace_config = &self.trace_config.trace_config;
+        let reexec = trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
+        let timeout = trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
+        let transaction = self.transaction.clone();
+        match trace_config.tracer {
+            TracerType::CallTracer => {
+                let config: CallTracerConfig = trace_config.tracer_config()?;
+                let call_trace = context
+                    .blockchain
+                    .trace_call_calls(
+                        transaction,
+                        header,
+                        overrides,
+                        reexec,
+                        timeout,
+                        config.only_top_call,
+                        config.with_log,
+                    )
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                let top_frame = call_trace
+                    .into_iter()
+                    .next()
+                    .ok_or(RpcErr::Internal("Empty call trace".to_string()))?;
+                Ok(serde_json::to_value(top_frame)?)
+            }
+            TracerType::PrestateTracer => {
+                let config: PrestateTracerConfig = trace_config.tracer_config()?;
+                config.validate()?;
+                let result = context
+                    .blockchain
+                    .trace_call_prestate(
+                        transaction,
+                        header,
+                        overrides,
+                        reexec,
+                        timeout,
+                        config.diff_mode,
+                        config.include_empty,
+                    )
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                match result {
+                    PrestateResult::Prestate(trace) => Ok(serde_json::to_value(trace)?),
+                    PrestateResult::Diff(diff) => Ok(serde_json::to_value(diff)?),
+                }
+            }
+            TracerType::OpcodeTracer => {
+                let cfg: OpcodeTracerConfig = trace_config.tracer_config()?;
+                let emit = StructLoggerEmit {
+                    mem_size: cfg.enable_memory,
+                    return_data: cfg.enable_return_data,
+                    refund: false,
+                };
+                let result = context
+                    .blockchain
+                    .trace_call_opcodes(transaction, header, overrides, reexec, timeout, cfg)
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                Ok(serde_json::to_value(StructLoggerResult {
+                    result: &result,
+                    emit,
+                })?)
+            }
+            TracerType::FourByteTracer
+            | TracerType::FlatCallTracer
+            | TracerType::MuxTracer
+            | TracerType::NoopTracer => {
+                let cfg = trace_config.native_tracer_config()?;
+                // Simulated calls aren't part of any block, like geth they get a zero tx hash.
+                let location = TxLocation {
+                    block_hash: header.hash(),
+                    block_number: header.number,
+                    tx_hash: H256::zero(),
+                    tx_position: 0,
+                };
+                let result = context
+                    .blockchain
+                    .trace_call_native(transaction, header, overrides, reexec, timeout, cfg)
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                native_trace_to_value(result, &location)
+            }
         }
     }
 }
@@ -34,16 +34,34 @@ impl RpcTransaction {
         transaction_index: Option<usize>,
     ) -> Result<Self, RpcErr> {
         let from = tx.sender(&NativeCrypto)?;
+        Ok(Self::build_with_sender(
+            tx,
+            from,
+            block_number,
+            block_hash,
+            transaction_index,
+        ))
+    }
+
+    /// Same as [`RpcTransaction::build`] for transactions whose sender is already known,
+    /// such as the unsigned ones produced by `eth_simulateV1`.
+    pub fn build_with_sender(
+        tx: Transaction,
+        from: Address,
+        block_number: Option<BlockNumber>,
+        block_hash: Option<BlockHash>,
+        transaction_index: Option<usize>,
+    ) -> Self {
         let hash = tx.hash();
         let transaction_index = transaction_index.map(|n| n as u64);
-        Ok(RpcTransaction {
+        RpcTransaction {
             tx,
             block_number,
             block_hash,
             from,
             hash,
             transaction_index,
-        })
+        }
     }
 }
 
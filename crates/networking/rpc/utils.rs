@@ -6,7 +6,7 @@
 //! - [`RpcNamespace`]: RPC method namespace (eth, engine, debug, etc.)
 //! - Response types for success and error cases
 
-use ethrex_common::U256;
+use ethrex_common::{U256, types::BlockNumber};
 use ethrex_storage::error::StoreError;
 use ethrex_vm::EvmError;
 use serde::{Deserialize, Serialize};
@@ -64,6 +64,8 @@ pub enum RpcErr {
     TooDeepReorg(String),
     #[error("Unknown payload: {0}")]
     UnknownPayload(String),
+    #[error("Pruned history unavailable for block {0}")]
+    PrunedHistory(BlockNumber),
     // EIP-8025 proof errors (-39001 .. -39004)
     #[error("Invalid proof format: {0}")]
     InvalidProofFormat(String),
@@ -73,6 +75,15 @@ pub enum RpcErr {
     InvalidPayload(String),
     #[error("Proof generation unavailable: {0}")]
     ProofGenerationUnavailable(String),
+    // eth_simulateV1 errors
+    #[error("Block gas limit exceeded: {0}")]
+    BlockGasLimitExceeded(String),
+    #[error("Invalid block number: {0}")]
+    InvalidBlockNumber(String),
+    #[error("Invalid block timestamp: {0}")]
+    InvalidBlockTimestamp(String),
+    #[error("Client adjustable limit exceeded: {0}")]
+    ClientLimitExceeded(String),
 }
 
 impl From<RpcErr> for RpcErrorMetadata {
@@ -184,6 +195,12 @@ impl From<RpcErr> for RpcErrorMetadata {
                 data: None,
                 message: format!("Unknown payload: {context}"),
             },
+            // EIP-4444 pruned history error code
+            RpcErr::PrunedHistory(block_number) => RpcErrorMetadata {
+                code: 4444,
+                data: None,
+                message: format!("pruned history unavailable for block {block_number}"),
+            },
             // EIP-8025 proof error codes
             RpcErr::InvalidProofFormat(context) => RpcErrorMetadata {
                 code: -39001,
@@ -205,6 +222,27 @@ impl From<RpcErr> for RpcErrorMetadata {
                 data: None,
                 message: format!("Proof generation unavailable: {context}"),
             },
+            // eth_simulateV1 error codes
+            RpcErr::BlockGasLimitExceeded(context) => RpcErrorMetadata {
+                code: -38015,
+                data: None,
+                message: format!("Block gas limit exceeded: {context}"),
+            },
+            RpcErr::InvalidBlockNumber(context) => RpcErrorMetadata {
+                code: -38020,
+                data: None,
+                message: format!("Invalid block number: {context}"),
+            },
+            RpcErr::InvalidBlockTimestamp(context) => RpcErrorMetadata {
+                code: -38021,
+                data: None,
+                message: format!("Invalid block timestamp: {context}"),
+            },
+            RpcErr::ClientLimitExceeded(context) => RpcErrorMetadata {
+                code: -38026,
+                data: None,
+                message: format!("Client adjustable limit exceeded: {context}"),
+            },
         }
     }
 }
@@ -252,6 +290,8 @@ pub enum RpcNamespace {
     Net,
     /// Transaction pool inspection methods (exposed as `txpool_*`).
     Mempool,
+    /// OpenEthereum-style transaction tracing methods.
+    Trace,
 }
 
 impl RpcNamespace {
@@ -265,6 +305,7 @@ impl RpcNamespace {
             "web3" => Some(RpcNamespace::Web3),
             "net" => Some(RpcNamespace::Net),
             "txpool" => Some(RpcNamespace::Mempool),
+            "trace" => Some(RpcNamespace::Trace),
             _ => None,
         }
     }
@@ -0,0 +1,258 @@
+//! Append-only flat-file store for finalized chain data ("ancients").
+//!
+//! Block bodies and receipts of old finalized blocks are moved out of the database
+//! into flat files, which never need compaction and can live on a separate (cheaper)
+//! disk. Each kind of data is kept in a `FreezerTable`: a data file with the
+//! concatenated items and an index file with the end offset of every item, as a
+//! big-endian `u64`. Items are numbered by block number starting at genesis. An empty
+//! item means the data wasn't available when the block was frozen (e.g. bodies that
+//! snap sync never downloaded).
+
+use std::fs::{File, OpenOptions};
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ethrex_common::{H256, types::BlockNumber};
+
+use crate::error::StoreError;
+
+/// Data of a block being frozen.
+#[derive(Debug, Clone, Default)]
+pub struct AncientBlock {
+    pub hash: H256,
+    /// RLP-encoded block body, `None` if it isn't available.
+    pub body: Option<Vec<u8>>,
+    /// RLP-encoded list of receipts, `None` if they aren't available.
+    pub receipts: Option<Vec<u8>>,
+}
+
+/// A pair of append-only data and index files.
+#[derive(Debug)]
+struct FreezerTable {
+    data: File,
+    index: File,
+    data_len: AtomicU64,
+}
+
+impl FreezerTable {
+    fn open(dir: &Path, name: &str) -> Result<(Self, u64), StoreError> {
+        let open = |extension: &str| {
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(dir.join(format!("{name}.{extension}")))
+        };
+        let table = Self {
+            data: open("dat")?,
+            index: open("idx")?,
+            data_len: AtomicU64::new(0),
+        };
+        // Drop items that were only partially written before a crash
+        let data_len = table.data.metadata()?.len();
+        let mut items = table.index.metadata()?.len() / 8;
+        while items > 0 && table.end_offset(items - 1)? > data_len {
+            items -= 1;
+        }
+        Ok((table, items))
+    }
+
+    /// Truncates the table to its first `items` items.
+    fn truncate(&self, items: u64) -> Result<(), StoreError> {
+        let data_len = match items {
+            0 => 0,
+            items => self.end_offset(items - 1)?,
+        };
+        self.index.set_len(items * 8)?;
+        self.data.set_len(data_len)?;
+        self.data_len.store(data_len, Ordering::Release);
+        Ok(())
+    }
+
+    fn end_offset(&self, item: u64) -> Result<u64, StoreError> {
+        let mut buf = [0u8; 8];
+        self.index.read_exact_at(&mut buf, item * 8)?;
+        Ok(u64::from_be_bytes(buf))
+    }
+
+    fn get(&self, item: u64) -> Result<Vec<u8>, StoreError> {
+        let start = match item {
+            0 => 0,
+            item => self.end_offset(item - 1)?,
+        };
+        let end = self.end_offset(item)?;
+        let len = end
+            .checked_sub(start)
+            .ok_or_else(|| StoreError::Custom("Corrupted ancient index".to_string()))?;
+        let mut buf = vec![0u8; len as usize];
+        self.data.read_exact_at(&mut buf, start)?;
+        Ok(buf)
+    }
+
+    /// Writes `value` as item number `item`, which must be the next one.
+    fn append(&self, item: u64, value: &[u8]) -> Result<(), StoreError> {
+        let start = self.data_len.load(Ordering::Acquire);
+        let end = start + value.len() as u64;
+        self.data.write_all_at(value, start)?;
+        self.index.write_all_at(&end.to_be_bytes(), item * 8)?;
+        self.data_len.store(end, Ordering::Release);
+        Ok(())
+    }
+
+    fn sync(&self) -> Result<(), StoreError> {
+        self.data.sync_data()?;
+        self.index.sync_data()?;
+        Ok(())
+    }
+}
+
+/// Flat-file store holding the hashes, bodies and receipts of blocks `0..frozen()`.
+#[derive(Debug)]
+pub struct AncientStore {
+    hashes: FreezerTable,
+    bodies: FreezerTable,
+    receipts: FreezerTable,
+    /// Number of blocks frozen, readers only look at items below it.
+    frozen: AtomicU64,
+    /// Serializes appends.
+    write_lock: Mutex<()>,
+}
+
+impl AncientStore {
+    /// Opens (or creates) the ancient store in `dir`, discarding any block that wasn't
+    /// fully written to every table.
+    pub fn open(dir: &Path) -> Result<Self, StoreError> {
+        std::fs::create_dir_all(dir)?;
+        let (hashes, hashes_items) = FreezerTable::open(dir, "hashes")?;
+        let (bodies, bodies_items) = FreezerTable::open(dir, "bodies")?;
+        let (receipts, receipts_items) = FreezerTable::open(dir, "receipts")?;
+        let frozen = hashes_items.min(bodies_items).min(receipts_items);
+        for table in [&hashes, &bodies, &receipts] {
+            table.truncate(frozen)?;
+        }
+        Ok(Self {
+            hashes,
+            bodies,
+            receipts,
+            frozen: AtomicU64::new(frozen),
+            write_lock: Mutex::new(()),
+        })
+    }
+
+    /// Returns the number of frozen blocks, i.e. the number of the next block to freeze.
+    pub fn frozen(&self) -> BlockNumber {
+        self.frozen.load(Ordering::Acquire)
+    }
+
+    /// Appends `blocks`, which must be consecutive and start at [`frozen`](Self::frozen),
+    /// and flushes them to disk.
+    pub fn append(&self, blocks: &[AncientBlock]) -> Result<(), StoreError> {
+        let _guard = self.write_lock.lock().map_err(|_| StoreError::LockError)?;
+        let first = self.frozen();
+        for (number, block) in (first..).zip(blocks) {
+            self.hashes.append(number, block.hash.as_bytes())?;
+            self.bodies
+                .append(number, block.body.as_deref().unwrap_or_default())?;
+            self.receipts
+                .append(number, block.receipts.as_deref().unwrap_or_default())?;
+        }
+        for table in [&self.hashes, &self.bodies, &self.receipts] {
+            table.sync()?;
+        }
+        self.frozen
+            .store(first + blocks.len() as u64, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the hash of frozen block `number`.
+    pub fn hash(&self, number: BlockNumber) -> Result<Option<H256>, StoreError> {
+        if number >= self.frozen() {
+            return Ok(None);
+        }
+        let hash = self.hashes.get(number)?;
+        if hash.len() != 32 {
+            return Err(StoreError::Custom(
+                "Corrupted ancient block hash".to_string(),
+            ));
+        }
+        Ok(Some(H256::from_slice(&hash)))
+    }
+
+    /// Returns the RLP-encoded body of frozen block `number`.
+    pub fn body(&self, number: BlockNumber) -> Result<Option<Vec<u8>>, StoreError> {
+        self.item(&self.bodies, number)
+    }
+
+    /// Returns the RLP-encoded receipts of frozen block `number`.
+    pub fn receipts(&self, number: BlockNumber) -> Result<Option<Vec<u8>>, StoreError> {
+        self.item(&self.receipts, number)
+    }
+
+    fn item(
+        &self,
+        table: &FreezerTable,
+        number: BlockNumber,
+    ) -> Result<Option<Vec<u8>>, StoreError> {
+        if number >= self.frozen() {
+            return Ok(None);
+        }
+        let value = table.get(number)?;
+        Ok((!value.is_empty()).then_some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(number: u64, body: Option<Vec<u8>>) -> AncientBlock {
+        AncientBlock {
+            hash: H256::from_low_u64_be(number),
+            receipts: body.clone(),
+            body,
+        }
+    }
+
+    #[test]
+    fn append_and_read_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let ancients = AncientStore::open(dir.path()).unwrap();
+        assert_eq!(ancients.frozen(), 0);
+
+        ancients
+            .append(&[block(0, Some(vec![1, 2, 3])), block(1, None)])
+            .unwrap();
+        ancients.append(&[block(2, Some(vec![4]))]).unwrap();
+
+        assert_eq!(ancients.frozen(), 3);
+        assert_eq!(ancients.hash(1).unwrap(), Some(H256::from_low_u64_be(1)));
+        assert_eq!(ancients.body(0).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(ancients.body(1).unwrap(), None);
+        assert_eq!(ancients.receipts(2).unwrap(), Some(vec![4]));
+        assert_eq!(ancients.hash(3).unwrap(), None);
+    }
+
+    #[test]
+    fn reopen_discards_partially_written_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let ancients = AncientStore::open(dir.path()).unwrap();
+            ancients
+                .append(&[block(0, Some(vec![1])), block(1, Some(vec![2, 3]))])
+                .unwrap();
+            // Simulate a crash after writing only part of block 2
+            ancients.hashes.append(2, &[0; 32]).unwrap();
+            ancients.bodies.append(2, &[4]).unwrap();
+        }
+
+        let ancients = AncientStore::open(dir.path()).unwrap();
+        assert_eq!(ancients.frozen(), 2);
+        assert_eq!(ancients.body(1).unwrap(), Some(vec![2, 3]));
+
+        ancients.append(&[block(2, Some(vec![5]))]).unwrap();
+        assert_eq!(ancients.body(2).unwrap(), Some(vec![5]));
+    }
+}
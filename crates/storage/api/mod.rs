@@ -65,6 +65,13 @@ pub trait StorageReadView: Send + Sync {
         table: &'static str,
         prefix: &[u8],
     ) -> Result<Box<dyn Iterator<Item = PrefixResult> + '_>, StoreError>;
+
+    /// Returns the first key-value pair whose key is greater than or equal to `key`.
+    fn seek(
+        &self,
+        table: &'static str,
+        key: &[u8],
+    ) -> Result<Option<(Box<[u8]>, Box<[u8]>)>, StoreError>;
 }
 
 /// Write transaction interface.
@@ -113,7 +113,28 @@ pub const EXECUTION_WITNESSES: &str = "execution_witnesses";
 /// - [`Vec<u8>`] = RLP-encoded `BlockAccessList`
 pub const BLOCK_ACCESS_LISTS: &str = "block_access_lists";
 
-pub const TABLES: [&str; 20] = [
+/// Archive trie node history column family: [`Vec<u8>`] => [`Vec<u8>`]
+/// - [`Vec<u8>`] = `[path.len() as u8] || path || version.to_be_bytes()`, where `path` is the
+///   (prefixed) trie node path used as key in `ACCOUNT_TRIE_NODES`/`STORAGE_TRIE_NODES`
+/// - [`Vec<u8>`] = node data before the diff-layer with that version was written to disk
+///   (empty if the node didn't exist)
+///
+/// Only written in archive mode.
+pub const TRIE_NODES_HISTORY: &str = "trie_nodes_history";
+
+/// Archive state root versions column family: [`H256`] => [`u8; 8`]
+/// - [`H256`] = `state_root.as_bytes()`
+/// - [`u8; 8`] = `version.to_be_bytes()`, the version of the diff-layer that produced it
+///
+/// Only written in archive mode.
+pub const STATE_ROOT_VERSIONS: &str = "state_root_versions";
+
+/// Peer table column family: [`H256`] => [`Vec<u8>`]
+/// - [`H256`] = `node_id.as_bytes()`
+/// - [`Vec<u8>`] = RLP-encoded peer table entry, opaque to the storage layer
+pub const PEERS: &str = "peers";
+
+pub const TABLES: [&str; 23] = [
     CHAIN_DATA,
     ACCOUNT_CODES,
     ACCOUNT_CODE_METADATA,
@@ -134,4 +155,7 @@ pub const TABLES: [&str; 20] = [
     MISC_VALUES,
     EXECUTION_WITNESSES,
     BLOCK_ACCESS_LISTS,
+    TRIE_NODES_HISTORY,
+    STATE_ROOT_VERSIONS,
+    PEERS,
 ];
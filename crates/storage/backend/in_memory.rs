@@ -3,10 +3,12 @@ use crate::api::{
 };
 use crate::error::StoreError;
 use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::{Arc, RwLock};
 
-type Table = FxHashMap<Vec<u8>, Vec<u8>>;
+// Ordered so that prefix iteration and `seek` don't need to scan the whole table
+type Table = BTreeMap<Vec<u8>, Vec<u8>>;
 type Database = FxHashMap<&'static str, Table>;
 
 #[derive(Debug)]
@@ -121,25 +123,36 @@ impl StorageReadView for InMemoryReadTx {
         table: &str,
         prefix: &[u8],
     ) -> Result<Box<dyn Iterator<Item = PrefixResult> + '_>, StoreError> {
-        let table_data = self.snapshot.get(table).cloned().unwrap_or_default();
-        let prefix_vec = prefix.to_vec();
-
-        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = table_data
-            .into_iter()
-            .filter(|(key, _)| key.starts_with(&prefix_vec))
-            .collect();
-        entries.sort_unstable_by(|(left, _), (right, _)| left.cmp(right));
-
-        let results: Vec<PrefixResult> = entries
-            .into_iter()
-            .map(|(k, v)| Ok((k.into_boxed_slice(), v.into_boxed_slice())))
-            .collect();
+        let results: Vec<PrefixResult> = self
+            .snapshot
+            .get(table)
+            .map(|table_ref| {
+                table_ref
+                    .range(prefix.to_vec()..)
+                    .take_while(|(key, _)| key.starts_with(prefix))
+                    .map(|(k, v)| Ok((k.clone().into_boxed_slice(), v.clone().into_boxed_slice())))
+                    .collect()
+            })
+            .unwrap_or_default();
 
         let iter = InMemoryPrefixIter {
             results: results.into_iter(),
         };
         Ok(Box::new(iter))
     }
+
+    fn seek(
+        &self,
+        table: &'static str,
+        key: &[u8],
+    ) -> Result<Option<(Box<[u8]>, Box<[u8]>)>, StoreError> {
+        Ok(self.snapshot.get(table).and_then(|table_ref| {
+            table_ref
+                .range(key.to_vec()..)
+                .next()
+                .map(|(k, v)| (k.clone().into_boxed_slice(), v.clone().into_boxed_slice()))
+        }))
+    }
 }
 
 pub struct InMemoryWriteTx {
@@ -1,7 +1,7 @@
 use crate::api::tables::{
     ACCOUNT_CODES, ACCOUNT_FLATKEYVALUE, ACCOUNT_TRIE_NODES, BLOCK_NUMBERS, BODIES,
     CANONICAL_BLOCK_HASHES, FULLSYNC_HEADERS, HEADERS, RECEIPTS_V2, STORAGE_FLATKEYVALUE,
-    STORAGE_TRIE_NODES, TRANSACTION_LOCATIONS,
+    STORAGE_TRIE_NODES, TRANSACTION_LOCATIONS, TRIE_NODES_HISTORY,
 };
 use crate::api::{
     PrefixResult, StorageBackend, StorageLockedView, StorageReadView, StorageWriteBatch,
@@ -11,8 +11,8 @@ use crate::error::StoreError;
 use rocksdb::DBWithThreadMode;
 use rocksdb::checkpoint::Checkpoint;
 use rocksdb::{
-    BlockBasedOptions, Cache, ColumnFamilyDescriptor, MergeOperands, MultiThreaded, Options,
-    SnapshotWithThreadMode, WriteBatch,
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, Direction, IteratorMode, MergeOperands,
+    MultiThreaded, Options, SnapshotWithThreadMode, WriteBatch,
 };
 use std::collections::HashSet;
 use std::path::Path;
@@ -82,6 +82,7 @@ impl RocksDBBackend {
             RECEIPTS_V2,
             TRANSACTION_LOCATIONS,
             FULLSYNC_HEADERS,
+            TRIE_NODES_HISTORY,
         ];
 
         // Open all column families
@@ -385,6 +386,23 @@ impl StorageReadView for RocksDBReadTx {
         });
         Ok(Box::new(iter))
     }
+
+    fn seek(
+        &self,
+        table: &'static str,
+        key: &[u8],
+    ) -> Result<Option<(Box<[u8]>, Box<[u8]>)>, StoreError> {
+        let cf = self
+            .db
+            .cf_handle(table)
+            .ok_or_else(|| StoreError::Custom(format!("Table {} not found", table)))?;
+
+        self.db
+            .iterator_cf(&cf, IteratorMode::From(key, Direction::Forward))
+            .next()
+            .transpose()
+            .map_err(|e| StoreError::Custom(format!("Failed to seek in {table}: {e}")))
+    }
 }
 
 /// Write batch for RocksDB
@@ -1,3 +1,4 @@
+use ethrex_common::H256;
 use ethrex_rlp::error::RLPDecodeError;
 use ethrex_trie::TrieError;
 use thiserror::Error;
@@ -52,4 +53,8 @@ pub enum StoreError {
     IncompatibleDBVersion { found: u64, expected: u64 },
     #[error("Migration from v{from} to v{to} failed: {reason}")]
     MigrationFailed { from: u64, to: u64, reason: String },
+    #[error(
+        "Cannot build on archived state root {0:#x}, reorgs deeper than the in-memory diff-layers aren't supported"
+    )]
+    ArchivedStateRoot(H256),
 }
@@ -16,6 +16,14 @@ struct TrieLayer {
     id: usize,
 }
 
+/// A diff-layer removed from the cache by [`TrieLayerCache::commit_layers`].
+#[derive(Debug)]
+pub struct CommittedLayer {
+    pub state_root: H256,
+    pub parent: H256,
+    pub nodes: FxHashMap<Vec<u8>, Vec<u8>>,
+}
+
 /// In-memory cache of trie diff-layers, one per block (or per batch of blocks in full sync).
 ///
 /// Layers form a singly-linked chain from newest to oldest via the `parent` field:
@@ -128,6 +136,11 @@ impl TrieLayerCache {
         None
     }
 
+    /// Returns whether a diff-layer for `state_root` is in the cache.
+    pub fn contains(&self, state_root: H256) -> bool {
+        self.layers.contains_key(&state_root)
+    }
+
     /// Returns the state root from which to start a disk commit, using the cache's
     /// default `commit_threshold`.
     ///
@@ -242,23 +255,38 @@ impl TrieLayerCache {
     /// After removal, any orphaned layers (older than the committed ones) are pruned, and
     /// the bloom filter is rebuilt to remove stale entries.
     pub fn commit(&mut self, state_root: H256) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
+        let nodes_to_commit = self
+            .commit_layers(state_root)?
+            .into_iter()
+            .flat_map(|layer| layer.nodes)
+            .collect();
+        Some(nodes_to_commit)
+    }
+
+    /// Same as [`commit`](Self::commit), but returns each removed layer separately
+    /// (oldest first) so that archive mode can record per-layer history.
+    pub fn commit_layers(&mut self, state_root: H256) -> Option<Vec<CommittedLayer>> {
         let mut layers_to_commit = vec![];
         let mut current_state_root = state_root;
         while let Some(layer) = self.layers.remove(&current_state_root) {
             let layer = Arc::unwrap_or_clone(layer);
+            layers_to_commit.push((current_state_root, layer.id, layer.parent, layer.nodes));
             current_state_root = layer.parent;
-            layers_to_commit.push(layer);
         }
-        let top_layer_id = layers_to_commit.first()?.id;
+        let top_layer_id = layers_to_commit.first()?.1;
         // older layers are useless
         self.layers.retain(|_, item| item.id > top_layer_id);
         self.rebuild_bloom(); // layers removed, rebuild global bloom filter.
-        let nodes_to_commit = layers_to_commit
+        let layers = layers_to_commit
             .into_iter()
             .rev()
-            .flat_map(|layer| layer.nodes)
+            .map(|(state_root, _, parent, nodes)| CommittedLayer {
+                state_root,
+                parent,
+                nodes,
+            })
             .collect();
-        Some(nodes_to_commit)
+        Some(layers)
     }
 }
 
@@ -64,6 +64,7 @@
 //! The store maintains a cache layer (`TrieLayerCache`) for efficient state access
 //! without requiring full trie traversal for recent blocks.
 
+pub mod ancients;
 pub mod api;
 pub mod backend;
 pub mod error;
@@ -76,8 +77,9 @@ pub mod utils;
 
 pub use layering::apply_prefix;
 pub use store::{
-    AccountUpdatesList, DEFAULT_ROCKSDB_BLOCK_CACHE_SIZE_BYTES, EngineType, Store, StoreConfig,
-    UpdateBatch, has_valid_db, hash_address, hash_key, read_chain_id_from_db,
+    ANCIENT_THRESHOLD, AccountUpdatesList, DEFAULT_ROCKSDB_BLOCK_CACHE_SIZE_BYTES, EngineType,
+    GcMode, HistoryRetention, Store, StoreConfig, UpdateBatch, has_valid_db, hash_address,
+    hash_key, read_chain_id_from_db, read_gc_mode_from_db,
 };
 
 /// Store Schema Version, must be updated on any breaking change.
@@ -2,22 +2,23 @@
 use crate::backend::rocksdb::RocksDBBackend;
 use crate::{
     STORE_METADATA_FILENAME, STORE_SCHEMA_VERSION,
+    ancients::{AncientBlock, AncientStore},
     api::{
-        StorageBackend, StorageReadView,
+        StorageBackend, StorageReadView, StorageWriteBatch,
         tables::{
             ACCOUNT_CODE_METADATA, ACCOUNT_CODES, ACCOUNT_FLATKEYVALUE, ACCOUNT_TRIE_NODES,
             BLOCK_ACCESS_LISTS, BLOCK_NUMBERS, BODIES, CANONICAL_BLOCK_HASHES, CHAIN_DATA,
-            EXECUTION_WITNESSES, FULLSYNC_HEADERS, HEADERS, INVALID_CHAINS, MISC_VALUES,
-            PENDING_BLOCKS, RECEIPTS_V2, SNAP_STATE, STORAGE_FLATKEYVALUE, STORAGE_TRIE_NODES,
-            TRANSACTION_LOCATIONS,
+            EXECUTION_WITNESSES, FULLSYNC_HEADERS, HEADERS, INVALID_CHAINS, MISC_VALUES, PEERS,
+            PENDING_BLOCKS, RECEIPTS_V2, SNAP_STATE, STATE_ROOT_VERSIONS, STORAGE_FLATKEYVALUE,
+            STORAGE_TRIE_NODES, TRANSACTION_LOCATIONS, TRIE_NODES_HISTORY,
         },
     },
     apply_prefix,
     backend::in_memory::InMemoryBackend,
     error::StoreError,
-    layering::{TrieLayerCache, TrieWrapper},
+    layering::{CommittedLayer, TrieLayerCache, TrieWrapper},
     rlp::{BlockBodyRLP, BlockHeaderRLP, BlockRLP},
-    trie::{BackendTrieDB, BackendTrieDBLocked},
+    trie::{BackendTrieDB, BackendTrieDBLocked, HistoricalTrieDB, history_key},
     utils::{ChainDataIndex, SnapStateIndex},
 };
 
@@ -38,7 +39,7 @@ use ethrex_rlp::{
     decode::{RLPDecode, decode_bytes},
     encode::RLPEncode,
 };
-use ethrex_trie::{EMPTY_TRIE_HASH, Nibbles, Trie, TrieLogger, TrieNode, TrieWitness};
+use ethrex_trie::{EMPTY_TRIE_HASH, Nibbles, Trie, TrieDB, TrieLogger, TrieNode, TrieWitness};
 use ethrex_trie::{Node, NodeRLP};
 use lru::LruCache;
 use rustc_hash::FxBuildHasher;
@@ -83,11 +84,21 @@ const BATCH_COMMIT_THRESHOLD: usize = 4;
 /// CFs) and ~8 GiB the floor where the filter set starts to thrash.
 pub const DEFAULT_ROCKSDB_BLOCK_CACHE_SIZE_BYTES: usize = 12 * 1024 * 1024 * 1024;
 
+/// Number of blocks behind the finalized block after which block bodies and receipts
+/// are moved to the ancient store.
+pub const ANCIENT_THRESHOLD: u64 = 90_000;
+
+/// Maximum number of blocks moved to the ancient store in a single write.
+const ANCIENT_BATCH_SIZE: u64 = 2048;
+
+/// Maximum number of blocks whose history is pruned in a single write.
+const HISTORY_PRUNE_BATCH_SIZE: u64 = 2048;
+
 /// Tunable configuration for [`Store::new_with_config`] and related constructors.
 ///
 /// Use [`StoreConfig::default()`] for production-tuned defaults; callers that
 /// don't need to override anything should keep calling [`Store::new`] directly.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct StoreConfig {
     /// Total size in bytes of the RocksDB shared block cache. With
     /// `cache_index_and_filter_blocks` enabled (the ethrex default), this cache
@@ -98,16 +109,61 @@ pub struct StoreConfig {
     ///
     /// Ignored when the engine type is in-memory.
     pub rocksdb_block_cache_size: usize,
+    /// Whether historical state is pruned ([`GcMode::Full`]) or retained
+    /// ([`GcMode::Archive`]). See the `--gcmode` CLI flag.
+    pub gc_mode: GcMode,
+    /// Directory of the ancient store, where bodies and receipts of blocks finalized
+    /// more than [`ANCIENT_THRESHOLD`] blocks ago are moved. Defaults to the `ancient`
+    /// subdirectory of the database. See the `--datadir.ancient` CLI flag.
+    ///
+    /// Ignored when the engine type is in-memory.
+    pub ancient_dir: Option<PathBuf>,
+    /// Which blocks keep their bodies, receipts and transaction locations. See the
+    /// `--history.retention` CLI flag.
+    pub history_retention: HistoryRetention,
 }
 
 impl Default for StoreConfig {
     fn default() -> Self {
         Self {
             rocksdb_block_cache_size: DEFAULT_ROCKSDB_BLOCK_CACHE_SIZE_BYTES,
+            gc_mode: GcMode::default(),
+            ancient_dir: None,
+            history_retention: HistoryRetention::default(),
         }
     }
 }
 
+/// Controls how much historical state the store keeps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GcMode {
+    /// Only the state of recent blocks (the in-memory diff-layers plus the disk layer)
+    /// is available.
+    #[default]
+    Full,
+    /// Every diff-layer written to disk also stores a reverse diff in
+    /// `TRIE_NODES_HISTORY`, so the state of every block imported since archive mode
+    /// was enabled remains available.
+    ///
+    /// Archived state is read-only: blocks can only be built on the in-memory diff-layers
+    /// or the disk layer, so a reorg deeper than the diff-layers fails with
+    /// [`StoreError::ArchivedStateRoot`].
+    Archive,
+}
+
+/// Controls which blocks keep their history (EIP-4444): bodies, receipts and
+/// transaction locations. Headers are always kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HistoryRetention {
+    /// The history of every block is kept.
+    #[default]
+    All,
+    /// The history of pre-merge blocks is pruned.
+    PostMerge,
+    /// Only the history of the last `n` blocks is kept.
+    Blocks(u64),
+}
+
 /// Control messages for the FlatKeyValue generator
 #[derive(Debug, PartialEq)]
 enum FKVGeneratorControlMessage {
@@ -231,6 +287,20 @@ pub struct Store {
     /// update and the DB write transaction remain mutually ordered.
     fcu_lock: Arc<tokio::sync::Mutex<()>>,
 
+    /// Hash of the canonical head, set when the head is loaded and after every
+    /// successful `forkchoice_update`. See [`Store::subscribe_head_updates`].
+    head_updates: Arc<tokio::sync::watch::Sender<BlockHash>>,
+
+    /// Whether the store keeps historical state (`--gcmode archive`).
+    archive: bool,
+
+    /// Flat-file store for the bodies and receipts of old finalized blocks.
+    /// `None` for in-memory stores.
+    ancients: Option<Arc<AncientStore>>,
+    /// Channel for waking up the background thread that prunes expired history and
+    /// moves blocks to `ancients`.
+    history_worker_tx: Option<SyncSender<()>>,
+
     background_threads: Arc<ThreadList>,
 }
 
@@ -569,6 +639,7 @@ impl Store {
     ) -> Result<Vec<Option<BlockBody>>, StoreError> {
         // TODO: Implement read bulk
         let backend = self.backend.clone();
+        let ancients = self.ancients.clone();
         tokio::task::spawn_blocking(move || {
             let numbers: Vec<BlockNumber> = (from..=to).collect();
             let mut block_bodies = Vec::new();
@@ -584,8 +655,13 @@ impl Store {
                     continue;
                 };
                 let hash_key = hash.encode_to_vec();
-                let block_body_opt = txn
-                    .get(BODIES, &hash_key)?
+                let block_body_opt = match txn.get(BODIES, &hash_key)? {
+                    Some(bytes) => Some(bytes),
+                    None => {
+                        read_ancient(txn.as_ref(), ancients.as_deref(), hash, AncientStore::body)?
+                    }
+                };
+                let block_body_opt = block_body_opt
                     .map(|bytes| BlockBodyRLP::from_bytes(bytes).to())
                     .transpose()
                     .map_err(StoreError::from)?;
@@ -605,6 +681,7 @@ impl Store {
         hashes: Vec<BlockHash>,
     ) -> Result<Vec<BlockBody>, StoreError> {
         let backend = self.backend.clone();
+        let ancients = self.ancients.clone();
         // TODO: Implement read bulk
         tokio::task::spawn_blocking(move || {
             let txn = backend.begin_read()?;
@@ -612,8 +689,13 @@ impl Store {
             for hash in hashes {
                 let hash_key = hash.encode_to_vec();
 
-                let Some(block_body) = txn
-                    .get(BODIES, &hash_key)?
+                let block_body = match txn.get(BODIES, &hash_key)? {
+                    Some(bytes) => Some(bytes),
+                    None => {
+                        read_ancient(txn.as_ref(), ancients.as_deref(), hash, AncientStore::body)?
+                    }
+                };
+                let Some(block_body) = block_body
                     .map(|bytes| BlockBodyRLP::from_bytes(bytes).to())
                     .transpose()
                     .map_err(StoreError::from)?
@@ -635,11 +717,25 @@ impl Store {
         &self,
         block_hash: BlockHash,
     ) -> Result<Option<BlockBody>, StoreError> {
-        self.read_async(BODIES, block_hash.encode_to_vec())
-            .await?
-            .map(|bytes| BlockBodyRLP::from_bytes(bytes).to())
-            .transpose()
-            .map_err(StoreError::from)
+        let backend = self.backend.clone();
+        let ancients = self.ancients.clone();
+        tokio::task::spawn_blocking(move || {
+            let txn = backend.begin_read()?;
+            let body = match txn.get(BODIES, &block_hash.encode_to_vec())? {
+                Some(bytes) => Some(bytes),
+                None => read_ancient(
+                    txn.as_ref(),
+                    ancients.as_deref(),
+                    block_hash,
+                    AncientStore::body,
+                )?,
+            };
+            body.map(|bytes| BlockBodyRLP::from_bytes(bytes).to())
+                .transpose()
+                .map_err(StoreError::from)
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
     }
 
     pub fn get_block_header_by_hash(
@@ -815,11 +911,38 @@ impl Store {
         index: Index,
     ) -> Result<Option<Receipt>, StoreError> {
         let key = receipt_key(&block_hash, index);
-        self.read_async(RECEIPTS_V2, key)
+        if let Some(bytes) = self.read_async(RECEIPTS_V2, key).await? {
+            return Ok(Some(Receipt::decode(bytes.as_slice())?));
+        }
+        let index: usize = index.try_into()?;
+        let mut receipts = self
+            .get_ancient_receipts(block_hash)
             .await?
-            .map(|bytes| Receipt::decode(bytes.as_slice()))
+            .unwrap_or_default();
+        Ok((index < receipts.len()).then(|| receipts.swap_remove(index)))
+    }
+
+    /// Reads the receipts of `block_hash` from the ancient store, if the block was moved there.
+    async fn get_ancient_receipts(
+        &self,
+        block_hash: BlockHash,
+    ) -> Result<Option<Vec<Receipt>>, StoreError> {
+        let backend = self.backend.clone();
+        let ancients = self.ancients.clone();
+        tokio::task::spawn_blocking(move || {
+            let txn = backend.begin_read()?;
+            read_ancient(
+                txn.as_ref(),
+                ancients.as_deref(),
+                block_hash,
+                AncientStore::receipts,
+            )?
+            .map(|bytes| Vec::<Receipt>::decode(&bytes))
             .transpose()
             .map_err(StoreError::from)
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
     }
 
     /// Get account code by its hash.
@@ -1093,6 +1216,99 @@ impl Store {
             .ok_or(StoreError::MissingEarliestBlockNumber)?
     }
 
+    /// Obtain the first block whose history (body, receipts and transaction locations)
+    /// is kept. The history of the blocks below it, except genesis, was pruned according
+    /// to the [`HistoryRetention`] policy.
+    pub async fn get_history_cutoff(&self) -> Result<BlockNumber, StoreError> {
+        let key = chain_data_key(ChainDataIndex::HistoryCutoff);
+        self.read_async(CHAIN_DATA, key)
+            .await?
+            .map(|bytes| -> Result<BlockNumber, StoreError> {
+                let array: [u8; 8] = bytes
+                    .try_into()
+                    .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+                Ok(BlockNumber::from_le_bytes(array))
+            })
+            .unwrap_or(Ok(0))
+    }
+
+    /// Obtain the number of blocks whose bodies and receipts were moved to the ancient
+    /// store, i.e. the number of the next block to be moved.
+    pub fn get_ancient_block_count(&self) -> BlockNumber {
+        self.ancients
+            .as_ref()
+            .map_or(0, |ancients| ancients.frozen())
+    }
+
+    /// Obtain the next block whose body and receipts must be downloaded by the history
+    /// backfill that follows snap sync, or `None` if there's no history left to backfill.
+    pub async fn get_backfill_block(&self) -> Result<Option<BlockNumber>, StoreError> {
+        let key = chain_data_key(ChainDataIndex::BackfillBlock);
+        self.read_async(CHAIN_DATA, key)
+            .await?
+            .map(|bytes| -> Result<BlockNumber, StoreError> {
+                let array: [u8; 8] = bytes
+                    .try_into()
+                    .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+                Ok(BlockNumber::from_le_bytes(array))
+            })
+            .transpose()
+    }
+
+    /// Sets the next block whose history must be backfilled, `None` marks the backfill
+    /// as done.
+    pub async fn set_backfill_block(
+        &self,
+        block_number: Option<BlockNumber>,
+    ) -> Result<(), StoreError> {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut txn = backend.begin_write()?;
+            put_backfill_block(txn.as_mut(), block_number)?;
+            txn.commit()
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    /// Stores the bodies, receipts and transaction locations of backfilled canonical
+    /// blocks, and moves the backfill cursor to `next_block` in the same write.
+    pub async fn add_backfilled_blocks(
+        &self,
+        blocks: Vec<(BlockNumber, BlockHash, BlockBody, Vec<Receipt>)>,
+        next_block: Option<BlockNumber>,
+    ) -> Result<(), StoreError> {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut txn = backend.begin_write()?;
+            for (block_number, block_hash, body, receipts) in blocks {
+                for (index, transaction) in body.transactions.iter().enumerate() {
+                    txn.merge(
+                        TRANSACTION_LOCATIONS,
+                        transaction.hash().as_bytes(),
+                        &encode_tx_location_operand(block_number, block_hash, index as u64),
+                    )?;
+                }
+                txn.put(
+                    BODIES,
+                    &block_hash.encode_to_vec(),
+                    BlockBodyRLP::from(body).bytes(),
+                )?;
+                for (index, receipt) in receipts.into_iter().enumerate() {
+                    txn.put(
+                        RECEIPTS_V2,
+                        &receipt_key(&block_hash, index as u64),
+                        &receipt.encode_to_vec(),
+                    )?;
+                }
+            }
+            put_backfill_block(txn.as_mut(), next_block)?;
+            txn.commit()
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
     /// Obtain finalized block number
     pub async fn get_finalized_block_number(&self) -> Result<Option<BlockNumber>, StoreError> {
         let key = chain_data_key(ChainDataIndex::FinalizedBlockNumber);
@@ -1206,7 +1422,15 @@ impl Store {
             txn.commit()
         })
         .await
-        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))??;
+
+        if finalized.is_some()
+            && let Some(worker_tx) = &self.history_worker_tx
+        {
+            // A full channel means the worker already has a pending wake-up.
+            let _ = worker_tx.try_send(());
+        }
+        Ok(())
     }
 
     pub async fn get_receipts_for_block(
@@ -1232,6 +1456,7 @@ impl Store {
         max_count: Option<usize>,
     ) -> Result<Vec<Receipt>, StoreError> {
         let backend = self.backend.clone();
+        let ancients = self.ancients.clone();
         let block_hash = *block_hash;
 
         tokio::task::spawn_blocking(move || {
@@ -1258,6 +1483,20 @@ impl Store {
                     break;
                 }
             }
+            if receipts.is_empty()
+                && let Some(bytes) = read_ancient(
+                    txn.as_ref(),
+                    ancients.as_deref(),
+                    block_hash,
+                    AncientStore::receipts,
+                )?
+            {
+                receipts = Vec::<Receipt>::decode(&bytes)?
+                    .into_iter()
+                    .skip(start_index.try_into()?)
+                    .take(max_count.unwrap_or(usize::MAX))
+                    .collect();
+            }
             Ok(receipts)
         })
         .await
@@ -1522,6 +1761,45 @@ impl Store {
         self.backend.clear_table(FULLSYNC_HEADERS)
     }
 
+    /// Replaces the persisted peer table with `peers`, a list of encoded entries keyed
+    /// by node id, in a single write.
+    pub async fn store_peers(&self, peers: Vec<(H256, Vec<u8>)>) -> Result<(), StoreError> {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || {
+            let stale_keys = {
+                let read_txn = backend.begin_read()?;
+                read_txn
+                    .prefix_iterator(PEERS, &[])?
+                    .map(|item| item.map(|(key, _)| key.to_vec()))
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            let mut txn = backend.begin_write()?;
+            for key in stale_keys {
+                txn.delete(PEERS, &key)?;
+            }
+            for (node_id, entry) in peers {
+                txn.put(PEERS, node_id.as_bytes(), &entry)?;
+            }
+            txn.commit()
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    /// Returns the encoded entries of the persisted peer table.
+    pub async fn get_peers(&self) -> Result<Vec<Vec<u8>>, StoreError> {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || {
+            let read_txn = backend.begin_read()?;
+            read_txn
+                .prefix_iterator(PEERS, &[])?
+                .map(|item| item.map(|(_, value)| value.to_vec()))
+                .collect()
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
     /// Delete a key from a table
     pub fn delete(&self, table: &'static str, key: Vec<u8>) -> Result<(), StoreError> {
         let mut txn = self.backend.begin_write()?;
@@ -1546,6 +1824,21 @@ impl Store {
             )?
             .map(|header| header.state_root)
             .unwrap_or_default();
+        if self.archive {
+            let cache = self
+                .trie_cache
+                .read()
+                .map_err(|_| StoreError::LockError)?
+                .clone();
+            let read_view = self.backend.begin_read()?;
+            if self
+                .history_version(parent_state_root, &cache, read_view.as_ref())?
+                .is_some()
+            {
+                // The new layer would be written to disk on top of a newer state.
+                return Err(StoreError::ArchivedStateRoot(parent_state_root));
+            }
+        }
         let last_state_root = update_batch
             .blocks
             .last()
@@ -1640,10 +1933,11 @@ impl Store {
     pub fn new_with_config(
         path: impl AsRef<Path>,
         engine_type: EngineType,
-        // `config` only feeds the RocksDB backend; without that feature it is unused.
-        #[cfg_attr(not(feature = "rocksdb"), allow(unused_variables))] config: StoreConfig,
+        config: StoreConfig,
     ) -> Result<Self, StoreError> {
         let db_path = path.as_ref().to_path_buf();
+        let archive = config.gc_mode == GcMode::Archive;
+        let history_retention = config.history_retention;
 
         if engine_type != EngineType::InMemory {
             let version = read_store_schema_version(&db_path)?;
@@ -1685,7 +1979,15 @@ impl Store {
                     crate::migrations::run_pending_migrations(rocksdb.as_ref(), &db_path, v)?;
                     rocksdb.drop_obsolete_cfs(&path);
                     let backend: Arc<dyn crate::api::StorageBackend> = rocksdb;
-                    return Self::from_backend(backend, db_path, DB_COMMIT_THRESHOLD);
+                    let ancients = open_ancients(&config, &db_path)?;
+                    return Self::from_backend(
+                        backend,
+                        db_path,
+                        DB_COMMIT_THRESHOLD,
+                        archive,
+                        history_retention,
+                        Some(ancients),
+                    );
                 }
                 Some(_) => {
                     // version == STORE_SCHEMA_VERSION, proceed normally.
@@ -1702,11 +2004,26 @@ impl Store {
                 let rocksdb = RocksDBBackend::open(&path, config.rocksdb_block_cache_size)?;
                 rocksdb.drop_obsolete_cfs(&path);
                 let backend: Arc<dyn StorageBackend> = Arc::new(rocksdb);
-                Self::from_backend(backend, db_path, DB_COMMIT_THRESHOLD)
+                let ancients = open_ancients(&config, &db_path)?;
+                Self::from_backend(
+                    backend,
+                    db_path,
+                    DB_COMMIT_THRESHOLD,
+                    archive,
+                    history_retention,
+                    Some(ancients),
+                )
             }
             EngineType::InMemory => {
                 let backend = Arc::new(InMemoryBackend::open()?);
-                Self::from_backend(backend, db_path, IN_MEMORY_COMMIT_THRESHOLD)
+                Self::from_backend(
+                    backend,
+                    db_path,
+                    IN_MEMORY_COMMIT_THRESHOLD,
+                    archive,
+                    history_retention,
+                    None,
+                )
             }
         }
     }
@@ -1715,6 +2032,9 @@ impl Store {
         backend: Arc<dyn StorageBackend>,
         db_path: PathBuf,
         commit_threshold: usize,
+        archive: bool,
+        history_retention: HistoryRetention,
+        ancients: Option<Arc<AncientStore>>,
     ) -> Result<Self, StoreError> {
         debug!("Initializing Store with {commit_threshold} in-memory diff-layers");
         let (fkv_tx, fkv_rx) = std::sync::mpsc::sync_channel(0);
@@ -1731,6 +2051,13 @@ impl Store {
                 last_written
             }
         };
+        if !archive && read_archive_version(backend.begin_read()?.as_ref())?.is_some() {
+            // Writing without history would leave the archived state inconsistent.
+            return Err(StoreError::Custom(
+                "The database keeps archive state history, it must be opened with `--gcmode archive`"
+                    .to_string(),
+            ));
+        }
         let mut background_threads = Vec::new();
         let mut store = Self {
             db_path,
@@ -1744,6 +2071,10 @@ impl Store {
             account_code_cache: Arc::new(Mutex::new(CodeCache::default())),
             code_metadata_cache: Arc::new(Mutex::new(rustc_hash::FxHashMap::default())),
             fcu_lock: Arc::new(tokio::sync::Mutex::new(())),
+            head_updates: Arc::new(tokio::sync::watch::channel(BlockHash::zero()).0),
+            archive,
+            ancients: ancients.clone(),
+            history_worker_tx: None,
             background_threads: Default::default(),
         };
         let backend_clone = store.backend.clone();
@@ -1799,6 +2130,7 @@ impl Store {
                             &flatkeyvalue_control_tx,
                             &trie_cache,
                             trie_update,
+                            archive,
                         )
                         .inspect_err(|err| error!("apply_trie_updates failed: {err}"));
                     }
@@ -1813,6 +2145,46 @@ impl Store {
                 }
             }
         }));
+        if ancients.is_some() || history_retention != HistoryRetention::All {
+            let (worker_tx, worker_rx) = sync_channel(1);
+            let backend = store.backend.clone();
+            background_threads.push(std::thread::spawn(move || {
+                // Woken up on startup and whenever the finalized block changes.
+                while worker_rx.recv().is_ok() {
+                    // Prune first, so expired history isn't moved to the ancient store
+                    loop {
+                        match prune_history(
+                            backend.as_ref(),
+                            ancients.as_deref(),
+                            history_retention,
+                        ) {
+                            Ok(pruned) if pruned == HISTORY_PRUNE_BATCH_SIZE => {}
+                            Ok(_) => break,
+                            Err(err) => {
+                                error!("Failed to prune block history: {err}");
+                                break;
+                            }
+                        }
+                    }
+                    let Some(ancients) = &ancients else {
+                        continue;
+                    };
+                    loop {
+                        match freeze_ancient_blocks(backend.as_ref(), ancients) {
+                            Ok(moved) if moved == ANCIENT_BATCH_SIZE => {}
+                            Ok(_) => break,
+                            Err(err) => {
+                                error!("Failed to move blocks to the ancient store: {err}");
+                                break;
+                            }
+                        }
+                    }
+                }
+                debug!("Closing block history worker.");
+            }));
+            let _ = worker_tx.try_send(());
+            store.history_worker_tx = Some(worker_tx);
+        }
         store.background_threads = Arc::new(ThreadList {
             list: background_threads,
         });
@@ -2390,6 +2762,7 @@ impl Store {
             let latest_block_header = self
                 .load_block_header(number)?
                 .ok_or_else(|| StoreError::MissingLatestBlockNumber)?;
+            self.head_updates.send_replace(latest_block_header.hash());
             self.latest_block_header.update(latest_block_header);
         }
 
@@ -2440,6 +2813,7 @@ impl Store {
         let latest_block_header = self
             .load_block_header(number)?
             .ok_or_else(|| StoreError::Custom("latest block header is missing".to_string()))?;
+        self.head_updates.send_replace(latest_block_header.hash());
         self.latest_block_header.update(latest_block_header);
         Ok(())
     }
@@ -2472,7 +2846,11 @@ impl Store {
             .map_err(|_| StoreError::LockError)?
             .clone();
         let last_written = self.last_written()?;
-        let use_fkv = Self::flatkeyvalue_computed_with_last_written(account_hash, &last_written);
+        // Flat key-values only hold the latest state
+        let use_fkv = Self::flatkeyvalue_computed_with_last_written(account_hash, &last_written)
+            && self
+                .history_version(state_root, &cache, read_view.as_ref())?
+                .is_none();
 
         let storage_root = if use_fkv {
             // We will use FKVs, we don't need the root
@@ -2527,8 +2905,13 @@ impl Store {
         // When FKV is active the real storage root is in the flatkeyvalue store,
         // not in the account's RLP-encoded storage_root field. Use EMPTY_TRIE_HASH
         // so open_storage_trie_shared falls through to the FKV path.
+        // Archived state is never read from the flatkeyvalue store.
         let storage_root =
-            if Self::flatkeyvalue_computed_with_last_written(account_hash, &last_written) {
+            if Self::flatkeyvalue_computed_with_last_written(account_hash, &last_written)
+                && self
+                    .history_version(state_root, &cache, read_view.as_ref())?
+                    .is_none()
+            {
                 *EMPTY_TRIE_HASH
             } else {
                 storage_root
@@ -2598,10 +2981,19 @@ impl Store {
             self.latest_block_header.update((*previous_head).clone());
             return Err(err);
         }
+        self.head_updates.send_replace(head_hash);
 
         Ok(())
     }
 
+    /// Returns a receiver holding the hash of the canonical head, which changes on every
+    /// successful [`Store::forkchoice_update`] whoever made it (engine API, full sync or
+    /// an L2 block producer). Intermediate heads may be skipped when updates come faster
+    /// than the receiver reads them.
+    pub fn subscribe_head_updates(&self) -> tokio::sync::watch::Receiver<BlockHash> {
+        self.head_updates.subscribe()
+    }
+
     /// Obtain the storage trie for the given block
     pub fn state_trie(&self, block_hash: BlockHash) -> Result<Option<Trie>, StoreError> {
         let Some(header) = self.get_block_header_by_hash(block_hash)? else {
@@ -2882,18 +3274,22 @@ impl Store {
     /// Doesn't check if the state root is valid
     /// Used for internal store operations
     pub fn open_state_trie(&self, state_root: H256) -> Result<Trie, StoreError> {
-        let trie_db = TrieWrapper::new(
-            state_root,
-            self.trie_cache
-                .read()
-                .map_err(|_| StoreError::LockError)?
-                .clone(),
-            Box::new(BackendTrieDB::new_for_accounts(
-                self.backend.clone(),
-                self.last_written()?,
-            )?),
-            None,
-        );
+        let cache = self
+            .trie_cache
+            .read()
+            .map_err(|_| StoreError::LockError)?
+            .clone();
+        let read_view = self.backend.begin_read()?;
+        let db: Box<dyn TrieDB> =
+            match self.history_version(state_root, &cache, read_view.as_ref())? {
+                Some(version) => Box::new(HistoricalTrieDB::new(read_view, version)),
+                None => Box::new(BackendTrieDB::new_for_accounts_with_view(
+                    self.backend.clone(),
+                    read_view,
+                    self.last_written()?,
+                )?),
+            };
+        let trie_db = TrieWrapper::new(state_root, cache, db, None);
         Ok(Trie::open(Box::new(trie_db), state_root))
     }
 
@@ -2937,18 +3333,22 @@ impl Store {
         state_root: H256,
         storage_root: H256,
     ) -> Result<Trie, StoreError> {
-        let trie_db = TrieWrapper::new(
-            state_root,
-            self.trie_cache
-                .read()
-                .map_err(|_| StoreError::LockError)?
-                .clone(),
-            Box::new(BackendTrieDB::new_for_storages(
-                self.backend.clone(),
-                self.last_written()?,
-            )?),
-            Some(account_hash),
-        );
+        let cache = self
+            .trie_cache
+            .read()
+            .map_err(|_| StoreError::LockError)?
+            .clone();
+        let read_view = self.backend.begin_read()?;
+        let db: Box<dyn TrieDB> =
+            match self.history_version(state_root, &cache, read_view.as_ref())? {
+                Some(version) => Box::new(HistoricalTrieDB::new(read_view, version)),
+                None => Box::new(BackendTrieDB::new_for_storages_with_view(
+                    self.backend.clone(),
+                    read_view,
+                    self.last_written()?,
+                )?),
+            };
+        let trie_db = TrieWrapper::new(state_root, cache, db, Some(account_hash));
         Ok(Trie::open(Box::new(trie_db), storage_root))
     }
 
@@ -2962,16 +3362,16 @@ impl Store {
         cache: Arc<TrieLayerCache>,
         last_written: Vec<u8>,
     ) -> Result<Trie, StoreError> {
-        let trie_db = TrieWrapper::new(
-            state_root,
-            cache,
-            Box::new(BackendTrieDB::new_for_accounts_with_view(
-                self.backend.clone(),
-                read_view,
-                last_written,
-            )?),
-            None,
-        );
+        let db: Box<dyn TrieDB> =
+            match self.history_version(state_root, &cache, read_view.as_ref())? {
+                Some(version) => Box::new(HistoricalTrieDB::new(read_view, version)),
+                None => Box::new(BackendTrieDB::new_for_accounts_with_view(
+                    self.backend.clone(),
+                    read_view,
+                    last_written,
+                )?),
+            };
+        let trie_db = TrieWrapper::new(state_root, cache, db, None);
         Ok(Trie::open(Box::new(trie_db), state_root))
     }
 
@@ -2985,16 +3385,16 @@ impl Store {
         cache: Arc<TrieLayerCache>,
         last_written: Vec<u8>,
     ) -> Result<Trie, StoreError> {
-        let trie_db = TrieWrapper::new(
-            state_root,
-            cache,
-            Box::new(BackendTrieDB::new_for_storages_with_view(
-                self.backend.clone(),
-                read_view,
-                last_written,
-            )?),
-            Some(account_hash),
-        );
+        let db: Box<dyn TrieDB> =
+            match self.history_version(state_root, &cache, read_view.as_ref())? {
+                Some(version) => Box::new(HistoricalTrieDB::new(read_view, version)),
+                None => Box::new(BackendTrieDB::new_for_storages_with_view(
+                    self.backend.clone(),
+                    read_view,
+                    last_written,
+                )?),
+            };
+        let trie_db = TrieWrapper::new(state_root, cache, db, Some(account_hash));
         Ok(Trie::open(Box::new(trie_db), storage_root))
     }
 
@@ -3054,6 +3454,33 @@ impl Store {
         Ok(state_root == root_hash)
     }
 
+    /// Returns whether the store keeps historical state (`--gcmode archive`).
+    pub fn is_archive(&self) -> bool {
+        self.archive
+    }
+
+    /// Returns the archived version `state_root` must be read at, or `None` if it is
+    /// served by the in-memory diff-layers and the current disk layer.
+    fn history_version(
+        &self,
+        state_root: H256,
+        cache: &TrieLayerCache,
+        read_view: &dyn StorageReadView,
+    ) -> Result<Option<u64>, StoreError> {
+        if !self.archive || cache.contains(state_root) {
+            return Ok(None);
+        }
+        let Some(version) = read_view.get(STATE_ROOT_VERSIONS, state_root.as_bytes())? else {
+            return Ok(None);
+        };
+        let version = decode_version(&version)?;
+        if read_archive_version(read_view)? == Some(version) {
+            // This is the disk layer
+            return Ok(None);
+        }
+        Ok(Some(version))
+    }
+
     /// Takes a block hash and returns an iterator to its ancestors. Block headers are returned
     /// in reverse order, starting from the given block and going up to the genesis block.
     pub fn ancestors(&self, block_hash: BlockHash) -> AncestorIterator {
@@ -3190,6 +3617,7 @@ fn apply_trie_updates(
     fkv_ctl: &SyncSender<FKVGeneratorControlMessage>,
     trie_cache: &Arc<RwLock<Arc<TrieLayerCache>>>,
     trie_update: TrieUpdate,
+    archive: bool,
 ) -> Result<(), StoreError> {
     let TrieUpdate {
         result_sender,
@@ -3252,9 +3680,18 @@ fn apply_trie_updates(
     // the account address (32 bytes) + storage path (up to 32 bytes).
 
     // Commit removes the bottom layer and returns it, this is the mutation step.
-    let nodes = trie_mut.commit(root).unwrap_or_default();
     let mut result = Ok(());
+    let nodes = if archive {
+        let layers = trie_mut.commit_layers(root).unwrap_or_default();
+        result = write_trie_history(backend, write_tx.as_mut(), &layers);
+        layers.into_iter().flat_map(|layer| layer.nodes).collect()
+    } else {
+        trie_mut.commit(root).unwrap_or_default()
+    };
     for (key, value) in nodes {
+        if result.is_err() {
+            break;
+        }
         let is_leaf = key.len() == 65 || key.len() == 131;
         let is_account = key.len() <= 65;
 
@@ -3292,6 +3729,81 @@ fn apply_trie_updates(
     Ok(())
 }
 
+/// Key in `MISC_VALUES` holding the version of the disk layer in archive mode.
+const ARCHIVE_VERSION_KEY: &[u8] = b"archive_version";
+
+fn decode_version(bytes: &[u8]) -> Result<u64, StoreError> {
+    let bytes: [u8; 8] = bytes
+        .try_into()
+        .map_err(|_| StoreError::Custom("Invalid archive version bytes".to_string()))?;
+    Ok(u64::from_be_bytes(bytes))
+}
+
+/// Returns the version of the disk layer, or `None` if no history was ever written.
+fn read_archive_version(read_view: &dyn StorageReadView) -> Result<Option<u64>, StoreError> {
+    read_view
+        .get(MISC_VALUES, ARCHIVE_VERSION_KEY)?
+        .map(|bytes| decode_version(&bytes))
+        .transpose()
+}
+
+/// Records, for each layer about to be written to disk (oldest first), the previous value
+/// of every trie node it modifies, and the version each layer's state root maps to.
+fn write_trie_history(
+    backend: &dyn StorageBackend,
+    write_tx: &mut dyn StorageWriteBatch,
+    layers: &[CommittedLayer],
+) -> Result<(), StoreError> {
+    let Some(oldest) = layers.first() else {
+        return Ok(());
+    };
+    let read_view = backend.begin_read()?;
+    let mut version = read_archive_version(read_view.as_ref())?.unwrap_or_default();
+    if read_view
+        .get(STATE_ROOT_VERSIONS, oldest.parent.as_bytes())?
+        .is_none()
+    {
+        // First layer written in archive mode, the disk layer becomes the base version
+        write_tx.put(
+            STATE_ROOT_VERSIONS,
+            oldest.parent.as_bytes(),
+            &version.to_be_bytes(),
+        )?;
+    }
+    // Values written by the layers processed so far, which aren't visible on disk yet
+    let mut pending: rustc_hash::FxHashMap<&[u8], &[u8]> = Default::default();
+    for layer in layers {
+        version += 1;
+        for key in layer.nodes.keys() {
+            if key.len() == 65 || key.len() == 131 {
+                // Leaves are resolved through the trie nodes
+                continue;
+            }
+            let previous = match pending.get(key.as_slice()) {
+                Some(previous) => previous.to_vec(),
+                None => {
+                    let table = if key.len() < 65 {
+                        ACCOUNT_TRIE_NODES
+                    } else {
+                        STORAGE_TRIE_NODES
+                    };
+                    read_view.get(table, key)?.unwrap_or_default()
+                }
+            };
+            write_tx.put(TRIE_NODES_HISTORY, &history_key(key, version), &previous)?;
+        }
+        for (key, value) in &layer.nodes {
+            pending.insert(key, value);
+        }
+        write_tx.put(
+            STATE_ROOT_VERSIONS,
+            layer.state_root.as_bytes(),
+            &version.to_be_bytes(),
+        )?;
+    }
+    write_tx.put(MISC_VALUES, ARCHIVE_VERSION_KEY, &version.to_be_bytes())
+}
+
 // NOTE: we don't receive `Store` here to avoid cyclic dependencies
 // with the other end of `control_rx`
 fn flatkeyvalue_generator(
@@ -3510,6 +4022,275 @@ pub fn hash_key_fixed(key: &H256) -> [u8; 32] {
     keccak_hash(key.to_fixed_bytes())
 }
 
+/// Opens the ancient store configured in `config`, defaulting to `<db_path>/ancient`.
+fn open_ancients(config: &StoreConfig, db_path: &Path) -> Result<Arc<AncientStore>, StoreError> {
+    let dir = config
+        .ancient_dir
+        .clone()
+        .unwrap_or_else(|| db_path.join("ancient"));
+    Ok(Arc::new(AncientStore::open(&dir)?))
+}
+
+/// Reads an item of `block_hash` from the ancient store, if the block was moved there.
+fn read_ancient(
+    read_view: &dyn StorageReadView,
+    ancients: Option<&AncientStore>,
+    block_hash: BlockHash,
+    item: fn(&AncientStore, BlockNumber) -> Result<Option<Vec<u8>>, StoreError>,
+) -> Result<Option<Vec<u8>>, StoreError> {
+    let Some(ancients) = ancients else {
+        return Ok(None);
+    };
+    let Some(number) = read_view.get(BLOCK_NUMBERS, &block_hash.encode_to_vec())? else {
+        return Ok(None);
+    };
+    let number: [u8; 8] = number
+        .try_into()
+        .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+    let number = BlockNumber::from_le_bytes(number);
+    // The ancient store is append-only, so pruned history is only hidden
+    if number != 0 && number < read_history_cutoff(read_view)? {
+        return Ok(None);
+    }
+    if ancients.hash(number)? != Some(block_hash) {
+        return Ok(None);
+    }
+    item(ancients, number)
+}
+
+/// Reads the [`ChainDataIndex::HistoryCutoff`], see [`Store::get_history_cutoff`].
+fn read_history_cutoff(read_view: &dyn StorageReadView) -> Result<BlockNumber, StoreError> {
+    read_view
+        .get(CHAIN_DATA, &chain_data_key(ChainDataIndex::HistoryCutoff))?
+        .map(|bytes| -> Result<BlockNumber, StoreError> {
+            let array: [u8; 8] = bytes
+                .try_into()
+                .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+            Ok(BlockNumber::from_le_bytes(array))
+        })
+        .unwrap_or(Ok(0))
+}
+
+/// Moves the bodies and receipts of up to [`ANCIENT_BATCH_SIZE`] canonical blocks
+/// finalized more than [`ANCIENT_THRESHOLD`] blocks ago to the ancient store, and
+/// returns how many blocks were moved. Nothing is moved while the next block to move
+/// still awaits the history backfill that follows snap sync.
+// NOTE: we don't receive `Store` here, since this runs on a thread owned by it.
+fn freeze_ancient_blocks(
+    backend: &dyn StorageBackend,
+    ancients: &AncientStore,
+) -> Result<u64, StoreError> {
+    let read_view = backend.begin_read()?;
+    let finalized_key = chain_data_key(ChainDataIndex::FinalizedBlockNumber);
+    let Some(finalized) = read_view.get(CHAIN_DATA, &finalized_key)? else {
+        return Ok(0);
+    };
+    let finalized: [u8; 8] = finalized
+        .try_into()
+        .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+    let first = ancients.frozen();
+    // The history of the blocks up to the backfill cursor hasn't been downloaded yet, and
+    // as the ancient store is append-only they must wait for the backfill to be frozen
+    let backfill_key = chain_data_key(ChainDataIndex::BackfillBlock);
+    if let Some(backfill_block) = read_view.get(CHAIN_DATA, &backfill_key)? {
+        let backfill_block: [u8; 8] = backfill_block
+            .try_into()
+            .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+        if first <= BlockNumber::from_le_bytes(backfill_block) {
+            return Ok(0);
+        }
+    }
+    let end = BlockNumber::from_le_bytes(finalized)
+        .saturating_sub(ANCIENT_THRESHOLD)
+        .min(first + ANCIENT_BATCH_SIZE);
+
+    let mut blocks = Vec::new();
+    let mut receipt_counts = Vec::new();
+    for number in first..end {
+        let Some(hash) = read_view
+            .get(CANONICAL_BLOCK_HASHES, &number.to_le_bytes())?
+            .map(|bytes| H256::decode(&bytes))
+            .transpose()?
+        else {
+            break;
+        };
+        let body = read_view.get(BODIES, &hash.encode_to_vec())?;
+        let mut receipts = Vec::new();
+        for result in read_view.prefix_iterator(RECEIPTS_V2, hash.as_bytes())? {
+            let (key, value) = result?;
+            if !key.starts_with(hash.as_bytes()) {
+                break;
+            }
+            if key.len() != 40 {
+                continue;
+            }
+            receipts.push(Receipt::decode(&value)?);
+        }
+        receipt_counts.push(receipts.len() as u64);
+        blocks.push(AncientBlock {
+            hash,
+            body,
+            receipts: (!receipts.is_empty()).then(|| receipts.encode_to_vec()),
+        });
+    }
+    if blocks.is_empty() {
+        return Ok(0);
+    }
+
+    ancients.append(&blocks)?;
+    // If we crash before this commit, the moved data is left behind in the database,
+    // where it's still served from.
+    let mut write_tx = backend.begin_write()?;
+    for (block, receipt_count) in blocks.iter().zip(receipt_counts) {
+        write_tx.delete(BODIES, &block.hash.encode_to_vec())?;
+        for index in 0..receipt_count {
+            write_tx.delete(RECEIPTS_V2, &receipt_key(&block.hash, index))?;
+        }
+    }
+    write_tx.commit()?;
+    debug!(
+        "Moved blocks {first}..{} to the ancient store",
+        first + blocks.len() as u64
+    );
+    Ok(blocks.len() as u64)
+}
+
+/// Deletes the bodies, receipts and transaction locations of up to
+/// [`HISTORY_PRUNE_BATCH_SIZE`] finalized blocks below the cutoff of `retention`, and
+/// returns how many blocks were pruned.
+// NOTE: we don't receive `Store` here, since this runs on a thread owned by it.
+fn prune_history(
+    backend: &dyn StorageBackend,
+    ancients: Option<&AncientStore>,
+    retention: HistoryRetention,
+) -> Result<u64, StoreError> {
+    let read_view = backend.begin_read()?;
+    let read_number = |index: ChainDataIndex| -> Result<Option<BlockNumber>, StoreError> {
+        read_view
+            .get(CHAIN_DATA, &chain_data_key(index))?
+            .map(|bytes| {
+                let array: [u8; 8] = bytes
+                    .try_into()
+                    .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+                Ok(BlockNumber::from_le_bytes(array))
+            })
+            .transpose()
+    };
+    let Some(finalized) = read_number(ChainDataIndex::FinalizedBlockNumber)? else {
+        return Ok(0);
+    };
+    let cutoff = match retention {
+        HistoryRetention::All => return Ok(0),
+        HistoryRetention::PostMerge => {
+            let Some(merge_block) = first_post_merge_block(read_view.as_ref(), finalized)? else {
+                return Ok(0);
+            };
+            merge_block
+        }
+        HistoryRetention::Blocks(blocks) => {
+            let latest = read_number(ChainDataIndex::LatestBlockNumber)?.unwrap_or_default();
+            (latest + 1).saturating_sub(blocks)
+        }
+    };
+    // Genesis is always kept, and blocks that may still be reorged out aren't pruned
+    let first = read_number(ChainDataIndex::HistoryCutoff)?
+        .unwrap_or_default()
+        .max(1);
+    let end = cutoff.min(finalized).min(first + HISTORY_PRUNE_BATCH_SIZE);
+    if first >= end {
+        return Ok(0);
+    }
+
+    let mut write_tx = backend.begin_write()?;
+    for number in first..end {
+        let Some(hash) = read_view
+            .get(CANONICAL_BLOCK_HASHES, &number.to_le_bytes())?
+            .map(|bytes| H256::decode(&bytes))
+            .transpose()?
+        else {
+            continue;
+        };
+        let hash_key = hash.encode_to_vec();
+        // Blocks already moved to the ancient store still need their transaction
+        // locations removed
+        let body = match read_view.get(BODIES, &hash_key)? {
+            Some(body) => Some(body),
+            None => read_ancient(read_view.as_ref(), ancients, hash, AncientStore::body)?,
+        };
+        if let Some(body) = body {
+            for transaction in BlockBody::decode(&body)?.transactions {
+                write_tx.delete(TRANSACTION_LOCATIONS, transaction.hash().as_bytes())?;
+            }
+        }
+        write_tx.delete(BODIES, &hash_key)?;
+        for result in read_view.prefix_iterator(RECEIPTS_V2, hash.as_bytes())? {
+            let (key, _) = result?;
+            if !key.starts_with(hash.as_bytes()) {
+                break;
+            }
+            if key.len() == 40 {
+                write_tx.delete(RECEIPTS_V2, &key)?;
+            }
+        }
+    }
+    write_tx.put(
+        CHAIN_DATA,
+        &chain_data_key(ChainDataIndex::HistoryCutoff),
+        &end.to_le_bytes(),
+    )?;
+    write_tx.commit()?;
+    debug!("Pruned the history of blocks {first}..{end}");
+    Ok(end - first)
+}
+
+/// Returns the number of the first post-merge block, or `None` if the chain didn't merge
+/// by `finalized`. Pre-merge blocks are the ones with a difficulty, so it's the first
+/// canonical header without one, found with a binary search as headers are never pruned.
+fn first_post_merge_block(
+    read_view: &dyn StorageReadView,
+    finalized: BlockNumber,
+) -> Result<Option<BlockNumber>, StoreError> {
+    let is_post_merge = |number: BlockNumber| -> Result<Option<bool>, StoreError> {
+        let Some(hash) = read_view
+            .get(CANONICAL_BLOCK_HASHES, &number.to_le_bytes())?
+            .map(|bytes| H256::decode(&bytes))
+            .transpose()?
+        else {
+            return Ok(None);
+        };
+        let Some(header) = read_view.get(HEADERS, &hash.encode_to_vec())? else {
+            return Ok(None);
+        };
+        Ok(Some(BlockHeader::decode(&header)?.difficulty.is_zero()))
+    };
+    if is_post_merge(finalized)? != Some(true) {
+        return Ok(None);
+    }
+    // Genesis is the terminal block of networks that start after the merge
+    let (mut low, mut high) = (0, finalized);
+    while low < high {
+        let middle = low + (high - low) / 2;
+        match is_post_merge(middle)? {
+            Some(true) => high = middle,
+            Some(false) => low = middle + 1,
+            // Headers are still being synced
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(high))
+}
+
+fn put_backfill_block(
+    txn: &mut dyn StorageWriteBatch,
+    block_number: Option<BlockNumber>,
+) -> Result<(), StoreError> {
+    let key = chain_data_key(ChainDataIndex::BackfillBlock);
+    match block_number {
+        Some(block_number) => txn.put(CHAIN_DATA, &key, &block_number.to_le_bytes()),
+        None => txn.delete(CHAIN_DATA, &key),
+    }
+}
+
 fn chain_data_key(index: ChainDataIndex) -> Vec<u8> {
     (index as u8).encode_to_vec()
 }
@@ -3682,6 +4463,45 @@ pub fn read_chain_id_from_db(path: &Path) -> Option<u64> {
     }
 }
 
+/// Reads from an existing database whether it keeps archive state history, i.e. the
+/// [`GcMode`] it must be opened with. Returns `None` if the database doesn't exist or
+/// can't be read. Always returns `None` when compiled without the `rocksdb` feature.
+pub fn read_gc_mode_from_db(path: &Path) -> Option<GcMode> {
+    if !has_valid_db(path) {
+        return None;
+    }
+    #[cfg(feature = "rocksdb")]
+    {
+        let backend = match RocksDBBackend::open(path, DEFAULT_ROCKSDB_BLOCK_CACHE_SIZE_BYTES) {
+            Ok(backend) => backend,
+            Err(e) => {
+                warn!("Failed to open RocksDB at {path:?} to read the gc mode: {e}");
+                return None;
+            }
+        };
+        let read = match backend.begin_read() {
+            Ok(read) => read,
+            Err(e) => {
+                warn!("Failed to begin read transaction at {path:?}: {e}");
+                return None;
+            }
+        };
+        match read_archive_version(read.as_ref()) {
+            Ok(Some(_)) => Some(GcMode::Archive),
+            Ok(None) => Some(GcMode::Full),
+            Err(e) => {
+                warn!("Failed to read the archive version from database at {path:?}: {e}");
+                None
+            }
+        }
+    }
+    #[cfg(not(feature = "rocksdb"))]
+    {
+        let _ = path;
+        None
+    }
+}
+
 #[cfg(test)]
 mod merge_tests {
     use super::*;
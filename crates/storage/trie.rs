@@ -1,5 +1,6 @@
 use crate::api::tables::{
     ACCOUNT_FLATKEYVALUE, ACCOUNT_TRIE_NODES, STORAGE_FLATKEYVALUE, STORAGE_TRIE_NODES,
+    TRIE_NODES_HISTORY,
 };
 use crate::api::{StorageBackend, StorageLockedView, StorageReadView};
 use crate::error::StoreError;
@@ -211,3 +212,74 @@ impl TrieDB for BackendTrieDBLocked {
         Err(TrieError::DbError(anyhow::anyhow!("trie is read-only")))
     }
 }
+
+/// Returns the [`TRIE_NODES_HISTORY`] key prefix for a (prefixed) trie node path.
+pub(crate) fn history_prefix(path: &[u8]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(path.len() + 9);
+    key.push(path.len() as u8);
+    key.extend_from_slice(path);
+    key
+}
+
+/// Returns the [`TRIE_NODES_HISTORY`] key for a (prefixed) trie node path at `version`.
+pub(crate) fn history_key(path: &[u8], version: u64) -> Vec<u8> {
+    let mut key = history_prefix(path);
+    key.extend_from_slice(&version.to_be_bytes());
+    key
+}
+
+/// Read-only view of the account and storage tries as they were at an archived state
+/// version.
+///
+/// For each node, the first history entry written after `version` holds the node as it
+/// was at `version`; if the node never changed afterwards the current one is used.
+/// Leaves are always resolved by traversing the trie, since the flat key-value tables
+/// only hold the latest state.
+pub struct HistoricalTrieDB {
+    read_view: Arc<dyn StorageReadView>,
+    version: u64,
+}
+
+impl HistoricalTrieDB {
+    pub fn new(read_view: Arc<dyn StorageReadView>, version: u64) -> Self {
+        Self { read_view, version }
+    }
+}
+
+impl TrieDB for HistoricalTrieDB {
+    fn get(&self, key: Nibbles) -> Result<Option<Vec<u8>>, TrieError> {
+        let key = key.as_ref();
+        if key.len() == 65 || key.len() == 131 {
+            // Flat key-value entries aren't versioned
+            return Ok(None);
+        }
+        let prefix = history_prefix(key);
+        let entry = self
+            .read_view
+            .seek(
+                TRIE_NODES_HISTORY,
+                &history_key(key, self.version.saturating_add(1)),
+            )
+            .map_err(|e| {
+                TrieError::DbError(anyhow::anyhow!("Failed to get from database: {}", e))
+            })?;
+        if let Some((history_key, value)) = entry
+            && history_key.len() == prefix.len() + 8
+            && history_key.starts_with(&prefix)
+        {
+            return Ok((!value.is_empty()).then(|| value.into_vec()));
+        }
+        let table = if key.len() < 65 {
+            ACCOUNT_TRIE_NODES
+        } else {
+            STORAGE_TRIE_NODES
+        };
+        self.read_view
+            .get(table, key)
+            .map_err(|e| TrieError::DbError(anyhow::anyhow!("Failed to get from database: {}", e)))
+    }
+
+    fn put_batch(&self, _key_values: Vec<(Nibbles, Vec<u8>)>) -> Result<(), TrieError> {
+        Err(TrieError::DbError(anyhow::anyhow!("trie is read-only")))
+    }
+}
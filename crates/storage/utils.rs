@@ -8,6 +8,10 @@ pub enum ChainDataIndex {
     SafeBlockNumber = 3,
     LatestBlockNumber = 4,
     PendingBlockNumber = 5,
+    // First block whose history wasn't pruned by the history retention policy
+    HistoryCutoff = 6,
+    // Next block whose body and receipts are downloaded by the post snap sync backfill
+    BackfillBlock = 7,
 }
 
 impl From<u8> for ChainDataIndex {
@@ -25,6 +29,8 @@ impl From<u8> for ChainDataIndex {
             x if x == ChainDataIndex::PendingBlockNumber as u8 => {
                 ChainDataIndex::PendingBlockNumber
             }
+            x if x == ChainDataIndex::HistoryCutoff as u8 => ChainDataIndex::HistoryCutoff,
+            x if x == ChainDataIndex::BackfillBlock as u8 => ChainDataIndex::BackfillBlock,
             _ => panic!("Invalid value when casting to ChainDataIndex: {value}"),
         }
     }
@@ -1,7 +1,9 @@
 pub mod db;
+mod simulate;
 mod tracing;
 
 use super::{BlockExecutionResult, TxGasBreakdown};
+use crate::overrides::BlockOverrides;
 use crate::system_contracts::{
     BEACON_ROOTS_ADDRESS, CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, HISTORY_STORAGE_ADDRESS,
     PRAGUE_SYSTEM_CONTRACTS, SYSTEM_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
@@ -55,9 +57,11 @@ use ethrex_levm::db::gen_db::{
 use ethrex_levm::db::{Database, gen_db::CacheDB};
 use ethrex_levm::errors::{InternalError, TxValidationError};
 use ethrex_levm::memory::Memory;
+#[cfg(all(feature = "rayon", not(feature = "eip-8025")))]
+use ethrex_levm::precompiles::PrecompileRegistry;
 #[cfg(feature = "perf_opcode_timings")]
 use ethrex_levm::timings::{OPCODE_TIMINGS, PRECOMPILES_TIMINGS};
-use ethrex_levm::tracing::LevmCallTracer;
+use ethrex_levm::tracing::{LevmCallTracer, Tracer, downcast_tracer};
 use ethrex_levm::utils::get_base_fee_per_blob_gas;
 use ethrex_levm::utils::intrinsic_gas_dimensions;
 use ethrex_levm::vm::VMType;
@@ -87,7 +91,7 @@ use std::sync::mpsc::Sender;
 pub struct LEVM;
 
 /// Checks that adding `tx_gas_limit` to `block_gas_used` doesn't exceed `block_gas_limit`.
-fn check_gas_limit(
+pub fn check_gas_limit(
     block_gas_used: u64,
     tx_gas_limit: u64,
     block_gas_limit: u64,
@@ -203,7 +207,8 @@ impl LEVM {
         // Block-invariant EVM config + chain id + base blob fee, computed once and
         // reused by every tx (mirrors `execute_block_pipeline`): avoids a per-tx
         // chain-config copy, fork/blob-schedule recompute, and `fake_exponential` call.
-        let evm_config = EVMConfig::new_from_chain_config(&chain_config, &block.header);
+        let evm_config = EVMConfig::new_from_chain_config(&chain_config, &block.header)
+            .with_precompiles(db.precompiles);
         let chain_id = chain_config.chain_id;
         let base_blob_fee_per_gas =
             get_base_fee_per_blob_gas(block.header.excess_blob_gas, &evm_config)?;
@@ -400,7 +405,8 @@ impl LEVM {
         let is_amsterdam = chain_config.is_amsterdam_activated(block.header.timestamp);
         // Block-invariant EVM config + chain id, computed once and reused by every tx
         // (avoids a per-tx chain-config dyn-dispatch copy + fork/blob-schedule recompute).
-        let evm_config = EVMConfig::new_from_chain_config(&chain_config, &block.header);
+        let evm_config = EVMConfig::new_from_chain_config(&chain_config, &block.header)
+            .with_precompiles(db.precompiles);
         let chain_id = chain_config.chain_id;
 
         // EIP-7928 BlockAccessIndex invariant — see `execute_block` for rationale.
@@ -1032,7 +1038,8 @@ impl LEVM {
         let is_amsterdam = chain_config.is_amsterdam_activated(header.timestamp);
         // Block-invariant EVM config + chain id, computed once and shared across the
         // parallel workers (both are `Copy` + `Send`/`Sync`).
-        let evm_config = EVMConfig::new_from_chain_config(&chain_config, header);
+        let evm_config = EVMConfig::new_from_chain_config(&chain_config, header)
+            .with_precompiles(db.precompiles);
         let chain_id = chain_config.chain_id;
         // Block-invariant base blob fee, computed once and shared across workers.
         let base_blob_fee_per_gas = get_base_fee_per_blob_gas(header.excess_blob_gas, &evm_config)?;
@@ -2071,12 +2078,14 @@ impl LEVM {
     ///
     /// The `store` parameter should be a `CachingDatabase`-wrapped store so that
     /// parallel workers can benefit from shared caching. The same cache should
-    /// be used by the sequential execution phase.
+    /// be used by the sequential execution phase. `precompiles` must be the custom
+    /// precompiles the block is executed with, so calls to them are warmed the same way.
     #[cfg(all(feature = "rayon", not(feature = "eip-8025")))]
     pub fn warm_block(
         block: &Block,
         store: Arc<dyn Database>,
         vm_type: VMType,
+        precompiles: Option<&'static PrecompileRegistry>,
         crypto: &dyn Crypto,
         cancelled: &AtomicBool,
     ) -> Result<(), EvmError> {
@@ -2098,7 +2107,8 @@ impl LEVM {
         // Block-invariant EVM config + chain id, computed once and shared (by copy)
         // across the parallel warming workers.
         let chain_config = store.get_chain_config()?;
-        let evm_config = EVMConfig::new_from_chain_config(&chain_config, &block.header);
+        let evm_config = EVMConfig::new_from_chain_config(&chain_config, &block.header)
+            .with_precompiles(precompiles);
         let chain_id = chain_config.chain_id;
         // Block-invariant base blob fee, computed once and shared across workers.
         let base_blob_fee_per_gas =
@@ -2250,7 +2260,8 @@ impl LEVM {
         // block-invariant; in a block loop, compute them once and use
         // `setup_env_with_config` instead. This single-tx entry point computes them here.
         let chain_config = db.store.get_chain_config()?;
-        let config = EVMConfig::new_from_chain_config(&chain_config, block_header);
+        let config = EVMConfig::new_from_chain_config(&chain_config, block_header)
+            .with_precompiles(db.precompiles);
         let base_blob_fee_per_gas =
             get_base_fee_per_blob_gas(block_header.excess_blob_gas, &config)?;
         Self::setup_env_with_config(
@@ -2334,6 +2345,31 @@ impl LEVM {
         vm.execute().map_err(VMError::into)
     }
 
+    /// Like [`LEVM::execute_tx`], but with `tracer` installed for the execution.
+    /// The tracer is handed back along with the report so its results can be read.
+    pub fn execute_tx_with_tracer<T: Tracer>(
+        tx: &Transaction,
+        tx_sender: Address,
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+        tracer: T,
+    ) -> Result<(ExecutionReport, T), EvmError> {
+        if !tracer.is_active() {
+            let report = Self::execute_tx(tx, tx_sender, block_header, db, vm_type, crypto)?;
+            return Ok((report, tracer));
+        }
+        let env = Self::setup_env(tx, tx_sender, block_header, db, vm_type)?;
+        let mut vm = VM::new(env, db, tx, tracer, vm_type, crypto)?;
+        let report = vm.execute()?;
+        let tracer = vm
+            .take_tracer()
+            .and_then(downcast_tracer::<T>)
+            .ok_or_else(|| EvmError::Custom("Tracer was not installed".to_string()))?;
+        Ok((report, tracer))
+    }
+
     // Like execute_tx but allows reusing the stack pool. Takes the block-invariant
     // `config`/`chain_id` precomputed once per block (see `setup_env_with_config`).
     #[allow(clippy::too_many_arguments)]
@@ -2393,11 +2429,14 @@ impl LEVM {
         tx: &GenericTransaction,
         // The block header for the current block.
         block_header: &BlockHeader,
+        // Block overrides that can't be expressed in the header, like the blob base fee.
+        block_overrides: &BlockOverrides,
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
         crypto: &dyn Crypto,
     ) -> Result<ExecutionResult, EvmError> {
         let mut env = env_from_generic(tx, block_header, db, vm_type)?;
+        block_overrides.apply_to_env(&mut env);
 
         env.block_gas_limit = i64::MAX as u64; // disable block gas limit
 
@@ -2633,7 +2672,8 @@ pub fn generic_system_contract_levm(
     crypto: &dyn Crypto,
 ) -> Result<ExecutionReport, EvmError> {
     let chain_config = db.store.get_chain_config()?;
-    let config = EVMConfig::new_from_chain_config(&chain_config, block_header);
+    let config = EVMConfig::new_from_chain_config(&chain_config, block_header)
+        .with_precompiles(db.precompiles);
     let env = Environment {
         origin: system_address,
         // EIPs 2935, 4788, 7002 and 7251 dictate that the system calls have a gas limit of 30 million and they do not use intrinsic gas.
@@ -2835,7 +2875,8 @@ fn env_from_generic(
     let gas_price =
         calculate_gas_price_for_generic(tx, header.base_fee_per_gas.unwrap_or(INITIAL_BASE_FEE));
     let block_excess_blob_gas = header.excess_blob_gas;
-    let config = EVMConfig::new_from_chain_config(&chain_config, header);
+    let config =
+        EVMConfig::new_from_chain_config(&chain_config, header).with_precompiles(db.precompiles);
 
     // Validate slot_number for Amsterdam+ blocks
     // For L2 chains, slot_number is always 0
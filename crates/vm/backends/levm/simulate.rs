@@ -0,0 +1,106 @@
+use ethrex_common::{
+    Address, H160, U256,
+    tracing::{CallLog, CallTraceFrame, CallType},
+    types::{BlockHeader, GenericTransaction, Log},
+};
+use ethrex_crypto::Crypto;
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase, tracing::LevmCallTracer, utils::create_eth_transfer_log,
+    vm::VM, vm::VMType,
+};
+
+use crate::{
+    EvmError, ExecutionResult,
+    backends::levm::{
+        LEVM, adjust_disabled_base_fee, adjust_disabled_l2_fees, env_from_generic,
+        generic_tx_to_transaction,
+    },
+    simulate::SimulationOptions,
+};
+
+/// Pseudo-address ETH transfers are attributed to when `traceTransfers` is enabled (ERC-7528).
+const ERC7528_ETH_ADDRESS: Address = H160([0xee; 20]);
+
+impl LEVM {
+    /// Executes a simulated (unsigned) call on top of the state held by `db`.
+    ///
+    /// Unlike [`LEVM::simulate_tx_from_generic`] the changes are kept in `db`, so the next
+    /// call observes them. Without `options.validation` the call follows `eth_call`
+    /// semantics: the block gas limit is lifted and fees are disabled when no gas price is
+    /// given. A missing nonce is always taken from the sender's current state.
+    pub fn simulate_call(
+        tx: &GenericTransaction,
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        options: &SimulationOptions,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<ExecutionResult, EvmError> {
+        let mut env = env_from_generic(tx, block_header, db, vm_type)?;
+        if tx.nonce.is_none() {
+            env.tx_nonce = db.get_account(env.origin)?.info.nonce;
+        }
+        let vm_type = if options.validation {
+            vm_type
+        } else {
+            env.block_gas_limit = i64::MAX as u64; // disable block gas limit
+            adjust_disabled_base_fee(&mut env);
+            adjust_disabled_l2_fees(&env, vm_type)
+        };
+
+        let tracer = if options.trace_transfers {
+            LevmCallTracer::new(false, true)
+        } else {
+            LevmCallTracer::disabled()
+        };
+        let converted_tx = generic_tx_to_transaction(tx)?;
+        let mut vm = VM::new(env, db, &converted_tx, tracer, vm_type, crypto)?;
+        vm.moved_precompiles = options.moved_precompiles.clone();
+
+        let mut result: ExecutionResult = vm.execute()?.into();
+        if options.trace_transfers
+            && let ExecutionResult::Success { logs, .. } = &mut result
+        {
+            let trace = vm.get_trace_result()?;
+            logs.clear();
+            collect_logs_with_transfers(&trace, logs);
+        }
+        Ok(result)
+    }
+}
+
+/// Rebuilds the logs emitted by a successful call, inserting an ERC-7528 `Transfer` log
+/// right before the logs of every frame that moved ETH. Reverted frames contribute no logs.
+fn collect_logs_with_transfers(frame: &CallTraceFrame, logs: &mut Vec<Log>) {
+    if frame.error.is_some() {
+        return;
+    }
+    let moves_value = matches!(
+        frame.call_type,
+        CallType::CALL
+            | CallType::CALLCODE
+            | CallType::CREATE
+            | CallType::CREATE2
+            | CallType::SELFDESTRUCT
+    );
+    if moves_value && frame.value > U256::zero() {
+        let mut transfer = create_eth_transfer_log(frame.from, frame.to, frame.value);
+        transfer.address = ERC7528_ETH_ADDRESS;
+        logs.push(transfer);
+    }
+
+    let to_log = |log: &CallLog| Log {
+        address: log.address,
+        topics: log.topics.clone(),
+        data: log.data.clone(),
+    };
+    // A log's position is the number of sub-calls made by the frame before emitting it.
+    let mut frame_logs = frame.logs.iter().peekable();
+    for (index, call) in frame.calls.iter().enumerate() {
+        while let Some(log) = frame_logs.next_if(|log| log.position <= index as u64) {
+            logs.push(to_log(log));
+        }
+        collect_logs_with_transfers(call, logs);
+    }
+    logs.extend(frame_logs.map(to_log));
+}
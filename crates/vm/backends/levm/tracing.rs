@@ -1,6 +1,8 @@
 use ethrex_common::constants::EMPTY_KECCAK_HASH;
-use ethrex_common::tracing::{PrePostState, PrestateAccountState, PrestateResult, PrestateTrace};
-use ethrex_common::types::{Block, Transaction};
+use ethrex_common::tracing::{
+    NativeTraceResult, PrePostState, PrestateAccountState, PrestateResult, PrestateTrace,
+};
+use ethrex_common::types::{Block, GenericTransaction, Transaction};
 use ethrex_common::{
     Address, BigEndianHash, H256, U256,
     tracing::{CallTrace, OpcodeTraceResult},
@@ -9,14 +11,26 @@ use ethrex_common::{
 use ethrex_crypto::Crypto;
 use ethrex_levm::account::{AccountStatus, LevmAccount};
 use ethrex_levm::db::gen_db::CacheDB;
+use ethrex_levm::errors::ExecutionReport;
 use ethrex_levm::vm::VMType;
 use ethrex_levm::{
+    Environment,
     db::gen_db::GeneralizedDatabase,
-    tracing::{LevmCallTracer, LevmOpcodeTracer, OpcodeTracerConfig},
+    tracing::{
+        FlatCallTracer, FourByteTracer, LevmCallTracer, LevmOpcodeTracer, MuxTracer,
+        NativeTracerConfig, OpcodeTracerConfig, Tracer, downcast_tracer,
+    },
     vm::VM,
 };
 
-use crate::{EvmError, backends::levm::LEVM};
+use crate::{
+    EvmError,
+    backends::levm::{
+        LEVM, adjust_disabled_base_fee, adjust_disabled_l2_fees, env_from_generic,
+        generic_tx_to_transaction,
+    },
+    overrides::BlockOverrides,
+};
 
 impl LEVM {
     /// Execute all transactions of the block up until a certain transaction specified in `stop_index`.
@@ -77,26 +91,7 @@ impl LEVM {
         let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type, crypto)?;
         vm.execute()?;
 
-        preload_touched_codes(&pre_snapshot, db)?;
-
-        let mut pre_map = build_pre_state_map(&pre_snapshot, &db.current_accounts_state, db)?;
-
-        if diff_mode {
-            let (post_map, kept) =
-                build_post_state_map(&pre_snapshot, &db.current_accounts_state, db)?;
-            filter_diff_pre_storage(&mut pre_map, &db.current_accounts_state);
-            pre_map.retain(|addr, _| kept.contains(addr));
-            pre_map.retain(|_, state| !state.is_empty());
-            Ok(PrestateResult::Diff(PrePostState {
-                pre: pre_map,
-                post: post_map,
-            }))
-        } else {
-            if !include_empty {
-                pre_map.retain(|_, state| !state.is_empty());
-            }
-            Ok(PrestateResult::Prestate(pre_map))
-        }
+        build_prestate_result(&pre_snapshot, db, diff_mode, include_empty)
     }
 
     /// Run transaction with opcode (EIP-3155) tracer activated.
@@ -123,6 +118,24 @@ impl LEVM {
         Ok(vm.opcode_tracer.take_result())
     }
 
+    /// Like [`LEVM::trace_tx_opcodes`], but with an already recovered sender, and returning
+    /// the execution report along with the trace so the tx can be committed as usual.
+    pub fn execute_tx_with_opcode_tracer(
+        tx: &Transaction,
+        tx_sender: Address,
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        cfg: OpcodeTracerConfig,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<(ExecutionReport, OpcodeTraceResult), EvmError> {
+        let env = Self::setup_env(tx, tx_sender, block_header, db, vm_type)?;
+        let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type, crypto)?;
+        vm.opcode_tracer = LevmOpcodeTracer::new(cfg);
+        let report = vm.execute()?;
+        Ok((report, vm.opcode_tracer.take_result()))
+    }
+
     /// Run transaction with callTracer activated.
     pub fn trace_tx_calls(
         db: &mut GeneralizedDatabase,
@@ -158,6 +171,260 @@ impl LEVM {
         // We only return the top call because a transaction only has one call with subcalls
         Ok(vec![callframe])
     }
+
+    /// Run a simulated (unsigned) transaction with callTracer activated.
+    /// Used by `debug_traceCall`, the transaction is executed on top of the state held by `db`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn trace_generic_tx_calls(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        tx: &GenericTransaction,
+        only_top_call: bool,
+        with_log: bool,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<CallTrace, EvmError> {
+        let (env, vm_type) = generic_trace_env(tx, block_header, block_overrides, db, vm_type)?;
+        let converted_tx = generic_tx_to_transaction(tx)?;
+        let mut vm = VM::new(
+            env,
+            db,
+            &converted_tx,
+            LevmCallTracer::new(only_top_call, with_log),
+            vm_type,
+            crypto,
+        )?;
+
+        vm.execute()?;
+
+        Ok(vec![vm.get_trace_result()?])
+    }
+
+    /// Executes a simulated (unsigned) transaction and returns the prestateTracer result.
+    /// See [`LEVM::trace_tx_prestate`] for the meaning of `diff_mode` and `include_empty`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn trace_generic_tx_prestate(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        tx: &GenericTransaction,
+        diff_mode: bool,
+        include_empty: bool,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<PrestateResult, EvmError> {
+        let pre_snapshot: CacheDB = db.current_accounts_state.clone();
+
+        let (env, vm_type) = generic_trace_env(tx, block_header, block_overrides, db, vm_type)?;
+        let converted_tx = generic_tx_to_transaction(tx)?;
+        let mut vm = VM::new(
+            env,
+            db,
+            &converted_tx,
+            LevmCallTracer::disabled(),
+            vm_type,
+            crypto,
+        )?;
+        vm.execute()?;
+
+        build_prestate_result(&pre_snapshot, db, diff_mode, include_empty)
+    }
+
+    /// Run a simulated (unsigned) transaction with the opcode (EIP-3155) tracer activated.
+    pub fn trace_generic_tx_opcodes(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        tx: &GenericTransaction,
+        cfg: OpcodeTracerConfig,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<OpcodeTraceResult, EvmError> {
+        let (env, vm_type) = generic_trace_env(tx, block_header, block_overrides, db, vm_type)?;
+        let converted_tx = generic_tx_to_transaction(tx)?;
+        let mut vm = VM::new(
+            env,
+            db,
+            &converted_tx,
+            LevmCallTracer::disabled(),
+            vm_type,
+            crypto,
+        )?;
+        vm.opcode_tracer = LevmOpcodeTracer::new(cfg);
+        vm.execute()?;
+        Ok(vm.opcode_tracer.take_result())
+    }
+
+    /// Run transaction with the given built-in tracer (or tracers, through `muxTracer`) activated.
+    pub fn trace_tx_native(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        cfg: &NativeTracerConfig,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<NativeTraceResult, EvmError> {
+        let env = Self::setup_env(
+            tx,
+            tx.sender(crypto).map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?,
+            block_header,
+            db,
+            vm_type,
+        )?;
+        run_native_tracer(env, db, tx, cfg, vm_type, crypto)
+    }
+
+    /// Run a simulated (unsigned) transaction with the given built-in tracer activated.
+    pub fn trace_generic_tx_native(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        tx: &GenericTransaction,
+        cfg: &NativeTracerConfig,
+        vm_type: VMType,
+        crypto: &dyn Crypto,
+    ) -> Result<NativeTraceResult, EvmError> {
+        let (env, vm_type) = generic_trace_env(tx, block_header, block_overrides, db, vm_type)?;
+        let converted_tx = generic_tx_to_transaction(tx)?;
+        run_native_tracer(env, db, &converted_tx, cfg, vm_type, crypto)
+    }
+}
+
+/// Executes `tx` once with the tracer built from `cfg` installed and collects its result.
+fn run_native_tracer(
+    env: Environment,
+    db: &mut GeneralizedDatabase,
+    tx: &Transaction,
+    cfg: &NativeTracerConfig,
+    vm_type: VMType,
+    crypto: &dyn Crypto,
+) -> Result<NativeTraceResult, EvmError> {
+    let pre_snapshot: Option<CacheDB> = cfg
+        .needs_prestate()
+        .then(|| db.current_accounts_state.clone());
+
+    let tracer = cfg.build(env.config.fork, vm_type);
+    let mut vm = VM::new(env, db, tx, tracer, vm_type, crypto)?;
+    vm.execute()?;
+    let tracer = vm
+        .take_tracer()
+        .ok_or_else(|| EvmError::Custom("Tracer was not installed".to_string()))?;
+
+    native_trace_result(cfg, tracer, pre_snapshot.as_ref(), db)
+}
+
+/// Reads the result of a tracer built from `cfg` once the traced transaction has been executed.
+fn native_trace_result(
+    cfg: &NativeTracerConfig,
+    tracer: Box<dyn Tracer>,
+    pre_snapshot: Option<&CacheDB>,
+    db: &mut GeneralizedDatabase,
+) -> Result<NativeTraceResult, EvmError> {
+    let unexpected = || EvmError::Custom("Unexpected tracer type".to_string());
+    Ok(match cfg {
+        NativeTracerConfig::Call { .. } => NativeTraceResult::Call(
+            downcast_tracer::<LevmCallTracer>(tracer)
+                .ok_or_else(unexpected)?
+                .into_result()?,
+        ),
+        NativeTracerConfig::FlatCall { .. } => NativeTraceResult::FlatCall(
+            downcast_tracer::<FlatCallTracer>(tracer)
+                .ok_or_else(unexpected)?
+                .into_result()?,
+        ),
+        NativeTracerConfig::FourByte => NativeTraceResult::FourByte(
+            downcast_tracer::<FourByteTracer>(tracer)
+                .ok_or_else(unexpected)?
+                .into_result(),
+        ),
+        NativeTracerConfig::Prestate {
+            diff_mode,
+            include_empty,
+        } => {
+            let pre_snapshot = pre_snapshot.ok_or_else(|| {
+                EvmError::Custom("Missing pre-execution state for prestateTracer".to_string())
+            })?;
+            NativeTraceResult::Prestate(build_prestate_result(
+                pre_snapshot,
+                db,
+                *diff_mode,
+                *include_empty,
+            )?)
+        }
+        NativeTracerConfig::Noop => NativeTraceResult::Noop,
+        NativeTracerConfig::Mux(configs) => {
+            let tracers = downcast_tracer::<MuxTracer>(tracer)
+                .ok_or_else(unexpected)?
+                .into_tracers();
+            NativeTraceResult::Mux(
+                configs
+                    .iter()
+                    .zip(tracers)
+                    .map(|((name, cfg), tracer)| {
+                        Ok((
+                            name.clone(),
+                            native_trace_result(cfg, tracer, pre_snapshot, db)?,
+                        ))
+                    })
+                    .collect::<Result<_, EvmError>>()?,
+            )
+        }
+    })
+}
+
+/// Builds the execution environment for tracing a simulated transaction, mirroring
+/// `eth_call` semantics: the block gas limit is lifted, fees are disabled when no gas
+/// price is given and a missing nonce is filled in from the sender's current state.
+/// `block_header` must already have `block_overrides` applied, only the fields that live
+/// in the environment alone are set here.
+fn generic_trace_env(
+    tx: &GenericTransaction,
+    block_header: &BlockHeader,
+    block_overrides: &BlockOverrides,
+    db: &mut GeneralizedDatabase,
+    vm_type: VMType,
+) -> Result<(Environment, VMType), EvmError> {
+    let mut env = env_from_generic(tx, block_header, db, vm_type)?;
+    block_overrides.apply_to_env(&mut env);
+    env.block_gas_limit = i64::MAX as u64; // disable block gas limit
+    adjust_disabled_base_fee(&mut env);
+    if tx.nonce.is_none() {
+        env.tx_nonce = db.get_account(env.origin)?.info.nonce;
+    }
+    let vm_type = adjust_disabled_l2_fees(&env, vm_type);
+    Ok((env, vm_type))
+}
+
+/// Builds the prestateTracer output once the traced transaction has been executed on `db`.
+/// `pre_snapshot` must be the account cache as it was right before the execution.
+fn build_prestate_result(
+    pre_snapshot: &CacheDB,
+    db: &mut GeneralizedDatabase,
+    diff_mode: bool,
+    include_empty: bool,
+) -> Result<PrestateResult, EvmError> {
+    preload_touched_codes(pre_snapshot, db)?;
+
+    let mut pre_map = build_pre_state_map(pre_snapshot, &db.current_accounts_state, db)?;
+
+    if diff_mode {
+        let (post_map, kept) = build_post_state_map(pre_snapshot, &db.current_accounts_state, db)?;
+        filter_diff_pre_storage(&mut pre_map, &db.current_accounts_state);
+        pre_map.retain(|addr, _| kept.contains(addr));
+        pre_map.retain(|_, state| !state.is_empty());
+        Ok(PrestateResult::Diff(PrePostState {
+            pre: pre_map,
+            post: post_map,
+        }))
+    } else {
+        if !include_empty {
+            pre_map.retain(|_, state| !state.is_empty());
+        }
+        Ok(PrestateResult::Prestate(pre_map))
+    }
 }
 
 /// Returns `(address, pre_account, post_account)` for every account in `post_cache`.
@@ -4,6 +4,8 @@ use levm::LEVM;
 use crate::db::{DynVmDatabase, VmDatabase};
 use crate::errors::EvmError;
 use crate::execution_result::ExecutionResult;
+use crate::overrides::BlockOverrides;
+use ethrex_common::tracing::OpcodeTraceResult;
 use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::requests::Requests;
 use ethrex_common::types::{
@@ -16,6 +18,8 @@ pub use ethrex_levm::call_frame::CallFrameBackup;
 use ethrex_levm::db::gen_db::GeneralizedDatabase;
 pub use ethrex_levm::db::{CachingDatabase, Database as LevmDatabase};
 use ethrex_levm::errors::{ExecutionReport, TxResult};
+use ethrex_levm::precompiles::PrecompileRegistry;
+use ethrex_levm::tracing::{OpcodeTracerConfig, Tracer};
 use ethrex_levm::vm::VMType;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
@@ -89,6 +93,13 @@ impl Evm {
         }
     }
 
+    /// Runs every block and transaction with the custom precompiles of `precompiles` on top of
+    /// the builtin ones.
+    pub fn with_precompiles(mut self, precompiles: Option<&'static PrecompileRegistry>) -> Self {
+        self.db.precompiles = precompiles;
+        self
+    }
+
     /// Execute a block and return the execution result.
     ///
     /// Also records and returns the Block Access List (EIP-7928) for Amsterdam+ forks.
@@ -160,6 +171,69 @@ impl Evm {
         Ok((receipt, execution_report))
     }
 
+    /// Like [`Evm::execute_tx`], but with `tracer` receiving the execution callbacks.
+    /// The tracer is handed back along with the receipt and report so its results can be read.
+    pub fn execute_tx_with_tracer<T: Tracer>(
+        &mut self,
+        tx: &Transaction,
+        block_header: &BlockHeader,
+        cumulative_gas_spent: &mut u64,
+        sender: Address,
+        tracer: T,
+    ) -> Result<(Receipt, ExecutionReport, T), EvmError> {
+        let (execution_report, tracer) = LEVM::execute_tx_with_tracer(
+            tx,
+            sender,
+            block_header,
+            &mut self.db,
+            self.vm_type,
+            self.crypto.as_ref(),
+            tracer,
+        )?;
+
+        *cumulative_gas_spent += execution_report.gas_spent;
+
+        let receipt = Receipt::new(
+            tx.tx_type(),
+            execution_report.is_success(),
+            *cumulative_gas_spent,
+            execution_report.logs.clone(),
+        );
+
+        Ok((receipt, execution_report, tracer))
+    }
+
+    /// Like [`Evm::execute_tx`], but with the opcode (EIP-3155) tracer activated.
+    pub fn execute_tx_with_opcode_tracer(
+        &mut self,
+        tx: &Transaction,
+        block_header: &BlockHeader,
+        cumulative_gas_spent: &mut u64,
+        sender: Address,
+        cfg: OpcodeTracerConfig,
+    ) -> Result<(Receipt, ExecutionReport, OpcodeTraceResult), EvmError> {
+        let (execution_report, trace) = LEVM::execute_tx_with_opcode_tracer(
+            tx,
+            sender,
+            block_header,
+            &mut self.db,
+            cfg,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )?;
+
+        *cumulative_gas_spent += execution_report.gas_spent;
+
+        let receipt = Receipt::new(
+            tx.tx_type(),
+            execution_report.is_success(),
+            *cumulative_gas_spent,
+            execution_report.logs.clone(),
+        );
+
+        Ok((receipt, execution_report, trace))
+    }
+
     pub fn undo_last_tx(&mut self) -> Result<(), EvmError> {
         LEVM::undo_last_tx(&mut self.db)
     }
@@ -238,7 +312,14 @@ impl Evm {
         tx: &GenericTransaction,
         header: &BlockHeader,
     ) -> Result<ExecutionResult, EvmError> {
-        LEVM::simulate_tx_from_generic(tx, header, &mut self.db, self.vm_type, self.crypto.as_ref())
+        LEVM::simulate_tx_from_generic(
+            tx,
+            header,
+            &BlockOverrides::default(),
+            &mut self.db,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
     }
 
     pub fn create_access_list(
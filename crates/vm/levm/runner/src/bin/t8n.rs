@@ -0,0 +1,245 @@
+use clap::Parser;
+use ethrex_common::types::Transaction;
+use ethrex_levm::tracing::OpcodeTracerConfig;
+use ethrex_rlp::decode::RLPDecode;
+use runner::t8n::{
+    T8nError, chain_config, transition,
+    types::{Alloc, T8nEnv, T8nTransaction},
+};
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Map, Value};
+use std::{fs, io, path::Path};
+
+const STDIN: &str = "stdin";
+const STDOUT: &str = "stdout";
+const STDERR: &str = "stderr";
+
+/// State transition tool compatible with geth's `evm t8n`.
+#[derive(Parser)]
+struct Cli {
+    #[arg(
+        long = "input.alloc",
+        default_value = "alloc.json",
+        help = "`stdin` or file name of where to find the prestate alloc to use"
+    )]
+    input_alloc: String,
+
+    #[arg(
+        long = "input.env",
+        default_value = "env.json",
+        help = "`stdin` or file name of where to find the block environment to use"
+    )]
+    input_env: String,
+
+    #[arg(
+        long = "input.txs",
+        default_value = "txs.json",
+        help = "`stdin` or file name of where to find the transactions to apply. A `.rlp` file holds the RLP encoded transactions as a hex string"
+    )]
+    input_txs: String,
+
+    #[arg(
+        long = "output.basedir",
+        default_value = "",
+        help = "Directory where output files (and traces) are placed"
+    )]
+    output_basedir: String,
+
+    #[arg(
+        long = "output.result",
+        default_value = "result.json",
+        help = "`stdout`, `stderr` or file name of where to place the execution result"
+    )]
+    output_result: String,
+
+    #[arg(
+        long = "output.alloc",
+        default_value = "alloc.json",
+        help = "`stdout`, `stderr` or file name of where to place the post state"
+    )]
+    output_alloc: String,
+
+    #[arg(
+        long = "output.body",
+        help = "`stdout`, `stderr` or file name of where to place the RLP encoded transactions"
+    )]
+    output_body: Option<String>,
+
+    #[arg(
+        long = "state.fork",
+        default_value = "Prague",
+        help = "Fork to use, e.g. `Cancun` or `CancunToPragueAtTime15k`"
+    )]
+    state_fork: String,
+
+    #[arg(long = "state.chainid", default_value_t = 1, help = "Chain id to use")]
+    state_chainid: u64,
+
+    #[arg(
+        long = "state.reward",
+        default_value_t = 0,
+        allow_negative_numbers = true,
+        help = "Mining reward, ignored as only post-merge forks are supported"
+    )]
+    _state_reward: i64,
+
+    #[arg(
+        long,
+        help = "Write an EIP-3155 trace of every transaction to the output basedir"
+    )]
+    trace: bool,
+
+    #[arg(long = "trace.memory", help = "Include memory in the traces")]
+    trace_memory: bool,
+
+    #[arg(long = "trace.nostack", help = "Exclude the stack from the traces")]
+    trace_nostack: bool,
+
+    #[arg(long = "trace.returndata", help = "Include return data in the traces")]
+    trace_returndata: bool,
+}
+
+/// Inputs read from stdin, as a single JSON object.
+#[derive(Default, Deserialize)]
+struct StdinInput {
+    alloc: Option<Alloc>,
+    env: Option<T8nEnv>,
+    txs: Option<Vec<T8nTransaction>>,
+    #[serde(rename = "txsRlp")]
+    txs_rlp: Option<String>,
+}
+
+fn main() {
+    let cli = Cli::parse();
+    if let Err(err) = run(cli) {
+        eprintln!("{err}");
+        std::process::exit(err.exit_code());
+    }
+}
+
+fn run(cli: Cli) -> Result<(), T8nError> {
+    let chain_config = chain_config(&cli.state_fork, cli.state_chainid)?;
+    let chain_id = chain_config.chain_id;
+
+    let reads_stdin = [&cli.input_alloc, &cli.input_env, &cli.input_txs]
+        .iter()
+        .any(|input| input.as_str() == STDIN);
+    let mut stdin = if reads_stdin {
+        serde_json::from_reader(io::stdin().lock())
+            .map_err(|err| T8nError::Json(format!("failed unmarshalling stdin: {err}")))?
+    } else {
+        StdinInput::default()
+    };
+
+    let alloc: Alloc = if cli.input_alloc == STDIN {
+        stdin
+            .alloc
+            .take()
+            .ok_or_else(|| T8nError::Json("missing alloc in stdin".to_string()))?
+    } else {
+        read_json(&cli.input_alloc)?
+    };
+    let env: T8nEnv = if cli.input_env == STDIN {
+        stdin
+            .env
+            .take()
+            .ok_or_else(|| T8nError::Json("missing env in stdin".to_string()))?
+    } else {
+        read_json(&cli.input_env)?
+    };
+    let txs = if cli.input_txs == STDIN {
+        match (stdin.txs.take(), stdin.txs_rlp.take()) {
+            (Some(txs), _) => json_txs(txs, chain_id),
+            (None, Some(rlp)) => rlp_txs(&rlp)?,
+            (None, None) => Vec::new(),
+        }
+    } else if cli.input_txs.ends_with(".rlp") {
+        rlp_txs(&read_json::<String>(&cli.input_txs)?)?
+    } else {
+        json_txs(read_json(&cli.input_txs)?, chain_id)
+    };
+
+    let trace = cli.trace.then(|| OpcodeTracerConfig {
+        disable_stack: cli.trace_nostack,
+        enable_memory: cli.trace_memory,
+        enable_return_data: cli.trace_returndata,
+        ..Default::default()
+    });
+
+    let output = transition(alloc, env, txs, chain_config, trace)?;
+
+    let base_dir = Path::new(&cli.output_basedir);
+    if !cli.output_basedir.is_empty() {
+        fs::create_dir_all(base_dir).map_err(|err| T8nError::Io(err.to_string()))?;
+    }
+    for trace in &output.traces {
+        let lines = trace
+            .to_jsonl()
+            .map_err(|err| T8nError::Json(err.to_string()))?;
+        fs::write(base_dir.join(trace.file_name()), lines)
+            .map_err(|err| T8nError::Io(err.to_string()))?;
+    }
+
+    let mut stdout = Map::new();
+    let mut stderr = Map::new();
+    let mut outputs = vec![
+        (&cli.output_alloc, "alloc", to_value(&output.alloc)?),
+        (&cli.output_result, "result", to_value(&output.result)?),
+    ];
+    if let Some(output_body) = &cli.output_body {
+        let body = Value::String(format!("0x{}", hex::encode(&output.body)));
+        outputs.push((output_body, "body", body));
+    }
+    for (dest, name, value) in outputs {
+        match dest.as_str() {
+            STDOUT => {
+                stdout.insert(name.to_string(), value);
+            }
+            STDERR => {
+                stderr.insert(name.to_string(), value);
+            }
+            file => {
+                let contents = serde_json::to_string_pretty(&value)
+                    .map_err(|err| T8nError::Json(err.to_string()))?;
+                fs::write(base_dir.join(file), contents)
+                    .map_err(|err| T8nError::Io(format!("failed writing output {file}: {err}")))?;
+            }
+        }
+    }
+    if !stdout.is_empty() {
+        println!("{}", to_pretty_string(&stdout)?);
+    }
+    if !stderr.is_empty() {
+        eprintln!("{}", to_pretty_string(&stderr)?);
+    }
+    Ok(())
+}
+
+fn read_json<T: DeserializeOwned>(path: &str) -> Result<T, T8nError> {
+    let contents = fs::read_to_string(path)
+        .map_err(|err| T8nError::Io(format!("failed reading {path}: {err}")))?;
+    serde_json::from_str(&contents)
+        .map_err(|err| T8nError::Json(format!("failed unmarshalling {path}: {err}")))
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Result<Value, T8nError> {
+    serde_json::to_value(value).map_err(|err| T8nError::Json(err.to_string()))
+}
+
+fn to_pretty_string(value: &Map<String, Value>) -> Result<String, T8nError> {
+    serde_json::to_string_pretty(value).map_err(|err| T8nError::Json(err.to_string()))
+}
+
+fn json_txs(txs: Vec<T8nTransaction>, chain_id: u64) -> Vec<Result<Transaction, String>> {
+    txs.into_iter()
+        .map(|tx| tx.into_transaction(chain_id))
+        .collect()
+}
+
+fn rlp_txs(rlp: &str) -> Result<Vec<Result<Transaction, String>>, T8nError> {
+    let bytes = hex::decode(rlp.trim_start_matches("0x"))
+        .map_err(|err| T8nError::Rlp(format!("invalid hex: {err}")))?;
+    let txs = Vec::<Transaction>::decode(&bytes)
+        .map_err(|err| T8nError::Rlp(format!("failed decoding transactions: {err}")))?;
+    Ok(txs.into_iter().map(Ok).collect())
+}
@@ -0,0 +1,227 @@
+use ethrex_common::{Address, H256, U256};
+use ethrex_levm::{
+    debugger::{Breakpoint, DebugStop, DebuggerFrontend, StepMode},
+    errors::VMError,
+    opcodes::Opcode,
+};
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Commands:
+  s, step                      execute one opcode, entering calls
+  n, next                      execute one opcode, stepping over calls
+  c, continue                  run until the next breakpoint
+  q, quit                      run to the end without stopping again
+  b pc <pc>                    break at a program counter
+  b op <opcode>                break at an opcode, e.g. `b op SSTORE`
+  b addr <address>             break when a frame starts running at, or the code of, an address
+  b slot <slot> [address]      break at an SLOAD or SSTORE of a storage slot
+  bl, breakpoints              list breakpoints
+  d, delete <index>            delete a breakpoint
+  i, info                      show the current opcode
+  st, stack                    show the stack, top first
+  m, memory [offset] [size]    show memory, 32 bytes per line
+  sto, storage                 show the storage slots read or written so far
+  f, frames                    show the call stack
+  h, help                      show this help";
+
+/// Interactive [`DebuggerFrontend`] reading commands from stdin.
+#[derive(Default)]
+pub struct Repl {
+    /// Set by `quit` or at the end of the input, after which execution never stops again.
+    detached: bool,
+}
+
+impl DebuggerFrontend for Repl {
+    fn on_stop(&mut self, stop: &DebugStop<'_>, breakpoints: &mut Vec<Breakpoint>) -> StepMode {
+        if self.detached {
+            return StepMode::Continue;
+        }
+        if let Some(index) = stop.breakpoint {
+            println!("Breakpoint {index} hit");
+        }
+        print_location(stop);
+
+        let stdin = io::stdin();
+        let mut line = String::new();
+        loop {
+            print!("(levm) ");
+            let _ = io::stdout().flush();
+            line.clear();
+            if stdin.lock().read_line(&mut line).unwrap_or(0) == 0 {
+                self.detach(breakpoints);
+                return StepMode::Continue;
+            }
+            let args: Vec<&str> = line.split_whitespace().collect();
+            let Some((&command, args)) = args.split_first() else {
+                continue;
+            };
+            match command {
+                "s" | "step" => return StepMode::Step,
+                "n" | "next" => return StepMode::Next,
+                "c" | "continue" => return StepMode::Continue,
+                "q" | "quit" => {
+                    self.detach(breakpoints);
+                    return StepMode::Continue;
+                }
+                "b" | "break" => match parse_breakpoint(args) {
+                    Ok(breakpoint) => {
+                        println!("Breakpoint {}: {breakpoint:?}", breakpoints.len());
+                        breakpoints.push(breakpoint);
+                    }
+                    Err(err) => println!("{err}"),
+                },
+                "bl" | "breakpoints" => {
+                    for (index, breakpoint) in breakpoints.iter().enumerate() {
+                        println!("{index}: {breakpoint:?}");
+                    }
+                }
+                "d" | "delete" => {
+                    match args.first().and_then(|index| index.parse::<usize>().ok()) {
+                        Some(index) if index < breakpoints.len() => {
+                            breakpoints.remove(index);
+                        }
+                        _ => println!("Usage: delete <index>"),
+                    }
+                }
+                "i" | "info" => print_location(stop),
+                "st" | "stack" => print_stack(stop),
+                "m" | "memory" => print_memory(stop, args),
+                "sto" | "storage" => print_storage(stop),
+                "f" | "frames" => print_frames(stop),
+                "h" | "help" => println!("{HELP}"),
+                _ => println!("Unknown command `{command}`, `help` lists the commands"),
+            }
+        }
+    }
+
+    fn on_fault(&mut self, pc: usize, opcode: u8, depth: usize, error: &VMError) {
+        if !self.detached {
+            println!(
+                "Frame at depth {depth} halted at pc {pc} ({:?}): {error}",
+                Opcode::from(opcode)
+            );
+        }
+    }
+}
+
+impl Repl {
+    fn detach(&mut self, breakpoints: &mut Vec<Breakpoint>) {
+        self.detached = true;
+        breakpoints.clear();
+    }
+}
+
+fn parse_breakpoint(args: &[&str]) -> Result<Breakpoint, String> {
+    let usage = "Usage: b pc <pc> | b op <opcode> | b addr <address> | b slot <slot> [address]";
+    match args {
+        ["pc", pc] => parse_number(pc)
+            .and_then(|pc| usize::try_from(pc).ok())
+            .map(Breakpoint::Pc)
+            .ok_or_else(|| format!("Invalid program counter `{pc}`")),
+        ["op", opcode] => opcode
+            .to_uppercase()
+            .parse::<Opcode>()
+            .map(|opcode| Breakpoint::Opcode(opcode.into()))
+            .map_err(|_| format!("Unknown opcode `{opcode}`")),
+        ["addr", address] => parse_address(address).map(Breakpoint::Address),
+        ["slot", slot, rest @ ..] if rest.len() <= 1 => {
+            let slot = parse_number(slot)
+                .map(|slot| H256(slot.to_big_endian()))
+                .ok_or_else(|| format!("Invalid slot `{slot}`"))?;
+            let address = rest
+                .first()
+                .map(|address| parse_address(address))
+                .transpose()?;
+            Ok(Breakpoint::Storage { address, slot })
+        }
+        _ => Err(usage.to_string()),
+    }
+}
+
+/// Parses a decimal or `0x`-prefixed hex number.
+fn parse_number(value: &str) -> Option<U256> {
+    match value.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).ok(),
+        None => U256::from_dec_str(value).ok(),
+    }
+}
+
+fn parse_address(value: &str) -> Result<Address, String> {
+    value
+        .parse::<Address>()
+        .map_err(|_| format!("Invalid address `{value}`"))
+}
+
+fn print_location(stop: &DebugStop<'_>) {
+    let opcode = Opcode::from(stop.opcode);
+    let mut location = format!(
+        "[step {}] depth {} {:#x} pc {} {opcode:?}",
+        stop.steps, stop.frame.depth, stop.frame.code_address, stop.pc
+    );
+    if (Opcode::PUSH1..=Opcode::PUSH32).contains(&opcode) {
+        let size = usize::from(stop.opcode - u8::from(Opcode::PUSH1) + 1);
+        let start = stop.pc + 1;
+        let code = &stop.frame.bytecode.bytecode;
+        let immediate = code.get(start..(start + size).min(code.len()));
+        location.push_str(&format!(
+            " 0x{}",
+            hex::encode(immediate.unwrap_or_default())
+        ));
+    }
+    location.push_str(&format!(" gas {}", stop.frame.gas_remaining));
+    println!("{location}");
+}
+
+fn print_stack(stop: &DebugStop<'_>) {
+    let stack = stop.stack();
+    if stack.is_empty() {
+        println!("Stack is empty");
+    }
+    for (index, value) in stack.iter().enumerate() {
+        println!("{index:>4}: {value:#x}");
+    }
+}
+
+fn print_memory(stop: &DebugStop<'_>, args: &[&str]) {
+    let memory = stop.memory();
+    let offset = args
+        .first()
+        .and_then(|offset| parse_number(offset))
+        .map_or(0, |offset| offset.low_u64() as usize);
+    let size = args
+        .get(1)
+        .and_then(|size| parse_number(size))
+        .map_or(memory.len(), |size| size.low_u64() as usize);
+    let end = offset.saturating_add(size).min(memory.len());
+    if offset >= end {
+        println!("Memory is {} bytes long", memory.len());
+        return;
+    }
+    for (index, chunk) in memory[offset..end].chunks(32).enumerate() {
+        println!("{:#06x}: {}", offset + index * 32, hex::encode(chunk));
+    }
+}
+
+fn print_storage(stop: &DebugStop<'_>) {
+    if stop.storage.is_empty() {
+        println!("No storage slot read or written yet");
+    }
+    for ((address, slot), value) in stop.storage {
+        println!("{address:#x} {slot:#x}: {value:#x}");
+    }
+}
+
+fn print_frames(stop: &DebugStop<'_>) {
+    for (depth, call) in stop.calls.iter().enumerate() {
+        println!(
+            "{depth:>3}: {:?} {:#x} -> {:#x} value {} gas {} input {} bytes",
+            call.call_type,
+            call.from,
+            call.to,
+            call.value,
+            call.gas,
+            call.input.len()
+        );
+    }
+}
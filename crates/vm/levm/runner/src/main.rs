@@ -3,7 +3,7 @@ use clap::Parser;
 use env_logger::Env;
 use ethrex_blockchain::vm::StoreVmDatabase;
 use ethrex_common::{
-    Address, H160, U256,
+    Address, H160, H256, U256,
     constants::EMPTY_TRIE_HASH,
     types::{Account, BlockHeader, Code, LegacyTransaction, Transaction},
 };
@@ -12,21 +12,26 @@ use ethrex_levm::{
     EVMConfig, Environment,
     account::LevmAccount,
     db::gen_db::GeneralizedDatabase,
+    debugger::Debugger,
     opcodes::Opcode,
-    tracing::LevmCallTracer,
+    tracing::{LevmCallTracer, Tracer},
     vm::{VM, VMType},
 };
-use ethrex_storage::Store;
-use ethrex_vm::DynVmDatabase;
+use ethrex_storage::{EngineType, Store, StoreConfig, read_gc_mode_from_db};
+use ethrex_vm::{DynVmDatabase, Evm};
 use log::{debug, error, info};
 use num_bigint::BigUint;
 use num_traits::Num;
-use runner::input::{InputAccount, InputTransaction, RunnerInput};
+use runner::{
+    debugger::Repl,
+    input::{InputAccount, InputTransaction, RunnerInput},
+};
 use rustc_hash::FxHashMap;
 use std::{collections::BTreeMap, io::Write};
 use std::{
     fs::{self, File},
     io::BufReader,
+    path::Path,
     sync::Arc,
 };
 
@@ -49,6 +54,27 @@ struct Cli {
         help = "Converts mnemonics file into a bytecode file"
     )]
     emit_bytes: Option<String>,
+
+    #[arg(
+        long,
+        short,
+        action = clap::ArgAction::SetTrue,
+        help = "Execute the transaction in the interactive step debugger"
+    )]
+    debug: bool,
+
+    #[arg(
+        long,
+        requires = "datadir",
+        help = "Hash of a mined transaction to replay in the interactive step debugger"
+    )]
+    tx: Option<H256>,
+
+    #[arg(
+        long,
+        help = "Path to the ethrex datadir holding the transaction to replay"
+    )]
+    datadir: Option<String>,
 }
 
 fn main() {
@@ -83,6 +109,11 @@ fn main() {
         return;
     }
 
+    if let (Some(tx_hash), Some(datadir)) = (cli.tx, &cli.datadir) {
+        debug_mined_tx(datadir, tx_hash);
+        return;
+    }
+
     // Parse input
     // Input is mutable just to assign bytecode to the transaction recipient if provided
     let mut runner_input: RunnerInput = if let Some(input_file_path) = cli.input {
@@ -143,7 +174,7 @@ fn main() {
 
     // DB
     let initial_state = setup_initial_state(&mut runner_input, bytecode);
-    let in_memory_db = Store::new("", ethrex_storage::EngineType::InMemory).unwrap();
+    let in_memory_db = Store::new("", EngineType::InMemory).unwrap();
     let header = BlockHeader {
         state_root: *EMPTY_TRIE_HASH,
         ..Default::default()
@@ -155,15 +186,13 @@ fn main() {
     // local that outlives the VM instead of passing a temporary.
     let tx =
         Transaction::LegacyTransaction(LegacyTransaction::from(runner_input.transaction.clone()));
-    let mut vm = VM::new(
-        env,
-        &mut db,
-        &tx,
-        LevmCallTracer::disabled(),
-        VMType::L1,
-        &NativeCrypto,
-    )
-    .expect("Failed to initialize VM");
+    let tracer: Box<dyn Tracer> = if cli.debug {
+        Box::new(Debugger::new(Repl::default()))
+    } else {
+        Box::new(LevmCallTracer::disabled())
+    };
+    let mut vm = VM::new(env, &mut db, &tx, tracer, VMType::L1, &NativeCrypto)
+        .expect("Failed to initialize VM");
 
     // Set initial stack and memory
     info!("Setting initial stack: {:?}", runner_input.initial_stack);
@@ -211,6 +240,63 @@ fn main() {
     );
 }
 
+/// Replays the mined transaction `tx_hash` in the step debugger, on top of the state left by
+/// the transactions before it in its block. Needs the state of the parent block in `datadir`.
+fn debug_mined_tx(datadir: &str, tx_hash: H256) {
+    // A database that keeps archive history can only be opened in archive mode. History
+    // retention is left at its default of keeping everything, so the replay never prunes
+    // the node's block history.
+    let config = StoreConfig {
+        gc_mode: read_gc_mode_from_db(Path::new(datadir)).unwrap_or_default(),
+        ..Default::default()
+    };
+    let store = Store::new_with_config(datadir, EngineType::RocksDB, config)
+        .expect("Failed to open the datadir");
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .expect("Failed to start the async runtime");
+    let (_, block_hash, tx_index) = runtime
+        .block_on(store.get_transaction_location(tx_hash))
+        .expect("Failed to read the transaction location")
+        .unwrap_or_else(|| panic!("Transaction {tx_hash:#x} not found"));
+    let block = runtime
+        .block_on(store.get_block_by_hash(block_hash))
+        .expect("Failed to read the block")
+        .unwrap_or_else(|| panic!("Block {block_hash:#x} not found"));
+    let parent_header = store
+        .get_block_header_by_hash(block.header.parent_hash)
+        .expect("Failed to read the parent block header")
+        .expect("Parent block header not found");
+
+    let vm_db = StoreVmDatabase::new(store, parent_header).expect("Parent block state not found");
+    let mut evm = Evm::new_for_l1(vm_db, Arc::new(NativeCrypto));
+    let tx_index = tx_index as usize;
+    evm.rerun_block(&block, Some(tx_index))
+        .expect("Failed to execute the previous transactions of the block");
+
+    let tx = &block.body.transactions[tx_index];
+    let sender = tx
+        .sender(&NativeCrypto)
+        .expect("Failed to recover the transaction sender");
+    info!(
+        "Replaying transaction {tx_hash:#x} (index {tx_index} of block {})",
+        block.header.number
+    );
+    let result = evm.execute_tx_with_tracer(
+        tx,
+        &block.header,
+        &mut 0,
+        sender,
+        Debugger::new(Repl::default()),
+    );
+
+    info!("\n\nResult:");
+    match result {
+        Ok((_, report, _)) => info!(" {:?}\n", report),
+        Err(e) => error!(" Error: {}\n", e),
+    }
+}
+
 /// Prints on screen difference between initial state and current one.
 fn compare_initial_and_current_accounts(
     initial_accounts: FxHashMap<Address, LevmAccount>,
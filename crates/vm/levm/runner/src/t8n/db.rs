@@ -0,0 +1,93 @@
+use super::types::Alloc;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{AccountState, ChainConfig, Code, CodeMetadata},
+};
+use ethrex_crypto::NativeCrypto;
+use ethrex_levm::{db::Database, errors::DatabaseError};
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+
+/// Read-only LEVM database backed by the `alloc` pre-state and the `blockHashes` of the env.
+pub struct AllocDatabase {
+    accounts: FxHashMap<Address, AccountState>,
+    storage: FxHashMap<(Address, H256), U256>,
+    codes: FxHashMap<H256, Code>,
+    block_hashes: BTreeMap<u64, H256>,
+    chain_config: ChainConfig,
+}
+
+impl AllocDatabase {
+    pub fn new(
+        alloc: &Alloc,
+        block_hashes: BTreeMap<u64, H256>,
+        chain_config: ChainConfig,
+    ) -> Self {
+        let mut accounts = FxHashMap::default();
+        let mut storage = FxHashMap::default();
+        let mut codes = FxHashMap::default();
+
+        for (address, account) in alloc {
+            let state = AccountState::from(account);
+            if !account.code.is_empty() {
+                codes.insert(
+                    state.code_hash,
+                    Code::from_bytecode(account.code.clone(), &NativeCrypto),
+                );
+            }
+            for (key, value) in &account.storage {
+                storage.insert((*address, H256(key.to_big_endian())), *value);
+            }
+            accounts.insert(*address, state);
+        }
+
+        Self {
+            accounts,
+            storage,
+            codes,
+            block_hashes,
+            chain_config,
+        }
+    }
+}
+
+impl Database for AllocDatabase {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        Ok(self.accounts.get(&address).copied().unwrap_or_default())
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        Ok(self
+            .storage
+            .get(&(address, key))
+            .copied()
+            .unwrap_or_default())
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        self.block_hashes
+            .get(&block_number)
+            .copied()
+            .ok_or_else(|| {
+                DatabaseError::Custom(format!("missing block hash for block {block_number}"))
+            })
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        Ok(self.chain_config)
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        Ok(self.codes.get(&code_hash).cloned().unwrap_or_default())
+    }
+
+    fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
+        Ok(CodeMetadata {
+            length: self
+                .codes
+                .get(&code_hash)
+                .map(|code| code.bytecode.len() as u64)
+                .unwrap_or_default(),
+        })
+    }
+}
@@ -0,0 +1,597 @@
+//! State transition tool compatible with geth's `evm t8n`.
+//!
+//! Takes a pre-state (`alloc`), a block environment (`env`) and a list of transactions,
+//! executes them on top of the pre-state with LEVM and produces the post-state, the
+//! execution result (roots, receipts, rejected transactions, ...) and the RLP encoded
+//! body of the included transactions. This is the interface the execution-spec-tests
+//! filler drives, so ethrex can be used to fill fixtures and to diff results against
+//! other clients.
+
+pub mod db;
+pub mod types;
+
+use bytes::Bytes;
+use db::AllocDatabase;
+use ethrex_common::{
+    Address, H256, U256,
+    constants::GAS_PER_BLOB,
+    evm::calculate_create_address,
+    tracing::{Eip3155Step, OpcodeTraceResult},
+    types::{
+        AccountState, BlobSchedule, BlockHeader, ChainConfig, ELASTICITY_MULTIPLIER, Fork,
+        GenesisAccount, Log, Transaction, bloom_from_logs, calc_excess_blob_gas,
+        calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
+        compute_receipts_root_and_logs_bloom, compute_requests_hash, compute_transactions_root,
+        compute_withdrawals_root,
+    },
+    utils::keccak,
+};
+use ethrex_crypto::{NativeCrypto, keccak::keccak_hash};
+use ethrex_levm::{errors::TxResult, tracing::OpcodeTracerConfig};
+use ethrex_rlp::encode::RLPEncode;
+use ethrex_trie::Trie;
+use ethrex_vm::{Evm, EvmError, check_2d_gas_allowance, check_gas_limit};
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
+use types::{Alloc, ExecutionResult, RejectedTx, T8nEnv, T8nLog, T8nReceipt};
+
+/// Mainnet deposit contract, used by the EIP-6110 deposit requests parsing.
+const DEPOSIT_CONTRACT_ADDRESS: Address = Address([
+    0x00, 0x00, 0x00, 0x00, 0x21, 0x9a, 0xb5, 0x40, 0x35, 0x6c, 0xbb, 0x83, 0x9c, 0xbe, 0x05, 0x30,
+    0x3d, 0x77, 0x05, 0xfa,
+]);
+
+/// Timestamp at which the second fork of a transition fork (e.g.
+/// `ShanghaiToCancunAtTime15k`) activates.
+const TRANSITION_TIMESTAMP: u64 = 15_000;
+
+#[derive(Debug, thiserror::Error)]
+pub enum T8nError {
+    #[error("EVM error: {0}")]
+    Evm(String),
+    #[error("Config error: {0}")]
+    Config(String),
+    #[error("JSON error: {0}")]
+    Json(String),
+    #[error("IO error: {0}")]
+    Io(String),
+    #[error("RLP error: {0}")]
+    Rlp(String),
+}
+
+impl T8nError {
+    /// Process exit code, following the ones used by geth's `evm t8n`.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            T8nError::Evm(_) => 2,
+            T8nError::Config(_) => 3,
+            T8nError::Json(_) => 10,
+            T8nError::Io(_) => 11,
+            T8nError::Rlp(_) => 12,
+        }
+    }
+}
+
+impl From<EvmError> for T8nError {
+    fn from(err: EvmError) -> Self {
+        T8nError::Evm(err.to_string())
+    }
+}
+
+/// Opcode trace of a single included transaction.
+pub struct TxTrace {
+    /// Index of the transaction in the input list.
+    pub index: usize,
+    pub hash: H256,
+    pub trace: OpcodeTraceResult,
+    pub error: Option<String>,
+}
+
+impl TxTrace {
+    /// Name of the file the trace is written to, as expected by the spec tests filler.
+    pub fn file_name(&self) -> String {
+        format!("trace-{}-{:#x}.jsonl", self.index, self.hash)
+    }
+
+    /// Renders the trace as JSON lines: one EIP-3155 line per step followed by a summary
+    /// line with the output, the gas used and the error, if any.
+    pub fn to_jsonl(&self) -> Result<String, serde_json::Error> {
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Summary<'a> {
+            output: String,
+            gas_used: String,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            error: Option<&'a str>,
+        }
+
+        let mut lines = String::new();
+        for step in &self.trace.steps {
+            lines.push_str(&serde_json::to_string(&Eip3155Step(step))?);
+            lines.push('\n');
+        }
+        lines.push_str(&serde_json::to_string(&Summary {
+            output: hex::encode(&self.trace.output),
+            gas_used: format!("{:#x}", self.trace.gas_used),
+            error: self.error.as_deref(),
+        })?);
+        lines.push('\n');
+        Ok(lines)
+    }
+}
+
+pub struct TransitionOutput {
+    pub alloc: Alloc,
+    pub result: ExecutionResult,
+    /// RLP encoded list of the included transactions.
+    pub body: Bytes,
+    pub traces: Vec<TxTrace>,
+}
+
+/// Builds the chain config for a fork name as used by the spec tests, e.g. `Cancun` or
+/// `CancunToPragueAtTime15k`. Every block based fork is active from genesis.
+///
+/// Only post-merge forks are supported: pre-merge ones fail with a config error.
+pub fn chain_config(fork_name: &str, chain_id: u64) -> Result<ChainConfig, T8nError> {
+    let (fork, next_fork) = match fork_name.split_once("To") {
+        Some((from, to)) => {
+            let to = to.strip_suffix("AtTime15k").ok_or_else(|| {
+                T8nError::Config(format!("unsupported transition fork {fork_name}"))
+            })?;
+            (parse_fork(from)?, Some(parse_fork(to)?))
+        }
+        None => (parse_fork(fork_name)?, None),
+    };
+
+    let mut config = ChainConfig {
+        chain_id,
+        homestead_block: Some(0),
+        dao_fork_block: Some(0),
+        dao_fork_support: true,
+        eip150_block: Some(0),
+        eip155_block: Some(0),
+        eip158_block: Some(0),
+        byzantium_block: Some(0),
+        constantinople_block: Some(0),
+        petersburg_block: Some(0),
+        istanbul_block: Some(0),
+        muir_glacier_block: Some(0),
+        berlin_block: Some(0),
+        london_block: Some(0),
+        arrow_glacier_block: Some(0),
+        gray_glacier_block: Some(0),
+        merge_netsplit_block: Some(0),
+        terminal_total_difficulty: Some(0),
+        terminal_total_difficulty_passed: true,
+        blob_schedule: BlobSchedule::default(),
+        deposit_contract_address: DEPOSIT_CONTRACT_ADDRESS,
+        ..Default::default()
+    };
+    set_fork_time(&mut config, fork, 0);
+    if let Some(next_fork) = next_fork {
+        set_fork_time(&mut config, next_fork, TRANSITION_TIMESTAMP);
+    }
+    Ok(config)
+}
+
+fn parse_fork(name: &str) -> Result<Fork, T8nError> {
+    let fork = match name {
+        "Paris" | "Merge" => Fork::Paris,
+        "Shanghai" => Fork::Shanghai,
+        "Cancun" => Fork::Cancun,
+        "Prague" => Fork::Prague,
+        "Osaka" => Fork::Osaka,
+        "BPO1" => Fork::BPO1,
+        "BPO2" => Fork::BPO2,
+        "BPO3" => Fork::BPO3,
+        "BPO4" => Fork::BPO4,
+        "BPO5" => Fork::BPO5,
+        "Amsterdam" => Fork::Amsterdam,
+        "Frontier" | "Homestead" | "EIP150" | "EIP158" | "Byzantium" | "Constantinople"
+        | "ConstantinopleFix" | "Petersburg" | "Istanbul" | "MuirGlacier" | "Berlin" | "London"
+        | "ArrowGlacier" | "GrayGlacier" => {
+            return Err(T8nError::Config(format!(
+                "unsupported fork {name}: pre-merge forks are not supported, the block \
+                 rewards and difficulty aren't implemented (use Paris or later)"
+            )));
+        }
+        other => return Err(T8nError::Config(format!("unsupported fork {other}"))),
+    };
+    Ok(fork)
+}
+
+/// Activates `fork` and every timestamp based fork before it at `timestamp`, unless
+/// they are already scheduled earlier.
+fn set_fork_time(config: &mut ChainConfig, fork: Fork, timestamp: u64) {
+    let forks = [
+        (Fork::Shanghai, &mut config.shanghai_time),
+        (Fork::Cancun, &mut config.cancun_time),
+        (Fork::Prague, &mut config.prague_time),
+        (Fork::Osaka, &mut config.osaka_time),
+        (Fork::BPO1, &mut config.bpo1_time),
+        (Fork::BPO2, &mut config.bpo2_time),
+        (Fork::BPO3, &mut config.bpo3_time),
+        (Fork::BPO4, &mut config.bpo4_time),
+        (Fork::BPO5, &mut config.bpo5_time),
+        (Fork::Amsterdam, &mut config.amsterdam_time),
+    ];
+    for (activation_fork, time) in forks {
+        if activation_fork <= fork && time.is_none() {
+            *time = Some(timestamp);
+        }
+    }
+}
+
+/// Executes `txs` on top of `alloc` in the block described by `env`.
+///
+/// Transactions that fail to decode, recover their sender or pass the block level checks
+/// are reported in `rejected` and don't abort the transition. Errors that invalidate the
+/// whole block (e.g. a failing system call) do.
+pub fn transition(
+    mut alloc: Alloc,
+    env: T8nEnv,
+    txs: Vec<Result<Transaction, String>>,
+    chain_config: ChainConfig,
+    trace: Option<OpcodeTracerConfig>,
+) -> Result<TransitionOutput, T8nError> {
+    for account in alloc.values_mut() {
+        account.storage.retain(|_, value| !value.is_zero());
+    }
+
+    let header = block_header(&env, &chain_config)?;
+    let fork = chain_config.fork(header.timestamp);
+    let blob_schedule = chain_config.get_fork_blob_schedule(header.timestamp);
+    let max_blob_gas = blob_schedule
+        .map(|schedule| u64::from(schedule.max) * u64::from(GAS_PER_BLOB))
+        .unwrap_or_default();
+    let blob_gas_price = match (blob_schedule, header.excess_blob_gas) {
+        (Some(schedule), Some(excess_blob_gas)) => Some(calculate_base_fee_per_blob_gas(
+            excess_blob_gas,
+            schedule.base_fee_update_fraction,
+        )),
+        _ => None,
+    };
+
+    let db = AllocDatabase::new(&alloc, env.block_hashes.clone(), chain_config);
+    let mut evm = Evm::new_from_db_for_l1(Arc::new(db), Arc::new(NativeCrypto));
+    evm.apply_system_calls(&header)?;
+
+    let mut included = Vec::new();
+    let mut receipts = Vec::new();
+    let mut t8n_receipts = Vec::new();
+    let mut rejected = Vec::new();
+    let mut traces = Vec::new();
+    let mut all_logs: Vec<Log> = Vec::new();
+    let mut cumulative_gas_spent = 0;
+    let mut block_gas_used = 0;
+    let mut block_regular_gas_used = 0_u64;
+    let mut block_state_gas_used = 0_u64;
+    let mut blob_gas_used = 0;
+
+    for (index, tx) in txs.into_iter().enumerate() {
+        let tx = match tx {
+            Ok(tx) => tx,
+            Err(error) => {
+                rejected.push(RejectedTx { index, error });
+                continue;
+            }
+        };
+        let sender = match tx.sender(&NativeCrypto) {
+            Ok(sender) => sender,
+            Err(err) => {
+                rejected.push(RejectedTx {
+                    index,
+                    error: format!("could not recover sender: {err}"),
+                });
+                continue;
+            }
+        };
+        if let Some(tx_chain_id) = tx.chain_id()
+            && tx_chain_id != chain_config.chain_id
+        {
+            rejected.push(RejectedTx {
+                index,
+                error: format!(
+                    "invalid chain id: have {tx_chain_id}, want {}",
+                    chain_config.chain_id
+                ),
+            });
+            continue;
+        }
+        let allowance = if fork >= Fork::Amsterdam {
+            check_2d_gas_allowance(
+                &tx,
+                fork,
+                block_regular_gas_used,
+                block_state_gas_used,
+                header.gas_limit,
+            )
+        } else {
+            check_gas_limit(cumulative_gas_spent, tx.gas_limit(), header.gas_limit)
+        };
+        if let Err(err) = allowance {
+            rejected.push(RejectedTx {
+                index,
+                error: err.to_string(),
+            });
+            continue;
+        }
+        let tx_blob_gas = tx.blob_versioned_hashes().len() as u64 * u64::from(GAS_PER_BLOB);
+        if blob_gas_used + tx_blob_gas > max_blob_gas {
+            rejected.push(RejectedTx {
+                index,
+                error: format!(
+                    "blob gas limit reached: have {}, want {tx_blob_gas}",
+                    max_blob_gas.saturating_sub(blob_gas_used)
+                ),
+            });
+            continue;
+        }
+
+        let execution = match &trace {
+            Some(cfg) => evm
+                .execute_tx_with_opcode_tracer(
+                    &tx,
+                    &header,
+                    &mut cumulative_gas_spent,
+                    sender,
+                    cfg.clone(),
+                )
+                .map(|(receipt, report, trace)| (receipt, report, Some(trace))),
+            None => evm
+                .execute_tx(&tx, &header, &mut cumulative_gas_spent, sender)
+                .map(|(receipt, report)| (receipt, report, None)),
+        };
+        let (receipt, report, tx_trace) = match execution {
+            Ok(execution) => execution,
+            Err(EvmError::Transaction(error)) => {
+                rejected.push(RejectedTx { index, error });
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        blob_gas_used += tx_blob_gas;
+        let tx_state_gas = report.state_gas_used;
+        let tx_regular_gas = report.gas_used.saturating_sub(tx_state_gas);
+        block_regular_gas_used = block_regular_gas_used.saturating_add(tx_regular_gas);
+        block_state_gas_used = block_state_gas_used.saturating_add(tx_state_gas);
+        if fork >= Fork::Amsterdam {
+            block_gas_used = block_regular_gas_used.max(block_state_gas_used);
+        } else {
+            block_gas_used += report.gas_used;
+        }
+
+        let tx_hash = tx.hash();
+        let tx_index = included.len() as u64;
+        let error = match &report.result {
+            TxResult::Success => None,
+            TxResult::Revert(err) => Some(err.to_string()),
+        };
+        if let Some(trace) = tx_trace {
+            traces.push(TxTrace {
+                index,
+                hash: tx_hash,
+                trace,
+                error,
+            });
+        }
+
+        let logs = receipt
+            .logs
+            .iter()
+            .enumerate()
+            .map(|(i, log)| T8nLog {
+                address: log.address,
+                topics: log.topics.clone(),
+                data: log.data.clone(),
+                block_number: header.number,
+                transaction_hash: tx_hash,
+                transaction_index: tx_index,
+                block_hash: H256::zero(),
+                log_index: (all_logs.len() + i) as u64,
+                removed: false,
+            })
+            .collect();
+        all_logs.extend(receipt.logs.iter().cloned());
+        t8n_receipts.push(T8nReceipt {
+            tx_type: tx.tx_type(),
+            root: Bytes::new(),
+            status: u64::from(receipt.succeeded),
+            cumulative_gas_used: receipt.cumulative_gas_used,
+            logs_bloom: bloom_from_logs(&receipt.logs, &NativeCrypto),
+            logs,
+            transaction_hash: tx_hash,
+            contract_address: if tx.is_contract_creation() {
+                calculate_create_address(sender, tx.nonce())
+            } else {
+                Address::zero()
+            },
+            gas_used: report.gas_spent,
+            effective_gas_price: tx
+                .effective_gas_price(header.base_fee_per_gas)
+                .unwrap_or_default(),
+            blob_gas_used: (tx_blob_gas > 0).then_some(tx_blob_gas),
+            blob_gas_price: blob_gas_price.filter(|_| tx_blob_gas > 0),
+            block_hash: H256::zero(),
+            transaction_index: tx_index,
+        });
+        receipts.push(receipt);
+        included.push(tx);
+    }
+
+    let requests = if fork >= Fork::Prague {
+        let requests = evm.extract_requests(&receipts, &header)?;
+        Some(
+            requests
+                .iter()
+                .map(|request| request.encode())
+                .filter(|encoded| !encoded.is_empty())
+                .collect::<Vec<_>>(),
+        )
+    } else {
+        None
+    };
+
+    if let Some(withdrawals) = &env.withdrawals {
+        evm.process_withdrawals(withdrawals)?;
+    }
+
+    for update in evm.get_state_transitions()? {
+        if update.removed {
+            alloc.remove(&update.address);
+            continue;
+        }
+        let account = alloc.entry(update.address).or_insert(GenesisAccount {
+            code: Bytes::new(),
+            storage: BTreeMap::new(),
+            balance: U256::zero(),
+            nonce: 0,
+        });
+        if update.removed_storage {
+            account.storage.clear();
+        }
+        if let Some(info) = update.info {
+            account.balance = info.balance;
+            account.nonce = info.nonce;
+        }
+        if let Some(code) = update.code {
+            account.code = code.bytecode;
+        }
+        for (key, value) in update.added_storage {
+            let key = U256::from_big_endian(key.as_bytes());
+            if value.is_zero() {
+                account.storage.remove(&key);
+            } else {
+                account.storage.insert(key, value);
+            }
+        }
+    }
+
+    let state_root = Trie::compute_hash_from_unsorted_iter(
+        alloc.iter().map(|(address, account)| {
+            (
+                keccak_hash(address).to_vec(),
+                AccountState::from(account).encode_to_vec(),
+            )
+        }),
+        &NativeCrypto,
+    );
+    let (receipts_root, logs_bloom) =
+        compute_receipts_root_and_logs_bloom(&receipts, &NativeCrypto);
+
+    let result = ExecutionResult {
+        state_root,
+        tx_root: compute_transactions_root(&included, &NativeCrypto),
+        receipts_root,
+        logs_hash: keccak(all_logs.encode_to_vec()),
+        logs_bloom,
+        receipts: t8n_receipts,
+        rejected,
+        current_difficulty: None,
+        gas_used: block_gas_used,
+        current_base_fee: header.base_fee_per_gas,
+        withdrawals_root: header.withdrawals_root,
+        current_excess_blob_gas: header.excess_blob_gas,
+        blob_gas_used: header.excess_blob_gas.map(|_| blob_gas_used),
+        requests_hash: requests.as_deref().map(compute_requests_hash),
+        requests: requests.map(|requests| {
+            requests
+                .iter()
+                .map(|request| format!("0x{}", hex::encode(&request.0)))
+                .collect()
+        }),
+    };
+
+    Ok(TransitionOutput {
+        alloc,
+        result,
+        body: Bytes::from(included.encode_to_vec()),
+        traces,
+    })
+}
+
+/// Builds the header of the block being produced, deriving the base fee and the excess
+/// blob gas from the parent fields of the env when they are not given explicitly.
+fn block_header(env: &T8nEnv, chain_config: &ChainConfig) -> Result<BlockHeader, T8nError> {
+    let fork = chain_config.fork(env.current_timestamp);
+
+    let prev_randao = env.current_random.ok_or_else(|| {
+        T8nError::Config("post-merge requires currentRandom to be defined in env".to_string())
+    })?;
+    let base_fee_per_gas = match env.current_base_fee {
+        Some(base_fee) => base_fee,
+        None => match (
+            env.parent_base_fee,
+            env.parent_gas_used,
+            env.parent_gas_limit,
+        ) {
+            (Some(parent_base_fee), Some(parent_gas_used), Some(parent_gas_limit)) => {
+                calculate_base_fee_per_gas(
+                    env.current_gas_limit,
+                    parent_gas_limit,
+                    parent_gas_used,
+                    parent_base_fee,
+                    ELASTICITY_MULTIPLIER,
+                )
+                .ok_or_else(|| {
+                    T8nError::Config("invalid gas limit relative to the parent".to_string())
+                })?
+            }
+            _ => {
+                return Err(T8nError::Config(
+                    "EIP-1559 config but missing 'currentBaseFee' in env section".to_string(),
+                ));
+            }
+        },
+    };
+    let excess_blob_gas = if fork >= Fork::Cancun {
+        let excess_blob_gas = match env.current_excess_blob_gas {
+            Some(excess_blob_gas) => excess_blob_gas,
+            None => {
+                let parent = BlockHeader {
+                    base_fee_per_gas: env.parent_base_fee,
+                    excess_blob_gas: Some(env.parent_excess_blob_gas.unwrap_or_default()),
+                    blob_gas_used: Some(env.parent_blob_gas_used.unwrap_or_default()),
+                    ..Default::default()
+                };
+                match chain_config.get_fork_blob_schedule(env.current_timestamp) {
+                    Some(schedule) => calc_excess_blob_gas(&parent, schedule, fork),
+                    None => 0,
+                }
+            }
+        };
+        Some(excess_blob_gas)
+    } else {
+        None
+    };
+    let parent_hash = env
+        .parent_hash
+        .or_else(|| {
+            env.current_number
+                .checked_sub(1)
+                .and_then(|number| env.block_hashes.get(&number).copied())
+        })
+        .unwrap_or_default();
+
+    Ok(BlockHeader {
+        parent_hash,
+        coinbase: env.current_coinbase,
+        difficulty: env.current_difficulty.unwrap_or_default(),
+        number: env.current_number,
+        gas_limit: env.current_gas_limit,
+        timestamp: env.current_timestamp,
+        prev_randao,
+        base_fee_per_gas: Some(base_fee_per_gas),
+        withdrawals_root: (fork >= Fork::Shanghai).then(|| {
+            compute_withdrawals_root(
+                env.withdrawals.as_deref().unwrap_or_default(),
+                &NativeCrypto,
+            )
+        }),
+        excess_blob_gas,
+        parent_beacon_block_root: env.parent_beacon_block_root,
+        slot_number: env.slot_number,
+        ..Default::default()
+    })
+}
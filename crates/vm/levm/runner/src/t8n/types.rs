@@ -0,0 +1,494 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, Bloom, H256, U256, serde_utils,
+    types::{
+        AccessListEntry, AuthorizationTuple, EIP1559Transaction, EIP2930Transaction,
+        EIP4844Transaction, EIP7702Transaction, GenesisAccount, LegacyTransaction, Transaction,
+        TxKind, TxType, Withdrawal,
+    },
+    utils::keccak,
+};
+use ethrex_rlp::{encode::PayloadRLPEncode, structs::Encoder};
+use secp256k1::{Message, SECP256K1, SecretKey};
+use serde::{Deserialize, Deserializer, Serialize, de::Error};
+use std::collections::BTreeMap;
+
+/// Pre and post state of the transition, keyed by account address (`alloc.json`).
+pub type Alloc = BTreeMap<Address, GenesisAccount>;
+
+/// Block environment the transactions are executed in (`env.json`).
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nEnv {
+    pub current_coinbase: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub current_gas_limit: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub current_number: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub current_timestamp: u64,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub current_difficulty: Option<U256>,
+    #[serde(default)]
+    pub current_random: Option<H256>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub current_base_fee: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_base_fee: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_gas_used: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_gas_limit: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub current_excess_blob_gas: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_excess_blob_gas: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub parent_blob_gas_used: Option<u64>,
+    #[serde(default)]
+    pub parent_beacon_block_root: Option<H256>,
+    #[serde(default)]
+    pub parent_hash: Option<H256>,
+    #[serde(default, deserialize_with = "deserialize_block_hashes")]
+    pub block_hashes: BTreeMap<u64, H256>,
+    #[serde(default)]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub slot_number: Option<u64>,
+}
+
+/// `blockHashes` keys are block numbers, either hex (`0x..`) or decimal.
+fn deserialize_block_hashes<'de, D>(d: D) -> Result<BTreeMap<u64, H256>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = Option::<BTreeMap<String, H256>>::deserialize(d)?.unwrap_or_default();
+    raw.into_iter()
+        .map(|(number, hash)| {
+            let parsed = match number.strip_prefix("0x") {
+                Some(hex) => u64::from_str_radix(hex, 16),
+                None => number.parse(),
+            };
+            parsed
+                .map(|number| (number, hash))
+                .map_err(|_| D::Error::custom(format!("invalid block number {number}")))
+        })
+        .collect()
+}
+
+/// A transaction as found in `txs.json`: either signed (`v`, `r`, `s`) or carrying the
+/// `secretKey` to sign it with.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nTransaction {
+    #[serde(rename = "type", default)]
+    pub tx_type: Option<TxType>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub chain_id: Option<U256>,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub nonce: u64,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub gas_price: Option<U256>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub gas: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub gas_limit: Option<u64>,
+    #[serde(default, deserialize_with = "deserialize_to")]
+    pub to: Option<Address>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub value: Option<U256>,
+    #[serde(default, with = "serde_utils::bytes::opt")]
+    pub input: Option<Bytes>,
+    #[serde(default, with = "serde_utils::bytes::opt")]
+    pub data: Option<Bytes>,
+    #[serde(default)]
+    pub access_list: Option<Vec<AccessListEntry>>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub max_fee_per_blob_gas: Option<U256>,
+    #[serde(default)]
+    pub blob_versioned_hashes: Option<Vec<H256>>,
+    #[serde(default)]
+    pub authorization_list: Option<Vec<T8nAuthorization>>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub v: Option<U256>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub y_parity: Option<U256>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub r: Option<U256>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub s: Option<U256>,
+    #[serde(default)]
+    pub secret_key: Option<H256>,
+    /// Whether a legacy transaction signed with `secretKey` is EIP-155 replay protected.
+    #[serde(default = "default_protected")]
+    pub protected: bool,
+}
+
+fn default_protected() -> bool {
+    true
+}
+
+/// Contract creations come with a `null`, missing or empty `to`.
+fn deserialize_to<'de, D>(d: D) -> Result<Option<Address>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(d)? {
+        Some(to) if !to.is_empty() => to
+            .parse()
+            .map(Some)
+            .map_err(|_| D::Error::custom(format!("invalid address {to}"))),
+        _ => Ok(None),
+    }
+}
+
+/// EIP-7702 authorization tuple of a `txs.json` entry.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nAuthorization {
+    #[serde(deserialize_with = "serde_utils::u256::deser_hex_str")]
+    pub chain_id: U256,
+    pub address: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub nonce: u64,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub v: Option<U256>,
+    #[serde(default, with = "serde_utils::u256::hex_str_opt")]
+    pub y_parity: Option<U256>,
+    #[serde(deserialize_with = "serde_utils::u256::deser_hex_str")]
+    pub r: U256,
+    #[serde(deserialize_with = "serde_utils::u256::deser_hex_str")]
+    pub s: U256,
+}
+
+impl From<T8nAuthorization> for AuthorizationTuple {
+    fn from(auth: T8nAuthorization) -> Self {
+        AuthorizationTuple {
+            chain_id: auth.chain_id,
+            address: auth.address,
+            nonce: auth.nonce,
+            y_parity: auth.y_parity.or(auth.v).unwrap_or_default(),
+            r_signature: auth.r,
+            s_signature: auth.s,
+        }
+    }
+}
+
+impl T8nTransaction {
+    /// Builds the transaction, signing it when a `secretKey` is given.
+    ///
+    /// `chain_id` is used when the transaction doesn't specify one. Errors are reported
+    /// per transaction, so the caller rejects it instead of aborting the transition.
+    pub fn into_transaction(self, chain_id: u64) -> Result<Transaction, String> {
+        let tx_type = self
+            .tx_type
+            .unwrap_or(if self.authorization_list.is_some() {
+                TxType::EIP7702
+            } else if self.blob_versioned_hashes.is_some() {
+                TxType::EIP4844
+            } else if self.max_fee_per_gas.is_some() {
+                TxType::EIP1559
+            } else if self.access_list.is_some() {
+                TxType::EIP2930
+            } else {
+                TxType::Legacy
+            });
+
+        let chain_id = match self.chain_id {
+            Some(id) => to_u64(id, "chain id")?,
+            None => chain_id,
+        };
+        let gas = self
+            .gas
+            .or(self.gas_limit)
+            .ok_or("missing gas limit".to_string())?;
+        let to = self.to.map_or(TxKind::Create, TxKind::Call);
+        let value = self.value.unwrap_or_default();
+        let data = self.input.or(self.data).unwrap_or_default();
+        let access_list = self
+            .access_list
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| (entry.address, entry.storage_keys))
+            .collect();
+        let max_priority_fee_per_gas = to_u64(
+            self.max_priority_fee_per_gas.unwrap_or_default(),
+            "max priority fee per gas",
+        )?;
+        let max_fee_per_gas = to_u64(self.max_fee_per_gas.unwrap_or_default(), "max fee per gas")?;
+        let r = self.r.unwrap_or_default();
+        let s = self.s.unwrap_or_default();
+        let y_parity = !self.y_parity.or(self.v).unwrap_or_default().is_zero();
+
+        let mut tx = match tx_type {
+            TxType::Legacy => Transaction::LegacyTransaction(LegacyTransaction {
+                nonce: self.nonce,
+                gas_price: self.gas_price.unwrap_or_default(),
+                gas,
+                to,
+                value,
+                data,
+                v: self.v.unwrap_or_default(),
+                r,
+                s,
+                ..Default::default()
+            }),
+            TxType::EIP2930 => Transaction::EIP2930Transaction(EIP2930Transaction {
+                chain_id,
+                nonce: self.nonce,
+                gas_price: self.gas_price.unwrap_or_default(),
+                gas_limit: gas,
+                to,
+                value,
+                data,
+                access_list,
+                signature_y_parity: y_parity,
+                signature_r: r,
+                signature_s: s,
+                ..Default::default()
+            }),
+            TxType::EIP1559 => Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id,
+                nonce: self.nonce,
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas_limit: gas,
+                to,
+                value,
+                data,
+                access_list,
+                signature_y_parity: y_parity,
+                signature_r: r,
+                signature_s: s,
+                ..Default::default()
+            }),
+            TxType::EIP4844 => Transaction::EIP4844Transaction(EIP4844Transaction {
+                chain_id,
+                nonce: self.nonce,
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas,
+                to: call_target(to, "blob")?,
+                value,
+                data,
+                access_list,
+                max_fee_per_blob_gas: self.max_fee_per_blob_gas.unwrap_or_default(),
+                blob_versioned_hashes: self.blob_versioned_hashes.unwrap_or_default(),
+                signature_y_parity: y_parity,
+                signature_r: r,
+                signature_s: s,
+                ..Default::default()
+            }),
+            TxType::EIP7702 => Transaction::EIP7702Transaction(EIP7702Transaction {
+                chain_id,
+                nonce: self.nonce,
+                max_priority_fee_per_gas,
+                max_fee_per_gas,
+                gas_limit: gas,
+                to: call_target(to, "set code")?,
+                value,
+                data,
+                access_list,
+                authorization_list: self
+                    .authorization_list
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(AuthorizationTuple::from)
+                    .collect(),
+                signature_y_parity: y_parity,
+                signature_r: r,
+                signature_s: s,
+                ..Default::default()
+            }),
+            other => return Err(format!("unsupported transaction type {other:?}")),
+        };
+
+        if let Some(secret_key) = self.secret_key {
+            sign_transaction(&mut tx, &secret_key, self.protected, chain_id)?;
+        }
+        Ok(tx)
+    }
+}
+
+fn to_u64(value: U256, field: &str) -> Result<u64, String> {
+    u64::try_from(value).map_err(|_| format!("{field} higher than 2^64-1"))
+}
+
+fn call_target(to: TxKind, kind: &str) -> Result<Address, String> {
+    match to {
+        TxKind::Call(address) => Ok(address),
+        TxKind::Create => Err(format!("{kind} transaction cannot be a contract creation")),
+    }
+}
+
+/// Signs `tx` in place. Legacy transactions are signed following EIP-155 when `protected`.
+fn sign_transaction(
+    tx: &mut Transaction,
+    secret_key: &H256,
+    protected: bool,
+    chain_id: u64,
+) -> Result<(), String> {
+    let secret_key = SecretKey::from_slice(secret_key.as_bytes())
+        .map_err(|err| format!("invalid secret key: {err}"))?;
+    let sign = |payload: &[u8]| {
+        let digest = keccak(payload);
+        let (recovery_id, signature) = SECP256K1
+            .sign_ecdsa_recoverable(&Message::from_digest(digest.0), &secret_key)
+            .serialize_compact();
+        (
+            U256::from(i32::from(recovery_id)),
+            U256::from_big_endian(&signature[..32]),
+            U256::from_big_endian(&signature[32..]),
+        )
+    };
+    let typed_payload = |tx_type: TxType, payload: Vec<u8>| [vec![tx_type as u8], payload].concat();
+
+    match tx {
+        Transaction::LegacyTransaction(tx) => {
+            let (recovery_id, r, s) = if protected {
+                let mut payload = Vec::new();
+                Encoder::new(&mut payload)
+                    .encode_field(&tx.nonce)
+                    .encode_field(&tx.gas_price)
+                    .encode_field(&tx.gas)
+                    .encode_field(&tx.to)
+                    .encode_field(&tx.value)
+                    .encode_field(&tx.data)
+                    .encode_field(&chain_id)
+                    .encode_field(&0u8)
+                    .encode_field(&0u8)
+                    .finish();
+                sign(&payload)
+            } else {
+                sign(&tx.encode_payload_to_vec())
+            };
+            tx.v = if protected {
+                recovery_id + 35 + U256::from(chain_id) * 2
+            } else {
+                recovery_id + 27
+            };
+            (tx.r, tx.s) = (r, s);
+        }
+        Transaction::EIP2930Transaction(tx) => {
+            let (recovery_id, r, s) =
+                sign(&typed_payload(TxType::EIP2930, tx.encode_payload_to_vec()));
+            tx.signature_y_parity = !recovery_id.is_zero();
+            (tx.signature_r, tx.signature_s) = (r, s);
+        }
+        Transaction::EIP1559Transaction(tx) => {
+            let (recovery_id, r, s) =
+                sign(&typed_payload(TxType::EIP1559, tx.encode_payload_to_vec()));
+            tx.signature_y_parity = !recovery_id.is_zero();
+            (tx.signature_r, tx.signature_s) = (r, s);
+        }
+        Transaction::EIP4844Transaction(tx) => {
+            let (recovery_id, r, s) =
+                sign(&typed_payload(TxType::EIP4844, tx.encode_payload_to_vec()));
+            tx.signature_y_parity = !recovery_id.is_zero();
+            (tx.signature_r, tx.signature_s) = (r, s);
+        }
+        Transaction::EIP7702Transaction(tx) => {
+            let (recovery_id, r, s) =
+                sign(&typed_payload(TxType::EIP7702, tx.encode_payload_to_vec()));
+            tx.signature_y_parity = !recovery_id.is_zero();
+            (tx.signature_r, tx.signature_s) = (r, s);
+        }
+        _ => return Err("unsupported transaction type".to_string()),
+    }
+    Ok(())
+}
+
+/// A transaction that couldn't be included in the block.
+#[derive(Debug, Serialize)]
+pub struct RejectedTx {
+    pub index: usize,
+    pub error: String,
+}
+
+/// Result of the transition (`result.json`), following geth's `evm t8n` output.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionResult {
+    pub state_root: H256,
+    pub tx_root: H256,
+    pub receipts_root: H256,
+    pub logs_hash: H256,
+    pub logs_bloom: Bloom,
+    pub receipts: Vec<T8nReceipt>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub rejected: Vec<RejectedTx>,
+    /// Always `null`: only post-merge forks are supported.
+    pub current_difficulty: Option<U256>,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas_used: u64,
+    #[serde(with = "serde_utils::u64::hex_str_opt")]
+    pub current_base_fee: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<H256>,
+    #[serde(
+        with = "serde_utils::u64::hex_str_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub current_excess_blob_gas: Option<u64>,
+    #[serde(
+        with = "serde_utils::u64::hex_str_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub blob_gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests_hash: Option<H256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub requests: Option<Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nReceipt {
+    #[serde(rename = "type")]
+    pub tx_type: TxType,
+    #[serde(with = "serde_utils::bytes")]
+    pub root: Bytes,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub status: u64,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub cumulative_gas_used: u64,
+    pub logs_bloom: Bloom,
+    pub logs: Vec<T8nLog>,
+    pub transaction_hash: H256,
+    pub contract_address: Address,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub gas_used: u64,
+    pub effective_gas_price: U256,
+    #[serde(
+        with = "serde_utils::u64::hex_str_opt",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub blob_gas_used: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_gas_price: Option<U256>,
+    pub block_hash: H256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub transaction_index: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct T8nLog {
+    pub address: Address,
+    pub topics: Vec<H256>,
+    #[serde(with = "serde_utils::bytes")]
+    pub data: Bytes,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub block_number: u64,
+    pub transaction_hash: H256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub transaction_index: u64,
+    pub block_hash: H256,
+    #[serde(with = "serde_utils::u64::hex_str")]
+    pub log_index: u64,
+    pub removed: bool,
+}
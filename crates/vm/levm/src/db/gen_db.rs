@@ -19,6 +19,7 @@ use crate::account::LevmAccount;
 use crate::call_frame::CallFrameBackup;
 use crate::errors::InternalError;
 use crate::errors::VMError;
+use crate::precompiles::PrecompileRegistry;
 use crate::utils::account_to_levm_account;
 use crate::utils::restore_cache_state;
 use crate::vm::VM;
@@ -228,6 +229,9 @@ pub struct GeneralizedDatabase {
     /// Optional BAL cursor for lazy per-read prefix materialization.
     /// When set, account loads and storage reads consult the BAL before hitting the store.
     pub lazy_bal: Option<LazyBalCursor>,
+    /// Custom precompiles applied to the `EVMConfig` of every block or transaction executed
+    /// against this database.
+    pub precompiles: Option<&'static PrecompileRegistry>,
 }
 
 impl GeneralizedDatabase {
@@ -244,6 +248,7 @@ impl GeneralizedDatabase {
             skip_initial_tracking: false,
             accessed_accounts: None,
             lazy_bal: None,
+            precompiles: None,
         }
     }
 
@@ -277,6 +282,7 @@ impl GeneralizedDatabase {
             skip_initial_tracking: true,
             accessed_accounts: None,
             lazy_bal: None,
+            precompiles: None,
         }
     }
 
@@ -336,6 +342,7 @@ impl GeneralizedDatabase {
             skip_initial_tracking: false,
             accessed_accounts: None,
             lazy_bal: None,
+            precompiles: None,
         }
     }
 
@@ -873,6 +880,8 @@ impl<'a> VM<'a> {
             recorder.set_initial_balance(address, initial_balance);
             recorder.record_balance_change(address, new_balance);
         }
+        self.tracer
+            .on_balance_change(address, initial_balance, new_balance);
 
         Ok(())
     }
@@ -903,6 +912,8 @@ impl<'a> VM<'a> {
             recorder.set_initial_balance(address, initial_balance);
             recorder.record_balance_change(address, new_balance);
         }
+        self.tracer
+            .on_balance_change(address, initial_balance, new_balance);
 
         Ok(())
     }
@@ -1119,6 +1130,10 @@ impl<'a> VM<'a> {
 
         let account = self.get_account_mut(address)?;
         account.storage.insert(key, new_value);
+        if new_value != current_value {
+            self.tracer
+                .on_storage_change(address, key, current_value, new_value);
+        }
         Ok(())
     }
 
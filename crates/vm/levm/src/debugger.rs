@@ -0,0 +1,276 @@
+//! Step debugger built on the [`Tracer`] callbacks.
+//!
+//! [`Debugger`] is installed on the VM like any other tracer. Before each opcode it decides
+//! whether execution has to stop and, if so, hands control to a [`DebuggerFrontend`], which
+//! inspects the paused state, edits the breakpoints and picks how to resume. The VM is
+//! blocked for as long as the frontend doesn't return, which is what makes an interactive
+//! frontend (such as the one of the levm runner) possible.
+
+use crate::{
+    call_frame::CallFrame,
+    errors::{ContextResult, InternalError, VMError},
+    opcodes::Opcode,
+    tracing::Tracer,
+};
+use bytes::Bytes;
+use ethrex_common::{Address, H256, U256, tracing::CallType};
+use std::collections::BTreeMap;
+
+/// Condition that stops execution when it's met.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Before executing the opcode at this program counter, in any frame.
+    Pc(usize),
+    /// Before executing this opcode.
+    Opcode(u8),
+    /// At the first opcode of a frame that runs at, or runs the code of, this address.
+    Address(Address),
+    /// Before an SLOAD or SSTORE of `slot`, of `address` when given.
+    Storage {
+        address: Option<Address>,
+        slot: H256,
+    },
+}
+
+impl Breakpoint {
+    fn is_hit(&self, pc: usize, opcode: u8, frame: &CallFrame, entering_frame: bool) -> bool {
+        match *self {
+            Breakpoint::Pc(breakpoint_pc) => pc == breakpoint_pc,
+            Breakpoint::Opcode(breakpoint_opcode) => opcode == breakpoint_opcode,
+            Breakpoint::Address(address) => {
+                entering_frame && (frame.to == address || frame.code_address == address)
+            }
+            Breakpoint::Storage { address, slot } => {
+                is_storage_access(opcode)
+                    && address.is_none_or(|address| address == frame.to)
+                    && stack_top(frame).is_some_and(|key| H256(key.to_big_endian()) == slot)
+            }
+        }
+    }
+}
+
+/// How to resume execution after a stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StepMode {
+    /// Stop at the next opcode, entering calls.
+    Step,
+    /// Stop at the next opcode of the current frame or of a caller, stepping over calls.
+    Next,
+    /// Run until a breakpoint is hit.
+    Continue,
+}
+
+/// A call frame on the call stack tracked by the [`Debugger`].
+#[derive(Debug, Clone)]
+pub struct DebugCall {
+    pub call_type: CallType,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub gas: u64,
+    pub input: Bytes,
+}
+
+/// The paused execution, as handed to [`DebuggerFrontend::on_stop`].
+pub struct DebugStop<'a> {
+    /// Program counter of the opcode about to be executed.
+    pub pc: usize,
+    /// Opcode about to be executed.
+    pub opcode: u8,
+    /// Frame executing the opcode. Gas, stack and memory are as they are before the opcode.
+    pub frame: &'a CallFrame,
+    /// Call stack, from the top-level call to the frame executing the opcode.
+    pub calls: &'a [DebugCall],
+    /// Storage slots read or written so far in the transaction, with their current value.
+    pub storage: &'a BTreeMap<(Address, H256), U256>,
+    /// Number of opcodes executed before this one.
+    pub steps: u64,
+    /// Index of the breakpoint that stopped execution, if any.
+    pub breakpoint: Option<usize>,
+}
+
+impl DebugStop<'_> {
+    /// Stack of the frame, top first.
+    pub fn stack(&self) -> &[U256] {
+        self.frame
+            .stack
+            .values
+            .get(self.frame.stack.offset..)
+            .unwrap_or_default()
+    }
+
+    /// Memory of the frame.
+    pub fn memory(&self) -> Vec<u8> {
+        self.frame.memory.live_bytes()
+    }
+}
+
+/// Drives a [`Debugger`] whenever execution stops.
+pub trait DebuggerFrontend: 'static {
+    /// Execution stopped before an opcode. The frontend can inspect `stop` and edit
+    /// `breakpoints`, then returns how to resume.
+    fn on_stop(&mut self, stop: &DebugStop<'_>, breakpoints: &mut Vec<Breakpoint>) -> StepMode;
+
+    /// The opcode at `pc` halted the frame at `depth` with `error`, REVERT included.
+    fn on_fault(&mut self, _pc: usize, _opcode: u8, _depth: usize, _error: &VMError) {}
+}
+
+/// Pending SLOAD whose result is read from the stack at the next opcode of the same frame.
+struct PendingLoad {
+    depth: usize,
+    address: Address,
+    slot: H256,
+}
+
+/// Tracer that pauses execution on breakpoints and steps, see the [module docs](self).
+pub struct Debugger<F: DebuggerFrontend> {
+    pub frontend: F,
+    pub breakpoints: Vec<Breakpoint>,
+    mode: StepMode,
+    /// Depth at which a `Next` stops, the depth of the frame it was issued from.
+    next_depth: usize,
+    calls: Vec<DebugCall>,
+    /// Set by `on_enter` until the first opcode of the new frame runs.
+    entering_frame: bool,
+    storage: BTreeMap<(Address, H256), U256>,
+    pending_load: Option<PendingLoad>,
+    steps: u64,
+}
+
+impl<F: DebuggerFrontend> Debugger<F> {
+    /// Creates a debugger that stops at the first opcode.
+    pub fn new(frontend: F) -> Self {
+        Self::with_mode(frontend, StepMode::Step)
+    }
+
+    /// Creates a debugger that starts executing in `mode`.
+    pub fn with_mode(frontend: F, mode: StepMode) -> Self {
+        Self {
+            frontend,
+            breakpoints: Vec::new(),
+            mode,
+            next_depth: 0,
+            calls: Vec::new(),
+            entering_frame: false,
+            storage: BTreeMap::new(),
+            pending_load: None,
+            steps: 0,
+        }
+    }
+
+    pub fn with_breakpoints(mut self, breakpoints: Vec<Breakpoint>) -> Self {
+        self.breakpoints = breakpoints;
+        self
+    }
+
+    /// Storage slots read or written during the execution, with their last value.
+    pub fn storage(&self) -> &BTreeMap<(Address, H256), U256> {
+        &self.storage
+    }
+
+    fn should_stop(&self, frame: &CallFrame) -> bool {
+        match self.mode {
+            StepMode::Step => true,
+            StepMode::Next => frame.depth <= self.next_depth,
+            StepMode::Continue => false,
+        }
+    }
+}
+
+impl<F: DebuggerFrontend> Tracer for Debugger<F> {
+    fn on_enter(
+        &mut self,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        self.calls.push(DebugCall {
+            call_type,
+            from,
+            to,
+            value,
+            gas,
+            input: input.clone(),
+        });
+        self.entering_frame = true;
+    }
+
+    fn on_exit(
+        &mut self,
+        _ctx_result: &ContextResult,
+        _is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        self.calls.pop();
+        self.entering_frame = false;
+        Ok(())
+    }
+
+    fn on_exit_early(
+        &mut self,
+        _gas_used: u64,
+        _error: Option<String>,
+    ) -> Result<(), InternalError> {
+        self.calls.pop();
+        self.entering_frame = false;
+        Ok(())
+    }
+
+    fn on_opcode(&mut self, pc: usize, opcode: u8, frame: &CallFrame) {
+        if let Some(load) = self.pending_load.take()
+            && load.depth == frame.depth
+            && let Some(value) = stack_top(frame)
+        {
+            self.storage.insert((load.address, load.slot), value);
+        }
+        if opcode == u8::from(Opcode::SLOAD)
+            && let Some(key) = stack_top(frame)
+        {
+            self.pending_load = Some(PendingLoad {
+                depth: frame.depth,
+                address: frame.to,
+                slot: H256(key.to_big_endian()),
+            });
+        }
+
+        let entering_frame = std::mem::take(&mut self.entering_frame);
+        let breakpoint = self
+            .breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.is_hit(pc, opcode, frame, entering_frame));
+
+        if breakpoint.is_some() || self.should_stop(frame) {
+            let stop = DebugStop {
+                pc,
+                opcode,
+                frame,
+                calls: &self.calls,
+                storage: &self.storage,
+                steps: self.steps,
+                breakpoint,
+            };
+            self.mode = self.frontend.on_stop(&stop, &mut self.breakpoints);
+            self.next_depth = frame.depth;
+        }
+        self.steps = self.steps.saturating_add(1);
+    }
+
+    fn on_fault(&mut self, pc: usize, opcode: u8, error: &VMError, frame: &CallFrame) {
+        self.pending_load = None;
+        self.frontend.on_fault(pc, opcode, frame.depth, error);
+    }
+
+    fn on_storage_change(&mut self, address: Address, key: H256, _previous: U256, new: U256) {
+        self.storage.insert((address, key), new);
+    }
+}
+
+fn is_storage_access(opcode: u8) -> bool {
+    opcode == u8::from(Opcode::SLOAD) || opcode == u8::from(Opcode::SSTORE)
+}
+
+fn stack_top(frame: &CallFrame) -> Option<U256> {
+    frame.stack.values.get(frame.stack.offset).copied()
+}
@@ -7,6 +7,7 @@ use crate::constants::{
     BLOB_BASE_FEE_UPDATE_FRACTION, BLOB_BASE_FEE_UPDATE_FRACTION_PRAGUE, MAX_BLOB_COUNT,
     MAX_BLOB_COUNT_ELECTRA, TARGET_BLOB_GAS_PER_BLOCK, TARGET_BLOB_GAS_PER_BLOCK_PECTRA,
 };
+use crate::precompiles::PrecompileRegistry;
 
 use rustc_hash::FxHashMap;
 /// [EIP-1153]: https://eips.ethereum.org/EIPS/eip-1153#reference-implementation
@@ -62,6 +63,8 @@ pub struct Environment {
 pub struct EVMConfig {
     pub fork: Fork,
     pub blob_schedule: ForkBlobSchedule,
+    /// Custom precompiles on top of the builtin set of `fork`, if any.
+    pub precompiles: Option<&'static PrecompileRegistry>,
 }
 
 impl EVMConfig {
@@ -69,9 +72,16 @@ impl EVMConfig {
         EVMConfig {
             fork,
             blob_schedule,
+            precompiles: None,
         }
     }
 
+    /// Sets the registry of custom precompiles used on top of the builtin ones.
+    pub fn with_precompiles(mut self, precompiles: Option<&'static PrecompileRegistry>) -> Self {
+        self.precompiles = precompiles;
+        self
+    }
+
     pub fn new_from_chain_config(chain_config: &ChainConfig, block_header: &BlockHeader) -> Self {
         let fork = chain_config.fork(block_header.timestamp);
 
@@ -132,6 +142,7 @@ impl Default for EVMConfig {
         EVMConfig {
             fork,
             blob_schedule: Self::canonical_values(fork),
+            precompiles: None,
         }
     }
 }
@@ -39,6 +39,7 @@
 //! - [`environment::Environment`]: Block and transaction context
 //! - [`precompiles`]: Native implementations of precompiled contracts
 //! - [`hooks`]: Execution hooks for pre/post-execution logic and L2-specific behavior
+//! - [`tracing::Tracer`]: Execution callbacks for built-in and user-defined tracers
 //!
 //! ## Supported Forks
 //!
@@ -68,6 +69,7 @@ pub mod call_frame;
 pub mod constants;
 pub mod db;
 pub mod debug;
+pub mod debugger;
 pub mod environment;
 pub mod errors;
 pub mod execution_handlers;
@@ -47,7 +47,7 @@ impl<const N: usize> OpcodeHandler for OpLogHandler<N> {
             topics: topics.into(),
             data: vm.current_call_frame.memory.load_range(offset, len)?,
         };
-        vm.tracer.log(&log)?;
+        vm.tracer.on_log(&log)?;
         vm.substate.add_log(log);
 
         Ok(OpcodeResult::Continue)
@@ -449,6 +449,9 @@ fn jump(vm: &mut VM<'_>, target: usize, parent_gas_cost: u64) -> Result<(), VMEr
                     .is_ok()
         })
     {
+        // The fused JUMPDEST is reported like any other executed opcode.
+        vm.tracer
+            .on_opcode(target, Opcode::JUMPDEST as u8, &vm.current_call_frame);
         if vm.opcode_tracer.active {
             // Override the parent JUMP/JUMPI's gasCost so the dispatch loop
             // doesn't roll the upcoming JUMPDEST charge into it.
@@ -18,13 +18,13 @@ use crate::{
     gas_cost,
     memory::{self, calculate_memory_size},
     opcode_handlers::OpcodeHandler,
-    precompiles,
     utils::{address_to_word, create_burn_log, create_eth_transfer_log, word_to_address, *},
     vm::VM,
 };
 use bytes::Bytes;
 use ethrex_common::{Address, H256, U256, evm::calculate_create_address, types::Fork};
 use ethrex_common::{tracing::CallType, types::Code};
+use std::mem;
 
 pub struct OpCallHandler;
 impl OpcodeHandler for OpCallHandler {
@@ -173,7 +173,7 @@ impl OpcodeHandler for OpCallHandler {
 
         // Trace CALL operation.
         let data = vm.get_calldata(args_offset, args_len)?;
-        vm.tracer.enter(
+        vm.tracer.on_enter(
             CallType::CALL,
             vm.current_call_frame.to,
             callee,
@@ -288,7 +288,7 @@ impl OpcodeHandler for OpCallCodeHandler {
 
         // Trace CALL operation.
         let data = vm.get_calldata(args_offset, args_len)?;
-        vm.tracer.enter(
+        vm.tracer.on_enter(
             CallType::CALLCODE,
             vm.current_call_frame.to,
             code_address,
@@ -392,7 +392,7 @@ impl OpcodeHandler for OpDelegateCallHandler {
         let data = vm.get_calldata(args_offset, args_len)?;
         // In this trace the `from` is the current contract, we don't want the `from` to be,
         // for example, the EOA that sent the transaction.
-        vm.tracer.enter(
+        vm.tracer.on_enter(
             CallType::DELEGATECALL,
             vm.current_call_frame.to,
             code_address,
@@ -494,7 +494,7 @@ impl OpcodeHandler for OpStaticCallHandler {
 
         // Trace CALL operation.
         let data = vm.get_calldata(args_offset, args_len)?;
-        vm.tracer.enter(
+        vm.tracer.on_enter(
             CallType::STATICCALL,
             vm.current_call_frame.to,
             address,
@@ -688,7 +688,10 @@ impl OpcodeHandler for OpSelfDestructHandler {
             // Selfdestruct is executed in the same transaction as the contract was created
             if vm.substate.is_account_created(&to) {
                 // If target is the same as the contract calling, Ether will be burnt.
-                vm.get_account_mut(to)?.info.balance = U256::zero();
+                let burnt = mem::take(&mut vm.get_account_mut(to)?.info.balance);
+                if !burnt.is_zero() {
+                    vm.tracer.on_balance_change(to, burnt, U256::zero());
+                }
 
                 // Record balance change to zero for destroyed account in BAL
                 if let Some(recorder) = vm.db.bal_recorder.as_mut() {
@@ -712,7 +715,10 @@ impl OpcodeHandler for OpSelfDestructHandler {
             }
         } else {
             vm.increase_account_balance(beneficiary, balance)?;
-            vm.get_account_mut(to)?.info.balance = U256::zero();
+            let previous = mem::take(&mut vm.get_account_mut(to)?.info.balance);
+            if !previous.is_zero() {
+                vm.tracer.on_balance_change(to, previous, U256::zero());
+            }
 
             // Record balance change to zero for destroyed account in BAL
             if let Some(recorder) = vm.db.bal_recorder.as_mut() {
@@ -722,7 +728,7 @@ impl OpcodeHandler for OpSelfDestructHandler {
             vm.substate.add_selfdestruct(to);
         }
 
-        vm.tracer.enter(
+        vm.tracer.on_enter(
             CallType::SELFDESTRUCT,
             vm.current_call_frame.to,
             beneficiary,
@@ -730,7 +736,7 @@ impl OpcodeHandler for OpSelfDestructHandler {
             0,
             &Default::default(),
         );
-        vm.tracer.exit_early(0, None)?;
+        vm.tracer.on_exit_early(0, None)?;
 
         Ok(OpcodeResult::Halt)
     }
@@ -821,7 +827,7 @@ impl<'a> VM<'a> {
             None => CallType::CREATE,
         };
         self.tracer
-            .enter(call_type, deployer, new_address, value, gas_limit, &code);
+            .on_enter(call_type, deployer, new_address, value, gas_limit, &code);
 
         let new_depth = self
             .current_call_frame
@@ -873,7 +879,7 @@ impl<'a> VM<'a> {
             }
             self.current_call_frame.stack.push(FAIL)?;
             self.tracer
-                .exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
+                .on_exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
             return Ok(OpcodeResult::Continue);
         }
 
@@ -1061,7 +1067,7 @@ impl<'a> VM<'a> {
             return Ok(OpcodeResult::Continue);
         }
 
-        if precompiles::is_precompile(&code_address, self.env.config.fork, self.vm_type)
+        if let Some(precompile_address) = self.precompile_at(&code_address)
             && !is_delegation_7702
         {
             // Record precompile address touch for BAL per EIP-7928
@@ -1071,11 +1077,11 @@ impl<'a> VM<'a> {
 
             let mut gas_remaining = gas_limit;
             let ctx_result = Self::execute_precompile(
-                code_address,
+                precompile_address,
                 &calldata,
                 gas_limit,
                 &mut gas_remaining,
-                self.env.config.fork,
+                &self.env.config,
                 self.db.store.precompile_cache(),
                 self.crypto,
             )?;
@@ -1127,7 +1133,7 @@ impl<'a> VM<'a> {
                 }
             }
 
-            self.tracer.exit_context(&ctx_result, false)?;
+            self.tracer.on_exit(&ctx_result, false)?;
         } else {
             // Create BAL checkpoint before entering nested call for potential revert per EIP-7928
             let bal_checkpoint = self.db.bal_recorder.as_ref().map(|r| r.checkpoint());
@@ -1292,7 +1298,7 @@ impl<'a> VM<'a> {
             }
         };
 
-        self.tracer.exit_context(ctx_result, false)?;
+        self.tracer.on_exit(ctx_result, false)?;
 
         let mut stack = stack;
         stack.clear();
@@ -1361,7 +1367,7 @@ impl<'a> VM<'a> {
             }
         };
 
-        self.tracer.exit_context(ctx_result, false)?;
+        self.tracer.on_exit(ctx_result, false)?;
 
         let mut stack = stack;
         stack.clear();
@@ -1385,7 +1391,7 @@ impl<'a> VM<'a> {
             .ok_or(InternalError::Overflow)?;
         callframe.stack.push(FAIL)?; // It's the same as revert for CREATE
 
-        self.tracer.exit_early(0, Some(reason))?;
+        self.tracer.on_exit_early(0, Some(reason))?;
         Ok(())
     }
 }
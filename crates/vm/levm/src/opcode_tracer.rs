@@ -62,6 +62,9 @@ pub struct LevmOpcodeTracer {
     /// frames (consistent with how slot keys are indexed — by slot only, not by
     /// `(address, slot)` — so cross-frame frame isolation is a separate concern).
     pub cumulative_storage: BTreeMap<H256, H256>,
+    /// Code run by each frame with at least one captured step, in the order the frames
+    /// were entered.
+    pub frame_codes: Vec<Bytes>,
 }
 
 impl LevmOpcodeTracer {
@@ -77,6 +80,7 @@ impl LevmOpcodeTracer {
             last_opcode_gas_cost: None,
             last_step_index: None,
             cumulative_storage: BTreeMap::new(),
+            frame_codes: Vec::new(),
         }
     }
 
@@ -92,6 +96,7 @@ impl LevmOpcodeTracer {
             last_opcode_gas_cost: None,
             last_step_index: None,
             cumulative_storage: BTreeMap::new(),
+            frame_codes: Vec::new(),
         }
     }
 
@@ -108,6 +113,9 @@ impl LevmOpcodeTracer {
     ///
     /// `storage_kv` is pre-fetched by the caller via `read_storage_for_trace`; it is
     /// `None` for all opcodes except SLOAD/SSTORE (or when storage capture is disabled).
+    ///
+    /// `code` is the code run by the current frame, recorded in `frame_codes` when this
+    /// is the first step of the frame.
     #[expect(
         clippy::too_many_arguments,
         reason = "all fields are required per-step state from the dispatch-loop hook"
@@ -124,6 +132,7 @@ impl LevmOpcodeTracer {
         mem_size: u64,
         return_data: &Bytes,
         storage_kv: Option<(H256, H256)>,
+        code: &Bytes,
     ) {
         // Update the cumulative storage map BEFORE the limit check so that the
         // observed slot value is preserved even when a later step is dropped by
@@ -164,6 +173,11 @@ impl LevmOpcodeTracer {
             log.storage = Some(self.cumulative_storage.clone());
         }
 
+        // A step deeper than the previous one is the first step of a frame it entered
+        if self.logs.last().is_none_or(|last| depth > last.depth) {
+            self.frame_codes.push(code.clone());
+        }
+
         self.last_step_index = Some(self.logs.len());
         self.logs.push(log);
     }
@@ -211,6 +225,7 @@ impl LevmOpcodeTracer {
             gas_used: self.gas_used,
             output: std::mem::take(&mut self.output),
             steps: std::mem::take(&mut self.logs),
+            frame_codes: std::mem::take(&mut self.frame_codes),
         }
     }
 }
@@ -5,7 +5,7 @@ use ethrex_common::{
     Address, H256, U256, types::Fork, types::Fork::*, utils::u256_from_big_endian,
 };
 use ethrex_crypto::{Crypto, CryptoError};
-use rustc_hash::FxHashMap;
+use rustc_hash::{FxHashMap, FxHashSet};
 use std::borrow::Cow;
 use std::sync::RwLock;
 
@@ -259,6 +259,103 @@ pub fn is_precompile(address: &Address, fork: Fork, vm_type: VMType) -> bool {
         || precompiles_for_fork(fork).any(|precompile| precompile.address == *address)
 }
 
+/// Signature shared by every precompile implementation. Implementations charge their own gas
+/// through [`increase_precompile_consumed_gas`].
+pub type PrecompileFn = fn(&Bytes, &mut u64, Fork, &dyn Crypto) -> Result<Bytes, VMError>;
+
+/// A precompile registered in a [`PrecompileRegistry`].
+#[derive(Debug, Clone, Copy)]
+pub enum RegisteredPrecompile {
+    /// A builtin precompile moved away from its canonical address. It stays subject to the
+    /// activation rules of its canonical address.
+    Builtin(Address),
+    /// An embedder-provided precompile, active on every fork.
+    Custom {
+        name: &'static str,
+        precompile: PrecompileFn,
+    },
+}
+
+/// Precompiles added, overridden or moved on top of the builtin set selected by fork, so that
+/// embedders such as L2s can ship app-specific precompiles.
+///
+/// An address with a registered precompile ignores the builtin set entirely. Registered
+/// addresses follow the regular EIP-2929 warmth rules: only builtin addresses start warm.
+#[derive(Debug, Clone, Default)]
+pub struct PrecompileRegistry {
+    precompiles: FxHashMap<Address, RegisteredPrecompile>,
+    /// Builtin addresses whose precompile was moved away.
+    removed: FxHashSet<Address>,
+}
+
+impl PrecompileRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `precompile` at `address`, overriding any precompile that ran there.
+    pub fn with_precompile(
+        mut self,
+        address: Address,
+        name: &'static str,
+        precompile: PrecompileFn,
+    ) -> Self {
+        self.removed.remove(&address);
+        self.precompiles
+            .insert(address, RegisteredPrecompile::Custom { name, precompile });
+        self
+    }
+
+    /// Moves the precompile running at `from`, builtin or registered, to `to`. After the move
+    /// `from` is no longer a precompile.
+    pub fn with_moved_precompile(mut self, from: Address, to: Address) -> Self {
+        let precompile = self
+            .precompiles
+            .remove(&from)
+            .unwrap_or(RegisteredPrecompile::Builtin(from));
+        self.removed.insert(from);
+        self.removed.remove(&to);
+        self.precompiles.insert(to, precompile);
+        self
+    }
+
+    /// Returns the precompile registered at `address`, if any.
+    pub fn get(&self, address: &Address) -> Option<&RegisteredPrecompile> {
+        self.precompiles.get(address)
+    }
+
+    /// Like [`is_precompile`], taking the registered precompiles into account.
+    pub fn is_precompile(&self, address: &Address, fork: Fork, vm_type: VMType) -> bool {
+        match self.precompiles.get(address) {
+            Some(RegisteredPrecompile::Custom { .. }) => true,
+            Some(RegisteredPrecompile::Builtin(original)) => is_precompile(original, fork, vm_type),
+            None => !self.removed.contains(address) && is_precompile(address, fork, vm_type),
+        }
+    }
+
+    /// Like [`execute_precompile`], dispatching registered addresses to their precompile.
+    /// Custom precompile results are not cached.
+    pub fn execute_precompile(
+        &self,
+        address: Address,
+        calldata: &Bytes,
+        gas_remaining: &mut u64,
+        fork: Fork,
+        cache: Option<&PrecompileCache>,
+        crypto: &dyn Crypto,
+    ) -> Result<Bytes, VMError> {
+        match self.precompiles.get(&address) {
+            Some(RegisteredPrecompile::Custom { precompile, .. }) => {
+                precompile(calldata, gas_remaining, fork, crypto)
+            }
+            Some(RegisteredPrecompile::Builtin(original)) => {
+                execute_precompile(*original, calldata, gas_remaining, fork, cache, crypto)
+            }
+            None => execute_precompile(address, calldata, gas_remaining, fork, cache, crypto),
+        }
+    }
+}
+
 /// Per-block cache for precompile results shared between warmer and executor.
 pub struct PrecompileCache {
     cache: RwLock<FxHashMap<(Address, Bytes), (Bytes, u64)>>,
@@ -305,8 +402,6 @@ pub fn execute_precompile(
     cache: Option<&PrecompileCache>,
     crypto: &dyn Crypto,
 ) -> Result<Bytes, VMError> {
-    type PrecompileFn = fn(&Bytes, &mut u64, Fork, &dyn Crypto) -> Result<Bytes, VMError>;
-
     const PRECOMPILES: [Option<PrecompileFn>; 512] = const {
         let mut precompiles = [const { None }; 512];
         precompiles[ECRECOVER.address.0[19] as usize] = Some(ecrecover as PrecompileFn);
@@ -381,7 +476,7 @@ pub fn execute_precompile(
 }
 
 /// Consumes gas and if it's higher than the gas limit returns an error.
-pub(crate) fn increase_precompile_consumed_gas(
+pub fn increase_precompile_consumed_gas(
     gas_cost: u64,
     gas_remaining: &mut u64,
 ) -> Result<(), VMError> {
@@ -1,14 +1,286 @@
 pub use crate::opcode_tracer::{LevmOpcodeTracer, OpcodeTracerConfig};
 use crate::{
-    errors::{ContextResult, InternalError, TxResult, VMError},
-    vm::VM,
+    Environment,
+    call_frame::CallFrame,
+    errors::{ContextResult, ExecutionReport, InternalError, TxResult, VMError},
+    precompiles::is_precompile,
+    vm::{VM, VMType},
 };
 use bytes::Bytes;
 use ethrex_common::{
-    Address, U256,
-    tracing::{CallLog, CallTraceFrame, CallType},
-    types::Log,
+    Address, H256, U256,
+    tracing::{CallLog, CallTraceFrame, CallType, FourByteTrace},
+    types::{Fork, Log, Transaction},
 };
+use std::{any::Any, mem};
+
+/// Callbacks emitted by the VM while it executes a transaction.
+///
+/// This is the extension point for in-process tracers: implement it and pass the tracer to
+/// [`VM::new`] (or `Evm::execute_tx_with_tracer`), then get it back with [`VM::take_tracer`]
+/// and [`downcast_tracer`] once execution is over. Every LEVM-native tracer implements it too,
+/// which lets [`MuxTracer`] fan the callbacks out to several tracers during a single execution.
+///
+/// All callbacks default to doing nothing, so a tracer only implements the ones it needs.
+/// When no tracer is installed the VM skips the callbacks with a single branch each.
+///
+/// Callbacks fire in execution order: `on_tx_start`, `on_enter` for the top-level call,
+/// then `on_opcode` for each executed opcode interleaved with the callbacks of nested calls
+/// and state changes, the top-level `on_exit` and finally `on_tx_end`. State changes of
+/// frames that revert are reported as they happen and not undone afterwards; a reverted
+/// frame is the one whose `on_exit` carries a [`TxResult::Revert`].
+pub trait Tracer: Any {
+    /// Inactive tracers are not installed on the VM, so untraced execution neither allocates
+    /// nor dispatches dynamically.
+    fn is_active(&self) -> bool {
+        true
+    }
+
+    /// The transaction starts executing, before it is validated.
+    fn on_tx_start(&mut self, _env: &Environment, _tx: &Transaction) {}
+
+    /// The transaction finished executing. Invalid transactions end with an error.
+    fn on_tx_end(&mut self, _result: Result<&ExecutionReport, &VMError>) {}
+
+    /// A call frame starts, including the top-level call of the transaction.
+    fn on_enter(
+        &mut self,
+        _call_type: CallType,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+    }
+
+    /// A call frame finished executing.
+    fn on_exit(
+        &mut self,
+        _ctx_result: &ContextResult,
+        _is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        Ok(())
+    }
+
+    /// CALL or CREATE returned without executing code, or SELFDESTRUCT was executed.
+    fn on_exit_early(
+        &mut self,
+        _gas_used: u64,
+        _error: Option<String>,
+    ) -> Result<(), InternalError> {
+        Ok(())
+    }
+
+    /// The opcode at `pc` is about to be executed in `frame`. Gas, stack and memory are
+    /// observed as they are before the opcode runs.
+    fn on_opcode(&mut self, _pc: usize, _opcode: u8, _frame: &CallFrame) {}
+
+    /// The opcode at `pc` halted `frame` with `error`, REVERT included.
+    fn on_fault(&mut self, _pc: usize, _opcode: u8, _error: &VMError, _frame: &CallFrame) {}
+
+    /// A LOG opcode was executed.
+    fn on_log(&mut self, _log: &Log) -> Result<(), InternalError> {
+        Ok(())
+    }
+
+    /// A storage slot of `address` changed from `previous` to `new`.
+    fn on_storage_change(&mut self, _address: Address, _key: H256, _previous: U256, _new: U256) {}
+
+    /// The balance of `address` changed from `previous` to `new`.
+    fn on_balance_change(&mut self, _address: Address, _previous: U256, _new: U256) {}
+}
+
+impl Tracer for Box<dyn Tracer> {
+    fn is_active(&self) -> bool {
+        (**self).is_active()
+    }
+
+    fn on_tx_start(&mut self, env: &Environment, tx: &Transaction) {
+        (**self).on_tx_start(env, tx)
+    }
+
+    fn on_tx_end(&mut self, result: Result<&ExecutionReport, &VMError>) {
+        (**self).on_tx_end(result)
+    }
+
+    fn on_enter(
+        &mut self,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        (**self).on_enter(call_type, from, to, value, gas, input)
+    }
+
+    fn on_exit(
+        &mut self,
+        ctx_result: &ContextResult,
+        is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        (**self).on_exit(ctx_result, is_top_call)
+    }
+
+    fn on_exit_early(&mut self, gas_used: u64, error: Option<String>) -> Result<(), InternalError> {
+        (**self).on_exit_early(gas_used, error)
+    }
+
+    fn on_opcode(&mut self, pc: usize, opcode: u8, frame: &CallFrame) {
+        (**self).on_opcode(pc, opcode, frame)
+    }
+
+    fn on_fault(&mut self, pc: usize, opcode: u8, error: &VMError, frame: &CallFrame) {
+        (**self).on_fault(pc, opcode, error, frame)
+    }
+
+    fn on_log(&mut self, log: &Log) -> Result<(), InternalError> {
+        (**self).on_log(log)
+    }
+
+    fn on_storage_change(&mut self, address: Address, key: H256, previous: U256, new: U256) {
+        (**self).on_storage_change(address, key, previous, new)
+    }
+
+    fn on_balance_change(&mut self, address: Address, previous: U256, new: U256) {
+        (**self).on_balance_change(address, previous, new)
+    }
+}
+
+/// Recovers the concrete tracer from a boxed one, e.g. after [`VM::take_tracer`].
+pub fn downcast_tracer<T: Tracer>(tracer: Box<dyn Tracer>) -> Option<T> {
+    let tracer: Box<dyn Any> = tracer;
+    tracer.downcast().ok().map(|tracer| *tracer)
+}
+
+/// The tracer installed on a [`VM`], empty when tracing is disabled.
+///
+/// Each callback is forwarded only when a tracer is installed, so with none the cost of a
+/// callback site is a single branch on a field that never changes during execution.
+#[derive(Default)]
+pub struct VmTracer(Option<Box<dyn Tracer>>);
+
+impl VmTracer {
+    pub fn new(tracer: impl Tracer) -> Self {
+        if !tracer.is_active() {
+            return Self(None);
+        }
+        let mut tracer: Box<dyn Tracer> = Box::new(tracer);
+        // Tracers selected at runtime come in already boxed, unwrap them instead of nesting
+        // so they can be downcast back to their concrete type.
+        let any: &mut dyn Any = &mut *tracer;
+        if let Some(inner) = any.downcast_mut::<Box<dyn Tracer>>() {
+            tracer = mem::replace(inner, Box::new(NoopTracer));
+        }
+        Self(Some(tracer))
+    }
+
+    #[inline(always)]
+    pub fn is_active(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Returns the installed tracer if it is a `T`.
+    pub fn downcast_mut<T: Tracer>(&mut self) -> Option<&mut T> {
+        let tracer: &mut dyn Any = self.0.as_deref_mut()?;
+        tracer.downcast_mut()
+    }
+
+    pub fn take(&mut self) -> Option<Box<dyn Tracer>> {
+        self.0.take()
+    }
+
+    #[inline]
+    pub fn on_tx_start(&mut self, env: &Environment, tx: &Transaction) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_tx_start(env, tx);
+        }
+    }
+
+    #[inline]
+    pub fn on_tx_end(&mut self, result: Result<&ExecutionReport, &VMError>) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_tx_end(result);
+        }
+    }
+
+    #[inline]
+    pub fn on_enter(
+        &mut self,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_enter(call_type, from, to, value, gas, input);
+        }
+    }
+
+    #[inline]
+    pub fn on_exit(
+        &mut self,
+        ctx_result: &ContextResult,
+        is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        match &mut self.0 {
+            Some(tracer) => tracer.on_exit(ctx_result, is_top_call),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    pub fn on_exit_early(
+        &mut self,
+        gas_used: u64,
+        error: Option<String>,
+    ) -> Result<(), InternalError> {
+        match &mut self.0 {
+            Some(tracer) => tracer.on_exit_early(gas_used, error),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    pub fn on_opcode(&mut self, pc: usize, opcode: u8, frame: &CallFrame) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_opcode(pc, opcode, frame);
+        }
+    }
+
+    #[inline]
+    pub fn on_fault(&mut self, pc: usize, opcode: u8, error: &VMError, frame: &CallFrame) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_fault(pc, opcode, error, frame);
+        }
+    }
+
+    #[inline]
+    pub fn on_log(&mut self, log: &Log) -> Result<(), InternalError> {
+        match &mut self.0 {
+            Some(tracer) => tracer.on_log(log),
+            None => Ok(()),
+        }
+    }
+
+    #[inline]
+    pub fn on_storage_change(&mut self, address: Address, key: H256, previous: U256, new: U256) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_storage_change(address, key, previous, new);
+        }
+    }
+
+    #[inline]
+    pub fn on_balance_change(&mut self, address: Address, previous: U256, new: U256) {
+        if let Some(tracer) = &mut self.0 {
+            tracer.on_balance_change(address, previous, new);
+        }
+    }
+}
 
 /// Geth's callTracer (https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers)
 /// Use `LevmCallTracer::disabled()` when tracing is not wanted.
@@ -34,9 +306,7 @@ impl LevmCallTracer {
         }
     }
 
-    /// This is to keep LEVM's code clean, like `self.tracer.enter(...)`,
-    /// instead of something more complex or uglier when we don't want to trace.
-    /// (For now that we only implement one tracer it may be the most convenient solution)
+    /// Disabled tracers aren't installed on the VM, so this is the way of not tracing at all.
     pub fn disabled() -> Self {
         LevmCallTracer {
             active: false,
@@ -44,8 +314,45 @@ impl LevmCallTracer {
         }
     }
 
+    /// Returns the finished call tree. Meant to be called after transaction execution.
+    pub fn into_result(mut self) -> Result<CallTraceFrame, InternalError> {
+        self.callframes.pop().ok_or(InternalError::CallFrame)
+    }
+
+    /// Exits trace call.
+    /// Has no validations because it's a private method.
+    fn exit(
+        &mut self,
+        gas_used: u64,
+        output: Bytes,
+        error: Option<String>,
+        revert_reason: Option<String>,
+    ) -> Result<(), InternalError> {
+        let mut callframe = self.callframes.pop().ok_or(InternalError::CallFrame)?;
+
+        process_output(&mut callframe, gas_used, output, error, revert_reason);
+
+        // Append executed callframe to parent callframe if appropriate.
+        if let Some(parent_callframe) = self.callframes.last_mut() {
+            parent_callframe.calls.push(callframe);
+        } else {
+            self.callframes.push(callframe);
+        };
+        Ok(())
+    }
+
+    fn current_callframe_mut(&mut self) -> Result<&mut CallTraceFrame, InternalError> {
+        self.callframes.last_mut().ok_or(InternalError::CallFrame)
+    }
+}
+
+impl Tracer for LevmCallTracer {
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
     /// Starts trace call.
-    pub fn enter(
+    fn on_enter(
         &mut self,
         call_type: CallType,
         from: Address,
@@ -75,30 +382,8 @@ impl LevmCallTracer {
         self.callframes.push(callframe);
     }
 
-    /// Exits trace call.
-    /// Has no validations because it's a private method.
-    fn exit(
-        &mut self,
-        gas_used: u64,
-        output: Bytes,
-        error: Option<String>,
-        revert_reason: Option<String>,
-    ) -> Result<(), InternalError> {
-        let mut callframe = self.callframes.pop().ok_or(InternalError::CallFrame)?;
-
-        process_output(&mut callframe, gas_used, output, error, revert_reason);
-
-        // Append executed callframe to parent callframe if appropriate.
-        if let Some(parent_callframe) = self.callframes.last_mut() {
-            parent_callframe.calls.push(callframe);
-        } else {
-            self.callframes.push(callframe);
-        };
-        Ok(())
-    }
-
     /// Exits trace call using the ContextResult.
-    pub fn exit_context(
+    fn on_exit(
         &mut self,
         ctx_result: &ContextResult,
         is_top_call: bool,
@@ -128,11 +413,7 @@ impl LevmCallTracer {
     }
 
     /// Exits trace call when CALL or CREATE opcodes return early or in case SELFDESTRUCT is called.
-    pub fn exit_early(
-        &mut self,
-        gas_used: u64,
-        error: Option<String>,
-    ) -> Result<(), InternalError> {
+    fn on_exit_early(&mut self, gas_used: u64, error: Option<String>) -> Result<(), InternalError> {
         if !self.active || self.only_top_call {
             return Ok(());
         }
@@ -141,7 +422,7 @@ impl LevmCallTracer {
 
     /// Registers log when opcode log is executed.
     /// Note: Logs of callframes that reverted will be removed at end of execution.
-    pub fn log(&mut self, log: &Log) -> Result<(), InternalError> {
+    fn on_log(&mut self, log: &Log) -> Result<(), InternalError> {
         if !self.active || !self.with_log {
             return Ok(());
         }
@@ -164,9 +445,286 @@ impl LevmCallTracer {
         callframe.logs.push(log);
         Ok(())
     }
+}
 
-    fn current_callframe_mut(&mut self) -> Result<&mut CallTraceFrame, InternalError> {
-        self.callframes.last_mut().ok_or(InternalError::CallFrame)
+/// Geth's flatCallTracer: records the same call tree as [`LevmCallTracer`], which the caller
+/// flattens into Parity-style traces. Calls to precompiles are dropped unless
+/// `include_precompiles` is set.
+#[derive(Debug)]
+pub struct FlatCallTracer {
+    calls: LevmCallTracer,
+    include_precompiles: bool,
+    fork: Fork,
+    vm_type: VMType,
+}
+
+impl FlatCallTracer {
+    pub fn new(include_precompiles: bool, fork: Fork, vm_type: VMType) -> Self {
+        FlatCallTracer {
+            calls: LevmCallTracer::new(false, false),
+            include_precompiles,
+            fork,
+            vm_type,
+        }
+    }
+
+    /// Returns the finished call tree. Meant to be called after transaction execution.
+    pub fn into_result(self) -> Result<CallTraceFrame, InternalError> {
+        let mut frame = self.calls.into_result()?;
+        if !self.include_precompiles {
+            remove_precompile_calls(&mut frame, self.fork, self.vm_type);
+        }
+        Ok(frame)
+    }
+}
+
+impl Tracer for FlatCallTracer {
+    fn on_enter(
+        &mut self,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        self.calls.on_enter(call_type, from, to, value, gas, input);
+    }
+
+    fn on_exit(
+        &mut self,
+        ctx_result: &ContextResult,
+        is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        self.calls.on_exit(ctx_result, is_top_call)
+    }
+
+    fn on_exit_early(&mut self, gas_used: u64, error: Option<String>) -> Result<(), InternalError> {
+        self.calls.on_exit_early(gas_used, error)
+    }
+}
+
+/// Removes subcalls to precompiles, which are just fancy opcodes for the flat call output.
+fn remove_precompile_calls(callframe: &mut CallTraceFrame, fork: Fork, vm_type: VMType) {
+    callframe.calls.retain(|call| {
+        matches!(call.call_type, CallType::CREATE | CallType::CREATE2)
+            || !is_precompile(&call.to, fork, vm_type)
+    });
+    for subcall in &mut callframe.calls {
+        remove_precompile_calls(subcall, fork, vm_type);
+    }
+}
+
+/// Geth's 4byteTracer: counts the calls made to each function selector, keyed by selector
+/// and calldata size (without the selector). Calls to precompiles aren't counted.
+#[derive(Debug)]
+pub struct FourByteTracer {
+    ids: FourByteTrace,
+    fork: Fork,
+    vm_type: VMType,
+}
+
+impl FourByteTracer {
+    pub fn new(fork: Fork, vm_type: VMType) -> Self {
+        FourByteTracer {
+            ids: FourByteTrace::new(),
+            fork,
+            vm_type,
+        }
+    }
+
+    pub fn into_result(self) -> FourByteTrace {
+        self.ids
+    }
+}
+
+impl Tracer for FourByteTracer {
+    fn on_enter(
+        &mut self,
+        call_type: CallType,
+        _from: Address,
+        to: Address,
+        _value: U256,
+        _gas: u64,
+        input: &Bytes,
+    ) {
+        // CREATE, CREATE2 and SELFDESTRUCT don't call a function.
+        if !matches!(
+            call_type,
+            CallType::CALL | CallType::CALLCODE | CallType::DELEGATECALL | CallType::STATICCALL
+        ) {
+            return;
+        }
+        let Some(selector) = input.get(..4).and_then(|s| <[u8; 4]>::try_from(s).ok()) else {
+            return;
+        };
+        if is_precompile(&to, self.fork, self.vm_type) {
+            return;
+        }
+        let key = format!(
+            "0x{:08x}-{}",
+            u32::from_be_bytes(selector),
+            input.len().saturating_sub(4)
+        );
+        let count = self.ids.entry(key).or_default();
+        *count = count.saturating_add(1);
+    }
+}
+
+/// Geth's noopTracer: receives every callback and records nothing.
+#[derive(Debug, Default)]
+pub struct NoopTracer;
+
+impl Tracer for NoopTracer {}
+
+/// Geth's muxTracer: runs several tracers during the same execution by forwarding every
+/// callback to each of them, in order.
+#[derive(Default)]
+pub struct MuxTracer {
+    tracers: Vec<Box<dyn Tracer>>,
+}
+
+impl MuxTracer {
+    pub fn new(tracers: Vec<Box<dyn Tracer>>) -> Self {
+        MuxTracer { tracers }
+    }
+
+    /// Returns the inner tracers, in the order they were given.
+    pub fn into_tracers(self) -> Vec<Box<dyn Tracer>> {
+        self.tracers
+    }
+}
+
+impl Tracer for MuxTracer {
+    fn on_tx_start(&mut self, env: &Environment, tx: &Transaction) {
+        for tracer in &mut self.tracers {
+            tracer.on_tx_start(env, tx);
+        }
+    }
+
+    fn on_tx_end(&mut self, result: Result<&ExecutionReport, &VMError>) {
+        for tracer in &mut self.tracers {
+            tracer.on_tx_end(result);
+        }
+    }
+
+    fn on_enter(
+        &mut self,
+        call_type: CallType,
+        from: Address,
+        to: Address,
+        value: U256,
+        gas: u64,
+        input: &Bytes,
+    ) {
+        for tracer in &mut self.tracers {
+            tracer.on_enter(call_type, from, to, value, gas, input);
+        }
+    }
+
+    fn on_exit(
+        &mut self,
+        ctx_result: &ContextResult,
+        is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        for tracer in &mut self.tracers {
+            tracer.on_exit(ctx_result, is_top_call)?;
+        }
+        Ok(())
+    }
+
+    fn on_exit_early(&mut self, gas_used: u64, error: Option<String>) -> Result<(), InternalError> {
+        for tracer in &mut self.tracers {
+            tracer.on_exit_early(gas_used, error.clone())?;
+        }
+        Ok(())
+    }
+
+    fn on_opcode(&mut self, pc: usize, opcode: u8, frame: &CallFrame) {
+        for tracer in &mut self.tracers {
+            tracer.on_opcode(pc, opcode, frame);
+        }
+    }
+
+    fn on_fault(&mut self, pc: usize, opcode: u8, error: &VMError, frame: &CallFrame) {
+        for tracer in &mut self.tracers {
+            tracer.on_fault(pc, opcode, error, frame);
+        }
+    }
+
+    fn on_log(&mut self, log: &Log) -> Result<(), InternalError> {
+        for tracer in &mut self.tracers {
+            tracer.on_log(log)?;
+        }
+        Ok(())
+    }
+
+    fn on_storage_change(&mut self, address: Address, key: H256, previous: U256, new: U256) {
+        for tracer in &mut self.tracers {
+            tracer.on_storage_change(address, key, previous, new);
+        }
+    }
+
+    fn on_balance_change(&mut self, address: Address, previous: U256, new: U256) {
+        for tracer in &mut self.tracers {
+            tracer.on_balance_change(address, previous, new);
+        }
+    }
+}
+
+/// Selects one of geth's built-in tracers, with its options, to run natively on LEVM.
+#[derive(Debug, Clone)]
+pub enum NativeTracerConfig {
+    /// `callTracer`
+    Call { only_top_call: bool, with_log: bool },
+    /// `flatCallTracer`
+    FlatCall { include_precompiles: bool },
+    /// `4byteTracer`
+    FourByte,
+    /// `prestateTracer`. Built from the state before and after execution rather than from VM
+    /// callbacks, so it has no [`Tracer`] of its own.
+    Prestate {
+        diff_mode: bool,
+        include_empty: bool,
+    },
+    /// `noopTracer`
+    Noop,
+    /// `muxTracer`: every named tracer runs in the same execution.
+    Mux(Vec<(String, NativeTracerConfig)>),
+}
+
+impl NativeTracerConfig {
+    /// Builds the tracer to install on the VM. Results are read back by downcasting it to the
+    /// concrete type matching each variant.
+    pub fn build(&self, fork: Fork, vm_type: VMType) -> Box<dyn Tracer> {
+        match self {
+            NativeTracerConfig::Call {
+                only_top_call,
+                with_log,
+            } => Box::new(LevmCallTracer::new(*only_top_call, *with_log)),
+            NativeTracerConfig::FlatCall {
+                include_precompiles,
+            } => Box::new(FlatCallTracer::new(*include_precompiles, fork, vm_type)),
+            NativeTracerConfig::FourByte => Box::new(FourByteTracer::new(fork, vm_type)),
+            NativeTracerConfig::Prestate { .. } | NativeTracerConfig::Noop => Box::new(NoopTracer),
+            NativeTracerConfig::Mux(tracers) => Box::new(MuxTracer::new(
+                tracers
+                    .iter()
+                    .map(|(_, config)| config.build(fork, vm_type))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Whether the state before execution has to be kept around to build the result.
+    pub fn needs_prestate(&self) -> bool {
+        match self {
+            NativeTracerConfig::Prestate { .. } => true,
+            NativeTracerConfig::Mux(tracers) => {
+                tracers.iter().any(|(_, config)| config.needs_prestate())
+            }
+            _ => false,
+        }
     }
 }
 
@@ -197,8 +755,13 @@ impl<'a> VM<'a> {
     /// This method is intended to be accessed after transaction execution
     pub fn get_trace_result(&mut self) -> Result<CallTraceFrame, VMError> {
         self.tracer
-            .callframes
-            .pop()
+            .downcast_mut::<LevmCallTracer>()
+            .and_then(|tracer| tracer.callframes.pop())
             .ok_or(InternalError::CallFrame.into())
     }
+
+    /// Removes the installed tracer so its result can be read once execution is over.
+    pub fn take_tracer(&mut self) -> Option<Box<dyn Tracer>> {
+        self.tracer.take()
+    }
 }
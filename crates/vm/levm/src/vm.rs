@@ -3,7 +3,7 @@ use crate::{
     call_frame::{CallFrame, Stack},
     db::gen_db::GeneralizedDatabase,
     debug::DebugMode,
-    environment::Environment,
+    environment::{EVMConfig, Environment},
     errors::{
         ContextResult, ExceptionalHalt, ExecutionReport, InternalError, OpcodeResult, TxResult,
         VMError,
@@ -22,7 +22,7 @@ use crate::{
     precompiles::{
         self, SIZE_PRECOMPILES_CANCUN, SIZE_PRECOMPILES_PRAGUE, SIZE_PRECOMPILES_PRE_CANCUN,
     },
-    tracing::LevmCallTracer,
+    tracing::{Tracer, VmTracer},
 };
 use bytes::Bytes;
 use ethrex_common::{
@@ -479,8 +479,8 @@ pub struct VM<'a> {
     /// Original storage values before transaction (for SSTORE gas calculation),
     /// keyed first by account to avoid hashing the full tuple on each access.
     pub storage_original_values: FxHashMap<Address, FxHashMap<H256, U256>>,
-    /// Call tracer for execution tracing.
-    pub tracer: LevmCallTracer,
+    /// Tracer receiving call, exit and log callbacks. Empty when not tracing.
+    pub tracer: VmTracer,
     /// Opcode (EIP-3155) tracer.  Disabled by default; zero overhead when inactive.
     pub opcode_tracer: LevmOpcodeTracer,
     /// Debug mode for development diagnostics.
@@ -546,6 +546,10 @@ pub struct VM<'a> {
     pub(crate) opcode_table: &'static [OpCodeFn; 256],
     /// Crypto provider for cryptographic operations.
     pub crypto: &'a dyn Crypto,
+    /// Precompiles relocated to another address, keyed by their new address. A relocated
+    /// precompile no longer runs at its original address. Only used by call simulations
+    /// (`movePrecompileToAddress`), empty for regular execution.
+    pub moved_precompiles: FxHashMap<Address, Address>,
 }
 
 impl<'a> VM<'a> {
@@ -557,7 +561,7 @@ impl<'a> VM<'a> {
         env: Environment,
         db: &'a mut GeneralizedDatabase,
         tx: &'a Transaction,
-        tracer: LevmCallTracer,
+        tracer: impl Tracer,
         vm_type: VMType,
         crypto: &'a dyn Crypto,
     ) -> Result<Self, VMError> {
@@ -565,7 +569,7 @@ impl<'a> VM<'a> {
             env,
             db,
             tx,
-            tracer,
+            VmTracer::new(tracer),
             vm_type,
             crypto,
             Stack::default(),
@@ -585,7 +589,7 @@ impl<'a> VM<'a> {
         env: Environment,
         db: &'a mut GeneralizedDatabase,
         tx: &'a Transaction,
-        tracer: LevmCallTracer,
+        tracer: impl Tracer,
         vm_type: VMType,
         crypto: &'a dyn Crypto,
         stack_pool: &mut Vec<Stack>,
@@ -605,7 +609,7 @@ impl<'a> VM<'a> {
             env,
             db,
             tx,
-            tracer,
+            VmTracer::new(tracer),
             vm_type,
             crypto,
             root_stack,
@@ -656,7 +660,7 @@ impl<'a> VM<'a> {
         env: Environment,
         db: &'a mut GeneralizedDatabase,
         tx: &'a Transaction,
-        tracer: LevmCallTracer,
+        tracer: VmTracer,
         vm_type: VMType,
         crypto: &'a dyn Crypto,
         root_stack: Stack,
@@ -749,22 +753,9 @@ impl<'a> VM<'a> {
             env,
             opcode_table: VM::build_opcode_table(fork),
             crypto,
+            moved_precompiles: FxHashMap::default(),
         };
 
-        let call_type = if is_create {
-            CallType::CREATE
-        } else {
-            CallType::CALL
-        };
-        vm.tracer.enter(
-            call_type,
-            vm.env.origin,
-            callee,
-            vm.tx.value(),
-            vm.env.gas_limit,
-            vm.tx.data(),
-        );
-
         #[cfg(feature = "debug")]
         {
             // Enable debug mode for printing in Solidity contracts.
@@ -774,6 +765,29 @@ impl<'a> VM<'a> {
         Ok(vm)
     }
 
+    /// Returns the address of the precompile that runs at `address`, if any, taking
+    /// [`VM::moved_precompiles`] and the [`EVMConfig::precompiles`] registry into account.
+    #[inline]
+    pub fn precompile_at(&self, address: &Address) -> Option<Address> {
+        if !self.moved_precompiles.is_empty() {
+            if let Some(original) = self.moved_precompiles.get(address) {
+                return Some(*original);
+            }
+            if self
+                .moved_precompiles
+                .values()
+                .any(|original| original == address)
+            {
+                return None;
+            }
+        }
+        let is_precompile = match self.env.config.precompiles {
+            Some(registry) => registry.is_precompile(address, self.env.config.fork, self.vm_type),
+            None => precompiles::is_precompile(address, self.env.config.fork, self.vm_type),
+        };
+        is_precompile.then_some(*address)
+    }
+
     fn add_hook(&mut self, hook: impl Hook + 'static) {
         // Keep `preserve_top_level_backup` in sync: a hook added after construction (e.g. the
         // `BackupHook` in `stateless_execute`) may read the top-level backup in `finalize_execution`.
@@ -874,6 +888,29 @@ impl<'a> VM<'a> {
 
     /// Executes a whole external transaction. Performing validations at the beginning.
     pub fn execute(&mut self) -> Result<ExecutionReport, VMError> {
+        if !self.tracer.is_active() {
+            return self.execute_transaction();
+        }
+        self.tracer.on_tx_start(&self.env, self.tx);
+        let call_type = if self.current_call_frame.is_create {
+            CallType::CREATE
+        } else {
+            CallType::CALL
+        };
+        self.tracer.on_enter(
+            call_type,
+            self.env.origin,
+            self.current_call_frame.to,
+            self.tx.value(),
+            self.env.gas_limit,
+            self.tx.data(),
+        );
+        let result = self.execute_transaction();
+        self.tracer.on_tx_end(result.as_ref());
+        result
+    }
+
+    fn execute_transaction(&mut self) -> Result<ExecutionReport, VMError> {
         if let Err(e) = self.prepare_execution() {
             // Restore cache to state previous to this Tx execution because this Tx is invalid.
             // Consume the backup unless a `BackupHook` will read it (L2 / stateless); on L1 it
@@ -942,11 +979,7 @@ impl<'a> VM<'a> {
             && self.current_call_frame.gas_remaining >= 0
             && self.tx.authorization_list().is_none()
             // Precompiles dispatch via run_execution even with empty bytecode.
-            && !precompiles::is_precompile(
-                &self.current_call_frame.to,
-                self.env.config.fork,
-                self.vm_type,
-            )
+            && self.precompile_at(&self.current_call_frame.to).is_none()
     }
 
     /// Main execution loop.
@@ -964,20 +997,16 @@ impl<'a> VM<'a> {
         }
 
         #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
-        if precompiles::is_precompile(
-            &self.current_call_frame.to,
-            self.env.config.fork,
-            self.vm_type,
-        ) {
+        if let Some(precompile_address) = self.precompile_at(&self.current_call_frame.to) {
             let call_frame = &mut self.current_call_frame;
 
             let mut gas_remaining = call_frame.gas_remaining as u64;
             let result = Self::execute_precompile(
-                call_frame.code_address,
+                precompile_address,
                 &call_frame.calldata,
                 call_frame.gas_limit,
                 &mut gas_remaining,
-                self.env.config.fork,
+                &self.env.config,
                 self.db.store.precompile_cache(),
                 self.crypto,
             );
@@ -1063,12 +1092,16 @@ impl<'a> VM<'a> {
                     mem_size_for_trace,
                     &return_data,
                     storage_kv,
+                    &self.current_call_frame.bytecode.bytecode,
                 );
                 gas_before
             } else {
                 0
             };
 
+            self.tracer
+                .on_opcode(pc_of_current_op, opcode, &self.current_call_frame);
+
             #[cfg(feature = "perf_opcode_timings")]
             let opcode_time_start = std::time::Instant::now();
 
@@ -1111,7 +1144,15 @@ impl<'a> VM<'a> {
                 OpcodeResult::Continue => continue,
                 OpcodeResult::Halt => match error.take() {
                     None => self.handle_opcode_result()?,
-                    Some(error) => self.handle_opcode_error(error)?,
+                    Some(error) => {
+                        self.tracer.on_fault(
+                            pc_of_current_op,
+                            opcode,
+                            &error,
+                            &self.current_call_frame,
+                        );
+                        self.handle_opcode_error(error)?
+                    }
                 },
             };
 
@@ -1135,22 +1176,29 @@ impl<'a> VM<'a> {
         calldata: &Bytes,
         gas_limit: u64,
         gas_remaining: &mut u64,
-        fork: Fork,
+        config: &EVMConfig,
         cache: Option<&precompiles::PrecompileCache>,
         crypto: &dyn Crypto,
     ) -> Result<ContextResult, VMError> {
-        Self::handle_precompile_result(
-            precompiles::execute_precompile(
+        let result = match config.precompiles {
+            Some(registry) => registry.execute_precompile(
                 code_address,
                 calldata,
                 gas_remaining,
-                fork,
+                config.fork,
                 cache,
                 crypto,
             ),
-            gas_limit,
-            *gas_remaining,
-        )
+            None => precompiles::execute_precompile(
+                code_address,
+                calldata,
+                gas_remaining,
+                config.fork,
+                cache,
+                crypto,
+            ),
+        };
+        Self::handle_precompile_result(result, gas_limit, *gas_remaining)
     }
 
     /// True if external transaction is a contract creation
@@ -1241,7 +1289,7 @@ impl<'a> VM<'a> {
             }
         }
 
-        self.tracer.exit_context(&ctx_result, true)?;
+        self.tracer.on_exit(&ctx_result, true)?;
 
         // Struct-log end-of-tx capture: record final output, gas used, and revert error.
         // gas matches geth's `executionResult.Gas` which is post-refund (`receipt.GasUsed`).
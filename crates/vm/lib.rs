@@ -1,6 +1,8 @@
 mod db;
 mod errors;
 mod execution_result;
+pub mod overrides;
+pub mod simulate;
 pub mod tracing;
 mod witness_db;
 
@@ -9,10 +11,15 @@ pub mod backends;
 /// EIP-8037 (Amsterdam+, PR #2703) per-tx 2D inclusion check. Re-exported so the
 /// payload builder can enforce it with identical semantics to the validator.
 pub use backends::levm::check_2d_gas_allowance;
+/// Pre-Amsterdam per-tx block gas allowance check.
+pub use backends::levm::check_gas_limit;
 pub use backends::{BlockExecutionResult, Evm, TxGasBreakdown, TxStatus, log_gas_used_mismatch};
 pub use db::{DynVmDatabase, VmDatabase};
 pub use errors::EvmError;
-pub use ethrex_levm::precompiles::{PrecompileCache, precompiles_for_fork};
+pub use ethrex_levm::precompiles::{
+    PrecompileCache, PrecompileFn, PrecompileRegistry, increase_precompile_consumed_gas,
+    precompiles_for_fork,
+};
 /// EIP-8037 intrinsic gas split `(regular, state)` for a transaction.
 /// Re-exported for mempool / payload-builder use.
 pub use ethrex_levm::utils::intrinsic_gas_dimensions;
@@ -0,0 +1,183 @@
+//! geth-compatible state and block overrides used by simulation endpoints
+//! (`eth_call`, `eth_estimateGas`, `debug_traceCall`, ...).
+//!
+//! Overrides are layered on top of the `GeneralizedDatabase` cache and the block
+//! header used for execution, they are never written back to the store.
+
+use std::collections::HashMap;
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256, serde_utils,
+    types::{BlockHeader, Code, GenericTransaction},
+};
+use ethrex_crypto::Crypto;
+use ethrex_levm::{Environment, account::AccountStatus, db::gen_db::GeneralizedDatabase};
+use serde::Deserialize;
+
+use crate::{Evm, EvmError, ExecutionResult, backends::levm::LEVM};
+
+/// Per-account overrides keyed by address.
+pub type StateOverride = HashMap<Address, AccountOverride>;
+
+/// Fields of an account that can be replaced before executing a simulated call.
+///
+/// `state` replaces the whole storage of the account (slots not listed read as zero),
+/// while `state_diff` only replaces the listed slots. Setting both is an error.
+/// `move_precompile_to_address` is only honored by `eth_simulateV1` and rejected by the
+/// other endpoints, see [`crate::simulate::SimulationOptions`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountOverride {
+    #[serde(default, deserialize_with = "serde_utils::u256::deser_hex_str_opt")]
+    pub balance: Option<U256>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub nonce: Option<u64>,
+    #[serde(default, with = "serde_utils::bytes::opt")]
+    pub code: Option<Bytes>,
+    #[serde(default)]
+    pub state: Option<HashMap<H256, H256>>,
+    #[serde(default)]
+    pub state_diff: Option<HashMap<H256, H256>>,
+    #[serde(default)]
+    pub move_precompile_to_address: Option<Address>,
+}
+
+/// State and block overrides applied together to a simulated call.
+#[derive(Debug, Clone, Default)]
+pub struct CallOverrides {
+    pub state: StateOverride,
+    pub block: BlockOverrides,
+}
+
+/// Block header fields that can be replaced before executing a simulated call.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOverrides {
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub number: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub time: Option<u64>,
+    #[serde(default, with = "serde_utils::u64::hex_str_opt")]
+    pub gas_limit: Option<u64>,
+    #[serde(default, alias = "coinbase")]
+    pub fee_recipient: Option<Address>,
+    #[serde(default, alias = "baseFee", with = "serde_utils::u64::hex_str_opt")]
+    pub base_fee_per_gas: Option<u64>,
+    #[serde(default, alias = "random")]
+    pub prev_randao: Option<H256>,
+    /// Not part of the header, replaces the blob base fee derived from `excess_blob_gas`.
+    #[serde(default, deserialize_with = "serde_utils::u256::deser_hex_str_opt")]
+    pub blob_base_fee: Option<U256>,
+}
+
+impl BlockOverrides {
+    /// Replaces the overridden header fields in `header`.
+    pub fn apply(&self, header: &mut BlockHeader) {
+        if let Some(number) = self.number {
+            header.number = number;
+        }
+        if let Some(time) = self.time {
+            header.timestamp = time;
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            header.gas_limit = gas_limit;
+        }
+        if let Some(fee_recipient) = self.fee_recipient {
+            header.coinbase = fee_recipient;
+        }
+        if let Some(base_fee_per_gas) = self.base_fee_per_gas {
+            header.base_fee_per_gas = Some(base_fee_per_gas);
+        }
+        if let Some(prev_randao) = self.prev_randao {
+            header.prev_randao = prev_randao;
+        }
+    }
+
+    /// Replaces the overridden fields that only exist in the execution environment.
+    pub(crate) fn apply_to_env(&self, env: &mut Environment) {
+        if let Some(blob_base_fee) = self.blob_base_fee {
+            env.base_blob_fee_per_gas = blob_base_fee;
+        }
+    }
+}
+
+impl Evm {
+    /// Layers the given account overrides into the execution cache.
+    pub fn apply_state_overrides(&mut self, overrides: &StateOverride) -> Result<(), EvmError> {
+        apply_state_overrides(&mut self.db, overrides, self.crypto.as_ref())
+    }
+
+    /// Same as [`Evm::simulate_tx_from_generic`] with `overrides` layered on top of the
+    /// state and of `header`.
+    pub fn simulate_tx_with_overrides(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        overrides: &CallOverrides,
+    ) -> Result<ExecutionResult, EvmError> {
+        self.apply_state_overrides(&overrides.state)?;
+        let mut header = header.clone();
+        overrides.block.apply(&mut header);
+        LEVM::simulate_tx_from_generic(
+            tx,
+            &header,
+            &overrides.block,
+            &mut self.db,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+}
+
+/// Layers the given account overrides into `db`'s cache so that subsequent executions
+/// observe them instead of the values held by the underlying store.
+pub fn apply_state_overrides(
+    db: &mut GeneralizedDatabase,
+    overrides: &StateOverride,
+    crypto: &dyn Crypto,
+) -> Result<(), EvmError> {
+    for (address, account_override) in overrides {
+        if account_override.state.is_some() && account_override.state_diff.is_some() {
+            return Err(EvmError::Custom(format!(
+                "account {address:#x} has both 'state' and 'stateDiff'"
+            )));
+        }
+        let code = account_override
+            .code
+            .clone()
+            .map(|bytecode| Code::from_bytecode(bytecode, crypto));
+
+        let account = db.get_account_mut(*address)?;
+        if let Some(balance) = account_override.balance {
+            account.info.balance = balance;
+        }
+        if let Some(nonce) = account_override.nonce {
+            account.info.nonce = nonce;
+        }
+        if let Some(code) = &code {
+            account.info.code_hash = code.hash;
+        }
+        if let Some(state) = &account_override.state {
+            // Slots not present in the override must read as zero instead of falling
+            // back to the store, which is exactly how a re-created account behaves.
+            account.storage = state
+                .iter()
+                .map(|(key, value)| (*key, U256::from_big_endian(value.as_bytes())))
+                .collect();
+            account.has_storage = !state.is_empty();
+            account.status = AccountStatus::DestroyedModified;
+        }
+        if let Some(state_diff) = &account_override.state_diff {
+            for (key, value) in state_diff {
+                account
+                    .storage
+                    .insert(*key, U256::from_big_endian(value.as_bytes()));
+            }
+        }
+        if let Some(code) = code {
+            db.codes.entry(code.hash).or_insert(code);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,109 @@
+//! Execution support for `eth_simulateV1`.
+//!
+//! Simulated blocks are executed one after the other on the same [`Evm`], so every block
+//! observes the state left by the previous one through the `GeneralizedDatabase` cache.
+//! Nothing is ever written back to the store.
+
+use ethrex_common::types::{AccountUpdate, BlockHeader, GenericTransaction};
+use ethrex_common::{Address, types::Fork};
+use ethrex_levm::{precompiles::is_precompile, vm::VMType};
+use rustc_hash::FxHashMap;
+
+use crate::{Evm, EvmError, ExecutionResult, backends::levm::LEVM, overrides::StateOverride};
+
+/// Settings shared by every call of a simulation request.
+#[derive(Debug, Clone, Default)]
+pub struct SimulationOptions {
+    /// Enforce nonce, balance, fee and block gas limit checks like a real block would.
+    pub validation: bool,
+    /// Report ETH transfers as ERC-7528 `Transfer` logs.
+    pub trace_transfers: bool,
+    /// Precompiles moved with `movePrecompileToAddress`, keyed by their new address.
+    /// Relocations persist for the remaining blocks of the simulation.
+    pub moved_precompiles: FxHashMap<Address, Address>,
+}
+
+impl SimulationOptions {
+    /// Records the `movePrecompileToAddress` entries of `overrides`.
+    fn move_precompiles(
+        &mut self,
+        overrides: &StateOverride,
+        fork: Fork,
+        vm_type: VMType,
+    ) -> Result<(), EvmError> {
+        for (address, account_override) in overrides {
+            let Some(destination) = account_override.move_precompile_to_address else {
+                continue;
+            };
+            if !is_precompile(address, fork, vm_type) {
+                return Err(EvmError::Custom(format!(
+                    "account {address:#x} is not a precompile"
+                )));
+            }
+            if destination == *address {
+                return Err(EvmError::Custom(format!(
+                    "precompile {address:#x} cannot be moved to itself"
+                )));
+            }
+            if self
+                .moved_precompiles
+                .values()
+                .any(|moved| moved == address)
+                || self.moved_precompiles.contains_key(&destination)
+                || overrides.contains_key(&destination)
+            {
+                return Err(EvmError::Custom(format!(
+                    "account {destination:#x} is already overridden"
+                )));
+            }
+            self.moved_precompiles.insert(destination, *address);
+        }
+        Ok(())
+    }
+}
+
+impl Evm {
+    /// Prepares the state for a simulated block: relocates precompiles, layers the account
+    /// overrides and runs the system calls a real block would start with.
+    pub fn begin_simulated_block(
+        &mut self,
+        block_header: &BlockHeader,
+        overrides: &StateOverride,
+        options: &mut SimulationOptions,
+    ) -> Result<(), EvmError> {
+        let fork = self
+            .db
+            .store
+            .get_chain_config()?
+            .fork(block_header.timestamp);
+        options.move_precompiles(overrides, fork, self.vm_type)?;
+        self.apply_state_overrides(overrides)?;
+        if let VMType::L1 = self.vm_type {
+            self.apply_system_calls(block_header)?;
+        }
+        Ok(())
+    }
+
+    /// Executes a call of a simulated block, see [`LEVM::simulate_call`].
+    pub fn simulate_call(
+        &mut self,
+        tx: &GenericTransaction,
+        block_header: &BlockHeader,
+        options: &SimulationOptions,
+    ) -> Result<ExecutionResult, EvmError> {
+        LEVM::simulate_call(
+            tx,
+            block_header,
+            &mut self.db,
+            options,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+
+    /// Returns every change made since the simulation started, relative to the state of
+    /// the block it runs on. Works on a copy of the cache so the simulation can go on.
+    pub fn simulated_state_transitions(&self) -> Result<Vec<AccountUpdate>, EvmError> {
+        Ok(self.db.clone().get_state_transitions()?)
+    }
+}
@@ -1,9 +1,9 @@
 use crate::backends::levm::LEVM;
-use ethrex_common::tracing::{CallTrace, OpcodeTraceResult, PrestateResult};
-use ethrex_common::types::Block;
-pub use ethrex_levm::tracing::OpcodeTracerConfig;
+use ethrex_common::tracing::{CallTrace, NativeTraceResult, OpcodeTraceResult, PrestateResult};
+use ethrex_common::types::{Block, BlockHeader, GenericTransaction};
+pub use ethrex_levm::tracing::{NativeTracerConfig, OpcodeTracerConfig, Tracer, downcast_tracer};
 
-use crate::{Evm, EvmError};
+use crate::{Evm, EvmError, overrides::BlockOverrides};
 
 impl Evm {
     /// Runs a single tx with the call tracer and outputs its trace.
@@ -90,6 +90,114 @@ impl Evm {
         )
     }
 
+    /// Executes a single tx with the given built-in tracer (4byteTracer, flatCallTracer, muxTracer, …).
+    /// Assumes that the received state already contains changes from previous transactions.
+    pub fn trace_tx_native(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+        cfg: &NativeTracerConfig,
+    ) -> Result<NativeTraceResult, EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        LEVM::trace_tx_native(
+            &mut self.db,
+            &block.header,
+            tx,
+            cfg,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+
+    /// Runs a simulated (unsigned) transaction with the call tracer and outputs its trace.
+    /// The transaction is executed on top of the current state, as `eth_call` would.
+    /// `header` must already have `block_overrides` applied.
+    pub fn trace_generic_tx_calls(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        only_top_call: bool,
+        with_log: bool,
+    ) -> Result<CallTrace, EvmError> {
+        LEVM::trace_generic_tx_calls(
+            &mut self.db,
+            header,
+            block_overrides,
+            tx,
+            only_top_call,
+            with_log,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+
+    /// Executes a simulated (unsigned) transaction and captures the pre/post account state (prestateTracer).
+    pub fn trace_generic_tx_prestate(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        diff_mode: bool,
+        include_empty: bool,
+    ) -> Result<PrestateResult, EvmError> {
+        LEVM::trace_generic_tx_prestate(
+            &mut self.db,
+            header,
+            block_overrides,
+            tx,
+            diff_mode,
+            include_empty,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+
+    /// Executes a simulated (unsigned) transaction and captures the per-opcode (EIP-3155) trace.
+    pub fn trace_generic_tx_opcodes(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        cfg: OpcodeTracerConfig,
+    ) -> Result<OpcodeTraceResult, EvmError> {
+        LEVM::trace_generic_tx_opcodes(
+            &mut self.db,
+            header,
+            block_overrides,
+            tx,
+            cfg,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+
+    /// Executes a simulated (unsigned) transaction with the given built-in tracer.
+    pub fn trace_generic_tx_native(
+        &mut self,
+        tx: &GenericTransaction,
+        header: &BlockHeader,
+        block_overrides: &BlockOverrides,
+        cfg: &NativeTracerConfig,
+    ) -> Result<NativeTraceResult, EvmError> {
+        LEVM::trace_generic_tx_native(
+            &mut self.db,
+            header,
+            block_overrides,
+            tx,
+            cfg,
+            self.vm_type,
+            self.crypto.as_ref(),
+        )
+    }
+
     /// Reruns the given block, saving the changes on the state, doesn't output any results or receipts.
     /// If the optional argument `stop_index` is set, the run will stop just before executing the transaction at that index
     /// and won't process the withdrawals afterwards.
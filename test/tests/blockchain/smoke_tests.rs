@@ -1,9 +1,10 @@
-use std::{fs::File, io::BufReader, path::PathBuf};
+use std::{fs::File, io::BufReader, path::PathBuf, sync::Arc, time::Duration};
 
 use bytes::Bytes;
 use ethrex_blockchain::{
     Blockchain,
     error::{ChainError, InvalidForkChoice},
+    events::{BlockWithReceipts, ChainEvent},
     fork_choice::apply_fork_choice,
     is_canonical, latest_canonical_block_hash,
     payload::{BuildPayloadArgs, create_payload},
@@ -13,6 +14,7 @@ use ethrex_common::{
     types::{Block, BlockHeader, DEFAULT_BUILDER_GAS_CEIL, ELASTICITY_MULTIPLIER},
 };
 use ethrex_storage::{EngineType, Store};
+use tokio::sync::broadcast;
 
 #[tokio::test]
 async fn test_small_to_long_reorg() {
@@ -345,6 +347,58 @@ async fn unfinalized_reorg_deeper_than_32_is_allowed() {
     }
 }
 
+#[tokio::test]
+async fn head_changes_made_through_the_store_are_published() {
+    let store = test_store().await;
+    let genesis_header = store.get_block_header(0).unwrap().unwrap();
+    let blockchain = Arc::new(Blockchain::default_with_store(store.clone()));
+    let mut events = blockchain.subscribe_events();
+
+    // Move the head straight through the store, as full sync does.
+    let block_1a = new_block(&store, &genesis_header).await;
+    blockchain.add_block(block_1a.clone()).unwrap();
+    store
+        .forkchoice_update(vec![], 1, block_1a.hash(), None, None)
+        .await
+        .unwrap();
+    let (removed, added) = next_head_change(&mut events).await;
+    assert!(removed.is_empty());
+    assert_eq!(added, vec![block_1a.hash()]);
+
+    // Reorg to a longer branch through the fork choice.
+    let block_1b = new_block(&store, &genesis_header).await;
+    blockchain.add_block(block_1b.clone()).unwrap();
+    let block_2 = new_block(&store, &block_1b.header).await;
+    blockchain.add_block(block_2.clone()).unwrap();
+    apply_fork_choice(
+        &store,
+        block_2.hash(),
+        genesis_header.hash(),
+        genesis_header.hash(),
+    )
+    .await
+    .unwrap();
+    let (removed, added) = next_head_change(&mut events).await;
+    assert_eq!(removed, vec![block_1a.hash()]);
+    assert_eq!(added, vec![block_1b.hash(), block_2.hash()]);
+}
+
+/// Waits for the next head change and returns the hashes of the removed and added blocks.
+async fn next_head_change(events: &mut broadcast::Receiver<ChainEvent>) -> (Vec<H256>, Vec<H256>) {
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("Timed out waiting for a head change")
+            .unwrap();
+        if let ChainEvent::HeadChanged { removed, added } = event {
+            let hashes = |blocks: &[BlockWithReceipts]| {
+                blocks.iter().map(|block| block.block.hash()).collect()
+            };
+            return (hashes(&removed), hashes(&added));
+        }
+    }
+}
+
 async fn new_block(store: &Store, parent: &BlockHeader) -> Block {
     let args = BuildPayloadArgs {
         parent: parent.hash(),
@@ -0,0 +1,236 @@
+//! Tests for user-defined tracers implementing `ethrex_levm::tracing::Tracer`, installed
+//! through `LEVM::execute_tx_with_tracer`.
+
+use super::test_db::TestDatabase;
+use bytes::Bytes;
+use ethrex_common::tracing::CallType;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, BlockHeader, Code, EIP1559Transaction, Transaction, TxKind},
+};
+use ethrex_crypto::NativeCrypto;
+use ethrex_levm::call_frame::CallFrame;
+use ethrex_levm::db::gen_db::GeneralizedDatabase;
+use ethrex_levm::errors::{ContextResult, ExecutionReport, InternalError, VMError};
+use ethrex_levm::tracing::Tracer;
+use ethrex_levm::vm::VMType;
+use ethrex_levm::{Environment, opcodes::Opcode};
+use ethrex_vm::backends::levm::LEVM;
+use once_cell::sync::OnceCell;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+const CONTRACT: u64 = 0xC000;
+const SENDER: u64 = 0x1000;
+const COINBASE: u64 = 0xCCC;
+
+/// Records every callback it receives.
+#[derive(Default)]
+struct RecordingTracer {
+    tx_starts: usize,
+    tx_ends: Vec<bool>,
+    enters: Vec<(CallType, Address)>,
+    exits: Vec<bool>,
+    opcodes: Vec<(usize, u8)>,
+    faults: Vec<(usize, u8)>,
+    storage_changes: Vec<(Address, H256, U256, U256)>,
+    balance_changes: Vec<(Address, U256, U256)>,
+}
+
+impl Tracer for RecordingTracer {
+    fn on_tx_start(&mut self, _env: &Environment, _tx: &Transaction) {
+        self.tx_starts += 1;
+    }
+
+    fn on_tx_end(&mut self, result: Result<&ExecutionReport, &VMError>) {
+        self.tx_ends
+            .push(result.is_ok_and(|report| report.is_success()));
+    }
+
+    fn on_enter(
+        &mut self,
+        call_type: CallType,
+        _from: Address,
+        to: Address,
+        _value: U256,
+        _gas: u64,
+        _input: &Bytes,
+    ) {
+        self.enters.push((call_type, to));
+    }
+
+    fn on_exit(
+        &mut self,
+        ctx_result: &ContextResult,
+        is_top_call: bool,
+    ) -> Result<(), InternalError> {
+        assert!(is_top_call);
+        self.exits.push(ctx_result.is_success());
+        Ok(())
+    }
+
+    fn on_opcode(&mut self, pc: usize, opcode: u8, _frame: &CallFrame) {
+        self.opcodes.push((pc, opcode));
+    }
+
+    fn on_fault(&mut self, pc: usize, opcode: u8, _error: &VMError, _frame: &CallFrame) {
+        self.faults.push((pc, opcode));
+    }
+
+    fn on_storage_change(&mut self, address: Address, key: H256, previous: U256, new: U256) {
+        self.storage_changes.push((address, key, previous, new));
+    }
+
+    fn on_balance_change(&mut self, address: Address, previous: U256, new: U256) {
+        self.balance_changes.push((address, previous, new));
+    }
+}
+
+fn make_tx(contract: Address, sender: Address) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: 1,
+        max_fee_per_gas: 10,
+        gas_limit: 100_000,
+        to: TxKind::Call(contract),
+        value: U256::zero(),
+        data: Bytes::new(),
+        access_list: vec![],
+        signature_y_parity: false,
+        signature_r: U256::one(),
+        signature_s: U256::one(),
+        inner_hash: OnceCell::new(),
+        sender_cache: {
+            let cell = OnceCell::new();
+            let _ = cell.set(sender);
+            cell
+        },
+        cached_canonical: OnceCell::new(),
+    })
+}
+
+/// Runs `bytecode` under a contract account with a [`RecordingTracer`] installed.
+fn trace(bytecode: Vec<u8>) -> RecordingTracer {
+    let contract_addr = Address::from_low_u64_be(CONTRACT);
+    let sender_addr = Address::from_low_u64_be(SENDER);
+
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        contract_addr,
+        Account::new(
+            U256::zero(),
+            Code::from_bytecode(Bytes::from(bytecode), &NativeCrypto),
+            1,
+            FxHashMap::default(),
+        ),
+    );
+    accounts.insert(
+        sender_addr,
+        Account::new(
+            U256::from(10u64) * U256::from(10u64).pow(U256::from(18)),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+
+    let mut db = GeneralizedDatabase::new(Arc::new(TestDatabase { accounts }));
+    let header = BlockHeader {
+        coinbase: Address::from_low_u64_be(COINBASE),
+        base_fee_per_gas: Some(1),
+        gas_limit: 30_000_000,
+        ..Default::default()
+    };
+    let tx = make_tx(contract_addr, sender_addr);
+
+    let (_, tracer) = LEVM::execute_tx_with_tracer(
+        &tx,
+        sender_addr,
+        &header,
+        &mut db,
+        VMType::L1,
+        &NativeCrypto,
+        RecordingTracer::default(),
+    )
+    .expect("execution should succeed");
+    tracer
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+/// `PUSH1 0x2A PUSH1 0x01 SSTORE STOP`
+#[test]
+fn custom_tracer_receives_execution_callbacks() {
+    let tracer = trace(vec![0x60, 0x2A, 0x60, 0x01, 0x55, 0x00]);
+    let contract = Address::from_low_u64_be(CONTRACT);
+
+    assert_eq!(tracer.tx_starts, 1);
+    assert_eq!(tracer.tx_ends, [true]);
+    assert_eq!(tracer.enters, [(CallType::CALL, contract)]);
+    assert_eq!(tracer.exits, [true]);
+    assert_eq!(
+        tracer.opcodes,
+        [
+            (0, Opcode::PUSH1 as u8),
+            (2, Opcode::PUSH1 as u8),
+            (4, Opcode::SSTORE as u8),
+            (5, Opcode::STOP as u8),
+        ]
+    );
+    assert!(tracer.faults.is_empty());
+    assert_eq!(
+        tracer.storage_changes,
+        [(
+            contract,
+            H256::from_low_u64_be(1),
+            U256::zero(),
+            U256::from(0x2A)
+        )]
+    );
+
+    // The sender pays the upfront cost, gets the unused gas back and the coinbase gets its tip.
+    let sender = Address::from_low_u64_be(SENDER);
+    let coinbase = Address::from_low_u64_be(COINBASE);
+    assert!(
+        tracer
+            .balance_changes
+            .iter()
+            .any(|(address, previous, new)| *address == sender && new < previous)
+    );
+    assert!(
+        tracer
+            .balance_changes
+            .iter()
+            .any(|(address, previous, new)| *address == coinbase && new > previous)
+    );
+}
+
+/// `PUSH1 0x00 PUSH1 0x00 REVERT`
+#[test]
+fn custom_tracer_receives_fault() {
+    let tracer = trace(vec![0x60, 0x00, 0x60, 0x00, 0xFD]);
+
+    assert_eq!(tracer.faults, [(4, Opcode::REVERT as u8)]);
+    assert_eq!(tracer.exits, [false]);
+    assert_eq!(tracer.tx_ends, [false]);
+    assert!(tracer.storage_changes.is_empty());
+}
+
+/// `PUSH1 0x03 JUMP JUMPDEST STOP`: the JUMPDEST fused into the JUMP is still reported.
+#[test]
+fn custom_tracer_sees_fused_jumpdest() {
+    let tracer = trace(vec![0x60, 0x03, 0x56, 0x5B, 0x00]);
+
+    assert_eq!(
+        tracer.opcodes,
+        [
+            (0, Opcode::PUSH1 as u8),
+            (2, Opcode::JUMP as u8),
+            (3, Opcode::JUMPDEST as u8),
+            (4, Opcode::STOP as u8),
+        ]
+    );
+}
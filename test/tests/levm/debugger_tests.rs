@@ -0,0 +1,201 @@
+//! Tests for the step debugger in `ethrex_levm::debugger`, driven by a scripted frontend.
+
+use super::test_db::TestDatabase;
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, BlockHeader, Code, EIP1559Transaction, Transaction, TxKind},
+};
+use ethrex_crypto::NativeCrypto;
+use ethrex_levm::db::gen_db::GeneralizedDatabase;
+use ethrex_levm::debugger::{Breakpoint, DebugStop, Debugger, DebuggerFrontend, StepMode};
+use ethrex_levm::opcodes::Opcode;
+use ethrex_levm::vm::VMType;
+use ethrex_vm::backends::levm::LEVM;
+use once_cell::sync::OnceCell;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+const CONTRACT: u64 = 0xC000;
+const SENDER: u64 = 0x1000;
+
+/// Records every stop and resumes with `mode`.
+struct ScriptedFrontend {
+    mode: StepMode,
+    /// `(pc, opcode, stack top first, breakpoint)` of every stop.
+    stops: Vec<(usize, u8, Vec<U256>, Option<usize>)>,
+}
+
+impl DebuggerFrontend for ScriptedFrontend {
+    fn on_stop(&mut self, stop: &DebugStop<'_>, _breakpoints: &mut Vec<Breakpoint>) -> StepMode {
+        self.stops
+            .push((stop.pc, stop.opcode, stop.stack().to_vec(), stop.breakpoint));
+        self.mode
+    }
+}
+
+fn make_tx(contract: Address, sender: Address) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: 1,
+        max_fee_per_gas: 10,
+        gas_limit: 100_000,
+        to: TxKind::Call(contract),
+        value: U256::zero(),
+        data: Bytes::new(),
+        access_list: vec![],
+        signature_y_parity: false,
+        signature_r: U256::one(),
+        signature_s: U256::one(),
+        inner_hash: OnceCell::new(),
+        sender_cache: {
+            let cell = OnceCell::new();
+            let _ = cell.set(sender);
+            cell
+        },
+        cached_canonical: OnceCell::new(),
+    })
+}
+
+/// Runs `bytecode` under a contract account with `debugger` installed.
+fn debug(bytecode: Vec<u8>, debugger: Debugger<ScriptedFrontend>) -> Debugger<ScriptedFrontend> {
+    let contract_addr = Address::from_low_u64_be(CONTRACT);
+    let sender_addr = Address::from_low_u64_be(SENDER);
+
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        contract_addr,
+        Account::new(
+            U256::zero(),
+            Code::from_bytecode(Bytes::from(bytecode), &NativeCrypto),
+            1,
+            FxHashMap::default(),
+        ),
+    );
+    accounts.insert(
+        sender_addr,
+        Account::new(
+            U256::from(10u64) * U256::from(10u64).pow(U256::from(18)),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+
+    let mut db = GeneralizedDatabase::new(Arc::new(TestDatabase { accounts }));
+    let header = BlockHeader {
+        base_fee_per_gas: Some(1),
+        gas_limit: 30_000_000,
+        ..Default::default()
+    };
+    let tx = make_tx(contract_addr, sender_addr);
+
+    let (_, debugger) = LEVM::execute_tx_with_tracer(
+        &tx,
+        sender_addr,
+        &header,
+        &mut db,
+        VMType::L1,
+        &NativeCrypto,
+        debugger,
+    )
+    .expect("execution should succeed");
+    debugger
+}
+
+fn frontend(mode: StepMode) -> ScriptedFrontend {
+    ScriptedFrontend {
+        mode,
+        stops: Vec::new(),
+    }
+}
+
+/// `PUSH1 0x2A PUSH1 0x01 SSTORE PUSH1 0x01 SLOAD STOP`
+const STORE_AND_LOAD: [u8; 10] = [0x60, 0x2A, 0x60, 0x01, 0x55, 0x60, 0x01, 0x54, 0x00, 0x00];
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+#[test]
+fn debugger_steps_through_every_opcode() {
+    let debugger = debug(
+        STORE_AND_LOAD.to_vec(),
+        Debugger::new(frontend(StepMode::Step)),
+    );
+    let stops = &debugger.frontend.stops;
+
+    let pcs: Vec<(usize, u8)> = stops.iter().map(|(pc, op, _, _)| (*pc, *op)).collect();
+    assert_eq!(
+        pcs,
+        [
+            (0, Opcode::PUSH1 as u8),
+            (2, Opcode::PUSH1 as u8),
+            (4, Opcode::SSTORE as u8),
+            (5, Opcode::PUSH1 as u8),
+            (7, Opcode::SLOAD as u8),
+            (8, Opcode::STOP as u8),
+        ]
+    );
+    // The stack is shown as it is before the opcode, top first.
+    assert_eq!(stops[2].2, [U256::from(1), U256::from(0x2A)]);
+    assert_eq!(stops[5].2, [U256::from(0x2A)]);
+    assert!(
+        stops
+            .iter()
+            .all(|(_, _, _, breakpoint)| breakpoint.is_none())
+    );
+}
+
+#[test]
+fn debugger_continues_to_breakpoints() {
+    let debugger = debug(
+        STORE_AND_LOAD.to_vec(),
+        Debugger::with_mode(frontend(StepMode::Continue), StepMode::Continue).with_breakpoints(
+            vec![Breakpoint::Opcode(Opcode::SLOAD as u8), Breakpoint::Pc(8)],
+        ),
+    );
+
+    let stops: Vec<(usize, Option<usize>)> = debugger
+        .frontend
+        .stops
+        .iter()
+        .map(|(pc, _, _, breakpoint)| (*pc, *breakpoint))
+        .collect();
+    assert_eq!(stops, [(7, Some(0)), (8, Some(1))]);
+}
+
+#[test]
+fn debugger_breaks_on_storage_slot() {
+    let contract = Address::from_low_u64_be(CONTRACT);
+    let slot = H256::from_low_u64_be(1);
+    let debugger = debug(
+        STORE_AND_LOAD.to_vec(),
+        Debugger::with_mode(frontend(StepMode::Continue), StepMode::Continue).with_breakpoints(
+            vec![
+                Breakpoint::Storage {
+                    address: Some(contract),
+                    slot,
+                },
+                Breakpoint::Storage {
+                    address: None,
+                    slot: H256::from_low_u64_be(2),
+                },
+            ],
+        ),
+    );
+
+    let pcs: Vec<usize> = debugger
+        .frontend
+        .stops
+        .iter()
+        .map(|(pc, _, _, _)| *pc)
+        .collect();
+    assert_eq!(pcs, [4, 7]);
+    // Both the written and the loaded value are recorded.
+    assert_eq!(
+        debugger.storage().get(&(contract, slot)),
+        Some(&U256::from(0x2A))
+    );
+}
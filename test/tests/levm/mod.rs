@@ -2,6 +2,8 @@ mod test_db;
 
 mod bal_view_tests;
 mod bls12_tests;
+mod custom_tracer_tests;
+mod debugger_tests;
 mod destroyed_refault_tests;
 mod eip7702_tests;
 mod eip7708_tests;
@@ -14,7 +16,9 @@ mod l2_gas_reservation_tests;
 mod l2_hook_tests;
 mod l2_privileged_tx_tests;
 mod memory_tests;
+mod native_tracer_tests;
 mod opcode_tracer_tests;
 mod precompile_tests;
 mod prestate_tracer_tests;
 mod stack_tests;
+mod t8n_tests;
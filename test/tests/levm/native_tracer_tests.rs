@@ -0,0 +1,216 @@
+//! End-to-end tests for the built-in tracers run through `LEVM::trace_tx_native`
+//! (`4byteTracer`, `flatCallTracer`, `muxTracer` and `noopTracer`).
+
+use super::test_db::TestDatabase;
+use bytes::Bytes;
+use ethrex_common::tracing::{CallType, NativeTraceResult, PrestateResult};
+use ethrex_common::{
+    Address, U256,
+    types::{Account, BlockHeader, Code, EIP1559Transaction, Transaction, TxKind},
+};
+use ethrex_crypto::NativeCrypto;
+use ethrex_levm::db::gen_db::GeneralizedDatabase;
+use ethrex_levm::tracing::NativeTracerConfig;
+use ethrex_levm::vm::VMType;
+use ethrex_vm::backends::levm::LEVM;
+use once_cell::sync::OnceCell;
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+// ── Helpers ──────────────────────────────────────────────────────────────────
+
+const CALLER: u64 = 0xC000;
+const CALLEE: u64 = 0xB000;
+const SENDER: u64 = 0x1000;
+/// Identity precompile.
+const IDENTITY: u64 = 0x04;
+
+fn default_header() -> BlockHeader {
+    BlockHeader {
+        coinbase: Address::from_low_u64_be(0xCCC),
+        base_fee_per_gas: Some(1),
+        gas_limit: 30_000_000,
+        ..Default::default()
+    }
+}
+
+fn make_tx(contract: Address, sender: Address, data: Bytes) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: 1,
+        nonce: 0,
+        max_priority_fee_per_gas: 1,
+        max_fee_per_gas: 10,
+        gas_limit: 200_000,
+        to: TxKind::Call(contract),
+        value: U256::zero(),
+        data,
+        access_list: vec![],
+        signature_y_parity: false,
+        signature_r: U256::one(),
+        signature_s: U256::one(),
+        inner_hash: OnceCell::new(),
+        sender_cache: {
+            let cell = OnceCell::new();
+            let _ = cell.set(sender);
+            cell
+        },
+        cached_canonical: OnceCell::new(),
+    })
+}
+
+/// Contract that CALLs `CALLEE` with selector `0xdeadbeef` plus 32 bytes of arguments,
+/// then STATICCALLs the identity precompile with 8 bytes.
+///
+/// ```text
+/// PUSH4 0xdeadbeef PUSH1 0xe0 SHL PUSH1 0x00 MSTORE
+/// PUSH1 0x00 PUSH1 0x00 PUSH1 0x24 PUSH1 0x00 PUSH1 0x00 PUSH2 0xB000 GAS CALL POP
+/// PUSH1 0x00 PUSH1 0x00 PUSH1 0x08 PUSH1 0x00 PUSH1 0x04 GAS STATICCALL POP
+/// STOP
+/// ```
+fn caller_contract() -> Account {
+    let bytecode = Bytes::from(vec![
+        0x63, 0xde, 0xad, 0xbe, 0xef, 0x60, 0xe0, 0x1b, 0x60, 0x00, 0x52, // selector
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x24, 0x60, 0x00, 0x60, 0x00, 0x61, 0xb0, 0x00, 0x5a, 0xf1,
+        0x50, // CALL
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x08, 0x60, 0x00, 0x60, 0x04, 0x5a, 0xfa,
+        0x50, // STATICCALL
+        0x00,
+    ]);
+    Account::new(
+        U256::zero(),
+        Code::from_bytecode(bytecode, &NativeCrypto),
+        1,
+        FxHashMap::default(),
+    )
+}
+
+/// Traces a tx calling `caller_contract` with selector `0x12345678` and 4 bytes of arguments.
+fn trace(cfg: &NativeTracerConfig) -> NativeTraceResult {
+    let mut accounts = FxHashMap::default();
+    accounts.insert(Address::from_low_u64_be(CALLER), caller_contract());
+    accounts.insert(
+        Address::from_low_u64_be(CALLEE),
+        Account::new(
+            U256::zero(),
+            Code::from_bytecode(Bytes::from(vec![0x00]), &NativeCrypto),
+            1,
+            FxHashMap::default(),
+        ),
+    );
+    accounts.insert(
+        Address::from_low_u64_be(SENDER),
+        Account::new(
+            U256::from(10u64) * U256::from(10u64).pow(U256::from(18)),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+
+    let mut db = GeneralizedDatabase::new(Arc::new(TestDatabase { accounts }));
+    let tx = make_tx(
+        Address::from_low_u64_be(CALLER),
+        Address::from_low_u64_be(SENDER),
+        Bytes::from(vec![0x12, 0x34, 0x56, 0x78, 0, 0, 0, 1]),
+    );
+    LEVM::trace_tx_native(
+        &mut db,
+        &default_header(),
+        &tx,
+        cfg,
+        VMType::L1,
+        &NativeCrypto,
+    )
+    .expect("trace should succeed")
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+/// The top-level call and the CALL are counted, the precompile call isn't.
+#[test]
+fn four_byte_tracer_counts_selectors_without_precompiles() {
+    let NativeTraceResult::FourByte(ids) = trace(&NativeTracerConfig::FourByte) else {
+        panic!("expected a 4byteTracer result");
+    };
+    assert_eq!(ids.len(), 2, "unexpected selectors: {ids:?}");
+    assert_eq!(ids.get("0x12345678-4"), Some(&1));
+    assert_eq!(ids.get("0xdeadbeef-32"), Some(&1));
+}
+
+#[test]
+fn flat_call_tracer_skips_precompiles_unless_included() {
+    let NativeTraceResult::FlatCall(frame) = trace(&NativeTracerConfig::FlatCall {
+        include_precompiles: false,
+    }) else {
+        panic!("expected a flatCallTracer result");
+    };
+    assert_eq!(frame.calls.len(), 1);
+    assert_eq!(frame.calls[0].to, Address::from_low_u64_be(CALLEE));
+
+    let NativeTraceResult::FlatCall(frame) = trace(&NativeTracerConfig::FlatCall {
+        include_precompiles: true,
+    }) else {
+        panic!("expected a flatCallTracer result");
+    };
+    assert_eq!(frame.calls.len(), 2);
+    assert_eq!(frame.calls[1].to, Address::from_low_u64_be(IDENTITY));
+    assert_eq!(frame.calls[1].call_type, CallType::STATICCALL);
+}
+
+#[test]
+fn noop_tracer_outputs_nothing() {
+    assert!(matches!(
+        trace(&NativeTracerConfig::Noop),
+        NativeTraceResult::Noop
+    ));
+}
+
+/// Every tracer in the mux sees the same execution and returns the same result it would
+/// return on its own.
+#[test]
+fn mux_tracer_runs_every_tracer_in_one_pass() {
+    let cfg = NativeTracerConfig::Mux(vec![
+        (
+            "callTracer".to_string(),
+            NativeTracerConfig::Call {
+                only_top_call: false,
+                with_log: false,
+            },
+        ),
+        ("4byteTracer".to_string(), NativeTracerConfig::FourByte),
+        (
+            "prestateTracer".to_string(),
+            NativeTracerConfig::Prestate {
+                diff_mode: false,
+                include_empty: false,
+            },
+        ),
+        ("noopTracer".to_string(), NativeTracerConfig::Noop),
+    ]);
+    let NativeTraceResult::Mux(results) = trace(&cfg) else {
+        panic!("expected a muxTracer result");
+    };
+    let names: Vec<&str> = results.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(
+        names,
+        ["callTracer", "4byteTracer", "prestateTracer", "noopTracer"]
+    );
+
+    let [
+        (_, NativeTraceResult::Call(frame)),
+        (_, NativeTraceResult::FourByte(ids)),
+        (_, NativeTraceResult::Prestate(PrestateResult::Prestate(prestate))),
+        (_, NativeTraceResult::Noop),
+    ] = results.as_slice()
+    else {
+        panic!("unexpected mux results: {results:?}");
+    };
+    assert_eq!(frame.to, Address::from_low_u64_be(CALLER));
+    assert_eq!(frame.calls.len(), 2);
+    let NativeTraceResult::FourByte(expected_ids) = trace(&NativeTracerConfig::FourByte) else {
+        panic!("expected a 4byteTracer result");
+    };
+    assert_eq!(ids, &expected_ids);
+    assert!(prestate.contains_key(&Address::from_low_u64_be(CALLEE)));
+    assert!(prestate.contains_key(&Address::from_low_u64_be(SENDER)));
+}
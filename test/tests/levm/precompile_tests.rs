@@ -1,7 +1,21 @@
+use super::test_db::TestDatabase;
 use bytes::Bytes;
-use ethrex_common::types::Fork;
-use ethrex_crypto::NativeCrypto;
-use ethrex_levm::precompiles::ecpairing;
+use ethrex_common::{
+    Address, U256,
+    types::{Account, BlockHeader, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_crypto::{Crypto, NativeCrypto};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    errors::VMError,
+    precompiles::{
+        IDENTITY, P256VERIFY, PrecompileRegistry, ecpairing, increase_precompile_consumed_gas,
+    },
+    vm::VMType,
+};
+use ethrex_vm::backends::levm::LEVM;
+use rustc_hash::FxHashMap;
+use std::sync::{Arc, LazyLock};
 
 fn test_ec_pairing(calldata: &str, expected_output: &str, mut gas: u64) {
     let calldata = Bytes::from(hex::decode(calldata).unwrap());
@@ -150,3 +164,160 @@ fn test_ec_pairing_coordinate_out_of_bounds() {
         Err(PrecompileError::CoordinateExceedsFieldModulus.into())
     );
 }
+
+fn reverse(
+    calldata: &Bytes,
+    gas_remaining: &mut u64,
+    _fork: Fork,
+    _crypto: &dyn Crypto,
+) -> Result<Bytes, VMError> {
+    increase_precompile_consumed_gas(100, gas_remaining)?;
+    Ok(calldata.iter().rev().copied().collect())
+}
+
+#[test]
+fn test_registry_custom_precompile() {
+    let address = Address::from_low_u64_be(0x0a00);
+    let registry = PrecompileRegistry::new()
+        .with_precompile(address, "REVERSE", reverse)
+        .with_precompile(IDENTITY.address, "REVERSE", reverse);
+
+    assert!(registry.is_precompile(&address, Fork::Prague, VMType::L1));
+    assert!(registry.is_precompile(&IDENTITY.address, Fork::Prague, VMType::L1));
+
+    for address in [address, IDENTITY.address] {
+        let mut gas = 1_000;
+        let output = registry
+            .execute_precompile(
+                address,
+                &Bytes::from_static(&[1, 2, 3]),
+                &mut gas,
+                Fork::Prague,
+                None,
+                &NativeCrypto,
+            )
+            .unwrap();
+        assert_eq!(output, Bytes::from_static(&[3, 2, 1]));
+        assert_eq!(gas, 900);
+    }
+}
+
+#[test]
+fn test_registry_moved_precompile() {
+    let address = Address::from_low_u64_be(0x0a00);
+    let registry = PrecompileRegistry::new().with_moved_precompile(IDENTITY.address, address);
+
+    assert!(!registry.is_precompile(&IDENTITY.address, Fork::Prague, VMType::L1));
+    assert!(registry.is_precompile(&address, Fork::Prague, VMType::L1));
+
+    let calldata = Bytes::from_static(&[1, 2, 3]);
+    let mut gas = 1_000;
+    let output = registry
+        .execute_precompile(
+            address,
+            &calldata,
+            &mut gas,
+            Fork::Prague,
+            None,
+            &NativeCrypto,
+        )
+        .unwrap();
+    assert_eq!(output, calldata);
+    assert_eq!(gas, 1_000 - 18);
+
+    // A moved precompile keeps the activation rules of its original address.
+    let registry = PrecompileRegistry::new().with_moved_precompile(P256VERIFY.address, address);
+    assert!(!registry.is_precompile(&address, Fork::Prague, VMType::L1));
+    assert!(registry.is_precompile(&address, Fork::Osaka, VMType::L1));
+}
+
+const CALLER: u64 = 0xC000;
+const SENDER: u64 = 0x1000;
+const CUSTOM: u16 = 0x0a00;
+const MOVED_IDENTITY: u16 = 0x0b00;
+
+static REGISTRY: LazyLock<PrecompileRegistry> = LazyLock::new(|| {
+    PrecompileRegistry::new()
+        .with_precompile(Address::from_low_u64_be(CUSTOM.into()), "REVERSE", reverse)
+        .with_moved_precompile(
+            IDENTITY.address,
+            Address::from_low_u64_be(MOVED_IDENTITY.into()),
+        )
+});
+
+/// Bytecode that CALLs `target` with `0x010203` as calldata and returns its return data.
+fn call_and_return(target: u16) -> Bytes {
+    let [high, low] = target.to_be_bytes();
+    Bytes::from(vec![
+        0x62, 0x01, 0x02, 0x03, // PUSH3 0x010203
+        0x60, 0x00, 0x52, // PUSH1 0x00 MSTORE
+        0x60, 0x20, // PUSH1 0x20 (ret size)
+        0x60, 0x00, // PUSH1 0x00 (ret offset)
+        0x60, 0x03, // PUSH1 0x03 (args size)
+        0x60, 0x1d, // PUSH1 0x1d (args offset)
+        0x60, 0x00, // PUSH1 0x00 (value)
+        0x61, high, low, // PUSH2 target
+        0x5a, 0xf1, 0x50, // GAS CALL POP
+        0x3d, 0x60, 0x00, 0x60, 0x00, 0x3e, // RETURNDATASIZE PUSH1 0 PUSH1 0 RETURNDATACOPY
+        0x3d, 0x60, 0x00, 0xf3, // RETURNDATASIZE PUSH1 0 RETURN
+    ])
+}
+
+/// Runs a transaction to a contract that calls `target`, with the custom precompiles of
+/// [`REGISTRY`] installed, and returns the output of the call.
+fn call_through_vm(target: u16) -> Bytes {
+    let caller = Address::from_low_u64_be(CALLER);
+    let sender = Address::from_low_u64_be(SENDER);
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        caller,
+        Account::new(
+            U256::zero(),
+            Code::from_bytecode(call_and_return(target), &NativeCrypto),
+            1,
+            FxHashMap::default(),
+        ),
+    );
+    accounts.insert(
+        sender,
+        Account::new(
+            U256::from(10u64).pow(U256::from(18)),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    let mut db = GeneralizedDatabase::new(Arc::new(TestDatabase { accounts }));
+    db.precompiles = Some(&*REGISTRY);
+
+    let header = BlockHeader {
+        base_fee_per_gas: Some(1),
+        gas_limit: 30_000_000,
+        ..Default::default()
+    };
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: 1,
+        max_priority_fee_per_gas: 1,
+        max_fee_per_gas: 10,
+        gas_limit: 100_000,
+        to: TxKind::Call(caller),
+        signature_r: U256::one(),
+        signature_s: U256::one(),
+        ..Default::default()
+    });
+    let report = LEVM::execute_tx(&tx, sender, &header, &mut db, VMType::L1, &NativeCrypto)
+        .expect("execution should succeed");
+    assert!(report.is_success());
+    report.output
+}
+
+#[test]
+fn test_registry_precompiles_through_vm() {
+    assert_eq!(call_through_vm(CUSTOM), Bytes::from_static(&[3, 2, 1]));
+    assert_eq!(
+        call_through_vm(MOVED_IDENTITY),
+        Bytes::from_static(&[1, 2, 3])
+    );
+    // The identity precompile no longer runs at its canonical address.
+    assert_eq!(call_through_vm(0x0004), Bytes::new());
+}
@@ -0,0 +1,119 @@
+//! Fixture based tests for the state transition tool (`t8n`).
+//!
+//! Each directory under `fixtures/t8n` holds the `alloc.json`, `env.json` and
+//! `txs.json` inputs of a transition and an `exp.json` with the expected output. The
+//! expectation only lists the fields the case is about: objects match if every expected
+//! key matches, arrays must have the same length and match element by element.
+
+use std::path::PathBuf;
+
+use ethrex_levm::tracing::OpcodeTracerConfig;
+use runner::t8n::{
+    chain_config, transition,
+    types::{Alloc, T8nEnv, T8nTransaction},
+};
+use serde_json::Value;
+
+const CHAIN_ID: u64 = 1;
+
+fn fixture(case: &str, file: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("../fixtures/t8n")
+        .join(case)
+        .join(file);
+    let contents = std::fs::read_to_string(&path)
+        .unwrap_or_else(|err| panic!("failed reading {}: {err}", path.display()));
+    serde_json::from_str(&contents)
+        .unwrap_or_else(|err| panic!("failed parsing {}: {err}", path.display()))
+}
+
+/// Checks that `actual` contains everything in `expected`, reporting the first mismatch.
+fn assert_matches(actual: &Value, expected: &Value, path: &str) {
+    match (actual, expected) {
+        (Value::Object(actual), Value::Object(expected)) => {
+            for (key, expected) in expected {
+                let actual = actual
+                    .get(key)
+                    .unwrap_or_else(|| panic!("{path}.{key} is missing"));
+                assert_matches(actual, expected, &format!("{path}.{key}"));
+            }
+        }
+        (Value::Array(actual), Value::Array(expected)) => {
+            assert_eq!(actual.len(), expected.len(), "{path} length");
+            for (i, (actual, expected)) in actual.iter().zip(expected).enumerate() {
+                assert_matches(actual, expected, &format!("{path}[{i}]"));
+            }
+        }
+        _ => assert_eq!(actual, expected, "{path}"),
+    }
+}
+
+/// Runs the transition of `case` on `fork` and checks its output against `exp.json`.
+fn run_fixture(case: &str, fork: &str, trace: bool) {
+    let alloc: Alloc = serde_json::from_value(fixture(case, "alloc.json")).unwrap();
+    let env: T8nEnv = serde_json::from_value(fixture(case, "env.json")).unwrap();
+    let txs: Vec<T8nTransaction> = serde_json::from_value(fixture(case, "txs.json")).unwrap();
+    let expected = fixture(case, "exp.json");
+
+    let txs = txs
+        .into_iter()
+        .map(|tx| tx.into_transaction(CHAIN_ID))
+        .collect();
+    let config = chain_config(fork, CHAIN_ID).unwrap();
+    let trace = trace.then(OpcodeTracerConfig::default);
+    let output = transition(alloc, env, txs, config, trace).unwrap();
+
+    if let Some(alloc) = expected.get("alloc") {
+        assert_matches(
+            &serde_json::to_value(&output.alloc).unwrap(),
+            alloc,
+            "alloc",
+        );
+    }
+    if let Some(result) = expected.get("result") {
+        assert_matches(
+            &serde_json::to_value(&output.result).unwrap(),
+            result,
+            "result",
+        );
+    }
+    if let Some(trace) = expected.get("trace") {
+        let [tx_trace] = output.traces.as_slice() else {
+            panic!("expected the trace of a single transaction");
+        };
+        let lines = tx_trace
+            .to_jsonl()
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_matches(&Value::Array(lines), trace, "trace");
+    }
+}
+
+#[test]
+fn invalid_transactions_are_rejected_without_aborting_the_block() {
+    run_fixture("rejected", "Cancun", false);
+}
+
+#[test]
+fn withdrawals_are_credited_in_gwei() {
+    run_fixture("withdrawals", "Shanghai", false);
+}
+
+#[test]
+fn deposit_logs_become_prague_requests() {
+    run_fixture("prague_requests", "Prague", false);
+}
+
+#[test]
+fn opcode_trace_follows_eip_3155() {
+    run_fixture("trace", "Cancun", true);
+}
+
+#[test]
+fn pre_merge_forks_are_rejected() {
+    let err = chain_config("London", CHAIN_ID).unwrap_err();
+    assert_eq!(err.exit_code(), 3);
+    assert!(err.to_string().contains("pre-merge"), "{err}");
+}
@@ -4,7 +4,8 @@ use ethrex_p2p::discovery::DiscoveryServer;
 use ethrex_p2p::discv5::messages::PongMessage;
 use ethrex_p2p::discv5::session::Session;
 use ethrex_p2p::peer_table::{PeerTable, PeerTableServer, PeerTableServerProtocol as _};
-use ethrex_p2p::types::{Node, NodeRecord};
+use ethrex_p2p::types::{Endpoint, Node, NodeRecord};
+use ethrex_p2p::utils::public_key_from_signing_key;
 use ethrex_storage::{EngineType, Store};
 use rand::{SeedableRng, rngs::StdRng};
 use rustc_hash::FxHashSet;
@@ -12,7 +13,7 @@ use secp256k1::SecretKey;
 use std::{
     net::{IpAddr, SocketAddr},
     sync::Arc,
-    time::Instant,
+    time::{Duration, Instant},
 };
 use tokio::net::UdpSocket;
 
@@ -258,6 +259,77 @@ async fn test_enr_update_request_on_pong() {
     );
 }
 
+#[tokio::test]
+async fn test_find_node_sent_over_ipv6_to_dual_stack_node() {
+    let local_node = Node::from_enode_url(
+        "enode://d860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666@[::1]:30303",
+    ).expect("Bad enode url");
+    let signer = SecretKey::new(&mut rand::rngs::OsRng);
+    let local_node_record = NodeRecord::from_node(&local_node, 1, &signer).unwrap();
+
+    // The remote node announces an unused IPv4 port and a reachable IPv6 one
+    let remote_socket = UdpSocket::bind("[::1]:0").await.unwrap();
+    let remote_port = remote_socket.local_addr().unwrap().port();
+    let remote_signer = SecretKey::new(&mut rand::rngs::OsRng);
+    let remote_node = Node::new(
+        "127.0.0.1".parse().unwrap(),
+        1,
+        1,
+        public_key_from_signing_key(&remote_signer),
+    )
+    .with_dual_stack_endpoint(Endpoint {
+        ip: "::1".parse().unwrap(),
+        udp_port: remote_port,
+        tcp_port: remote_port,
+    });
+    let remote_record = NodeRecord::from_node(&remote_node, 5, &remote_signer).unwrap();
+    let remote_node_id = Node::from_enr(&remote_record).unwrap().node_id();
+
+    let peer_table = PeerTableServer::spawn(
+        local_node.node_id(),
+        10,
+        Store::new("", EngineType::InMemory).expect("Failed to create store"),
+    );
+    peer_table.new_contact_records(vec![remote_record]).unwrap();
+    peer_table
+        .set_session_info(
+            remote_node_id,
+            Session {
+                outbound_key: [0u8; 16],
+                inbound_key: [0u8; 16],
+            },
+        )
+        .unwrap();
+
+    let server_socket = Arc::new(UdpSocket::bind("[::1]:0").await.unwrap());
+    let server_addr = server_socket.local_addr().unwrap();
+    let mut server = DiscoveryServer::new_for_discv5_test(
+        local_node,
+        local_node_record,
+        signer,
+        server_socket,
+        peer_table,
+    );
+
+    // A PONG with a newer ENR makes the server request it with FINDNODE
+    let pong = PongMessage {
+        req_id: Bytes::from(vec![1, 2, 3]),
+        enr_seq: 10,
+        recipient_addr: "[::1]:30303".parse().unwrap(),
+    };
+    server
+        .discv5_handle_pong(pong, remote_node_id)
+        .await
+        .expect("handle_pong failed");
+
+    let mut buf = [0u8; 1280];
+    let (_, from) = tokio::time::timeout(Duration::from_secs(1), remote_socket.recv_from(&mut buf))
+        .await
+        .expect("FINDNODE should be sent to the IPv6 endpoint")
+        .unwrap();
+    assert_eq!(from, server_addr);
+}
+
 #[tokio::test]
 async fn test_ip_voting_updates_ip_on_threshold() {
     let mut server = test_server(None).await;
@@ -268,16 +340,16 @@ async fn test_ip_voting_updates_ip_on_threshold() {
     let voter2 = H256::from_low_u64_be(2);
     let voter3 = H256::from_low_u64_be(3);
 
-    assert_eq!(discv5(&mut server).record_ip_vote(new_ip, voter1), None);
+    assert_eq!(server.ip_votes.record(new_ip, voter1), None);
     assert_eq!(server.local_node.ip, original_ip);
 
-    assert_eq!(discv5(&mut server).record_ip_vote(new_ip, voter2), None);
+    assert_eq!(server.ip_votes.record(new_ip, voter2), None);
     assert_eq!(server.local_node.ip, original_ip);
 
     // Vote 3 triggers round end and returns the winning IP
-    let result = discv5(&mut server).record_ip_vote(new_ip, voter3);
+    let result = server.ip_votes.record(new_ip, voter3);
     assert_eq!(result, Some(new_ip));
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    assert!(server.ip_votes.votes.is_empty());
 }
 
 #[tokio::test]
@@ -287,14 +359,11 @@ async fn test_ip_voting_same_peer_votes_once() {
     let new_ip: IpAddr = "203.0.113.50".parse().unwrap();
     let same_voter = H256::from_low_u64_be(1);
 
-    discv5(&mut server).record_ip_vote(new_ip, same_voter);
-    discv5(&mut server).record_ip_vote(new_ip, same_voter);
-    discv5(&mut server).record_ip_vote(new_ip, same_voter);
+    server.ip_votes.record(new_ip, same_voter);
+    server.ip_votes.record(new_ip, same_voter);
+    server.ip_votes.record(new_ip, same_voter);
 
-    assert_eq!(
-        discv5(&mut server).ip_votes.get(&new_ip).map(|v| v.len()),
-        Some(1)
-    );
+    assert_eq!(server.ip_votes.votes.get(&new_ip).map(|v| v.len()), Some(1));
 }
 
 #[tokio::test]
@@ -306,13 +375,13 @@ async fn test_ip_voting_no_update_if_same_ip() {
     let voter2 = H256::from_low_u64_be(2);
     let voter3 = H256::from_low_u64_be(3);
 
-    discv5(&mut server).record_ip_vote(original_ip, voter1);
-    discv5(&mut server).record_ip_vote(original_ip, voter2);
-    discv5(&mut server).record_ip_vote(original_ip, voter3);
+    server.ip_votes.record(original_ip, voter1);
+    server.ip_votes.record(original_ip, voter2);
+    server.ip_votes.record(original_ip, voter3);
 
     assert_eq!(server.local_node.ip, original_ip);
-    assert!(discv5(&mut server).ip_votes.is_empty());
-    assert!(discv5(&mut server).first_ip_vote_round_completed);
+    assert!(server.ip_votes.votes.is_empty());
+    assert!(server.ip_votes.first_round_completed);
 }
 
 #[tokio::test]
@@ -338,7 +407,7 @@ async fn test_handle_pong_same_ip_does_not_bump_enr_seq() {
 
     // Round must have actually completed; otherwise the guard at discv5_handle_pong
     // is never evaluated and the assertions below would trivially pass.
-    assert!(discv5(&mut server).first_ip_vote_round_completed);
+    assert!(server.ip_votes.first_round_completed);
     // Voting round reached threshold with the local IP as winner; the guard at
     // discv5_handle_pong must skip update_local_ip and leave the ENR sequence intact.
     assert_eq!(server.local_node.ip, original_ip);
@@ -356,16 +425,16 @@ async fn test_ip_voting_split_votes_no_update() {
     let voter2 = H256::from_low_u64_be(2);
     let voter3 = H256::from_low_u64_be(3);
 
-    discv5(&mut server).record_ip_vote(ip1, voter1);
+    server.ip_votes.record(ip1, voter1);
     assert_eq!(server.local_node.ip, original_ip);
 
-    discv5(&mut server).record_ip_vote(ip2, voter2);
+    server.ip_votes.record(ip2, voter2);
     assert_eq!(server.local_node.ip, original_ip);
 
-    discv5(&mut server).record_ip_vote(ip1, voter3);
+    server.ip_votes.record(ip1, voter3);
     assert_eq!(server.local_node.ip, original_ip);
-    assert!(discv5(&mut server).ip_votes.is_empty());
-    assert!(discv5(&mut server).first_ip_vote_round_completed);
+    assert!(server.ip_votes.votes.is_empty());
+    assert!(server.ip_votes.first_round_completed);
 }
 
 #[tokio::test]
@@ -377,14 +446,14 @@ async fn test_ip_vote_cleanup() {
 
     let mut voters = FxHashSet::default();
     voters.insert(voter1);
-    discv5(&mut server).ip_votes.insert(ip, voters);
-    discv5(&mut server).ip_vote_period_start = Some(Instant::now());
-    assert_eq!(discv5(&mut server).ip_votes.len(), 1);
+    server.ip_votes.votes.insert(ip, voters);
+    server.ip_votes.period_start = Some(Instant::now());
+    assert_eq!(server.ip_votes.votes.len(), 1);
 
-    discv5(&mut server).cleanup_stale_entries();
-    assert_eq!(discv5(&mut server).ip_votes.len(), 1);
+    assert_eq!(server.ip_votes.expire_round(), None);
+    assert_eq!(server.ip_votes.votes.len(), 1);
 
-    assert!(!discv5(&mut server).first_ip_vote_round_completed);
+    assert!(!server.ip_votes.first_round_completed);
 }
 
 #[tokio::test]
@@ -396,33 +465,33 @@ async fn test_ip_voting_ignores_private_ips() {
     let voter3 = H256::from_low_u64_be(3);
 
     let private_ip: IpAddr = "192.168.1.100".parse().unwrap();
-    discv5(&mut server).record_ip_vote(private_ip, voter1);
-    discv5(&mut server).record_ip_vote(private_ip, voter2);
-    discv5(&mut server).record_ip_vote(private_ip, voter3);
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    server.ip_votes.record(private_ip, voter1);
+    server.ip_votes.record(private_ip, voter2);
+    server.ip_votes.record(private_ip, voter3);
+    assert!(server.ip_votes.votes.is_empty());
 
     let loopback: IpAddr = "127.0.0.1".parse().unwrap();
-    discv5(&mut server).record_ip_vote(loopback, voter1);
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    server.ip_votes.record(loopback, voter1);
+    assert!(server.ip_votes.votes.is_empty());
 
     let link_local: IpAddr = "169.254.1.1".parse().unwrap();
-    discv5(&mut server).record_ip_vote(link_local, voter1);
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    server.ip_votes.record(link_local, voter1);
+    assert!(server.ip_votes.votes.is_empty());
 
     let ipv6_loopback: IpAddr = "::1".parse().unwrap();
-    discv5(&mut server).record_ip_vote(ipv6_loopback, voter1);
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    server.ip_votes.record(ipv6_loopback, voter1);
+    assert!(server.ip_votes.votes.is_empty());
 
     let ipv6_link_local: IpAddr = "fe80::1".parse().unwrap();
-    discv5(&mut server).record_ip_vote(ipv6_link_local, voter1);
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    server.ip_votes.record(ipv6_link_local, voter1);
+    assert!(server.ip_votes.votes.is_empty());
 
     let ipv6_unique_local: IpAddr = "fd12::1".parse().unwrap();
-    discv5(&mut server).record_ip_vote(ipv6_unique_local, voter1);
-    assert!(discv5(&mut server).ip_votes.is_empty());
+    server.ip_votes.record(ipv6_unique_local, voter1);
+    assert!(server.ip_votes.votes.is_empty());
 
     let public_ip: IpAddr = "203.0.113.50".parse().unwrap();
-    discv5(&mut server).record_ip_vote(public_ip, voter1);
+    server.ip_votes.record(public_ip, voter1);
     assert_eq!(
         discv5(&mut server)
             .ip_votes
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use ethrex_common::{H512, base64};
+use ethrex_crypto::keccak::keccak_hash;
+use ethrex_p2p::discovery::dns::{
+    DnsDiscovery, DnsDiscoveryError, DnsResolver, EnrTreeUrl, subdomain_hash,
+};
+use ethrex_p2p::types::{Node, NodeRecord};
+use secp256k1::{PublicKey, SECP256K1, SecretKey};
+
+/// In-process DNS zone mapping names to their TXT records.
+#[derive(Default)]
+struct FakeZone {
+    records: HashMap<String, Vec<String>>,
+}
+
+impl DnsResolver for FakeZone {
+    async fn txt_records(&self, name: &str) -> Result<Vec<String>, DnsDiscoveryError> {
+        Ok(self.records.get(name).cloned().unwrap_or_default())
+    }
+}
+
+impl FakeZone {
+    /// Publishes `entry` under its hash in `domain` and returns the hash.
+    fn add_entry(&mut self, domain: &str, entry: String) -> String {
+        let hash = subdomain_hash(&entry);
+        self.records
+            .entry(format!("{hash}.{domain}"))
+            .or_default()
+            .push(entry);
+        hash
+    }
+
+    /// Publishes a tree with `records` and `links` signed by `key` and returns its URL.
+    fn add_tree(
+        &mut self,
+        domain: &str,
+        key: &SecretKey,
+        records: &[NodeRecord],
+        links: &[EnrTreeUrl],
+    ) -> EnrTreeUrl {
+        let record_hashes: Vec<String> = records
+            .iter()
+            .map(|record| self.add_entry(domain, record.enr_url().unwrap()))
+            .collect();
+        let enr_root = self.add_entry(
+            domain,
+            format!("enrtree-branch:{}", record_hashes.join(",")),
+        );
+        let link_hashes: Vec<String> = links
+            .iter()
+            .map(|link| self.add_entry(domain, link.to_string()))
+            .collect();
+        let link_root = self.add_entry(domain, format!("enrtree-branch:{}", link_hashes.join(",")));
+
+        let signed = format!("enrtree-root:v1 e={enr_root} l={link_root} seq=1");
+        let root = format!("{signed} sig={}", sign(&signed, key));
+        self.records.insert(domain.to_string(), vec![root]);
+
+        EnrTreeUrl {
+            public_key: PublicKey::from_secret_key(SECP256K1, key),
+            domain: domain.to_string(),
+        }
+    }
+}
+
+/// Signs a root record like EIP-1459 publishers do: unpadded base64 of [R || S || V].
+fn sign(text: &str, key: &SecretKey) -> String {
+    let message = secp256k1::Message::from_digest(keccak_hash(text.as_bytes()));
+    let (recovery_id, signature) = SECP256K1
+        .sign_ecdsa_recoverable(&message, key)
+        .serialize_compact();
+    let mut bytes = signature.to_vec();
+    bytes.push(Into::<i32>::into(recovery_id) as u8);
+    String::from_utf8(base64::encode(&bytes))
+        .unwrap()
+        .trim_end_matches('=')
+        .to_string()
+}
+
+fn node_record(port: u16) -> NodeRecord {
+    let signer = SecretKey::new(&mut rand::rngs::OsRng);
+    let public_key = PublicKey::from_secret_key(SECP256K1, &signer);
+    let node = Node::new(
+        "127.0.0.1".parse().unwrap(),
+        port,
+        port,
+        H512::from_slice(&public_key.serialize_uncompressed()[1..]),
+    );
+    NodeRecord::from_node(&node, 1, &signer).unwrap()
+}
+
+fn tree_key() -> SecretKey {
+    SecretKey::new(&mut rand::rngs::OsRng)
+}
+
+#[test]
+fn enrtree_url_round_trip() {
+    let url = EnrTreeUrl {
+        public_key: PublicKey::from_secret_key(SECP256K1, &tree_key()),
+        domain: "nodes.example.org".to_string(),
+    };
+    let parsed: EnrTreeUrl = url.to_string().parse().unwrap();
+    assert_eq!(parsed, url);
+
+    assert!("enrtree://nodes.example.org".parse::<EnrTreeUrl>().is_err());
+    assert!(
+        "enrtree://AAAA@nodes.example.org"
+            .parse::<EnrTreeUrl>()
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn resolves_records_of_tree_and_linked_trees() {
+    let mut zone = FakeZone::default();
+    let linked_records = [node_record(30303)];
+    let linked = zone.add_tree("linked.example.org", &tree_key(), &linked_records, &[]);
+    let records = [node_record(30304), node_record(30305)];
+    let url = zone.add_tree(
+        "nodes.example.org",
+        &tree_key(),
+        &records,
+        std::slice::from_ref(&linked),
+    );
+
+    let mut resolved = DnsDiscovery::new(zone).resolve(&url).await.unwrap();
+    resolved.sort_by_key(|record| record.pairs().udp_port);
+    assert_eq!(
+        resolved,
+        [linked_records.as_slice(), records.as_slice()].concat()
+    );
+}
+
+#[tokio::test]
+async fn rejects_root_signed_by_another_key() {
+    let mut zone = FakeZone::default();
+    let mut url = zone.add_tree("nodes.example.org", &tree_key(), &[node_record(30303)], &[]);
+    url.public_key = PublicKey::from_secret_key(SECP256K1, &tree_key());
+
+    let result = DnsDiscovery::new(zone).resolve(&url).await;
+    assert!(matches!(
+        result,
+        Err(DnsDiscoveryError::InvalidSignature(_))
+    ));
+}
+
+#[tokio::test]
+async fn rejects_entry_not_matching_its_hash() {
+    let mut zone = FakeZone::default();
+    let url = zone.add_tree("nodes.example.org", &tree_key(), &[node_record(30303)], &[]);
+    // Replace the published node record with a different one
+    let tampered = node_record(30304).enr_url().unwrap();
+    for (name, entries) in zone.records.iter_mut() {
+        if name != "nodes.example.org" && entries[0].starts_with("enr:") {
+            entries[0] = tampered.clone();
+        }
+    }
+
+    let result = DnsDiscovery::new(zone).resolve(&url).await;
+    assert!(matches!(result, Err(DnsDiscoveryError::MissingEntry(_))));
+}
@@ -2,4 +2,5 @@ mod discv4_messages_tests;
 mod discv5_messages_tests;
 mod discv5_server_tests;
 mod discv5_session_tests;
+mod dns_tests;
 mod multiplexer_tests;
@@ -2,4 +2,5 @@ mod backend_tests;
 mod discovery;
 mod rlpx;
 mod snap_server_tests;
+mod sync_tests;
 mod types_tests;
@@ -1,4 +1,7 @@
-use ethrex_common::types::BlockHash;
+use ethrex_common::{
+    H256,
+    types::{BlockHash, BlockHeader},
+};
 use ethrex_p2p::rlpx::{
     eth::blocks::{BlockBodies, GetBlockBodies, GetBlockHeaders, HashOrNumber},
     message::RLPxMessage,
@@ -32,6 +35,26 @@ fn get_block_headers_startblock_hash_message() {
     );
 }
 
+#[test]
+fn hash_or_number_identifies_only_the_requested_header() {
+    let header = BlockHeader {
+        number: 7,
+        state_root: H256::repeat_byte(1),
+        ..Default::default()
+    };
+    // Same number, but a different block
+    let other = BlockHeader {
+        state_root: H256::repeat_byte(2),
+        ..header.clone()
+    };
+
+    assert!(HashOrNumber::Hash(header.hash()).identifies(&header));
+    assert!(!HashOrNumber::Hash(header.hash()).identifies(&other));
+    assert!(HashOrNumber::Number(7).identifies(&header));
+    assert!(HashOrNumber::Number(7).identifies(&other));
+    assert!(!HashOrNumber::Number(8).identifies(&header));
+}
+
 #[test]
 fn get_block_bodies_empty_message() {
     let blocks_hash = vec![];
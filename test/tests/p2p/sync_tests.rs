@@ -0,0 +1,52 @@
+use std::str::FromStr;
+
+use ethrex_common::H256;
+use ethrex_p2p::{rlpx::eth::blocks::HashOrNumber, sync::SyncCheckpoint};
+
+const HASH: &str = "0x88e96d4537bea4d9c05d12549907b32561d3bf31f45aae734cdc119f13406cb6";
+const STATE_ROOT: &str = "0xd7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544";
+
+#[test]
+fn sync_checkpoint_from_hash() {
+    let checkpoint = SyncCheckpoint::from_str(&format!("{HASH}:{STATE_ROOT}")).unwrap();
+    assert_eq!(
+        checkpoint,
+        SyncCheckpoint {
+            block: HashOrNumber::Hash(H256::from_str(&HASH[2..]).unwrap()),
+            state_root: H256::from_str(&STATE_ROOT[2..]).unwrap(),
+        }
+    );
+}
+
+#[test]
+fn sync_checkpoint_from_number() {
+    let checkpoint = SyncCheckpoint::from_str(&format!("1:{STATE_ROOT}")).unwrap();
+    assert_eq!(checkpoint.block, HashOrNumber::Number(1));
+    assert_eq!(
+        checkpoint.state_root,
+        H256::from_str(&STATE_ROOT[2..]).unwrap()
+    );
+}
+
+#[test]
+fn malformed_sync_checkpoints_are_rejected() {
+    for checkpoint in [
+        // Missing state root
+        HASH.to_string(),
+        "1".to_string(),
+        "1:".to_string(),
+        // Missing block
+        format!(":{STATE_ROOT}"),
+        // Hashes must be 0x-prefixed, anything else is a block number
+        format!("{}:{STATE_ROOT}", &HASH[2..]),
+        format!("-1:{STATE_ROOT}"),
+        format!("0x1234:{STATE_ROOT}"),
+        format!("1:{}", &STATE_ROOT[..20]),
+        format!("1:{STATE_ROOT}:{STATE_ROOT}"),
+    ] {
+        assert!(
+            SyncCheckpoint::from_str(&checkpoint).is_err(),
+            "checkpoint {checkpoint:?} was accepted"
+        );
+    }
+}
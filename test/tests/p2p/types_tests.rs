@@ -1,12 +1,15 @@
 use bytes::Bytes;
 use ethrex_common::H512;
-use ethrex_p2p::types::{Node, NodeRecord};
+use ethrex_p2p::types::{Endpoint, Node, NodeRecord};
 use ethrex_p2p::utils::public_key_from_signing_key;
 use ethrex_rlp::decode::RLPDecode;
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_storage::{EngineType, Store};
 use secp256k1::SecretKey;
-use std::{net::SocketAddr, str::FromStr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    str::FromStr,
+};
 
 const TEST_GENESIS: &str = include_str!("../../../fixtures/genesis/l1.json");
 
@@ -62,6 +65,96 @@ fn parse_node_from_enr_string() {
     assert_eq!(node, expected_node);
 }
 
+#[test]
+fn parse_node_from_ipv6_enode_string() {
+    let input = "enode://d860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666@[::1]:30303?discport=30305";
+    let node = Node::from_enode_url(input).unwrap();
+    assert_eq!(node.ip, "::1".parse::<IpAddr>().unwrap());
+    assert_eq!(
+        node.tcp_addr(),
+        SocketAddr::from_str("[::1]:30303").unwrap()
+    );
+    assert_eq!(
+        node.udp_addr(),
+        SocketAddr::from_str("[::1]:30305").unwrap()
+    );
+    assert_eq!(node.enode_url(), input);
+
+    let missing_bracket = "enode://d860a01f9722d78051619d1e2351aba3f43f943f6f00718d1b9baa4101932a1f5011f16bb2b1bb35db20d6fe28fa0bf09636d26a87d31de9ec6203eeedb1f666@[::1:30303";
+    assert!(Node::from_enode_url(missing_bracket).is_err());
+}
+
+#[test]
+fn ipv6_node_record_round_trip() {
+    let signer = SecretKey::new(&mut rand::rngs::OsRng);
+    let node = Node::new(
+        "::1".parse().unwrap(),
+        30305,
+        30303,
+        public_key_from_signing_key(&signer),
+    );
+    let record = NodeRecord::from_node(&node, 1, &signer).unwrap();
+    let pairs = record.pairs();
+    assert_eq!(pairs.ip, None);
+    assert_eq!(pairs.ip6, Some("::1".parse().unwrap()));
+    assert_eq!(pairs.tcp6_port, Some(30303));
+    assert_eq!(pairs.udp6_port, Some(30305));
+
+    let decoded = NodeRecord::decode(&record.encode_to_vec()).unwrap();
+    assert!(decoded.verify_signature());
+    assert_eq!(Node::from_enr(&decoded).unwrap(), node);
+}
+
+#[test]
+fn dual_stack_node_record_round_trip() {
+    let signer = SecretKey::new(&mut rand::rngs::OsRng);
+    let node = Node::new(
+        "127.0.0.1".parse().unwrap(),
+        30303,
+        30303,
+        public_key_from_signing_key(&signer),
+    )
+    .with_dual_stack_endpoint(Endpoint {
+        ip: "::1".parse().unwrap(),
+        udp_port: 30304,
+        tcp_port: 30304,
+    });
+    let record = NodeRecord::from_node(&node, 1, &signer).unwrap();
+    let pairs = record.pairs();
+    assert!(pairs.has_family(false) && pairs.has_family(true));
+    assert_eq!(pairs.udp_port, Some(30303));
+    assert_eq!(pairs.udp6_port, Some(30304));
+
+    let decoded = Node::from_enr(&record).unwrap();
+    assert_eq!(decoded, node);
+    assert!(decoded.has_ip("::1".parse().unwrap()));
+    assert!(decoded.has_ip("::ffff:127.0.0.1".parse().unwrap()));
+
+    // The endpoint of the requested family becomes the primary one
+    let v6 = decoded.in_family(true).unwrap();
+    assert_eq!(v6.udp_addr(), SocketAddr::from_str("[::1]:30304").unwrap());
+    assert_eq!(v6.in_family(false).unwrap(), decoded);
+
+    // Sockets reach the endpoint of their own family, and `::` reaches IPv4 too
+    let ipv6_loopback = "::1".parse().unwrap();
+    let ipv4_any = "0.0.0.0".parse().unwrap();
+    let dual_stack = "::".parse().unwrap();
+    assert_eq!(
+        decoded.udp_addr_from(ipv6_loopback),
+        Some(SocketAddr::from_str("[::1]:30304").unwrap())
+    );
+    assert_eq!(
+        decoded.udp_addr_from(ipv4_any),
+        Some(SocketAddr::from_str("127.0.0.1:30303").unwrap())
+    );
+    assert_eq!(
+        decoded.tcp_addr_from(dual_stack),
+        Some(SocketAddr::from_str("[::ffff:127.0.0.1]:30303").unwrap())
+    );
+    let ipv4_only = Node::new("127.0.0.1".parse().unwrap(), 30303, 30303, node.public_key);
+    assert_eq!(ipv4_only.udp_addr_from(ipv6_loopback), None);
+}
+
 #[tokio::test]
 async fn encode_node_record_to_enr_url() {
     // https://github.com/ethereum/devp2p/blob/master/enr.md#test-vectors
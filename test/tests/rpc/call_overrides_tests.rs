@@ -0,0 +1,140 @@
+use ethrex_rpc::test_utils::{call_http, default_context_with_storage, setup_store};
+use serde_json::{Value, json};
+
+const CONTRACT: &str = "0x000000000000000000000000000000000000c0de";
+const SENDER: &str = "0x0000000000000000000000000000000000001000";
+/// The ecrecover precompile.
+const ECRECOVER: &str = "0x0000000000000000000000000000000000000001";
+
+/// `PUSH1 0x00 SLOAD PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN`
+const RETURN_SLOT_0: &str = "0x60005460005260206000f3";
+/// `NUMBER PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN`
+const RETURN_NUMBER: &str = "0x4360005260206000f3";
+/// `BLOBBASEFEE PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN`
+const RETURN_BLOB_BASE_FEE: &str = "0x4a60005260206000f3";
+/// Reverts unless slot 0 is set: `PUSH1 0x00 SLOAD PUSH1 0x0b JUMPI PUSH1 0x00 PUSH1 0x00
+/// REVERT JUMPDEST STOP`
+const REVERT_UNLESS_SLOT_0: &str = "0x600054600b5760006000fd5b00";
+
+const SLOT_0: &str = "0x0000000000000000000000000000000000000000000000000000000000000000";
+
+async fn request(method: &str, params: Value) -> Value {
+    let storage = setup_store().await;
+    let context = default_context_with_storage(storage).await;
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    call_http(context, request.to_string()).await
+}
+
+fn call() -> Value {
+    json!({ "from": SENDER, "to": CONTRACT })
+}
+
+fn word(value: u64) -> String {
+    format!("{value:#066x}")
+}
+
+fn with_code(code: &str) -> Value {
+    json!({ CONTRACT: { "code": code } })
+}
+
+#[tokio::test]
+async fn eth_call_applies_state_overrides() {
+    let state_diff =
+        json!({ CONTRACT: { "code": RETURN_SLOT_0, "stateDiff": { SLOT_0: word(0x2a) } } });
+    let response = request("eth_call", json!([call(), "latest", state_diff])).await;
+    assert_eq!(response["result"], word(0x2a), "{response}");
+
+    let state = json!({ CONTRACT: { "code": RETURN_SLOT_0, "state": { SLOT_0: word(7) } } });
+    let response = request("eth_call", json!([call(), "latest", state])).await;
+    assert_eq!(response["result"], word(7), "{response}");
+
+    let response = request(
+        "eth_call",
+        json!([call(), "latest", with_code(RETURN_SLOT_0)]),
+    )
+    .await;
+    assert_eq!(response["result"], word(0), "{response}");
+}
+
+#[tokio::test]
+async fn eth_call_applies_block_overrides() {
+    let response = request(
+        "eth_call",
+        json!([call(), "latest", with_code(RETURN_NUMBER), { "number": "0x99" }]),
+    )
+    .await;
+    assert_eq!(response["result"], word(0x99), "{response}");
+
+    let response = request(
+        "eth_call",
+        json!([call(), "latest", with_code(RETURN_BLOB_BASE_FEE), { "blobBaseFee": "0x7" }]),
+    )
+    .await;
+    assert_eq!(response["result"], word(7), "{response}");
+}
+
+#[tokio::test]
+async fn eth_estimate_gas_applies_state_overrides() {
+    let response = request(
+        "eth_estimateGas",
+        json!([call(), "latest", with_code(REVERT_UNLESS_SLOT_0)]),
+    )
+    .await;
+    assert!(response.get("error").is_some(), "{response}");
+
+    let state_diff =
+        json!({ CONTRACT: { "code": REVERT_UNLESS_SLOT_0, "stateDiff": { SLOT_0: word(1) } } });
+    let response = request("eth_estimateGas", json!([call(), "latest", state_diff])).await;
+    assert!(response["result"].is_string(), "{response}");
+}
+
+#[tokio::test]
+async fn debug_trace_call_applies_overrides() {
+    let trace_call = |code: &str, block_overrides: Value| {
+        json!([
+            call(),
+            "latest",
+            {
+                "tracer": "callTracer",
+                "stateOverrides": with_code(code),
+                "blockOverrides": block_overrides,
+            },
+        ])
+    };
+
+    let response = request(
+        "debug_traceCall",
+        trace_call(RETURN_NUMBER, json!({ "number": "0x99" })),
+    )
+    .await;
+    assert_eq!(response["result"]["output"], word(0x99), "{response}");
+
+    // The blob base fee only lives in the execution environment, not in the header
+    let response = request(
+        "debug_traceCall",
+        trace_call(RETURN_BLOB_BASE_FEE, json!({ "blobBaseFee": "0x7" })),
+    )
+    .await;
+    assert_eq!(response["result"]["output"], word(7), "{response}");
+}
+
+#[tokio::test]
+async fn moved_precompiles_are_rejected_outside_simulate() {
+    let state = json!({ ECRECOVER: { "movePrecompileToAddress": CONTRACT } });
+    for (method, params) in [
+        ("eth_call", json!([call(), "latest", state])),
+        ("eth_estimateGas", json!([call(), "latest", state])),
+        (
+            "debug_traceCall",
+            json!([call(), "latest", { "stateOverrides": state }]),
+        ),
+    ] {
+        let response = request(method, params).await;
+        assert_eq!(response["error"]["code"], -32000, "{method}: {response}");
+        let message = response["error"]["message"].as_str().unwrap_or_default();
+        assert!(
+            message.contains("movePrecompileToAddress"),
+            "{method}: {message}"
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ethrex_common::types::BlockHeader;
+use ethrex_rpc::ipc::{IpcConfig, MAX_IPC_LINE_LENGTH, handle_ipc_connection, ipc_context};
+use ethrex_rpc::subscription_manager::{SubscriptionManager, SubscriptionManagerProtocol};
+use ethrex_rpc::test_utils::default_context_with_storage;
+use ethrex_rpc::{DEFAULT_HTTP_API, RpcApiContext, map_http_requests};
+use ethrex_storage::{EngineType, Store};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::UnixStream;
+
+async fn test_context() -> RpcApiContext {
+    let storage = Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+    let mut context = default_context_with_storage(storage).await;
+    context.allowed_namespaces = Arc::new(DEFAULT_HTTP_API.iter().copied().collect());
+    context
+}
+
+async fn connect() -> (BufReader<UnixStream>, tokio::task::JoinHandle<()>) {
+    connect_with(test_context().await)
+}
+
+/// Spawns an IPC connection handler on one end of a socket pair and returns the other end.
+fn connect_with(context: RpcApiContext) -> (BufReader<UnixStream>, tokio::task::JoinHandle<()>) {
+    let context = ipc_context(&context);
+    let (client, server) = UnixStream::pair().expect("Failed to create socket pair");
+    let handle = tokio::spawn(async move {
+        let route_ctx = context.clone();
+        handle_ipc_connection(server, &context, move |req| {
+            let c = route_ctx.clone();
+            async move { map_http_requests(&req, c).await }
+        })
+        .await;
+    });
+    (BufReader::new(client), handle)
+}
+
+async fn request(client: &mut BufReader<UnixStream>, body: &str) -> Value {
+    client
+        .get_mut()
+        .write_all(format!("{body}\n").as_bytes())
+        .await
+        .unwrap();
+    read_message(client).await
+}
+
+async fn read_message(client: &mut BufReader<UnixStream>) -> Value {
+    let mut line = String::new();
+    tokio::time::timeout(Duration::from_secs(2), client.read_line(&mut line))
+        .await
+        .expect("timed out waiting for a message")
+        .unwrap();
+    assert!(line.ends_with('\n'), "messages must be newline-terminated");
+    serde_json::from_str(&line).unwrap()
+}
+
+/// IPC serves namespaces left out of `--http.api`, like `txpool`.
+#[tokio::test]
+async fn ipc_serves_namespaces_outside_http_api() {
+    let (mut client, _handle) = connect().await;
+
+    let value = request(
+        &mut client,
+        r#"{"jsonrpc":"2.0","method":"txpool_status","params":[],"id":1}"#,
+    )
+    .await;
+    assert!(value.get("error").is_none(), "unexpected error: {value}");
+    assert_eq!(value["id"], 1);
+    assert!(value["result"].get("pending").is_some());
+}
+
+/// A malformed line gets a parse error without closing the connection, and batches
+/// are answered on a single line.
+#[tokio::test]
+async fn ipc_keeps_connection_after_parse_error_and_handles_batches() {
+    let (mut client, _handle) = connect().await;
+
+    let value = request(&mut client, "{not json").await;
+    assert_eq!(value["error"]["code"], -32700);
+
+    let value = request(
+        &mut client,
+        r#"[{"jsonrpc":"2.0","method":"web3_clientVersion","params":[],"id":1},{"jsonrpc":"2.0","method":"net_version","params":[],"id":2}]"#,
+    )
+    .await;
+    let responses = value.as_array().expect("batch response must be an array");
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["id"], 1);
+    assert_eq!(responses[1]["id"], 2);
+}
+
+/// The connection handler returns once the client hangs up.
+#[tokio::test]
+async fn ipc_connection_ends_on_client_close() {
+    let (client, handle) = connect().await;
+    drop(client);
+    handle.await.unwrap();
+}
+
+/// `eth_subscribe` over IPC delivers `eth_subscription` notifications on the same
+/// connection.
+#[tokio::test]
+async fn ipc_subscription_receives_notifications() {
+    let subscription_manager = SubscriptionManager::spawn();
+    let mut context = test_context().await;
+    context.ipc = Some(IpcConfig {
+        path: PathBuf::from("ethrex.ipc"),
+        subscription_manager: subscription_manager.clone(),
+    });
+    let (mut client, _handle) = connect_with(context);
+
+    let value = request(
+        &mut client,
+        r#"{"jsonrpc":"2.0","method":"eth_subscribe","params":["newHeads"],"id":1}"#,
+    )
+    .await;
+    assert!(value.get("error").is_none(), "unexpected error: {value}");
+    let id = value["result"]
+        .as_str()
+        .expect("subscription id")
+        .to_string();
+
+    subscription_manager
+        .new_head(BlockHeader::default())
+        .unwrap();
+
+    let notification = read_message(&mut client).await;
+    assert_eq!(notification["method"], "eth_subscription");
+    assert_eq!(notification["params"]["subscription"], id);
+    assert!(notification["params"]["result"]["hash"].is_string());
+}
+
+/// A request line longer than [`MAX_IPC_LINE_LENGTH`] closes the connection instead of
+/// being buffered.
+#[tokio::test]
+async fn ipc_closes_connection_on_oversized_line() {
+    let (mut client, handle) = connect().await;
+
+    // The handler hangs up before the whole line is written, so the write may fail
+    let _ = client
+        .get_mut()
+        .write_all(&vec![b'a'; MAX_IPC_LINE_LENGTH + 1])
+        .await;
+    tokio::time::timeout(Duration::from_secs(10), handle)
+        .await
+        .expect("connection wasn't closed")
+        .unwrap();
+}
@@ -1,6 +1,11 @@
 mod authrpc_batch_tests;
 mod block_access_list_tests;
+mod call_overrides_tests;
 mod client_version_tests;
 mod fork_choice_tests;
 mod http_batch_tests;
+mod ipc_tests;
+mod pruned_history_tests;
+mod simulate_tests;
 mod subscription_manager_tests;
+mod tracing_tests;
@@ -0,0 +1,83 @@
+use std::time::{Duration, Instant};
+
+use ethrex_common::types::{Block, BlockBody, BlockHeader, Genesis};
+use ethrex_rpc::test_utils::{TEST_GENESIS, call_http, default_context_with_storage};
+use ethrex_storage::{EngineType, HistoryRetention, Store, StoreConfig};
+use serde_json::{Value, json};
+
+const BLOCKS: u64 = 5;
+
+/// Returns a store whose history was pruned up to the last block.
+async fn pruned_store() -> Store {
+    let config = StoreConfig {
+        history_retention: HistoryRetention::Blocks(1),
+        ..Default::default()
+    };
+    let mut store =
+        Store::new_with_config("", EngineType::InMemory, config).expect("Failed to create store");
+    let genesis: Genesis = serde_json::from_str(TEST_GENESIS).expect("valid genesis");
+    let mut parent = genesis.get_block().header;
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis");
+
+    let mut canonical = vec![];
+    for number in 1..=BLOCKS {
+        let header = BlockHeader {
+            parent_hash: parent.hash(),
+            number,
+            ..Default::default()
+        };
+        store
+            .add_block(Block::new(header.clone(), BlockBody::empty()))
+            .await
+            .unwrap();
+        canonical.push((number, header.hash()));
+        parent = header;
+    }
+    store
+        .forkchoice_update(canonical, BLOCKS, parent.hash(), Some(BLOCKS), Some(BLOCKS))
+        .await
+        .unwrap();
+
+    let start = Instant::now();
+    while store.get_history_cutoff().await.unwrap() < BLOCKS {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "history wasn't pruned"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    store
+}
+
+async fn request(store: Store, method: &str, params: Value) -> Value {
+    let context = default_context_with_storage(store).await;
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    call_http(context, request.to_string()).await
+}
+
+#[tokio::test]
+async fn pruned_history_is_reported_with_eip_4444_error() {
+    let store = pruned_store().await;
+    for (method, params) in [
+        ("eth_getBlockByNumber", json!(["0x2", false])),
+        ("eth_getBlockReceipts", json!(["0x2"])),
+        ("debug_getRawReceipts", json!(["0x2"])),
+        ("trace_block", json!(["0x2"])),
+        (
+            "trace_filter",
+            json!([{ "fromBlock": "0x2", "toBlock": "0x2" }]),
+        ),
+    ] {
+        let response = request(store.clone(), method, params).await;
+        assert_eq!(response["error"]["code"], 4444, "{method}: {response}");
+    }
+
+    // Genesis and the retained blocks are still served
+    for block in ["0x0", "0x5"] {
+        let response = request(store.clone(), "eth_getBlockByNumber", json!([block, false])).await;
+        assert_eq!(response["result"]["number"], block, "{response}");
+    }
+}
@@ -0,0 +1,194 @@
+use ethrex_rpc::test_utils::{call_http, default_context_with_storage, setup_store};
+use serde_json::{Value, json};
+
+const CONTRACT: &str = "0x000000000000000000000000000000000000c0de";
+const SENDER: &str = "0x0000000000000000000000000000000000001000";
+
+const RECIPIENT: &str = "0x000000000000000000000000000000000000beef";
+
+/// `PUSH1 0x01 BLOCKHASH PUSH1 0x00 MSTORE PUSH1 0x20 PUSH1 0x00 RETURN`
+const RETURN_BLOCKHASH_OF_1: &str = "0x60014060005260206000f3";
+
+/// `CALL(GAS, RECIPIENT, 1, 0, 0, 0, 0) POP LOG0(0, 0) STOP`
+const FORWARD_ONE_WEI_AND_LOG: &str =
+    "0x6000600060006000600173000000000000000000000000000000000000beef5af15060006000a000";
+
+/// keccak256("Transfer(address,address,uint256)")
+const TRANSFER_TOPIC: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+
+/// ERC-7528 pseudo-address of ETH.
+const ETH_ADDRESS: &str = "0xeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee";
+
+async fn simulate(block_state_calls: Value) -> Value {
+    simulate_payload(json!({ "blockStateCalls": block_state_calls })).await
+}
+
+async fn simulate_payload(payload: Value) -> Value {
+    let storage = setup_store().await;
+    let context = default_context_with_storage(storage).await;
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_simulateV1",
+        "params": [payload, "latest"],
+    });
+    call_http(context, request.to_string()).await
+}
+
+/// An address as a 32 bytes log topic.
+fn address_topic(address: &str) -> String {
+    format!("0x{:0>64}", address.trim_start_matches("0x"))
+}
+
+#[tokio::test]
+async fn simulated_blocks_are_visible_to_blockhash() {
+    let response = simulate(json!([
+        {},
+        {
+            "stateOverrides": { CONTRACT: { "code": RETURN_BLOCKHASH_OF_1 } },
+            "calls": [{ "from": SENDER, "to": CONTRACT }],
+        },
+    ]))
+    .await;
+    let blocks = response["result"]
+        .as_array()
+        .unwrap_or_else(|| panic!("simulation failed: {response}"));
+    assert_eq!(blocks.len(), 2);
+
+    let first_hash = &blocks[0]["hash"];
+    assert_eq!(blocks[0]["number"], "0x1");
+    assert_eq!(&blocks[1]["parentHash"], first_hash);
+    assert_eq!(&blocks[1]["calls"][0]["returnData"], first_hash);
+}
+
+#[tokio::test]
+async fn simulated_blocks_have_their_own_state_root() {
+    let response = simulate(json!([
+        {},
+        {
+            "stateOverrides": { CONTRACT: { "code": RETURN_BLOCKHASH_OF_1 } },
+            "calls": [{ "from": SENDER, "to": CONTRACT }],
+        },
+    ]))
+    .await;
+    let storage = setup_store().await;
+    let genesis = storage
+        .get_block_header(0)
+        .expect("Failed to read genesis")
+        .expect("Genesis missing");
+
+    let first_root = &response["result"][0]["stateRoot"];
+    let second_root = &response["result"][1]["stateRoot"];
+    assert_ne!(*first_root, json!(format!("{:#x}", genesis.state_root)));
+    assert_ne!(first_root, second_root);
+
+    // The state root only depends on the state changes, so it's deterministic
+    let again = simulate(json!([{}])).await;
+    assert_eq!(&again["result"][0]["stateRoot"], first_root);
+}
+
+#[tokio::test]
+async fn simulate_reports_spec_error_codes() {
+    let unordered = simulate(json!([
+        { "blockOverrides": { "number": "0x2" } },
+        { "blockOverrides": { "number": "0x1" } },
+    ]))
+    .await;
+    assert_eq!(unordered["error"]["code"], -38020);
+
+    // Earlier than the genesis timestamp
+    let stale = simulate(json!([{ "blockOverrides": { "time": "0x10" } }])).await;
+    assert_eq!(stale["error"]["code"], -38021);
+
+    let too_many = simulate(json!([{ "blockOverrides": { "number": "0x1000" } }])).await;
+    assert_eq!(too_many["error"]["code"], -38026);
+
+    let over_gas_limit = simulate(json!([{
+        "blockOverrides": { "gasLimit": "0x5208" },
+        "calls": [
+            { "from": SENDER, "to": CONTRACT, "gas": "0x5208" },
+            { "from": SENDER, "to": CONTRACT, "gas": "0x5208" },
+        ],
+    }]))
+    .await;
+    assert_eq!(over_gas_limit["error"]["code"], -38015);
+}
+
+#[tokio::test]
+async fn trace_transfers_reports_eth_transfers_as_logs() {
+    let response = simulate_payload(json!({
+        "blockStateCalls": [{
+            "stateOverrides": {
+                SENDER: { "balance": "0xde0b6b3a7640000" },
+                CONTRACT: { "code": FORWARD_ONE_WEI_AND_LOG },
+            },
+            "calls": [{ "from": SENDER, "to": CONTRACT, "value": "0x2" }],
+        }],
+        "traceTransfers": true,
+    }))
+    .await;
+    let call = &response["result"][0]["calls"][0];
+    assert_eq!(call["status"], "0x1", "simulation failed: {response}");
+    let logs = call["logs"].as_array().unwrap();
+    assert_eq!(logs.len(), 3, "{logs:?}");
+
+    // The value sent by the call, then the one forwarded by the contract
+    for (log, (from, to, value)) in logs
+        .iter()
+        .zip([(SENDER, CONTRACT, 2_u8), (CONTRACT, RECIPIENT, 1)])
+    {
+        assert_eq!(log["address"], ETH_ADDRESS);
+        assert_eq!(
+            log["topics"],
+            json!([TRANSFER_TOPIC, address_topic(from), address_topic(to)])
+        );
+        assert_eq!(log["data"], format!("0x{value:064x}"));
+    }
+    // The contract's own log comes after the transfer made before it
+    assert_eq!(logs[2]["address"], CONTRACT);
+    assert_eq!(logs[2]["topics"], json!([]));
+
+    // Without the flag only the contract's log is reported
+    let response = simulate(json!([{
+        "stateOverrides": {
+            SENDER: { "balance": "0xde0b6b3a7640000" },
+            CONTRACT: { "code": FORWARD_ONE_WEI_AND_LOG },
+        },
+        "calls": [{ "from": SENDER, "to": CONTRACT, "value": "0x2" }],
+    }]))
+    .await;
+    let logs = response["result"][0]["calls"][0]["logs"]
+        .as_array()
+        .unwrap();
+    assert_eq!(logs.len(), 1, "{logs:?}");
+    assert_eq!(logs[0]["address"], CONTRACT);
+}
+
+#[tokio::test]
+async fn moved_precompile_runs_at_its_new_address() {
+    const IDENTITY: &str = "0x0000000000000000000000000000000000000004";
+    const NEW_ADDRESS: &str = "0x0000000000000000000000000000000000123456";
+    let response = simulate(json!([
+        {
+            "stateOverrides": { IDENTITY: { "movePrecompileToAddress": NEW_ADDRESS } },
+            "calls": [
+                { "from": SENDER, "to": NEW_ADDRESS, "input": "0x1234" },
+                { "from": SENDER, "to": IDENTITY, "input": "0x1234" },
+            ],
+        },
+        // The precompile stays moved for the following blocks
+        { "calls": [{ "from": SENDER, "to": NEW_ADDRESS, "input": "0x5678" }] },
+    ]))
+    .await;
+    let blocks = response["result"]
+        .as_array()
+        .unwrap_or_else(|| panic!("simulation failed: {response}"));
+
+    let calls = &blocks[0]["calls"];
+    assert_eq!(calls[0]["status"], "0x1");
+    assert_eq!(calls[0]["returnData"], "0x1234");
+    // Its original address no longer runs it
+    assert_eq!(calls[1]["status"], "0x1");
+    assert_eq!(calls[1]["returnData"], "0x");
+    assert_eq!(blocks[1]["calls"][0]["returnData"], "0x5678");
+}
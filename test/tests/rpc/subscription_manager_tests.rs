@@ -1,6 +1,12 @@
-use ethrex_common::types::BlockHeader;
+use std::sync::Arc;
+
+use ethrex_blockchain::events::{BlockWithReceipts, ChainEvent};
+use ethrex_common::H256;
+use ethrex_common::types::{
+    Block, BlockBody, BlockHeader, EIP1559Transaction, MempoolTransaction, Transaction,
+};
 use ethrex_rpc::subscription_manager::{
-    MAX_TOTAL_SUBSCRIPTIONS, SUBSCRIBER_CHANNEL_CAPACITY, SubscriptionManager,
+    MAX_TOTAL_SUBSCRIPTIONS, SUBSCRIBER_CHANNEL_CAPACITY, SubscriptionKind, SubscriptionManager,
     SubscriptionManagerProtocol,
 };
 use tokio::sync::mpsc;
@@ -12,8 +18,16 @@ async fn subscribe_returns_unique_ids() {
     let (tx1, _rx1) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
     let (tx2, _rx2) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
 
-    let id1 = manager.subscribe(tx1).await.unwrap().unwrap();
-    let id2 = manager.subscribe(tx2).await.unwrap().unwrap();
+    let id1 = manager
+        .subscribe(tx1, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
+    let id2 = manager
+        .subscribe(tx2, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
 
     assert_ne!(id1, id2);
     assert!(id1.starts_with("0x"));
@@ -25,7 +39,11 @@ async fn unsubscribe_existing_returns_true() {
     let manager = SubscriptionManager::spawn();
 
     let (tx, _rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
-    let id = manager.subscribe(tx).await.unwrap().unwrap();
+    let id = manager
+        .subscribe(tx, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
 
     let removed = manager.unsubscribe(id).await.unwrap();
     assert!(removed);
@@ -44,7 +62,11 @@ async fn unsubscribe_twice_returns_false_second_time() {
     let manager = SubscriptionManager::spawn();
 
     let (tx, _rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
-    let id = manager.subscribe(tx).await.unwrap().unwrap();
+    let id = manager
+        .subscribe(tx, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
 
     assert!(manager.unsubscribe(id.clone()).await.unwrap());
     assert!(!manager.unsubscribe(id).await.unwrap());
@@ -57,8 +79,16 @@ async fn new_head_fans_out_to_all_subscribers() {
     let (tx1, mut rx1) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
     let (tx2, mut rx2) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
 
-    let id1 = manager.subscribe(tx1).await.unwrap().unwrap();
-    let id2 = manager.subscribe(tx2).await.unwrap().unwrap();
+    let id1 = manager
+        .subscribe(tx1, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
+    let id2 = manager
+        .subscribe(tx2, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
 
     let header = BlockHeader::default();
     manager.new_head(header).unwrap();
@@ -90,8 +120,16 @@ async fn new_head_removes_dead_subscribers() {
     let (tx_alive, mut rx_alive) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
     let (tx_dead, rx_dead) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
 
-    let _id_alive = manager.subscribe(tx_alive).await.unwrap().unwrap();
-    let id_dead = manager.subscribe(tx_dead).await.unwrap().unwrap();
+    let _id_alive = manager
+        .subscribe(tx_alive, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
+    let id_dead = manager
+        .subscribe(tx_dead, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
 
     // Drop the receiver so the dead subscriber's channel is closed.
     drop(rx_dead);
@@ -126,7 +164,11 @@ async fn notification_contains_block_hash() {
     let manager = SubscriptionManager::spawn();
 
     let (tx, mut rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
-    let _id = manager.subscribe(tx).await.unwrap().unwrap();
+    let _id = manager
+        .subscribe(tx, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
 
     let header = BlockHeader::default();
     let expected_hash = format!("{:#x}", header.hash());
@@ -154,13 +196,215 @@ async fn subscribe_returns_none_at_global_cap() {
     let mut keep_alive = Vec::with_capacity(MAX_TOTAL_SUBSCRIPTIONS);
     for _ in 0..MAX_TOTAL_SUBSCRIPTIONS {
         let (tx, rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
-        let id = manager.subscribe(tx).await.unwrap();
+        let id = manager
+            .subscribe(tx, SubscriptionKind::NewHeads)
+            .await
+            .unwrap();
         assert!(id.is_some());
         keep_alive.push(rx);
     }
 
     // The next subscribe must be refused.
     let (tx_overflow, _rx_overflow) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
-    let result = manager.subscribe(tx_overflow).await.unwrap();
+    let result = manager
+        .subscribe(tx_overflow, SubscriptionKind::NewHeads)
+        .await
+        .unwrap();
     assert!(result.is_none(), "expected None at MAX_TOTAL_SUBSCRIPTIONS");
 }
+
+async fn recv_notification(rx: &mut mpsc::Receiver<String>) -> serde_json::Value {
+    let msg = tokio::time::timeout(std::time::Duration::from_secs(2), rx.recv())
+        .await
+        .expect("timed out")
+        .expect("channel closed");
+    serde_json::from_str(&msg).unwrap()
+}
+
+#[tokio::test]
+async fn new_head_is_only_sent_to_new_heads_subscribers() {
+    let manager = SubscriptionManager::spawn();
+
+    let (tx_heads, mut rx_heads) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    let (tx_syncing, mut rx_syncing) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    manager
+        .subscribe(tx_heads, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
+    manager
+        .subscribe(tx_syncing, SubscriptionKind::Syncing)
+        .await
+        .unwrap()
+        .unwrap();
+
+    manager.new_head(BlockHeader::default()).unwrap();
+
+    recv_notification(&mut rx_heads).await;
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(rx_syncing.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn pending_transaction_is_sent_as_hash_or_full_transaction() {
+    let manager = SubscriptionManager::spawn();
+
+    let (tx_hashes, mut rx_hashes) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    let (tx_full, mut rx_full) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    let (tx_heads, mut rx_heads) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    manager
+        .subscribe(
+            tx_hashes,
+            SubscriptionKind::NewPendingTransactions { full: false },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    manager
+        .subscribe(
+            tx_full,
+            SubscriptionKind::NewPendingTransactions { full: true },
+        )
+        .await
+        .unwrap()
+        .unwrap();
+    manager
+        .subscribe(tx_heads, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let transaction = Transaction::EIP1559Transaction(EIP1559Transaction::default());
+    let hash = transaction.hash();
+    let sender = ethrex_common::Address::from_low_u64_be(1);
+    manager
+        .notify_chain_event(ChainEvent::NewPendingTransaction {
+            hash,
+            transaction: Box::new(MempoolTransaction::new(transaction, sender)),
+        })
+        .unwrap();
+
+    let expected_hash = format!("{hash:#x}");
+    let v = recv_notification(&mut rx_hashes).await;
+    assert_eq!(v["params"]["result"].as_str().unwrap(), expected_hash);
+
+    let v = recv_notification(&mut rx_full).await;
+    assert_eq!(
+        v["params"]["result"]["hash"].as_str().unwrap(),
+        expected_hash
+    );
+    assert_eq!(
+        v["params"]["result"]["from"].as_str().unwrap(),
+        format!("{sender:#x}")
+    );
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(rx_heads.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn sync_status_changes_are_sent_to_syncing_subscribers() {
+    let manager = SubscriptionManager::spawn();
+
+    let (tx, mut rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    manager
+        .subscribe(tx, SubscriptionKind::Syncing)
+        .await
+        .unwrap()
+        .unwrap();
+
+    manager
+        .notify_chain_event(ChainEvent::SyncStatusChanged { syncing: true })
+        .unwrap();
+    manager
+        .notify_chain_event(ChainEvent::SyncStatusChanged { syncing: false })
+        .unwrap();
+
+    let v = recv_notification(&mut rx).await;
+    assert_eq!(v["params"]["result"]["syncing"], true);
+    let v = recv_notification(&mut rx).await;
+    assert_eq!(v["params"]["result"], false);
+}
+
+#[tokio::test]
+async fn head_changes_are_sent_to_new_heads_subscribers() {
+    let manager = SubscriptionManager::spawn();
+
+    let (tx, mut rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    manager
+        .subscribe(tx, SubscriptionKind::NewHeads)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let block = |number| BlockWithReceipts {
+        block: Block::new(
+            BlockHeader {
+                number,
+                ..Default::default()
+            },
+            BlockBody::default(),
+        ),
+        receipts: Vec::new(),
+    };
+    let added = vec![block(1), block(2)];
+    let expected: Vec<_> = added
+        .iter()
+        .map(|b| format!("{:#x}", b.block.hash()))
+        .collect();
+    manager
+        .notify_chain_event(ChainEvent::HeadChanged {
+            removed: Arc::new(vec![block(3)]),
+            added: Arc::new(added),
+        })
+        .unwrap();
+
+    // One notification per block that joined the canonical chain, oldest first.
+    for hash in expected {
+        let v = recv_notification(&mut rx).await;
+        assert_eq!(v["params"]["result"]["hash"].as_str().unwrap(), hash);
+    }
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(rx.try_recv().is_err());
+}
+
+#[tokio::test]
+async fn chain_events_are_forwarded_from_the_blockchain_channels() {
+    let (events, receiver) = tokio::sync::broadcast::channel(16);
+    let (pending_transactions, pending_receiver) = tokio::sync::broadcast::channel(16);
+    let manager = SubscriptionManager::spawn_with_chain_events(receiver, pending_receiver);
+
+    let (tx, mut rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    manager
+        .subscribe(tx, SubscriptionKind::NewPendingTransactions { full: false })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let transaction = Transaction::EIP1559Transaction(EIP1559Transaction::default());
+    pending_transactions
+        .send(ChainEvent::NewPendingTransaction {
+            hash: H256::repeat_byte(1),
+            transaction: Box::new(MempoolTransaction::new(transaction, Default::default())),
+        })
+        .unwrap();
+
+    let v = recv_notification(&mut rx).await;
+    assert_eq!(
+        v["params"]["result"].as_str().unwrap(),
+        format!("{:#x}", H256::repeat_byte(1))
+    );
+
+    let (tx, mut rx) = mpsc::channel(SUBSCRIBER_CHANNEL_CAPACITY);
+    manager
+        .subscribe(tx, SubscriptionKind::Syncing)
+        .await
+        .unwrap()
+        .unwrap();
+    events
+        .send(ChainEvent::SyncStatusChanged { syncing: true })
+        .unwrap();
+
+    let v = recv_notification(&mut rx).await;
+    assert_eq!(v["params"]["result"]["syncing"], true);
+}
@@ -0,0 +1,315 @@
+use bytes::Bytes;
+use ethrex_blockchain::payload::{BuildPayloadArgs, create_payload};
+use ethrex_common::{
+    Address, H160, H256, U256,
+    types::{
+        Block, DEFAULT_BUILDER_GAS_CEIL, EIP1559Transaction, ELASTICITY_MULTIPLIER, Genesis,
+        GenesisAccount, Transaction, TxKind,
+    },
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_rlp::encode::RLPEncode;
+use ethrex_rpc::{
+    rpc::RpcApiContext,
+    test_utils::{TEST_GENESIS, call_http, default_context_with_storage},
+};
+use ethrex_storage::{EngineType, Store};
+use secp256k1::SecretKey;
+use serde_json::{Value, json};
+
+const TEST_PRIVATE_KEY: &str = "850643a0224065ecce3882673c21f56bcf6eef86274cc21cadff15930b59fc8c";
+
+fn contract() -> Address {
+    Address::from_low_u64_be(0xc0de)
+}
+
+fn secret_key() -> SecretKey {
+    SecretKey::from_slice(&hex::decode(TEST_PRIVATE_KEY).unwrap()).expect("valid key")
+}
+
+fn sender() -> Address {
+    LocalSigner::new(secret_key()).address
+}
+
+/// Builds a chain whose block 1 holds a single call to a contract that writes a slot.
+async fn chain_with_one_call() -> (RpcApiContext, Block) {
+    let sender = sender();
+    let signer: Signer = LocalSigner::new(secret_key()).into();
+
+    let mut genesis: Genesis = serde_json::from_str(TEST_GENESIS).expect("valid genesis");
+    genesis.alloc.insert(
+        sender,
+        GenesisAccount {
+            balance: U256::from(10).pow(U256::from(20)),
+            code: Bytes::new(),
+            storage: Default::default(),
+            nonce: 0,
+        },
+    );
+    genesis.alloc.insert(
+        contract(),
+        GenesisAccount {
+            // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+            code: Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x00]),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 0,
+        },
+    );
+    let chain_id = genesis.config.chain_id;
+    let mut store = Store::new("", EngineType::InMemory).expect("Failed to create store");
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis");
+    let context = default_context_with_storage(store.clone()).await;
+
+    let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id,
+        nonce: 0,
+        max_priority_fee_per_gas: 0,
+        max_fee_per_gas: 10_000_000_000,
+        gas_limit: 100_000,
+        to: TxKind::Call(contract()),
+        ..Default::default()
+    });
+    tx.sign_inplace(&signer).await.unwrap();
+    context
+        .blockchain
+        .add_transaction_to_pool(tx)
+        .await
+        .expect("tx should enter the pool");
+
+    let parent = store.get_block_header(0).unwrap().unwrap();
+    let args = BuildPayloadArgs {
+        parent: parent.hash(),
+        timestamp: parent.timestamp + 12,
+        fee_recipient: H160::zero(),
+        random: H256::zero(),
+        withdrawals: Some(Vec::new()),
+        beacon_root: Some(H256::zero()),
+        slot_number: None,
+        version: 1,
+        elasticity_multiplier: ELASTICITY_MULTIPLIER,
+        gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+    };
+    let payload = create_payload(&args, &store, Bytes::new()).unwrap();
+    let block = context.blockchain.build_payload(payload).unwrap().payload;
+    assert_eq!(block.body.transactions.len(), 1);
+    context
+        .blockchain
+        .add_block(block.clone())
+        .expect("block should be valid");
+    store
+        .forkchoice_update(vec![], block.header.number, block.hash(), None, None)
+        .await
+        .unwrap();
+    (context, block)
+}
+
+async fn trace_block(context: RpcApiContext, method: &str, block: Value) -> Value {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": method,
+        "params": [block, { "tracer": "callTracer" }],
+    });
+    call_http(context, request.to_string()).await
+}
+
+fn assert_block_trace(response: &Value, block: &Block) {
+    let traces = response["result"]
+        .as_array()
+        .unwrap_or_else(|| panic!("trace failed: {response}"));
+    assert_eq!(traces.len(), 1);
+    let tx_hash = block.body.transactions[0].hash();
+    assert_eq!(traces[0]["txHash"], json!(tx_hash));
+    assert_eq!(traces[0]["result"]["to"], json!(contract()));
+    assert_eq!(traces[0]["result"]["type"], "CALL");
+    assert!(traces[0]["result"]["error"].is_null());
+}
+
+#[tokio::test]
+async fn debug_trace_block_by_number() {
+    let (context, block) = chain_with_one_call().await;
+    let response = trace_block(context, "debug_traceBlockByNumber", json!("0x1")).await;
+    assert_block_trace(&response, &block);
+}
+
+#[tokio::test]
+async fn debug_trace_block_by_hash() {
+    let (context, block) = chain_with_one_call().await;
+    let response = trace_block(context, "debug_traceBlockByHash", json!(block.hash())).await;
+    assert_block_trace(&response, &block);
+}
+
+#[tokio::test]
+async fn debug_trace_block_from_rlp() {
+    let (context, block) = chain_with_one_call().await;
+    let rlp = format!("0x{}", hex::encode(block.encode_to_vec()));
+    let response = trace_block(context, "debug_traceBlock", json!(rlp)).await;
+    assert_block_trace(&response, &block);
+}
+
+#[tokio::test]
+async fn debug_trace_block_rejects_invalid_rlp() {
+    let (context, block) = chain_with_one_call().await;
+    let mut rlp = block.encode_to_vec();
+    rlp.truncate(rlp.len() - 1);
+    let response = trace_block(
+        context,
+        "debug_traceBlock",
+        json!(format!("0x{}", hex::encode(rlp))),
+    )
+    .await;
+    let message = response["error"]["message"]
+        .as_str()
+        .unwrap_or_else(|| panic!("expected an error: {response}"));
+    assert!(message.contains("Invalid block RLP"), "{message}");
+}
+
+async fn request(context: RpcApiContext, method: &str, params: Value) -> Value {
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    call_http(context, request.to_string()).await
+}
+
+fn results(response: &Value) -> &Vec<Value> {
+    response["result"]
+        .as_array()
+        .unwrap_or_else(|| panic!("request failed: {response}"))
+}
+
+/// Checks `trace` is the localized top-level call of the block's single transaction.
+fn assert_localized_call(trace: &Value, block: &Block) {
+    assert_eq!(trace["type"], "call");
+    assert_eq!(trace["action"]["callType"], "call");
+    assert_eq!(trace["action"]["from"], json!(sender()));
+    assert_eq!(trace["action"]["to"], json!(contract()));
+    assert_eq!(trace["traceAddress"], json!([]));
+    assert_eq!(trace["subtraces"], 0);
+    assert!(trace["error"].is_null(), "{trace}");
+    assert_eq!(trace["blockNumber"], 1);
+    assert_eq!(trace["blockHash"], json!(block.hash()));
+    assert_eq!(
+        trace["transactionHash"],
+        json!(block.body.transactions[0].hash())
+    );
+    assert_eq!(trace["transactionPosition"], 0);
+}
+
+#[tokio::test]
+async fn trace_block_returns_localized_traces() {
+    let (context, block) = chain_with_one_call().await;
+    let response = request(context, "trace_block", json!(["0x1"])).await;
+    let traces = results(&response);
+    assert_eq!(traces.len(), 1);
+    assert_localized_call(&traces[0], &block);
+}
+
+#[tokio::test]
+async fn trace_transaction_returns_localized_traces() {
+    let (context, block) = chain_with_one_call().await;
+    let tx_hash = block.body.transactions[0].hash();
+    let response = request(context, "trace_transaction", json!([tx_hash])).await;
+    let traces = results(&response);
+    assert_eq!(traces.len(), 1);
+    assert_localized_call(&traces[0], &block);
+}
+
+#[tokio::test]
+async fn trace_filter_matches_addresses_and_pages() {
+    let (context, block) = chain_with_one_call().await;
+    let filter = |extra: Value| {
+        let mut filter = json!({ "fromBlock": "0x1", "toBlock": "0x1" });
+        filter
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        json!([filter])
+    };
+
+    let response = request(
+        context.clone(),
+        "trace_filter",
+        filter(json!({ "fromAddress": [sender()], "toAddress": [contract()] })),
+    )
+    .await;
+    let traces = results(&response);
+    assert_eq!(traces.len(), 1);
+    assert_localized_call(&traces[0], &block);
+
+    for (extra, expected) in [
+        (json!({ "toAddress": [sender()] }), 0),
+        (json!({ "fromAddress": [contract()] }), 0),
+        (json!({ "after": 1 }), 0),
+        (json!({ "count": 0 }), 0),
+        (json!({ "after": 0, "count": 1 }), 1),
+    ] {
+        let response = request(context.clone(), "trace_filter", filter(extra.clone())).await;
+        assert_eq!(results(&response).len(), expected, "{extra}");
+    }
+}
+
+#[tokio::test]
+async fn trace_replay_block_transactions_state_diff() {
+    let (context, block) = chain_with_one_call().await;
+    let response = request(
+        context,
+        "trace_replayBlockTransactions",
+        json!(["0x1", ["stateDiff"]]),
+    )
+    .await;
+    let results = results(&response);
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert_eq!(
+        result["transactionHash"],
+        json!(block.body.transactions[0].hash())
+    );
+    assert_eq!(result["trace"], json!([]));
+    assert!(result["vmTrace"].is_null());
+
+    let contract_diff = &result["stateDiff"][format!("{:#x}", contract())];
+    assert_eq!(contract_diff["balance"], "=");
+    assert_eq!(contract_diff["code"], "=");
+    assert_eq!(
+        contract_diff["storage"][format!("{:#x}", H256::zero())]["*"],
+        json!({ "from": H256::zero(), "to": H256::from_low_u64_be(1) })
+    );
+    // The sender paid for gas and bumped its nonce
+    let sender_diff = &result["stateDiff"][format!("{:#x}", sender())];
+    assert_eq!(
+        sender_diff["nonce"]["*"],
+        json!({ "from": "0x0", "to": "0x1" })
+    );
+}
+
+#[tokio::test]
+async fn trace_replay_block_transactions_vm_trace() {
+    let (context, _) = chain_with_one_call().await;
+    let response = request(
+        context,
+        "trace_replayBlockTransactions",
+        json!(["0x1", ["trace", "vmTrace"]]),
+    )
+    .await;
+    let results = results(&response);
+    assert_eq!(results.len(), 1);
+    let result = &results[0];
+    assert!(result["stateDiff"].is_null());
+    assert_eq!(result["trace"].as_array().unwrap().len(), 1);
+
+    let vm_trace = &result["vmTrace"];
+    assert_eq!(vm_trace["code"], "0x600160005500");
+    let ops = vm_trace["ops"].as_array().unwrap();
+    // PUSH1 0x01
+    assert_eq!(ops[0]["pc"], 0);
+    assert_eq!(ops[0]["ex"]["push"], json!(["0x1"]));
+    // SSTORE writes the slot
+    let sstore = ops
+        .iter()
+        .find(|op| op["pc"] == 4)
+        .expect("SSTORE wasn't traced");
+    assert_eq!(sstore["ex"]["store"], json!({ "key": "0x0", "val": "0x1" }));
+    assert!(ops.iter().all(|op| op["sub"].is_null()));
+}
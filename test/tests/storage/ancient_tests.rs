@@ -0,0 +1,168 @@
+//! Tests for the ancient store (`--datadir.ancient`): the bodies and receipts of blocks
+//! finalized more than `ANCIENT_THRESHOLD` blocks ago are moved out of the database in the
+//! background, and reads fall through to the ancient store.
+
+use std::{
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use ethrex_common::{
+    H256,
+    types::{
+        Block, BlockBody, BlockHeader, BlockNumber, EIP1559Transaction, Genesis, Receipt,
+        Transaction, TxType,
+    },
+};
+use ethrex_rlp::encode::RLPEncode;
+use ethrex_storage::{ANCIENT_THRESHOLD, EngineType, Store, StoreConfig};
+
+const BLOCKS: u64 = 10;
+
+fn receipts() -> Vec<Receipt> {
+    vec![Receipt::new(TxType::EIP1559, true, 21_000, vec![])]
+}
+
+/// Stores genesis and blocks `1..=BLOCKS`, each with a transaction and its receipt, and
+/// finalizes a block deep enough for all of them to be moved to the ancient store.
+async fn store_chain(store: &mut Store) -> Vec<Block> {
+    let genesis = Genesis::default();
+    let mut parent = genesis.get_block().header;
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis");
+
+    let mut blocks = vec![];
+    for number in 1..=BLOCKS {
+        let header = BlockHeader {
+            parent_hash: parent.hash(),
+            number,
+            ..Default::default()
+        };
+        let body = BlockBody {
+            transactions: vec![Transaction::EIP1559Transaction(EIP1559Transaction {
+                nonce: number,
+                ..Default::default()
+            })],
+            ..BlockBody::empty()
+        };
+        let block = Block::new(header.clone(), body);
+        store.add_block(block.clone()).await.unwrap();
+        store.add_receipts(block.hash(), receipts()).await.unwrap();
+        parent = header;
+        blocks.push(block);
+    }
+
+    let canonical = blocks
+        .iter()
+        .map(|block| (block.header.number, block.hash()))
+        .collect();
+    store
+        .forkchoice_update(
+            canonical,
+            BLOCKS,
+            parent.hash(),
+            None,
+            Some(ANCIENT_THRESHOLD + BLOCKS + 1),
+        )
+        .await
+        .unwrap();
+    blocks
+}
+
+/// Waits for the background worker to move the blocks up to `count` to the ancient store.
+async fn wait_for_ancients(store: &Store, count: BlockNumber) {
+    let start = Instant::now();
+    while store.get_ancient_block_count() < count {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "blocks weren't moved to the ancient store"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Checks that the history of `blocks` is served, whether from the database or the
+/// ancient store.
+async fn assert_history(store: &Store, blocks: &[Block]) {
+    assert!(store.get_block_body(0).await.unwrap().is_some());
+    for block in blocks {
+        let number = block.header.number;
+        let body = Some(block.body.encode_to_vec());
+        assert_eq!(
+            store
+                .get_block_body(number)
+                .await
+                .unwrap()
+                .map(|body| body.encode_to_vec()),
+            body,
+            "body of block {number}"
+        );
+        assert_eq!(
+            store
+                .get_block_body_by_hash(block.hash())
+                .await
+                .unwrap()
+                .map(|body| body.encode_to_vec()),
+            body,
+            "body of block {number} by hash"
+        );
+        assert_eq!(
+            store.get_receipts_for_block(&block.hash()).await.unwrap(),
+            receipts(),
+            "receipts of block {number}"
+        );
+        assert!(
+            store
+                .get_transaction_location(block.body.transactions[0].hash())
+                .await
+                .unwrap()
+                .is_some(),
+            "transaction location of block {number}"
+        );
+    }
+}
+
+async fn ancient_history_survives_reopen(config: impl Fn() -> StoreConfig) {
+    let path = format!("ancient-test-db-{}", H256::random().to_low_u64_be());
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut store = Store::new_with_config(&path, EngineType::RocksDB, config())
+        .expect("Failed to create store");
+    let blocks = store_chain(&mut store).await;
+    wait_for_ancients(&store, BLOCKS + 1).await;
+    assert_history(&store, &blocks).await;
+    drop(store);
+
+    let store = Store::new_with_config(&path, EngineType::RocksDB, config())
+        .expect("Failed to reopen store");
+    assert_eq!(store.get_ancient_block_count(), BLOCKS + 1);
+    assert_history(&store, &blocks).await;
+
+    drop(store);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+#[tokio::test]
+async fn ancient_history_is_served_after_reopen() {
+    ancient_history_survives_reopen(StoreConfig::default).await;
+}
+
+#[tokio::test]
+async fn ancient_history_in_a_separate_dir_is_served_after_reopen() {
+    let ancient_dir = PathBuf::from(format!(
+        "ancient-test-dir-{}",
+        H256::random().to_low_u64_be()
+    ));
+    let _ = std::fs::remove_dir_all(&ancient_dir);
+
+    ancient_history_survives_reopen(|| StoreConfig {
+        ancient_dir: Some(ancient_dir.clone()),
+        ..Default::default()
+    })
+    .await;
+    assert!(ancient_dir.is_dir());
+
+    let _ = std::fs::remove_dir_all(&ancient_dir);
+}
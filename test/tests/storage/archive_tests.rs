@@ -0,0 +1,218 @@
+//! Tests for archive mode (`--gcmode archive`): the state of blocks whose diff-layers were
+//! already written to disk is read back through the trie node history.
+//!
+//! Blocks are stored in batch mode, which writes every diff-layer but the 4 most recent to
+//! disk. After storing blocks `1..=n`, block `n - 4` is the disk layer, blocks
+//! `n - 3..=n` are in memory and every older block, genesis included, is archived.
+
+use ethrex_common::{
+    Address, H256, U256,
+    constants::EMPTY_KECCAK_HASH,
+    types::{AccountInfo, AccountUpdate, Block, BlockBody, BlockHeader, Genesis, GenesisAccount},
+};
+use ethrex_storage::{EngineType, GcMode, Store, StoreConfig, UpdateBatch, error::StoreError};
+
+const BLOCKS: u64 = 10;
+
+fn account() -> Address {
+    Address::from_low_u64_be(0xA)
+}
+
+/// Account only written in block 2, so its leaf is never overwritten afterwards.
+fn untouched_account() -> Address {
+    Address::from_low_u64_be(0xB)
+}
+
+fn slot() -> H256 {
+    H256::from_low_u64_be(1)
+}
+
+fn archive_config() -> StoreConfig {
+    StoreConfig {
+        gc_mode: GcMode::Archive,
+        ..Default::default()
+    }
+}
+
+async fn new_archive_store(path: &str, engine_type: EngineType) -> (Store, BlockHeader) {
+    let mut store = Store::new_with_config(path, engine_type, archive_config())
+        .expect("Failed to create store");
+    let genesis = Genesis {
+        alloc: [(
+            account(),
+            GenesisAccount {
+                code: Default::default(),
+                storage: Default::default(),
+                balance: U256::zero(),
+                nonce: 0,
+            },
+        )]
+        .into(),
+        ..Default::default()
+    };
+    let genesis_header = genesis.get_block().header;
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis");
+    (store, genesis_header)
+}
+
+/// Updates of block `number`: `account` gets `value` as balance, nonce and `slot`.
+fn block_updates(number: u64, value: u64) -> Vec<AccountUpdate> {
+    let mut update = AccountUpdate::new(account());
+    update.info = Some(AccountInfo {
+        code_hash: *EMPTY_KECCAK_HASH,
+        balance: U256::from(value),
+        nonce: value,
+    });
+    update.added_storage.insert(slot(), U256::from(value));
+    let mut updates = vec![update];
+    if number == 2 {
+        let mut update = AccountUpdate::new(untouched_account());
+        update.info = Some(AccountInfo {
+            code_hash: *EMPTY_KECCAK_HASH,
+            balance: U256::from(2),
+            nonce: 0,
+        });
+        updates.push(update);
+    }
+    updates
+}
+
+/// Executes and stores a block on top of `parent`, setting `account` to `value`.
+fn store_block(store: &Store, parent: &BlockHeader, value: u64) -> Result<BlockHeader, StoreError> {
+    let number = parent.number + 1;
+    let updates = store
+        .apply_account_updates_batch(parent.hash(), &block_updates(number, value))?
+        .expect("parent state should be available");
+    let header = BlockHeader {
+        parent_hash: parent.hash(),
+        number,
+        state_root: updates.state_trie_hash,
+        ..Default::default()
+    };
+    store.store_block_updates(UpdateBatch {
+        account_updates: updates.state_updates,
+        storage_updates: updates.storage_updates,
+        blocks: vec![Block::new(header.clone(), BlockBody::empty())],
+        receipts: vec![],
+        code_updates: updates.code_updates,
+        batch_mode: true,
+    })?;
+    Ok(header)
+}
+
+/// Stores blocks `parent.number + 1..=last`, with each block setting `account` to its number.
+async fn store_blocks(store: &Store, parent: BlockHeader, last: u64) -> Vec<BlockHeader> {
+    let mut headers = vec![];
+    let mut parent = parent;
+    for number in parent.number + 1..=last {
+        parent = store_block(store, &parent, number).expect("Failed to store block");
+        headers.push(parent.clone());
+    }
+    // Wait for the diff-layers to be written to disk
+    store
+        .wait_for_persistence_idle()
+        .await
+        .expect("Failed to wait for persistence");
+    headers
+}
+
+/// Checks the state of `header` as written by [`store_blocks`].
+fn assert_state_at(store: &Store, header: &BlockHeader) {
+    let number = header.number;
+    let state = store
+        .get_account_state_by_root(header.state_root, account())
+        .expect("Failed to read account");
+    let slot_value = store
+        .get_storage_at_root(header.state_root, account(), slot())
+        .expect("Failed to read storage");
+    let untouched = store
+        .get_account_state_by_root(header.state_root, untouched_account())
+        .expect("Failed to read account");
+    let state = state.unwrap_or_else(|| panic!("account missing at block {number}"));
+    assert_eq!(
+        state.balance,
+        U256::from(number),
+        "balance at block {number}"
+    );
+    assert_eq!(state.nonce, number, "nonce at block {number}");
+    assert_eq!(
+        slot_value,
+        (number > 0).then(|| U256::from(number)),
+        "slot at block {number}"
+    );
+    assert_eq!(
+        untouched.map(|state| state.balance),
+        (number >= 2).then(|| U256::from(2)),
+        "untouched account at block {number}"
+    );
+}
+
+#[tokio::test]
+async fn archive_serves_state_of_every_block() {
+    let (store, genesis) = new_archive_store("", EngineType::InMemory).await;
+    let headers = store_blocks(&store, genesis.clone(), BLOCKS).await;
+
+    // Genesis is the base version, blocks 1..=5 are archived, block 6 is the disk layer
+    // and blocks 7..=10 are in memory
+    assert_state_at(&store, &genesis);
+    for header in &headers {
+        assert_state_at(&store, header);
+    }
+}
+
+#[tokio::test]
+async fn archive_rejects_building_on_archived_state() {
+    let (store, genesis) = new_archive_store("", EngineType::InMemory).await;
+    let headers = store_blocks(&store, genesis, BLOCKS).await;
+
+    // Block 2 is archived: its state can be read but a reorg on top of it fails
+    let archived = &headers[1];
+    let result = store_block(&store, archived, 100);
+    assert!(
+        matches!(result, Err(StoreError::ArchivedStateRoot(root)) if root == archived.state_root),
+        "building on archived state should fail, got {result:?}"
+    );
+    assert_state_at(&store, archived);
+
+    // Reorgs within the in-memory diff-layers and on top of the disk layer are fine
+    let in_memory = &headers[7];
+    store_block(&store, in_memory, 100).expect("Failed to build on an in-memory layer");
+    let disk_layer = &headers[5];
+    store_block(&store, disk_layer, 100).expect("Failed to build on the disk layer");
+}
+
+#[cfg(feature = "rocksdb")]
+#[tokio::test]
+async fn archive_history_survives_restart() {
+    let path = format!("archive-test-db-{}", H256::random().to_low_u64_be());
+    let _ = std::fs::remove_dir_all(&path);
+
+    let (store, genesis) = new_archive_store(&path, EngineType::RocksDB).await;
+    let headers = store_blocks(&store, genesis.clone(), BLOCKS).await;
+    drop(store);
+
+    // The history can't be kept up to date without archive mode
+    assert!(Store::new(&path, EngineType::RocksDB).is_err());
+    assert_eq!(
+        ethrex_storage::read_gc_mode_from_db(std::path::Path::new(&path)),
+        Some(GcMode::Archive)
+    );
+
+    // The in-memory diff-layers are lost on restart, so the chain continues from block 6,
+    // the disk layer. New versions must follow the ones persisted before the restart.
+    let store = Store::new_with_config(&path, EngineType::RocksDB, archive_config())
+        .expect("Failed to reopen store");
+    let disk_layer = headers[5].clone();
+    let new_headers = store_blocks(&store, disk_layer, BLOCKS + 2).await;
+    // Blocks 6 and 7 are archived now and block 8 is the disk layer
+    assert_state_at(&store, &genesis);
+    for header in headers[..6].iter().chain(&new_headers) {
+        assert_state_at(&store, header);
+    }
+
+    drop(store);
+    let _ = std::fs::remove_dir_all(&path);
+}
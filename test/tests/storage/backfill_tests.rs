@@ -0,0 +1,200 @@
+//! Tests for the history backfill cursor (`ChainDataIndex::BackfillBlock`): after snap
+//! sync only headers are stored, and the bodies and receipts are downloaded backwards
+//! from the pivot. The cursor is moved in the same write as the backfilled history, so an
+//! interrupted backfill resumes where it stopped.
+
+use ethrex_common::types::{
+    BlockBody, BlockHash, BlockHeader, BlockNumber, EIP1559Transaction, Genesis, Receipt,
+    Transaction, TxType,
+};
+use ethrex_storage::{EngineType, Store};
+
+const PIVOT: BlockNumber = 3;
+
+/// Stores genesis and the headers of blocks `1..=PIVOT` as canonical, as snap sync does.
+async fn synced_headers(store: &mut Store) -> Vec<BlockHeader> {
+    let genesis = Genesis::default();
+    let mut parent = genesis.get_block().header;
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis");
+
+    let mut headers = vec![];
+    for number in 1..=PIVOT {
+        let header = BlockHeader {
+            parent_hash: parent.hash(),
+            number,
+            ..Default::default()
+        };
+        parent = header.clone();
+        headers.push(header);
+    }
+    store.add_block_headers(headers.clone()).await.unwrap();
+    let canonical = headers
+        .iter()
+        .map(|header| (header.number, header.hash()))
+        .collect();
+    store
+        .forkchoice_update(canonical, PIVOT, parent.hash(), None, None)
+        .await
+        .unwrap();
+    headers
+}
+
+/// Body and receipts of a block with a single transaction.
+fn history(header: &BlockHeader) -> (BlockNumber, BlockHash, BlockBody, Vec<Receipt>) {
+    let body = BlockBody {
+        transactions: vec![Transaction::EIP1559Transaction(EIP1559Transaction {
+            nonce: header.number,
+            ..Default::default()
+        })],
+        ..BlockBody::empty()
+    };
+    let receipts = vec![Receipt::new(TxType::EIP1559, true, 21_000, vec![])];
+    (header.number, header.hash(), body, receipts)
+}
+
+/// Checks whether the history of `header` was stored.
+async fn assert_backfilled(store: &Store, header: &BlockHeader, backfilled: bool) {
+    let number = header.number;
+    let tx_hash = history(header).2.transactions[0].hash();
+    assert_eq!(
+        store
+            .get_block_body_by_hash(header.hash())
+            .await
+            .unwrap()
+            .is_some(),
+        backfilled,
+        "body of block {number}"
+    );
+    assert_eq!(
+        store
+            .get_receipts_for_block(&header.hash())
+            .await
+            .unwrap()
+            .len(),
+        usize::from(backfilled),
+        "receipts of block {number}"
+    );
+    assert_eq!(
+        store.get_transaction_location(tx_hash).await.unwrap(),
+        backfilled.then(|| (number, header.hash(), 0)),
+        "transaction location of block {number}"
+    );
+}
+
+#[tokio::test]
+async fn backfill_moves_the_cursor_with_the_history() {
+    let mut store = Store::new("", EngineType::InMemory).expect("Failed to create store");
+    let headers = synced_headers(&mut store).await;
+    assert_eq!(store.get_backfill_block().await.unwrap(), None);
+
+    store.set_backfill_block(Some(PIVOT)).await.unwrap();
+    assert_eq!(store.get_backfill_block().await.unwrap(), Some(PIVOT));
+
+    // Blocks 3 and 2 are backfilled, block 1 is next
+    store
+        .add_backfilled_blocks(vec![history(&headers[2]), history(&headers[1])], Some(1))
+        .await
+        .unwrap();
+    assert_eq!(store.get_backfill_block().await.unwrap(), Some(1));
+    assert_backfilled(&store, &headers[0], false).await;
+    assert_backfilled(&store, &headers[1], true).await;
+    assert_backfilled(&store, &headers[2], true).await;
+
+    // Backfilling the last block marks the backfill as done
+    store
+        .add_backfilled_blocks(vec![history(&headers[0])], None)
+        .await
+        .unwrap();
+    assert_eq!(store.get_backfill_block().await.unwrap(), None);
+    for header in &headers {
+        assert_backfilled(&store, header, true).await;
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+#[tokio::test]
+async fn backfill_resumes_after_restart() {
+    let path = format!(
+        "backfill-test-db-{}",
+        ethrex_common::H256::random().to_low_u64_be()
+    );
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut store = Store::new(&path, EngineType::RocksDB).expect("Failed to create store");
+    let headers = synced_headers(&mut store).await;
+    store.set_backfill_block(Some(PIVOT)).await.unwrap();
+    store
+        .add_backfilled_blocks(vec![history(&headers[2])], Some(2))
+        .await
+        .unwrap();
+    drop(store);
+
+    // The cursor and the backfilled history survive the restart
+    let store = Store::new(&path, EngineType::RocksDB).expect("Failed to reopen store");
+    assert_eq!(store.get_backfill_block().await.unwrap(), Some(2));
+    assert_backfilled(&store, &headers[2], true).await;
+    assert_backfilled(&store, &headers[1], false).await;
+    drop(store);
+    let _ = std::fs::remove_dir_all(&path);
+}
+
+/// Finalizes a block deep enough for the synced blocks to be moved to the ancient
+/// store, which wakes up the ancient mover.
+#[cfg(feature = "rocksdb")]
+async fn finalize_for_ancients(store: &Store, head: &BlockHeader) {
+    store
+        .forkchoice_update(
+            vec![],
+            head.number,
+            head.hash(),
+            None,
+            Some(ethrex_storage::ANCIENT_THRESHOLD + PIVOT + 1),
+        )
+        .await
+        .unwrap();
+}
+
+#[cfg(feature = "rocksdb")]
+#[tokio::test]
+async fn ancient_blocks_wait_for_the_backfill() {
+    use std::time::{Duration, Instant};
+
+    let path = format!(
+        "backfill-ancient-test-db-{}",
+        ethrex_common::H256::random().to_low_u64_be()
+    );
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut store = Store::new(&path, EngineType::RocksDB).expect("Failed to create store");
+    let headers = synced_headers(&mut store).await;
+    store.set_backfill_block(Some(PIVOT)).await.unwrap();
+
+    // Nothing is moved while the history is missing
+    finalize_for_ancients(&store, &headers[2]).await;
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(store.get_ancient_block_count(), 0);
+
+    store
+        .add_backfilled_blocks(headers.iter().rev().map(history).collect(), None)
+        .await
+        .unwrap();
+    finalize_for_ancients(&store, &headers[2]).await;
+    let start = Instant::now();
+    while store.get_ancient_block_count() < PIVOT + 1 {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "blocks weren't moved to the ancient store"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The backfilled history is served from the ancient store
+    for header in &headers {
+        assert_backfilled(&store, header, true).await;
+    }
+    drop(store);
+    let _ = std::fs::remove_dir_all(&path);
+}
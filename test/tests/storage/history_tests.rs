@@ -0,0 +1,180 @@
+//! Tests for history retention (`--history.retention`, EIP-4444): once blocks are
+//! finalized, the bodies, receipts and transaction locations of the blocks outside the
+//! retention window are pruned in the background, while their headers are kept.
+
+use std::time::{Duration, Instant};
+
+use ethrex_common::{
+    U256,
+    types::{
+        Block, BlockBody, BlockHeader, BlockNumber, EIP1559Transaction, Genesis, Receipt,
+        Transaction, TxType,
+    },
+};
+use ethrex_storage::{EngineType, HistoryRetention, Store, StoreConfig};
+
+const BLOCKS: u64 = 10;
+
+fn new_store(path: &str, engine_type: EngineType, retention: HistoryRetention) -> Store {
+    let config = StoreConfig {
+        history_retention: retention,
+        ..Default::default()
+    };
+    Store::new_with_config(path, engine_type, config).expect("Failed to create store")
+}
+
+fn transaction(number: BlockNumber) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        nonce: number,
+        ..Default::default()
+    })
+}
+
+/// Stores genesis and blocks `1..=BLOCKS`, each with a transaction and its receipt, and
+/// finalizes them all. Blocks from `merge_block` on have no difficulty.
+async fn store_chain(store: &mut Store, merge_block: BlockNumber) -> Vec<Block> {
+    let genesis = Genesis {
+        difficulty: U256::from(u64::from(merge_block > 0)),
+        ..Default::default()
+    };
+    let mut parent = genesis.get_block().header;
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis");
+
+    let mut blocks = vec![];
+    for number in 1..=BLOCKS {
+        let header = BlockHeader {
+            parent_hash: parent.hash(),
+            number,
+            difficulty: U256::from(u64::from(number < merge_block)),
+            ..Default::default()
+        };
+        let body = BlockBody {
+            transactions: vec![transaction(number)],
+            ..BlockBody::empty()
+        };
+        let block = Block::new(header.clone(), body);
+        store.add_block(block.clone()).await.unwrap();
+        store
+            .add_receipts(
+                block.hash(),
+                vec![Receipt::new(TxType::EIP1559, true, 21_000, vec![])],
+            )
+            .await
+            .unwrap();
+        parent = header;
+        blocks.push(block);
+    }
+
+    let canonical = blocks
+        .iter()
+        .map(|block| (block.header.number, block.hash()))
+        .collect();
+    store
+        .forkchoice_update(canonical, BLOCKS, parent.hash(), Some(BLOCKS), Some(BLOCKS))
+        .await
+        .unwrap();
+    blocks
+}
+
+/// Waits for the background worker to prune the history up to `cutoff`.
+async fn wait_for_cutoff(store: &Store, cutoff: BlockNumber) {
+    let start = Instant::now();
+    while store.get_history_cutoff().await.unwrap() < cutoff {
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "history wasn't pruned up to block {cutoff}"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+/// Checks that the history of the blocks below `cutoff` was pruned and the rest is kept.
+async fn assert_history(store: &Store, blocks: &[Block], cutoff: BlockNumber) {
+    assert_eq!(store.get_history_cutoff().await.unwrap(), cutoff);
+    assert!(store.get_block_body(0).await.unwrap().is_some());
+    for block in blocks {
+        let number = block.header.number;
+        let pruned = number < cutoff;
+        let tx_hash = block.body.transactions[0].hash();
+        assert!(
+            store.get_block_header(number).unwrap().is_some(),
+            "header of block {number} must be kept"
+        );
+        assert_eq!(
+            store.get_block_body(number).await.unwrap().is_none(),
+            pruned,
+            "body of block {number}"
+        );
+        assert_eq!(
+            store
+                .get_receipts_for_block(&block.hash())
+                .await
+                .unwrap()
+                .is_empty(),
+            pruned,
+            "receipts of block {number}"
+        );
+        assert_eq!(
+            store
+                .get_transaction_location(tx_hash)
+                .await
+                .unwrap()
+                .is_none(),
+            pruned,
+            "transaction location of block {number}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn history_outside_the_window_is_pruned() {
+    let mut store = new_store("", EngineType::InMemory, HistoryRetention::Blocks(3));
+    let blocks = store_chain(&mut store, 0).await;
+
+    // Blocks 8, 9 and 10 are the last 3 blocks
+    wait_for_cutoff(&store, 8).await;
+    assert_history(&store, &blocks, 8).await;
+}
+
+#[tokio::test]
+async fn post_merge_retention_prunes_pre_merge_history() {
+    let mut store = new_store("", EngineType::InMemory, HistoryRetention::PostMerge);
+    let blocks = store_chain(&mut store, 5).await;
+
+    // The merge block is the first one without difficulty
+    wait_for_cutoff(&store, 5).await;
+    assert_history(&store, &blocks, 5).await;
+}
+
+#[tokio::test]
+async fn nothing_is_pruned_before_the_merge() {
+    let mut store = new_store("", EngineType::InMemory, HistoryRetention::PostMerge);
+    let blocks = store_chain(&mut store, BLOCKS + 1).await;
+
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_history(&store, &blocks, 0).await;
+}
+
+#[cfg(feature = "rocksdb")]
+#[tokio::test]
+async fn history_cutoff_survives_restart() {
+    let path = format!(
+        "history-test-db-{}",
+        ethrex_common::H256::random().to_low_u64_be()
+    );
+    let _ = std::fs::remove_dir_all(&path);
+
+    let mut store = new_store(&path, EngineType::RocksDB, HistoryRetention::Blocks(3));
+    let blocks = store_chain(&mut store, 0).await;
+    wait_for_cutoff(&store, 8).await;
+    drop(store);
+
+    // Pruned history stays pruned even if the node is restarted keeping everything
+    let store = new_store(&path, EngineType::RocksDB, HistoryRetention::All);
+    assert_history(&store, &blocks, 8).await;
+    drop(store);
+    let _ = std::fs::remove_dir_all(&path);
+}
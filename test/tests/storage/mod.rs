@@ -1,3 +1,8 @@
+#[cfg(feature = "rocksdb")]
+mod ancient_tests;
+mod archive_tests;
+mod backfill_tests;
 mod fcu_race_tests;
+mod history_tests;
 mod store_tests;
 mod trie_db_tests;
@@ -13,7 +13,8 @@ use ethrex_blockchain::Blockchain;
 use ethrex_common::types::DEFAULT_BUILDER_GAS_CEIL;
 use ethrex_p2p::sync_manager::SyncManager;
 use ethrex_rpc::{
-    ClientVersion, GasTipEstimator, NodeData, RpcApiContext, start_block_executor,
+    ClientVersion, DEFAULT_MAX_FILTERS, GasTipEstimator, NodeData, RpcApiContext,
+    start_block_executor,
     test_utils::{
         all_namespaces_for_tests, dummy_sync_manager, example_local_node_record, example_p2p_node,
     },
@@ -69,6 +70,7 @@ pub async fn engine_only_context(storage: Store) -> RpcApiContext {
         storage,
         blockchain,
         active_filters: Default::default(),
+        max_filters: DEFAULT_MAX_FILTERS,
         syncer: Some(shared_syncer),
         peer_handler: None,
         node_data: NodeData {
@@ -90,6 +92,7 @@ pub async fn engine_only_context(storage: Store) -> RpcApiContext {
         gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
         block_worker_channel,
         ws: None,
+        ipc: None,
         allowed_namespaces: Arc::new(all_namespaces_for_tests()),
     }
 }
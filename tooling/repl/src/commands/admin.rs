@@ -15,7 +15,7 @@ const ENODE: &[ParamDef] = &[ParamDef {
     param_type: ParamType::StringParam,
     required: true,
     default_value: None,
-    description: "Enode URL of peer to add",
+    description: "Enode URL of the peer",
 }];
 
 pub fn commands() -> Vec<CommandDef> {
@@ -48,6 +48,27 @@ pub fn commands() -> Vec<CommandDef> {
             params: ENODE,
             description: "Adds a peer by enode URL",
         },
+        CommandDef {
+            namespace: "admin",
+            name: "removePeer",
+            rpc_method: "admin_removePeer",
+            params: ENODE,
+            description: "Disconnects a peer and removes it from the static peers",
+        },
+        CommandDef {
+            namespace: "admin",
+            name: "addTrustedPeer",
+            rpc_method: "admin_addTrustedPeer",
+            params: ENODE,
+            description: "Marks a peer as trusted",
+        },
+        CommandDef {
+            namespace: "admin",
+            name: "removeTrustedPeer",
+            rpc_method: "admin_removeTrustedPeer",
+            params: ENODE,
+            description: "Removes a peer from the trusted peers",
+        },
         CommandDef {
             namespace: "admin",
             name: "peerScores",